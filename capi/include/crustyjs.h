@@ -0,0 +1,82 @@
+/*
+ * C interface to the CrustyJS engine.
+ *
+ * Link against libcrustyjs_capi (static or shared). A context and every
+ * value taken from it belong to the thread that created the context.
+ * Free all values of a context before freeing the context itself.
+ */
+#ifndef CRUSTYJS_H
+#define CRUSTYJS_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct CrustyContext CrustyContext;
+typedef struct CrustyValue CrustyValue;
+
+typedef enum CrustyStatus {
+    CRUSTY_OK = 0,
+    /* The script threw or failed to parse. */
+    CRUSTY_ERROR_EXCEPTION = 1,
+    /* A null pointer, invalid UTF-8 or a re-entrant call. */
+    CRUSTY_ERROR_INVALID_ARGUMENT = 2,
+    CRUSTY_ERROR_PANIC = 3
+} CrustyStatus;
+
+typedef enum CrustyType {
+    CRUSTY_TYPE_INVALID = -1,
+    CRUSTY_TYPE_UNDEFINED = 0,
+    CRUSTY_TYPE_NULL = 1,
+    CRUSTY_TYPE_BOOLEAN = 2,
+    CRUSTY_TYPE_NUMBER = 3,
+    CRUSTY_TYPE_STRING = 4,
+    CRUSTY_TYPE_SYMBOL = 5,
+    CRUSTY_TYPE_OBJECT = 6,
+    CRUSTY_TYPE_FUNCTION = 7
+} CrustyType;
+
+/*
+ * Host function callback. `args` are borrowed for the duration of the
+ * call and must not be freed. Return a new handle (ownership passes to
+ * the engine) or NULL to throw a TypeError. Only the value constructors
+ * below may be called on `ctx` from inside a callback.
+ */
+typedef CrustyValue *(*CrustyHostFn)(CrustyContext *ctx, CrustyValue *const *args,
+                                     size_t argc, void *user_data);
+
+/* Returns NULL on failure. console output goes to stdout. */
+CrustyContext *crusty_context_new(void);
+void crusty_context_free(CrustyContext *ctx);
+
+/*
+ * Evaluates `len` bytes of UTF-8 source and returns the value of the last
+ * expression statement. On failure returns NULL and, if `out_err` is not
+ * NULL, stores a message to release with crusty_string_free.
+ */
+CrustyValue *crusty_eval(CrustyContext *ctx, const char *source, size_t len, char **out_err);
+
+CrustyStatus crusty_register_function(CrustyContext *ctx, const char *name,
+                                      CrustyHostFn callback, void *user_data);
+CrustyStatus crusty_run_microtasks(CrustyContext *ctx, char **out_err);
+CrustyStatus crusty_collect_garbage(CrustyContext *ctx);
+
+CrustyType crusty_value_type(const CrustyValue *value);
+CrustyStatus crusty_value_to_number(const CrustyValue *value, double *out);
+/* Caller frees the result with crusty_string_free. */
+char *crusty_value_to_string(const CrustyValue *value);
+void crusty_value_free(CrustyValue *value);
+
+CrustyValue *crusty_value_undefined(const CrustyContext *ctx);
+CrustyValue *crusty_value_from_number(const CrustyContext *ctx, double number);
+CrustyValue *crusty_value_from_string(const CrustyContext *ctx, const char *text, size_t len);
+
+void crusty_string_free(char *text);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CRUSTYJS_H */
@@ -0,0 +1,303 @@
+use std::cell::Cell;
+use std::ffi::{c_char, c_void};
+use std::{ptr, slice, str};
+
+use crustyjs::embedding::function_args::FunctionArgs;
+use crustyjs::errors::RuntimeError;
+use crustyjs::{Context, OutputMode, PersistentRoots, Value};
+
+use crate::value::{CrustyValue, new_handle};
+use crate::{CrustyStatus, Failure, call};
+
+/// A script context together with the root table that keeps the values
+/// handed to C alive.
+pub struct CrustyContext {
+    context: Context,
+    roots: PersistentRoots,
+    /// Set while the context runs script, so a host function cannot
+    /// re-enter it.
+    busy: Cell<bool>,
+}
+
+impl CrustyContext {
+    /// Runs `body` on the context behind `ctx`, refusing calls made from
+    /// inside a host function while it is already running.
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must be null or a live pointer from `crusty_context_new`.
+    unsafe fn enter<T>(
+        ctx: *mut CrustyContext,
+        body: impl FnOnce(&mut Context, &PersistentRoots) -> Result<T, Failure>,
+    ) -> Result<T, Failure> {
+        if ctx.is_null() {
+            return Err(Failure::InvalidArgument("context is null"));
+        }
+        // Fields are reached one at a time so host functions can read
+        // `roots` while `context` is borrowed further up the stack.
+        let busy = unsafe { &(*ctx).busy };
+        if busy.replace(true) {
+            return Err(Failure::InvalidArgument("context is already running"));
+        }
+        let _running = Running(busy);
+        let roots = unsafe { &(*ctx).roots };
+        let context = unsafe { &mut (*ctx).context };
+        body(context, roots)
+    }
+
+    /// The root table of `ctx`, usable while the context is running.
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must be null or a live pointer from `crusty_context_new`.
+    pub(crate) unsafe fn roots<'a>(ctx: *const CrustyContext) -> Option<&'a PersistentRoots> {
+        if ctx.is_null() {
+            None
+        } else {
+            Some(unsafe { &(*ctx).roots })
+        }
+    }
+}
+
+/// Clears a context's `busy` flag when dropped, so a panic unwinding out
+/// of a running context, which `call` reports, does not leave it locked.
+struct Running<'a>(&'a Cell<bool>);
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// Called with the context, borrowed argument handles and the
+/// registration's `user_data`. Returns a new handle, which the runtime
+/// takes ownership of, or null to throw a `TypeError`.
+pub type CrustyHostFn = unsafe extern "C" fn(
+    ctx: *mut CrustyContext,
+    args: *const *mut CrustyValue,
+    argc: usize,
+    user_data: *mut c_void,
+) -> *mut CrustyValue;
+
+struct HostFunction {
+    name: String,
+    callback: CrustyHostFn,
+    user_data: *mut c_void,
+    ctx: *mut CrustyContext,
+    roots: PersistentRoots,
+}
+
+// SAFETY: a context and the functions registered on it never leave the
+// thread that created the context; the bounds only satisfy
+// `Context::set_global_function`.
+unsafe impl Send for HostFunction {}
+unsafe impl Sync for HostFunction {}
+
+impl HostFunction {
+    fn call(&self, args: FunctionArgs<'_>) -> Result<Value, RuntimeError> {
+        let handles: Vec<_> = (0..args.len())
+            .filter_map(|i| args.get(i))
+            .map(|arg| new_handle(&self.roots, arg.clone()))
+            .collect();
+        let result =
+            unsafe { (self.callback)(self.ctx, handles.as_ptr(), handles.len(), self.user_data) };
+        let value = (!result.is_null()).then(|| unsafe { (*result).get() });
+        for handle in &handles {
+            drop(unsafe { Box::from_raw(*handle) });
+        }
+        if !result.is_null() && !handles.contains(&result) {
+            drop(unsafe { Box::from_raw(result) });
+        }
+        value.ok_or_else(|| RuntimeError::TypeError {
+            message: format!("host function '{}' failed", self.name),
+        })
+    }
+}
+
+/// Creates a context whose `console` output goes to stdout. Returns null
+/// on failure.
+#[unsafe(no_mangle)]
+pub extern "C" fn crusty_context_new() -> *mut CrustyContext {
+    unsafe {
+        call(ptr::null_mut(), || {
+            let mut context = Context::new();
+            context.set_output_mode(OutputMode::Stdout);
+            let roots = context.persistent_roots();
+            Ok(Box::into_raw(Box::new(CrustyContext {
+                context,
+                roots,
+                busy: Cell::new(false),
+            })))
+        })
+    }
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Destroys a context. Null is ignored.
+///
+/// # Safety
+///
+/// `ctx` must be null or a live pointer from `crusty_context_new`, not
+/// currently running. Every value taken from it must be freed first.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_context_free(ctx: *mut CrustyContext) {
+    if ctx.is_null() {
+        return;
+    }
+    let _ = unsafe {
+        call(ptr::null_mut(), || {
+            drop(Box::from_raw(ctx));
+            Ok(())
+        })
+    };
+}
+
+/// Evaluates `len` bytes of UTF-8 source and returns the value of its
+/// last expression statement. On failure returns null and, when
+/// `out_err` is non-null, stores a message to free with
+/// `crusty_string_free`.
+///
+/// # Safety
+///
+/// `ctx` must be a live context, `source` valid for `len` bytes, and
+/// `out_err` null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_eval(
+    ctx: *mut CrustyContext,
+    source: *const c_char,
+    len: usize,
+    out_err: *mut *mut c_char,
+) -> *mut CrustyValue {
+    unsafe {
+        call(out_err, || {
+            let source = utf8(source, len)?;
+            CrustyContext::enter(ctx, |context, roots| {
+                let value = context
+                    .eval_value(source)
+                    .map_err(|err| Failure::Exception(err.to_string()))?;
+                Ok(new_handle(roots, value))
+            })
+        })
+    }
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Defines a global function `name` that calls `callback` with
+/// `user_data`. Argument handles are only valid during the call and must
+/// not be freed by the callback.
+///
+/// # Safety
+///
+/// `ctx` must be a live context and `name` a NUL-terminated UTF-8
+/// string. `user_data` must stay valid for as long as the context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_register_function(
+    ctx: *mut CrustyContext,
+    name: *const c_char,
+    callback: Option<CrustyHostFn>,
+    user_data: *mut c_void,
+) -> CrustyStatus {
+    let registered = unsafe {
+        call(ptr::null_mut(), || {
+            if name.is_null() {
+                return Err(Failure::InvalidArgument("name is null"));
+            }
+            let name = std::ffi::CStr::from_ptr(name)
+                .to_str()
+                .map_err(|_| Failure::InvalidArgument("name is not valid UTF-8"))?
+                .to_string();
+            let callback = callback.ok_or(Failure::InvalidArgument("callback is null"))?;
+            CrustyContext::enter(ctx, |context, roots| {
+                let host = HostFunction {
+                    name: name.clone(),
+                    callback,
+                    user_data,
+                    ctx,
+                    roots: roots.clone(),
+                };
+                context.set_global_function(name, move |args| host.call(args));
+                Ok(())
+            })
+        })
+    };
+    registered.err().unwrap_or(CrustyStatus::Ok)
+}
+
+/// Runs queued promise reactions until none are left.
+///
+/// # Safety
+///
+/// `ctx` must be a live context and `out_err` null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_run_microtasks(
+    ctx: *mut CrustyContext,
+    out_err: *mut *mut c_char,
+) -> CrustyStatus {
+    let ran = unsafe {
+        call(out_err, || {
+            CrustyContext::enter(ctx, |context, _| {
+                context
+                    .run_microtasks()
+                    .map_err(|err| Failure::Exception(err.to_string()))
+            })
+        })
+    };
+    ran.err().unwrap_or(CrustyStatus::Ok)
+}
+
+/// Frees every script value that is neither reachable from the context
+/// nor held through a value handle.
+///
+/// # Safety
+///
+/// `ctx` must be a live context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_collect_garbage(ctx: *mut CrustyContext) -> CrustyStatus {
+    let collected = unsafe {
+        call(ptr::null_mut(), || {
+            CrustyContext::enter(ctx, |context, _| {
+                // Every value handed to C is a handle in `roots`, so
+                // nothing the host holds can be freed.
+                context.collect_garbage();
+                Ok(())
+            })
+        })
+    };
+    collected.err().unwrap_or(CrustyStatus::Ok)
+}
+
+/// Borrows `len` bytes at `text` as UTF-8. Null is accepted for an empty
+/// string.
+///
+/// # Safety
+///
+/// `text` must be null or valid for `len` bytes.
+pub(crate) unsafe fn utf8<'a>(text: *const c_char, len: usize) -> Result<&'a str, Failure> {
+    if text.is_null() {
+        return match len {
+            0 => Ok(""),
+            _ => Err(Failure::InvalidArgument("string pointer is null")),
+        };
+    }
+    let bytes = unsafe { slice::from_raw_parts(text.cast::<u8>(), len) };
+    str::from_utf8(bytes).map_err(|_| Failure::InvalidArgument("string is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::*;
+
+    #[test]
+    fn a_panic_while_running_leaves_the_context_usable() {
+        let ctx = crusty_context_new();
+        let panicked = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+            CrustyContext::enter(ctx, |_, _| -> Result<(), Failure> { panic!("host bug") })
+        }));
+        assert!(panicked.is_err());
+        let reentered = unsafe { CrustyContext::enter(ctx, |_, _| Ok(())) };
+        assert!(reentered.is_ok());
+        unsafe { crusty_context_free(ctx) };
+    }
+}
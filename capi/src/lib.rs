@@ -0,0 +1,89 @@
+#![allow(clippy::result_large_err)]
+
+//! C interface to CrustyJS for hosts that are not written in Rust. The
+//! matching declarations live in `include/crustyjs.h`.
+//!
+//! Every entry point catches panics and reports them as
+//! `CRUSTY_ERROR_PANIC` or a null handle instead of unwinding into C.
+//! A context and the values taken from it belong to the thread that
+//! created the context.
+
+mod context;
+mod value;
+
+use std::ffi::{CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+
+pub use context::{
+    CrustyContext, CrustyHostFn, crusty_collect_garbage, crusty_context_free, crusty_context_new,
+    crusty_eval, crusty_register_function, crusty_run_microtasks,
+};
+pub use value::{
+    CrustyType, CrustyValue, crusty_value_free, crusty_value_from_number, crusty_value_from_string,
+    crusty_value_to_number, crusty_value_to_string, crusty_value_type, crusty_value_undefined,
+};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrustyStatus {
+    Ok = 0,
+    /// The script threw or failed to parse.
+    Exception = 1,
+    /// A null pointer, invalid UTF-8 or a call the context can't take
+    /// right now.
+    InvalidArgument = 2,
+    Panic = 3,
+}
+
+/// Why an entry point failed, before it is reported to C.
+pub(crate) enum Failure {
+    Exception(String),
+    InvalidArgument(&'static str),
+}
+
+/// Runs `body`, turning a failure or panic into a status and, when
+/// `out_err` is non-null, a message the caller frees with
+/// `crusty_string_free`.
+///
+/// # Safety
+///
+/// `out_err` must be null or valid for writes.
+pub(crate) unsafe fn call<T>(
+    out_err: *mut *mut c_char,
+    body: impl FnOnce() -> Result<T, Failure>,
+) -> Result<T, CrustyStatus> {
+    let (status, message) = match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(value)) => return Ok(value),
+        Ok(Err(Failure::Exception(message))) => (CrustyStatus::Exception, message),
+        Ok(Err(Failure::InvalidArgument(message))) => {
+            (CrustyStatus::InvalidArgument, message.to_string())
+        }
+        Err(_) => (CrustyStatus::Panic, "panic inside crustyjs".to_string()),
+    };
+    if !out_err.is_null() {
+        unsafe { *out_err = c_string(message) };
+    }
+    Err(status)
+}
+
+/// Hands `text` to C as a string freed with `crusty_string_free`; null
+/// when it contains a NUL byte.
+pub(crate) fn c_string(text: String) -> *mut c_char {
+    CString::new(text).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Frees a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `text` must be null or a string returned by this library that has
+/// not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_string_free(text: *mut c_char) {
+    if text.is_null() {
+        return;
+    }
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        drop(unsafe { CString::from_raw(text) })
+    }));
+}
@@ -0,0 +1,185 @@
+use std::ffi::c_char;
+use std::ptr;
+
+use crustyjs::{Persistent, PersistentRoots, Value};
+
+use crate::context::{CrustyContext, utf8};
+use crate::{CrustyStatus, Failure, c_string, call};
+
+/// An owned reference to a script value. The value stays alive across
+/// garbage collections until the handle is freed with
+/// `crusty_value_free`.
+pub struct CrustyValue(Persistent);
+
+impl CrustyValue {
+    pub(crate) fn get(&self) -> Value {
+        self.0.get()
+    }
+}
+
+pub(crate) fn new_handle(roots: &PersistentRoots, value: Value) -> *mut CrustyValue {
+    Box::into_raw(Box::new(CrustyValue(roots.persist(value))))
+}
+
+/// What `typeof` reports, with `null` split out.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrustyType {
+    Invalid = -1,
+    Undefined = 0,
+    Null = 1,
+    Boolean = 2,
+    Number = 3,
+    String = 4,
+    Symbol = 5,
+    Object = 6,
+    Function = 7,
+}
+
+/// # Safety
+///
+/// `value` must be null or a live handle.
+unsafe fn borrow<'a>(value: *const CrustyValue) -> Result<&'a CrustyValue, Failure> {
+    if value.is_null() {
+        Err(Failure::InvalidArgument("value is null"))
+    } else {
+        Ok(unsafe { &*value })
+    }
+}
+
+/// The type of `value`, or `CRUSTY_TYPE_INVALID` for a null handle.
+///
+/// # Safety
+///
+/// `value` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_value_type(value: *const CrustyValue) -> CrustyType {
+    unsafe {
+        call(ptr::null_mut(), || {
+            Ok(match borrow(value)?.get() {
+                Value::Undefined => CrustyType::Undefined,
+                Value::Null => CrustyType::Null,
+                Value::Boolean(_) => CrustyType::Boolean,
+                Value::Number(_) => CrustyType::Number,
+                Value::String(_) => CrustyType::String,
+                Value::Symbol(_) => CrustyType::Symbol,
+                Value::Function { .. } | Value::NativeFunction { .. } => CrustyType::Function,
+                _ => CrustyType::Object,
+            })
+        })
+    }
+    .unwrap_or(CrustyType::Invalid)
+}
+
+/// Converts `value` with the `Number(value)` rules and stores the result
+/// in `out`.
+///
+/// # Safety
+///
+/// `value` must be a live handle and `out` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_value_to_number(
+    value: *const CrustyValue,
+    out: *mut f64,
+) -> CrustyStatus {
+    let converted = unsafe {
+        call(ptr::null_mut(), || {
+            if out.is_null() {
+                return Err(Failure::InvalidArgument("out is null"));
+            }
+            *out = borrow(value)?.get().to_number();
+            Ok(())
+        })
+    };
+    converted.err().unwrap_or(CrustyStatus::Ok)
+}
+
+/// Converts `value` with the `String(value)` rules. The result is freed
+/// with `crusty_string_free`; null for a null handle or a string with an
+/// embedded NUL.
+///
+/// # Safety
+///
+/// `value` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_value_to_string(value: *const CrustyValue) -> *mut c_char {
+    unsafe {
+        call(ptr::null_mut(), || {
+            Ok(c_string(borrow(value)?.get().to_js_string()))
+        })
+    }
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Releases a handle. Null is ignored.
+///
+/// # Safety
+///
+/// `value` must be null or a live handle, and is invalid afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_value_free(value: *mut CrustyValue) {
+    if value.is_null() {
+        return;
+    }
+    let _ = unsafe {
+        call(ptr::null_mut(), || {
+            drop(Box::from_raw(value));
+            Ok(())
+        })
+    };
+}
+
+/// # Safety
+///
+/// `ctx` must be null or a live context.
+unsafe fn create(ctx: *const CrustyContext, value: Value) -> *mut CrustyValue {
+    unsafe {
+        call(ptr::null_mut(), || {
+            let roots =
+                CrustyContext::roots(ctx).ok_or(Failure::InvalidArgument("context is null"))?;
+            Ok(new_handle(roots, value))
+        })
+    }
+    .unwrap_or(ptr::null_mut())
+}
+
+/// A new `undefined` handle. Safe to call from inside a host function.
+///
+/// # Safety
+///
+/// `ctx` must be a live context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_value_undefined(ctx: *const CrustyContext) -> *mut CrustyValue {
+    unsafe { create(ctx, Value::Undefined) }
+}
+
+/// A new number handle. Safe to call from inside a host function.
+///
+/// # Safety
+///
+/// `ctx` must be a live context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_value_from_number(
+    ctx: *const CrustyContext,
+    number: f64,
+) -> *mut CrustyValue {
+    unsafe { create(ctx, Value::Number(number)) }
+}
+
+/// A new string handle copied from `len` bytes of UTF-8, or null when the
+/// bytes are not valid UTF-8. Safe to call from inside a host function.
+///
+/// # Safety
+///
+/// `ctx` must be a live context and `text` valid for `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn crusty_value_from_string(
+    ctx: *const CrustyContext,
+    text: *const c_char,
+    len: usize,
+) -> *mut CrustyValue {
+    match unsafe { call(ptr::null_mut(), || utf8(text, len).map(str::to_string)) } {
+        Ok(text) => unsafe { create(ctx, Value::String(text.into())) },
+        Err(_) => ptr::null_mut(),
+    }
+}
@@ -0,0 +1,56 @@
+/* Links against the static library and drives it through the header. */
+#include <stdio.h>
+#include <string.h>
+
+#include "crustyjs.h"
+
+static CrustyValue *scale(CrustyContext *ctx, CrustyValue *const *args, size_t argc,
+                          void *user_data) {
+    double factor = *(double *)user_data;
+    double input = 0;
+    if (argc != 1 || crusty_value_to_number(args[0], &input) != CRUSTY_OK) {
+        return NULL;
+    }
+    return crusty_value_from_number(ctx, input * factor);
+}
+
+static int expect(int ok, const char *what) {
+    if (!ok) {
+        fprintf(stderr, "smoke: %s\n", what);
+    }
+    return ok ? 0 : 1;
+}
+
+int main(void) {
+    int failures = 0;
+    double factor = 3;
+    CrustyContext *ctx = crusty_context_new();
+    failures += expect(ctx != NULL, "context created");
+    failures += expect(crusty_register_function(ctx, "scale", scale, &factor) == CRUSTY_OK,
+                       "function registered");
+
+    const char *source = "let parts = ['a', 'b']; parts.join('-') + scale(14);";
+    char *err = NULL;
+    CrustyValue *value = crusty_eval(ctx, source, strlen(source), &err);
+    failures += expect(value != NULL, "eval succeeded");
+    failures += expect(crusty_value_type(value) == CRUSTY_TYPE_STRING, "string result");
+    failures += expect(crusty_collect_garbage(ctx) == CRUSTY_OK, "collected");
+    char *text = crusty_value_to_string(value);
+    failures += expect(text != NULL && strcmp(text, "a-b42") == 0, "string contents");
+    crusty_string_free(text);
+    crusty_value_free(value);
+
+    const char *failing = "throw new TypeError('nope');";
+    value = crusty_eval(ctx, failing, strlen(failing), &err);
+    failures += expect(value == NULL && err != NULL && strstr(err, "nope") != NULL,
+                       "error reported");
+    crusty_string_free(err);
+
+    failures += expect(crusty_run_microtasks(ctx, NULL) == CRUSTY_OK, "microtasks ran");
+    crusty_context_free(ctx);
+
+    if (failures == 0) {
+        printf("ok\n");
+    }
+    return failures;
+}
@@ -0,0 +1,48 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// `target/<profile>`, where cargo puts the static library next to the
+/// `deps` directory holding this test binary.
+fn profile_dir() -> PathBuf {
+    let exe = env::current_exe().expect("test binary path");
+    exe.parent()
+        .and_then(Path::parent)
+        .expect("test binary lives in target/<profile>/deps")
+        .to_path_buf()
+}
+
+#[test]
+fn c_program_links_and_runs() {
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    if Command::new(&compiler).arg("--version").output().is_err() {
+        eprintln!("skipping: no C compiler ({compiler}) on PATH");
+        return;
+    }
+
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library = profile_dir().join("libcrustyjs_capi.a");
+    assert!(library.exists(), "missing {}", library.display());
+    let program = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("crusty_smoke");
+
+    let compiled = Command::new(&compiler)
+        .arg(manifest.join("tests/c/smoke.c"))
+        .arg("-I")
+        .arg(manifest.join("include"))
+        .arg(&library)
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .expect("compiler should start");
+    assert!(compiled.success(), "smoke.c failed to compile");
+
+    let run = Command::new(&program)
+        .output()
+        .expect("smoke program should start");
+    assert!(
+        run.status.success(),
+        "smoke program failed: {}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&run.stdout).trim_end(), "ok");
+}
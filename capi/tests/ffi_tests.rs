@@ -0,0 +1,178 @@
+use std::ffi::{CStr, c_char, c_void};
+use std::ptr;
+
+use crustyjs_capi::*;
+
+unsafe fn eval(ctx: *mut CrustyContext, source: &str) -> Result<*mut CrustyValue, String> {
+    let mut err: *mut c_char = ptr::null_mut();
+    let value = unsafe { crusty_eval(ctx, source.as_ptr().cast(), source.len(), &mut err) };
+    if value.is_null() {
+        let message = unsafe { CStr::from_ptr(err) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { crusty_string_free(err) };
+        Err(message)
+    } else {
+        Ok(value)
+    }
+}
+
+unsafe fn take_string(value: *mut CrustyValue) -> String {
+    let text = unsafe { crusty_value_to_string(value) };
+    let owned = unsafe { CStr::from_ptr(text) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe {
+        crusty_string_free(text);
+        crusty_value_free(value);
+    }
+    owned
+}
+
+#[test]
+fn eval_returns_typed_handles() {
+    unsafe {
+        let ctx = crusty_context_new();
+        let number = eval(ctx, "let x = 20; x + 22;").unwrap();
+        assert_eq!(crusty_value_type(number), CrustyType::Number);
+        let mut out = 0.0;
+        assert_eq!(crusty_value_to_number(number, &mut out), CrustyStatus::Ok);
+        assert_eq!(out, 42.0);
+        crusty_value_free(number);
+
+        let text = eval(ctx, "'crusty' + 'js'").unwrap();
+        assert_eq!(crusty_value_type(text), CrustyType::String);
+        assert_eq!(take_string(text), "crustyjs");
+
+        let func = eval(ctx, "(function () {})").unwrap();
+        assert_eq!(crusty_value_type(func), CrustyType::Function);
+        crusty_value_free(func);
+        assert_eq!(crusty_value_type(ptr::null()), CrustyType::Invalid);
+        crusty_context_free(ctx);
+    }
+}
+
+#[test]
+fn eval_reports_errors_and_bad_input() {
+    unsafe {
+        let ctx = crusty_context_new();
+        let err = eval(ctx, "throw new RangeError('too far');").unwrap_err();
+        assert!(err.contains("too far"), "{err}");
+        assert!(eval(ctx, "let = ;").is_err());
+
+        let mut message: *mut c_char = ptr::null_mut();
+        let invalid = [0xffu8, 0xfe];
+        let value = crusty_eval(ctx, invalid.as_ptr().cast(), invalid.len(), &mut message);
+        assert!(value.is_null());
+        assert_eq!(
+            CStr::from_ptr(message).to_str().unwrap(),
+            "string is not valid UTF-8"
+        );
+        crusty_string_free(message);
+
+        assert!(crusty_eval(ptr::null_mut(), ptr::null(), 0, ptr::null_mut()).is_null());
+        crusty_context_free(ctx);
+    }
+}
+
+unsafe extern "C" fn sum(
+    ctx: *mut CrustyContext,
+    args: *const *mut CrustyValue,
+    argc: usize,
+    user_data: *mut c_void,
+) -> *mut CrustyValue {
+    unsafe {
+        *user_data.cast::<u32>() += 1;
+        let mut total = 0.0;
+        for i in 0..argc {
+            let mut n = 0.0;
+            if crusty_value_to_number(*args.add(i), &mut n) != CrustyStatus::Ok {
+                return ptr::null_mut();
+            }
+            total += n;
+        }
+        if total < 0.0 {
+            return ptr::null_mut();
+        }
+        crusty_value_from_number(ctx, total)
+    }
+}
+
+#[test]
+fn registered_functions_receive_arguments_and_user_data() {
+    unsafe {
+        let ctx = crusty_context_new();
+        let mut calls = 0u32;
+        let name = c"sum";
+        let status =
+            crusty_register_function(ctx, name.as_ptr(), Some(sum), (&raw mut calls).cast());
+        assert_eq!(status, CrustyStatus::Ok);
+
+        let value = eval(ctx, "sum(1, 2, 3) + sum(10)").unwrap();
+        assert_eq!(take_string(value), "16");
+        assert_eq!(calls, 2);
+
+        let err = eval(ctx, "sum(-5)").unwrap_err();
+        assert!(err.contains("host function 'sum' failed"), "{err}");
+
+        let status = crusty_register_function(ctx, name.as_ptr(), None, ptr::null_mut());
+        assert_eq!(status, CrustyStatus::InvalidArgument);
+        crusty_context_free(ctx);
+    }
+}
+
+unsafe extern "C" fn reenter(
+    ctx: *mut CrustyContext,
+    _args: *const *mut CrustyValue,
+    _argc: usize,
+    _user_data: *mut c_void,
+) -> *mut CrustyValue {
+    unsafe {
+        let mut err: *mut c_char = ptr::null_mut();
+        let nested = crusty_eval(ctx, c"1".as_ptr(), 1, &mut err);
+        assert!(nested.is_null());
+        let message = CStr::from_ptr(err).to_string_lossy().into_owned();
+        crusty_string_free(err);
+        crusty_value_from_string(ctx, message.as_ptr().cast(), message.len())
+    }
+}
+
+#[test]
+fn host_functions_cannot_reenter_the_context() {
+    unsafe {
+        let ctx = crusty_context_new();
+        crusty_register_function(ctx, c"reenter".as_ptr(), Some(reenter), ptr::null_mut());
+        let value = eval(ctx, "reenter()").unwrap();
+        assert_eq!(take_string(value), "context is already running");
+        crusty_context_free(ctx);
+    }
+}
+
+#[test]
+fn handles_keep_values_alive_across_collection() {
+    unsafe {
+        let ctx = crusty_context_new();
+        let list = eval(ctx, "[1, 2, 3].map(n => ({ n }))").unwrap();
+        let joined = eval(ctx, "['x', 'y'].join('+')").unwrap();
+        assert_eq!(crusty_collect_garbage(ctx), CrustyStatus::Ok);
+        assert_eq!(crusty_value_type(list), CrustyType::Object);
+        assert_eq!(
+            take_string(list),
+            "[object Object],[object Object],[object Object]"
+        );
+        assert_eq!(take_string(joined), "x+y");
+
+        let value = eval(
+            ctx,
+            "let seen = 0; Promise.resolve(4).then(v => { seen = v; }); seen",
+        )
+        .unwrap();
+        crusty_value_free(value);
+        assert_eq!(
+            crusty_run_microtasks(ctx, ptr::null_mut()),
+            CrustyStatus::Ok
+        );
+        assert_eq!(take_string(eval(ctx, "seen").unwrap()), "4");
+        crusty_context_free(ctx);
+    }
+}
@@ -1,12 +1,20 @@
 #![allow(clippy::result_large_err)]
 
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use clap::Parser;
 use owo_colors::OwoColorize;
 
+mod module_graph;
+#[cfg(feature = "profiling")]
+mod profile;
 mod repl;
+mod report;
+mod watch;
 
 #[derive(Parser)]
 #[command(name = "crustyjs", about = "A minimal JavaScript interpreter in Rust")]
@@ -28,11 +36,42 @@ struct Cli {
     /// Evaluate inline JavaScript source
     #[arg(long)]
     eval: Option<String>,
+    /// Re-run the script whenever it or a module it imports changes
+    #[arg(long)]
+    watch: bool,
+    /// Keep parsed modules in this directory to skip re-parsing them
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Print errors on one line instead of with a source excerpt
+    #[arg(long)]
+    plain_errors: bool,
+    /// Print builtin timings and statement, expression and opcode counts
+    /// once the script finishes (needs the `profiling` feature)
+    #[arg(long)]
+    profile: bool,
+    /// Print each statement to stderr as it runs (interpreter only)
+    #[arg(long)]
+    trace: bool,
+    /// Print each call into a script function and each return to stderr
+    /// (interpreter only)
+    #[arg(long)]
+    trace_calls: bool,
+    /// Print the modules the script loaded as a tree to stderr once it
+    /// finishes (interpreter only)
+    #[arg(long)]
+    dump_module_graph: bool,
     /// Print version and exit
     #[arg(long)]
     version: bool,
 }
 
+/// What a single run of a script left behind.
+struct RunOutcome {
+    /// Source files the run loaded, including the entry script.
+    loaded: Vec<PathBuf>,
+    succeeded: bool,
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -45,6 +84,14 @@ fn main() {
         return;
     }
 
+    if cli.profile && !cfg!(feature = "profiling") {
+        eprintln!(
+            "{} --profile needs a build with the `profiling` feature",
+            "error:".red().bold()
+        );
+        process::exit(1);
+    }
+
     if cli.file.is_none() && cli.eval.is_none() {
         if let Err(err) = repl::run() {
             eprintln!("{} {err:?}", "error:".red().bold());
@@ -53,12 +100,21 @@ fn main() {
         return;
     }
 
-    let (source, source_path) = if let Some(code) = cli.eval {
-        (code, std::path::PathBuf::from("."))
+    if cli.watch {
+        let Some(file) = cli.file.clone() else {
+            eprintln!("{} --watch needs a script file", "error:".red().bold());
+            process::exit(1);
+        };
+        run_watch(&cli, PathBuf::from(file));
+        return;
+    }
+
+    let (source, source_path) = if let Some(code) = cli.eval.clone() {
+        (code, PathBuf::from("."))
     } else {
-        let file = cli.file.expect("checked above");
+        let file = cli.file.clone().expect("checked above");
         match fs::read_to_string(&file) {
-            Ok(s) => (s, std::path::PathBuf::from(file)),
+            Ok(s) => (s, PathBuf::from(file)),
             Err(e) => {
                 eprintln!(
                     "{} could not read '{}': {e}",
@@ -70,14 +126,24 @@ fn main() {
         }
     };
 
-    let tokens = match crustyjs::lexer::lex(&source) {
+    if !execute(&cli, &source, &source_path).succeeded {
+        process::exit(1);
+    }
+}
+
+/// Lexes, parses and runs `source` as the flags ask, printing any error.
+fn execute(cli: &Cli, source: &str, source_path: &Path) -> RunOutcome {
+    let failed = RunOutcome {
+        loaded: vec![source_path.to_path_buf()],
+        succeeded: false,
+    };
+    let reporter = report::Reporter::new(cli.plain_errors);
+
+    let tokens = match crustyjs::lexer::lex(source) {
         Ok(tokens) => tokens,
         Err(err) => {
-            eprintln!(
-                "{}",
-                format_syntax_error(&source, &source_path, "lex", &err)
-            );
-            process::exit(1);
+            eprintln!("{}", reporter.syntax_error(err, source, source_path, "lex"));
+            return failed;
         }
     };
 
@@ -87,14 +153,14 @@ fn main() {
         }
     }
 
-    let program = match crustyjs::parser::parse(tokens.clone()) {
+    let program = match crustyjs::parser::parse_with_source(tokens.clone(), source) {
         Ok(program) => program,
         Err(err) => {
             eprintln!(
                 "{}",
-                format_syntax_error(&source, &source_path, "parse", &err)
+                reporter.syntax_error(err, source, source_path, "parse")
             );
-            process::exit(1);
+            return failed;
         }
     };
 
@@ -105,42 +171,123 @@ fn main() {
 
     if cli.bytecode {
         let mut compiler = crustyjs::vm::compiler::Compiler::new();
-        let chunk = compiler.compile(program.clone());
-        println!("{}", "Bytecode".bright_blue().bold());
-        print!("{}", chunk.disassemble());
+        match compiler.compile(program.clone()) {
+            Ok(chunk) => {
+                println!("{}", "Bytecode".bright_blue().bold());
+                print!("{}", chunk.disassemble_with_source(source));
+            }
+            Err(err) => eprintln!(
+                "{}",
+                reporter.syntax_error(err, source, source_path, "compile")
+            ),
+        }
     }
 
-    let result = if cli.vm {
-        crustyjs::run_vm_with_path(&source, Some(source_path.clone())).map(|_| ())
+    let (error, loaded) = if cli.vm {
+        let print: crustyjs::runtime::output::OutputSink = Arc::new(|line| println!("{line}"));
+        let path = Some(source_path.to_path_buf());
+        #[cfg(feature = "profiling")]
+        let result = if cli.profile {
+            let (result, profile) = crustyjs::vm::run_vm_profiled(source, path, Some(print));
+            eprint!("{}", profile::render(&profile));
+            result
+        } else {
+            crustyjs::run_vm_with_path(source, path, Some(print))
+        };
+        #[cfg(not(feature = "profiling"))]
+        let result = crustyjs::run_vm_with_path(source, path, Some(print));
+        let error = result
+            .err()
+            .map(|err| reporter.crusty_error(err, source, source_path));
+        (error, failed.loaded)
     } else {
         let mut interp =
             crustyjs::runtime::interpreter::Interpreter::new_with_realtime_timers(true);
-        interp
-            .run_with_path(&program, source_path)
-            .map_err(crustyjs::errors::CrustyError::from)
+        interp.set_output_mode(crustyjs::OutputMode::Stdout);
+        if let Some(dir) = &cli.cache_dir {
+            interp.set_ast_cache_dir(dir);
+            interp.set_diagnostics_hook(Arc::new(|message| {
+                eprintln!("{} {message}", "note:".bright_black().bold());
+            }));
+        }
+        if cli.trace || cli.trace_calls {
+            interp.set_trace(
+                crustyjs::diagnostics::trace::TraceConfig::new()
+                    .statements(cli.trace)
+                    .calls(cli.trace_calls),
+            );
+        }
+        let error = interp
+            .run_with_path(&program, source_path.to_path_buf())
+            .err()
+            .map(|err| reporter.runtime_error(err, source, source_path, interp.error_location()));
+        #[cfg(feature = "profiling")]
+        if cli.profile {
+            eprint!("{}", profile::render(&interp.take_profile()));
+        }
+        if cli.dump_module_graph {
+            eprint!("{}", module_graph::render(&interp.module_graph()));
+        }
+        (error, interp.loaded_source_paths())
     };
 
-    if let Err(err) = result {
-        eprintln!("{} {err:?}", "runtime error:".red().bold());
-        process::exit(1);
+    if let Some(error) = &error {
+        eprintln!("{error}");
+    }
+    RunOutcome {
+        loaded,
+        succeeded: error.is_none(),
     }
 }
 
-fn format_syntax_error(
-    source: &str,
-    source_path: &std::path::Path,
-    phase: &str,
-    err: &crustyjs::errors::SyntaxError,
-) -> String {
-    let map = crustyjs::diagnostics::source_map::SourceMap::from_source(source);
-    let pos = map.byte_to_pos(err.span.offset());
-    format!(
-        "{} {} at {}:{}:{}: {}",
-        "syntax".red().bold(),
-        phase.yellow(),
-        source_path.display().to_string().cyan(),
-        pos.line,
-        pos.col,
-        err.message.bright_white()
-    )
+/// Runs `entry` with a fresh interpreter each time it or one of the files
+/// it loaded changes. The first Ctrl-C stops watching once the current run
+/// finishes; a second one exits immediately.
+fn run_watch(cli: &Cli, entry: PathBuf) {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = Arc::clone(&stop);
+    if let Err(err) = ctrlc::set_handler(move || {
+        if handler_stop.swap(true, Ordering::SeqCst) {
+            process::exit(130);
+        }
+    }) {
+        eprintln!(
+            "{} could not install Ctrl-C handler: {err}",
+            "warning:".yellow().bold()
+        );
+    }
+
+    let mut poller = watch::MtimePoller::default();
+    watch::watch_loop(
+        &mut poller,
+        watch::WatchTiming::default(),
+        &stop,
+        |changed| {
+            if !changed.is_empty() {
+                let names: Vec<_> = changed.iter().map(|p| p.display().to_string()).collect();
+                print!("\x1b[2J\x1b[H");
+                println!(
+                    "{} {} {}",
+                    format!("[{}]", watch::timestamp()).bright_black(),
+                    "restarting:".bright_cyan().bold(),
+                    format!("{} changed", names.join(", ")).yellow()
+                );
+            }
+            let mut watched = match fs::read_to_string(&entry) {
+                Ok(source) => execute(cli, &source, &entry).loaded,
+                Err(e) => {
+                    eprintln!(
+                        "{} could not read '{}': {e}",
+                        "error:".red().bold(),
+                        entry.display().to_string().yellow()
+                    );
+                    Vec::new()
+                }
+            };
+            watched.push(entry.clone());
+            watched.sort();
+            watched.dedup();
+            watched
+        },
+    );
 }
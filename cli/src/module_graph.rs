@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crustyjs::ModuleInfo;
+
+/// Renders the `--dump-module-graph` listing: the imports of the entry
+/// script as a tree, each module shown with its evaluation order and
+/// time, then totals. A module reached again is marked instead of being
+/// expanded a second time, which also keeps cycles finite. Paths are
+/// shown relative to the entry script's directory where they can be.
+pub fn render(modules: &[ModuleInfo]) -> String {
+    let Some(entry) = modules.first() else {
+        return "no modules loaded\n".to_string();
+    };
+    let base = match entry.path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    // Imports resolve to absolute paths, while the entry script may have
+    // been given relative to the working directory.
+    let absolute_base = std::path::absolute(&base).unwrap_or_else(|_| base.clone());
+    let mut tree = Tree {
+        modules,
+        bases: [base, absolute_base],
+        seen: HashSet::new(),
+        out: String::new(),
+    };
+    // Modules no import reached from the entry, such as ones a failed
+    // import left behind, are listed as roots of their own.
+    for (index, module) in modules.iter().enumerate() {
+        if tree.seen.insert(index) {
+            let label = tree.display(&module.path);
+            tree.line("", &label, module);
+            tree.children(index, "");
+        }
+    }
+
+    let imports: usize = modules.iter().map(|module| module.imports.len()).sum();
+    let mut out = tree.out;
+    out.push_str(&format!("\n{} modules, {imports} imports", modules.len()));
+    if let Some(duration) = entry.duration {
+        out.push_str(&format!(", {} in total", format_duration(duration)));
+    }
+    out.push('\n');
+    out
+}
+
+struct Tree<'a> {
+    modules: &'a [ModuleInfo],
+    bases: [PathBuf; 2],
+    seen: HashSet<usize>,
+    out: String,
+}
+
+impl Tree<'_> {
+    fn children(&mut self, index: usize, indent: &str) {
+        let imports = &self.modules[index].imports;
+        for (i, import) in imports.iter().enumerate() {
+            let last = i + 1 == imports.len();
+            let branch = format!("{indent}{}", if last { "└─ " } else { "├─ " });
+            let label = format!("{} → {}", import.specifier, self.display(&import.path));
+            let Some(target) = self.find(&import.path) else {
+                self.out.push_str(&format!("{branch}{label}\n"));
+                continue;
+            };
+            if !self.seen.insert(target) {
+                self.out
+                    .push_str(&format!("{branch}{label}  ↻ already loaded\n"));
+                continue;
+            }
+            let modules = self.modules;
+            self.line(&branch, &label, &modules[target]);
+            let nested = format!("{indent}{}", if last { "   " } else { "│  " });
+            self.children(target, &nested);
+        }
+    }
+
+    fn line(&mut self, branch: &str, label: &str, module: &ModuleInfo) {
+        let status = match (module.evaluation_order, module.duration) {
+            (Some(order), Some(duration)) => format!("#{order}  {}", format_duration(duration)),
+            _ => "not evaluated".to_string(),
+        };
+        self.out.push_str(&format!("{branch}{label}  {status}\n"));
+    }
+
+    fn find(&self, path: &Path) -> Option<usize> {
+        self.modules.iter().position(|module| module.path == path)
+    }
+
+    fn display(&self, path: &Path) -> String {
+        self.bases
+            .iter()
+            .find_map(|base| path.strip_prefix(base).ok())
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros >= 1_000 {
+        format!("{:.2}ms", micros as f64 / 1_000.0)
+    } else {
+        format!("{micros}µs")
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,61 @@
+use crustyjs::ModuleImport;
+
+use super::*;
+
+fn module(path: &str, imports: &[(&str, &str)], order: Option<usize>) -> ModuleInfo {
+    ModuleInfo {
+        path: PathBuf::from(path),
+        imports: imports
+            .iter()
+            .map(|(specifier, path)| ModuleImport {
+                specifier: specifier.to_string(),
+                path: PathBuf::from(path),
+            })
+            .collect(),
+        evaluation_order: order,
+        duration: order.map(|_| Duration::from_micros(1_500)),
+    }
+}
+
+#[test]
+fn shared_imports_are_expanded_once() {
+    let graph = [
+        module(
+            "app/a.js",
+            &[("./b.js", "app/b.js"), ("./c.js", "app/c.js")],
+            Some(3),
+        ),
+        module("app/b.js", &[("./d.js", "app/d.js")], Some(1)),
+        module("app/d.js", &[], Some(0)),
+        module("app/c.js", &[("./d", "app/d.js")], Some(2)),
+    ];
+    assert_eq!(
+        render(&graph),
+        "a.js  #3  1.50ms\n\
+         ├─ ./b.js → b.js  #1  1.50ms\n\
+         │  └─ ./d.js → d.js  #0  1.50ms\n\
+         └─ ./c.js → c.js  #2  1.50ms\n\
+         \u{20}  └─ ./d → d.js  ↻ already loaded\n\
+         \n\
+         4 modules, 4 imports, 1.50ms in total\n"
+    );
+}
+
+#[test]
+fn cycles_and_failed_modules_are_annotated() {
+    let graph = [
+        module("a.js", &[("./b.js", "b.js")], None),
+        module("b.js", &[("./a.js", "a.js")], None),
+    ];
+    let report = render(&graph);
+    assert!(report.starts_with("a.js  not evaluated\n"), "{report}");
+    assert!(
+        report.contains("└─ ./b.js → b.js  not evaluated\n"),
+        "{report}"
+    );
+    assert!(
+        report.contains("   └─ ./a.js → a.js  ↻ already loaded\n"),
+        "{report}"
+    );
+    assert!(report.ends_with("2 modules, 2 imports\n"), "{report}");
+}
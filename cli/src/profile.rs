@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crustyjs::diagnostics::profile::Profile;
+
+/// Rows each table shows.
+const TOP: usize = 20;
+
+/// Renders the `--profile` report: builtins by the time spent in them,
+/// then the statements, expressions and opcodes run most often, then the
+/// collector's pauses. Empty tables are left out.
+pub fn render(profile: &Profile) -> String {
+    let mut out = String::new();
+    let builtins = profile.builtins_by_time();
+    if !builtins.is_empty() {
+        out.push_str(&format!(
+            "{:<32} {:>10} {:>12}\n",
+            "builtin", "calls", "time"
+        ));
+        for (name, stats) in builtins.into_iter().take(TOP) {
+            out.push_str(&format!(
+                "{name:<32} {:>10} {:>12}\n",
+                stats.calls,
+                format_duration(stats.time)
+            ));
+        }
+    }
+    for (title, counts) in [
+        ("statement", &profile.statements),
+        ("expression", &profile.expressions),
+        ("opcode", &profile.opcodes),
+    ] {
+        count_table(&mut out, title, counts);
+    }
+    let gc = profile.gc;
+    if gc.pauses > 0 {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "gc: {} pauses, {} total, {} longest\n",
+            gc.pauses,
+            format_duration(Duration::from_micros(gc.total_micros)),
+            format_duration(Duration::from_micros(gc.max_micros))
+        ));
+    }
+    out
+}
+
+fn count_table(out: &mut String, title: &str, counts: &HashMap<&'static str, u64>) {
+    if counts.is_empty() {
+        return;
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&format!("{title:<32} {:>10}\n", "count"));
+    for (name, count) in Profile::by_count(counts).into_iter().take(TOP) {
+        out.push_str(&format!("{name:<32} {count:>10}\n"));
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros >= 1_000 {
+        format!("{:.2}ms", micros as f64 / 1_000.0)
+    } else {
+        format!("{micros}µs")
+    }
+}
+
+#[cfg(test)]
+mod tests;
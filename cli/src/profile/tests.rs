@@ -0,0 +1,49 @@
+use crustyjs::diagnostics::profile::BuiltinStats;
+
+use super::*;
+
+#[test]
+fn builtins_are_listed_by_time_before_the_count_tables() {
+    let mut profile = Profile::default();
+    profile.builtins.insert(
+        "Math.floor".into(),
+        BuiltinStats {
+            calls: 40,
+            time: Duration::from_micros(30),
+        },
+    );
+    profile.builtins.insert(
+        "Array.push".into(),
+        BuiltinStats {
+            calls: 3,
+            time: Duration::from_micros(2_500),
+        },
+    );
+    profile.statements.insert("ExprStmt", 7);
+    profile.statements.insert("ForLoop", 1);
+
+    let report = render(&profile);
+    let lines: Vec<&str> = report.lines().collect();
+    assert!(lines[1].starts_with("Array.push") && lines[1].ends_with("2.50ms"));
+    assert!(lines[2].starts_with("Math.floor") && lines[2].ends_with("30µs"));
+    assert!(lines[5].starts_with("ExprStmt") && lines[5].ends_with(" 7"));
+    assert!(lines[6].starts_with("ForLoop"));
+    assert!(!report.contains("opcode") && !report.contains("gc:"));
+}
+
+#[test]
+fn tables_stop_after_the_top_entries() {
+    let mut profile = Profile::default();
+    for (count, kind) in [
+        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R",
+        "S", "T", "U", "V",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        profile.opcodes.insert(kind, count as u64 + 1);
+    }
+    let report = render(&profile);
+    assert_eq!(report.lines().count(), TOP + 1);
+    assert!(report.lines().nth(1).unwrap().starts_with("V "));
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::io::{self, Write};
+use std::time::Instant;
+
+use crustyjs::context::Context;
+use crustyjs::lexer::lex;
+use crustyjs::parser::parse;
+use owo_colors::OwoColorize;
+
+use super::inspect::inspect;
+
+/// What the REPL loop should do with a line after dispatch.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The line is JavaScript, not a command.
+    NotCommand,
+    Handled,
+    Exit,
+}
+
+const HELP: &[(&str, &str)] = &[
+    (".help", "show commands"),
+    (".clear", "reset interpreter state"),
+    (".load <file.js>", "load and run script"),
+    (".ast <code>", "print the parsed AST without running it"),
+    (".tokens <code>", "print the token stream with byte spans"),
+    (".time <code>", "run code and report wall time and steps"),
+    (
+        ".inspect <code>",
+        "run code and pretty-print the result in depth",
+    ),
+    (".exit", "exit REPL"),
+];
+
+/// Runs `line` if it is a dot-command, writing what it prints to `out`.
+/// The argument may span several lines when the input was continued.
+pub fn dispatch(line: &str, ctx: &mut Context, out: &mut dyn Write) -> io::Result<Outcome> {
+    let line = line.trim();
+    if line == "exit" {
+        return Ok(Outcome::Exit);
+    }
+    let Some(rest) = line.strip_prefix('.') else {
+        return Ok(Outcome::NotCommand);
+    };
+    if !rest.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+        return Ok(Outcome::NotCommand);
+    }
+    let (name, arg) = match rest.find(char::is_whitespace) {
+        Some(split) => (&rest[..split], rest[split..].trim()),
+        None => (rest, ""),
+    };
+
+    match name {
+        "exit" => return Ok(Outcome::Exit),
+        "help" => {
+            for (usage, description) in HELP {
+                writeln!(
+                    out,
+                    "{}",
+                    format!("{usage:<20} {description}").bright_blue()
+                )?;
+            }
+        }
+        "clear" => {
+            *ctx = super::new_context();
+            writeln!(out, "{}", "environment cleared".green())?;
+        }
+        "load" if !arg.is_empty() => match fs::read_to_string(arg) {
+            Ok(source) => super::run_snippet(ctx, &source),
+            Err(err) => writeln!(out, "{} {err}", "load error:".red().bold())?,
+        },
+        "ast" if !arg.is_empty() => print_ast(arg, out)?,
+        "tokens" if !arg.is_empty() => print_tokens(arg, out)?,
+        "time" if !arg.is_empty() => time(arg, ctx, out)?,
+        "inspect" if !arg.is_empty() => match ctx.eval_value(arg) {
+            Ok(value) => writeln!(out, "{}", inspect(&value))?,
+            Err(err) => writeln!(out, "{} {err}", "error:".red().bold())?,
+        },
+        "load" | "ast" | "tokens" | "time" | "inspect" => {
+            let usage = HELP
+                .iter()
+                .find(|(usage, _)| usage[1..].split(' ').next() == Some(name))
+                .map_or(name, |(usage, _)| usage);
+            writeln!(out, "{} {usage}", "usage:".yellow())?;
+        }
+        _ => writeln!(
+            out,
+            "{} .{name} (type .help for commands)",
+            "unknown command:".red().bold()
+        )?,
+    }
+    Ok(Outcome::Handled)
+}
+
+fn print_ast(source: &str, out: &mut dyn Write) -> io::Result<()> {
+    match lex(source).and_then(parse) {
+        Ok(program) => writeln!(out, "{program:#?}"),
+        Err(err) => writeln!(out, "{} {err}", "syntax error:".red().bold()),
+    }
+}
+
+fn print_tokens(source: &str, out: &mut dyn Write) -> io::Result<()> {
+    let tokens = match lex(source) {
+        Ok(tokens) => tokens,
+        Err(err) => return writeln!(out, "{} {err}", "syntax error:".red().bold()),
+    };
+    for token in tokens {
+        let span = format!("{}..{}", token.span.start, token.span.end);
+        writeln!(
+            out,
+            "{} {:?}",
+            format!("{span:>9}").bright_black(),
+            token.kind
+        )?;
+    }
+    Ok(())
+}
+
+fn time(source: &str, ctx: &mut Context, out: &mut dyn Write) -> io::Result<()> {
+    let steps_before = ctx.step_count();
+    let started = Instant::now();
+    let result = ctx.eval_value(source);
+    let elapsed = started.elapsed();
+    let steps = ctx.step_count().saturating_sub(steps_before);
+    match result {
+        Ok(value) => writeln!(out, "{}", inspect(&value))?,
+        Err(err) => writeln!(out, "{} {err}", "error:".red().bold())?,
+    }
+    writeln!(
+        out,
+        "{}",
+        format!(
+            "time: {:.3}ms, steps: {steps}",
+            elapsed.as_secs_f64() * 1000.0
+        )
+        .bright_black()
+    )
+}
+
+#[cfg(test)]
+mod tests;
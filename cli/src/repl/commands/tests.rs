@@ -0,0 +1,106 @@
+use super::*;
+
+fn run(ctx: &mut Context, line: &str) -> (Outcome, String) {
+    let mut out = Vec::new();
+    let outcome = dispatch(line, ctx, &mut out).unwrap();
+    (outcome, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn test_plain_code_and_exit_are_not_handled_as_output() {
+    let mut ctx = Context::new();
+    assert_eq!(run(&mut ctx, "1 + 2").0, Outcome::NotCommand);
+    assert_eq!(run(&mut ctx, ".5 + 1").0, Outcome::NotCommand);
+    assert_eq!(run(&mut ctx, ".exit").0, Outcome::Exit);
+    assert_eq!(run(&mut ctx, "exit").0, Outcome::Exit);
+}
+
+#[test]
+fn test_help_lists_every_command() {
+    let (outcome, out) = run(&mut Context::new(), ".help");
+    assert_eq!(outcome, Outcome::Handled);
+    for command in [".ast", ".tokens", ".time", ".inspect", ".load", ".clear"] {
+        assert!(out.contains(command), "{command} missing from {out}");
+    }
+}
+
+#[test]
+fn test_ast_parses_without_running() {
+    let mut ctx = Context::new();
+    let (_, out) = run(&mut ctx, ".ast let x = 1 + 2;");
+    assert!(out.contains("VarDecl"), "{out}");
+    assert!(ctx.get_global("x").is_err());
+}
+
+#[test]
+fn test_tokens_show_spans() {
+    let (_, out) = run(&mut Context::new(), ".tokens let x");
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(
+        lines[0].contains("0..3") && lines[0].contains("Let"),
+        "{out}"
+    );
+    assert!(
+        lines[1].contains("4..5") && lines[1].contains("Ident"),
+        "{out}"
+    );
+}
+
+#[test]
+fn test_syntax_errors_leave_the_context_alone() {
+    let mut ctx = Context::new();
+    ctx.eval("let kept = 7;").unwrap();
+    for line in [".ast let = ;", ".tokens \"open", ".time let = ;"] {
+        let (outcome, out) = run(&mut ctx, line);
+        assert_eq!(outcome, Outcome::Handled);
+        assert!(out.contains("error"), "{line}: {out}");
+    }
+    assert_eq!(ctx.get_global("kept").unwrap().to_string(), "7");
+}
+
+#[test]
+fn test_time_reports_steps_taken() {
+    let mut ctx = Context::new();
+    let (_, out) = run(
+        &mut ctx,
+        ".time let n = 0; for (let i = 0; i < 10; i++) n += i; n",
+    );
+    assert!(out.starts_with("45\n"), "{out}");
+    let steps: usize = out
+        .split("steps: ")
+        .nth(1)
+        .map(|rest| {
+            rest.chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+        })
+        .and_then(|n| n.parse().ok())
+        .expect(&out);
+    assert!(steps >= 10, "{out}");
+}
+
+#[test]
+fn test_inspect_shows_internals_across_continued_lines() {
+    let mut ctx = Context::new();
+    let (_, out) = run(&mut ctx, ".inspect new TypeError(\n  \"bad\"\n)");
+    assert!(out.starts_with("TypeError {"), "{out}");
+    assert!(out.contains("[[ErrorType]]: \"TypeError\""), "{out}");
+    assert!(out.contains("message"), "{out}");
+
+    let (_, out) = run(&mut ctx, ".inspect Promise.resolve([1, { a: 2 }])");
+    assert!(out.contains("[[PromiseState]]: \"fulfilled\""), "{out}");
+    assert!(out.contains("[[PromiseResult]]: [ 1, { a: 2 } ]"), "{out}");
+
+    let (_, out) = run(&mut ctx, ".inspect let o = { name: \"o\" }; o.self = o; o");
+    assert!(out.contains("self: [Circular]"), "{out}");
+}
+
+#[test]
+fn test_missing_argument_and_unknown_command() {
+    let mut ctx = Context::new();
+    let (_, out) = run(&mut ctx, ".inspect");
+    assert!(out.contains(".inspect <code>"), "{out}");
+    let (outcome, out) = run(&mut ctx, ".frobnicate");
+    assert_eq!(outcome, Outcome::Handled);
+    assert!(out.contains("unknown command"), "{out}");
+}
@@ -1,42 +1,122 @@
+use crustyjs::context::Context;
 use rustyline::completion::Pair;
 
-pub fn complete_line(line: &str, pos: usize) -> (usize, Vec<Pair>) {
-    let safe_pos = pos.min(line.len());
+/// Live bindings the completer can draw on besides the static tables.
+pub trait CompletionScope {
+    fn global_names(&self) -> Vec<String>;
+    /// Property names of the value a bare identifier chain refers to.
+    fn property_names_of(&self, path: &str) -> Option<Vec<String>>;
+}
+
+impl CompletionScope for Context {
+    fn global_names(&self) -> Vec<String> {
+        Context::global_names(self)
+    }
+
+    fn property_names_of(&self, path: &str) -> Option<Vec<String>> {
+        Context::property_names_of(self, path)
+    }
+}
+
+pub fn complete_line(
+    line: &str,
+    pos: usize,
+    scope: Option<&dyn CompletionScope>,
+) -> (usize, Vec<Pair>) {
+    let (start, names) = candidates(line, pos, scope);
+    (start, pairs(&names))
+}
+
+/// Where the completed word starts and what it may be replaced with.
+/// Without a scope, or when it can't resolve the receiver, this falls
+/// back to the keyword and built-in tables.
+pub fn candidates(
+    line: &str,
+    pos: usize,
+    scope: Option<&dyn CompletionScope>,
+) -> (usize, Vec<String>) {
+    let mut safe_pos = pos.min(line.len());
+    while !line.is_char_boundary(safe_pos) {
+        safe_pos -= 1;
+    }
     let prefix = &line[..safe_pos];
 
-    if let Some((start, members)) = member_completion(prefix) {
-        return (start, pairs(&members));
+    if let Some(completion) = member_completion(prefix, scope) {
+        return completion;
     }
 
     let start = word_start(prefix);
     let needle = &prefix[start..];
-    let words = keywords()
+    let live = scope.map(|s| s.global_names()).unwrap_or_default();
+    let mut words: Vec<String> = Vec::new();
+    for word in keywords()
         .iter()
         .chain(globals().iter())
-        .copied()
-        .filter(|kw| kw.starts_with(needle))
-        .collect::<Vec<_>>();
+        .map(|w| w.to_string())
+        .chain(live)
+    {
+        if word.starts_with(needle) && !words.contains(&word) {
+            words.push(word);
+        }
+    }
 
-    (start, pairs(&words))
+    (start, words)
 }
 
-fn member_completion(prefix: &str) -> Option<(usize, Vec<&'static str>)> {
+fn member_completion(
+    prefix: &str,
+    scope: Option<&dyn CompletionScope>,
+) -> Option<(usize, Vec<String>)> {
     let dot = prefix.rfind('.')?;
     let object_part = &prefix[..dot];
-    let object_start = word_start(object_part);
-    let object_name = &object_part[object_start..];
     let member_prefix = &prefix[dot + 1..];
+    if !member_prefix.chars().all(is_ident_char) {
+        return None;
+    }
 
-    let members = members_for(object_name)?;
+    let live =
+        receiver_chain(object_part).and_then(|path| scope.and_then(|s| s.property_names_of(path)));
+    let members = live.unwrap_or_else(|| {
+        let object_name = &object_part[word_start(object_part)..];
+        members_for(object_name)
+            .unwrap_or_default()
+            .iter()
+            .map(|name| name.to_string())
+            .collect()
+    });
     let filtered = members
-        .iter()
-        .copied()
+        .into_iter()
         .filter(|name| name.starts_with(member_prefix))
-        .collect::<Vec<_>>();
+        .collect();
 
     Some((dot + 1, filtered))
 }
 
+/// The `a.b.c` chain at the end of `text`, if it is made of plain
+/// identifiers only. Anything involving calls, indexing or literals is
+/// rejected so completion never has to run code.
+fn receiver_chain(text: &str) -> Option<&str> {
+    let start = text
+        .char_indices()
+        .rev()
+        .find(|(_, ch)| !is_ident_char(*ch) && *ch != '.')
+        .map_or(0, |(idx, ch)| idx + ch.len_utf8());
+    if text[..start]
+        .trim_end()
+        .ends_with([')', ']', '"', '\'', '`'])
+    {
+        return None;
+    }
+    let chain = &text[start..];
+    let valid = chain.split('.').all(|segment| {
+        segment
+            .chars()
+            .next()
+            .is_some_and(|ch| !ch.is_ascii_digit())
+    });
+    valid.then_some(chain)
+}
+
 fn members_for(object_name: &str) -> Option<&'static [&'static str]> {
     match object_name {
         "Math" => Some(&[
@@ -50,12 +130,12 @@ fn members_for(object_name: &str) -> Option<&'static [&'static str]> {
     }
 }
 
-fn pairs(values: &[&str]) -> Vec<Pair> {
+fn pairs(values: &[String]) -> Vec<Pair> {
     values
         .iter()
         .map(|v| Pair {
-            display: (*v).to_string(),
-            replacement: (*v).to_string(),
+            display: v.clone(),
+            replacement: v.clone(),
         })
         .collect()
 }
@@ -122,3 +202,6 @@ fn globals() -> &'static [&'static str] {
         "setInterval",
     ]
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,82 @@
+use super::*;
+
+struct FakeScope;
+
+impl CompletionScope for FakeScope {
+    fn global_names(&self) -> Vec<String> {
+        vec!["player".into(), "playlist".into(), "console".into()]
+    }
+
+    fn property_names_of(&self, path: &str) -> Option<Vec<String>> {
+        match path {
+            "player" => Some(vec!["name".into(), "stats".into()]),
+            "player.stats" => Some(vec!["hp".into(), "hpMax".into(), "mp".into()]),
+            _ => None,
+        }
+    }
+}
+
+fn complete(line: &str) -> (usize, Vec<String>) {
+    candidates(line, line.len(), Some(&FakeScope))
+}
+
+#[test]
+fn test_globals_from_scope_are_offered_once() {
+    assert_eq!(
+        complete("pla"),
+        (0, vec!["player".into(), "playlist".into()])
+    );
+    let (_, words) = complete("cons");
+    assert_eq!(words, vec!["const".to_string(), "console".to_string()]);
+}
+
+#[test]
+fn test_member_chain_resolves_through_scope() {
+    assert_eq!(
+        complete("x = player.stats.hp"),
+        (17, vec!["hp".into(), "hpMax".into()])
+    );
+    assert_eq!(
+        complete("player."),
+        (7, vec!["name".into(), "stats".into()])
+    );
+}
+
+#[test]
+fn test_unresolvable_receivers_fall_back_to_static_tables() {
+    assert_eq!(complete("JSON.st"), (5, vec!["stringify".into()]));
+    assert_eq!(complete("player.name().").1, Vec::<String>::new());
+    assert_eq!(complete("items[0].").1, Vec::<String>::new());
+    assert_eq!(complete("1.5.").1, Vec::<String>::new());
+}
+
+#[test]
+fn test_without_scope_only_keywords_and_builtins() {
+    let (start, words) = candidates("Math.fl", 7, None);
+    assert_eq!((start, words), (5, vec!["floor".to_string()]));
+    let (_, words) = candidates("pla", 3, None);
+    assert!(words.is_empty());
+}
+
+#[test]
+fn test_live_context_lists_own_and_builtin_members() {
+    let mut ctx = Context::new_with_realtime(false);
+    ctx.eval(
+        r#"
+        const game = { level: 3, hero: { hp: 10 }, tags: ["a"], seen: new Set() };
+        let label = "x";
+        "#,
+    )
+    .unwrap();
+
+    let (_, words) = candidates("game.", 5, Some(&ctx));
+    assert_eq!(words, vec!["hero", "level", "seen", "tags"]);
+    let (_, words) = candidates("game.tags.fil", 13, Some(&ctx));
+    assert_eq!(words, vec!["filter"]);
+    let (_, words) = candidates("game.seen.h", 11, Some(&ctx));
+    assert_eq!(words, vec!["has"]);
+    let (_, words) = candidates("label.toU", 9, Some(&ctx));
+    assert_eq!(words, vec!["toUpperCase"]);
+    let (_, words) = candidates("gam", 3, Some(&ctx));
+    assert_eq!(words, vec!["game"]);
+}
@@ -1,4 +1,8 @@
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crustyjs::context::Context;
 
 use rustyline::completion::{Completer, Pair};
 use rustyline::highlight::{CmdKind, Highlighter};
@@ -8,8 +12,17 @@ use rustyline::{Context as RustyContext, Result as RustyResult};
 
 use super::{completer, highlighter, hinter};
 
-#[derive(Clone, Default)]
-pub struct ReplHelper;
+/// Shares the REPL's context so completion can see the live bindings.
+#[derive(Clone)]
+pub struct ReplHelper {
+    ctx: Rc<RefCell<Context>>,
+}
+
+impl ReplHelper {
+    pub fn new(ctx: Rc<RefCell<Context>>) -> Self {
+        Self { ctx }
+    }
+}
 
 impl rustyline::Helper for ReplHelper {}
 
@@ -22,7 +35,11 @@ impl Completer for ReplHelper {
         pos: usize,
         _ctx: &RustyContext<'_>,
     ) -> RustyResult<(usize, Vec<Pair>)> {
-        Ok(completer::complete_line(line, pos))
+        let ctx = self.ctx.try_borrow().ok();
+        let scope = ctx
+            .as_deref()
+            .map(|ctx| ctx as &dyn completer::CompletionScope);
+        Ok(completer::complete_line(line, pos, scope))
     }
 }
 
@@ -0,0 +1,183 @@
+use crustyjs::runtime::value::JsValue;
+use crustyjs::runtime::value::object::Property;
+use crustyjs::runtime::value::promise::PromiseState;
+
+/// Containers nested deeper than this are shown as `[Object]`.
+const MAX_DEPTH: usize = 4;
+/// Containers that fit in this many columns stay on one line.
+const INLINE_WIDTH: usize = 72;
+
+/// Pretty-prints `value` for `.inspect`, listing non-enumerable and
+/// internal properties such as `[[ErrorType]]` and a promise's state.
+pub fn inspect(value: &JsValue) -> String {
+    Inspector { seen: Vec::new() }.value(value, 0)
+}
+
+struct Inspector {
+    /// Containers being printed, to spot cycles.
+    seen: Vec<JsValue>,
+}
+
+impl Inspector {
+    fn value(&mut self, value: &JsValue, depth: usize) -> String {
+        match value {
+            JsValue::String(s) => format!("{:?}", s.as_str()),
+            JsValue::Function { name, .. } | JsValue::NativeFunction { name, .. } => {
+                if name.is_empty() {
+                    "[Function (anonymous)]".to_string()
+                } else {
+                    format!("[Function: {name}]")
+                }
+            }
+            JsValue::Object(obj) => {
+                let obj = obj.borrow();
+                let mut props: Vec<(String, Property)> = obj
+                    .properties
+                    .iter()
+                    .map(|(key, prop)| (key.to_string(), prop.clone()))
+                    .collect();
+                props.sort_by(|(a, _), (b, _)| {
+                    (!a.starts_with("[["))
+                        .cmp(&!b.starts_with("[["))
+                        .then(a.cmp(b))
+                });
+                props.extend(
+                    obj.symbol_properties
+                        .values()
+                        .map(|(sym, prop)| (format!("[{sym}]"), prop.clone())),
+                );
+                drop(obj);
+                let prefix = match props.iter().find(|(key, _)| key == "[[ErrorType]]") {
+                    Some((_, prop)) => format!("{} ", prop.value),
+                    None => String::new(),
+                };
+                self.container(value, depth, prefix, ('{', '}'), |this, depth| {
+                    props
+                        .iter()
+                        .map(|(key, prop)| this.property(key, prop, depth))
+                        .collect()
+                })
+            }
+            JsValue::Array(arr) => {
+                let elements = arr.borrow().elements.clone();
+                self.container(value, depth, String::new(), ('[', ']'), |this, depth| {
+                    elements.iter().map(|v| this.value(v, depth)).collect()
+                })
+            }
+            JsValue::Promise(promise) => {
+                let state = promise.borrow().state.clone();
+                let prefix = "Promise ".to_string();
+                self.container(
+                    value,
+                    depth,
+                    prefix,
+                    ('{', '}'),
+                    |this, depth| match &state {
+                        PromiseState::Pending => vec!["[[PromiseState]]: \"pending\"".to_string()],
+                        PromiseState::Fulfilled(result) => vec![
+                            "[[PromiseState]]: \"fulfilled\"".to_string(),
+                            format!("[[PromiseResult]]: {}", this.value(result, depth)),
+                        ],
+                        PromiseState::Rejected(result) => vec![
+                            "[[PromiseState]]: \"rejected\"".to_string(),
+                            format!("[[PromiseResult]]: {}", this.value(result, depth)),
+                        ],
+                    },
+                )
+            }
+            JsValue::Map(map) => {
+                let entries: Vec<_> = map.borrow().iter().cloned().collect();
+                let prefix = format!("Map({}) ", entries.len());
+                self.container(value, depth, prefix, ('{', '}'), |this, depth| {
+                    entries
+                        .iter()
+                        .map(|(k, v)| {
+                            format!("{} => {}", this.value(k, depth), this.value(v, depth))
+                        })
+                        .collect()
+                })
+            }
+            JsValue::Set(set) => {
+                let entries: Vec<_> = set.borrow().iter().cloned().collect();
+                let prefix = format!("Set({}) ", entries.len());
+                self.container(value, depth, prefix, ('{', '}'), |this, depth| {
+                    entries.iter().map(|v| this.value(v, depth)).collect()
+                })
+            }
+            _ => value.to_string(),
+        }
+    }
+
+    fn property(&mut self, key: &str, prop: &Property, depth: usize) -> String {
+        let label = if key.starts_with('[') {
+            key.to_string()
+        } else if !prop.enumerable {
+            format!("[{}]", key_label(key))
+        } else {
+            key_label(key)
+        };
+        let value = match (&prop.getter, &prop.setter) {
+            (Some(_), Some(_)) => "[Getter/Setter]".to_string(),
+            (Some(_), None) => "[Getter]".to_string(),
+            (None, Some(_)) => "[Setter]".to_string(),
+            (None, None) => self.value(&prop.value, depth),
+        };
+        format!("{label}: {value}")
+    }
+
+    /// Lays out a container's entries on one line when they fit, else one
+    /// per line, guarding against cycles and runaway nesting.
+    fn container(
+        &mut self,
+        value: &JsValue,
+        depth: usize,
+        prefix: String,
+        (open, close): (char, char),
+        entries: impl FnOnce(&mut Self, usize) -> Vec<String>,
+    ) -> String {
+        if self.seen.contains(value) {
+            return "[Circular]".to_string();
+        }
+        if depth >= MAX_DEPTH {
+            let kind = if open == '[' { "Array" } else { "Object" };
+            return format!("[{kind}]");
+        }
+        self.seen.push(value.clone());
+        let entries = entries(self, depth + 1);
+        self.seen.pop();
+
+        if entries.is_empty() {
+            return format!("{prefix}{open}{close}");
+        }
+        let inline = entries.join(", ");
+        if !inline.contains('\n') && prefix.len() + inline.len() + 4 <= INLINE_WIDTH {
+            return format!("{prefix}{open} {inline} {close}");
+        }
+        let body: Vec<String> = entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .lines()
+                    .map(|line| format!("  {line}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect();
+        format!("{prefix}{open}\n{}\n{close}", body.join(",\n"))
+    }
+}
+
+fn key_label(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_alphabetic() || ch == '_' || ch == '$')
+        && key
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$');
+    if is_identifier || key.parse::<u32>().is_ok() {
+        key.to_string()
+    } else {
+        format!("{key:?}")
+    }
+}
@@ -1,15 +1,20 @@
+mod commands;
 mod completer;
 mod helper;
 mod highlighter;
 mod hinter;
+mod inspect;
 
 use crustyjs::context::Context;
 use crustyjs::errors::{CrustyError, RuntimeError};
 use owo_colors::OwoColorize;
 use rustyline::error::ReadlineError;
 use rustyline::{Config, EditMode, Editor};
-use std::fs;
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
 
+use self::commands::Outcome;
 use self::helper::ReplHelper;
 
 pub fn run() -> Result<(), CrustyError> {
@@ -22,9 +27,8 @@ pub fn run() -> Result<(), CrustyError> {
 
     let mut rl: Editor<ReplHelper, rustyline::history::DefaultHistory> =
         Editor::with_config(config).map_err(to_runtime_error)?;
-    rl.set_helper(Some(ReplHelper));
-
-    let mut ctx = Context::new_with_realtime(true);
+    let ctx = Rc::new(RefCell::new(new_context()));
+    rl.set_helper(Some(ReplHelper::new(Rc::clone(&ctx))));
 
     println!(
         "{} {}",
@@ -41,12 +45,16 @@ pub fn run() -> Result<(), CrustyError> {
                     continue;
                 }
 
-                if handle_command(trimmed, &mut ctx)? {
-                    continue;
+                let _ = rl.add_history_entry(trimmed);
+                match commands::dispatch(trimmed, &mut ctx.borrow_mut(), &mut io::stdout())
+                    .map_err(to_output_error)?
+                {
+                    Outcome::Exit => break,
+                    Outcome::Handled => continue,
+                    Outcome::NotCommand => {}
                 }
 
-                let _ = rl.add_history_entry(trimmed);
-                run_snippet(&mut ctx, trimmed);
+                run_snippet(&mut ctx.borrow_mut(), trimmed);
             }
             Err(ReadlineError::Interrupted) => {
                 println!("{}", "^C".yellow());
@@ -66,45 +74,16 @@ pub fn run() -> Result<(), CrustyError> {
     Ok(())
 }
 
-fn handle_command(trimmed: &str, ctx: &mut Context) -> Result<bool, CrustyError> {
-    if trimmed == ".exit" || trimmed == "exit" {
-        std::process::exit(0);
-    }
-    if trimmed == ".help" {
-        println!("{}", ".help                show commands".bright_blue());
-        println!(
-            "{}",
-            ".clear               reset interpreter state".bright_blue()
-        );
-        println!(
-            "{}",
-            ".load <file.js>      load and run script".bright_blue()
-        );
-        println!("{}", ".exit                exit REPL".bright_blue());
-        return Ok(true);
-    }
-    if trimmed == ".clear" {
-        *ctx = Context::new_with_realtime(true);
-        println!("{}", "environment cleared".green());
-        return Ok(true);
-    }
-    if let Some(path) = trimmed.strip_prefix(".load ") {
-        let path = path.trim();
-        match fs::read_to_string(path) {
-            Ok(source) => {
-                run_snippet(ctx, &source);
-            }
-            Err(err) => eprintln!("{} {err}", "load error:".red().bold()),
-        }
-        return Ok(true);
-    }
-    Ok(false)
+fn new_context() -> Context {
+    let mut ctx = Context::new_with_realtime(true);
+    ctx.set_output_mode(crustyjs::OutputMode::Stdout);
+    ctx
 }
 
 fn run_snippet(ctx: &mut Context, source: &str) {
     match ctx.eval(source) {
         Ok(()) => println!("{}", "undefined".bright_black()),
-        Err(err) => eprintln!("{} {err:?}", "error:".red().bold()),
+        Err(err) => eprintln!("{} {err}", "error:".red().bold()),
     }
 }
 
@@ -160,3 +139,9 @@ fn to_runtime_error(err: ReadlineError) -> CrustyError {
         message: format!("failed to initialize REPL: {err}"),
     })
 }
+
+fn to_output_error(err: io::Error) -> CrustyError {
+    CrustyError::Runtime(RuntimeError::TypeError {
+        message: format!("failed to write REPL output: {err}"),
+    })
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crustyjs::diagnostics::source_map::SourceMap;
+use crustyjs::diagnostics::stack_trace::CallFrame;
+use crustyjs::errors::{CrustyError, LocatedRuntimeError, RuntimeError, SyntaxError};
+use miette::{Diagnostic, MietteHandler, MietteHandlerOpts, ReportHandler};
+use owo_colors::OwoColorize;
+
+/// Formats the errors a script run ends with, either through miette's
+/// report handler with a source excerpt or as one plain line.
+pub struct Reporter {
+    /// `None` for the compact `--plain-errors` format.
+    handler: Option<MietteHandler>,
+}
+
+impl Reporter {
+    pub fn new(plain: bool) -> Self {
+        Self::with_handler((!plain).then(|| MietteHandlerOpts::new().build()))
+    }
+
+    pub fn with_handler(handler: Option<MietteHandler>) -> Self {
+        Self { handler }
+    }
+
+    /// `phase` is "lex" or "parse", shown only in the plain format.
+    pub fn syntax_error(
+        &self,
+        err: SyntaxError,
+        source: &str,
+        source_path: &Path,
+        phase: &str,
+    ) -> String {
+        let Some(handler) = &self.handler else {
+            return plain_syntax_error(&err, source, source_path, phase);
+        };
+        let err = err.with_source_code(source_path.display().to_string(), source);
+        render(handler, &err)
+    }
+
+    /// `frame` is where the interpreter says the error was raised; it may
+    /// be in a module other than the entry script.
+    pub fn runtime_error(
+        &self,
+        err: RuntimeError,
+        source: &str,
+        source_path: &Path,
+        frame: Option<&CallFrame>,
+    ) -> String {
+        let Some(handler) = &self.handler else {
+            // The causes of a thrown error would follow on lines of their
+            // own.
+            let text = err.to_string();
+            let title = text.lines().next().unwrap_or_default();
+            return format!("{} {title}", "runtime error:".red().bold());
+        };
+        let entry = source_path.display().to_string();
+        let located = match frame {
+            Some(frame) if frame.file == entry => {
+                LocatedRuntimeError::new(err, entry, source, Some(frame))
+            }
+            Some(frame) => match fs::read_to_string(&frame.file) {
+                Ok(module) => LocatedRuntimeError::new(err, &frame.file, module, Some(frame)),
+                Err(_) => LocatedRuntimeError::new(err, entry, source, None),
+            },
+            None => LocatedRuntimeError::new(err, entry, source, None),
+        };
+        render(handler, &located)
+    }
+
+    /// Errors from the VM path, which lexes and parses on its own.
+    pub fn crusty_error(&self, err: CrustyError, source: &str, source_path: &Path) -> String {
+        match err {
+            CrustyError::Syntax(err) => self.syntax_error(err, source, source_path, "parse"),
+            CrustyError::Runtime(err) => self.runtime_error(err, source, source_path, None),
+        }
+    }
+}
+
+fn render(handler: &MietteHandler, diagnostic: &dyn Diagnostic) -> String {
+    struct Report<'a>(&'a MietteHandler, &'a dyn Diagnostic);
+
+    impl fmt::Display for Report<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.debug(self.1, f)
+        }
+    }
+
+    Report(handler, diagnostic).to_string()
+}
+
+fn plain_syntax_error(err: &SyntaxError, source: &str, source_path: &Path, phase: &str) -> String {
+    let map = SourceMap::from_source(source);
+    let pos = map.byte_to_pos(err.span.offset());
+    format!(
+        "{} {} at {}:{}:{}: {}",
+        "syntax".red().bold(),
+        phase.yellow(),
+        source_path.display().to_string().cyan(),
+        pos.line,
+        pos.col,
+        err.message.bright_white()
+    )
+}
+
+#[cfg(test)]
+mod tests;
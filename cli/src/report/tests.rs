@@ -0,0 +1,149 @@
+use super::*;
+use crustyjs::runtime::interpreter::Interpreter;
+
+fn reporter() -> Reporter {
+    Reporter::with_handler(Some(
+        MietteHandlerOpts::new()
+            .color(false)
+            .unicode(false)
+            .terminal_links(false)
+            .force_graphical(true)
+            .width(80)
+            .build(),
+    ))
+}
+
+/// Runs `source` from a real file, as the CLI does, and renders the error
+/// it ends with under the stable name `demo.js`.
+fn render_runtime_error(source: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("crustyjs_report_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("demo.js");
+    fs::write(&path, source).unwrap();
+
+    let program = crustyjs::parser::parse(crustyjs::lexer::lex(source).unwrap()).unwrap();
+    let mut interp = Interpreter::new();
+    let err = interp
+        .run_with_path(&program, path.clone())
+        .expect_err("script should throw");
+    reporter()
+        .runtime_error(err, source, &path, interp.error_location())
+        .replace(&path.display().to_string(), "demo.js")
+}
+
+#[test]
+fn test_syntax_error_snapshot() {
+    let source = "let total = 1;\nlet next = (total + ;\n";
+    let err = crustyjs::parser::parse(crustyjs::lexer::lex(source).unwrap()).unwrap_err();
+    let rendered = reporter().syntax_error(err, source, Path::new("demo.js"), "parse");
+    assert_eq!(
+        rendered,
+        "  x SyntaxError: unexpected token Semicolon in expression
+   ,-[demo.js:2:21]
+ 1 | let total = 1;
+ 2 | let next = (total + ;
+   :                     |
+   :                     `-- here
+   `----
+  help: check the syntax around this location
+"
+    );
+}
+
+#[test]
+fn test_thrown_type_error_snapshot() {
+    let source = "let limit = 3;
+function check(value) {
+  if (value > limit) {
+    throw new TypeError(\"value is over the limit\");
+  }
+}
+check(5);
+";
+    assert_eq!(
+        render_runtime_error(source),
+        "  x Uncaught TypeError: value is over the limit
+   ,-[demo.js:2:1]
+ 1 | let limit = 3;
+ 2 | function check(value) {
+   : ^^^^|^^^
+   :     `-- raised in 'check'
+ 3 |   if (value > limit) {
+   `----
+  help: nothing caught this error; wrap the call in try/catch to handle it
+"
+    );
+}
+
+#[test]
+fn test_internal_errors_are_titled_once_without_frames() {
+    let source = "function run() {
+  const x = 1;
+  x();
+}
+run();
+";
+    let rendered = render_runtime_error(source);
+    assert_eq!(
+        rendered.lines().next(),
+        Some("  x TypeError: '1' is not a function"),
+        "{rendered}"
+    );
+    assert!(!rendered.contains("    at "), "{rendered}");
+}
+
+#[test]
+fn test_caught_errors_leave_no_location() {
+    let source = "function fail() { throw new Error(\"inner\"); }
+try { fail(); } catch (e) {}
+null.x;
+";
+    let rendered = render_runtime_error(source);
+    assert!(
+        rendered.contains("Cannot read properties of null"),
+        "{rendered}"
+    );
+    assert!(!rendered.contains("raised in"), "{rendered}");
+}
+
+#[test]
+fn test_plain_errors_stay_on_one_line() {
+    let reporter = Reporter::new(true);
+    let source = "let x = ;";
+    let err = crustyjs::parser::parse(crustyjs::lexer::lex(source).unwrap()).unwrap_err();
+    let rendered = reporter.syntax_error(err, source, Path::new("demo.js"), "parse");
+    assert_eq!(rendered.lines().count(), 1, "{rendered}");
+    assert!(rendered.contains("demo.js"), "{rendered}");
+
+    let err = RuntimeError::TypeError {
+        message: "boom".to_string(),
+    };
+    let rendered = reporter.runtime_error(err, source, Path::new("demo.js"), None);
+    assert!(rendered.ends_with("TypeError: boom"), "{rendered}");
+}
+
+#[test]
+fn test_plain_internal_errors_are_titled_once() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_plain_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("demo.js");
+    let source = "function run() {\n  const x = 1;\n  x();\n}\nrun();\n";
+    fs::write(&path, source).unwrap();
+
+    let program = crustyjs::parser::parse(crustyjs::lexer::lex(source).unwrap()).unwrap();
+    let mut interp = Interpreter::new();
+    let err = interp
+        .run_with_path(&program, path.clone())
+        .expect_err("script should throw");
+    let rendered = Reporter::new(true).runtime_error(err, source, &path, interp.error_location());
+    assert_eq!(rendered.lines().count(), 1, "{rendered}");
+    assert!(
+        rendered.ends_with(" TypeError: '1' is not a function"),
+        "{rendered}"
+    );
+    assert!(
+        interp
+            .error_trace()
+            .is_some_and(|trace| trace.contains("at run"))
+    );
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Something that can tell the watch loop which files changed.
+pub trait ChangeSource {
+    /// Starts tracking `paths`, replacing the previously watched set.
+    fn watch(&mut self, paths: &[PathBuf]);
+    /// Watched paths modified, created or removed since the last call.
+    fn changed(&mut self) -> Vec<PathBuf>;
+}
+
+/// Detects changes by comparing modification times on every poll.
+#[derive(Default)]
+pub struct MtimePoller {
+    mtimes: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+fn mtime(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl ChangeSource for MtimePoller {
+    fn watch(&mut self, paths: &[PathBuf]) {
+        self.mtimes = paths.iter().map(|p| (p.clone(), mtime(p))).collect();
+    }
+
+    fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, seen) in &mut self.mtimes {
+            let current = mtime(path);
+            if current != *seen {
+                *seen = current;
+                changed.push(path.clone());
+            }
+        }
+        changed.sort();
+        changed
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WatchTiming {
+    /// How long to sleep between polls while idle.
+    pub poll: Duration,
+    /// Quiet period a burst of changes must settle for before re-running.
+    pub debounce: Duration,
+}
+
+impl Default for WatchTiming {
+    fn default() -> Self {
+        Self {
+            poll: Duration::from_millis(200),
+            debounce: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Calls `run` with the files that triggered it (empty on the first run)
+/// and watches the paths it returns, re-running whenever one changes
+/// until `stop` is set.
+pub fn watch_loop<S, F>(source: &mut S, timing: WatchTiming, stop: &AtomicBool, mut run: F)
+where
+    S: ChangeSource,
+    F: FnMut(&[PathBuf]) -> Vec<PathBuf>,
+{
+    let mut trigger = Vec::new();
+    loop {
+        let paths = run(&trigger);
+        source.watch(&paths);
+
+        trigger = loop {
+            if stop.load(Ordering::SeqCst) {
+                return;
+            }
+            let changed = source.changed();
+            if !changed.is_empty() {
+                break changed;
+            }
+            thread::sleep(timing.poll);
+        };
+
+        loop {
+            thread::sleep(timing.debounce);
+            let more = source.changed();
+            if more.is_empty() {
+                break;
+            }
+            trigger.extend(more);
+        }
+        trigger.sort();
+        trigger.dedup();
+    }
+}
+
+/// `HH:MM:SS` wall-clock time (UTC) for the restart banner.
+pub fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+
+use super::*;
+
+/// Replays scripted batches of changes, then requests a stop once they
+/// run out.
+struct ScriptedChanges<'a> {
+    batches: VecDeque<Vec<PathBuf>>,
+    watched: Vec<Vec<PathBuf>>,
+    stop: &'a AtomicBool,
+}
+
+impl ChangeSource for ScriptedChanges<'_> {
+    fn watch(&mut self, paths: &[PathBuf]) {
+        self.watched.push(paths.to_vec());
+    }
+
+    fn changed(&mut self) -> Vec<PathBuf> {
+        self.batches.pop_front().unwrap_or_else(|| {
+            self.stop.store(true, Ordering::SeqCst);
+            Vec::new()
+        })
+    }
+}
+
+fn instant() -> WatchTiming {
+    WatchTiming {
+        poll: Duration::ZERO,
+        debounce: Duration::ZERO,
+    }
+}
+
+fn paths(names: &[&str]) -> Vec<PathBuf> {
+    names.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn test_burst_of_changes_triggers_one_rerun() {
+    let stop = AtomicBool::new(false);
+    let mut source = ScriptedChanges {
+        batches: VecDeque::from([
+            paths(&["main.js"]),
+            paths(&["lib.js"]),
+            paths(&["main.js"]),
+            Vec::new(),
+            Vec::new(),
+            paths(&["lib.js"]),
+            Vec::new(),
+        ]),
+        watched: Vec::new(),
+        stop: &stop,
+    };
+    let mut triggers = Vec::new();
+    watch_loop(&mut source, instant(), &stop, |changed| {
+        triggers.push(changed.to_vec());
+        paths(&["lib.js", "main.js"])
+    });
+
+    assert_eq!(
+        triggers,
+        vec![
+            Vec::new(),
+            paths(&["lib.js", "main.js"]),
+            paths(&["lib.js"]),
+        ]
+    );
+}
+
+#[test]
+fn test_watched_set_follows_loaded_files() {
+    let stop = AtomicBool::new(false);
+    let mut source = ScriptedChanges {
+        batches: VecDeque::from([paths(&["main.js"]), Vec::new()]),
+        watched: Vec::new(),
+        stop: &stop,
+    };
+    let mut run = 0;
+    watch_loop(&mut source, instant(), &stop, |_| {
+        run += 1;
+        if run == 1 {
+            paths(&["main.js"])
+        } else {
+            paths(&["dep.js", "main.js"])
+        }
+    });
+
+    assert_eq!(
+        source.watched,
+        vec![paths(&["main.js"]), paths(&["dep.js", "main.js"])]
+    );
+}
+
+#[test]
+fn test_mtime_poller_reports_modified_and_removed_files() {
+    let dir = std::env::temp_dir().join(format!("crustyjs-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("main.js");
+    fs::write(&file, "1").unwrap();
+
+    let mut poller = MtimePoller::default();
+    poller.watch(std::slice::from_ref(&file));
+    assert!(poller.changed().is_empty());
+
+    let later = SystemTime::now() + Duration::from_secs(5);
+    fs::File::options()
+        .write(true)
+        .open(&file)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    assert_eq!(poller.changed(), vec![file.clone()]);
+    assert!(poller.changed().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(poller.changed(), vec![file]);
+}
@@ -0,0 +1,33 @@
+use crate::errors::SyntaxError;
+use crate::lexer::token::Token;
+use crate::parser::ast::Program;
+use crate::vm::bytecode::Chunk;
+
+/// What [`crate::analyze`] learned about a source text, for editors and
+/// other tools that want the pipeline's artifacts rather than a run.
+#[derive(Debug)]
+pub struct AnalysisResult {
+    /// Every token, comments included, ending with `Eof`. Empty when the
+    /// source could not be tokenized.
+    pub tokens: Vec<Token>,
+    /// The parsed program, present only when it parsed without errors.
+    pub program: Option<Program>,
+    /// Syntax errors in source order, from the scanner or the parser.
+    pub errors: Vec<SyntaxError>,
+    /// Bytecode for the program, when the VM compiler handles all of it.
+    pub bytecode: Option<CompiledBytecode>,
+}
+
+/// A compiled chunk together with its listing.
+#[derive(Debug, Clone)]
+pub struct CompiledBytecode {
+    pub chunk: Chunk,
+    /// One instruction per line, with the source line it came from.
+    pub disassembly: String,
+}
+
+impl AnalysisResult {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
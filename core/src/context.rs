@@ -1,18 +1,38 @@
+use std::cell::{Ref, RefMut};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
 
+use crate::context_builder::ContextBuilder;
+#[cfg(feature = "profiling")]
+use crate::diagnostics::profile::Profile;
+use crate::diagnostics::trace::TraceConfig;
+use crate::embedding::call_scope::CallScope;
 use crate::embedding::callback::NativeFunctionBoxed;
 use crate::embedding::class_builder::NativeClassDef;
-use crate::embedding::event_target::EventTarget;
+use crate::embedding::event_target::{EventTarget, EventTargetHandle};
 use crate::embedding::function_args::FunctionArgs;
-use crate::errors::CrustyError;
+use crate::embedding::handle::{Persistent, PersistentRoots};
+use crate::embedding::object_builder::JsObjectBuilder;
+use crate::embedding::promise_resolver::PromiseResolver;
+use crate::errors::{CrustyError, RuntimeError};
 use crate::runtime::environment::BindingKind;
-use crate::runtime::interpreter::Interpreter;
+#[cfg(feature = "http")]
+use crate::runtime::fetch::FetchPolicy;
+use crate::runtime::gc::{CollectStats, GcMode, PauseHistogram};
+#[cfg(feature = "ast-serde")]
+use crate::runtime::modules::ast_cache::AstCacheStats;
+use crate::runtime::interpreter::{Interpreter, ModuleInfo, YieldCallback};
+use crate::runtime::output::OutputMode;
+use crate::runtime::snapshot::Snapshot;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::typed_array::{JsArrayBuffer, JsTypedArray, TypedArrayKind};
 use crate::runtime::value::{JsValue, NativeFunction};
 
 pub struct Context {
-    interpreter: Interpreter,
+    pub(crate) interpreter: Interpreter,
 }
 
 impl Context {
@@ -20,21 +40,119 @@ impl Context {
         Self::new_with_realtime(true)
     }
 
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+
     pub fn new_with_realtime(realtime: bool) -> Self {
-        Self { interpreter: Interpreter::new_with_realtime_timers(realtime) }
+        let mut interpreter = Interpreter::new_with_realtime_timers(realtime);
+        interpreter.set_output_mode(OutputMode::Capture);
+        Self { interpreter }
+    }
+
+    pub fn set_output_mode(&mut self, mode: OutputMode) {
+        self.interpreter.set_output_mode(mode);
+    }
+
+    /// Installs a hook for errors thrown by callbacks the host drives,
+    /// such as event listeners. Without one, the first such error is
+    /// returned once the remaining listeners have run.
+    pub fn set_error_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&RuntimeError) + Send + Sync + 'static,
+    {
+        self.interpreter.set_error_hook(Arc::new(hook));
+    }
+
+    /// Installs a hook for errors thrown by timer and animation-frame
+    /// callbacks, which no longer stop the event loop. Without one they
+    /// are written to the output.
+    pub fn on_uncaught_exception<F>(&mut self, hook: F)
+    where
+        F: Fn(&RuntimeError) + Send + Sync + 'static,
+    {
+        self.interpreter.on_uncaught_exception(Arc::new(hook));
     }
 
     pub fn set_max_steps(&mut self, max: usize) {
         self.interpreter.set_max_steps(max);
     }
 
+    /// Records an execution trace of the statements and calls the config
+    /// selects, replacing any trace already being recorded.
+    pub fn set_trace(&mut self, config: TraceConfig) {
+        self.interpreter.set_trace(config);
+    }
+
+    pub fn clear_trace(&mut self) {
+        self.interpreter.clear_trace();
+    }
+
+    /// Installs a hook for notes about problems the engine recovered from,
+    /// such as an AST cache entry that could not be read.
+    pub fn set_diagnostics_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.interpreter.set_diagnostics_hook(Arc::new(hook));
+    }
+
+    /// Caches the parsed form of imported modules in `dir`, keyed by a
+    /// hash of their source. Missing or corrupt entries are parsed anew.
+    #[cfg(feature = "ast-serde")]
+    pub fn set_ast_cache_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.interpreter.set_ast_cache_dir(dir);
+    }
+
+    /// Hits and misses since `set_ast_cache_dir`, or `None` without a cache.
+    #[cfg(feature = "ast-serde")]
+    pub fn ast_cache_stats(&self) -> Option<AstCacheStats> {
+        self.interpreter.ast_cache_stats()
+    }
+
+    /// Limits the hosts scripts may `fetch` from, or turns `fetch` off so
+    /// that every call rejects.
+    #[cfg(feature = "http")]
+    pub fn set_fetch_policy(&mut self, policy: FetchPolicy) {
+        self.interpreter.set_fetch_policy(policy);
+    }
+
+    /// Evaluation steps taken so far, the count `set_max_steps` limits.
+    pub fn step_count(&self) -> usize {
+        self.interpreter.step_count
+    }
+
     pub fn eval(&mut self, source: &str) -> Result<(), CrustyError> {
         let tokens = crate::lexer::lex(source)?;
-        let program = crate::parser::parse(tokens)?;
-        self.interpreter.run(&program)?;
+        let program = self.interpreter.parse_tokens(tokens, source)?;
+        let result = self.interpreter.run(&program);
+        self.interpreter.collect_garbage_if_requested();
+        result?;
         Ok(())
     }
 
+    /// Evaluates `source` and returns the value of its last expression
+    /// statement, or `undefined` when it has none.
+    pub fn eval_value(&mut self, source: &str) -> Result<JsValue, CrustyError> {
+        let tokens = crate::lexer::lex(source)?;
+        let program = self.interpreter.parse_tokens(tokens, source)?;
+        let result = self.interpreter.run_for_value(&program);
+        self.interpreter.collect_garbage_if_requested();
+        Ok(result?)
+    }
+
+    /// `eval_value` for hosts running inside tokio. Timers are waited for
+    /// by awaiting on the runtime that polls the returned future, so other
+    /// contexts and tasks on its thread run in the meantime. It has to be
+    /// polled within a tokio runtime that has its time driver enabled.
+    pub async fn eval_async(&mut self, source: &str) -> Result<JsValue, CrustyError> {
+        let tokens = crate::lexer::lex(source)?;
+        let program = self.interpreter.parse_tokens(tokens, source)?;
+        let result = self.interpreter.run_for_value_async(&program).await;
+        self.interpreter.collect_garbage_if_requested();
+        Ok(result?)
+    }
+
     pub fn eval_module<P: AsRef<Path>>(&mut self, path: P) -> Result<(), CrustyError> {
         let path_buf: PathBuf = path.as_ref().to_path_buf();
         let source = fs::read_to_string(&path_buf).map_err(|e| {
@@ -43,11 +161,43 @@ impl Context {
             })
         })?;
         let tokens = crate::lexer::lex(&source)?;
-        let program = crate::parser::parse(tokens)?;
-        self.interpreter.run_with_path(&program, path_buf)?;
+        let program = self.interpreter.parse_module_tokens(tokens, &source)?;
+        let result = self.interpreter.run_with_path(&program, path_buf);
+        self.interpreter.collect_garbage_if_requested();
+        result?;
         Ok(())
     }
 
+    /// The modules loaded so far; see [`Interpreter::module_graph`].
+    pub fn module_graph(&self) -> Vec<ModuleInfo> {
+        self.interpreter.module_graph()
+    }
+
+    /// Installs a hook that adds host-defined properties, such as `hot`,
+    /// to each module's `import.meta` before it is frozen.
+    pub fn set_import_meta_provider<F>(&mut self, provider: F)
+    where
+        F: Fn(&Path, &mut JsObjectBuilder) + Send + Sync + 'static,
+    {
+        self.interpreter
+            .set_import_meta_provider(Arc::new(provider));
+    }
+
+    /// Loads modules imported `with { type: import_type }`, such as
+    /// `"text"`, by calling `handler` with the resolved path and the
+    /// import's attributes. It sets the module's exports on the builder,
+    /// or returns a message that the import throws as a `TypeError`.
+    pub fn set_import_type_handler<F>(&mut self, import_type: impl Into<String>, handler: F)
+    where
+        F: Fn(&Path, &[(String, String)], &mut JsObjectBuilder) -> Result<(), String>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.interpreter
+            .set_import_type_handler(import_type, Arc::new(handler));
+    }
+
     pub fn get_global(&self, name: &str) -> Result<JsValue, CrustyError> {
         Ok(self.interpreter.env.get(name)?)
     }
@@ -59,23 +209,116 @@ impl Context {
         }
     }
 
+    /// Binds `name` as a `const` global: scripts can read it, and assigning
+    /// to it throws the usual "Assignment to constant variable" TypeError.
+    /// The value itself is not frozen.
+    pub fn set_global_readonly(&mut self, name: impl Into<String>, value: JsValue) {
+        self.interpreter
+            .env
+            .define_with_kind(name.into(), value, BindingKind::Const);
+    }
+
+    /// Binds `name` to the value `json` parses to, the way `JSON.parse`
+    /// would build it.
+    pub fn set_global_json(&mut self, name: impl Into<String>, json: &str) -> Result<(), CrustyError> {
+        let name = name.into();
+        let parsed: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| RuntimeError::TypeError {
+                message: format!("invalid JSON for global '{name}': {e}"),
+            })?;
+        let value = self.interpreter.from_json_value(&parsed);
+        self.set_global(name, value);
+        Ok(())
+    }
+
     pub fn set_global_function<F>(&mut self, name: impl Into<String>, callback: F)
     where
         F: Fn(FunctionArgs) -> Result<JsValue, crate::errors::RuntimeError> + Send + Sync + 'static,
     {
-        let name = name.into();
+        self.set_global_host_function(name.into(), NativeFunctionBoxed::from_closure(callback));
+    }
+
+    /// Like [`Context::set_global_function`], for a function that calls
+    /// back into script, such as one taking a callback.
+    pub fn set_global_function_with_scope<F>(&mut self, name: impl Into<String>, callback: F)
+    where
+        F: Fn(&mut CallScope, FunctionArgs) -> Result<JsValue, crate::errors::RuntimeError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let callback = NativeFunctionBoxed::from_scoped_closure(callback);
+        self.set_global_host_function(name.into(), callback);
+    }
+
+    fn set_global_host_function(&mut self, name: String, callback: NativeFunctionBoxed) {
+        self.interpreter
+            .host_functions
+            .insert(name.clone(), callback.clone());
         let function = JsValue::NativeFunction {
             name: name.clone(),
-            handler: NativeFunction::Host(NativeFunctionBoxed::from_closure(callback)),
+            handler: NativeFunction::Host(callback),
         };
         self.set_global(name, function);
     }
 
+    /// Captures the global state built up so far, such as a prelude's
+    /// functions and classes, so that new contexts can start from it
+    /// without running the prelude again.
+    pub fn snapshot(&self) -> Result<Snapshot, CrustyError> {
+        Ok(self.interpreter.snapshot()?)
+    }
+
+    /// A new context with the globals of `snapshot` in place. Host
+    /// functions it names must be registered again with
+    /// `set_global_function` before they are called.
+    pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let mut context = Self::new();
+        context.interpreter.restore_snapshot(snapshot);
+        context
+    }
+
+    /// Structured-clones `value` into this context's heap, so values read
+    /// out of one context can be handed to another.
+    pub fn deep_clone(&mut self, value: &JsValue) -> Result<JsValue, CrustyError> {
+        Ok(self.interpreter.deep_clone(value)?)
+    }
+
+    /// Names bound in the global scope, for tools such as REPL completion.
+    pub fn global_names(&self) -> Vec<String> {
+        self.interpreter.global_binding_names()
+    }
+
+    /// Property names visible on the value of `path`, a bare identifier
+    /// chain like `player.stats`. The chain is resolved without running
+    /// any script code; `None` when that isn't possible.
+    pub fn property_names_of(&self, path: &str) -> Option<Vec<String>> {
+        let value = self.interpreter.peek_path(path)?;
+        Some(self.interpreter.visible_property_names(&value))
+    }
+
+    /// Stores `value` for native functions to reach through
+    /// `FunctionArgs::data`, replacing any earlier value of the same type.
+    pub fn set_data<T: 'static>(&mut self, value: T) -> Option<T> {
+        Rc::get_mut(&mut self.interpreter.context_data)
+            .expect("context data is only shared while a host function runs")
+            .insert(value)
+    }
+
+    pub fn data<T: 'static>(&self) -> Option<Ref<'_, T>> {
+        self.interpreter.context_data.get()
+    }
+
+    pub fn data_mut<T: 'static>(&self) -> Option<RefMut<'_, T>> {
+        self.interpreter.context_data.get_mut()
+    }
+
     pub fn register_class(&mut self, class_def: NativeClassDef) {
         let class_name = class_def.name.clone();
         let mut merged_methods = HashMap::new();
         let mut merged_getters = HashMap::new();
         let mut merged_setters = HashMap::new();
+        let mut coercion = class_def.coercion.clone();
 
         if let Some(parent_name) = &class_def.parent
             && let Some(parent) = self.interpreter.native_classes.get(parent_name)
@@ -83,6 +326,7 @@ impl Context {
             merged_methods.extend(parent.methods.clone());
             merged_getters.extend(parent.getters.clone());
             merged_setters.extend(parent.setters.clone());
+            coercion = coercion.or(&parent.coercion);
         }
 
         merged_methods.extend(class_def.methods.clone());
@@ -96,6 +340,7 @@ impl Context {
             static_methods: class_def.static_methods.clone(),
             getters: merged_getters,
             setters: merged_setters,
+            coercion,
             parent: class_def.parent.clone(),
         };
 
@@ -116,6 +361,36 @@ impl Context {
         Ok(())
     }
 
+    /// See `Interpreter::set_yield_hook`.
+    pub fn set_yield_hook(&mut self, interval_steps: usize, hook: YieldCallback) {
+        self.interpreter.set_yield_hook(interval_steps, hook);
+    }
+
+    /// See `Interpreter::set_lazy_functions`.
+    pub fn set_lazy_functions(&mut self, lazy: bool) {
+        self.interpreter.set_lazy_functions(lazy);
+    }
+
+    /// See `Interpreter::set_manual_timers`.
+    pub fn set_manual_timers(&mut self, manual: bool) {
+        self.interpreter.set_manual_timers(manual);
+    }
+
+    /// Moves the virtual clock forward by `ms`, firing the timers that
+    /// come due without sleeping.
+    pub fn advance_time(&mut self, ms: u64) -> Result<(), CrustyError> {
+        self.interpreter.advance_time(ms)?;
+        Ok(())
+    }
+
+    pub fn pending_timer_count(&self) -> usize {
+        self.interpreter.pending_timer_count()
+    }
+
+    pub fn next_timer_due_in(&self) -> Option<u64> {
+        self.interpreter.next_timer_due_in()
+    }
+
     pub fn run_animation_callbacks(&mut self, timestamp_ms: f64) -> Result<(), CrustyError> {
         self.interpreter.run_animation_callbacks(timestamp_ms)?;
         Ok(())
@@ -137,9 +412,149 @@ impl Context {
         Ok(())
     }
 
+    /// Creates an `EventTarget` object that scripts can subscribe to and
+    /// the host can fire events on through the returned handle.
+    pub fn create_event_target(&mut self) -> EventTargetHandle {
+        let object = self.interpreter.heap.alloc_cell(JsObject::new());
+        let target = self.interpreter.attach_event_target(object);
+        EventTargetHandle {
+            target,
+            object: self.persist(JsValue::Object(object)),
+        }
+    }
+
+    /// Fires an event object `{ type, detail, target }` at the listeners
+    /// of `target`, then drains the microtask queue.
+    pub fn emit_event(
+        &mut self,
+        target: &EventTargetHandle,
+        event_type: &str,
+        detail: JsValue,
+    ) -> Result<(), CrustyError> {
+        let mut event = JsObject::new();
+        event.set("type", JsValue::String(event_type.into()));
+        event.set("detail", detail);
+        event.set("target", target.object());
+        let event = JsValue::Object(self.interpreter.heap.alloc_cell(event));
+        let dispatched = self.interpreter.dispatch_to_listeners(
+            &target.target,
+            target.object(),
+            event_type,
+            event,
+        );
+        self.interpreter.run_microtasks_only()?;
+        dispatched?;
+        Ok(())
+    }
+
+    /// A pending promise for scripts to wait on, and the handle the host
+    /// settles it through once its work, such as a database query, is done.
+    pub fn create_promise(&mut self) -> (JsValue, PromiseResolver) {
+        self.interpreter.create_resolvable_promise()
+    }
+
+    /// A `Uint8Array` over a new buffer holding a copy of `bytes`, for
+    /// handing binary data such as a file's contents to scripts.
+    pub fn create_uint8_array(&mut self, bytes: &[u8]) -> JsValue {
+        let heap = &mut self.interpreter.heap;
+        let buffer = heap.alloc_cell(JsArrayBuffer::new(bytes.to_vec()));
+        let array = JsTypedArray::new(TypedArrayKind::Uint8, buffer, 0, bytes.len());
+        JsValue::TypedArray(heap.alloc_cell(array))
+    }
+
+    /// A copy of the bytes an `ArrayBuffer`, typed array or `DataView`
+    /// covers, in memory order; `None` for any other value.
+    pub fn bytes_of(&self, value: &JsValue) -> Option<Vec<u8>> {
+        match value {
+            JsValue::ArrayBuffer(buffer) => Some(buffer.borrow().bytes.clone()),
+            JsValue::TypedArray(array) => Some(array.borrow().bytes()),
+            JsValue::DataView(view) => Some(view.borrow().bytes()),
+            _ => None,
+        }
+    }
+
+    /// Installs the `$262` host object used by the test262 suite as a
+    /// global and as a property of `globalThis`.
+    ///
+    /// # Safety
+    ///
+    /// `$262.gc()` collects once the current evaluation returns, so the
+    /// contract of [`Context::collect_garbage`] applies to every
+    /// evaluation from here on.
+    pub unsafe fn install_test262_host(&mut self) {
+        let host = self.interpreter.create_test262_host();
+        self.set_global("$262", host);
+    }
+
+    /// Defines a global `gc()` that asks for a collection once the current
+    /// evaluation returns.
+    ///
+    /// # Safety
+    ///
+    /// The contract of [`Context::collect_garbage`] applies to every
+    /// evaluation from here on.
+    pub unsafe fn expose_gc(&mut self) {
+        let gc = JsValue::NativeFunction {
+            name: "gc".to_string(),
+            handler: NativeFunction::CollectGarbage,
+        };
+        self.set_global("gc", gc);
+    }
+
+    /// Keeps `value` alive across garbage collections until the returned
+    /// handle is dropped.
+    pub fn persist(&self, value: JsValue) -> Persistent {
+        self.interpreter.persistent_roots.persist(value)
+    }
+
+    /// The table behind `persist`, for hosts that need to create handles
+    /// while the context itself is borrowed, such as inside a native
+    /// function.
+    pub fn persistent_roots(&self) -> PersistentRoots {
+        self.interpreter.persistent_roots.clone()
+    }
+
+    /// Frees every value that is neither reachable from script nor
+    /// persisted.
+    ///
+    /// # Safety
+    ///
+    /// Values the host holds are not roots. A value taken out of the
+    /// context, by `get_global`, `eval_value`, a native function's
+    /// arguments and the like, that script no longer reaches must be
+    /// kept through [`Context::persist`] or dropped before this call.
+    /// Using it afterwards reads freed memory.
+    pub unsafe fn collect_garbage(&mut self) -> CollectStats {
+        unsafe { self.interpreter.collect_garbage() }
+    }
+
+    /// Chooses whether the context collects on its own between timer and
+    /// animation callbacks, and how long each step may pause the script.
+    ///
+    /// # Safety
+    ///
+    /// A budgeted mode collects while the event loop runs, so the
+    /// contract of [`Context::collect_garbage`] applies to every call
+    /// that runs script or timers from here on.
+    pub unsafe fn set_gc_mode(&mut self, mode: GcMode) {
+        unsafe { self.interpreter.set_gc_mode(mode) };
+    }
+
+    /// Every collector pause so far, explicit or automatic.
+    pub fn gc_pauses(&self) -> PauseHistogram {
+        self.interpreter.gc_pauses()
+    }
+
     pub fn output(&self) -> &[String] {
         self.interpreter.output()
     }
+
+    /// Statements, expressions and builtin calls counted since the
+    /// profile was last taken, and the collector's pauses.
+    #[cfg(feature = "profiling")]
+    pub fn take_profile(&mut self) -> Profile {
+        self.interpreter.take_profile()
+    }
 }
 
 impl Default for Context {
@@ -0,0 +1,173 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::context::Context;
+#[cfg(feature = "http")]
+use crate::runtime::fetch::FetchPolicy;
+use crate::runtime::interpreter::{
+    DEFAULT_MAX_CALL_DEPTH, DEFAULT_MICROTASK_LIMIT, ModuleResolver,
+};
+use crate::runtime::output::OutputMode;
+
+/// Configures a [`Context`] before it is created. The defaults match
+/// `Context::new()`: sloppy mode, lazily parsed function bodies, realtime
+/// timers, no step limit, captured console output and no `gc` global.
+#[derive(Clone)]
+pub struct ContextBuilder {
+    strict: bool,
+    lazy_functions: bool,
+    realtime_timers: bool,
+    max_steps: Option<usize>,
+    max_call_depth: usize,
+    microtask_limit: usize,
+    expose_gc: bool,
+    output_mode: OutputMode,
+    module_resolver: Option<ModuleResolver>,
+    #[cfg(feature = "http")]
+    fetch_policy: FetchPolicy,
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            lazy_functions: true,
+            realtime_timers: true,
+            max_steps: None,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            microtask_limit: DEFAULT_MICROTASK_LIMIT,
+            expose_gc: false,
+            output_mode: OutputMode::Capture,
+            module_resolver: None,
+            #[cfg(feature = "http")]
+            fetch_policy: FetchPolicy::default(),
+        }
+    }
+}
+
+impl ContextBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses every script and module evaluated in the context as strict
+    /// mode code. Without it, a `"use strict"` directive still opts a file
+    /// in.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Parses function bodies when first called, the default, instead of
+    /// up front. Turn it off to have every syntax error reported before
+    /// anything runs.
+    pub fn lazy_functions(mut self, lazy: bool) -> Self {
+        self.lazy_functions = lazy;
+        self
+    }
+
+    /// Sleeps until timers are due instead of jumping the virtual clock.
+    pub fn realtime_timers(mut self, realtime: bool) -> Self {
+        self.realtime_timers = realtime;
+        self
+    }
+
+    pub fn max_steps(mut self, max: usize) -> Self {
+        self.max_steps = Some(max);
+        self
+    }
+
+    pub fn max_call_depth(mut self, max: usize) -> Self {
+        self.max_call_depth = max;
+        self
+    }
+
+    /// Microtasks run in one drain of the queue before evaluation fails
+    /// as a likely endless microtask loop.
+    pub fn microtask_limit(mut self, max: usize) -> Self {
+        self.microtask_limit = max;
+        self
+    }
+
+    /// Defines a global `gc()` that asks for a collection once the
+    /// current evaluation returns.
+    ///
+    /// # Safety
+    ///
+    /// With `expose` set, the contract of `Context::collect_garbage`
+    /// applies to every evaluation on the built context.
+    pub unsafe fn expose_gc(mut self, expose: bool) -> Self {
+        self.expose_gc = expose;
+        self
+    }
+
+    /// Hands each line of console output to `sink` instead of keeping it
+    /// for `output()`.
+    pub fn console_sink<F>(mut self, sink: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.output_mode = OutputMode::sink(sink);
+        self
+    }
+
+    /// Resolves import specifiers with `resolve`, which is given the
+    /// specifier and the path of the importing module.
+    pub fn module_resolver<F>(mut self, resolve: F) -> Self
+    where
+        F: Fn(&str, &Path) -> PathBuf + Send + Sync + 'static,
+    {
+        self.module_resolver = Some(Arc::new(resolve));
+        self
+    }
+
+    /// Which hosts `fetch` may reach. Every host is allowed by default.
+    #[cfg(feature = "http")]
+    pub fn fetch_policy(mut self, policy: FetchPolicy) -> Self {
+        self.fetch_policy = policy;
+        self
+    }
+
+    pub fn build(self) -> Context {
+        let mut ctx = Context::new_with_realtime(self.realtime_timers);
+        ctx.set_output_mode(self.output_mode);
+        let interp = &mut ctx.interpreter;
+        interp.set_strict(self.strict);
+        interp.set_lazy_functions(self.lazy_functions);
+        interp.set_max_call_depth(self.max_call_depth);
+        interp.set_microtask_limit(self.microtask_limit);
+        if let Some(max) = self.max_steps {
+            interp.set_max_steps(max);
+        }
+        if let Some(resolver) = self.module_resolver {
+            interp.set_module_resolver(resolver);
+        }
+        #[cfg(feature = "http")]
+        interp.set_fetch_policy(self.fetch_policy);
+        if self.expose_gc {
+            // SAFETY: the caller accepted the contract in `expose_gc`.
+            unsafe { ctx.expose_gc() };
+        }
+        ctx
+    }
+}
+
+impl fmt::Debug for ContextBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("ContextBuilder");
+        builder
+            .field("strict", &self.strict)
+            .field("lazy_functions", &self.lazy_functions)
+            .field("realtime_timers", &self.realtime_timers)
+            .field("max_steps", &self.max_steps)
+            .field("max_call_depth", &self.max_call_depth)
+            .field("microtask_limit", &self.microtask_limit)
+            .field("expose_gc", &self.expose_gc)
+            .field("output_mode", &self.output_mode)
+            .field("module_resolver", &self.module_resolver.is_some());
+        #[cfg(feature = "http")]
+        builder.field("fetch_policy", &self.fetch_policy);
+        builder.finish()
+    }
+}
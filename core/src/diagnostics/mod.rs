@@ -1,2 +1,5 @@
+#[cfg(feature = "profiling")]
+pub mod profile;
 pub mod source_map;
 pub mod stack_trace;
+pub mod trace;
@@ -0,0 +1,103 @@
+//! Counters collected behind the `profiling` feature: the statements and
+//! expressions the interpreter evaluated, calls into builtins and the time
+//! spent in them, collector pauses, and the opcodes the VM executed.
+//!
+//! Without the feature none of this is compiled and the engines keep no
+//! counters at all.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::runtime::gc::PauseHistogram;
+
+/// Calls to one builtin and the time spent in them, including any script
+/// callbacks they ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuiltinStats {
+    pub calls: u64,
+    pub time: Duration,
+}
+
+/// Collector pauses, full and budgeted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GcProfile {
+    pub pauses: u64,
+    pub total_micros: u64,
+    pub max_micros: u64,
+}
+
+impl From<PauseHistogram> for GcProfile {
+    fn from(pauses: PauseHistogram) -> Self {
+        Self {
+            pauses: pauses.total(),
+            total_micros: pauses.total_micros(),
+            max_micros: pauses.max_micros(),
+        }
+    }
+}
+
+/// What an engine did since it was created or since the profile was last
+/// taken.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    /// Statements executed, by `Stmt::kind`.
+    pub statements: HashMap<&'static str, u64>,
+    /// Expressions evaluated, by `Expr::kind`.
+    pub expressions: HashMap<&'static str, u64>,
+    /// Builtin calls by name, such as `Math.floor` or `Array.push`.
+    pub builtins: HashMap<String, BuiltinStats>,
+    /// Instructions the VM executed, by `Opcode::name`.
+    pub opcodes: HashMap<&'static str, u64>,
+    pub gc: GcProfile,
+}
+
+impl Profile {
+    pub(crate) fn count_statement(&mut self, kind: &'static str) {
+        *self.statements.entry(kind).or_default() += 1;
+    }
+
+    pub(crate) fn count_expression(&mut self, kind: &'static str) {
+        *self.expressions.entry(kind).or_default() += 1;
+    }
+
+    pub(crate) fn count_opcode(&mut self, name: &'static str) {
+        *self.opcodes.entry(name).or_default() += 1;
+    }
+
+    /// Records a call to `owner.method` that began at `started`. Global
+    /// functions have an empty owner.
+    pub(crate) fn record_builtin(&mut self, owner: &str, method: &str, started: Instant) {
+        let elapsed = started.elapsed();
+        let name = if owner.is_empty() {
+            method.to_string()
+        } else {
+            format!("{owner}.{method}")
+        };
+        let stats = self.builtins.entry(name).or_default();
+        stats.calls += 1;
+        stats.time += elapsed;
+    }
+
+    /// Builtins ordered by the time spent in them, then by their calls.
+    pub fn builtins_by_time(&self) -> Vec<(&str, BuiltinStats)> {
+        let mut rows: Vec<_> = self
+            .builtins
+            .iter()
+            .map(|(name, stats)| (name.as_str(), *stats))
+            .collect();
+        rows.sort_by(|a, b| {
+            b.1.time
+                .cmp(&a.1.time)
+                .then(b.1.calls.cmp(&a.1.calls))
+                .then(a.0.cmp(b.0))
+        });
+        rows
+    }
+
+    /// The entries of one of the count tables, most frequent first.
+    pub fn by_count(counts: &HashMap<&'static str, u64>) -> Vec<(&'static str, u64)> {
+        let mut rows: Vec<_> = counts.iter().map(|(name, count)| (*name, *count)).collect();
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        rows
+    }
+}
@@ -20,6 +20,12 @@ impl SourceMap {
         Self { line_offsets }
     }
 
+    /// Byte offset of `pos`. Lines past the end count as the last line.
+    pub fn pos_to_byte(&self, pos: SourcePos) -> usize {
+        let line_idx = pos.line.saturating_sub(1).min(self.line_offsets.len() - 1);
+        self.line_offsets[line_idx] + pos.col.saturating_sub(1)
+    }
+
     pub fn byte_to_pos(&self, byte: usize) -> SourcePos {
         let line_idx = match self.line_offsets.binary_search(&byte) {
             Ok(i) => i,
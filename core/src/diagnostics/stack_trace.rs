@@ -1,3 +1,7 @@
+use crate::errors::RuntimeError;
+use crate::runtime::value::JsValue;
+use crate::runtime::value::object::Property;
+
 #[derive(Debug, Clone)]
 pub struct CallFrame {
     pub function_name: String,
@@ -20,6 +24,10 @@ impl CallStack {
         self.frames.pop();
     }
 
+    pub fn top(&self) -> Option<&CallFrame> {
+        self.frames.last()
+    }
+
     pub fn snapshot(&self) -> Vec<CallFrame> {
         self.frames.clone()
     }
@@ -35,3 +43,39 @@ impl CallStack {
         out
     }
 }
+
+/// Records `trace` on a thrown error object. Other kinds are returned
+/// as they are; their trace is kept apart from the message, by
+/// `Interpreter::error_trace` or the VM's own.
+pub(crate) fn attach_stack(err: RuntimeError, trace: &str) -> RuntimeError {
+    if let RuntimeError::Thrown { value } = &err
+        && !trace.is_empty()
+    {
+        record_error_stack(value, trace);
+    }
+    err
+}
+
+/// Gives a thrown error object a non-enumerable `stack` the first time it
+/// unwinds out of a call, so it keeps the frames it was thrown from.
+fn record_error_stack(value: &JsValue, trace: &str) {
+    let JsValue::Object(obj) = value else {
+        return;
+    };
+    let mut obj = obj.borrow_mut();
+    let (Some(name), Some(message)) = (obj.get("name"), obj.get("message")) else {
+        return;
+    };
+    if obj.properties.contains_key("stack") {
+        return;
+    }
+    let (name, message) = (name.to_js_string(), message.to_js_string());
+    let stack = format!("{name}: {message}\n{}", trace.trim_end());
+    obj.properties.insert(
+        "stack".into(),
+        Property {
+            enumerable: false,
+            ..Property::new(JsValue::String(stack.into()))
+        },
+    );
+}
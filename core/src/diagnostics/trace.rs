@@ -0,0 +1,139 @@
+//! An opt-in trace of what the interpreter executes: each statement, each
+//! call into a script function and each return from one.
+//!
+//! Statements carry no source positions of their own, so an event is
+//! placed at the function it happened in, as its call frame records it.
+
+use std::fmt;
+
+/// Receives trace events as they happen.
+pub type TraceSink = Box<dyn FnMut(&TraceEvent)>;
+
+/// What a [`TraceEvent`] records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEventKind {
+    /// A statement about to run, by `Stmt::kind`.
+    Statement(&'static str),
+    Call,
+    Return,
+}
+
+/// One event of an execution trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent {
+    pub kind: TraceEventKind,
+    /// The function the statement runs in, or the one being called or
+    /// returned from. Top-level code is `<global>`.
+    pub function: String,
+    pub path: String,
+    pub line: usize,
+    pub col: usize,
+    /// How deeply calls were nested when the event happened. A call or
+    /// return event is at the depth of its caller.
+    pub depth: usize,
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            TraceEventKind::Statement(kind) => kind,
+            TraceEventKind::Call => "call",
+            TraceEventKind::Return => "return",
+        };
+        write!(
+            f,
+            "{}{what} {} ({}:{}:{})",
+            "  ".repeat(self.depth),
+            self.function,
+            self.path,
+            self.line,
+            self.col
+        )
+    }
+}
+
+/// Selects what is traced and where the events go. Nothing is traced
+/// until [`statements`](Self::statements) or [`calls`](Self::calls) turns
+/// it on; events are written to stderr unless a sink is given.
+pub struct TraceConfig {
+    pub(crate) statements: bool,
+    pub(crate) calls: bool,
+    pub(crate) path_filter: Option<String>,
+    pub(crate) max_events: Option<usize>,
+    pub(crate) sink: TraceSink,
+}
+
+impl Default for TraceConfig {
+    fn default() -> Self {
+        Self {
+            statements: false,
+            calls: false,
+            path_filter: None,
+            max_events: None,
+            sink: Box::new(|event| eprintln!("{event}")),
+        }
+    }
+}
+
+impl TraceConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records every statement before it runs.
+    pub fn statements(mut self, on: bool) -> Self {
+        self.statements = on;
+        self
+    }
+
+    /// Records calls into script functions and the returns from them.
+    pub fn calls(mut self, on: bool) -> Self {
+        self.calls = on;
+        self
+    }
+
+    /// Only records events whose source path contains `filter`.
+    pub fn path_filter(mut self, filter: impl Into<String>) -> Self {
+        self.path_filter = Some(filter.into());
+        self
+    }
+
+    /// Stops recording after `max` events, so that a long loop does not
+    /// flood the sink.
+    pub fn max_events(mut self, max: usize) -> Self {
+        self.max_events = Some(max);
+        self
+    }
+
+    pub fn sink(mut self, sink: impl FnMut(&TraceEvent) + 'static) -> Self {
+        self.sink = Box::new(sink);
+        self
+    }
+}
+
+/// A trace being recorded.
+pub(crate) struct Tracer {
+    pub(crate) config: TraceConfig,
+    emitted: usize,
+}
+
+impl Tracer {
+    pub(crate) fn new(config: TraceConfig) -> Self {
+        Self { config, emitted: 0 }
+    }
+
+    /// Whether an event with `path` would still be recorded.
+    pub(crate) fn wants(&self, path: &str) -> bool {
+        self.config.max_events.is_none_or(|max| self.emitted < max)
+            && self
+                .config
+                .path_filter
+                .as_deref()
+                .is_none_or(|filter| path.contains(filter))
+    }
+
+    pub(crate) fn emit(&mut self, event: &TraceEvent) {
+        self.emitted += 1;
+        (self.config.sink)(event);
+    }
+}
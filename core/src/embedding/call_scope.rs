@@ -0,0 +1,48 @@
+use crate::errors::RuntimeError;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::JsValue;
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::object::JsObject;
+
+/// The interpreter a native function was called from, lent to it for the
+/// length of the call so that it can call back into script.
+pub struct CallScope<'a> {
+    interpreter: &'a mut Interpreter,
+}
+
+impl<'a> CallScope<'a> {
+    pub(crate) fn new(interpreter: &'a mut Interpreter) -> Self {
+        Self { interpreter }
+    }
+
+    /// Calls `function` with `args` and no `this`, as `function(...args)`.
+    pub fn call(&mut self, function: &JsValue, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
+        self.interpreter.call_function(function, args)
+    }
+
+    /// Calls the method `name` of `object`, as `object.name(...args)`.
+    pub fn call_method(
+        &mut self,
+        object: &JsValue,
+        name: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let method = self.interpreter.get_property(object, name)?;
+        self.interpreter
+            .call_function_with_this(&method, args, Some(object.clone()))
+    }
+
+    pub fn alloc_object(&mut self) -> JsValue {
+        JsValue::Object(self.interpreter.heap.alloc_cell(JsObject::new()))
+    }
+
+    pub fn alloc_array(&mut self, values: Vec<JsValue>) -> JsValue {
+        JsValue::Array(self.interpreter.heap.alloc_cell(JsArray::new(values)))
+    }
+
+    /// A `TypeError` the script can catch, for the native function to
+    /// return.
+    pub fn throw_type_error(&mut self, message: &str) -> RuntimeError {
+        self.interpreter.throw_type_error(message)
+    }
+}
@@ -1,11 +1,16 @@
 use std::sync::Arc;
 
+use crate::embedding::call_scope::CallScope;
 use crate::embedding::function_args::FunctionArgs;
 use crate::errors::RuntimeError;
 use crate::runtime::value::JsValue;
 
 pub trait NativeFunction: Send + Sync {
-    fn call(&self, args: FunctionArgs) -> Result<JsValue, RuntimeError>;
+    fn call(
+        &self,
+        scope: &mut CallScope<'_>,
+        args: FunctionArgs<'_>,
+    ) -> Result<JsValue, RuntimeError>;
 }
 
 #[derive(Clone)]
@@ -26,6 +31,7 @@ impl NativeFunctionBoxed {
         }
     }
 
+    /// Wraps a closure that only needs its arguments.
     pub fn from_closure<F>(callback: F) -> Self
     where
         F: Fn(FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync + 'static,
@@ -33,8 +39,24 @@ impl NativeFunctionBoxed {
         Self::new(ClosureFunction { callback })
     }
 
-    pub fn call(&self, args: FunctionArgs) -> Result<JsValue, RuntimeError> {
-        self.callback.call(args)
+    /// Wraps a closure that may call back into script through its
+    /// [`CallScope`].
+    pub fn from_scoped_closure<F>(callback: F) -> Self
+    where
+        F: Fn(&mut CallScope, FunctionArgs) -> Result<JsValue, RuntimeError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self::new(ScopedClosureFunction { callback })
+    }
+
+    pub fn call(
+        &self,
+        scope: &mut CallScope<'_>,
+        args: FunctionArgs<'_>,
+    ) -> Result<JsValue, RuntimeError> {
+        self.callback.call(scope, args)
     }
 
     pub fn ptr_eq(&self, other: &Self) -> bool {
@@ -50,7 +72,28 @@ impl<F> NativeFunction for ClosureFunction<F>
 where
     F: Fn(FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync,
 {
-    fn call(&self, args: FunctionArgs) -> Result<JsValue, RuntimeError> {
+    fn call(
+        &self,
+        _scope: &mut CallScope<'_>,
+        args: FunctionArgs<'_>,
+    ) -> Result<JsValue, RuntimeError> {
         (self.callback)(args)
     }
 }
+
+struct ScopedClosureFunction<F> {
+    callback: F,
+}
+
+impl<F> NativeFunction for ScopedClosureFunction<F>
+where
+    F: Fn(&mut CallScope, FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync,
+{
+    fn call(
+        &self,
+        scope: &mut CallScope<'_>,
+        args: FunctionArgs<'_>,
+    ) -> Result<JsValue, RuntimeError> {
+        (self.callback)(scope, args)
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use crate::embedding::call_scope::CallScope;
 use crate::embedding::callback::NativeFunctionBoxed;
 use crate::embedding::function_args::FunctionArgs;
 use crate::errors::RuntimeError;
@@ -13,9 +14,36 @@ pub struct NativeClassDef {
     pub static_methods: HashMap<String, NativeFunctionBoxed>,
     pub getters: HashMap<String, NativeFunctionBoxed>,
     pub setters: HashMap<String, NativeFunctionBoxed>,
+    pub coercion: CoercionHooks,
     pub parent: Option<String>,
 }
 
+/// How instances of a native class turn into primitives. Each hook is
+/// installed on instances as the matching non-enumerable method, so
+/// string concatenation, template literals and comparisons find it the
+/// way they find a script-defined one.
+#[derive(Clone, Default)]
+pub struct CoercionHooks {
+    /// Installed as `toString`.
+    pub to_string: Option<NativeFunctionBoxed>,
+    /// Installed as `valueOf`.
+    pub value_of: Option<NativeFunctionBoxed>,
+    /// Installed as `[Symbol.toPrimitive]`; called with the hint,
+    /// `"default"`, `"number"` or `"string"`, as its only argument.
+    pub to_primitive: Option<NativeFunctionBoxed>,
+}
+
+impl CoercionHooks {
+    /// These hooks, falling back to `parent`'s where one is missing.
+    pub fn or(self, parent: &CoercionHooks) -> CoercionHooks {
+        CoercionHooks {
+            to_string: self.to_string.or_else(|| parent.to_string.clone()),
+            value_of: self.value_of.or_else(|| parent.value_of.clone()),
+            to_primitive: self.to_primitive.or_else(|| parent.to_primitive.clone()),
+        }
+    }
+}
+
 pub struct ClassBuilder {
     name: String,
     constructor: Option<NativeFunctionBoxed>,
@@ -23,6 +51,7 @@ pub struct ClassBuilder {
     static_methods: HashMap<String, NativeFunctionBoxed>,
     getters: HashMap<String, NativeFunctionBoxed>,
     setters: HashMap<String, NativeFunctionBoxed>,
+    coercion: CoercionHooks,
     parent: Option<String>,
 }
 
@@ -35,6 +64,7 @@ impl ClassBuilder {
             static_methods: HashMap::new(),
             getters: HashMap::new(),
             setters: HashMap::new(),
+            coercion: CoercionHooks::default(),
             parent: None,
         }
     }
@@ -56,6 +86,22 @@ impl ClassBuilder {
         self
     }
 
+    /// Like [`ClassBuilder::method`], for a method that calls back into
+    /// script.
+    pub fn method_with_scope<F>(mut self, name: impl Into<String>, callback: F) -> Self
+    where
+        F: Fn(&mut CallScope, FunctionArgs) -> Result<JsValue, RuntimeError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.methods.insert(
+            name.into(),
+            NativeFunctionBoxed::from_scoped_closure(callback),
+        );
+        self
+    }
+
     pub fn static_method<F>(mut self, name: impl Into<String>, callback: F) -> Self
     where
         F: Fn(FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync + 'static,
@@ -65,6 +111,22 @@ impl ClassBuilder {
         self
     }
 
+    /// Like [`ClassBuilder::static_method`], for a method that calls back
+    /// into script.
+    pub fn static_method_with_scope<F>(mut self, name: impl Into<String>, callback: F) -> Self
+    where
+        F: Fn(&mut CallScope, FunctionArgs) -> Result<JsValue, RuntimeError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.static_methods.insert(
+            name.into(),
+            NativeFunctionBoxed::from_scoped_closure(callback),
+        );
+        self
+    }
+
     pub fn property_getter<F>(mut self, name: impl Into<String>, callback: F) -> Self
     where
         F: Fn(FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync + 'static,
@@ -83,6 +145,34 @@ impl ClassBuilder {
         self
     }
 
+    /// Sets how instances convert to strings, as their `toString`.
+    pub fn to_string<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync + 'static,
+    {
+        self.coercion.to_string = Some(NativeFunctionBoxed::from_closure(callback));
+        self
+    }
+
+    /// Sets how instances convert to numbers, as their `valueOf`.
+    pub fn value_of<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync + 'static,
+    {
+        self.coercion.value_of = Some(NativeFunctionBoxed::from_closure(callback));
+        self
+    }
+
+    /// Sets `[Symbol.toPrimitive]`, which takes precedence over
+    /// `to_string` and `value_of` and receives the hint as its argument.
+    pub fn to_primitive<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync + 'static,
+    {
+        self.coercion.to_primitive = Some(NativeFunctionBoxed::from_closure(callback));
+        self
+    }
+
     pub fn inherit(mut self, parent: impl Into<String>) -> Self {
         self.parent = Some(parent.into());
         self
@@ -96,6 +186,7 @@ impl ClassBuilder {
             static_methods: self.static_methods,
             getters: self.getters,
             setters: self.setters,
+            coercion: self.coercion,
             parent: self.parent,
         }
     }
@@ -0,0 +1,51 @@
+use std::any::{Any, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+
+/// Host values stored on a context, one per type, so native functions can
+/// reach shared state without capturing it.
+///
+/// Each entry sits in its own `RefCell`: different types can be borrowed
+/// at the same time, and an entry that is already mutably borrowed reads
+/// as absent instead of panicking.
+#[derive(Default)]
+pub struct ContextData {
+    entries: HashMap<TypeId, RefCell<Box<dyn Any>>>,
+}
+
+impl ContextData {
+    /// Stores `value`, returning the previous value of the same type.
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.entries
+            .insert(TypeId::of::<T>(), RefCell::new(Box::new(value)))
+            .and_then(|old| old.into_inner().downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.entries
+            .remove(&TypeId::of::<T>())
+            .and_then(|old| old.into_inner().downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<Ref<'_, T>> {
+        let entry = self.entries.get(&TypeId::of::<T>())?.try_borrow().ok()?;
+        Ref::filter_map(entry, |value| value.downcast_ref::<T>()).ok()
+    }
+
+    pub fn get_mut<T: 'static>(&self) -> Option<RefMut<'_, T>> {
+        let entry = self
+            .entries
+            .get(&TypeId::of::<T>())?
+            .try_borrow_mut()
+            .ok()?;
+        RefMut::filter_map(entry, |value| value.downcast_mut::<T>()).ok()
+    }
+}
+
+impl std::fmt::Debug for ContextData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ContextData({} entries)", self.entries.len())
+    }
+}
@@ -1,10 +1,23 @@
 use std::collections::HashMap;
 
-use crate::runtime::value::JsValue;
+use crate::context::Context;
+use crate::embedding::handle::Persistent;
+use crate::errors::CrustyError;
+use crate::runtime::gc::{Gc, GcCell, Trace, Tracer};
+use crate::runtime::value::{JsValue, NativeFunction};
 
-#[derive(Default, Clone)]
+/// The internal slot of an event target object that holds its listeners.
+pub(crate) const LISTENERS_SLOT: &str = "[[EventTarget]]";
+
+#[derive(Debug, Clone)]
+struct Listener {
+    callback: JsValue,
+    once: bool,
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct EventTarget {
-    listeners: HashMap<String, Vec<JsValue>>,
+    listeners: HashMap<String, Vec<Listener>>,
 }
 
 impl EventTarget {
@@ -13,39 +26,142 @@ impl EventTarget {
     }
 
     pub fn add_event_listener(&mut self, event_type: impl Into<String>, callback: JsValue) {
-        self.listeners
-            .entry(event_type.into())
-            .or_default()
-            .push(callback);
+        self.add_event_listener_with_options(event_type, callback, false);
+    }
+
+    /// Registers `callback`, removing it after its first invocation when
+    /// `once` is set. Adding a listener that is already registered for
+    /// `event_type` is a no-op, as in the DOM.
+    pub fn add_event_listener_with_options(
+        &mut self,
+        event_type: impl Into<String>,
+        callback: JsValue,
+        once: bool,
+    ) {
+        let listeners = self.listeners.entry(event_type.into()).or_default();
+        if listeners
+            .iter()
+            .any(|listener| same_listener(&listener.callback, &callback))
+        {
+            return;
+        }
+        listeners.push(Listener { callback, once });
     }
 
     pub fn remove_event_listener(&mut self, event_type: &str, callback: &JsValue) {
         if let Some(listeners) = self.listeners.get_mut(event_type) {
-            listeners.retain(|listener| !same_listener(listener, callback));
+            listeners.retain(|listener| !same_listener(&listener.callback, callback));
         }
     }
 
     pub fn listeners_for(&self, event_type: &str) -> Vec<JsValue> {
-        self.listeners.get(event_type).cloned().unwrap_or_default()
+        self.listeners
+            .get(event_type)
+            .map(|listeners| listeners.iter().map(|l| l.callback.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn has_listener(&self, event_type: &str, callback: &JsValue) -> bool {
+        self.listeners.get(event_type).is_some_and(|listeners| {
+            listeners
+                .iter()
+                .any(|listener| same_listener(&listener.callback, callback))
+        })
+    }
+
+    /// Snapshots the listeners for a dispatch, in registration order, and
+    /// drops the `once` listeners so they cannot fire again.
+    pub(crate) fn begin_dispatch(&mut self, event_type: &str) -> Vec<(JsValue, bool)> {
+        let Some(listeners) = self.listeners.get_mut(event_type) else {
+            return Vec::new();
+        };
+        let snapshot = listeners
+            .iter()
+            .map(|listener| (listener.callback.clone(), listener.once))
+            .collect();
+        listeners.retain(|listener| !listener.once);
+        snapshot
     }
 }
 
+impl Trace for EventTarget {
+    fn trace(&self, tracer: &mut Tracer) {
+        for listener in self.listeners.values().flatten() {
+            listener.callback.trace(tracer);
+        }
+    }
+}
+
+/// The listener store of an event target object, if `value` is one.
+pub(crate) fn listener_store(value: &JsValue) -> Option<Gc<GcCell<EventTarget>>> {
+    let JsValue::Object(object) = value else {
+        return None;
+    };
+    match object.borrow().get(LISTENERS_SLOT)? {
+        JsValue::NativeFunction {
+            handler: NativeFunction::EventTargetListeners(target),
+            ..
+        } => Some(target),
+        _ => None,
+    }
+}
+
+/// A script-visible `EventTarget` object the host can fire events on.
+///
+/// The handle keeps the object, and with it the listeners, alive across
+/// garbage collections until it is dropped.
+#[derive(Debug, Clone)]
+pub struct EventTargetHandle {
+    pub(crate) target: Gc<GcCell<EventTarget>>,
+    pub(crate) object: Persistent,
+}
+
+impl EventTargetHandle {
+    /// Wraps an `EventTarget` instance created by script.
+    pub fn from_value(ctx: &Context, value: &JsValue) -> Option<Self> {
+        let target = listener_store(value)?;
+        Some(Self {
+            target,
+            object: ctx.persist(value.clone()),
+        })
+    }
+
+    pub fn object(&self) -> JsValue {
+        self.object.get()
+    }
+
+    /// Fires an event with the given `type` and `detail` at the target's
+    /// listeners, then drains the microtask queue.
+    pub fn emit(
+        &self,
+        ctx: &mut Context,
+        event_type: &str,
+        detail: JsValue,
+    ) -> Result<(), CrustyError> {
+        ctx.emit_event(self, event_type, detail)
+    }
+}
+
+/// Script functions are the same listener when they share a body and the
+/// environment they closed over.
 fn same_listener(a: &JsValue, b: &JsValue) -> bool {
     match (a, b) {
         (
             JsValue::Function {
-                name: a_name,
-                source_path: a_path,
-                source_offset: a_offset,
+                body: a_body,
+                closure_env: a_env,
                 ..
             },
             JsValue::Function {
-                name: b_name,
-                source_path: b_path,
-                source_offset: b_offset,
+                body: b_body,
+                closure_env: b_env,
                 ..
             },
-        ) => a_name == b_name && a_path == b_path && a_offset == b_offset,
+        ) => {
+            a_body.ptr_eq(b_body)
+                && a_env.len() == b_env.len()
+                && a_env.iter().zip(b_env).all(|(x, y)| Gc::ptr_eq(*x, *y))
+        }
         _ => a == b,
     }
 }
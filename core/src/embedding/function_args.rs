@@ -1,14 +1,27 @@
+use std::cell::{Ref, RefMut};
+
+use crate::embedding::context_data::ContextData;
 use crate::runtime::value::JsValue;
 
 #[derive(Debug, Clone)]
-pub struct FunctionArgs {
+pub struct FunctionArgs<'a> {
     this_value: JsValue,
     values: Vec<JsValue>,
+    data: Option<&'a ContextData>,
 }
 
-impl FunctionArgs {
+impl<'a> FunctionArgs<'a> {
     pub fn new(this_value: JsValue, values: Vec<JsValue>) -> Self {
-        Self { this_value, values }
+        Self {
+            this_value,
+            values,
+            data: None,
+        }
+    }
+
+    pub(crate) fn with_data(mut self, data: &'a ContextData) -> Self {
+        self.data = Some(data);
+        self
     }
 
     pub fn len(&self) -> usize {
@@ -26,4 +39,13 @@ impl FunctionArgs {
     pub fn this(&self) -> &JsValue {
         &self.this_value
     }
+
+    /// The context data of type `T`, as set with `Context::set_data`.
+    pub fn data<T: 'static>(&self) -> Option<Ref<'a, T>> {
+        self.data?.get()
+    }
+
+    pub fn data_mut<T: 'static>(&self) -> Option<RefMut<'a, T>> {
+        self.data?.get_mut()
+    }
 }
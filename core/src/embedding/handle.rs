@@ -1,4 +1,9 @@
-use crate::runtime::gc::{ErasedGc, Gc};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::runtime::gc::{ErasedGc, Gc, Trace, Tracer};
+use crate::runtime::value::JsValue;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Handle<T> {
@@ -38,3 +43,95 @@ impl HandleScope {
         &self.roots
     }
 }
+
+#[derive(Default)]
+struct RootSlots {
+    values: Vec<Option<JsValue>>,
+    free: Vec<usize>,
+}
+
+/// Values the host holds outside any script. Every entry is a GC root
+/// until the `Persistent` that owns it is dropped. Clones share the same
+/// table, so handles can be created and released without borrowing the
+/// context.
+#[derive(Clone, Default)]
+pub struct PersistentRoots {
+    slots: Rc<RefCell<RootSlots>>,
+}
+
+impl PersistentRoots {
+    pub fn persist(&self, value: JsValue) -> Persistent {
+        let mut slots = self.slots.borrow_mut();
+        let index = match slots.free.pop() {
+            Some(index) => {
+                slots.values[index] = Some(value);
+                index
+            }
+            None => {
+                slots.values.push(Some(value));
+                slots.values.len() - 1
+            }
+        };
+        Persistent {
+            slots: Rc::clone(&self.slots),
+            index,
+        }
+    }
+
+    /// Number of values currently kept alive.
+    pub fn len(&self) -> usize {
+        let slots = self.slots.borrow();
+        slots.values.len() - slots.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Trace for PersistentRoots {
+    fn trace(&self, tracer: &mut Tracer) {
+        for value in self.slots.borrow().values.iter().flatten() {
+            value.trace(tracer);
+        }
+    }
+}
+
+/// A host-owned reference that keeps its value alive across garbage
+/// collections until dropped.
+pub struct Persistent {
+    slots: Rc<RefCell<RootSlots>>,
+    index: usize,
+}
+
+impl Persistent {
+    pub fn get(&self) -> JsValue {
+        self.slots.borrow().values[self.index]
+            .clone()
+            .expect("persistent slot is occupied while its handle lives")
+    }
+}
+
+/// A clone is a second root for the same value, released on its own.
+impl Clone for Persistent {
+    fn clone(&self) -> Self {
+        let roots = PersistentRoots {
+            slots: Rc::clone(&self.slots),
+        };
+        roots.persist(self.get())
+    }
+}
+
+impl Drop for Persistent {
+    fn drop(&mut self) {
+        let mut slots = self.slots.borrow_mut();
+        slots.values[self.index] = None;
+        slots.free.push(self.index);
+    }
+}
+
+impl fmt::Debug for Persistent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Persistent").field(&self.get()).finish()
+    }
+}
@@ -1,8 +1,19 @@
+pub mod call_scope;
 pub mod callback;
 pub mod class_builder;
+pub mod context_data;
 pub mod event_target;
 pub mod function_args;
 pub mod handle;
+pub mod object_builder;
+pub mod promise_resolver;
+#[cfg(feature = "serde")]
+pub mod serde_value;
 
-pub use class_builder::{ClassBuilder, NativeClassDef};
-pub use event_target::EventTarget;
+pub use call_scope::CallScope;
+pub use class_builder::{ClassBuilder, CoercionHooks, NativeClassDef};
+pub use context_data::ContextData;
+pub use event_target::{EventTarget, EventTargetHandle};
+pub use handle::{Persistent, PersistentRoots};
+pub use object_builder::JsObjectBuilder;
+pub use promise_resolver::PromiseResolver;
@@ -0,0 +1,34 @@
+use crate::embedding::callback::NativeFunctionBoxed;
+use crate::embedding::function_args::FunctionArgs;
+use crate::errors::RuntimeError;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::{JsValue, NativeFunction};
+
+/// Adds properties to an object the engine is building for a script,
+/// such as a module's `import.meta`.
+pub struct JsObjectBuilder<'a> {
+    object: &'a mut JsObject,
+}
+
+impl<'a> JsObjectBuilder<'a> {
+    pub(crate) fn new(object: &'a mut JsObject) -> Self {
+        Self { object }
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: JsValue) -> &mut Self {
+        self.object.set(key.into(), value);
+        self
+    }
+
+    pub fn function<F>(&mut self, name: impl Into<String>, callback: F) -> &mut Self
+    where
+        F: Fn(FunctionArgs) -> Result<JsValue, RuntimeError> + Send + Sync + 'static,
+    {
+        let name = name.into();
+        let function = JsValue::NativeFunction {
+            name: name.clone(),
+            handler: NativeFunction::Host(NativeFunctionBoxed::from_closure(callback)),
+        };
+        self.set(name, function)
+    }
+}
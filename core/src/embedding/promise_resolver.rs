@@ -0,0 +1,86 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::context::Context;
+use crate::embedding::handle::Persistent;
+use crate::errors::{CrustyError, RuntimeError};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::JsValue;
+use crate::runtime::value::promise::JsPromise;
+
+/// The settling half of a promise made by [`Context::create_promise`],
+/// for hosts that finish work such as I/O after the script that asked for
+/// it has returned.
+///
+/// The promise stays rooted until it is settled. Clones share the same
+/// promise, and only the first `resolve` or `reject` among them has any
+/// effect.
+#[derive(Clone)]
+pub struct PromiseResolver {
+    promise: Rc<RefCell<Option<Persistent>>>,
+}
+
+impl PromiseResolver {
+    fn new(promise: Persistent) -> Self {
+        Self {
+            promise: Rc::new(RefCell::new(Some(promise))),
+        }
+    }
+
+    pub fn resolve(&self, ctx: &mut Context, value: JsValue) -> Result<(), CrustyError> {
+        self.settle(ctx, false, value)
+    }
+
+    pub fn reject(&self, ctx: &mut Context, reason: JsValue) -> Result<(), CrustyError> {
+        self.settle(ctx, true, reason)
+    }
+
+    /// Whether `resolve` or `reject` has been called.
+    pub fn is_settled(&self) -> bool {
+        self.promise.borrow().is_none()
+    }
+
+    /// Settles the promise and runs the reactions it queued. With manual
+    /// timers the reactions wait for the host's next `run_microtasks` or
+    /// `advance_time` instead.
+    fn settle(
+        &self,
+        ctx: &mut Context,
+        is_reject: bool,
+        value: JsValue,
+    ) -> Result<(), CrustyError> {
+        let interpreter = &mut ctx.interpreter;
+        self.settle_in(interpreter, is_reject, value)?;
+        if !interpreter.manual_timers {
+            interpreter.run_microtasks_only()?;
+        }
+        Ok(())
+    }
+
+    /// Settles the promise, leaving its reactions queued for whichever
+    /// loop is running.
+    pub(crate) fn settle_in(
+        &self,
+        interpreter: &mut Interpreter,
+        is_reject: bool,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        let Some(handle) = self.promise.borrow_mut().take() else {
+            return Ok(());
+        };
+        let JsValue::Promise(promise) = handle.get() else {
+            unreachable!("a promise resolver roots a promise");
+        };
+        interpreter.settle_promise(&promise, is_reject, value)?;
+        Ok(())
+    }
+}
+
+impl Interpreter {
+    /// A pending promise and the resolver that settles it.
+    pub(crate) fn create_resolvable_promise(&mut self) -> (JsValue, PromiseResolver) {
+        let promise = JsValue::Promise(self.heap.alloc_cell(JsPromise::pending()));
+        let resolver = PromiseResolver::new(self.persistent_roots.persist(promise.clone()));
+        (promise, resolver)
+    }
+}
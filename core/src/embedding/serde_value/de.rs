@@ -0,0 +1,432 @@
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use super::error::{ConversionError, Segment};
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::value::JsValue;
+use crate::runtime::value::object::JsObject;
+
+/// Beyond this, an integral number may not be the integer it was written
+/// as, so it is handed over as a float.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// An object or array being read, so that meeting it again inside itself
+/// is reported rather than recursing forever.
+struct Ancestor<'a> {
+    id: usize,
+    parent: Option<&'a Ancestor<'a>>,
+}
+
+/// Reads a script value into a `Deserialize` implementation, visiting the
+/// heap values directly.
+pub(super) struct ValueDeserializer<'a> {
+    value: JsValue,
+    ancestors: Option<&'a Ancestor<'a>>,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    pub(super) fn new(value: JsValue) -> Self {
+        Self {
+            value,
+            ancestors: None,
+        }
+    }
+
+    fn child(value: JsValue, parent: &'a Ancestor<'a>) -> Self {
+        Self {
+            value,
+            ancestors: Some(parent),
+        }
+    }
+
+    /// The ancestor the children of container `id` are read under.
+    fn enter(&self, id: usize) -> Result<Ancestor<'a>, ConversionError> {
+        let mut current = self.ancestors;
+        while let Some(ancestor) = current {
+            if ancestor.id == id {
+                return Err(ConversionError::new(
+                    "cannot convert a value that contains itself",
+                ));
+            }
+            current = ancestor.parent;
+        }
+        Ok(Ancestor {
+            id,
+            parent: self.ancestors,
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = ConversionError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ConversionError> {
+        match &self.value {
+            JsValue::Undefined | JsValue::Null => visitor.visit_unit(),
+            JsValue::Boolean(b) => visitor.visit_bool(*b),
+            JsValue::Number(n) => visit_number(*n, visitor),
+            JsValue::String(s) => visitor.visit_str(s),
+            JsValue::Array(array) => {
+                let ancestor = self.enter(Gc::as_usize(*array))?;
+                let array = array.borrow();
+                if let Some(index) = array
+                    .index_attributes
+                    .iter()
+                    .find_map(|(index, prop)| prop.is_accessor().then_some(*index))
+                {
+                    return Err(accessor_error().within(Segment::Index(index)));
+                }
+                let values = array.elements.clone();
+                drop(array);
+                visitor.visit_seq(ElementsAccess::new(values, ancestor))
+            }
+            JsValue::Set(set) => {
+                let ancestor = self.enter(Gc::as_usize(*set))?;
+                let values = set.borrow().iter().cloned().collect();
+                visitor.visit_seq(ElementsAccess::new(values, ancestor))
+            }
+            JsValue::TypedArray(array) => {
+                let ancestor = self.enter(Gc::as_usize(*array))?;
+                let values = array.borrow().values().into_iter().map(JsValue::Number);
+                visitor.visit_seq(ElementsAccess::new(values.collect(), ancestor))
+            }
+            JsValue::Object(object) => {
+                let ancestor = self.enter(Gc::as_usize(*object))?;
+                let entries = object_entries(*object)?;
+                visitor.visit_map(EntriesAccess::new(entries, ancestor))
+            }
+            JsValue::Map(map) => {
+                let ancestor = self.enter(Gc::as_usize(*map))?;
+                let entries = map.borrow().iter().cloned().collect();
+                visitor.visit_map(EntriesAccess::new(entries, ancestor))
+            }
+            other => Err(ConversionError::new(format!(
+                "cannot convert {} to a Rust value",
+                describe(other)
+            ))),
+        }
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ConversionError> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ConversionError> {
+        match self.value {
+            JsValue::Number(n) => visitor.visit_f64(n),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ConversionError> {
+        match self.value {
+            JsValue::Undefined | JsValue::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ConversionError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ConversionError> {
+        match &self.value {
+            JsValue::String(s) => visitor.visit_enum(s.as_str().into_deserializer()),
+            JsValue::Object(object) => {
+                let ancestor = self.enter(Gc::as_usize(*object))?;
+                let mut entries = object_entries(*object)?;
+                let (Some((JsValue::String(variant), value)), true) =
+                    (entries.pop(), entries.is_empty())
+                else {
+                    return Err(ConversionError::new(
+                        "expected an object with a single key naming the enum variant",
+                    ));
+                };
+                visitor.visit_enum(VariantDeserializer {
+                    variant: variant.into_string(),
+                    value,
+                    ancestor,
+                })
+            }
+            _ => Err(ConversionError::new(
+                "expected a string or an object with a single key for an enum",
+            )),
+        }
+    }
+
+    /// Values the target skips are not read, so an unknown field holding
+    /// a function does not fail the conversion.
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, ConversionError> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+/// Integral numbers go to integer targets whole; other numbers, `-0` and
+/// integers too large to be exact stay floats.
+fn visit_number<'de, V: Visitor<'de>>(n: f64, visitor: V) -> Result<V::Value, ConversionError> {
+    let integral = n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER;
+    if integral && !(n == 0.0 && n.is_sign_negative()) {
+        visitor.visit_i64(n as i64)
+    } else {
+        visitor.visit_f64(n)
+    }
+}
+
+/// The enumerable string-keyed own properties of `object`, in order.
+fn object_entries(
+    object: Gc<GcCell<JsObject>>,
+) -> Result<Vec<(JsValue, JsValue)>, ConversionError> {
+    let object = object.borrow();
+    let mut entries = Vec::with_capacity(object.properties.len());
+    for (key, prop) in object.properties.iter() {
+        if !prop.enumerable {
+            continue;
+        }
+        if prop.is_accessor() {
+            return Err(accessor_error().within(Segment::Key(key.to_string())));
+        }
+        entries.push((JsValue::String(key.as_str().into()), prop.value.clone()));
+    }
+    Ok(entries)
+}
+
+fn accessor_error() -> ConversionError {
+    ConversionError::new("cannot convert an accessor property to a Rust value")
+}
+
+fn describe(value: &JsValue) -> &'static str {
+    match value {
+        JsValue::Function { .. } | JsValue::NativeFunction { .. } => "a function",
+        JsValue::Symbol(_) => "a symbol",
+        JsValue::Promise(_) => "a promise",
+        JsValue::WeakMap(_) => "a WeakMap",
+        JsValue::WeakSet(_) => "a WeakSet",
+        JsValue::RegExp(_) => "a regular expression",
+        JsValue::Proxy(_) => "a proxy",
+        _ => "this value",
+    }
+}
+
+/// The elements of an array or set.
+struct ElementsAccess<'a> {
+    values: std::vec::IntoIter<JsValue>,
+    index: usize,
+    ancestor: Ancestor<'a>,
+}
+
+impl<'a> ElementsAccess<'a> {
+    fn new(values: Vec<JsValue>, ancestor: Ancestor<'a>) -> Self {
+        Self {
+            values: values.into_iter(),
+            index: 0,
+            ancestor,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for ElementsAccess<'_> {
+    type Error = ConversionError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, ConversionError> {
+        let Some(value) = self.values.next() else {
+            return Ok(None);
+        };
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(ValueDeserializer::child(value, &self.ancestor))
+            .map(Some)
+            .map_err(|err| err.within(Segment::Index(index)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// The properties of an object or the entries of a map.
+struct EntriesAccess<'a> {
+    entries: std::vec::IntoIter<(JsValue, JsValue)>,
+    /// The value of the entry whose key was just read, and that key as
+    /// error paths show it.
+    value: Option<(JsValue, String)>,
+    ancestor: Ancestor<'a>,
+}
+
+impl<'a> EntriesAccess<'a> {
+    fn new(entries: Vec<(JsValue, JsValue)>, ancestor: Ancestor<'a>) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            value: None,
+            ancestor,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for EntriesAccess<'_> {
+    type Error = ConversionError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, ConversionError> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        let name = key.to_js_string();
+        let key = match key {
+            JsValue::String(key) => seed.deserialize(KeyDeserializer(key.as_str())),
+            key => seed.deserialize(ValueDeserializer::child(key, &self.ancestor)),
+        }
+        .map_err(|err| err.within(Segment::Key(name.clone())))?;
+        self.value = Some((value, name));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, ConversionError> {
+        let (value, name) = self
+            .value
+            .take()
+            .ok_or_else(|| ConversionError::new("a map value was read before its key"))?;
+        seed.deserialize(ValueDeserializer::child(value, &self.ancestor))
+            .map_err(|err| err.within(Segment::Key(name)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// An object key, which integer map keys are parsed from.
+struct KeyDeserializer<'k>(&'k str);
+
+macro_rules! parse_key {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ConversionError> {
+            match self.0.parse() {
+                Ok(n) => visitor.$visit(n),
+                Err(_) => self.deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'_> {
+    type Error = ConversionError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ConversionError> {
+        visitor.visit_str(self.0)
+    }
+
+    parse_key! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_bool => visit_bool,
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ConversionError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// `{ variant: value }`, an enum variant carrying data.
+struct VariantDeserializer<'a> {
+    variant: String,
+    value: JsValue,
+    ancestor: Ancestor<'a>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for VariantDeserializer<'a> {
+    type Error = ConversionError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), ConversionError> {
+        let variant = seed.deserialize(KeyDeserializer(&self.variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer<'_> {
+    type Error = ConversionError;
+
+    fn unit_variant(self) -> Result<(), ConversionError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, ConversionError> {
+        seed.deserialize(ValueDeserializer::child(self.value, &self.ancestor))
+            .map_err(|err| err.within(Segment::Key(self.variant)))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ConversionError> {
+        de::Deserializer::deserialize_seq(
+            ValueDeserializer::child(self.value, &self.ancestor),
+            visitor,
+        )
+        .map_err(|err| err.within(Segment::Key(self.variant)))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ConversionError> {
+        de::Deserializer::deserialize_map(
+            ValueDeserializer::child(self.value, &self.ancestor),
+            visitor,
+        )
+        .map_err(|err| err.within(Segment::Key(self.variant)))
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+use crate::errors::RuntimeError;
+
+/// One step into a value: an object key or map key, or an array index.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Why a value could not be converted, and where in it the conversion
+/// stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionError {
+    message: String,
+    /// Innermost step first: each container the error passes on its way
+    /// out adds its own.
+    path: Vec<Segment>,
+}
+
+impl ConversionError {
+    pub(super) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            path: Vec::new(),
+        }
+    }
+
+    /// The error as seen from the container holding the value at
+    /// `segment`.
+    pub(super) fn within(mut self, segment: Segment) -> Self {
+        self.path.push(segment);
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Where the conversion stopped, such as `config.handlers[2]`; empty
+    /// when it was the value itself.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in self.path.iter().rev() {
+            match segment {
+                Segment::Key(key) if is_identifier(key) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                }
+                Segment::Key(key) => path.push_str(&format!("[{key:?}]")),
+                Segment::Index(index) => path.push_str(&format!("[{index}]")),
+            }
+        }
+        path
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{} at {}", self.message, self.path())
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl serde::ser::Error for ConversionError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::new(msg.to_string())
+    }
+}
+
+impl serde::de::Error for ConversionError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::new(msg.to_string())
+    }
+}
+
+/// Lets a host function return a failed conversion with `?`, which
+/// scripts see as a TypeError.
+impl From<ConversionError> for RuntimeError {
+    fn from(err: ConversionError) -> Self {
+        RuntimeError::TypeError {
+            message: err.to_string(),
+        }
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
@@ -0,0 +1,59 @@
+//! Conversions between script values and Rust types through serde, for
+//! embedders passing structured data in and out of a [`Context`].
+
+mod de;
+mod error;
+mod ser;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::context::Context;
+use crate::runtime::value::JsValue;
+
+pub use error::ConversionError;
+
+/// How [`to_value_with`] builds Rust maps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MapFormat {
+    /// An object keyed by the map's keys, which have to be strings,
+    /// numbers or booleans.
+    #[default]
+    Object,
+    /// An array of `[key, value]` pairs, which keeps keys of any type and
+    /// can be handed straight to `new Map(...)`.
+    Entries,
+}
+
+/// Builds the script value for `value`: structs and maps become objects,
+/// sequences and tuples arrays, `None` and `()` `null`, and enums follow
+/// their serde representation.
+pub fn to_value<T: Serialize + ?Sized>(
+    context: &mut Context,
+    value: &T,
+) -> Result<JsValue, ConversionError> {
+    to_value_with(context, value, MapFormat::Object)
+}
+
+/// [`to_value`] with the given form for maps.
+pub fn to_value_with<T: Serialize + ?Sized>(
+    context: &mut Context,
+    value: &T,
+    maps: MapFormat,
+) -> Result<JsValue, ConversionError> {
+    value.serialize(ser::ValueSerializer {
+        heap: &mut context.interpreter.heap,
+        maps,
+    })
+}
+
+/// Reads `value` back into a Rust type. `undefined` and `null` read as
+/// `None` or `()`, `Map`s as maps and `Set`s as sequences. Functions,
+/// symbols, promises and other values with no Rust counterpart fail,
+/// naming where in `value` they were found.
+pub fn from_value<T: DeserializeOwned>(
+    _context: &Context,
+    value: &JsValue,
+) -> Result<T, ConversionError> {
+    T::deserialize(de::ValueDeserializer::new(value.clone()))
+}
@@ -0,0 +1,455 @@
+use serde::ser::{self, Serialize};
+
+use super::MapFormat;
+use super::error::{ConversionError, Segment};
+use crate::runtime::gc::{Gc, GcCell, Heap};
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::{JsValue, number_to_string};
+
+/// The largest integer every smaller one is exactly representable below.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Builds script values on the heap from a `Serialize` implementation.
+pub(super) struct ValueSerializer<'a> {
+    pub(super) heap: &'a mut Heap,
+    pub(super) maps: MapFormat,
+}
+
+impl ValueSerializer<'_> {
+    fn reborrow(&mut self) -> ValueSerializer<'_> {
+        ValueSerializer {
+            heap: self.heap,
+            maps: self.maps,
+        }
+    }
+
+    fn alloc_object(&mut self) -> Gc<GcCell<JsObject>> {
+        self.heap.alloc_cell(JsObject::new())
+    }
+
+    fn alloc_array(&mut self, values: Vec<JsValue>) -> JsValue {
+        JsValue::Array(self.heap.alloc_cell(JsArray::new(values)))
+    }
+
+    /// `{ variant: value }`, the externally tagged form of an enum variant
+    /// carrying data.
+    fn tagged(&mut self, variant: &str, value: JsValue) -> JsValue {
+        let object = self.alloc_object();
+        object.borrow_mut().set(variant, value);
+        JsValue::Object(object)
+    }
+
+    fn serialize_integer(self, n: u64, negative: bool) -> Result<JsValue, ConversionError> {
+        if n > MAX_SAFE_INTEGER {
+            let sign = if negative { "-" } else { "" };
+            return Err(ConversionError::new(format!(
+                "{sign}{n} cannot be represented exactly as a number"
+            )));
+        }
+        let n = n as f64;
+        Ok(JsValue::Number(if negative { -n } else { n }))
+    }
+}
+
+impl<'a> ser::Serializer for ValueSerializer<'a> {
+    type Ok = JsValue;
+    type Error = ConversionError;
+    type SerializeSeq = ArrayBuilder<'a>;
+    type SerializeTuple = ArrayBuilder<'a>;
+    type SerializeTupleStruct = ArrayBuilder<'a>;
+    type SerializeTupleVariant = ArrayBuilder<'a>;
+    type SerializeMap = MapBuilder<'a>;
+    type SerializeStruct = ObjectBuilder<'a>;
+    type SerializeStructVariant = ObjectBuilder<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Number(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Number(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Number(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<JsValue, ConversionError> {
+        self.serialize_integer(v.unsigned_abs(), v < 0)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Number(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Number(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Number(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<JsValue, ConversionError> {
+        self.serialize_integer(v, false)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Number(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Number(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::String(v.to_string().into()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::String(v.into()))
+    }
+
+    fn serialize_bytes(mut self, v: &[u8]) -> Result<JsValue, ConversionError> {
+        let values = v.iter().map(|&byte| JsValue::Number(byte.into())).collect();
+        Ok(self.alloc_array(values))
+    }
+
+    fn serialize_none(self) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<JsValue, ConversionError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<JsValue, ConversionError> {
+        Ok(JsValue::String(variant.into()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<JsValue, ConversionError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        mut self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<JsValue, ConversionError> {
+        let value = value
+            .serialize(self.reborrow())
+            .map_err(|err| err.within(Segment::Key(variant.to_string())))?;
+        Ok(self.tagged(variant, value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ArrayBuilder<'a>, ConversionError> {
+        Ok(ArrayBuilder {
+            serializer: self,
+            values: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ArrayBuilder<'a>, ConversionError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<ArrayBuilder<'a>, ConversionError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<ArrayBuilder<'a>, ConversionError> {
+        let mut builder = self.serialize_seq(Some(len))?;
+        builder.variant = Some(variant);
+        Ok(builder)
+    }
+
+    fn serialize_map(mut self, len: Option<usize>) -> Result<MapBuilder<'a>, ConversionError> {
+        let target = match self.maps {
+            MapFormat::Object => MapTarget::Object(self.alloc_object()),
+            MapFormat::Entries => MapTarget::Entries(Vec::with_capacity(len.unwrap_or(0))),
+        };
+        Ok(MapBuilder {
+            serializer: self,
+            target,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        mut self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<ObjectBuilder<'a>, ConversionError> {
+        let object = self.alloc_object();
+        Ok(ObjectBuilder {
+            serializer: self,
+            object,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<ObjectBuilder<'a>, ConversionError> {
+        let mut builder = self.serialize_struct(name, len)?;
+        builder.variant = Some(variant);
+        Ok(builder)
+    }
+}
+
+/// Collects the elements of a sequence, tuple or tuple variant.
+pub(super) struct ArrayBuilder<'a> {
+    serializer: ValueSerializer<'a>,
+    values: Vec<JsValue>,
+    variant: Option<&'static str>,
+}
+
+impl ArrayBuilder<'_> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ConversionError> {
+        let index = self.values.len();
+        let value = value
+            .serialize(self.serializer.reborrow())
+            .map_err(|err| within_variant(err.within(Segment::Index(index)), self.variant))?;
+        self.values.push(value);
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<JsValue, ConversionError> {
+        let array = self.serializer.alloc_array(self.values);
+        Ok(match self.variant {
+            Some(variant) => self.serializer.tagged(variant, array),
+            None => array,
+        })
+    }
+}
+
+impl ser::SerializeSeq for ArrayBuilder<'_> {
+    type Ok = JsValue;
+    type Error = ConversionError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), ConversionError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsValue, ConversionError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for ArrayBuilder<'_> {
+    type Ok = JsValue;
+    type Error = ConversionError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), ConversionError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsValue, ConversionError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for ArrayBuilder<'_> {
+    type Ok = JsValue;
+    type Error = ConversionError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ConversionError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsValue, ConversionError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for ArrayBuilder<'_> {
+    type Ok = JsValue;
+    type Error = ConversionError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ConversionError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsValue, ConversionError> {
+        self.finish()
+    }
+}
+
+/// Collects the fields of a struct or struct variant into an object.
+pub(super) struct ObjectBuilder<'a> {
+    serializer: ValueSerializer<'a>,
+    object: Gc<GcCell<JsObject>>,
+    variant: Option<&'static str>,
+}
+
+impl ObjectBuilder<'_> {
+    fn field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ConversionError> {
+        let value = value.serialize(self.serializer.reborrow()).map_err(|err| {
+            within_variant(err.within(Segment::Key(key.to_string())), self.variant)
+        })?;
+        self.object.borrow_mut().set(key, value);
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<JsValue, ConversionError> {
+        let object = JsValue::Object(self.object);
+        Ok(match self.variant {
+            Some(variant) => self.serializer.tagged(variant, object),
+            None => object,
+        })
+    }
+}
+
+impl ser::SerializeStruct for ObjectBuilder<'_> {
+    type Ok = JsValue;
+    type Error = ConversionError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ConversionError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<JsValue, ConversionError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for ObjectBuilder<'_> {
+    type Ok = JsValue;
+    type Error = ConversionError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ConversionError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<JsValue, ConversionError> {
+        self.finish()
+    }
+}
+
+enum MapTarget {
+    Object(Gc<GcCell<JsObject>>),
+    Entries(Vec<JsValue>),
+}
+
+/// Collects the entries of a map, as an object or as `[key, value]`
+/// pairs depending on the [`MapFormat`].
+pub(super) struct MapBuilder<'a> {
+    serializer: ValueSerializer<'a>,
+    target: MapTarget,
+    /// The key of the entry whose value comes next.
+    key: Option<JsValue>,
+}
+
+impl ser::SerializeMap for MapBuilder<'_> {
+    type Ok = JsValue;
+    type Error = ConversionError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), ConversionError> {
+        let key = key.serialize(self.serializer.reborrow())?;
+        if matches!(self.target, MapTarget::Object(_))
+            && !matches!(
+                key,
+                JsValue::String(_) | JsValue::Number(_) | JsValue::Boolean(_)
+            )
+        {
+            return Err(ConversionError::new(
+                "map keys must be strings, numbers or booleans to become object keys; \
+                 use MapFormat::Entries for other keys",
+            ));
+        }
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ConversionError> {
+        let key = self.key.take().unwrap_or(JsValue::Undefined);
+        let name = match &key {
+            JsValue::String(s) => s.to_string(),
+            JsValue::Number(n) => number_to_string(*n),
+            other => other.to_string(),
+        };
+        let value = value
+            .serialize(self.serializer.reborrow())
+            .map_err(|err| err.within(Segment::Key(name.clone())))?;
+        match &mut self.target {
+            MapTarget::Object(object) => object.borrow_mut().set(name, value),
+            MapTarget::Entries(entries) => {
+                let entry = self.serializer.alloc_array(vec![key, value]);
+                entries.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<JsValue, ConversionError> {
+        Ok(match self.target {
+            MapTarget::Object(object) => JsValue::Object(object),
+            MapTarget::Entries(entries) => self.serializer.alloc_array(entries),
+        })
+    }
+}
+
+/// An error from inside the data of `variant`, when building one.
+fn within_variant(err: ConversionError, variant: Option<&'static str>) -> ConversionError {
+    match variant {
+        Some(variant) => err.within(Segment::Key(variant.to_string())),
+        None => err,
+    }
+}
@@ -1,9 +1,11 @@
 use crate::context::Context;
+use crate::runtime::output::OutputMode;
 
 #[derive(Debug, Clone, Default)]
 pub struct Engine {
     max_steps: Option<usize>,
     realtime_timers: bool,
+    output_mode: Option<OutputMode>,
 }
 
 impl Engine {
@@ -21,11 +23,19 @@ impl Engine {
         self
     }
 
+    pub fn with_output_mode(mut self, mode: OutputMode) -> Self {
+        self.output_mode = Some(mode);
+        self
+    }
+
     pub fn new_context(&self) -> Context {
         let mut ctx = Context::new_with_realtime(self.realtime_timers);
         if let Some(max) = self.max_steps {
             ctx.set_max_steps(max);
         }
+        if let Some(mode) = &self.output_mode {
+            ctx.set_output_mode(mode.clone());
+        }
         ctx
     }
 }
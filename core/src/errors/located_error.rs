@@ -0,0 +1,107 @@
+use std::fmt;
+
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode, SourceSpan};
+use thiserror::Error;
+
+use super::RuntimeError;
+use crate::diagnostics::source_map::{SourceMap, SourcePos};
+use crate::diagnostics::stack_trace::CallFrame;
+use crate::runtime::value::JsValue;
+
+/// A runtime error paired with the script it escaped from, so a report
+/// can quote the function it was raised in.
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct LocatedRuntimeError {
+    pub error: RuntimeError,
+    message: String,
+    source_code: NamedSource<String>,
+    span: Option<SourceSpan>,
+    function: Option<String>,
+}
+
+impl LocatedRuntimeError {
+    /// `frame` is the innermost function the error unwound from, as
+    /// reported by `Interpreter::error_location`, and must belong to
+    /// `source`. Without one the report quotes no line.
+    pub fn new(
+        error: RuntimeError,
+        name: impl AsRef<str>,
+        source: impl Into<String>,
+        frame: Option<&CallFrame>,
+    ) -> Self {
+        let source = source.into();
+        let span = frame.and_then(|frame| {
+            let offset = SourceMap::from_source(&source).pos_to_byte(SourcePos {
+                line: frame.line,
+                col: frame.col,
+            });
+            let rest = source.get(offset..).filter(|rest| !rest.is_empty())?;
+            let len = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+                .unwrap_or(rest.len())
+                .max(1);
+            Some(SourceSpan::from((offset, len)))
+        });
+        Self {
+            message: headline(&error),
+            error,
+            source_code: NamedSource::new(name, source),
+            span,
+            function: frame.map(|frame| frame.function_name.clone()),
+        }
+    }
+}
+
+/// The error as a report title: a thrown error object by its name and
+/// message rather than its properties, followed by its causes.
+fn headline(error: &RuntimeError) -> String {
+    if let RuntimeError::Thrown { value } = error
+        && let Some(chain) = value.error_chain()
+    {
+        return format!("Uncaught {chain}");
+    }
+    if let RuntimeError::Thrown { value } = error
+        && let JsValue::Object(obj) = &**value
+    {
+        let obj = obj.borrow();
+        if let (Some(name), Some(message)) = (obj.get("name"), obj.get("message")) {
+            return format!(
+                "Uncaught {}: {}",
+                name.to_js_string(),
+                message.to_js_string()
+            );
+        }
+    }
+    error.to_string()
+}
+
+impl Diagnostic for LocatedRuntimeError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.error.code()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.error.help().or_else(|| {
+            Some(Box::new(
+                "nothing caught this error; wrap the call in try/catch to handle it",
+            ))
+        })
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.source_code)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let span = self.span?;
+        let label = match self.function.as_deref() {
+            Some("") | None => "raised in this function".to_string(),
+            Some(name) => format!("raised in '{name}'"),
+        };
+        Some(Box::new(std::iter::once(LabeledSpan::new_with_span(
+            Some(label),
+            span,
+        ))))
+    }
+}
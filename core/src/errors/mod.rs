@@ -1,6 +1,8 @@
+mod located_error;
 mod runtime_error;
 mod syntax_error;
 
+pub use located_error::LocatedRuntimeError;
 pub use runtime_error::RuntimeError;
 pub use syntax_error::SyntaxError;
 
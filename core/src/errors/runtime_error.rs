@@ -9,6 +9,9 @@ pub enum RuntimeError {
     #[diagnostic(help("declare '{name}' with let or const before using it"))]
     UndefinedVariable { name: String },
 
+    #[error("ReferenceError: Cannot access '{name}' before initialization")]
+    UninitializedBinding { name: String },
+
     #[error("TypeError: '{name}' is not a function")]
     #[diagnostic(help("ensure '{name}' is declared as a function before calling it"))]
     NotAFunction { name: String },
@@ -22,6 +25,59 @@ pub enum RuntimeError {
     #[error("TypeError: Assignment to constant variable '{name}'")]
     ConstReassignment { name: String },
 
-    #[error("Uncaught {value}")]
-    Thrown { value: JsValue },
+    /// Error objects print as their name and message, then their causes.
+    /// The value is boxed so that results carrying this error stay small.
+    #[error("Uncaught {}", thrown_text(value))]
+    Thrown { value: Box<JsValue> },
+
+    /// Raised once the `max_steps` budget runs out. Not catchable, so a
+    /// runaway loop inside `try` still stops.
+    #[error("execution step limit exceeded (possible infinite loop)")]
+    StepLimitExceeded,
+
+    /// Raised when one drain of the microtask queue runs more than the
+    /// microtask limit. Not catchable.
+    #[error("microtask limit of {limit} exceeded in one drain (possible infinite microtask loop)")]
+    MicrotaskLimitExceeded { limit: usize },
+
+    /// Raised when a yield hook asks to stop the script. Not catchable.
+    #[error("execution interrupted by the host")]
+    Interrupted,
+
+    /// Unwinds a generator body to its `next()` call when it pauses at a
+    /// `yield`. Never escapes the generator that raised it.
+    #[error("generator suspended")]
+    GeneratorSuspend,
+
+    /// Closes a paused generator for `return()`, running its `finally`
+    /// blocks on the way out.
+    #[error("generator returned")]
+    GeneratorReturn { value: Box<JsValue> },
+}
+
+impl RuntimeError {
+    /// A script-level `throw` of `value`.
+    pub fn thrown(value: JsValue) -> Self {
+        RuntimeError::Thrown {
+            value: Box::new(value),
+        }
+    }
+
+    /// Whether a script's `try`/`catch` sees this error. Everything except
+    /// the step and microtask limits, interruption and the generator control
+    /// signals becomes a JS error object at the catch boundary.
+    pub fn is_catchable(&self) -> bool {
+        !matches!(
+            self,
+            RuntimeError::StepLimitExceeded
+                | RuntimeError::MicrotaskLimitExceeded { .. }
+                | RuntimeError::Interrupted
+                | RuntimeError::GeneratorSuspend
+                | RuntimeError::GeneratorReturn { .. }
+        )
+    }
+}
+
+fn thrown_text(value: &JsValue) -> String {
+    value.error_chain().unwrap_or_else(|| value.to_string())
 }
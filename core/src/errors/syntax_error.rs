@@ -1,4 +1,4 @@
-use miette::Diagnostic;
+use miette::{Diagnostic, NamedSource};
 use thiserror::Error;
 
 #[derive(Debug, Error, Diagnostic)]
@@ -9,6 +9,11 @@ pub struct SyntaxError {
 
     #[label("here")]
     pub span: miette::SourceSpan,
+
+    /// The file the error is in, attached by whoever read it so a report
+    /// can quote the offending line.
+    #[source_code]
+    pub source_code: Option<NamedSource<String>>,
 }
 
 impl SyntaxError {
@@ -16,6 +21,12 @@ impl SyntaxError {
         Self {
             message: message.into(),
             span: (offset, length).into(),
+            source_code: None,
         }
     }
+
+    pub fn with_source_code(mut self, name: impl AsRef<str>, source: impl Into<String>) -> Self {
+        self.source_code = Some(NamedSource::new(name, source.into()));
+        self
+    }
 }
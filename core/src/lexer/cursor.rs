@@ -27,6 +27,11 @@ impl<'src> Cursor<'src> {
         self.source.get(self.pos + 1).copied()
     }
 
+    /// Peek `n` characters ahead of the current one.
+    pub fn peek_ahead(&self, n: usize) -> Option<u8> {
+        self.source.get(self.pos + n).copied()
+    }
+
     /// Advance one character and return it.
     pub fn advance(&mut self) -> Option<u8> {
         let ch = self.source.get(self.pos).copied()?;
@@ -34,6 +39,24 @@ impl<'src> Cursor<'src> {
         Some(ch)
     }
 
+    /// Finish reading the character whose first byte `first` was just
+    /// returned by [`advance`](Self::advance), consuming the rest of its
+    /// UTF-8 encoding.
+    pub fn finish_char(&mut self, first: u8) -> char {
+        let start = self.pos - 1;
+        let len = match first {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xFF => 4,
+            _ => 1,
+        };
+        self.pos = (start + len).min(self.source.len());
+        std::str::from_utf8(&self.source[start..self.pos])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
     pub fn advance_by(&mut self, n: usize) {
         self.pos = self.pos.saturating_add(n).min(self.source.len());
     }
@@ -12,3 +12,10 @@ pub fn lex(source: &str) -> Result<Vec<Token>, SyntaxError> {
     let mut scanner = scanner::Scanner::new(source);
     scanner.scan_tokens()
 }
+
+/// Tokenize source code, keeping comments as tokens. The result is for
+/// tooling; the parser does not accept comment tokens.
+pub fn lex_with_comments(source: &str) -> Result<Vec<Token>, SyntaxError> {
+    let mut scanner = scanner::Scanner::with_comments(source);
+    scanner.scan_tokens()
+}
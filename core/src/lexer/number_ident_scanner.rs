@@ -1,33 +1,180 @@
 use super::scanner::Scanner;
 use super::token::TokenKind;
 use crate::errors::SyntaxError;
+use crate::runtime::value::radix_digits_to_number;
 
 impl<'src> Scanner<'src> {
-    pub(super) fn scan_number(&mut self, start: usize) -> TokenKind {
-        while let Some(c) = self.cursor.peek() {
-            if c.is_ascii_digit() {
-                self.cursor.advance();
-            } else {
-                break;
+    pub(super) fn scan_number(&mut self, start: usize) -> Result<TokenKind, SyntaxError> {
+        let leading_zero = self.cursor.slice_from(start) == "0";
+        let value = if leading_zero {
+            match self.cursor.peek() {
+                Some(b'x' | b'X') => self.scan_radix_number(start, 16)?,
+                Some(b'o' | b'O') => self.scan_radix_number(start, 8)?,
+                Some(b'b' | b'B') => self.scan_radix_number(start, 2)?,
+                Some(b'_') => {
+                    return Err(SyntaxError::new(
+                        "numeric separator can not be used after leading 0",
+                        self.cursor.pos(),
+                        1,
+                    ));
+                }
+                Some(c) if c.is_ascii_digit() => self.scan_legacy_octal_like(start)?,
+                _ => self.scan_decimal_tail("0".to_owned())?,
             }
+        } else {
+            let mut digits = self.cursor.slice_from(start).to_owned();
+            self.scan_digits(10, &mut digits)?;
+            self.scan_decimal_tail(digits)?
+        };
+
+        self.check_numeric_literal_end(start)?;
+        Ok(TokenKind::Number(value))
+    }
+
+    /// Scans the digits following a `0x`, `0o` or `0b` prefix. The cursor
+    /// is on the prefix letter.
+    fn scan_radix_number(&mut self, start: usize, radix: u32) -> Result<f64, SyntaxError> {
+        self.cursor.advance();
+        let mut digits = String::new();
+        self.scan_digits(radix, &mut digits)?;
+        if digits.is_empty() {
+            return Err(SyntaxError::new(
+                "missing digits after numeric literal prefix",
+                start,
+                self.cursor.pos() - start,
+            ));
         }
+        Ok(radix_digits_to_number(&digits, radix))
+    }
 
-        if self.cursor.peek() == Some(b'.')
-            && self.cursor.peek_next().is_some_and(|c| c.is_ascii_digit())
-        {
+    /// Scans `0`-prefixed integers such as `0755` or `089`. All-octal digit
+    /// runs are legacy octal literals; anything else is a decimal literal
+    /// with a leading zero.
+    fn scan_legacy_octal_like(&mut self, start: usize) -> Result<f64, SyntaxError> {
+        while self.cursor.peek().is_some_and(|c| c.is_ascii_digit()) {
             self.cursor.advance();
-            while let Some(c) = self.cursor.peek() {
-                if c.is_ascii_digit() {
+        }
+        if self.cursor.peek() == Some(b'_') {
+            return Err(SyntaxError::new(
+                "numeric separators are not allowed in literals with a leading 0",
+                self.cursor.pos(),
+                1,
+            ));
+        }
+        let digits = self.cursor.slice_from(start).to_owned();
+        if digits.bytes().all(|c| c < b'8') {
+            Ok(radix_digits_to_number(&digits, 8))
+        } else {
+            self.scan_decimal_tail(digits)
+        }
+    }
+
+    /// Scans the optional fraction and exponent of a decimal literal whose
+    /// integer digits (separators removed) are `digits`.
+    fn scan_decimal_tail(&mut self, mut digits: String) -> Result<f64, SyntaxError> {
+        if self.cursor.peek() == Some(b'.') {
+            match self.cursor.peek_next() {
+                Some(c) if c.is_ascii_digit() => {
                     self.cursor.advance();
-                } else {
-                    break;
+                    digits.push('.');
+                    self.scan_digits(10, &mut digits)?;
+                }
+                Some(b'_') => {
+                    return Err(SyntaxError::new(
+                        "numeric separator must appear between digits",
+                        self.cursor.pos() + 1,
+                        1,
+                    ));
                 }
+                _ => {}
             }
         }
 
-        let text = self.cursor.slice_from(start);
-        let value: f64 = text.parse().expect("scanned digits should parse as f64");
-        TokenKind::Number(value)
+        if matches!(self.cursor.peek(), Some(b'e' | b'E')) {
+            let sign = matches!(self.cursor.peek_next(), Some(b'+' | b'-'));
+            let exponent_follows = self.cursor.peek_ahead(if sign { 2 } else { 1 });
+            if exponent_follows.is_some_and(|c| c.is_ascii_digit() || c == b'_') {
+                self.cursor.advance();
+                digits.push('e');
+                if sign {
+                    digits.extend(self.cursor.advance().map(char::from));
+                }
+                self.scan_digits(10, &mut digits)?;
+            }
+        }
+
+        Ok(digits.parse().expect("scanned digits should parse as f64"))
+    }
+
+    /// Consumes digits of `radix` into `digits`, skipping numeric separators.
+    /// A separator must sit between two digits.
+    fn scan_digits(&mut self, radix: u32, digits: &mut String) -> Result<(), SyntaxError> {
+        let mut after_digit = digits.ends_with(|c: char| c.is_digit(radix));
+        while let Some(c) = self.cursor.peek() {
+            if (c as char).is_digit(radix) {
+                digits.push(c as char);
+                after_digit = true;
+                self.cursor.advance();
+                continue;
+            }
+            if c != b'_' {
+                break;
+            }
+            let offset = self.cursor.pos();
+            if !after_digit {
+                return Err(SyntaxError::new(
+                    "numeric separator must appear between digits",
+                    offset,
+                    1,
+                ));
+            }
+            match self.cursor.peek_next() {
+                Some(b'_') => {
+                    return Err(SyntaxError::new(
+                        "only one underscore is allowed as numeric separator",
+                        offset + 1,
+                        1,
+                    ));
+                }
+                Some(next) if (next as char).is_digit(radix) => {
+                    self.cursor.advance();
+                }
+                Some(next) if next.is_ascii_alphanumeric() || next == b'.' => {
+                    return Err(SyntaxError::new(
+                        "numeric separator must appear between digits",
+                        offset,
+                        1,
+                    ));
+                }
+                _ => {
+                    return Err(SyntaxError::new(
+                        "numeric separators are not allowed at the end of numeric literals",
+                        offset,
+                        1,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A numeric literal must not run straight into an identifier or another
+    /// digit. A trailing `n` gets its own message since BigInt is missing.
+    fn check_numeric_literal_end(&self, start: usize) -> Result<(), SyntaxError> {
+        let pos = self.cursor.pos();
+        match self.cursor.peek() {
+            Some(b'n') => Err(SyntaxError::new(
+                "BigInt literals are not supported",
+                start,
+                pos + 1 - start,
+            )),
+            Some(c) if is_ident_continue(c) || c == b'\\' => Err(SyntaxError::new(
+                "identifier starts immediately after numeric literal",
+                pos,
+                1,
+            )),
+            _ => Ok(()),
+        }
     }
 
     pub(super) fn scan_identifier(&mut self, start: usize) -> Result<TokenKind, SyntaxError> {
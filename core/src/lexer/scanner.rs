@@ -7,7 +7,16 @@ use crate::errors::SyntaxError;
 pub struct Scanner<'src> {
     pub(super) cursor: Cursor<'src>,
     pub(super) pending: Vec<Token>,
-    pub(super) template_depth: usize,
+    /// One entry per open `{` or template substitution, true for the
+    /// latter, so a `}` knows whether it resumes a template.
+    pub(super) braces: Vec<bool>,
+    /// One entry per open `(`, true when it follows `if`, `while` or
+    /// `for`: a `/` after the matching `)` starts a statement.
+    parens: Vec<bool>,
+    /// Whether the last `)` closed one of those heads.
+    closed_head: bool,
+    /// Comments seen so far, when the scanner was asked to keep them.
+    comments: Option<Vec<Token>>,
 }
 
 impl<'src> Scanner<'src> {
@@ -15,7 +24,20 @@ impl<'src> Scanner<'src> {
         Self {
             cursor: Cursor::new(source),
             pending: Vec::new(),
-            template_depth: 0,
+            braces: Vec::new(),
+            parens: Vec::new(),
+            closed_head: false,
+            comments: None,
+        }
+    }
+
+    /// A scanner that also returns comments, as `LineComment` and
+    /// `BlockComment` tokens in source order. The parser does not accept
+    /// them, so they are for tooling only.
+    pub fn with_comments(source: &'src str) -> Self {
+        Self {
+            comments: Some(Vec::new()),
+            ..Self::new(source)
         }
     }
 
@@ -33,17 +55,37 @@ impl<'src> Scanner<'src> {
                 eof_had_line_terminator_before = had_line_terminator_before;
                 break;
             }
-            let prev_kind = tokens.last().map(|t: &Token| &t.kind).cloned();
-            let mut token = self.scan_token_with_context(prev_kind.as_ref())?;
+            let regex_allowed = self.regex_allowed(&tokens);
+            let mut token = self.scan_token_with_context(regex_allowed)?;
             token.had_line_terminator_before = had_line_terminator_before;
+            match token.kind {
+                TokenKind::LeftParen => {
+                    let head = matches!(
+                        tokens.last().map(|t: &Token| &t.kind),
+                        Some(TokenKind::If | TokenKind::While | TokenKind::For)
+                    );
+                    self.parens.push(head);
+                }
+                TokenKind::RightParen => {
+                    self.closed_head = self.parens.pop().unwrap_or(false);
+                }
+                _ => {}
+            }
             tokens.push(token);
         }
 
+        // Comments are merged in only now so that judging a `/` from
+        // the previous token never sees one.
+        if let Some(comments) = self.comments.take() {
+            tokens = merge_by_start(tokens, comments);
+        }
         let eof_pos = self.cursor.pos();
         tokens.push(Token {
             kind: TokenKind::Eof,
             span: Span::new(eof_pos, eof_pos),
             had_line_terminator_before: eof_had_line_terminator_before,
+            legacy_octal: false,
+            raw: None,
         });
 
         Ok(tokens)
@@ -62,6 +104,8 @@ impl<'src> Scanner<'src> {
 
             match self.cursor.peek() {
                 Some(b'/') if self.cursor.peek_next() == Some(b'/') => {
+                    let start = self.cursor.pos();
+                    let line_terminator_before = had_line_terminator;
                     while let Some(_) = self.cursor.peek() {
                         if self.cursor.line_terminator_len().is_some() {
                             had_line_terminator = true;
@@ -69,8 +113,11 @@ impl<'src> Scanner<'src> {
                         }
                         self.cursor.advance();
                     }
+                    self.record_comment(start, line_terminator_before, false);
                 }
                 Some(b'/') if self.cursor.peek_next() == Some(b'*') => {
+                    let start = self.cursor.pos();
+                    let line_terminator_before = had_line_terminator;
                     self.cursor.advance();
                     self.cursor.advance();
                     loop {
@@ -88,6 +135,7 @@ impl<'src> Scanner<'src> {
                             _ => {}
                         }
                     }
+                    self.record_comment(start, line_terminator_before, true);
                 }
                 _ => break,
             }
@@ -95,18 +143,54 @@ impl<'src> Scanner<'src> {
         had_line_terminator
     }
 
-    fn scan_token_with_context(&mut self, prev: Option<&TokenKind>) -> Result<Token, SyntaxError> {
-        let start = self.cursor.pos();
+    /// Keeps the comment from `start` to the cursor, without its
+    /// delimiters, if comments are being kept.
+    fn record_comment(&mut self, start: usize, had_line_terminator_before: bool, block: bool) {
+        let Some(comments) = &mut self.comments else {
+            return;
+        };
+        let text = &self.cursor.slice_from(start)[2..];
+        let kind = if block {
+            TokenKind::BlockComment(text.strip_suffix("*/").unwrap_or(text).to_string())
+        } else {
+            TokenKind::LineComment(text.to_string())
+        };
+        comments.push(Token {
+            kind,
+            span: Span::new(start, self.cursor.pos()),
+            had_line_terminator_before,
+            legacy_octal: false,
+            raw: None,
+        });
+    }
 
-        // Regex literal disambiguation: if we see '/' and the previous
-        // token is NOT a value-producing token, treat as regex.
-        if self.cursor.peek() == Some(b'/') && !is_division_context(prev) {
+    fn scan_token_with_context(&mut self, regex_allowed: bool) -> Result<Token, SyntaxError> {
+        let start = self.cursor.pos();
+        if self.cursor.peek() == Some(b'/') && regex_allowed {
             return self.scan_regex_literal(start);
         }
-
         self.scan_token()
     }
 
+    /// Whether a `/` here starts a regex literal rather than dividing,
+    /// judged from the tokens before it without parser feedback.
+    fn regex_allowed(&self, tokens: &[Token]) -> bool {
+        let Some(prev) = tokens.last() else {
+            return true;
+        };
+        let after_dot = tokens.len() >= 2
+            && matches!(
+                tokens[tokens.len() - 2].kind,
+                TokenKind::Dot | TokenKind::QuestionDot
+            );
+        match &prev.kind {
+            // `obj.return / 2`: a keyword used as a property name is a value.
+            _ if after_dot => false,
+            TokenKind::RightParen => self.closed_head,
+            kind => !is_division_context(kind),
+        }
+    }
+
     fn scan_regex_literal(&mut self, start: usize) -> Result<Token, SyntaxError> {
         self.cursor.advance(); // consume opening '/'
 
@@ -126,7 +210,7 @@ impl<'src> Scanner<'src> {
                     self.cursor.advance();
                     pattern.push('\\');
                     if let Some(escaped) = self.cursor.advance() {
-                        pattern.push(escaped as char);
+                        pattern.push(self.cursor.finish_char(escaped));
                     }
                 }
                 Some(b'[') => {
@@ -145,7 +229,7 @@ impl<'src> Scanner<'src> {
                 }
                 Some(ch) => {
                     self.cursor.advance();
-                    pattern.push(ch as char);
+                    pattern.push(self.cursor.finish_char(ch));
                 }
             }
         }
@@ -166,6 +250,8 @@ impl<'src> Scanner<'src> {
             kind: TokenKind::RegexLiteral { pattern, flags },
             span: Span::new(start, end),
             had_line_terminator_before: false,
+            legacy_octal: false,
+            raw: None,
         })
     }
 
@@ -176,9 +262,12 @@ impl<'src> Scanner<'src> {
         let kind = match ch {
             b'(' => TokenKind::LeftParen,
             b')' => TokenKind::RightParen,
-            b'{' => TokenKind::LeftBrace,
+            b'{' => {
+                self.braces.push(false);
+                TokenKind::LeftBrace
+            }
             b'}' => {
-                if self.template_depth > 0 {
+                if self.braces.pop() == Some(true) {
                     return self.scan_template_continue(start);
                 }
                 TokenKind::RightBrace
@@ -214,7 +303,13 @@ impl<'src> Scanner<'src> {
                 }
             }
             b'*' => {
-                if self.cursor.match_char(b'=') {
+                if self.cursor.match_char(b'*') {
+                    if self.cursor.match_char(b'=') {
+                        TokenKind::StarStarEquals
+                    } else {
+                        TokenKind::StarStar
+                    }
+                } else if self.cursor.match_char(b'=') {
                     TokenKind::StarEquals
                 } else {
                     TokenKind::Star
@@ -236,21 +331,45 @@ impl<'src> Scanner<'src> {
             }
             b'&' => {
                 if self.cursor.match_char(b'&') {
-                    TokenKind::AmpAmp
+                    if self.cursor.match_char(b'=') {
+                        TokenKind::AmpAmpEquals
+                    } else {
+                        TokenKind::AmpAmp
+                    }
+                } else if self.cursor.match_char(b'=') {
+                    TokenKind::AmpEquals
                 } else {
                     TokenKind::Amp
                 }
             }
             b'|' => {
                 if self.cursor.match_char(b'|') {
-                    TokenKind::PipePipe
+                    if self.cursor.match_char(b'=') {
+                        TokenKind::PipePipeEquals
+                    } else {
+                        TokenKind::PipePipe
+                    }
+                } else if self.cursor.match_char(b'=') {
+                    TokenKind::PipeEquals
                 } else {
-                    return Err(SyntaxError::new("unexpected '|'", start, 1));
+                    TokenKind::Pipe
                 }
             }
+            b'^' => {
+                if self.cursor.match_char(b'=') {
+                    TokenKind::CaretEquals
+                } else {
+                    TokenKind::Caret
+                }
+            }
+            b'~' => TokenKind::Tilde,
             b'?' => {
                 if self.cursor.match_char(b'?') {
-                    TokenKind::NullishCoalescing
+                    if self.cursor.match_char(b'=') {
+                        TokenKind::NullishEquals
+                    } else {
+                        TokenKind::NullishCoalescing
+                    }
                 } else if self.cursor.match_char(b'.') {
                     TokenKind::QuestionDot
                 } else {
@@ -258,14 +377,32 @@ impl<'src> Scanner<'src> {
                 }
             }
             b'<' => {
-                if self.cursor.match_char(b'=') {
+                if self.cursor.match_char(b'<') {
+                    if self.cursor.match_char(b'=') {
+                        TokenKind::LessLessEquals
+                    } else {
+                        TokenKind::LessLess
+                    }
+                } else if self.cursor.match_char(b'=') {
                     TokenKind::LessEq
                 } else {
                     TokenKind::Less
                 }
             }
             b'>' => {
-                if self.cursor.match_char(b'=') {
+                if self.cursor.match_char(b'>') {
+                    if self.cursor.match_char(b'>') {
+                        if self.cursor.match_char(b'=') {
+                            TokenKind::GreaterGreaterGreaterEquals
+                        } else {
+                            TokenKind::GreaterGreaterGreater
+                        }
+                    } else if self.cursor.match_char(b'=') {
+                        TokenKind::GreaterGreaterEquals
+                    } else {
+                        TokenKind::GreaterGreater
+                    }
+                } else if self.cursor.match_char(b'=') {
                     TokenKind::GreaterEq
                 } else {
                     TokenKind::Greater
@@ -297,8 +434,15 @@ impl<'src> Scanner<'src> {
             }
             b'"' | b'\'' => self.scan_string(ch, start)?,
             b'`' => return self.scan_template(start),
-            c if c.is_ascii_digit() => self.scan_number(start),
+            c if c.is_ascii_digit() => self.scan_number(start)?,
             c if is_ident_start(c) => self.scan_identifier(start)?,
+            b'#' if self.cursor.peek().is_some_and(is_ident_start) => {
+                self.cursor.advance();
+                match self.scan_identifier(start + 1)? {
+                    TokenKind::Ident(name) => TokenKind::PrivateName(name),
+                    _ => TokenKind::PrivateName(self.cursor.slice_from(start + 1).to_string()),
+                }
+            }
             b'\\' if self.cursor.peek() == Some(b'u') => {
                 self.scan_identifier_after_escape_start(start)?
             }
@@ -312,35 +456,82 @@ impl<'src> Scanner<'src> {
         };
 
         let end = self.cursor.pos();
+        let text = self.cursor.slice_from(start);
+        let legacy_octal = match kind {
+            TokenKind::Number(_) => is_legacy_octal_like(text),
+            TokenKind::String(_) => has_octal_escape(text),
+            _ => false,
+        };
         Ok(Token {
             kind,
             span: Span::new(start, end),
             had_line_terminator_before: false,
+            legacy_octal,
+            raw: None,
         })
     }
 }
 
-/// Returns true when the previous token indicates that `/` should be
-/// parsed as division rather than the start of a regex literal.
-fn is_division_context(prev: Option<&TokenKind>) -> bool {
+/// `0755` and `089` style literals, which strict-mode code rejects.
+fn is_legacy_octal_like(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() > 1 && bytes[0] == b'0' && bytes[1].is_ascii_digit()
+}
+
+/// String literals with an escape such as `\012`, `\0` before a digit, or
+/// `\8`, which strict-mode code rejects.
+fn has_octal_escape(text: &str) -> bool {
+    let mut bytes = text.bytes().peekable();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            continue;
+        }
+        match bytes.next() {
+            Some(b'1'..=b'9') => return true,
+            Some(b'0') if bytes.peek().is_some_and(u8::is_ascii_digit) => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Returns true when the previous token ends a value, so a following `/`
+/// divides. Operators, punctuators that open an expression and keywords
+/// such as `return` or `typeof` leave room for a regex literal instead.
+fn is_division_context(prev: &TokenKind) -> bool {
     matches!(
         prev,
-        Some(
-            TokenKind::Number(_)
-                | TokenKind::String(_)
-                | TokenKind::True
-                | TokenKind::False
-                | TokenKind::Null
-                | TokenKind::Undefined
-                | TokenKind::Ident(_)
-                | TokenKind::RightParen
-                | TokenKind::RightBracket
-                | TokenKind::RightBrace
-                | TokenKind::PlusPlus
-                | TokenKind::MinusMinus
-                | TokenKind::NoSubTemplate(_)
-                | TokenKind::TemplateTail(_)
-                | TokenKind::RegexLiteral { .. }
-        )
+        TokenKind::Number(_)
+            | TokenKind::String(_)
+            | TokenKind::True
+            | TokenKind::False
+            | TokenKind::Null
+            | TokenKind::Undefined
+            | TokenKind::Ident(_)
+            | TokenKind::PrivateName(_)
+            | TokenKind::From
+            | TokenKind::As
+            | TokenKind::RightParen
+            | TokenKind::RightBracket
+            | TokenKind::RightBrace
+            | TokenKind::PlusPlus
+            | TokenKind::MinusMinus
+            | TokenKind::NoSubTemplate(_)
+            | TokenKind::TemplateTail(_)
+            | TokenKind::RegexLiteral { .. }
     )
 }
+
+/// Interleaves two token lists that are each in source order.
+fn merge_by_start(tokens: Vec<Token>, comments: Vec<Token>) -> Vec<Token> {
+    let mut merged = Vec::with_capacity(tokens.len() + comments.len());
+    let mut comments = comments.into_iter().peekable();
+    for token in tokens {
+        while let Some(comment) = comments.next_if(|c| c.span.start < token.span.start) {
+            merged.push(comment);
+        }
+        merged.push(token);
+    }
+    merged.extend(comments);
+    merged
+}
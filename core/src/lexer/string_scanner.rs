@@ -19,7 +19,7 @@ impl Scanner<'_> {
                     Some(c) if c == quote => value.push(c as char),
                     Some(c) => {
                         value.push('\\');
-                        value.push(c as char);
+                        value.push(self.cursor.finish_char(c));
                     }
                     None => {
                         return Err(SyntaxError::new(
@@ -29,7 +29,7 @@ impl Scanner<'_> {
                         ));
                     }
                 },
-                Some(c) => value.push(c as char),
+                Some(c) => value.push(self.cursor.finish_char(c)),
                 None => {
                     return Err(SyntaxError::new(
                         "unterminated string literal",
@@ -45,35 +45,52 @@ impl Scanner<'_> {
     pub(super) fn scan_template(&mut self, start: usize) -> Result<Token, SyntaxError> {
         let (text, ended) = self.scan_template_text(start)?;
         let end = self.cursor.pos();
+        let raw = self.template_raw(start, ended);
         let kind = if ended {
             TokenKind::NoSubTemplate(text)
         } else {
-            self.template_depth += 1;
+            self.braces.push(true);
             TokenKind::TemplateHead(text)
         };
         Ok(Token {
             kind,
             span: Span::new(start, end),
             had_line_terminator_before: false,
+            legacy_octal: false,
+            raw: Some(raw),
         })
     }
 
     pub(super) fn scan_template_continue(&mut self, start: usize) -> Result<Token, SyntaxError> {
         let (text, ended) = self.scan_template_text(start)?;
         let end = self.cursor.pos();
+        let raw = self.template_raw(start, ended);
         let kind = if ended {
-            self.template_depth -= 1;
             TokenKind::TemplateTail(text)
         } else {
+            self.braces.push(true);
             TokenKind::TemplateMiddle(text)
         };
         Ok(Token {
             kind,
             span: Span::new(start, end),
             had_line_terminator_before: false,
+            legacy_octal: false,
+            raw: Some(raw),
         })
     }
 
+    /// The text of the template piece scanned from `start`, between its
+    /// opening `` ` `` or `}` and its closing `` ` `` or `${`, with carriage
+    /// returns read as line feeds.
+    fn template_raw(&self, start: usize, ended: bool) -> String {
+        let text = self.cursor.slice_from(start);
+        let close = if ended { 1 } else { 2 };
+        text[1..text.len() - close]
+            .replace("\r\n", "\n")
+            .replace('\r', "\n")
+    }
+
     fn scan_template_text(&mut self, start: usize) -> Result<(String, bool), SyntaxError> {
         let mut value = String::new();
         loop {
@@ -86,10 +103,10 @@ impl Scanner<'_> {
                 Some(b'\\') => match self.cursor.advance() {
                     Some(b'n') => value.push('\n'),
                     Some(b't') => value.push('\t'),
-                    Some(c) => value.push(c as char),
+                    Some(c) => value.push(self.cursor.finish_char(c)),
                     None => break,
                 },
-                Some(c) => value.push(c as char),
+                Some(c) => value.push(self.cursor.finish_char(c)),
                 None => break,
             }
         }
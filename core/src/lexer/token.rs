@@ -4,6 +4,12 @@ pub struct Token {
     pub kind: TokenKind,
     pub span: Span,
     pub had_line_terminator_before: bool,
+    /// Set on numeric literals written as `0755` or `089`, and on string
+    /// literals with an octal escape such as `"\012"`.
+    pub legacy_octal: bool,
+    /// Set on the pieces of a template literal: the text as written, with
+    /// its escapes left uncooked, for a tag's `strings.raw`.
+    pub raw: Option<String>,
 }
 
 /// Byte offset span in the source string.
@@ -45,6 +51,8 @@ pub enum TokenKind {
 
     // Identifier
     Ident(String),
+    /// `#name` in a class body or after `.`, without the `#`.
+    PrivateName(String),
 
     // Keywords
     Let,
@@ -88,6 +96,7 @@ pub enum TokenKind {
     Plus,
     Minus,
     Star,
+    StarStar,
     Slash,
     Percent,
     Assign,
@@ -95,14 +104,30 @@ pub enum TokenKind {
     PlusEquals,
     MinusEquals,
     StarEquals,
+    StarStarEquals,
     SlashEquals,
     PercentEquals,
+    AmpEquals,
+    PipeEquals,
+    CaretEquals,
+    LessLessEquals,
+    GreaterGreaterEquals,
+    GreaterGreaterGreaterEquals,
     PlusPlus,
     MinusMinus,
     AmpAmp,
+    AmpAmpEquals,
     Amp,
     PipePipe,
+    PipePipeEquals,
+    Pipe,
+    Caret,
+    Tilde,
+    LessLess,
+    GreaterGreater,
+    GreaterGreaterGreater,
     NullishCoalescing,
+    NullishEquals,
     QuestionDot,
     Question,
     EqEqEq,
@@ -131,6 +156,16 @@ pub enum TokenKind {
     // Regex
     RegexLiteral { pattern: String, flags: String },
 
+    // Comments, only produced when the scanner keeps them
+    LineComment(String),
+    BlockComment(String),
+
     // Special
     Eof,
 }
+
+impl TokenKind {
+    pub fn is_comment(&self) -> bool {
+        matches!(self, TokenKind::LineComment(_) | TokenKind::BlockComment(_))
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(clippy::result_large_err)]
 
+pub mod analysis;
 pub mod context;
+pub mod context_builder;
 pub mod diagnostics;
 pub mod embedding;
 pub mod engine;
@@ -10,9 +12,22 @@ pub mod parser;
 pub mod runtime;
 pub mod vm;
 
+pub use analysis::{AnalysisResult, CompiledBytecode};
 pub use context::Context;
-pub use embedding::{ClassBuilder, EventTarget, NativeClassDef};
+pub use context_builder::ContextBuilder;
+#[cfg(feature = "serde")]
+pub use embedding::serde_value::{MapFormat, from_value, to_value, to_value_with};
+pub use embedding::{
+    CallScope, ClassBuilder, CoercionHooks, EventTarget, EventTargetHandle, JsObjectBuilder,
+    NativeClassDef, Persistent, PersistentRoots, PromiseResolver,
+};
 pub use engine::Engine;
+#[cfg(feature = "http")]
+pub use runtime::fetch::FetchPolicy;
+pub use runtime::interpreter::{ModuleImport, ModuleInfo};
+pub use runtime::output::OutputMode;
+pub use runtime::report::{ConsoleLevel, RunEvent, RunOptions, RunReport};
+pub use runtime::snapshot::Snapshot;
 pub use runtime::value::JsValue as Value;
 
 use errors::CrustyError;
@@ -21,17 +36,111 @@ use runtime::interpreter::Interpreter;
 /// Convenience function to run JavaScript source code end-to-end.
 pub fn run(source: &str) -> Result<Interpreter, CrustyError> {
     let tokens = lexer::lex(source)?;
-    let program = parser::parse(tokens)?;
+    let program = parser::parse_with_source(tokens, source)?;
     let mut interp = Interpreter::new_with_realtime_timers(true);
     interp.run(&program)?;
     Ok(interp)
 }
 
-/// Execute source through the VM path.
-pub fn run_vm(source: &str) -> Result<(), CrustyError> {
+/// Tokenizes, parses and compiles `source` without running it. Parsing
+/// carries on past a syntax error, so one call reports them all.
+pub fn analyze(source: &str) -> AnalysisResult {
+    let tokens = match lexer::lex_with_comments(source) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            return AnalysisResult {
+                tokens: Vec::new(),
+                program: None,
+                errors: vec![err],
+                bytecode: None,
+            };
+        }
+    };
+    let code = tokens
+        .iter()
+        .filter(|t| !t.kind.is_comment())
+        .cloned()
+        .collect();
+    let (program, errors) = parser::parse_recovering(code);
+    if !errors.is_empty() {
+        return AnalysisResult {
+            tokens,
+            program: None,
+            errors,
+            bytecode: None,
+        };
+    }
+
+    let mut compiler = vm::compiler::Compiler::new();
+    let compiled = compiler.compile(program.clone());
+    let bytecode = compiled
+        .ok()
+        .filter(|_| !compiler.requires_tree_walk)
+        .map(|chunk| CompiledBytecode {
+            disassembly: chunk.disassemble_with_source(source),
+            chunk,
+        });
+    AnalysisResult {
+        tokens,
+        program: Some(program),
+        errors,
+        bytecode,
+    }
+}
+
+/// Runs `source` to completion and reports what it did as ordered events,
+/// without printing anything. Errors become `UncaughtError` events rather
+/// than an `Err`, so every script yields a report.
+pub fn run_collect(source: &str, options: RunOptions) -> RunReport {
+    let started = std::time::Instant::now();
+    let mut interp = Interpreter::new_with_realtime_timers(options.realtime_timers);
+    interp.set_output_mode(OutputMode::Capture);
+    if let Some(max) = options.max_steps {
+        interp.set_max_steps(max);
+    }
+    interp.run_events = Some(Vec::new());
+
+    let result = lexer::lex(source)
+        .map_err(CrustyError::from)
+        .and_then(|tokens| Ok(parser::parse_with_source(tokens, source)?))
+        .and_then(|program| Ok(interp.run(&program)?));
+    if let Err(err) = result {
+        let message = err.to_string();
+        let stack = interp.error_trace().unwrap_or_default().to_string();
+        interp.record_event(RunEvent::UncaughtError { message, stack });
+    }
+    for value in interp.take_unhandled_rejections() {
+        interp.record_event(RunEvent::UnhandledRejection {
+            value_string: value.to_string(),
+        });
+    }
+
+    let globals = options
+        .globals
+        .iter()
+        .filter_map(|name| {
+            let value = interp.env.get(name).ok()?;
+            Some((name.clone(), value.to_string()))
+        })
+        .collect();
+    RunReport {
+        events: interp.run_events.take().unwrap_or_default(),
+        globals,
+        steps: interp.step_count,
+        virtual_time_ms: interp.event_loop.now_ms(),
+        elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+    }
+}
+
+/// Execute source through the VM path, returning the lines it printed.
+pub fn run_vm(source: &str) -> Result<Vec<String>, CrustyError> {
     vm::run_vm(source)
 }
 
-pub fn run_vm_with_path(source: &str, path: Option<std::path::PathBuf>) -> Result<(), CrustyError> {
-    vm::run_vm_with_path(source, path)
+pub fn run_vm_with_path(
+    source: &str,
+    path: Option<std::path::PathBuf>,
+    sink: Option<runtime::output::OutputSink>,
+) -> Result<Vec<String>, CrustyError> {
+    vm::run_vm_with_path(source, path, sink)
 }
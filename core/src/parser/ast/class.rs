@@ -1,23 +1,43 @@
-use super::Stmt;
+use super::{Expr, FunctionBody, PropertyKey, SourceText};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassDecl {
     pub name: String,
     pub parent: Option<String>,
     pub constructor: Option<ClassMethod>,
     pub methods: Vec<ClassMethod>,
+    /// Instance and static fields, in source order.
+    pub fields: Vec<ClassField>,
+    #[cfg_attr(feature = "ast-serde", serde(skip))]
+    pub source: Option<SourceText>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassMethod {
-    pub name: String,
+    /// Evaluated in source order when the class is defined.
+    pub key: PropertyKey,
     pub params: Vec<String>,
-    pub body: Vec<Stmt>,
+    pub body: FunctionBody,
     pub is_static: bool,
     pub kind: ClassMethodKind,
+    #[cfg_attr(feature = "ast-serde", serde(skip))]
+    pub source: Option<SourceText>,
+}
+
+/// `key = value;` in a class body. Instance fields are initialized on
+/// each new instance, static ones on the constructor.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassField {
+    pub key: PropertyKey,
+    pub value: Option<Expr>,
+    pub is_static: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ClassMethodKind {
     Method,
     Getter,
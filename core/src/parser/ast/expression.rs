@@ -1,15 +1,17 @@
+use super::function_body::{FunctionBody, SourceText};
 use super::literal::Literal;
-use super::pattern::Param;
-use super::statement::Stmt;
+use super::pattern::{Param, Pattern};
 
 /// Binary operator kinds.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinOp {
     Add,
     Sub,
     Mul,
     Div,
     Mod,
+    Exp,
     EqEqEq,
     NotEqEq,
     EqEq,
@@ -19,20 +21,28 @@ pub enum BinOp {
     Greater,
     GreaterEq,
     BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    UShr,
     Instanceof,
     In,
 }
 
 /// Unary operator kinds.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOp {
     Neg,
     Not,
     Void,
     Pos,
+    BitNot,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LogicalOp {
     And,
     Or,
@@ -40,25 +50,67 @@ pub enum LogicalOp {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AssignOp {
     Add,
     Sub,
     Mul,
     Div,
     Mod,
+    Exp,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    UShr,
+    /// `&&=`, `||=` and `??=`, which only assign when the matching
+    /// logical operator would evaluate its right-hand side.
+    And,
+    Or,
+    Nullish,
+}
+
+impl AssignOp {
+    pub fn logical(&self) -> Option<LogicalOp> {
+        match self {
+            AssignOp::And => Some(LogicalOp::And),
+            AssignOp::Or => Some(LogicalOp::Or),
+            AssignOp::Nullish => Some(LogicalOp::Nullish),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UpdateOp {
     Inc,
     Dec,
 }
 
+/// Where the resolver found the binding a name refers to: `depth` scopes
+/// out from the innermost one at the point of use, at position `index`
+/// in that scope's bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Slot {
+    pub depth: u32,
+    pub index: u32,
+}
+
 /// Expression AST nodes.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expr {
     Literal(Literal),
     Identifier(String),
+    /// An identifier the resolver tied to a binding of the enclosing
+    /// function or block.
+    Local {
+        name: String,
+        slot: Slot,
+    },
     Binary {
         left: Box<Expr>,
         op: BinOp,
@@ -75,16 +127,23 @@ pub enum Expr {
     Assign {
         name: String,
         value: Box<Expr>,
+        /// Parsed in strict mode code, where assigning to an undeclared
+        /// name throws instead of creating a global.
+        strict: bool,
+        /// The binding assigned to, when the resolver found it.
+        slot: Option<Slot>,
     },
     CompoundAssign {
         name: String,
         op: AssignOp,
         value: Box<Expr>,
+        slot: Option<Slot>,
     },
     UpdateExpr {
         name: String,
         op: UpdateOp,
         prefix: bool,
+        slot: Option<Slot>,
     },
     MemberAccess {
         object: Box<Expr>,
@@ -103,10 +162,38 @@ pub enum Expr {
         object: Box<Expr>,
         property: Box<Expr>,
     },
+    /// `object.#name`, read from the private elements of a class instance.
+    PrivateMember {
+        object: Box<Expr>,
+        name: String,
+    },
+    /// `import.meta`, the object describing the module it appears in.
+    ImportMeta,
+    /// A bare `#name`: the property of a `MemberAssign` or
+    /// `MemberCompoundAssign` on a private member, or the left side of a
+    /// `#name in object` brand check.
+    PrivateName(String),
     MemberAssign {
         object: Box<Expr>,
         property: Box<Expr>,
         value: Box<Expr>,
+        /// Parsed in strict mode code, where a refused write throws.
+        strict: bool,
+    },
+    /// `obj.prop op= value` or `obj[key] op= value`.
+    MemberCompoundAssign {
+        object: Box<Expr>,
+        property: Box<Expr>,
+        op: AssignOp,
+        value: Box<Expr>,
+        /// Parsed in strict mode code, where a refused write throws.
+        strict: bool,
+    },
+    /// `[a, b] = value` or `({ a, b } = value)`, assigning to existing
+    /// bindings and properties.
+    DestructuringAssign {
+        pattern: Pattern,
+        value: Box<Expr>,
     },
     Logical {
         left: Box<Expr>,
@@ -134,8 +221,11 @@ pub enum Expr {
     },
     ArrowFunction {
         params: Vec<Param>,
-        body: ArrowBody,
+        /// An expression body is kept as a single `return` statement.
+        body: FunctionBody,
         is_async: bool,
+        #[cfg_attr(feature = "ast-serde", serde(skip))]
+        source: Option<SourceText>,
     },
     OptionalChain {
         base: Box<Expr>,
@@ -145,22 +235,149 @@ pub enum Expr {
         pattern: String,
         flags: String,
     },
-    Delete(Box<Expr>),
+    Delete {
+        operand: Box<Expr>,
+        /// Parsed in strict mode code, where a failed delete throws.
+        strict: bool,
+    },
     FunctionExpr {
         name: Option<String>,
         params: Vec<Param>,
-        body: Vec<Stmt>,
+        body: FunctionBody,
         is_async: bool,
         is_generator: bool,
     },
     TaggedTemplate {
         tag: Box<Expr>,
         parts: Vec<TemplatePart>,
+        /// Each string part as written, its escapes uncooked.
+        raw: Vec<String>,
     },
     Sequence(Vec<Expr>),
 }
 
+impl Expr {
+    /// The name of the variant, which profiles count evaluated expressions by.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Expr::Literal(..) => "Literal",
+            Expr::Identifier(..) => "Identifier",
+            Expr::Local { .. } => "Local",
+            Expr::Binary { .. } => "Binary",
+            Expr::Unary { .. } => "Unary",
+            Expr::Call { .. } => "Call",
+            Expr::Assign { .. } => "Assign",
+            Expr::CompoundAssign { .. } => "CompoundAssign",
+            Expr::UpdateExpr { .. } => "UpdateExpr",
+            Expr::MemberAccess { .. } => "MemberAccess",
+            Expr::TemplateLiteral { .. } => "TemplateLiteral",
+            Expr::ObjectLiteral { .. } => "ObjectLiteral",
+            Expr::ArrayLiteral { .. } => "ArrayLiteral",
+            Expr::ComputedMemberAccess { .. } => "ComputedMemberAccess",
+            Expr::PrivateMember { .. } => "PrivateMember",
+            Expr::ImportMeta => "ImportMeta",
+            Expr::PrivateName(..) => "PrivateName",
+            Expr::MemberAssign { .. } => "MemberAssign",
+            Expr::MemberCompoundAssign { .. } => "MemberCompoundAssign",
+            Expr::DestructuringAssign { .. } => "DestructuringAssign",
+            Expr::Logical { .. } => "Logical",
+            Expr::Ternary { .. } => "Ternary",
+            Expr::Typeof(..) => "Typeof",
+            Expr::Spread(..) => "Spread",
+            Expr::New { .. } => "New",
+            Expr::Await(..) => "Await",
+            Expr::Yield { .. } => "Yield",
+            Expr::SuperCall { .. } => "SuperCall",
+            Expr::ArrowFunction { .. } => "ArrowFunction",
+            Expr::OptionalChain { .. } => "OptionalChain",
+            Expr::RegexLiteral { .. } => "RegexLiteral",
+            Expr::Delete { .. } => "Delete",
+            Expr::FunctionExpr { .. } => "FunctionExpr",
+            Expr::TaggedTemplate { .. } => "TaggedTemplate",
+            Expr::Sequence(..) => "Sequence",
+        }
+    }
+
+    /// Whether evaluating the expression can reach a `yield` or `await`
+    /// of the function it appears in. Nested function bodies are not
+    /// searched, as their yields and awaits belong to them.
+    pub fn can_suspend(&self) -> bool {
+        match self {
+            Expr::Yield { .. } | Expr::Await(_) => true,
+            Expr::Literal(..)
+            | Expr::Identifier(..)
+            | Expr::Local { .. }
+            | Expr::UpdateExpr { .. }
+            | Expr::ImportMeta
+            | Expr::PrivateName(..)
+            | Expr::RegexLiteral { .. }
+            | Expr::ArrowFunction { .. }
+            | Expr::FunctionExpr { .. } => false,
+            Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+                left.can_suspend() || right.can_suspend()
+            }
+            Expr::Unary { operand: inner, .. }
+            | Expr::Assign { value: inner, .. }
+            | Expr::CompoundAssign { value: inner, .. }
+            | Expr::MemberAccess { object: inner, .. }
+            | Expr::PrivateMember { object: inner, .. }
+            | Expr::Typeof(inner)
+            | Expr::Spread(inner)
+            | Expr::Delete { operand: inner, .. } => inner.can_suspend(),
+            Expr::Call { callee, args } | Expr::New { callee, args } => {
+                callee.can_suspend() || args.iter().any(Expr::can_suspend)
+            }
+            Expr::SuperCall { args }
+            | Expr::ArrayLiteral { elements: args }
+            | Expr::Sequence(args) => args.iter().any(Expr::can_suspend),
+            Expr::TemplateLiteral { parts } => parts.iter().any(TemplatePart::can_suspend),
+            Expr::TaggedTemplate { tag, parts, .. } => {
+                tag.can_suspend() || parts.iter().any(TemplatePart::can_suspend)
+            }
+            Expr::ObjectLiteral { properties } => properties.iter().any(|prop| match prop {
+                ObjectProperty::KeyValue(key, value) => key.can_suspend() || value.can_suspend(),
+                ObjectProperty::Getter(key, _) | ObjectProperty::Setter(key, ..) => {
+                    key.can_suspend()
+                }
+                ObjectProperty::Spread(value) => value.can_suspend(),
+            }),
+            Expr::ComputedMemberAccess { object, property } => {
+                object.can_suspend() || property.can_suspend()
+            }
+            Expr::MemberAssign {
+                object,
+                property,
+                value,
+                ..
+            }
+            | Expr::MemberCompoundAssign {
+                object,
+                property,
+                value,
+                ..
+            } => object.can_suspend() || property.can_suspend() || value.can_suspend(),
+            Expr::DestructuringAssign { pattern, value } => {
+                pattern.can_suspend() || value.can_suspend()
+            }
+            Expr::Ternary {
+                condition,
+                then_expr,
+                else_expr,
+            } => condition.can_suspend() || then_expr.can_suspend() || else_expr.can_suspend(),
+            Expr::OptionalChain { base, chain } => {
+                base.can_suspend()
+                    || chain.iter().any(|op| match op {
+                        OptionalOp::PropertyAccess(_) => false,
+                        OptionalOp::ComputedAccess(expr) => expr.can_suspend(),
+                        OptionalOp::Call(args) => args.iter().any(Expr::can_suspend),
+                    })
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OptionalOp {
     PropertyAccess(String),
     ComputedAccess(Expr),
@@ -168,27 +385,41 @@ pub enum OptionalOp {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TemplatePart {
     Str(String),
     Expression(Expr),
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum ArrowBody {
-    Expr(Box<Expr>),
-    Block(Vec<Stmt>),
+impl TemplatePart {
+    fn can_suspend(&self) -> bool {
+        match self {
+            TemplatePart::Str(_) => false,
+            TemplatePart::Expression(expr) => expr.can_suspend(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObjectProperty {
     KeyValue(PropertyKey, Expr),
-    Getter(PropertyKey, Vec<Stmt>),
-    Setter(PropertyKey, String, Vec<Stmt>),
+    Getter(PropertyKey, FunctionBody),
+    Setter(PropertyKey, String, FunctionBody),
     Spread(Expr),
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropertyKey {
     Identifier(String),
     Computed(Expr),
+    /// `#name`, only found on class members.
+    Private(String),
+}
+
+impl PropertyKey {
+    fn can_suspend(&self) -> bool {
+        matches!(self, PropertyKey::Computed(expr) if expr.can_suspend())
+    }
 }
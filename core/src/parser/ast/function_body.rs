@@ -0,0 +1,191 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+use super::Stmt;
+use crate::errors::SyntaxError;
+use crate::lexer::token::Token;
+use crate::parser::resolver::ParamScope;
+
+/// The statements of a function declaration or expression.
+///
+/// The parser may skip a body it has only brace-matched, leaving the
+/// tokens to be parsed the first time the statements are asked for.
+/// Clones share one body, so every function value made from the same
+/// node parses it at most once.
+#[derive(Clone)]
+pub struct FunctionBody {
+    state: Rc<RefCell<BodyState>>,
+    /// The text of the whole function, when the parser was given it.
+    source: Option<SourceText>,
+}
+
+enum BodyState {
+    Parsed(Rc<[Stmt]>),
+    Deferred {
+        tokens: Rc<Vec<Token>>,
+        /// Index of the body's opening `{`.
+        start: usize,
+        strict: bool,
+        /// The source the tokens were lexed from, for the functions
+        /// nested in the body.
+        source: Option<Rc<str>>,
+        params: ParamScope,
+    },
+}
+
+impl FunctionBody {
+    pub(crate) fn deferred(
+        tokens: Rc<Vec<Token>>,
+        start: usize,
+        strict: bool,
+        source: Option<Rc<str>>,
+        params: ParamScope,
+    ) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(BodyState::Deferred {
+                tokens,
+                start,
+                strict,
+                source,
+                params,
+            })),
+            source: None,
+        }
+    }
+
+    /// Attaches the text of the function this body belongs to.
+    pub(crate) fn with_source(mut self, source: Option<SourceText>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// The function's text as written, from its first token to its
+    /// closing brace, or `None` when the parser was not given the source.
+    pub fn source_text(&self) -> Option<&str> {
+        self.source.as_ref().map(SourceText::as_str)
+    }
+
+    /// The body's statements, parsing them now if the parser skipped
+    /// them. A syntax error is reported with its offset in the original
+    /// source, and again on every later call.
+    pub fn stmts(&self) -> Result<Rc<[Stmt]>, SyntaxError> {
+        let stmts = match &*self.state.borrow() {
+            BodyState::Parsed(stmts) => return Ok(Rc::clone(stmts)),
+            BodyState::Deferred {
+                tokens,
+                start,
+                strict,
+                source,
+                params,
+            } => crate::parser::parse_deferred_body(
+                Rc::clone(tokens),
+                *start,
+                *strict,
+                source.clone(),
+                params,
+            )?,
+        };
+        let stmts: Rc<[Stmt]> = stmts.into();
+        *self.state.borrow_mut() = BodyState::Parsed(Rc::clone(&stmts));
+        Ok(stmts)
+    }
+
+    /// Whether the statements have been built yet.
+    pub fn is_parsed(&self) -> bool {
+        matches!(&*self.state.borrow(), BodyState::Parsed(_))
+    }
+
+    /// Whether both handles refer to the same body.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl From<Rc<[Stmt]>> for FunctionBody {
+    fn from(stmts: Rc<[Stmt]>) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(BodyState::Parsed(stmts))),
+            source: None,
+        }
+    }
+}
+
+impl From<Vec<Stmt>> for FunctionBody {
+    fn from(stmts: Vec<Stmt>) -> Self {
+        Rc::<[Stmt]>::from(stmts).into()
+    }
+}
+
+impl From<&[Stmt]> for FunctionBody {
+    fn from(stmts: &[Stmt]) -> Self {
+        Rc::<[Stmt]>::from(stmts).into()
+    }
+}
+
+impl PartialEq for FunctionBody {
+    fn eq(&self, other: &Self) -> bool {
+        if self.ptr_eq(other) {
+            return true;
+        }
+        match (self.stmts(), other.stmts()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for FunctionBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.stmts() {
+            Ok(stmts) => fmt::Debug::fmt(&stmts[..], f),
+            Err(err) => write!(f, "<syntax error: {}>", err.message),
+        }
+    }
+}
+
+/// A span of the source a node was parsed from. Every span shares the one
+/// copy of the source text.
+#[derive(Clone)]
+pub struct SourceText {
+    text: Rc<str>,
+    range: Range<usize>,
+}
+
+impl SourceText {
+    pub(crate) fn new(text: Rc<str>, range: Range<usize>) -> Self {
+        Self { text, range }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text[self.range.clone()]
+    }
+}
+
+impl PartialEq for SourceText {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl fmt::Debug for SourceText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "ast-serde")]
+impl serde::Serialize for FunctionBody {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let stmts = self.stmts().map_err(serde::ser::Error::custom)?;
+        serializer.collect_seq(stmts.iter())
+    }
+}
+
+#[cfg(feature = "ast-serde")]
+impl<'de> serde::Deserialize<'de> for FunctionBody {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Stmt>::deserialize(deserializer).map(Self::from)
+    }
+}
@@ -1,5 +1,6 @@
 /// Literal values in JavaScript.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Literal {
     Number(f64),
     String(String),
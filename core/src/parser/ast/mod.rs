@@ -1,15 +1,17 @@
 mod class;
 mod expression;
+mod function_body;
 mod literal;
 mod module;
 mod pattern;
 mod statement;
 
-pub use class::{ClassDecl, ClassMethod, ClassMethodKind};
+pub use class::{ClassDecl, ClassField, ClassMethod, ClassMethodKind};
 pub use expression::{
-    ArrowBody, AssignOp, BinOp, Expr, LogicalOp, ObjectProperty, OptionalOp, PropertyKey,
-    TemplatePart, UnaryOp, UpdateOp,
+    AssignOp, BinOp, Expr, LogicalOp, ObjectProperty, OptionalOp, PropertyKey, Slot, TemplatePart,
+    UnaryOp, UpdateOp,
 };
+pub use function_body::{FunctionBody, SourceText};
 pub use literal::Literal;
 pub use module::{ExportDecl, ExportSpecifier, ImportDecl, ImportSpecifier};
 pub use pattern::{ObjectPatternProp, Param, Pattern};
@@ -17,6 +19,10 @@ pub use statement::{Stmt, SwitchCase, VarDeclKind};
 
 /// A complete JavaScript program — a list of top-level statements.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     pub body: Vec<Stmt>,
+    /// Source byte offset where each top-level statement starts,
+    /// parallel to `body`.
+    pub offsets: Vec<usize>,
 }
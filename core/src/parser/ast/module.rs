@@ -2,12 +2,16 @@ use super::expression::Expr;
 use super::statement::Stmt;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImportDecl {
     pub specifiers: Vec<ImportSpecifier>,
     pub source: String,
+    /// The `with { key: "value" }` clause, in source order.
+    pub attributes: Vec<(String, String)>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImportSpecifier {
     Named { imported: String, local: String },
     Default(String),
@@ -15,6 +19,7 @@ pub enum ImportSpecifier {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExportDecl {
     NamedStmt(Box<Stmt>),
     Default(Expr),
@@ -23,6 +28,7 @@ pub enum ExportDecl {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExportSpecifier {
     pub local: String,
     pub exported: String,
@@ -1,22 +1,34 @@
 use super::expression::Expr;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pattern {
     Identifier(String),
     ObjectPattern { properties: Vec<ObjectPatternProp> },
     ArrayPattern { elements: Vec<Option<Pattern>> },
     Rest(Box<Pattern>),
+    /// An array element with an initializer, used when the value is
+    /// `undefined`.
+    Default { target: Box<Pattern>, value: Box<Expr> },
+    /// A property reference such as `obj.x` or `arr[0]`. Only destructuring
+    /// assignment produces these; declarations bind names.
+    Member(Box<Expr>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Param {
     pub pattern: Pattern,
     pub default: Option<Expr>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ObjectPatternProp {
     pub key: String,
+    /// The key expression of `{ [expr]: target }`, evaluated when the
+    /// pattern is matched; `key` is empty then.
+    pub computed: Option<Expr>,
     pub alias: Option<Pattern>,
     pub default: Option<Expr>,
     pub is_rest: bool,
@@ -29,4 +41,53 @@ impl Pattern {
             _ => None,
         }
     }
+
+    /// Every name the pattern binds, in source order.
+    pub fn bound_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_bound_names(&mut names);
+        names
+    }
+
+    fn collect_bound_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Pattern::Identifier(name) => names.push(name),
+            Pattern::ObjectPattern { properties } => {
+                for prop in properties {
+                    match &prop.alias {
+                        Some(alias) => alias.collect_bound_names(names),
+                        None => names.push(&prop.key),
+                    }
+                }
+            }
+            Pattern::ArrayPattern { elements } => {
+                for element in elements.iter().flatten() {
+                    element.collect_bound_names(names);
+                }
+            }
+            Pattern::Rest(inner) | Pattern::Default { target: inner, .. } => {
+                inner.collect_bound_names(names)
+            }
+            Pattern::Member(_) => {}
+        }
+    }
+
+    /// Whether matching the pattern evaluates a `yield` or `await`, in a
+    /// default, computed key or member target.
+    pub fn can_suspend(&self) -> bool {
+        match self {
+            Pattern::Identifier(_) => false,
+            Pattern::ObjectPattern { properties } => properties.iter().any(|prop| {
+                prop.computed.as_ref().is_some_and(Expr::can_suspend)
+                    || prop.alias.as_ref().is_some_and(Pattern::can_suspend)
+                    || prop.default.as_ref().is_some_and(Expr::can_suspend)
+            }),
+            Pattern::ArrayPattern { elements } => {
+                elements.iter().flatten().any(Pattern::can_suspend)
+            }
+            Pattern::Rest(inner) => inner.can_suspend(),
+            Pattern::Default { target, value } => target.can_suspend() || value.can_suspend(),
+            Pattern::Member(expr) => expr.can_suspend(),
+        }
+    }
 }
@@ -1,10 +1,12 @@
 use super::class::ClassDecl;
 use super::expression::Expr;
+use super::function_body::FunctionBody;
 use super::module::{ExportDecl, ImportDecl};
 use super::pattern::{Param, Pattern};
 
 /// Statement AST nodes.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Stmt {
     ExprStmt(Expr),
     VarDecl {
@@ -25,7 +27,7 @@ pub enum Stmt {
     FunctionDecl {
         name: String,
         params: Vec<Param>,
-        body: Vec<Stmt>,
+        body: FunctionBody,
         is_async: bool,
         is_generator: bool,
         decl_offset: usize,
@@ -47,19 +49,24 @@ pub enum Stmt {
         update: Option<Expr>,
         body: Box<Stmt>,
     },
+    /// `for (kind pattern of iterable)`. Without a `kind` the loop
+    /// assigns to a binding declared elsewhere.
     ForOf {
-        variable: String,
+        kind: Option<VarDeclKind>,
+        pattern: Pattern,
         iterable: Expr,
         body: Box<Stmt>,
     },
+    /// `for (kind pattern in object)`, declared like `ForOf`.
     ForIn {
-        variable: String,
+        kind: Option<VarDeclKind>,
+        pattern: Pattern,
         object: Expr,
         body: Box<Stmt>,
     },
     TryCatch {
         try_block: Vec<Stmt>,
-        catch_param: Option<String>,
+        catch_param: Option<Pattern>,
         catch_block: Option<Vec<Stmt>>,
         finally_block: Option<Vec<Stmt>>,
     },
@@ -82,13 +89,45 @@ pub enum Stmt {
     Export(ExportDecl),
 }
 
+impl Stmt {
+    /// The name of the variant, which profiles count executed statements by.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Stmt::ExprStmt(..) => "ExprStmt",
+            Stmt::VarDecl { .. } => "VarDecl",
+            Stmt::Block(..) => "Block",
+            Stmt::If { .. } => "If",
+            Stmt::While { .. } => "While",
+            Stmt::FunctionDecl { .. } => "FunctionDecl",
+            Stmt::Return(..) => "Return",
+            Stmt::Break { .. } => "Break",
+            Stmt::Continue { .. } => "Continue",
+            Stmt::Labeled { .. } => "Labeled",
+            Stmt::ForLoop { .. } => "ForLoop",
+            Stmt::ForOf { .. } => "ForOf",
+            Stmt::ForIn { .. } => "ForIn",
+            Stmt::TryCatch { .. } => "TryCatch",
+            Stmt::Throw(..) => "Throw",
+            Stmt::Switch { .. } => "Switch",
+            Stmt::DoWhile { .. } => "DoWhile",
+            Stmt::Empty => "Empty",
+            Stmt::VarDeclList { .. } => "VarDeclList",
+            Stmt::Class(..) => "Class",
+            Stmt::Import(..) => "Import",
+            Stmt::Export(..) => "Export",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SwitchCase {
     pub test: Option<Expr>,
     pub body: Vec<Stmt>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "ast-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VarDeclKind {
     Let,
     Const,
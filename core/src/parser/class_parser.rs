@@ -1,10 +1,25 @@
-use super::ast::{ClassDecl, ClassMethod, ClassMethodKind, Stmt};
+use std::collections::HashMap;
+
 use super::Parser;
+use super::ast::{ClassDecl, ClassField, ClassMethod, ClassMethodKind, PropertyKey, Stmt};
 use crate::errors::SyntaxError;
 use crate::lexer::token::TokenKind;
 
+/// The private names a class body declares and the ones used inside it,
+/// which have to be declared by it or by an enclosing class.
+#[derive(Default)]
+pub(super) struct PrivateScope {
+    /// The kind of each declared name, `None` for a field, and whether it
+    /// is static.
+    declared: HashMap<String, (Option<ClassMethodKind>, bool)>,
+    used: Vec<(String, usize)>,
+}
+
+type ClassMembers = (Option<ClassMethod>, Vec<ClassMethod>, Vec<ClassField>);
+
 impl Parser {
     pub(crate) fn parse_class_decl(&mut self) -> Result<Stmt, SyntaxError> {
+        let class_start = self.pos;
         self.advance(); // consume 'class'
         let name = self.expect_ident()?;
 
@@ -16,23 +31,96 @@ impl Parser {
         };
 
         self.expect(&TokenKind::LeftBrace)?;
+        self.private_scopes.push(PrivateScope::default());
+        // All parts of a class body are strict mode code.
+        let outer_strict = std::mem::replace(&mut self.strict_mode, true);
+        let members = self.parse_class_members();
+        self.strict_mode = outer_strict;
+        let resolved = self.close_private_scope();
+        let (constructor, methods, fields) = members?;
+        resolved?;
+
+        self.expect(&TokenKind::RightBrace)?;
+        Ok(Stmt::Class(ClassDecl {
+            name,
+            parent,
+            constructor,
+            methods,
+            fields,
+            source: self.source_since(class_start),
+        }))
+    }
+
+    fn parse_class_members(&mut self) -> Result<ClassMembers, SyntaxError> {
         let mut constructor = None;
         let mut methods = Vec::new();
+        let mut fields = Vec::new();
 
         while !self.check(&TokenKind::RightBrace) && !self.is_at_end() {
-            let mut method_kind = ClassMethodKind::Method;
-            let mut method_name = self.expect_ident()?;
-
-            if (method_name == "get" || method_name == "set") && !self.check(&TokenKind::LeftParen)
-            {
-                method_kind = if method_name == "get" {
+            if self.check(&TokenKind::Semicolon) {
+                self.advance();
+                continue;
+            }
+            let is_static = matches!(self.peek(), TokenKind::Ident(word) if word == "static")
+                && self.next_starts_property_key();
+            if is_static {
+                self.advance();
+            }
+            let start = self.pos;
+            let method_kind = match self.peek() {
+                TokenKind::Ident(word) if word == "get" && self.next_starts_property_key() => {
+                    self.advance();
                     ClassMethodKind::Getter
-                } else {
+                }
+                TokenKind::Ident(word) if word == "set" && self.next_starts_property_key() => {
+                    self.advance();
                     ClassMethodKind::Setter
+                }
+                _ => ClassMethodKind::Method,
+            };
+            let key_token = self.tokens[self.pos].clone();
+            let key = match self.peek().clone() {
+                TokenKind::PrivateName(name) => {
+                    self.advance();
+                    if name == "constructor" {
+                        return Err(SyntaxError::new(
+                            "classes may not have a private member named '#constructor'",
+                            key_token.span.start,
+                            key_token.span.len(),
+                        ));
+                    }
+                    PropertyKey::Private(name)
+                }
+                _ => self.parse_property_key()?,
+            };
+
+            if method_kind == ClassMethodKind::Method && !self.check(&TokenKind::LeftParen) {
+                if matches!(&key, PropertyKey::Identifier(name) if name == "constructor") {
+                    return Err(SyntaxError::new(
+                        "classes may not have a field named 'constructor'",
+                        key_token.span.start,
+                        key_token.span.len().max(1),
+                    ));
+                }
+                let value = if self.check(&TokenKind::Assign) {
+                    self.advance();
+                    Some(self.parse_expr(0)?)
+                } else {
+                    None
                 };
-                method_name = self.expect_ident()?;
+                self.consume_stmt_terminator()?;
+                if let PropertyKey::Private(name) = &key {
+                    self.declare_private_name(name, None, is_static, key_token.span.start)?;
+                }
+                fields.push(ClassField {
+                    key,
+                    value,
+                    is_static,
+                });
+                continue;
             }
 
+            let list_start = self.pos;
             self.expect(&TokenKind::LeftParen)?;
 
             let mut params = Vec::new();
@@ -47,6 +135,8 @@ impl Parser {
                 }
             }
             self.expect(&TokenKind::RightParen)?;
+            let names: Vec<&str> = params.iter().map(String::as_str).collect();
+            self.check_param_names(&names, true, true, list_start)?;
             let body = self.parse_block()?;
 
             if method_kind == ClassMethodKind::Getter && !params.is_empty() {
@@ -65,27 +155,105 @@ impl Parser {
                 ));
             }
 
+            if let PropertyKey::Private(name) = &key {
+                self.declare_private_name(
+                    name,
+                    Some(method_kind),
+                    is_static,
+                    key_token.span.start,
+                )?;
+            }
+            let is_constructor = !is_static
+                && method_kind == ClassMethodKind::Method
+                && matches!(&key, PropertyKey::Identifier(name) if name == "constructor");
             let method = ClassMethod {
-                name: method_name.clone(),
+                key,
                 params,
-                body,
-                is_static: false,
+                body: body.into(),
+                is_static,
                 kind: method_kind,
+                source: self.source_since(start),
             };
 
-            if method_name == "constructor" {
+            if is_constructor {
                 constructor = Some(method);
             } else {
                 methods.push(method);
             }
         }
 
-        self.expect(&TokenKind::RightBrace)?;
-        Ok(Stmt::Class(ClassDecl {
-            name,
-            parent,
-            constructor,
-            methods,
-        }))
+        Ok((constructor, methods, fields))
     }
+
+    /// Records a use of `#name` at `offset`, which the innermost class
+    /// body being parsed or one around it must declare.
+    pub(super) fn use_private_name(
+        &mut self,
+        name: String,
+        offset: usize,
+    ) -> Result<(), SyntaxError> {
+        match self.private_scopes.last_mut() {
+            Some(scope) => scope.used.push((name, offset)),
+            None if self.deferred => {}
+            None => return Err(undeclared_private_name(&name, offset)),
+        }
+        Ok(())
+    }
+
+    /// Declares `#name` in the innermost class body. A name may only be
+    /// declared twice as the getter and setter of one accessor.
+    fn declare_private_name(
+        &mut self,
+        name: &str,
+        kind: Option<ClassMethodKind>,
+        is_static: bool,
+        offset: usize,
+    ) -> Result<(), SyntaxError> {
+        let Some(scope) = self.private_scopes.last_mut() else {
+            return Ok(());
+        };
+        if let Some(&(previous, was_static)) = scope.declared.get(name) {
+            let accessor_pair = matches!(
+                (previous, kind),
+                (Some(ClassMethodKind::Getter), Some(ClassMethodKind::Setter))
+                    | (Some(ClassMethodKind::Setter), Some(ClassMethodKind::Getter))
+            );
+            if !accessor_pair || was_static != is_static {
+                return Err(SyntaxError::new(
+                    format!("Identifier '#{name}' has already been declared"),
+                    offset,
+                    name.len() + 1,
+                ));
+            }
+        }
+        scope.declared.insert(name.to_string(), (kind, is_static));
+        Ok(())
+    }
+
+    /// Ends the innermost class body, handing the names it used but did
+    /// not declare to the class around it.
+    fn close_private_scope(&mut self) -> Result<(), SyntaxError> {
+        let Some(scope) = self.private_scopes.pop() else {
+            return Ok(());
+        };
+        for (name, offset) in scope.used {
+            if scope.declared.contains_key(&name) {
+                continue;
+            }
+            match self.private_scopes.last_mut() {
+                Some(outer) => outer.used.push((name, offset)),
+                None if self.deferred => {}
+                None => return Err(undeclared_private_name(&name, offset)),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn undeclared_private_name(name: &str, offset: usize) -> SyntaxError {
+    SyntaxError::new(
+        format!("Private field '#{name}' must be declared in an enclosing class"),
+        offset,
+        name.len() + 1,
+    )
 }
@@ -1,7 +1,9 @@
 use super::ast::{
-    ArrowBody, AssignOp, Expr, ObjectProperty, Param, Pattern, PropertyKey, TemplatePart,
+    Expr, FunctionBody, Literal, ObjectProperty, Param, Pattern, PropertyKey, Stmt, TemplatePart,
 };
 use super::Parser;
+use super::expr_ops::compound_assign_op;
+use super::resolver;
 use crate::errors::SyntaxError;
 use crate::lexer::token::TokenKind;
 
@@ -14,15 +16,65 @@ impl Parser {
         Ok(Expr::SuperCall { args })
     }
 
+    /// `new Callee(args)`. The argument list right after the callee
+    /// belongs to the `new`; any accesses or calls after it apply to the
+    /// constructed object and are left to the caller.
     pub(crate) fn parse_new_expr(&mut self) -> Result<Expr, SyntaxError> {
         self.advance(); // consume 'new'
-        let callee_expr = self.parse_expr(12)?;
-        match callee_expr {
-            Expr::Call { callee, args } => Ok(Expr::New { callee, args }),
-            callee => Ok(Expr::New {
-                callee: Box::new(callee),
-                args: Vec::new(),
-            }),
+        let callee = self.parse_new_callee()?;
+        let args = if self.check(&TokenKind::LeftParen) {
+            self.advance();
+            let args = self.parse_call_args()?;
+            self.expect(&TokenKind::RightParen)?;
+            args
+        } else {
+            Vec::new()
+        };
+        Ok(Expr::New {
+            callee: Box::new(callee),
+            args,
+        })
+    }
+
+    /// The member expression a `new` constructs: a primary expression or
+    /// a nested `new` with its arguments, followed by any `.name` and
+    /// `[key]` accesses but no calls.
+    fn parse_new_callee(&mut self) -> Result<Expr, SyntaxError> {
+        let mut callee = if self.check(&TokenKind::New) {
+            self.parse_new_expr()?
+        } else {
+            self.parse_primary()?
+        };
+        loop {
+            callee = match self.peek() {
+                TokenKind::Dot
+                    if matches!(
+                        self.tokens.get(self.pos + 1).map(|t| &t.kind),
+                        Some(TokenKind::PrivateName(_))
+                    ) =>
+                {
+                    self.advance();
+                    self.parse_private_member(callee)?
+                }
+                TokenKind::Dot => {
+                    self.advance();
+                    let property = self.expect_property_name()?;
+                    Expr::MemberAccess {
+                        object: Box::new(callee),
+                        property,
+                    }
+                }
+                TokenKind::LeftBracket => {
+                    self.advance();
+                    let property = self.parse_expression()?;
+                    self.expect(&TokenKind::RightBracket)?;
+                    Expr::ComputedMemberAccess {
+                        object: Box::new(callee),
+                        property: Box::new(property),
+                    }
+                }
+                _ => return Ok(callee),
+            }
         }
     }
 
@@ -37,43 +89,38 @@ impl Parser {
         }
 
         if self.check(&TokenKind::Arrow) {
+            let start = self.pos - 1;
             self.advance();
-            let body = self.parse_arrow_body()?;
+            let mut params = vec![Param {
+                pattern: Pattern::Identifier(name),
+                default: None,
+            }];
+            let body = self.parse_arrow_body(&mut params, start)?;
             Ok(Expr::ArrowFunction {
-                params: vec![Param {
-                    pattern: Pattern::Identifier(name),
-                    default: None,
-                }],
+                params,
                 body,
                 is_async: false,
+                source: self.source_since(start),
             })
-        } else if self.check(&TokenKind::PlusEquals)
-            || self.check(&TokenKind::MinusEquals)
-            || self.check(&TokenKind::StarEquals)
-            || self.check(&TokenKind::SlashEquals)
-            || self.check(&TokenKind::PercentEquals)
-        {
-            let op_token = self.advance().kind.clone();
-            let op = match op_token {
-                TokenKind::PlusEquals => AssignOp::Add,
-                TokenKind::MinusEquals => AssignOp::Sub,
-                TokenKind::StarEquals => AssignOp::Mul,
-                TokenKind::SlashEquals => AssignOp::Div,
-                TokenKind::PercentEquals => AssignOp::Mod,
-                _ => unreachable!(),
-            };
+        } else if let Some(op) = compound_assign_op(self.peek()) {
+            self.check_strict_target(&name, &self.tokens[self.pos - 1])?;
+            self.advance();
             let value = self.parse_expr(0)?;
             Ok(Expr::CompoundAssign {
                 name,
                 op,
                 value: Box::new(value),
+                slot: None,
             })
         } else if self.check(&TokenKind::Assign) {
+            self.check_strict_target(&name, &self.tokens[self.pos - 1])?;
             self.advance();
             let value = self.parse_expr(0)?;
             Ok(Expr::Assign {
                 name,
                 value: Box::new(value),
+                strict: self.strict_mode,
+                slot: None,
             })
         } else {
             Ok(Expr::Identifier(name))
@@ -83,14 +130,16 @@ impl Parser {
     pub(crate) fn parse_paren_or_arrow(&mut self) -> Result<Expr, SyntaxError> {
         let after_lparen = self.pos;
         if self.scan_arrow_signature(after_lparen) {
-            let params = self.parse_params_list()?;
+            let mut params = self.parse_params_list()?;
             self.expect(&TokenKind::RightParen)?;
             self.expect(&TokenKind::Arrow)?;
-            let body = self.parse_arrow_body()?;
+            let body = self.parse_arrow_body(&mut params, after_lparen)?;
             return Ok(Expr::ArrowFunction {
                 params,
                 body,
                 is_async: false,
+                // The text starts at the `(` consumed before this call.
+                source: self.source_since(after_lparen - 1),
             });
         }
 
@@ -112,90 +161,104 @@ impl Parser {
                 continue;
             }
 
-            let (key, value) = if self.check(&TokenKind::LeftBracket) {
+            let start = self.pos;
+            let mut is_async = false;
+            let mut is_generator = false;
+            let mut accessor = None;
+            if self.check(&TokenKind::Async) && self.next_starts_property_key() {
                 self.advance();
-                let key_expr = self.parse_expr(0)?;
-                self.expect(&TokenKind::RightBracket)?;
-                self.expect(&TokenKind::Colon)?;
-                (PropertyKey::Computed(key_expr), self.parse_expr(0)?)
-            } else {
-                let was_identifier = matches!(self.peek(), TokenKind::Ident(_));
-                let key_name = self.expect_property_name()?;
-                let key_token = self.tokens[self.pos - 1].clone();
-                if (key_name == "get" || key_name == "set") && !self.check(&TokenKind::Colon) {
-                    let accessor_key = PropertyKey::Identifier(self.expect_ident()?);
-                    self.expect(&TokenKind::LeftParen)?;
-                    let params = self.parse_method_params()?;
-                    self.expect(&TokenKind::RightParen)?;
-                    let body = self.parse_block()?;
+                is_async = true;
+            } else if matches!(self.peek(), TokenKind::Ident(name) if name == "get" || name == "set")
+                && self.next_starts_property_key()
+            {
+                accessor = Some(self.expect_ident()?);
+            }
+            if accessor.is_none() && self.check(&TokenKind::Star) {
+                self.advance();
+                is_generator = true;
+            }
+
+            let key_token = self.tokens[self.pos].clone();
+            let was_identifier = matches!(key_token.kind, TokenKind::Ident(_));
+            let key = self.parse_property_key()?;
 
-                    let accessor = if key_name == "get" {
-                        if !params.is_empty() {
-                            let token = self.tokens[self.pos - 1].clone();
-                            return Err(SyntaxError::new(
-                                "getter must not declare parameters",
-                                token.span.start,
-                                token.span.len().max(1),
-                            ));
-                        }
-                        ObjectProperty::Getter(accessor_key, body)
-                    } else {
-                        if params.len() != 1 {
-                            let token = self.tokens[self.pos - 1].clone();
-                            return Err(SyntaxError::new(
-                                "setter must declare exactly one parameter",
-                                token.span.start,
-                                token.span.len().max(1),
-                            ));
-                        }
-                        ObjectProperty::Setter(accessor_key, params[0].clone(), body)
-                    };
+            if let Some(kind) = accessor {
+                self.expect(&TokenKind::LeftParen)?;
+                let params = self.parse_method_params()?;
+                self.expect(&TokenKind::RightParen)?;
+                let body = self.parse_function_block()?;
 
-                    properties.push(accessor);
-                    if !self.check(&TokenKind::RightBrace) {
-                        self.expect(&TokenKind::Comma)?;
+                let accessor = if kind == "get" {
+                    if !params.is_empty() {
+                        let token = self.tokens[self.pos - 1].clone();
+                        return Err(SyntaxError::new(
+                            "getter must not declare parameters",
+                            token.span.start,
+                            token.span.len().max(1),
+                        ));
+                    }
+                    ObjectProperty::Getter(key, body.into())
+                } else {
+                    if params.len() != 1 {
+                        let token = self.tokens[self.pos - 1].clone();
+                        return Err(SyntaxError::new(
+                            "setter must declare exactly one parameter",
+                            token.span.start,
+                            token.span.len().max(1),
+                        ));
                     }
-                    continue;
+                    ObjectProperty::Setter(key, params[0].clone(), body.into())
+                };
+
+                properties.push(accessor);
+                if !self.check(&TokenKind::RightBrace) {
+                    self.expect(&TokenKind::Comma)?;
                 }
+                continue;
+            }
 
-                if self.check(&TokenKind::Colon) {
-                    self.advance();
-                    (PropertyKey::Identifier(key_name), self.parse_expr(0)?)
-                } else if self.check(&TokenKind::LeftParen) {
-                    self.advance();
-                    let params = self.parse_method_params()?;
-                    self.expect(&TokenKind::RightParen)?;
-                    let body = self.parse_block()?;
-                    let params = params
-                        .into_iter()
-                        .map(|name| Param {
-                            pattern: Pattern::Identifier(name),
-                            default: None,
-                        })
-                        .collect();
-                    (
-                        PropertyKey::Identifier(key_name),
-                        Expr::ArrowFunction {
-                            params,
-                            body: ArrowBody::Block(body),
-                            is_async: false,
-                        },
-                    )
-                } else {
-                    if !was_identifier
-                        || key_name == "this"
-                        || self.is_disallowed_identifier_reference(&key_name)
+            let value = if is_async || is_generator || self.check(&TokenKind::LeftParen) {
+                let (params, body) = self.parse_function_params_and_body()?;
+                let body = body.with_source(self.source_since(start));
+                // Computed and numeric keys are only known at runtime;
+                // evaluation names those methods after the resolved key.
+                let name = match &key {
+                    PropertyKey::Identifier(name) => Some(name.clone()),
+                    PropertyKey::Computed(_) | PropertyKey::Private(_) => None,
+                };
+                Expr::FunctionExpr {
+                    name,
+                    params,
+                    body,
+                    is_async,
+                    is_generator,
+                }
+            } else if self.check(&TokenKind::Colon) {
+                self.advance();
+                self.parse_expr(0)?
+            } else {
+                match &key {
+                    PropertyKey::Identifier(name)
+                        if was_identifier
+                            && name != "this"
+                            && !self.is_disallowed_identifier_reference(name) =>
                     {
+                        Expr::Identifier(name.clone())
+                    }
+                    PropertyKey::Identifier(name) => {
+                        return Err(SyntaxError::new(
+                            format!("unexpected token '{name}'"),
+                            key_token.span.start,
+                            key_token.span.len().max(1),
+                        ));
+                    }
+                    PropertyKey::Computed(_) | PropertyKey::Private(_) => {
                         return Err(SyntaxError::new(
-                            format!("unexpected token '{}'", key_name),
+                            format!("unexpected token {:?}", key_token.kind),
                             key_token.span.start,
                             key_token.span.len().max(1),
                         ));
                     }
-                    (
-                        PropertyKey::Identifier(key_name.clone()),
-                        Expr::Identifier(key_name),
-                    )
                 }
             };
             properties.push(ObjectProperty::KeyValue(key, value));
@@ -225,7 +288,7 @@ impl Parser {
             parts.push(TemplatePart::Str(head));
         }
         loop {
-            let expr = self.parse_expr(0)?;
+            let expr = self.parse_expression()?;
             parts.push(TemplatePart::Expression(expr));
             let tok = self.advance().clone();
             match tok.kind {
@@ -252,14 +315,67 @@ impl Parser {
         Ok(Expr::TemplateLiteral { parts })
     }
 
-    fn parse_arrow_body(&mut self) -> Result<ArrowBody, SyntaxError> {
-        if self.check(&TokenKind::LeftBrace) {
-            Ok(ArrowBody::Block(self.parse_block()?))
+    /// Parses the body after an arrow function's `=>`, checking the
+    /// parameters listed from token `list_start` against it.
+    fn parse_arrow_body(
+        &mut self,
+        params: &mut [Param],
+        list_start: usize,
+    ) -> Result<FunctionBody, SyntaxError> {
+        let block = self.check(&TokenKind::LeftBrace);
+        let strict = self.strict_mode || (block && self.body_declares_strict());
+        self.check_params(params, strict, true, list_start)?;
+        let mut body = if block {
+            self.parse_function_block()?
         } else {
-            Ok(ArrowBody::Expr(Box::new(self.parse_expr(0)?)))
+            vec![Stmt::Return(Some(self.parse_expr(0)?))]
+        };
+        resolver::resolve_arrow(params, &mut body);
+        Ok(body.into())
+    }
+
+    /// Parses an object literal or class member name: an identifier or keyword,
+    /// a string or number literal, or a bracketed computed expression.
+    pub(super) fn parse_property_key(&mut self) -> Result<PropertyKey, SyntaxError> {
+        match self.peek().clone() {
+            TokenKind::LeftBracket => {
+                self.advance();
+                let key_expr = self.parse_expr(0)?;
+                self.expect(&TokenKind::RightBracket)?;
+                Ok(PropertyKey::Computed(key_expr))
+            }
+            TokenKind::String(name) => {
+                self.advance();
+                Ok(PropertyKey::Identifier(name))
+            }
+            // Numeric keys go through ToString at runtime so `1.0` and `1`
+            // name the same property.
+            TokenKind::Number(n) => {
+                self.advance();
+                Ok(PropertyKey::Computed(Expr::Literal(Literal::Number(n))))
+            }
+            _ => Ok(PropertyKey::Identifier(self.expect_property_name()?)),
         }
     }
 
+    /// Whether the token after the current one begins a property name,
+    /// which makes a leading `get`, `set` or `async` a modifier rather
+    /// than the name itself.
+    pub(super) fn next_starts_property_key(&self) -> bool {
+        !matches!(
+            self.tokens.get(self.pos + 1).map(|t| &t.kind),
+            None | Some(
+                TokenKind::Colon
+                    | TokenKind::LeftParen
+                    | TokenKind::Comma
+                    | TokenKind::RightBrace
+                    | TokenKind::Assign
+                    | TokenKind::Semicolon
+                    | TokenKind::Eof
+            )
+        )
+    }
+
     fn parse_method_params(&mut self) -> Result<Vec<String>, SyntaxError> {
         let mut params = Vec::new();
         if !self.check(&TokenKind::RightParen) {
@@ -276,6 +392,7 @@ impl Parser {
     }
 
     pub(crate) fn parse_async_expr(&mut self) -> Result<Expr, SyntaxError> {
+        let start = self.pos;
         self.advance(); // consume async
 
         if self.check(&TokenKind::Function) {
@@ -283,17 +400,20 @@ impl Parser {
         }
 
         if matches!(self.peek(), TokenKind::Ident(_)) {
+            let name_start = self.pos;
             let name = self.expect_ident()?;
             if self.check(&TokenKind::Arrow) {
                 self.advance();
-                let body = self.parse_arrow_body()?;
+                let mut params = vec![Param {
+                    pattern: Pattern::Identifier(name),
+                    default: None,
+                }];
+                let body = self.parse_arrow_body(&mut params, name_start)?;
                 return Ok(Expr::ArrowFunction {
-                    params: vec![Param {
-                        pattern: Pattern::Identifier(name),
-                        default: None,
-                    }],
+                    params,
                     body,
                     is_async: true,
+                    source: self.source_since(start),
                 });
             }
             return Err(SyntaxError::new(
@@ -307,14 +427,15 @@ impl Parser {
             self.advance();
             let after_lparen = self.pos;
             if self.scan_arrow_signature(after_lparen) {
-                let params = self.parse_params_list()?;
+                let mut params = self.parse_params_list()?;
                 self.expect(&TokenKind::RightParen)?;
                 self.expect(&TokenKind::Arrow)?;
-                let body = self.parse_arrow_body()?;
+                let body = self.parse_arrow_body(&mut params, after_lparen)?;
                 return Ok(Expr::ArrowFunction {
                     params,
                     body,
                     is_async: true,
+                    source: self.source_since(start),
                 });
             }
             return Err(SyntaxError::new(
@@ -332,6 +453,7 @@ impl Parser {
     }
 
     pub(crate) fn parse_function_expr(&mut self, is_async: bool) -> Result<Expr, SyntaxError> {
+        let start = if is_async { self.pos - 1 } else { self.pos };
         self.advance(); // consume 'function'
         let is_generator = self.check(&TokenKind::Star);
         if is_generator {
@@ -342,10 +464,8 @@ impl Parser {
         } else {
             None
         };
-        self.expect(&TokenKind::LeftParen)?;
-        let params = self.parse_params_list()?;
-        self.expect(&TokenKind::RightParen)?;
-        let body = self.parse_block()?;
+        let (params, body) = self.parse_function_params_and_body()?;
+        let body = body.with_source(self.source_since(start));
         Ok(Expr::FunctionExpr {
             name,
             params,
@@ -1,30 +1,67 @@
-use super::ast::{BinOp, LogicalOp};
+use super::ast::{AssignOp, BinOp, LogicalOp};
 use crate::lexer::token::TokenKind;
 
 pub(super) fn infix_binding_power(kind: &TokenKind) -> Option<(u8, u8)> {
     match kind {
         TokenKind::PipePipe | TokenKind::NullishCoalescing => Some((1, 2)),
         TokenKind::AmpAmp => Some((2, 3)),
-        TokenKind::Amp => Some((3, 4)),
-        TokenKind::EqEq | TokenKind::NotEq | TokenKind::EqEqEq | TokenKind::NotEqEq => Some((4, 5)),
+        TokenKind::Pipe => Some((3, 4)),
+        TokenKind::Caret => Some((4, 5)),
+        TokenKind::Amp => Some((5, 6)),
+        TokenKind::EqEq | TokenKind::NotEq | TokenKind::EqEqEq | TokenKind::NotEqEq => Some((6, 7)),
         TokenKind::Less | TokenKind::LessEq | TokenKind::Greater | TokenKind::GreaterEq => {
-            Some((6, 7))
+            Some((8, 9))
         }
-        TokenKind::Instanceof => Some((6, 7)),
-        TokenKind::In => Some((6, 7)),
-        TokenKind::Plus | TokenKind::Minus => Some((8, 9)),
-        TokenKind::Star | TokenKind::Slash | TokenKind::Percent => Some((10, 11)),
+        TokenKind::Instanceof => Some((8, 9)),
+        TokenKind::In => Some((8, 9)),
+        TokenKind::LessLess | TokenKind::GreaterGreater | TokenKind::GreaterGreaterGreater => {
+            Some((10, 11))
+        }
+        TokenKind::Plus | TokenKind::Minus => Some((12, 13)),
+        TokenKind::Star | TokenKind::Slash | TokenKind::Percent => Some((14, 15)),
+        // Right-associative, and looser than unary operands so that
+        // `-a ** b` reaches the infix loop and can be rejected there.
+        TokenKind::StarStar => Some((17, 16)),
         _ => None,
     }
 }
 
+/// Binding power for the operand of a unary operator.
+pub(super) const UNARY_BP: u8 = 18;
+
+/// Binding power for the operand of `new` and spread: tighter than every
+/// binary operator except `**`.
+pub(super) const OPERAND_BP: u8 = 16;
+
 pub(super) fn prefix_binding_power(kind: &TokenKind) -> Option<u8> {
     match kind {
-        TokenKind::Minus | TokenKind::Bang | TokenKind::Plus => Some(12),
+        TokenKind::Minus | TokenKind::Bang | TokenKind::Plus | TokenKind::Tilde => Some(UNARY_BP),
         _ => None,
     }
 }
 
+/// The operator of a compound assignment token such as `+=` or `??=`.
+pub(super) fn compound_assign_op(kind: &TokenKind) -> Option<AssignOp> {
+    Some(match kind {
+        TokenKind::PlusEquals => AssignOp::Add,
+        TokenKind::MinusEquals => AssignOp::Sub,
+        TokenKind::StarEquals => AssignOp::Mul,
+        TokenKind::SlashEquals => AssignOp::Div,
+        TokenKind::PercentEquals => AssignOp::Mod,
+        TokenKind::StarStarEquals => AssignOp::Exp,
+        TokenKind::AmpEquals => AssignOp::BitAnd,
+        TokenKind::PipeEquals => AssignOp::BitOr,
+        TokenKind::CaretEquals => AssignOp::BitXor,
+        TokenKind::LessLessEquals => AssignOp::Shl,
+        TokenKind::GreaterGreaterEquals => AssignOp::Shr,
+        TokenKind::GreaterGreaterGreaterEquals => AssignOp::UShr,
+        TokenKind::AmpAmpEquals => AssignOp::And,
+        TokenKind::PipePipeEquals => AssignOp::Or,
+        TokenKind::NullishEquals => AssignOp::Nullish,
+        _ => return None,
+    })
+}
+
 pub(super) fn token_to_logical_op(kind: &TokenKind) -> LogicalOp {
     match kind {
         TokenKind::AmpAmp => LogicalOp::And,
@@ -41,6 +78,7 @@ pub(super) fn token_to_binop(kind: &TokenKind) -> BinOp {
         TokenKind::Star => BinOp::Mul,
         TokenKind::Slash => BinOp::Div,
         TokenKind::Percent => BinOp::Mod,
+        TokenKind::StarStar => BinOp::Exp,
         TokenKind::EqEqEq => BinOp::EqEqEq,
         TokenKind::NotEqEq => BinOp::NotEqEq,
         TokenKind::EqEq => BinOp::EqEq,
@@ -50,6 +88,11 @@ pub(super) fn token_to_binop(kind: &TokenKind) -> BinOp {
         TokenKind::Greater => BinOp::Greater,
         TokenKind::GreaterEq => BinOp::GreaterEq,
         TokenKind::Amp => BinOp::BitAnd,
+        TokenKind::Pipe => BinOp::BitOr,
+        TokenKind::Caret => BinOp::BitXor,
+        TokenKind::LessLess => BinOp::Shl,
+        TokenKind::GreaterGreater => BinOp::Shr,
+        TokenKind::GreaterGreaterGreater => BinOp::UShr,
         TokenKind::Instanceof => BinOp::Instanceof,
         TokenKind::In => BinOp::In,
         _ => unreachable!("not a binary operator: {:?}", kind),
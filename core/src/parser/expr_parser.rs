@@ -1,8 +1,10 @@
-use super::ast::{Expr, Literal, OptionalOp, TemplatePart, UnaryOp, UpdateOp};
+use super::ast::{AssignOp, Expr, Literal, OptionalOp, TemplatePart, UnaryOp, UpdateOp};
 use super::expr_ops::{
-    infix_binding_power, prefix_binding_power, token_to_binop, token_to_logical_op,
+    OPERAND_BP, UNARY_BP, compound_assign_op, infix_binding_power, prefix_binding_power,
+    token_to_binop, token_to_logical_op,
 };
 use super::Parser;
+use super::function_body_parser::import_meta_outside_module;
 use crate::errors::SyntaxError;
 use crate::lexer::token::TokenKind;
 
@@ -21,6 +23,29 @@ impl Parser {
     }
 
     pub(crate) fn parse_expr(&mut self, min_bp: u8) -> Result<Expr, SyntaxError> {
+        self.nested(|parser| parser.parse_expr_unnested(min_bp))
+    }
+
+    fn parse_expr_unnested(&mut self, min_bp: u8) -> Result<Expr, SyntaxError> {
+        let mut unary_lhs = matches!(
+            self.peek(),
+            TokenKind::Minus
+                | TokenKind::Plus
+                | TokenKind::Bang
+                | TokenKind::Typeof
+                | TokenKind::Void
+                | TokenKind::Delete
+                | TokenKind::Await
+        );
+        if min_bp == 0 && self.starts_destructuring_assign() {
+            let pattern = self.parse_assignment_pattern()?;
+            self.expect(&TokenKind::Assign)?;
+            let value = self.parse_expr(0)?;
+            return Ok(Expr::DestructuringAssign {
+                pattern,
+                value: Box::new(value),
+            });
+        }
         let mut lhs = self.parse_prefix()?;
 
         loop {
@@ -34,6 +59,15 @@ impl Parser {
                         args,
                     }
                 }
+                TokenKind::Dot
+                    if matches!(
+                        self.tokens.get(self.pos + 1).map(|t| &t.kind),
+                        Some(TokenKind::PrivateName(_))
+                    ) =>
+                {
+                    self.advance();
+                    self.parse_private_member(lhs)?
+                }
                 TokenKind::Dot => {
                     self.advance();
                     let property = self.expect_property_name()?;
@@ -45,6 +79,17 @@ impl Parser {
                             object: Box::new(lhs),
                             property: Box::new(Expr::Literal(Literal::String(property))),
                             value: Box::new(value),
+                            strict: self.strict_mode,
+                        }
+                    } else if let Some(op) = self.member_compound_assign_op(&lhs) {
+                        self.advance();
+                        let value = self.parse_expr(0)?;
+                        Expr::MemberCompoundAssign {
+                            object: Box::new(lhs),
+                            property: Box::new(Expr::Literal(Literal::String(property))),
+                            op,
+                            value: Box::new(value),
+                            strict: self.strict_mode,
                         }
                     } else {
                         Expr::MemberAccess {
@@ -64,7 +109,7 @@ impl Parser {
                         }
                         TokenKind::LeftBracket => {
                             self.advance();
-                            let prop_expr = self.parse_expr(0)?;
+                            let prop_expr = self.parse_expression()?;
                             self.expect(&TokenKind::RightBracket)?;
                             self.push_optional_op(lhs, OptionalOp::ComputedAccess(prop_expr))
                         }
@@ -76,7 +121,7 @@ impl Parser {
                 }
                 TokenKind::LeftBracket => {
                     self.advance();
-                    let prop_expr = self.parse_expr(0)?;
+                    let prop_expr = self.parse_expression()?;
                     self.expect(&TokenKind::RightBracket)?;
                     if self.check(&TokenKind::Assign) && !matches!(lhs, Expr::OptionalChain { .. })
                     {
@@ -86,6 +131,17 @@ impl Parser {
                             object: Box::new(lhs),
                             property: Box::new(prop_expr),
                             value: Box::new(value),
+                            strict: self.strict_mode,
+                        }
+                    } else if let Some(op) = self.member_compound_assign_op(&lhs) {
+                        self.advance();
+                        let value = self.parse_expr(0)?;
+                        Expr::MemberCompoundAssign {
+                            object: Box::new(lhs),
+                            property: Box::new(prop_expr),
+                            op,
+                            value: Box::new(value),
+                            strict: self.strict_mode,
                         }
                     } else {
                         Expr::ComputedMemberAccess {
@@ -100,11 +156,15 @@ impl Parser {
                     }
                     self.advance();
                     match lhs {
-                        Expr::Identifier(name) => Expr::UpdateExpr {
-                            name,
-                            op: UpdateOp::Inc,
-                            prefix: false,
-                        },
+                        Expr::Identifier(name) => {
+                            self.check_strict_target(&name, &self.tokens[self.pos - 2])?;
+                            Expr::UpdateExpr {
+                                name,
+                                op: UpdateOp::Inc,
+                                prefix: false,
+                                slot: None,
+                            }
+                        }
                         _ => {
                             return Err(SyntaxError::new(
                                 "invalid postfix increment target",
@@ -120,11 +180,15 @@ impl Parser {
                     }
                     self.advance();
                     match lhs {
-                        Expr::Identifier(name) => Expr::UpdateExpr {
-                            name,
-                            op: UpdateOp::Dec,
-                            prefix: false,
-                        },
+                        Expr::Identifier(name) => {
+                            self.check_strict_target(&name, &self.tokens[self.pos - 2])?;
+                            Expr::UpdateExpr {
+                                name,
+                                op: UpdateOp::Dec,
+                                prefix: false,
+                                slot: None,
+                            }
+                        }
                         _ => {
                             return Err(SyntaxError::new(
                                 "invalid postfix decrement target",
@@ -136,19 +200,21 @@ impl Parser {
                 }
                 TokenKind::NoSubTemplate(s) => {
                     let s = s.clone();
-                    self.advance();
+                    let raw = self.advance().raw.clone().unwrap_or_default();
                     Expr::TaggedTemplate {
                         tag: Box::new(lhs),
                         parts: vec![TemplatePart::Str(s)],
+                        raw: vec![raw],
                     }
                 }
                 TokenKind::TemplateHead(s) => {
                     let head = s.clone();
-                    self.advance();
-                    let parts = self.parse_tagged_template_parts(head)?;
+                    let head_raw = self.advance().raw.clone().unwrap_or_default();
+                    let (parts, raw) = self.parse_tagged_template_parts(head, head_raw)?;
                     Expr::TaggedTemplate {
                         tag: Box::new(lhs),
                         parts,
+                        raw,
                     }
                 }
                 _ => break,
@@ -160,6 +226,16 @@ impl Parser {
                 break;
             }
 
+            if unary_lhs && self.check(&TokenKind::StarStar) {
+                let span = self.tokens[self.pos].span;
+                return Err(SyntaxError::new(
+                    "unparenthesized unary expression can't appear on the left-hand side of '**'",
+                    span.start,
+                    span.len(),
+                ));
+            }
+            unary_lhs = false;
+
             let op_token = self.advance().kind.clone();
             let rhs = self.parse_expr(r_bp)?;
 
@@ -197,13 +273,13 @@ impl Parser {
     fn parse_prefix(&mut self) -> Result<Expr, SyntaxError> {
         if self.check(&TokenKind::Typeof) {
             self.advance();
-            let operand = self.parse_expr(12)?;
+            let operand = self.parse_expr(UNARY_BP)?;
             return Ok(Expr::Typeof(Box::new(operand)));
         }
 
         if self.check(&TokenKind::Void) {
             self.advance();
-            let operand = self.parse_expr(12)?;
+            let operand = self.parse_expr(UNARY_BP)?;
             return Ok(Expr::Unary {
                 op: UnaryOp::Void,
                 operand: Box::new(operand),
@@ -211,14 +287,31 @@ impl Parser {
         }
 
         if self.check(&TokenKind::Delete) {
-            self.advance();
-            let operand = self.parse_expr(12)?;
-            return Ok(Expr::Delete(Box::new(operand)));
+            let delete_tok = self.advance().clone();
+            let operand = self.parse_expr(UNARY_BP)?;
+            if self.strict_mode && matches!(operand, Expr::Identifier(_)) {
+                return Err(SyntaxError::new(
+                    "Delete of an unqualified identifier in strict mode.",
+                    delete_tok.span.start,
+                    delete_tok.span.len(),
+                ));
+            }
+            if matches!(operand, Expr::PrivateMember { .. }) {
+                return Err(SyntaxError::new(
+                    "Private fields can not be deleted",
+                    delete_tok.span.start,
+                    delete_tok.span.len(),
+                ));
+            }
+            return Ok(Expr::Delete {
+                operand: Box::new(operand),
+                strict: self.strict_mode,
+            });
         }
 
         if self.check(&TokenKind::Await) {
             self.advance();
-            let operand = self.parse_expr(12)?;
+            let operand = self.parse_expr(UNARY_BP)?;
             return Ok(Expr::Await(Box::new(operand)));
         }
 
@@ -246,7 +339,7 @@ impl Parser {
 
         if self.check(&TokenKind::DotDotDot) {
             self.advance();
-            let inner = self.parse_expr(12)?;
+            let inner = self.parse_expr(OPERAND_BP)?;
             return Ok(Expr::Spread(Box::new(inner)));
         }
 
@@ -261,8 +354,8 @@ impl Parser {
         if matches!(self.peek(), TokenKind::PlusPlus | TokenKind::MinusMinus) {
             let op_tok = self.advance().clone();
             let ident_tok = self.advance().clone();
-            let name = match ident_tok.kind {
-                TokenKind::Ident(name) => name,
+            let name = match &ident_tok.kind {
+                TokenKind::Ident(name) => name.clone(),
                 _ => {
                     return Err(SyntaxError::new(
                         "expected identifier after update operator",
@@ -271,6 +364,7 @@ impl Parser {
                     ));
                 }
             };
+            self.check_strict_target(&name, &ident_tok)?;
             let op = match op_tok.kind {
                 TokenKind::PlusPlus => UpdateOp::Inc,
                 TokenKind::MinusMinus => UpdateOp::Dec,
@@ -280,6 +374,7 @@ impl Parser {
                 name,
                 op,
                 prefix: true,
+                slot: None,
             });
         }
 
@@ -289,6 +384,7 @@ impl Parser {
                 TokenKind::Minus => UnaryOp::Neg,
                 TokenKind::Bang => UnaryOp::Not,
                 TokenKind::Plus => UnaryOp::Pos,
+                TokenKind::Tilde => UnaryOp::BitNot,
                 _ => unreachable!(),
             };
             let operand = self.parse_expr(rbp)?;
@@ -301,9 +397,23 @@ impl Parser {
         self.parse_primary()
     }
 
-    fn parse_primary(&mut self) -> Result<Expr, SyntaxError> {
+    pub(crate) fn parse_primary(&mut self) -> Result<Expr, SyntaxError> {
         let token = self.advance().clone();
         match token.kind {
+            TokenKind::Number(_) if token.legacy_octal && self.strict_mode => {
+                Err(SyntaxError::new(
+                    "legacy octal and leading-zero literals are not allowed in strict mode",
+                    token.span.start,
+                    token.span.len(),
+                ))
+            }
+            TokenKind::String(_) if token.legacy_octal && self.strict_mode => {
+                Err(SyntaxError::new(
+                    "octal escape sequences are not allowed in strict mode",
+                    token.span.start,
+                    token.span.len(),
+                ))
+            }
             TokenKind::Number(n) => Ok(Expr::Literal(Literal::Number(n))),
             TokenKind::String(ref s) => Ok(Expr::Literal(Literal::String(s.clone()))),
             TokenKind::True => Ok(Expr::Literal(Literal::Boolean(true))),
@@ -314,6 +424,14 @@ impl Parser {
                 let name = name.clone();
                 self.parse_ident_or_arrow(name)
             }
+            // `#name in object`, the only place a private name stands alone.
+            TokenKind::PrivateName(ref name) if self.check(&TokenKind::In) => {
+                self.use_private_name(name.clone(), token.span.start)?;
+                Ok(Expr::PrivateName(name.clone()))
+            }
+            TokenKind::Import if self.check(&TokenKind::Dot) => {
+                self.parse_import_meta(token.span.start)
+            }
             TokenKind::LeftParen => self.parse_paren_or_arrow(),
             TokenKind::LeftBrace => self.parse_object_literal(),
             TokenKind::LeftBracket => self.parse_array_literal(),
@@ -360,6 +478,68 @@ impl Parser {
         Ok(args)
     }
 
+    /// Parses the `.meta` after `import`, which only module code may use.
+    /// A deferred body was checked for it when it was skipped.
+    fn parse_import_meta(&mut self, start: usize) -> Result<Expr, SyntaxError> {
+        self.advance(); // consume '.'
+        let token = self.advance().clone();
+        if !matches!(&token.kind, TokenKind::Ident(name) if name == "meta") {
+            return Err(SyntaxError::new(
+                format!("expected 'meta' after 'import.', found {:?}", token.kind),
+                token.span.start,
+                token.span.len().max(1),
+            ));
+        }
+        if !self.module && !self.deferred {
+            return Err(import_meta_outside_module(start));
+        }
+        Ok(Expr::ImportMeta)
+    }
+
+    /// Parses the `#name` after `object.`, along with an assignment to
+    /// the private member when one follows.
+    pub(crate) fn parse_private_member(&mut self, object: Expr) -> Result<Expr, SyntaxError> {
+        let token = self.advance().clone();
+        let TokenKind::PrivateName(name) = token.kind else {
+            unreachable!("parse_private_member is only called before a private name");
+        };
+        self.use_private_name(name.clone(), token.span.start)?;
+        if self.check(&TokenKind::Assign) && !matches!(object, Expr::OptionalChain { .. }) {
+            self.advance();
+            let value = self.parse_expr(0)?;
+            Ok(Expr::MemberAssign {
+                object: Box::new(object),
+                property: Box::new(Expr::PrivateName(name)),
+                value: Box::new(value),
+                strict: self.strict_mode,
+            })
+        } else if let Some(op) = self.member_compound_assign_op(&object) {
+            self.advance();
+            let value = self.parse_expr(0)?;
+            Ok(Expr::MemberCompoundAssign {
+                object: Box::new(object),
+                property: Box::new(Expr::PrivateName(name)),
+                op,
+                value: Box::new(value),
+                strict: self.strict_mode,
+            })
+        } else {
+            Ok(Expr::PrivateMember {
+                object: Box::new(object),
+                name,
+            })
+        }
+    }
+
+    /// The compound assignment operator after a member target, if any. An
+    /// optional chain is never a valid assignment target.
+    fn member_compound_assign_op(&self, object: &Expr) -> Option<AssignOp> {
+        if matches!(object, Expr::OptionalChain { .. }) {
+            return None;
+        }
+        compound_assign_op(self.peek())
+    }
+
     fn push_optional_op(&self, lhs: Expr, op: OptionalOp) -> Expr {
         match lhs {
             Expr::OptionalChain { base, mut chain } => {
@@ -373,23 +553,29 @@ impl Parser {
         }
     }
 
+    /// The parts of a tagged template after its head, and the raw text of
+    /// each of its strings.
     fn parse_tagged_template_parts(
         &mut self,
         head: String,
-    ) -> Result<Vec<TemplatePart>, SyntaxError> {
+        head_raw: String,
+    ) -> Result<(Vec<TemplatePart>, Vec<String>), SyntaxError> {
         let mut parts = Vec::new();
+        let mut raw = vec![head_raw];
         parts.push(TemplatePart::Str(head));
         loop {
-            let expr = self.parse_expr(0)?;
+            let expr = self.parse_expression()?;
             parts.push(TemplatePart::Expression(expr));
             let tok = self.advance().clone();
             match tok.kind {
                 TokenKind::TemplateTail(ref s) => {
                     parts.push(TemplatePart::Str(s.clone()));
+                    raw.push(tok.raw.unwrap_or_default());
                     break;
                 }
                 TokenKind::TemplateMiddle(ref s) => {
                     parts.push(TemplatePart::Str(s.clone()));
+                    raw.push(tok.raw.unwrap_or_default());
                 }
                 _ => {
                     return Err(SyntaxError::new(
@@ -400,6 +586,6 @@ impl Parser {
                 }
             }
         }
-        Ok(parts)
+        Ok((parts, raw))
     }
 }
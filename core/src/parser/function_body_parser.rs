@@ -0,0 +1,151 @@
+use std::rc::Rc;
+
+use super::Parser;
+use super::resolver::{self, ParamScope};
+use super::ast::{FunctionBody, Param, Stmt};
+use crate::errors::SyntaxError;
+use crate::lexer::token::{Token, TokenKind};
+
+impl Parser {
+    /// Parses the `(params) { ... }` of a function declaration, function
+    /// expression or method. With lazy functions on, the body is only
+    /// checked for balanced brackets and its statements are built on
+    /// first use.
+    pub(crate) fn parse_function_params_and_body(
+        &mut self,
+    ) -> Result<(Vec<Param>, FunctionBody), SyntaxError> {
+        let list_start = self.pos;
+        self.expect(&TokenKind::LeftParen)?;
+        let mut params = self.parse_params_list()?;
+        self.expect(&TokenKind::RightParen)?;
+        let strict = self.strict_mode || self.body_declares_strict();
+        self.check_params(&params, strict, false, list_start)?;
+        resolver::resolve_params(&mut params);
+        let scope = ParamScope::new(&params);
+        if !self.lazy_functions {
+            let mut body = self.parse_function_block()?;
+            resolver::resolve_function(&scope, &mut body);
+            return Ok((params, FunctionBody::from(body)));
+        }
+        let start = self.pos;
+        self.skip_balanced_body()?;
+        let body = FunctionBody::deferred(
+            Rc::clone(&self.tokens),
+            start,
+            strict,
+            self.source.clone(),
+            scope,
+        );
+        Ok((params, body))
+    }
+
+    /// Parses the `{ ... }` body of a function, as strict mode code when
+    /// it starts with a `"use strict"` directive.
+    pub(crate) fn parse_function_block(&mut self) -> Result<Vec<Stmt>, SyntaxError> {
+        let outer = self.strict_mode;
+        self.strict_mode = outer || self.body_declares_strict();
+        let body = self.parse_block();
+        self.strict_mode = outer;
+        body
+    }
+
+    /// Steps past a brace-delimited body. Strings, templates and regular
+    /// expressions are single tokens, so matching bracket tokens is
+    /// enough to find where the body ends. Any `import.meta` in a script
+    /// is reported now, and so are the private names it uses, unless a
+    /// class inside it may declare them.
+    fn skip_balanced_body(&mut self) -> Result<(), SyntaxError> {
+        let start = self.pos;
+        self.skip_balanced_brackets()?;
+        let body = &self.tokens[start..self.pos];
+        if !self.module && !self.deferred {
+            let import_meta = body
+                .windows(2)
+                .find(|pair| pair[0].kind == TokenKind::Import && pair[1].kind == TokenKind::Dot);
+            if let Some(pair) = import_meta {
+                return Err(import_meta_outside_module(pair[0].span.start));
+            }
+        }
+        if body.iter().any(|token| token.kind == TokenKind::Class) {
+            return Ok(());
+        }
+        let used: Vec<_> = body
+            .iter()
+            .filter_map(|token| match &token.kind {
+                TokenKind::PrivateName(name) => Some((name.clone(), token.span.start)),
+                _ => None,
+            })
+            .collect();
+        for (name, offset) in used {
+            self.use_private_name(name, offset)?;
+        }
+        Ok(())
+    }
+
+    fn skip_balanced_brackets(&mut self) -> Result<(), SyntaxError> {
+        self.expect(&TokenKind::LeftBrace)?;
+        let mut closers = vec![TokenKind::RightBrace];
+        while let Some(expected) = closers.last() {
+            let token = &self.tokens[self.pos];
+            match &token.kind {
+                TokenKind::LeftBrace => closers.push(TokenKind::RightBrace),
+                TokenKind::LeftParen => closers.push(TokenKind::RightParen),
+                TokenKind::LeftBracket => closers.push(TokenKind::RightBracket),
+                TokenKind::RightBrace | TokenKind::RightParen | TokenKind::RightBracket
+                    if token.kind == *expected =>
+                {
+                    closers.pop();
+                }
+                TokenKind::RightBrace
+                | TokenKind::RightParen
+                | TokenKind::RightBracket
+                | TokenKind::Eof => {
+                    return Err(SyntaxError::new(
+                        format!("expected {:?}, found {:?}", expected, token.kind),
+                        token.span.start,
+                        token.span.len().max(1),
+                    ));
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn import_meta_outside_module(offset: usize) -> SyntaxError {
+    SyntaxError::new(
+        "Cannot use 'import.meta' outside a module",
+        offset,
+        "import".len(),
+    )
+}
+
+/// Builds the statements of a body `parse_function_body` skipped, in the
+/// strictness it was written in, and resolves them against the function's
+/// parameters. Functions nested inside it are skipped in turn.
+pub(crate) fn parse_deferred_body(
+    tokens: Rc<Vec<Token>>,
+    start: usize,
+    strict: bool,
+    source: Option<Rc<str>>,
+    params: &ParamScope,
+) -> Result<Vec<Stmt>, SyntaxError> {
+    let mut parser = Parser {
+        tokens,
+        pos: start,
+        strict_mode: strict,
+        lazy_functions: true,
+        recovering: false,
+        errors: Vec::new(),
+        source,
+        private_scopes: Vec::new(),
+        deferred: true,
+        module: false,
+        nesting: 0,
+    };
+    let mut body = parser.parse_block()?;
+    resolver::resolve_function(params, &mut body);
+    Ok(body)
+}
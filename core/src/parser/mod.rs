@@ -3,44 +3,163 @@ mod class_parser;
 mod expr_literals;
 mod expr_ops;
 mod expr_parser;
+mod function_body_parser;
 mod module_parser;
 mod pattern_parser;
+mod recovery;
+mod resolver;
 mod stmt_parser;
 mod stmt_parser_loops;
 mod stmt_terminator;
+mod strict_mode;
 mod switch_parser;
 
+use std::rc::Rc;
+
 use crate::errors::SyntaxError;
 use crate::lexer::token::{Token, TokenKind};
-use ast::{Expr, Literal, Program, Stmt};
+use ast::{Expr, Literal, Program, SourceText, Stmt};
+use class_parser::PrivateScope;
+pub(crate) use function_body_parser::parse_deferred_body;
+pub use recovery::parse_recovering;
 
 /// Parse a token stream into a Program AST.
+///
+/// Function bodies are only brace-matched here and parsed on first use,
+/// so a syntax error inside one surfaces when the function is first
+/// called. [`parse_eager`] reports every error up front.
 pub fn parse(tokens: Vec<Token>) -> Result<Program, SyntaxError> {
     let mut parser = Parser::new(tokens);
     parser.parse_program()
 }
 
+/// Parse a token stream lexed from `source`, keeping the text of every
+/// function so that `Function.prototype.toString` can return it.
+pub fn parse_with_source(tokens: Vec<Token>, source: &str) -> Result<Program, SyntaxError> {
+    parse_source_tokens(tokens, source, false, true, false)
+}
+
+/// [`parse_with_source`] for module code, where `import.meta` may appear.
+pub fn parse_module(tokens: Vec<Token>, source: &str) -> Result<Program, SyntaxError> {
+    parse_source_tokens(tokens, source, false, true, true)
+}
+
+/// [`parse_with_source`] with the strictness and laziness an interpreter
+/// is configured with, as a module or as a script.
+pub(crate) fn parse_source_tokens(
+    tokens: Vec<Token>,
+    source: &str,
+    strict: bool,
+    lazy_functions: bool,
+    module: bool,
+) -> Result<Program, SyntaxError> {
+    let mut parser = Parser::new(tokens);
+    parser.source = Some(source.into());
+    parser.strict_mode = strict;
+    parser.lazy_functions = lazy_functions;
+    parser.module = module;
+    parser.parse_program()
+}
+
+/// Parse a token stream, building every function body now rather than
+/// on first use so that every syntax error is reported here. `strict`
+/// parses it as [`parse_strict`] does.
+pub fn parse_eager(tokens: Vec<Token>, strict: bool) -> Result<Program, SyntaxError> {
+    let mut parser = Parser::new(tokens);
+    parser.strict_mode = strict;
+    parser.lazy_functions = false;
+    parser.parse_program()
+}
+
+/// Parse a token stream as strict mode code, as if it began with a
+/// `"use strict"` directive.
+pub fn parse_strict(tokens: Vec<Token>) -> Result<Program, SyntaxError> {
+    let mut parser = Parser::new(tokens);
+    parser.strict_mode = true;
+    parser.parse_program()
+}
+
+/// How deeply expressions and statements may nest. Deeper source is a
+/// syntax error rather than a stack overflow, which would abort the
+/// process, even in a debug build on a thread with a small stack.
+const MAX_NESTING: usize = 1000;
+
 /// Recursive-descent parser over a token stream.
 pub(crate) struct Parser {
-    tokens: Vec<Token>,
+    /// Shared with the function bodies skipped so far.
+    tokens: Rc<Vec<Token>>,
     pos: usize,
     strict_mode: bool,
+    /// Skip function bodies, leaving them to be parsed on first use.
+    lazy_functions: bool,
+    /// Record a statement's syntax error in `errors` and carry on with
+    /// the next statement instead of stopping.
+    recovering: bool,
+    errors: Vec<SyntaxError>,
+    /// The text the tokens were lexed from, when the caller has it.
+    source: Option<Rc<str>>,
+    /// One entry per class body being parsed, innermost last.
+    private_scopes: Vec<PrivateScope>,
+    /// Parsing a body skipped earlier, whose private names were checked
+    /// against its enclosing classes when it was skipped.
+    deferred: bool,
+    /// Parsing module code rather than a script.
+    module: bool,
+    /// Expressions and statements being parsed, each inside the last.
+    nesting: usize,
 }
 
 impl Parser {
     fn new(tokens: Vec<Token>) -> Self {
         Self {
-            tokens,
+            tokens: Rc::new(tokens),
             pos: 0,
             strict_mode: false,
+            lazy_functions: true,
+            recovering: false,
+            errors: Vec::new(),
+            source: None,
+            private_scopes: Vec::new(),
+            deferred: false,
+            module: false,
+            nesting: 0,
+        }
+    }
+
+    /// Runs `parse` one level deeper, failing once the source nests
+    /// deeper than [`MAX_NESTING`] instead of overflowing the stack.
+    pub(crate) fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, SyntaxError>,
+    ) -> Result<T, SyntaxError> {
+        if self.nesting >= MAX_NESTING {
+            let token = &self.tokens[self.pos];
+            return Err(SyntaxError::new(
+                "expressions or statements are nested too deeply",
+                token.span.start,
+                token.span.len().max(1),
+            ));
         }
+        self.nesting += 1;
+        let result = parse(self);
+        self.nesting -= 1;
+        result
     }
 
     fn parse_program(&mut self) -> Result<Program, SyntaxError> {
         let mut body = Vec::new();
+        let mut offsets = Vec::new();
         let mut in_directive_prologue = true;
         while !self.is_at_end() {
-            let stmt = self.parse_statement()?;
+            let start = self.pos;
+            let stmt = match self.parse_statement() {
+                Ok(stmt) => stmt,
+                Err(err) => {
+                    self.recover(err, start)?;
+                    continue;
+                }
+            };
+            offsets.push(self.tokens[start].span.start);
             if in_directive_prologue {
                 if let Stmt::ExprStmt(Expr::Literal(Literal::String(s))) = &stmt {
                     if s == "use strict" {
@@ -52,7 +171,8 @@ impl Parser {
             }
             body.push(stmt);
         }
-        Ok(Program { body })
+        resolver::resolve_program(&mut body);
+        Ok(Program { body, offsets })
     }
 
     pub(crate) fn peek(&self) -> &TokenKind {
@@ -80,6 +200,14 @@ impl Parser {
         }
     }
 
+    /// The source text from the token at index `start` through the last
+    /// token consumed.
+    pub(crate) fn source_since(&self, start: usize) -> Option<SourceText> {
+        let text = self.source.as_ref()?;
+        let range = self.tokens[start].span.start..self.tokens[self.pos - 1].span.end;
+        Some(SourceText::new(Rc::clone(text), range))
+    }
+
     pub(crate) fn is_at_end(&self) -> bool {
         matches!(self.peek(), TokenKind::Eof)
     }
@@ -50,8 +50,60 @@ impl Parser {
                 ));
             }
         };
+        let attributes = if matches!(self.peek(), TokenKind::Ident(name) if name == "with") {
+            self.advance();
+            self.parse_import_attributes()?
+        } else {
+            Vec::new()
+        };
         self.consume_stmt_terminator()?;
-        Ok(Stmt::Import(ImportDecl { specifiers, source }))
+        Ok(Stmt::Import(ImportDecl {
+            specifiers,
+            source,
+            attributes,
+        }))
+    }
+
+    /// `{ type: "json" }` after `with`. Keys are identifiers or strings
+    /// and each appears once; values are strings.
+    fn parse_import_attributes(&mut self) -> Result<Vec<(String, String)>, SyntaxError> {
+        self.expect(&TokenKind::LeftBrace)?;
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        while !self.check(&TokenKind::RightBrace) && !self.is_at_end() {
+            let token = self.advance().clone();
+            let key = match token.kind {
+                TokenKind::Ident(name) | TokenKind::String(name) => name,
+                other => {
+                    return Err(SyntaxError::new(
+                        format!("expected import attribute key, found {other:?}"),
+                        token.span.start,
+                        token.span.len().max(1),
+                    ));
+                }
+            };
+            if attributes.iter().any(|(existing, _)| *existing == key) {
+                return Err(SyntaxError::new(
+                    format!("duplicate import attribute '{key}'"),
+                    token.span.start,
+                    token.span.len().max(1),
+                ));
+            }
+            self.expect(&TokenKind::Colon)?;
+            let token = self.advance().clone();
+            let TokenKind::String(value) = token.kind else {
+                return Err(SyntaxError::new(
+                    format!("import attribute '{key}' must be a string"),
+                    token.span.start,
+                    token.span.len().max(1),
+                ));
+            };
+            attributes.push((key, value));
+            if !self.check(&TokenKind::RightBrace) {
+                self.expect(&TokenKind::Comma)?;
+            }
+        }
+        self.expect(&TokenKind::RightBrace)?;
+        Ok(attributes)
     }
 
     pub(crate) fn parse_export_decl(&mut self) -> Result<Stmt, SyntaxError> {
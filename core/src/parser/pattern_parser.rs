@@ -1,7 +1,7 @@
-use super::ast::{ObjectPatternProp, Param, Pattern};
+use super::ast::{Expr, ObjectPatternProp, Param, Pattern};
 use super::Parser;
 use crate::errors::SyntaxError;
-use crate::lexer::token::TokenKind;
+use crate::lexer::token::{Token, TokenKind};
 
 impl Parser {
     pub(crate) fn parse_params_list(&mut self) -> Result<Vec<Param>, SyntaxError> {
@@ -11,6 +11,18 @@ impl Parser {
         }
 
         loop {
+            if self.check(&TokenKind::DotDotDot) {
+                self.advance();
+                let pattern = Pattern::Rest(Box::new(self.parse_pattern()?));
+                params.push(Param {
+                    pattern,
+                    default: None,
+                });
+                // A rest parameter must come last, so the caller's `)`
+                // check rejects anything after it.
+                break;
+            }
+
             let pattern = self.parse_pattern()?;
             let default = if self.check(&TokenKind::Assign) {
                 self.advance();
@@ -58,7 +70,7 @@ impl Parser {
 
     pub(crate) fn parse_pattern(&mut self) -> Result<Pattern, SyntaxError> {
         match self.peek() {
-            TokenKind::Ident(_) => Ok(Pattern::Identifier(self.expect_ident()?)),
+            TokenKind::Ident(_) => Ok(Pattern::Identifier(self.expect_binding_ident()?)),
             TokenKind::LeftBrace => self.parse_object_pattern(),
             TokenKind::LeftBracket => self.parse_array_pattern(),
             _ => {
@@ -79,19 +91,32 @@ impl Parser {
         while !self.check(&TokenKind::RightBrace) && !self.is_at_end() {
             if self.check(&TokenKind::DotDotDot) {
                 self.advance();
-                let rest = Pattern::Rest(Box::new(self.parse_pattern()?));
+                let rest =
+                    Pattern::Rest(Box::new(Pattern::Identifier(self.expect_binding_ident()?)));
                 properties.push(ObjectPatternProp {
                     key: String::new(),
+                    computed: None,
                     alias: Some(rest),
                     default: None,
                     is_rest: true,
                 });
+                self.expect_rest_last()?;
+            } else if self.check(&TokenKind::LeftBracket) {
+                let (computed, alias, default) = self.parse_computed_pattern_prop(false)?;
+                properties.push(ObjectPatternProp {
+                    key: String::new(),
+                    computed: Some(computed),
+                    alias: Some(alias),
+                    default,
+                    is_rest: false,
+                });
             } else {
                 let key = self.expect_ident()?;
                 let alias = if self.check(&TokenKind::Colon) {
                     self.advance();
                     Some(self.parse_pattern()?)
                 } else {
+                    self.check_strict_target(&key, &self.tokens[self.pos - 1])?;
                     None
                 };
 
@@ -104,6 +129,7 @@ impl Parser {
 
                 properties.push(ObjectPatternProp {
                     key,
+                    computed: None,
                     alias,
                     default,
                     is_rest: false,
@@ -134,7 +160,16 @@ impl Parser {
                 self.advance();
                 Pattern::Rest(Box::new(self.parse_pattern()?))
             } else {
-                self.parse_pattern()?
+                let target = self.parse_pattern()?;
+                if self.check(&TokenKind::Assign) {
+                    self.advance();
+                    Pattern::Default {
+                        target: Box::new(target),
+                        value: Box::new(self.parse_expr(0)?),
+                    }
+                } else {
+                    target
+                }
             };
             elements.push(Some(element));
 
@@ -146,4 +181,259 @@ impl Parser {
         self.expect(&TokenKind::RightBracket)?;
         Ok(Pattern::ArrayPattern { elements })
     }
+
+    /// An object rest element must close its pattern, without even a
+    /// trailing comma after it.
+    fn expect_rest_last(&self) -> Result<(), SyntaxError> {
+        if self.check(&TokenKind::RightBrace) {
+            return Ok(());
+        }
+        let token = &self.tokens[self.pos];
+        Err(SyntaxError::new(
+            "Rest element must be last element",
+            token.span.start,
+            token.span.len().max(1),
+        ))
+    }
+
+    /// Whether the `[` or `{` at the cursor opens a literal that is
+    /// really the target of a destructuring assignment: its matching
+    /// bracket is followed by `=`.
+    pub(crate) fn starts_destructuring_assign(&self) -> bool {
+        matches!(self.peek(), TokenKind::LeftBracket | TokenKind::LeftBrace)
+            && self
+                .token_after_brackets(self.pos)
+                .is_some_and(|kind| matches!(kind, TokenKind::Assign))
+    }
+
+    /// The token after the bracket that closes the one at `start`.
+    fn token_after_brackets(&self, start: usize) -> Option<&TokenKind> {
+        let mut depth = 0usize;
+        for (i, token) in self.tokens.iter().enumerate().skip(start) {
+            match token.kind {
+                TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftBrace => depth += 1,
+                TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.tokens.get(i + 1).map(|t| &t.kind);
+                    }
+                }
+                TokenKind::Eof => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Parses an array or object literal as an assignment pattern. Unlike
+    /// binding patterns, its targets may be property references.
+    pub(crate) fn parse_assignment_pattern(&mut self) -> Result<Pattern, SyntaxError> {
+        if self.check(&TokenKind::LeftBrace) {
+            self.parse_object_assignment_pattern()
+        } else {
+            self.parse_array_assignment_pattern()
+        }
+    }
+
+    fn parse_array_assignment_pattern(&mut self) -> Result<Pattern, SyntaxError> {
+        self.expect(&TokenKind::LeftBracket)?;
+        let mut elements = Vec::new();
+        while !self.check(&TokenKind::RightBracket) && !self.is_at_end() {
+            if self.check(&TokenKind::Comma) {
+                self.advance();
+                elements.push(None);
+                continue;
+            }
+            let element = if self.check(&TokenKind::DotDotDot) {
+                self.advance();
+                Pattern::Rest(Box::new(self.parse_assignment_target()?))
+            } else {
+                self.parse_assignment_element()?
+            };
+            elements.push(Some(element));
+            if !self.check(&TokenKind::RightBracket) {
+                self.expect(&TokenKind::Comma)?;
+            }
+        }
+        self.expect(&TokenKind::RightBracket)?;
+        Ok(Pattern::ArrayPattern { elements })
+    }
+
+    fn parse_object_assignment_pattern(&mut self) -> Result<Pattern, SyntaxError> {
+        self.expect(&TokenKind::LeftBrace)?;
+        let mut properties = Vec::new();
+        while !self.check(&TokenKind::RightBrace) && !self.is_at_end() {
+            if self.check(&TokenKind::DotDotDot) {
+                self.advance();
+                let rest = Pattern::Rest(Box::new(self.parse_assignment_target()?));
+                properties.push(ObjectPatternProp {
+                    key: String::new(),
+                    computed: None,
+                    alias: Some(rest),
+                    default: None,
+                    is_rest: true,
+                });
+                self.expect_rest_last()?;
+            } else if self.check(&TokenKind::LeftBracket) {
+                let (computed, alias, default) = self.parse_computed_pattern_prop(true)?;
+                properties.push(ObjectPatternProp {
+                    key: String::new(),
+                    computed: Some(computed),
+                    alias: Some(alias),
+                    default,
+                    is_rest: false,
+                });
+            } else {
+                let key_token = self.tokens[self.pos].clone();
+                let key = match key_token.kind {
+                    TokenKind::String(ref s) => {
+                        self.advance();
+                        s.clone()
+                    }
+                    _ => self.expect_property_name()?,
+                };
+                let (alias, default) = if self.check(&TokenKind::Colon) {
+                    self.advance();
+                    match self.parse_assignment_element()? {
+                        Pattern::Default { target, value } => (Some(*target), Some(*value)),
+                        target => (Some(target), None),
+                    }
+                } else if matches!(key_token.kind, TokenKind::Ident(_))
+                    && !self.is_disallowed_identifier_reference(&key)
+                {
+                    self.check_strict_target(&key, &key_token)?;
+                    let default = if self.check(&TokenKind::Assign) {
+                        self.advance();
+                        Some(self.parse_expr(0)?)
+                    } else {
+                        None
+                    };
+                    (None, default)
+                } else {
+                    return Err(SyntaxError::new(
+                        "invalid destructuring assignment target",
+                        key_token.span.start,
+                        key_token.span.len().max(1),
+                    ));
+                };
+                properties.push(ObjectPatternProp {
+                    key,
+                    computed: None,
+                    alias,
+                    default,
+                    is_rest: false,
+                });
+            }
+            if !self.check(&TokenKind::RightBrace) {
+                self.expect(&TokenKind::Comma)?;
+            }
+        }
+        self.expect(&TokenKind::RightBrace)?;
+        Ok(Pattern::ObjectPattern { properties })
+    }
+
+    /// `[expr]: target` in an object pattern, with the target's default.
+    fn parse_computed_pattern_prop(
+        &mut self,
+        assignment: bool,
+    ) -> Result<(Expr, Pattern, Option<Expr>), SyntaxError> {
+        self.expect(&TokenKind::LeftBracket)?;
+        let key = self.parse_expr(0)?;
+        self.expect(&TokenKind::RightBracket)?;
+        self.expect(&TokenKind::Colon)?;
+        if assignment {
+            return Ok(match self.parse_assignment_element()? {
+                Pattern::Default { target, value } => (key, *target, Some(*value)),
+                target => (key, target, None),
+            });
+        }
+        let target = self.parse_pattern()?;
+        let default = if self.check(&TokenKind::Assign) {
+            self.advance();
+            Some(self.parse_expr(0)?)
+        } else {
+            None
+        };
+        Ok((key, target, default))
+    }
+
+    /// A target with an optional `= default`.
+    fn parse_assignment_element(&mut self) -> Result<Pattern, SyntaxError> {
+        if self.starts_nested_assignment_pattern() {
+            let target = self.parse_assignment_pattern()?;
+            if !self.check(&TokenKind::Assign) {
+                return Ok(target);
+            }
+            self.advance();
+            return Ok(Pattern::Default {
+                target: Box::new(target),
+                value: Box::new(self.parse_expr(0)?),
+            });
+        }
+        // `a = 1` and `obj.x = 1` parse as assignments; split them back
+        // into a target and its default.
+        let token = self.tokens[self.pos].clone();
+        let (target, default) = match self.parse_expr(0)? {
+            Expr::Assign { name, value, .. } => (Expr::Identifier(name), Some(value)),
+            Expr::MemberAssign {
+                object,
+                property,
+                value,
+                ..
+            } => (Expr::ComputedMemberAccess { object, property }, Some(value)),
+            expr => (expr, None),
+        };
+        let target = self.simple_assignment_target(target, &token)?;
+        Ok(match default {
+            Some(value) => Pattern::Default {
+                target: Box::new(target),
+                value,
+            },
+            None => target,
+        })
+    }
+
+    /// A target without a default, as after `...`.
+    fn parse_assignment_target(&mut self) -> Result<Pattern, SyntaxError> {
+        if self.starts_nested_assignment_pattern() {
+            return self.parse_assignment_pattern();
+        }
+        let token = self.tokens[self.pos].clone();
+        let expr = self.parse_expr(0)?;
+        self.simple_assignment_target(expr, &token)
+    }
+
+    /// A nested `[` or `{` is a pattern when it ends where an element
+    /// would, rather than being indexed or called as in `[a][0]`.
+    fn starts_nested_assignment_pattern(&self) -> bool {
+        matches!(self.peek(), TokenKind::LeftBracket | TokenKind::LeftBrace)
+            && self.token_after_brackets(self.pos).is_some_and(|kind| {
+                matches!(
+                    kind,
+                    TokenKind::Comma
+                        | TokenKind::RightBracket
+                        | TokenKind::RightBrace
+                        | TokenKind::Assign
+                )
+            })
+    }
+
+    /// Accepts a name or property reference as an assignment target;
+    /// anything else is reported at `token`, where the element starts.
+    fn simple_assignment_target(&self, expr: Expr, token: &Token) -> Result<Pattern, SyntaxError> {
+        match expr {
+            Expr::Identifier(name) if name != "this" => {
+                self.check_strict_target(&name, token)?;
+                Ok(Pattern::Identifier(name))
+            }
+            Expr::MemberAccess { .. } | Expr::ComputedMemberAccess { .. } => {
+                Ok(Pattern::Member(Box::new(expr)))
+            }
+            _ => Err(SyntaxError::new(
+                "invalid destructuring assignment target",
+                token.span.start,
+                token.span.len().max(1),
+            )),
+        }
+    }
 }
@@ -0,0 +1,88 @@
+use super::Parser;
+use super::ast::Program;
+use crate::errors::SyntaxError;
+use crate::lexer::token::{Token, TokenKind};
+
+/// Parse a token stream, collecting every syntax error rather than
+/// stopping at the first. A statement that fails to parse is skipped up
+/// to the next `;` or closing `}` and left out of the program, so the
+/// program is only complete when no errors come back. Function bodies
+/// are parsed now, as with [`super::parse_eager`].
+pub fn parse_recovering(tokens: Vec<Token>) -> (Program, Vec<SyntaxError>) {
+    let mut parser = Parser::new(tokens);
+    parser.lazy_functions = false;
+    parser.recovering = true;
+    match parser.parse_program() {
+        Ok(program) => (program, parser.errors),
+        // Every statement's error is recovered from, so this is not
+        // reached, but an error is never dropped.
+        Err(err) => {
+            parser.errors.push(err);
+            let program = Program {
+                body: Vec::new(),
+                offsets: Vec::new(),
+            };
+            (program, parser.errors)
+        }
+    }
+}
+
+impl Parser {
+    /// Handles `err`, raised by the statement that began at token
+    /// `start`. Outside recovery mode it is returned as is; otherwise it
+    /// is recorded and the parser moves past the statement.
+    pub(crate) fn recover(&mut self, err: SyntaxError, start: usize) -> Result<(), SyntaxError> {
+        if !self.recovering {
+            return Err(err);
+        }
+        self.errors.push(err);
+        self.synchronize(start);
+        Ok(())
+    }
+
+    /// Skips to just past the `;` ending the statement that began at
+    /// `start`, or past the `}` closing a block it opened. A `}` closing
+    /// the enclosing block is left for that block.
+    fn synchronize(&mut self, start: usize) {
+        let mut depth =
+            self.tokens[start..self.pos]
+                .iter()
+                .fold(0usize, |depth, token| match token.kind {
+                    TokenKind::LeftBrace => depth + 1,
+                    TokenKind::RightBrace => depth.saturating_sub(1),
+                    _ => depth,
+                });
+        // The failing token may already have been consumed, and if it
+        // was the `;` the statement is over.
+        let consumed_terminator =
+            self.pos > start && self.tokens[self.pos - 1].kind == TokenKind::Semicolon;
+        while !(consumed_terminator && depth == 0) {
+            match self.peek() {
+                TokenKind::Eof => break,
+                TokenKind::Semicolon if depth == 0 => {
+                    self.advance();
+                    break;
+                }
+                TokenKind::RightBrace if depth == 0 => break,
+                TokenKind::RightBrace => {
+                    self.advance();
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                TokenKind::LeftBrace => {
+                    self.advance();
+                    depth += 1;
+                }
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+        // A stray `}` at the top level would otherwise stop progress.
+        if self.pos == start {
+            self.advance();
+        }
+    }
+}
@@ -0,0 +1,672 @@
+//! Resolves the names a function uses to the scope slots the interpreter
+//! keeps them in.
+//!
+//! The interpreter pushes scopes in a fixed pattern: one for a function's
+//! parameters, one for its body when a parameter has a default, and one
+//! for each block, loop head, loop iteration and catch clause. Where a
+//! name is bound can therefore mostly be worked out once a function is
+//! parsed. An identifier bound inside its own function becomes an
+//! [`Expr::Local`] naming the scope, counted outwards from the innermost,
+//! and the position in it the binding is expected at; assignments and
+//! updates record the same [`Slot`].
+//!
+//! Names bound outside the function, at the top level of a script, or
+//! declared both with `var` and lexically are left to be looked up by
+//! name. The interpreter checks the name at a slot before using it, so a
+//! binding that is not where it was expected, such as one whose
+//! declaration has not run yet, only costs that lookup.
+
+use std::collections::HashSet;
+
+use super::ast::{
+    ExportDecl, Expr, ObjectProperty, OptionalOp, Param, Pattern, PropertyKey, Slot, Stmt,
+    TemplatePart, VarDeclKind,
+};
+
+/// What resolving a function's body needs from its parameter list, kept
+/// for bodies the parser skipped.
+#[derive(Debug, Clone)]
+pub(crate) struct ParamScope {
+    names: Vec<String>,
+    /// A parameter has a default, so the body runs in a scope of its own.
+    defaults: bool,
+}
+
+impl ParamScope {
+    pub(crate) fn new(params: &[Param]) -> Self {
+        let mut names = Vec::new();
+        for param in params {
+            for name in param.pattern.bound_names() {
+                push_unique(&mut names, name);
+            }
+        }
+        Self {
+            names,
+            defaults: params.iter().any(|param| param.default.is_some()),
+        }
+    }
+}
+
+/// Resolves the defaults and patterns of a parameter list, which run in
+/// the scope holding the parameters.
+pub(crate) fn resolve_params(params: &mut [Param]) {
+    let mut resolver = Resolver {
+        scopes: vec![ParamScope::new(params).names],
+        opaque_root: false,
+        ambiguous: HashSet::new(),
+    };
+    for param in params {
+        resolver.pattern(&mut param.pattern);
+        if let Some(default) = &mut param.default {
+            resolver.expr(default);
+        }
+    }
+}
+
+/// Resolves the statements of a function body.
+pub(crate) fn resolve_function(params: &ParamScope, body: &mut [Stmt]) {
+    Resolver::function(params, body).stmts(body);
+}
+
+/// Resolves an arrow function's parameters and body.
+pub(crate) fn resolve_arrow(params: &mut [Param], body: &mut [Stmt]) {
+    resolve_params(params);
+    resolve_function(&ParamScope::new(params), body);
+}
+
+/// Resolves the blocks of a script or module. Its own top-level bindings
+/// are globals or module bindings and stay looked up by name.
+pub(crate) fn resolve_program(body: &mut [Stmt]) {
+    let mut resolver = Resolver {
+        scopes: vec![Vec::new()],
+        opaque_root: true,
+        ambiguous: ambiguous_names(&[], body),
+    };
+    resolver.stmts(body);
+}
+
+struct Resolver {
+    /// The names each scope is expected to bind, innermost last, in the
+    /// order the interpreter adds them.
+    scopes: Vec<Vec<String>>,
+    /// The outermost scope is the global or module one.
+    opaque_root: bool,
+    ambiguous: HashSet<String>,
+}
+
+impl Resolver {
+    fn function(params: &ParamScope, body: &[Stmt]) -> Self {
+        let mut scopes = vec![params.names.clone()];
+        if params.defaults {
+            scopes.push(Vec::new());
+        }
+        let scope = scopes.last_mut().expect("a function has a scope");
+        for name in var_names(body) {
+            push_unique(scope, &name);
+        }
+        for name in function_names(body) {
+            push_unique(scope, name);
+        }
+        lexical_names(body, scope);
+        Self {
+            scopes,
+            opaque_root: false,
+            ambiguous: ambiguous_names(&params.names, body),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Slot> {
+        if name == "this" || self.ambiguous.contains(name) {
+            return None;
+        }
+        let resolvable = &self.scopes[usize::from(self.opaque_root)..];
+        resolvable
+            .iter()
+            .rev()
+            .enumerate()
+            .find_map(|(depth, names)| {
+                let index = names.iter().position(|bound| bound == name)?;
+                Some(Slot {
+                    depth: depth as u32,
+                    index: index as u32,
+                })
+            })
+    }
+
+    fn scoped(&mut self, names: Vec<String>, resolve: impl FnOnce(&mut Self)) {
+        self.scopes.push(names);
+        resolve(self);
+        self.scopes.pop();
+    }
+
+    fn block(&mut self, stmts: &mut [Stmt]) {
+        let mut names = Vec::new();
+        for name in function_names(stmts) {
+            push_unique(&mut names, name);
+        }
+        lexical_names(stmts, &mut names);
+        self.scoped(names, |this| this.stmts(stmts));
+    }
+
+    fn stmts(&mut self, stmts: &mut [Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::ExprStmt(expr) | Stmt::Throw(expr) | Stmt::Return(Some(expr)) => self.expr(expr),
+            Stmt::VarDecl { pattern, init, .. } => {
+                if let Some(init) = init {
+                    self.expr(init);
+                }
+                self.pattern(pattern);
+            }
+            Stmt::VarDeclList { declarations, .. } => {
+                for (pattern, init) in declarations {
+                    if let Some(init) = init {
+                        self.expr(init);
+                    }
+                    self.pattern(pattern);
+                }
+            }
+            Stmt::Block(stmts) => self.block(stmts),
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(condition);
+                self.stmt(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.stmt(else_branch);
+                }
+            }
+            Stmt::While { condition, body } | Stmt::DoWhile { body, condition } => {
+                self.expr(condition);
+                self.stmt(body);
+            }
+            Stmt::Labeled { body, .. } => self.stmt(body),
+            Stmt::ForLoop {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                let mut names = Vec::new();
+                if let Some(init) = init {
+                    lexical_names(std::slice::from_ref(&**init), &mut names);
+                }
+                lexical_names(std::slice::from_ref(&**body), &mut names);
+                self.scoped(names, |this| {
+                    if let Some(init) = init {
+                        this.stmt(init);
+                    }
+                    if let Some(condition) = condition {
+                        this.expr(condition);
+                    }
+                    if let Some(update) = update {
+                        this.expr(update);
+                    }
+                    this.stmt(body);
+                });
+            }
+            Stmt::ForOf {
+                kind,
+                pattern,
+                iterable: source,
+                body,
+            }
+            | Stmt::ForIn {
+                kind,
+                pattern,
+                object: source,
+                body,
+            } => {
+                self.expr(source);
+                let mut names = Vec::new();
+                if matches!(kind, Some(VarDeclKind::Let | VarDeclKind::Const)) {
+                    for name in pattern.bound_names() {
+                        push_unique(&mut names, name);
+                    }
+                }
+                lexical_names(std::slice::from_ref(&**body), &mut names);
+                self.scoped(names, |this| {
+                    this.pattern(pattern);
+                    this.stmt(body);
+                });
+            }
+            Stmt::TryCatch {
+                try_block,
+                catch_param,
+                catch_block,
+                finally_block,
+            } => {
+                self.block(try_block);
+                if let Some(catch_block) = catch_block {
+                    let mut names = Vec::new();
+                    if let Some(param) = catch_param {
+                        for name in param.bound_names() {
+                            push_unique(&mut names, name);
+                        }
+                    }
+                    for name in function_names(catch_block) {
+                        push_unique(&mut names, name);
+                    }
+                    lexical_names(catch_block, &mut names);
+                    self.scoped(names, |this| {
+                        if let Some(param) = catch_param {
+                            this.pattern(param);
+                        }
+                        this.stmts(catch_block);
+                    });
+                }
+                if let Some(finally_block) = finally_block {
+                    self.block(finally_block);
+                }
+            }
+            Stmt::Switch {
+                discriminant,
+                cases,
+            } => {
+                self.expr(discriminant);
+                for case in cases {
+                    if let Some(test) = &mut case.test {
+                        self.expr(test);
+                    }
+                    self.stmts(&mut case.body);
+                }
+            }
+            Stmt::Export(ExportDecl::NamedStmt(inner) | ExportDecl::DefaultStmt(inner)) => {
+                self.stmt(inner)
+            }
+            Stmt::Export(ExportDecl::Default(expr)) => self.expr(expr),
+            // Functions were resolved as they were parsed, and class
+            // bodies run in scopes of their own.
+            Stmt::FunctionDecl { .. }
+            | Stmt::Class(_)
+            | Stmt::Import(_)
+            | Stmt::Export(ExportDecl::NamedList(_))
+            | Stmt::Return(None)
+            | Stmt::Break { .. }
+            | Stmt::Continue { .. }
+            | Stmt::Empty => {}
+        }
+    }
+
+    fn pattern(&mut self, pattern: &mut Pattern) {
+        match pattern {
+            Pattern::Identifier(_) => {}
+            Pattern::ObjectPattern { properties } => {
+                for prop in properties {
+                    if let Some(computed) = &mut prop.computed {
+                        self.expr(computed);
+                    }
+                    if let Some(alias) = &mut prop.alias {
+                        self.pattern(alias);
+                    }
+                    if let Some(default) = &mut prop.default {
+                        self.expr(default);
+                    }
+                }
+            }
+            Pattern::ArrayPattern { elements } => {
+                for element in elements.iter_mut().flatten() {
+                    self.pattern(element);
+                }
+            }
+            Pattern::Rest(inner) => self.pattern(inner),
+            Pattern::Default { target, value } => {
+                self.pattern(target);
+                self.expr(value);
+            }
+            Pattern::Member(expr) => self.expr(expr),
+        }
+    }
+
+    fn exprs(&mut self, exprs: &mut [Expr]) {
+        for expr in exprs {
+            self.expr(expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Identifier(name) => {
+                if let Some(slot) = self.lookup(name) {
+                    let name = std::mem::take(name);
+                    *expr = Expr::Local { name, slot };
+                }
+            }
+            Expr::Assign {
+                name, value, slot, ..
+            }
+            | Expr::CompoundAssign {
+                name, value, slot, ..
+            } => {
+                self.expr(value);
+                *slot = self.lookup(name);
+            }
+            Expr::UpdateExpr { name, slot, .. } => *slot = self.lookup(name),
+            Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::Unary { operand: inner, .. }
+            | Expr::Spread(inner)
+            | Expr::Await(inner)
+            | Expr::MemberAccess { object: inner, .. }
+            | Expr::PrivateMember { object: inner, .. } => self.expr(inner),
+            // `typeof` and `delete` treat an unbound name specially, and
+            // `new` recognizes builtin constructors by name.
+            Expr::Typeof(operand) | Expr::Delete { operand, .. } => {
+                if !matches!(**operand, Expr::Identifier(_)) {
+                    self.expr(operand);
+                }
+            }
+            Expr::New { callee, args } => {
+                if !matches!(**callee, Expr::Identifier(_)) {
+                    self.expr(callee);
+                }
+                self.exprs(args);
+            }
+            Expr::Call { callee, args } => {
+                self.expr(callee);
+                self.exprs(args);
+            }
+            Expr::SuperCall { args } => self.exprs(args),
+            Expr::ArrayLiteral { elements } | Expr::Sequence(elements) => self.exprs(elements),
+            Expr::ComputedMemberAccess { object, property } => {
+                self.expr(object);
+                self.expr(property);
+            }
+            Expr::MemberAssign {
+                object,
+                property,
+                value,
+                ..
+            }
+            | Expr::MemberCompoundAssign {
+                object,
+                property,
+                value,
+                ..
+            } => {
+                self.expr(object);
+                self.expr(property);
+                self.expr(value);
+            }
+            Expr::DestructuringAssign { pattern, value } => {
+                self.expr(value);
+                self.pattern(pattern);
+            }
+            Expr::Ternary {
+                condition,
+                then_expr,
+                else_expr,
+            } => {
+                self.expr(condition);
+                self.expr(then_expr);
+                self.expr(else_expr);
+            }
+            Expr::Yield { value, .. } => {
+                if let Some(value) = value {
+                    self.expr(value);
+                }
+            }
+            Expr::TemplateLiteral { parts } => self.template(parts),
+            Expr::TaggedTemplate { tag, parts, .. } => {
+                self.expr(tag);
+                self.template(parts);
+            }
+            Expr::ObjectLiteral { properties } => {
+                for property in properties {
+                    match property {
+                        ObjectProperty::KeyValue(key, value) => {
+                            self.key(key);
+                            self.expr(value);
+                        }
+                        // Accessor bodies run as functions of their own
+                        // and are not resolved.
+                        ObjectProperty::Getter(key, _) | ObjectProperty::Setter(key, _, _) => {
+                            self.key(key)
+                        }
+                        ObjectProperty::Spread(value) => self.expr(value),
+                    }
+                }
+            }
+            Expr::OptionalChain { base, chain } => {
+                self.expr(base);
+                for op in chain {
+                    match op {
+                        OptionalOp::PropertyAccess(_) => {}
+                        OptionalOp::ComputedAccess(property) => self.expr(property),
+                        OptionalOp::Call(args) => self.exprs(args),
+                    }
+                }
+            }
+            // Nested functions were resolved as they were parsed.
+            Expr::ArrowFunction { .. }
+            | Expr::FunctionExpr { .. }
+            | Expr::Local { .. }
+            | Expr::Literal(_)
+            | Expr::RegexLiteral { .. }
+            | Expr::ImportMeta
+            | Expr::PrivateName(_) => {}
+        }
+    }
+
+    fn key(&mut self, key: &mut PropertyKey) {
+        if let PropertyKey::Computed(expr) = key {
+            self.expr(expr);
+        }
+    }
+
+    fn template(&mut self, parts: &mut [TemplatePart]) {
+        for part in parts {
+            if let TemplatePart::Expression(expr) = part {
+                self.expr(expr);
+            }
+        }
+    }
+}
+
+fn push_unique(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|bound| bound == name) {
+        names.push(name.to_string());
+    }
+}
+
+/// The functions declared directly in `stmts`, which are bound before
+/// the statements run.
+fn function_names(stmts: &[Stmt]) -> impl Iterator<Item = &str> {
+    stmts.iter().filter_map(|stmt| match stmt {
+        Stmt::FunctionDecl { name, .. } => Some(name.as_str()),
+        _ => None,
+    })
+}
+
+/// Adds the names `stmts` bind in the scope they run in: `let`, `const`,
+/// classes and functions, including those in nested statements that do
+/// not push a scope of their own, such as the branches of an `if` or the
+/// cases of a `switch`.
+fn lexical_names(stmts: &[Stmt], names: &mut Vec<String>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::VarDecl {
+                kind: VarDeclKind::Let | VarDeclKind::Const,
+                pattern,
+                ..
+            } => {
+                for name in pattern.bound_names() {
+                    push_unique(names, name);
+                }
+            }
+            Stmt::VarDeclList {
+                kind: VarDeclKind::Let | VarDeclKind::Const,
+                declarations,
+            } => {
+                for name in declarations
+                    .iter()
+                    .flat_map(|(pattern, _)| pattern.bound_names())
+                {
+                    push_unique(names, name);
+                }
+            }
+            Stmt::Class(class) => push_unique(names, &class.name),
+            Stmt::FunctionDecl { name, .. } => push_unique(names, name),
+            Stmt::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                lexical_names(std::slice::from_ref(&**then_branch), names);
+                if let Some(else_branch) = else_branch {
+                    lexical_names(std::slice::from_ref(&**else_branch), names);
+                }
+            }
+            Stmt::While { body, .. } | Stmt::DoWhile { body, .. } | Stmt::Labeled { body, .. } => {
+                lexical_names(std::slice::from_ref(&**body), names)
+            }
+            Stmt::Switch { cases, .. } => {
+                for case in cases {
+                    lexical_names(&case.body, names);
+                }
+            }
+            Stmt::Export(ExportDecl::NamedStmt(inner) | ExportDecl::DefaultStmt(inner)) => {
+                lexical_names(std::slice::from_ref(&**inner), names)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The names `var` declares anywhere in `stmts`, outside nested
+/// functions, which are hoisted to the function's scope.
+fn var_names(stmts: &[Stmt]) -> Vec<String> {
+    let mut names = Vec::new();
+    for stmt in stmts {
+        collect_declarations(stmt, true, &mut |name, is_var| {
+            if is_var {
+                push_unique(&mut names, name);
+            }
+        });
+    }
+    names
+}
+
+/// Names declared with `var` that something else in the same function
+/// also declares. Which scope ends up holding such a name depends on the
+/// order the declarations run in, so it is looked up by name.
+fn ambiguous_names(params: &[String], stmts: &[Stmt]) -> HashSet<String> {
+    let mut vars = HashSet::new();
+    let mut lexical: HashSet<String> = params.iter().cloned().collect();
+    for stmt in stmts {
+        collect_declarations(stmt, true, &mut |name, is_var| {
+            if is_var {
+                vars.insert(name.to_string());
+            } else {
+                lexical.insert(name.to_string());
+            }
+        });
+    }
+    vars.retain(|name| lexical.contains(name));
+    vars
+}
+
+/// Reports every name `stmt` declares outside nested functions and
+/// classes, and whether it is hoisted like a `var`. Functions declared
+/// directly in the body are.
+fn collect_declarations(stmt: &Stmt, top: bool, found: &mut dyn FnMut(&str, bool)) {
+    let nested =
+        |stmt: &Stmt, found: &mut dyn FnMut(&str, bool)| collect_declarations(stmt, false, found);
+    match stmt {
+        Stmt::VarDecl { kind, pattern, .. } => {
+            for name in pattern.bound_names() {
+                found(name, *kind == VarDeclKind::Var);
+            }
+        }
+        Stmt::VarDeclList { kind, declarations } => {
+            for (pattern, _) in declarations {
+                for name in pattern.bound_names() {
+                    found(name, *kind == VarDeclKind::Var);
+                }
+            }
+        }
+        Stmt::FunctionDecl { name, .. } => found(name, top),
+        Stmt::Class(class) => found(&class.name, false),
+        Stmt::Block(stmts) => {
+            for stmt in stmts {
+                nested(stmt, found);
+            }
+        }
+        Stmt::If {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            nested(then_branch, found);
+            if let Some(else_branch) = else_branch {
+                nested(else_branch, found);
+            }
+        }
+        Stmt::While { body, .. } | Stmt::DoWhile { body, .. } | Stmt::Labeled { body, .. } => {
+            nested(body, found)
+        }
+        Stmt::ForLoop { init, body, .. } => {
+            if let Some(init) = init {
+                nested(init, found);
+            }
+            nested(body, found);
+        }
+        Stmt::ForOf {
+            kind,
+            pattern,
+            body,
+            ..
+        }
+        | Stmt::ForIn {
+            kind,
+            pattern,
+            body,
+            ..
+        } => {
+            if let Some(kind) = kind {
+                for name in pattern.bound_names() {
+                    found(name, *kind == VarDeclKind::Var);
+                }
+            }
+            nested(body, found);
+        }
+        Stmt::TryCatch {
+            try_block,
+            catch_param,
+            catch_block,
+            finally_block,
+        } => {
+            if let Some(param) = catch_param {
+                for name in param.bound_names() {
+                    found(name, false);
+                }
+            }
+            let blocks = [
+                Some(try_block),
+                catch_block.as_ref(),
+                finally_block.as_ref(),
+            ];
+            for stmt in blocks.into_iter().flatten().flatten() {
+                nested(stmt, found);
+            }
+        }
+        Stmt::Switch { cases, .. } => {
+            for stmt in cases.iter().flat_map(|case| &case.body) {
+                nested(stmt, found);
+            }
+        }
+        Stmt::Export(ExportDecl::NamedStmt(inner) | ExportDecl::DefaultStmt(inner)) => {
+            collect_declarations(inner, top, found)
+        }
+        _ => {}
+    }
+}
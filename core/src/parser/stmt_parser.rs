@@ -5,6 +5,10 @@ use crate::lexer::token::TokenKind;
 
 impl Parser {
     pub(crate) fn parse_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        self.nested(Self::parse_statement_unnested)
+    }
+
+    fn parse_statement_unnested(&mut self) -> Result<Stmt, SyntaxError> {
         if self.check(&TokenKind::Semicolon) {
             self.advance();
             return Ok(Stmt::Empty);
@@ -34,7 +38,11 @@ impl Parser {
             TokenKind::Throw => self.parse_throw(),
             TokenKind::Switch => self.parse_switch(),
             TokenKind::Class => self.parse_class_decl(),
-            TokenKind::Import => self.parse_import_decl(),
+            TokenKind::Import
+                if self.tokens.get(self.pos + 1).map(|t| &t.kind) != Some(&TokenKind::Dot) =>
+            {
+                self.parse_import_decl()
+            }
             TokenKind::Export => self.parse_export_decl(),
             TokenKind::LeftBrace => self.parse_block_stmt(),
             _ => self.parse_expr_stmt(),
@@ -42,40 +50,54 @@ impl Parser {
     }
 
     pub(crate) fn parse_var_decl(&mut self) -> Result<Stmt, SyntaxError> {
+        let decl = self.parse_var_declarations()?;
+        self.consume_stmt_terminator()?;
+        Ok(decl)
+    }
+
+    /// Parses a `let`/`const`/`var` keyword and its comma-separated
+    /// declarators, leaving the terminator to the caller.
+    pub(crate) fn parse_var_declarations(&mut self) -> Result<Stmt, SyntaxError> {
         let kind = match self.advance().kind {
             TokenKind::Let => VarDeclKind::Let,
             TokenKind::Const => VarDeclKind::Const,
             TokenKind::Var => VarDeclKind::Var,
-            _ => unreachable!("parse_var_decl called on non-var token"),
+            _ => unreachable!("parse_var_declarations called on non-var token"),
         };
-        let pattern = self.parse_pattern()?;
-        let init = if self.check(&TokenKind::Assign) {
+        let mut declarations = Vec::new();
+        loop {
+            let pattern = self.parse_pattern()?;
+            let init = if self.check(&TokenKind::Assign) {
+                self.advance();
+                Some(self.parse_expr(0)?)
+            } else {
+                // for-in/for-of heads bind from the iteration instead.
+                if kind == VarDeclKind::Const
+                    && !matches!(self.peek(), TokenKind::Of | TokenKind::In)
+                {
+                    let token = &self.tokens[self.pos];
+                    return Err(SyntaxError::new(
+                        "missing initializer in const declaration",
+                        token.span.start,
+                        token.span.len().max(1),
+                    ));
+                }
+                None
+            };
+            declarations.push((pattern, init));
+            if !self.check(&TokenKind::Comma) {
+                break;
+            }
             self.advance();
-            Some(self.parse_expr(0)?)
-        } else {
-            None
-        };
-        if !self.check(&TokenKind::Comma) {
-            self.consume_stmt_terminator()?;
+        }
+        if declarations.len() == 1 {
+            let (pattern, init) = declarations.remove(0);
             return Ok(Stmt::VarDecl {
                 kind,
                 pattern,
                 init,
             });
         }
-        let mut declarations = vec![(pattern, init)];
-        while self.check(&TokenKind::Comma) {
-            self.advance();
-            let pat = self.parse_pattern()?;
-            let ini = if self.check(&TokenKind::Assign) {
-                self.advance();
-                Some(self.parse_expr(0)?)
-            } else {
-                None
-            };
-            declarations.push((pat, ini));
-        }
-        self.consume_stmt_terminator()?;
         Ok(Stmt::VarDeclList { kind, declarations })
     }
 
@@ -87,18 +109,16 @@ impl Parser {
         &mut self,
         is_async: bool,
     ) -> Result<Stmt, SyntaxError> {
+        // An async function's text starts at the `async` before it.
+        let start = if is_async { self.pos - 1 } else { self.pos };
         let function_token = self.advance().clone();
         let is_generator = self.check(&TokenKind::Star);
         if is_generator {
             self.advance();
         }
         let name = self.expect_ident()?;
-        self.expect(&TokenKind::LeftParen)?;
-
-        let params = self.parse_params_list()?;
-        self.expect(&TokenKind::RightParen)?;
-
-        let body = self.parse_block()?;
+        let (params, body) = self.parse_function_params_and_body()?;
+        let body = body.with_source(self.source_since(start));
         Ok(Stmt::FunctionDecl {
             name,
             params,
@@ -122,7 +142,7 @@ impl Parser {
     fn parse_if(&mut self) -> Result<Stmt, SyntaxError> {
         self.advance(); // consume 'if'
         self.expect(&TokenKind::LeftParen)?;
-        let condition = self.parse_expr(0)?;
+        let condition = self.parse_expression()?;
         self.expect(&TokenKind::RightParen)?;
 
         let then_branch = Box::new(self.parse_statement()?);
@@ -144,7 +164,7 @@ impl Parser {
     fn parse_while(&mut self) -> Result<Stmt, SyntaxError> {
         self.advance(); // consume 'while'
         self.expect(&TokenKind::LeftParen)?;
-        let condition = self.parse_expr(0)?;
+        let condition = self.parse_expression()?;
         self.expect(&TokenKind::RightParen)?;
         let body = Box::new(self.parse_statement()?);
         Ok(Stmt::While { condition, body })
@@ -155,7 +175,7 @@ impl Parser {
         let body = Box::new(self.parse_statement()?);
         self.expect(&TokenKind::While)?;
         self.expect(&TokenKind::LeftParen)?;
-        let condition = self.parse_expr(0)?;
+        let condition = self.parse_expression()?;
         self.expect(&TokenKind::RightParen)?;
         if self.check(&TokenKind::Semicolon) {
             self.advance();
@@ -188,7 +208,11 @@ impl Parser {
         self.expect(&TokenKind::LeftBrace)?;
         let mut stmts = Vec::new();
         while !self.check(&TokenKind::RightBrace) && !self.is_at_end() {
-            stmts.push(self.parse_statement()?);
+            let start = self.pos;
+            match self.parse_statement() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(err) => self.recover(err, start)?,
+            }
         }
         self.expect(&TokenKind::RightBrace)?;
         Ok(stmts)
@@ -212,7 +236,7 @@ impl Parser {
             self.advance(); // consume 'catch'
             if self.check(&TokenKind::LeftParen) {
                 self.advance();
-                catch_param = Some(self.expect_ident()?);
+                catch_param = Some(self.parse_pattern()?);
                 self.expect(&TokenKind::RightParen)?;
             }
             catch_block = Some(self.parse_block()?);
@@ -249,7 +273,7 @@ impl Parser {
                 throw_token.span.len().max(1),
             ));
         }
-        let expr = self.parse_expr(0)?;
+        let expr = self.parse_expression()?;
         self.consume_stmt_terminator()?;
         Ok(Stmt::Throw(expr))
     }
@@ -1,4 +1,4 @@
-use super::ast::Stmt;
+use super::ast::{Stmt, VarDeclKind};
 use super::Parser;
 use crate::errors::SyntaxError;
 use crate::lexer::token::TokenKind;
@@ -8,39 +8,55 @@ impl Parser {
         self.advance(); // consume 'for'
         self.expect(&TokenKind::LeftParen)?;
 
-        if matches!(
-            self.peek(),
-            TokenKind::Let | TokenKind::Const | TokenKind::Var
-        ) {
-            let saved_pos = self.pos;
+        let head_start = self.pos;
+        let kind = match self.peek() {
+            TokenKind::Let => Some(VarDeclKind::Let),
+            TokenKind::Const => Some(VarDeclKind::Const),
+            TokenKind::Var => Some(VarDeclKind::Var),
+            _ => None,
+        };
+        if kind.is_some() {
             self.advance();
-            if let TokenKind::Ident(_) = self.peek() {
-                let name = self.expect_ident()?;
-                if self.check(&TokenKind::Of) || self.check(&TokenKind::In) {
-                    let is_for_in = self.check(&TokenKind::In);
-                    self.advance();
-                    let iterable_or_object = self.parse_expr(0)?;
-                    self.expect(&TokenKind::RightParen)?;
-                    let body = Box::new(self.parse_statement()?);
-                    return if is_for_in {
-                        Ok(Stmt::ForIn {
-                            variable: name,
-                            object: iterable_or_object,
-                            body,
-                        })
-                    } else {
-                        Ok(Stmt::ForOf {
-                            variable: name,
-                            iterable: iterable_or_object,
-                            body,
-                        })
-                    };
-                }
-                self.pos = saved_pos;
-            } else {
-                self.pos = saved_pos;
+        }
+        // Without a declaration the head assigns to an existing binding,
+        // which has to be a plain name.
+        let pattern = match self.peek() {
+            TokenKind::Ident(_) => self.parse_pattern().ok(),
+            TokenKind::LeftBrace | TokenKind::LeftBracket if kind.is_some() => {
+                self.parse_pattern().ok()
             }
+            _ => None,
+        };
+        if let Some(pattern) = pattern
+            && (self.check(&TokenKind::Of) || self.check(&TokenKind::In))
+        {
+            let is_for_in = self.check(&TokenKind::In);
+            self.advance();
+            // `in` takes a full expression, `of` only an assignment one.
+            let iterable_or_object = if is_for_in {
+                self.parse_expression()?
+            } else {
+                self.parse_expr(0)?
+            };
+            self.expect(&TokenKind::RightParen)?;
+            let body = Box::new(self.parse_statement()?);
+            return if is_for_in {
+                Ok(Stmt::ForIn {
+                    kind,
+                    pattern,
+                    object: iterable_or_object,
+                    body,
+                })
+            } else {
+                Ok(Stmt::ForOf {
+                    kind,
+                    pattern,
+                    iterable: iterable_or_object,
+                    body,
+                })
+            };
         }
+        self.pos = head_start;
 
         let init = if self.check(&TokenKind::Semicolon) {
             self.advance();
@@ -50,7 +66,10 @@ impl Parser {
                 self.peek(),
                 TokenKind::Let | TokenKind::Const | TokenKind::Var
             ) {
-                self.parse_var_decl()?
+                let decl = self.parse_var_declarations()?;
+                self.reject_for_in_of_declarators(&decl)?;
+                self.consume_stmt_terminator()?;
+                decl
             } else {
                 self.parse_expr_stmt()?
             };
@@ -79,4 +98,25 @@ impl Parser {
             body,
         })
     }
+
+    /// A declaration followed by `in`/`of` only reaches the generic
+    /// for-loop path when it isn't a single plain binding, so report why.
+    fn reject_for_in_of_declarators(&self, decl: &Stmt) -> Result<(), SyntaxError> {
+        if !matches!(self.peek(), TokenKind::Of | TokenKind::In) {
+            return Ok(());
+        }
+        let message = match decl {
+            Stmt::VarDeclList { .. } => "for-in/for-of loop head must declare a single binding",
+            Stmt::VarDecl { init: Some(_), .. } => {
+                "for-in/for-of loop variable declaration may not have an initializer"
+            }
+            _ => return Ok(()),
+        };
+        let token = &self.tokens[self.pos];
+        Err(SyntaxError::new(
+            message,
+            token.span.start,
+            token.span.len().max(1),
+        ))
+    }
 }
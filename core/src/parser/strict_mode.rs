@@ -0,0 +1,117 @@
+//! The early errors of strict mode code, other than the reserved words
+//! `is_disallowed_identifier_reference` rejects.
+
+use std::collections::HashSet;
+
+use super::Parser;
+use super::ast::{Param, Pattern};
+use crate::errors::SyntaxError;
+use crate::lexer::token::{Token, TokenKind};
+
+impl Parser {
+    /// Whether the body opening at the current `{` starts with a
+    /// `"use strict"` directive. The directive has to be written without
+    /// escapes, and only string literal statements may come before it.
+    pub(super) fn body_declares_strict(&self) -> bool {
+        let mut i = self.pos + 1;
+        while let TokenKind::String(value) = &self.tokens[i].kind {
+            let next = &self.tokens[i + 1];
+            let ends_statement = matches!(next.kind, TokenKind::Semicolon | TokenKind::RightBrace)
+                || next.had_line_terminator_before;
+            if !ends_statement {
+                return false;
+            }
+            if value == "use strict" && self.tokens[i].span.len() == "'use strict'".len() {
+                return true;
+            }
+            i += 1 + usize::from(next.kind == TokenKind::Semicolon);
+        }
+        false
+    }
+
+    /// [`Parser::expect_ident`] for a name a declaration or pattern binds.
+    pub(super) fn expect_binding_ident(&mut self) -> Result<String, SyntaxError> {
+        let name = self.expect_ident()?;
+        self.check_strict_target(&name, &self.tokens[self.pos - 1])?;
+        Ok(name)
+    }
+
+    /// Rejects binding or assigning to `eval` or `arguments` in strict
+    /// mode code.
+    pub(super) fn check_strict_target(&self, name: &str, token: &Token) -> Result<(), SyntaxError> {
+        if self.strict_mode && is_eval_or_arguments(name) {
+            return Err(SyntaxError::new(
+                "Unexpected eval or arguments in strict mode",
+                token.span.start,
+                token.span.len().max(1),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks the parameters of a function whose list starts at token
+    /// `list_start`, once the strictness of its body is known. Arrow
+    /// functions and lists with defaults, rest or destructuring may never
+    /// repeat a name; other functions only may outside strict mode code.
+    pub(super) fn check_params(
+        &self,
+        params: &[Param],
+        strict: bool,
+        arrow: bool,
+        list_start: usize,
+    ) -> Result<(), SyntaxError> {
+        let simple = params.iter().all(|param| {
+            param.default.is_none() && matches!(param.pattern, Pattern::Identifier(_))
+        });
+        let names: Vec<&str> = params
+            .iter()
+            .flat_map(|param| param.pattern.bound_names())
+            .collect();
+        self.check_param_names(&names, strict, arrow || !simple, list_start)
+    }
+
+    /// [`Parser::check_params`] over the bare names of a list, `unique`
+    /// when the list may not repeat a name even in sloppy mode code.
+    pub(super) fn check_param_names(
+        &self,
+        names: &[&str],
+        strict: bool,
+        unique: bool,
+        list_start: usize,
+    ) -> Result<(), SyntaxError> {
+        if strict && let Some(name) = names.iter().find(|name| is_eval_or_arguments(name)) {
+            let token = self.param_token(list_start, name, 1);
+            return Err(SyntaxError::new(
+                "Unexpected eval or arguments in strict mode",
+                token.span.start,
+                token.span.len().max(1),
+            ));
+        }
+        if strict || unique {
+            let mut seen = HashSet::new();
+            if let Some(name) = names.iter().find(|name| !seen.insert(**name)) {
+                let token = self.param_token(list_start, name, 2);
+                return Err(SyntaxError::new(
+                    "Duplicate parameter name not allowed in this context",
+                    token.span.start,
+                    token.span.len().max(1),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The `nth` token naming `name` from `list_start` on, to point an
+    /// error at, or the first token of the list.
+    fn param_token(&self, list_start: usize, name: &str, nth: usize) -> &Token {
+        self.tokens[list_start..self.pos]
+            .iter()
+            .filter(|token| matches!(&token.kind, TokenKind::Ident(ident) if ident == name))
+            .nth(nth - 1)
+            .unwrap_or(&self.tokens[list_start])
+    }
+}
+
+fn is_eval_or_arguments(name: &str) -> bool {
+    matches!(name, "eval" | "arguments")
+}
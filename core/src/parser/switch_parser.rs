@@ -7,7 +7,7 @@ impl Parser {
     pub(crate) fn parse_switch(&mut self) -> Result<Stmt, SyntaxError> {
         self.advance(); // consume 'switch'
         self.expect(&TokenKind::LeftParen)?;
-        let discriminant = self.parse_expr(0)?;
+        let discriminant = self.parse_expression()?;
         self.expect(&TokenKind::RightParen)?;
         self.expect(&TokenKind::LeftBrace)?;
 
@@ -15,7 +15,7 @@ impl Parser {
         while !self.check(&TokenKind::RightBrace) && !self.is_at_end() {
             if self.check(&TokenKind::Case) {
                 self.advance();
-                let test = self.parse_expr(0)?;
+                let test = self.parse_expression()?;
                 self.expect(&TokenKind::Colon)?;
                 let body = self.parse_switch_case_body()?;
                 cases.push(SwitchCase {
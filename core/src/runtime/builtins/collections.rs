@@ -1,78 +1,120 @@
 use crate::errors::RuntimeError;
 use crate::parser::ast::Expr;
+use crate::runtime::builtins::set_operations::SET_OPERATIONS;
 use crate::runtime::gc::{Gc, GcCell};
 use crate::runtime::interpreter::Interpreter;
 use crate::runtime::value::array::JsArray;
+use crate::runtime::value::collections::iterator::{
+    IteratedCollection, IterationKind, JsCollectionIterator,
+};
 use crate::runtime::value::collections::map::JsMap;
 use crate::runtime::value::collections::set::JsSet;
 use crate::runtime::value::collections::weak_map::{extract_weak_key, JsWeakMap};
 use crate::runtime::value::collections::weak_set::JsWeakSet;
-use crate::runtime::value::generator::JsGenerator;
+use crate::runtime::value::iterator::{iter_done, iter_result};
 use crate::runtime::value::object::JsObject;
 use crate::runtime::value::symbol;
 use crate::runtime::value::{JsValue, NativeFunction};
 
+pub(crate) const MAP_METHODS: &[&str] = &[
+    "clear", "delete", "entries", "forEach", "get", "has", "keys", "set", "values",
+];
+pub(crate) const SET_METHODS: &[&str] = &[
+    "add",
+    "clear",
+    "delete",
+    "difference",
+    "entries",
+    "forEach",
+    "has",
+    "intersection",
+    "isDisjointFrom",
+    "isSubsetOf",
+    "isSupersetOf",
+    "keys",
+    "symmetricDifference",
+    "union",
+    "values",
+];
+
 impl Interpreter {
-    pub(crate) fn eval_new_map(&mut self, args: &[Expr]) -> Result<JsValue, RuntimeError> {
-        let mut map = JsMap::new();
-        if let Some(arg) = args.first() {
-            let iterable = self.eval_expr(arg)?;
-            if !matches!(iterable, JsValue::Null | JsValue::Undefined) {
-                for entry in self.collect_iterable(&iterable)? {
-                    let k = self.get_property(&entry, "0")?;
-                    let v = self.get_property(&entry, "1")?;
+    pub(crate) fn eval_new_collection(
+        &mut self,
+        kind: &str,
+        args: &[Expr],
+    ) -> Result<JsValue, RuntimeError> {
+        let iterable = match args.first() {
+            Some(arg) => self.eval_expr(arg)?,
+            None => JsValue::Undefined,
+        };
+        self.construct_collection(kind, iterable)
+    }
+
+    /// Builds a `Map`, `Set`, `WeakMap` or `WeakSet` from anything
+    /// iterable, including another collection.
+    pub(crate) fn construct_collection(
+        &mut self,
+        kind: &str,
+        iterable: JsValue,
+    ) -> Result<JsValue, RuntimeError> {
+        let items = match iterable {
+            JsValue::Null | JsValue::Undefined => Vec::new(),
+            iterable => self.collect_iterable(&iterable)?,
+        };
+        match kind {
+            "Map" => {
+                let mut map = JsMap::new();
+                for entry in items {
+                    let (k, v) = self.collection_entry(&entry)?;
                     map.set(k, v);
                 }
+                Ok(JsValue::Map(self.heap.alloc_cell(map)))
             }
-        }
-        Ok(JsValue::Map(self.heap.alloc_cell(map)))
-    }
-
-    pub(crate) fn eval_new_set(&mut self, args: &[Expr]) -> Result<JsValue, RuntimeError> {
-        let mut set = JsSet::new();
-        if let Some(arg) = args.first() {
-            let iterable = self.eval_expr(arg)?;
-            if !matches!(iterable, JsValue::Null | JsValue::Undefined) {
-                for elem in self.collect_iterable(&iterable)? {
+            "Set" => {
+                let mut set = JsSet::new();
+                for elem in items {
                     set.add(elem);
                 }
+                Ok(JsValue::Set(self.heap.alloc_cell(set)))
             }
-        }
-        Ok(JsValue::Set(self.heap.alloc_cell(set)))
-    }
-
-    pub(crate) fn eval_new_weak_map(&mut self, args: &[Expr]) -> Result<JsValue, RuntimeError> {
-        let mut wm = JsWeakMap::new();
-        if let Some(arg) = args.first() {
-            let iterable = self.eval_expr(arg)?;
-            if !matches!(iterable, JsValue::Null | JsValue::Undefined) {
-                for entry in self.collect_iterable(&iterable)? {
-                    let key = self.get_property(&entry, "0")?;
+            "WeakMap" => {
+                let mut wm = JsWeakMap::new();
+                for entry in items {
+                    let (key, value) = self.collection_entry(&entry)?;
                     let erased = extract_weak_key(&key).ok_or_else(|| RuntimeError::TypeError {
                         message: "Invalid value used as weak map key".into(),
                     })?;
-                    wm.set(erased, self.get_property(&entry, "1")?);
+                    wm.set(erased, value);
                 }
+                Ok(JsValue::WeakMap(self.heap.alloc_cell(wm)))
             }
-        }
-        Ok(JsValue::WeakMap(self.heap.alloc_cell(wm)))
-    }
-
-    pub(crate) fn eval_new_weak_set(&mut self, args: &[Expr]) -> Result<JsValue, RuntimeError> {
-        let mut ws = JsWeakSet::new();
-        if let Some(arg) = args.first() {
-            let iterable = self.eval_expr(arg)?;
-            if !matches!(iterable, JsValue::Null | JsValue::Undefined) {
-                for elem in self.collect_iterable(&iterable)? {
+            _ => {
+                let mut ws = JsWeakSet::new();
+                for elem in items {
                     let erased =
                         extract_weak_key(&elem).ok_or_else(|| RuntimeError::TypeError {
                             message: "Invalid value used as weak set value".into(),
                         })?;
                     ws.add(erased);
                 }
+                Ok(JsValue::WeakSet(self.heap.alloc_cell(ws)))
             }
         }
-        Ok(JsValue::WeakSet(self.heap.alloc_cell(ws)))
+    }
+
+    fn collection_entry(&mut self, entry: &JsValue) -> Result<(JsValue, JsValue), RuntimeError> {
+        if !matches!(
+            entry,
+            JsValue::Object(_) | JsValue::Array(_) | JsValue::Proxy(_)
+        ) {
+            return Err(
+                self.throw_type_error(&format!("Iterator value {entry} is not an entry object"))
+            );
+        }
+        Ok((
+            self.get_property(entry, "0")?,
+            self.get_property(entry, "1")?,
+        ))
     }
 
     pub(crate) fn call_map_method(
@@ -95,44 +137,23 @@ impl Interpreter {
                 map_gc.borrow_mut().clear();
                 Ok(JsValue::Undefined)
             }
-            "size" => Ok(JsValue::Number(map_gc.borrow().entries.len() as f64)),
-            "keys" => {
-                let keys: Vec<JsValue> = map_gc
-                    .borrow()
-                    .entries
-                    .iter()
-                    .map(|(k, _)| k.clone())
-                    .collect();
-                Ok(self.make_iterator(keys))
-            }
-            "values" => {
-                let vals: Vec<JsValue> = map_gc
-                    .borrow()
-                    .entries
-                    .iter()
-                    .map(|(_, v)| v.clone())
-                    .collect();
-                Ok(self.make_iterator(vals))
-            }
-            "entries" => {
-                let pairs: Vec<JsValue> = map_gc
-                    .borrow()
-                    .entries
-                    .iter()
-                    .map(|(k, v)| {
-                        JsValue::Array(
-                            self.heap
-                                .alloc_cell(JsArray::new(vec![k.clone(), v.clone()])),
-                        )
-                    })
-                    .collect();
-                Ok(self.make_iterator(pairs))
-            }
+            "size" => Ok(JsValue::Number(map_gc.borrow().size() as f64)),
+            "keys" => Ok(self
+                .make_collection_iterator(IteratedCollection::Map(*map_gc), IterationKind::Keys)),
+            "values" => Ok(self
+                .make_collection_iterator(IteratedCollection::Map(*map_gc), IterationKind::Values)),
+            "entries" => Ok(self.make_collection_iterator(
+                IteratedCollection::Map(*map_gc),
+                IterationKind::Entries,
+            )),
             "forEach" => {
                 let cb = arg0();
-                let entries: Vec<(JsValue, JsValue)> = map_gc.borrow().entries.clone();
-                for (k, v) in entries {
-                    self.call_function(&cb, &[v, k])?;
+                let mut iter = JsCollectionIterator::new(
+                    IteratedCollection::Map(*map_gc),
+                    IterationKind::Entries,
+                );
+                while let Some((k, v)) = iter.next_entry() {
+                    self.call_function(&cb, &[v, k, JsValue::Map(*map_gc)])?;
                 }
                 Ok(JsValue::Undefined)
             }
@@ -160,27 +181,27 @@ impl Interpreter {
                 set_gc.borrow_mut().clear();
                 Ok(JsValue::Undefined)
             }
-            "size" => Ok(JsValue::Number(set_gc.borrow().entries.len() as f64)),
-            "keys" | "values" => {
-                let vals: Vec<JsValue> = set_gc.borrow().entries.clone();
-                Ok(self.make_iterator(vals))
-            }
-            "entries" => {
-                let raw: Vec<JsValue> = set_gc.borrow().entries.clone();
-                let pairs: Vec<JsValue> = raw
-                    .into_iter()
-                    .map(|v| JsValue::Array(self.heap.alloc_cell(JsArray::new(vec![v.clone(), v]))))
-                    .collect();
-                Ok(self.make_iterator(pairs))
-            }
+            "size" => Ok(JsValue::Number(set_gc.borrow().size() as f64)),
+            "keys" | "values" => Ok(self
+                .make_collection_iterator(IteratedCollection::Set(*set_gc), IterationKind::Values)),
+            "entries" => Ok(self.make_collection_iterator(
+                IteratedCollection::Set(*set_gc),
+                IterationKind::Entries,
+            )),
             "forEach" => {
                 let cb = arg0();
-                let entries: Vec<JsValue> = set_gc.borrow().entries.clone();
-                for v in entries {
-                    self.call_function(&cb, &[v.clone(), v])?;
+                let mut iter = JsCollectionIterator::new(
+                    IteratedCollection::Set(*set_gc),
+                    IterationKind::Values,
+                );
+                while let Some((k, v)) = iter.next_entry() {
+                    self.call_function(&cb, &[v, k, JsValue::Set(*set_gc)])?;
                 }
                 Ok(JsValue::Undefined)
             }
+            _ if SET_OPERATIONS.contains(&method) => {
+                self.call_set_operation(set_gc, method, &arg0())
+            }
             _ => Err(RuntimeError::TypeError {
                 message: format!("Set.{method} is not a function"),
             }),
@@ -254,27 +275,26 @@ impl Interpreter {
         }
     }
 
-    pub(crate) fn make_iterator(&mut self, items: Vec<JsValue>) -> JsValue {
-        let gen_gc = self.heap.alloc_cell(JsGenerator::from_values(items.into()));
-        let iter_sym = symbol::symbol_iterator();
+    fn make_collection_iterator(
+        &mut self,
+        collection: IteratedCollection,
+        kind: IterationKind,
+    ) -> JsValue {
+        let iter_gc = self
+            .heap
+            .alloc_cell(JsCollectionIterator::new(collection, kind));
         let mut obj = JsObject::new();
+        obj.prototype = Some(self.prototypes.iterator);
         obj.set(
-            "next".into(),
+            "next",
             JsValue::NativeFunction {
                 name: "next".into(),
-                handler: NativeFunction::GeneratorNext(gen_gc),
-            },
-        );
-        obj.set(
-            "return".into(),
-            JsValue::NativeFunction {
-                name: "return".into(),
-                handler: NativeFunction::GeneratorReturn(gen_gc),
+                handler: NativeFunction::CollectionIteratorNext(iter_gc),
             },
         );
         let obj_gc = self.heap.alloc_cell(obj);
         obj_gc.borrow_mut().set_symbol(
-            iter_sym,
+            symbol::symbol_iterator(),
             JsValue::NativeFunction {
                 name: "[Symbol.iterator]".into(),
                 handler: NativeFunction::GeneratorIterator,
@@ -282,4 +302,31 @@ impl Interpreter {
         );
         JsValue::Object(obj_gc)
     }
+
+    pub(crate) fn step_collection_iterator(
+        &mut self,
+        iter: &Gc<GcCell<JsCollectionIterator>>,
+    ) -> Result<JsValue, RuntimeError> {
+        let item = self.next_collection_item(&mut iter.borrow_mut());
+        Ok(match item {
+            Some(item) => iter_result(item, false, &mut self.heap),
+            None => iter_done(&mut self.heap),
+        })
+    }
+
+    /// The next key, value or `[key, value]` entry of a Map or Set
+    /// iterator, or `None` once it is exhausted.
+    pub(crate) fn next_collection_item(
+        &mut self,
+        iter: &mut JsCollectionIterator,
+    ) -> Option<JsValue> {
+        let (key, value) = iter.next_entry()?;
+        Some(match iter.kind {
+            IterationKind::Keys => key,
+            IterationKind::Values => value,
+            IterationKind::Entries => {
+                JsValue::Array(self.heap.alloc_cell(JsArray::new(vec![key, value])))
+            }
+        })
+    }
 }
@@ -1,19 +1,27 @@
 use crate::errors::RuntimeError;
 use crate::runtime::interpreter::Interpreter;
+use crate::runtime::report::{ConsoleLevel, RunEvent};
 use crate::runtime::value::JsValue;
 
 impl Interpreter {
     pub(crate) fn builtin_console_log(
         &mut self,
+        method: &str,
         args: &[JsValue],
     ) -> Result<JsValue, RuntimeError> {
         let line = args
             .iter()
-            .map(|v| v.to_string())
+            .map(|v| v.error_chain().unwrap_or_else(|| v.to_string()))
             .collect::<Vec<_>>()
             .join(" ");
-        println!("{line}");
-        self.output.push(line);
+        if self.run_events.is_some() {
+            let level = ConsoleLevel::from_method(method).unwrap_or(ConsoleLevel::Log);
+            self.record_event(RunEvent::ConsoleLog {
+                level,
+                text: line.clone(),
+            });
+        }
+        self.output_mode.write_line(line, &mut self.output);
         Ok(JsValue::Undefined)
     }
 }
@@ -0,0 +1,185 @@
+use crate::embedding::class_builder::ClassBuilder;
+use crate::embedding::event_target::{EventTarget, LISTENERS_SLOT, listener_store};
+use crate::errors::RuntimeError;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::object::{JsObject, Property};
+use crate::runtime::value::{JsValue, NativeFunction};
+
+impl Interpreter {
+    /// Registers the built-in `EventTarget` native class. Its instances,
+    /// and those of host classes that inherit from it, get the listener
+    /// methods from the shared `EventTarget.prototype`.
+    pub(crate) fn init_event_target_class(&mut self) {
+        self.native_classes.insert(
+            "EventTarget".into(),
+            ClassBuilder::new("EventTarget").build(),
+        );
+        let ctor = JsValue::NativeFunction {
+            name: "EventTarget".into(),
+            handler: NativeFunction::NativeClassConstructor("EventTarget".into()),
+        };
+        let methods = [
+            ("addEventListener", NativeFunction::EventTargetAdd),
+            ("removeEventListener", NativeFunction::EventTargetRemove),
+            ("dispatchEvent", NativeFunction::EventTargetDispatch),
+        ]
+        .map(|(name, handler)| {
+            let method = JsValue::NativeFunction {
+                name: name.into(),
+                handler,
+            };
+            (name, method)
+        });
+        let mut proto = self.prototypes.event_target.borrow_mut();
+        for (name, value) in [("constructor", ctor.clone())].into_iter().chain(methods) {
+            proto.properties.insert(
+                name.into(),
+                Property {
+                    enumerable: false,
+                    ..Property::new(value)
+                },
+            );
+        }
+        drop(proto);
+        self.def_global("EventTarget", ctor);
+    }
+
+    pub(crate) fn native_class_extends(&self, class_name: &str, ancestor: &str) -> bool {
+        let mut current = Some(class_name);
+        while let Some(name) = current {
+            if name == ancestor {
+                return true;
+            }
+            current = self
+                .native_classes
+                .get(name)
+                .and_then(|def| def.parent.as_deref());
+        }
+        false
+    }
+
+    /// Makes `object` an event target: it inherits the listener methods
+    /// from `EventTarget.prototype` and gets a fresh listener store.
+    pub(crate) fn attach_event_target(
+        &mut self,
+        object: Gc<GcCell<JsObject>>,
+    ) -> Gc<GcCell<EventTarget>> {
+        let target = self.heap.alloc_cell(EventTarget::new());
+        let mut obj = object.borrow_mut();
+        obj.prototype = Some(self.prototypes.event_target);
+        obj.properties.insert(
+            LISTENERS_SLOT.into(),
+            Property {
+                enumerable: false,
+                ..Property::new(JsValue::NativeFunction {
+                    name: LISTENERS_SLOT.into(),
+                    handler: NativeFunction::EventTargetListeners(target),
+                })
+            },
+        );
+        target
+    }
+
+    /// The listener store of the `this` an `EventTarget.prototype` method
+    /// was called with.
+    pub(crate) fn event_target_of(
+        &self,
+        this: Option<&JsValue>,
+    ) -> Result<Gc<GcCell<EventTarget>>, RuntimeError> {
+        this.and_then(listener_store)
+            .ok_or_else(|| RuntimeError::TypeError {
+                message: "EventTarget method called on an object that is not an EventTarget".into(),
+            })
+    }
+
+    pub(crate) fn event_target_add(
+        &mut self,
+        target: &Gc<GcCell<EventTarget>>,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let event_type = args.first().cloned().unwrap_or(JsValue::Undefined);
+        let callback = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+        if !matches!(
+            callback,
+            JsValue::Function { .. } | JsValue::NativeFunction { .. }
+        ) {
+            return Ok(JsValue::Undefined);
+        }
+        let once = match args.get(2) {
+            Some(options @ JsValue::Object(_)) => self.get_property(options, "once")?.to_boolean(),
+            _ => false,
+        };
+        target.borrow_mut().add_event_listener_with_options(
+            event_type.to_js_string(),
+            callback,
+            once,
+        );
+        Ok(JsValue::Undefined)
+    }
+
+    pub(crate) fn event_target_remove(
+        &mut self,
+        target: &Gc<GcCell<EventTarget>>,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let event_type = args.first().cloned().unwrap_or(JsValue::Undefined);
+        let callback = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+        target
+            .borrow_mut()
+            .remove_event_listener(&event_type.to_js_string(), &callback);
+        Ok(JsValue::Undefined)
+    }
+
+    pub(crate) fn event_target_dispatch(
+        &mut self,
+        target: &Gc<GcCell<EventTarget>>,
+        this: Option<JsValue>,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let Some(event @ JsValue::Object(event_obj)) = args.first() else {
+            return Err(RuntimeError::TypeError {
+                message: "dispatchEvent requires an event object".into(),
+            });
+        };
+        let this = this.unwrap_or(JsValue::Undefined);
+        let event_type = self.get_property(event, "type")?.to_js_string();
+        event_obj.borrow_mut().set("target", this.clone());
+        self.dispatch_to_listeners(target, this, &event_type, event.clone())?;
+        Ok(JsValue::Boolean(true))
+    }
+
+    /// Calls each listener registered for `event_type` in registration
+    /// order. A throwing listener does not stop the rest: its error goes
+    /// to the error hook, or, without one, the first error is returned
+    /// once every listener has run.
+    pub(crate) fn dispatch_to_listeners(
+        &mut self,
+        target: &Gc<GcCell<EventTarget>>,
+        this: JsValue,
+        event_type: &str,
+        event: JsValue,
+    ) -> Result<(), RuntimeError> {
+        let listeners = target.borrow_mut().begin_dispatch(event_type);
+        let mut first_error = None;
+        for (callback, once) in listeners {
+            if !once && !target.borrow().has_listener(event_type, &callback) {
+                continue;
+            }
+            let result = self.call_function_with_this(
+                &callback,
+                std::slice::from_ref(&event),
+                Some(this.clone()),
+            );
+            if let Err(err) = result
+                && let Err(err) = self.report_error(err)
+            {
+                first_error.get_or_insert(err);
+            }
+        }
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
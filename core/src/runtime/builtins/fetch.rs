@@ -0,0 +1,171 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::embedding::promise_resolver::PromiseResolver;
+use crate::errors::RuntimeError;
+use crate::runtime::fetch::{FetchRequest, FetchedResponse};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::{JsValue, NativeFunction};
+
+impl Interpreter {
+    /// `fetch(url, options)`. The request starts on a worker thread and
+    /// the returned promise settles from the event loop once it is done.
+    pub(crate) fn call_fetch(&mut self, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
+        let request = self.fetch_request(args)?;
+        let (promise, resolver) = self.create_resolvable_promise();
+        match self.fetches.policy.check(&request.url) {
+            Ok(()) => self.fetches.start(request, resolver),
+            Err(cause) => self.reject_fetch(&resolver, &cause)?,
+        }
+        Ok(promise)
+    }
+
+    /// Reads the URL and the `method`, `headers` and `body` options. Header
+    /// values and the body are converted to strings.
+    fn fetch_request(&mut self, args: &[JsValue]) -> Result<FetchRequest, RuntimeError> {
+        let mut request = FetchRequest {
+            url: args.first().map(JsValue::to_js_string).unwrap_or_default(),
+            method: "GET".to_string(),
+            headers: Vec::new(),
+            body: None,
+        };
+        let Some(options @ JsValue::Object(_)) = args.get(1) else {
+            return Ok(request);
+        };
+        let method = self.get_property(options, "method")?;
+        if !matches!(method, JsValue::Undefined) {
+            request.method = method.to_js_string().to_ascii_uppercase();
+        }
+        let headers = self.get_property(options, "headers")?;
+        if let JsValue::Object(_) = headers
+            && let JsValue::Array(names) = self.object_keys(std::slice::from_ref(&headers))?
+        {
+            let names = names.borrow().elements.clone();
+            for name in names {
+                let name = name.to_js_string();
+                let value = self.get_property(&headers, &name)?;
+                request.headers.push((name, value.to_js_string()));
+            }
+        }
+        let body = self.get_property(options, "body")?;
+        if !matches!(body, JsValue::Undefined | JsValue::Null) {
+            request.body = Some(body.to_js_string());
+        }
+        Ok(request)
+    }
+
+    pub(crate) fn has_pending_fetches(&self) -> bool {
+        self.fetches.has_pending()
+    }
+
+    /// Settles the promise of every fetch that has finished, waiting up to
+    /// `timeout` for the first one (`None` for as long as it takes).
+    pub(crate) fn settle_finished_fetches(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> Result<(), RuntimeError> {
+        let mut wait = timeout;
+        while let Some((resolver, result)) = self.fetches.next_finished(wait) {
+            match result {
+                Ok(response) => {
+                    let response = self.create_fetch_response(response);
+                    resolver.settle_in(self, false, response)?;
+                }
+                Err(cause) => self.reject_fetch(&resolver, &cause)?,
+            }
+            wait = Some(Duration::ZERO);
+        }
+        Ok(())
+    }
+
+    /// Rejects with `TypeError: fetch failed: <cause>`, keeping the cause
+    /// on the error as well.
+    fn reject_fetch(
+        &mut self,
+        resolver: &PromiseResolver,
+        cause: &str,
+    ) -> Result<(), RuntimeError> {
+        let error = self.create_typed_error_object("TypeError", &format!("fetch failed: {cause}"));
+        if let JsValue::Object(obj) = &error {
+            obj.borrow_mut().set("cause", JsValue::String(cause.into()));
+        }
+        resolver.settle_in(self, true, error)
+    }
+
+    fn create_fetch_response(&mut self, response: FetchedResponse) -> JsValue {
+        let mut headers = JsObject::new();
+        headers.set(
+            "get",
+            JsValue::NativeFunction {
+                name: "get".to_string(),
+                handler: NativeFunction::FetchHeadersGet(response.headers.into()),
+            },
+        );
+        let body: Rc<str> = response.body.into();
+        let mut object = JsObject::new();
+        object.set("url", JsValue::String(response.url.into()));
+        object.set("status", JsValue::Number(response.status as f64));
+        object.set("statusText", JsValue::String(response.status_text.into()));
+        object.set(
+            "ok",
+            JsValue::Boolean((200..300).contains(&response.status)),
+        );
+        object.set("headers", JsValue::Object(self.heap.alloc_cell(headers)));
+        object.set(
+            "text",
+            JsValue::NativeFunction {
+                name: "text".to_string(),
+                handler: NativeFunction::FetchResponseText(Rc::clone(&body)),
+            },
+        );
+        object.set(
+            "json",
+            JsValue::NativeFunction {
+                name: "json".to_string(),
+                handler: NativeFunction::FetchResponseJson(body),
+            },
+        );
+        JsValue::Object(self.heap.alloc_cell(object))
+    }
+
+    /// `headers.get(name)`, `response.text()` and `response.json()`.
+    /// The body is already read, so the promises the last two return are
+    /// settled from the start.
+    pub(crate) fn call_fetch_response_method(
+        &mut self,
+        handler: &NativeFunction,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        match handler {
+            NativeFunction::FetchHeadersGet(headers) => {
+                let name = args
+                    .first()
+                    .map(JsValue::to_js_string)
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                Ok(headers
+                    .iter()
+                    .find(|(header, _)| *header == name)
+                    .map_or(JsValue::Null, |(_, value)| {
+                        JsValue::String(value.as_str().into())
+                    }))
+            }
+            NativeFunction::FetchResponseText(body) => {
+                let text = JsValue::String(body.as_ref().into());
+                self.builtin_promise_static("resolve", &[text])
+            }
+            NativeFunction::FetchResponseJson(body) => {
+                let text = JsValue::String(body.as_ref().into());
+                match self.builtin_json_call("parse", &[text]) {
+                    Ok(value) => self.builtin_promise_static("resolve", &[value]),
+                    Err(err) => {
+                        let reason = self.error_to_value(err);
+                        self.builtin_promise_static("reject", &[reason])
+                    }
+                }
+            }
+            _ => unreachable!("not a fetch response method"),
+        }
+    }
+}
@@ -1,17 +1,21 @@
-use super::number::parse_int;
-use crate::embedding::function_args::FunctionArgs;
 use crate::errors::RuntimeError;
 use crate::runtime::event_loop::Microtask;
+use crate::runtime::gc::Gc;
 use crate::runtime::interpreter::Interpreter;
+use crate::runtime::modules::resolver;
 use crate::runtime::value::array::JsArray;
+use crate::runtime::value::generator::GeneratorResume;
 use crate::runtime::value::object::JsObject;
+use crate::runtime::value::typed_array::BINARY_CONSTRUCTORS;
 use crate::runtime::value::{JsValue, NativeFunction};
 impl Interpreter {
     pub(crate) fn init_builtins(&mut self) {
-        self.env.define("NaN".into(), JsValue::Number(f64::NAN));
         self.env
-            .define("Infinity".into(), JsValue::Number(f64::INFINITY));
-        self.env.define("undefined".into(), JsValue::Undefined);
+            .define_builtin("NaN", JsValue::Number(f64::NAN), false);
+        self.env
+            .define_builtin("Infinity", JsValue::Number(f64::INFINITY), false);
+        self.env
+            .define_builtin("undefined", JsValue::Undefined, false);
         self.def_native("isNaN", NativeFunction::IsNaN);
         self.def_native("isFinite", NativeFunction::IsFinite);
         self.def_native("parseInt", NativeFunction::ParseInt);
@@ -33,9 +37,16 @@ impl Interpreter {
             "RangeError",
             "URIError",
             "EvalError",
+            "AggregateError",
         ] {
             self.def_native(kind, NativeFunction::ErrorCtor(kind.to_string()));
         }
+        for kind in ["Map", "Set", "WeakMap", "WeakSet"] {
+            self.def_native(kind, NativeFunction::CollectionCtor(kind.to_string()));
+        }
+        for kind in BINARY_CONSTRUCTORS {
+            self.def_native(kind, NativeFunction::BinaryCtor(kind.to_string()));
+        }
         self.def_native("setTimeout", NativeFunction::SetTimeout);
         self.def_native("setInterval", NativeFunction::SetInterval);
         self.def_native("clearTimeout", NativeFunction::ClearTimeout);
@@ -46,24 +57,71 @@ impl Interpreter {
         );
         self.def_native("cancelAnimationFrame", NativeFunction::CancelAnimationFrame);
         self.def_native("queueMicrotask", NativeFunction::QueueMicrotask);
+        self.def_native("structuredClone", NativeFunction::StructuredClone);
+        #[cfg(feature = "http")]
+        self.def_native("fetch", NativeFunction::Fetch);
+        self.init_prototypes();
+        self.init_event_target_class();
         self.init_math_object();
-        self.env.define(
-            "Reflect".into(),
-            JsValue::Object(self.heap.alloc_cell(JsObject::new())),
+        self.def_native("Promise", NativeFunction::PromiseCtor);
+        self.def_native("Proxy", NativeFunction::ProxyCtor);
+        self.def_method_object("JSON", &["parse", "stringify"], NativeFunction::JsonMethod);
+        self.def_method_object(
+            "console",
+            &["log", "info", "warn", "error", "debug"],
+            NativeFunction::ConsoleMethod,
         );
+        let mut performance = JsObject::new();
+        performance.set(
+            "now",
+            JsValue::NativeFunction {
+                name: "now".into(),
+                handler: NativeFunction::PerformanceNow,
+            },
+        );
+        let performance = JsValue::Object(self.heap.alloc_cell(performance));
+        self.def_global("performance", performance);
+        let reflect = JsValue::Object(self.heap.alloc_cell(JsObject::new()));
+        self.def_global("Reflect", reflect);
         let global_val = JsValue::Object(self.heap.alloc_cell(JsObject::new()));
-        self.env.define("globalThis".into(), global_val.clone());
+        self.def_global("globalThis", global_val.clone());
         self.env.set_global_this(global_val);
     }
+    /// Binds a builtin in the global scope as a `var`, so it also reads
+    /// as a property of `globalThis`.
+    pub(crate) fn def_global(&mut self, name: &str, value: JsValue) {
+        self.env.define_builtin(name, value, true);
+    }
     fn def_native(&mut self, name: &str, handler: NativeFunction) {
-        self.env.define(
-            name.into(),
+        self.def_global(
+            name,
             JsValue::NativeFunction {
                 name: name.into(),
                 handler,
             },
         );
     }
+    /// Binds `name` to an object whose methods dispatch to `handler`
+    /// by method name, like `JSON` and `console`.
+    fn def_method_object(
+        &mut self,
+        name: &str,
+        methods: &[&str],
+        handler: fn(String) -> NativeFunction,
+    ) {
+        let mut object = JsObject::new();
+        for &method in methods {
+            object.set(
+                method,
+                JsValue::NativeFunction {
+                    name: method.into(),
+                    handler: handler(method.into()),
+                },
+            );
+        }
+        let object = JsValue::Object(self.heap.alloc_cell(object));
+        self.def_global(name, object);
+    }
     fn init_math_object(&mut self) {
         use std::f64::consts;
         let mut math = JsObject::new();
@@ -78,7 +136,7 @@ impl Interpreter {
             ("SQRT1_2", consts::FRAC_1_SQRT_2),
         ];
         for (name, val) in constants {
-            math.set(name.into(), JsValue::Number(val));
+            math.set(name, JsValue::Number(val));
         }
         let methods = [
             "abs", "floor", "ceil", "round", "trunc", "sqrt", "cbrt", "exp", "log", "log2",
@@ -87,15 +145,15 @@ impl Interpreter {
         ];
         for m in methods {
             math.set(
-                m.into(),
+                m,
                 JsValue::NativeFunction {
                     name: m.into(),
                     handler: NativeFunction::MathMethod(m.into()),
                 },
             );
         }
-        self.env
-            .define("Math".into(), JsValue::Object(self.heap.alloc_cell(math)));
+        let math = JsValue::Object(self.heap.alloc_cell(math));
+        self.def_global("Math", math);
     }
     pub(crate) fn call_native_function(
         &mut self,
@@ -112,6 +170,10 @@ impl Interpreter {
                 let val = args.first().cloned().unwrap_or(JsValue::Undefined);
                 self.settle_promise(p, true, val)
             }
+            NativeFunction::PromiseAnyReject(state, index) => {
+                let reason = args.first().cloned().unwrap_or(JsValue::Undefined);
+                self.promise_any_reject(state, *index, reason)
+            }
             NativeFunction::SetTimeout => self.schedule_timer(args, false),
             NativeFunction::SetInterval => self.schedule_timer(args, true),
             NativeFunction::ClearTimeout | NativeFunction::ClearInterval => {
@@ -158,9 +220,61 @@ impl Interpreter {
                     .enqueue_microtask(Microtask::Callback { callback: cb });
                 Ok(JsValue::Undefined)
             }
+            NativeFunction::EvalScript => {
+                let source = args
+                    .first()
+                    .cloned()
+                    .unwrap_or(JsValue::Undefined)
+                    .to_js_string();
+                self.eval_script(&source)
+            }
+            NativeFunction::StructuredClone => {
+                self.deep_clone(args.first().unwrap_or(&JsValue::Undefined))
+            }
+            #[cfg(feature = "http")]
+            NativeFunction::Fetch => self.call_fetch(args),
+            #[cfg(feature = "http")]
+            NativeFunction::FetchHeadersGet(_)
+            | NativeFunction::FetchResponseText(_)
+            | NativeFunction::FetchResponseJson(_) => {
+                self.call_fetch_response_method(handler, args)
+            }
+            NativeFunction::ImportMetaResolve(module) => {
+                let specifier = args
+                    .first()
+                    .cloned()
+                    .unwrap_or(JsValue::Undefined)
+                    .to_js_string();
+                let path = self.resolve_module_path(&specifier, module)?;
+                Ok(JsValue::String(resolver::file_url(&path).into()))
+            }
+            NativeFunction::CollectGarbage => {
+                self.gc_requested = true;
+                Ok(JsValue::Undefined)
+            }
+            NativeFunction::DetachArrayBuffer => match args.first() {
+                Some(JsValue::ArrayBuffer(buffer)) => {
+                    buffer.borrow_mut().detach();
+                    Ok(JsValue::Null)
+                }
+                _ => Err(self.throw_type_error("detachArrayBuffer requires an ArrayBuffer")),
+            },
+            NativeFunction::FunctionToString => match this {
+                Some(func @ (JsValue::Function { .. } | JsValue::NativeFunction { .. })) => {
+                    Ok(JsValue::String(func.to_js_string().into()))
+                }
+                // Function.prototype is itself a function in the spec, if
+                // not in this engine.
+                Some(JsValue::Object(obj)) if Gc::ptr_eq(obj, self.prototypes.function) => {
+                    Ok(JsValue::String("function () { [native code] }".into()))
+                }
+                _ => Err(self.throw_type_error(
+                    "Function.prototype.toString requires that 'this' be a Function",
+                )),
+            },
             NativeFunction::SymbolConstructor => {
                 let desc = args.first().and_then(|v| match v {
-                    JsValue::String(s) => Some(s.clone()),
+                    JsValue::String(s) => Some(s.to_string()),
                     JsValue::Undefined => None,
                     other => Some(other.to_js_string()),
                 });
@@ -170,31 +284,81 @@ impl Interpreter {
             }
             NativeFunction::Host(cb) => {
                 let this_val = this.unwrap_or(JsValue::Undefined);
-                cb.call(FunctionArgs::new(this_val, args.to_vec()))
+                self.call_host(cb, this_val, args)
             }
-            NativeFunction::GeneratorNext(gc_gen) => {
-                let gc_gen = *gc_gen;
-                self.step_generator(&gc_gen)
+            NativeFunction::HostRef(name) => {
+                let Some(cb) = self.host_functions.get(name).cloned() else {
+                    return Err(self.throw_type_error(&format!(
+                        "host function '{name}' was not registered again after restoring a snapshot"
+                    )));
+                };
+                let this_val = this.unwrap_or(JsValue::Undefined);
+                self.call_host(&cb, this_val, args)
             }
-            NativeFunction::GeneratorReturn(gc_gen) => {
-                let gc_gen = *gc_gen;
+            NativeFunction::GeneratorNext(gc_gen)
+            | NativeFunction::GeneratorReturn(gc_gen)
+            | NativeFunction::GeneratorThrow(gc_gen) => {
                 let val = args.first().cloned().unwrap_or(JsValue::Undefined);
-                let mut g = gc_gen.borrow_mut();
-                g.state = crate::runtime::value::generator::GeneratorState::Completed;
-                g.yielded_values.clear();
-                g.return_value = val.clone();
-                drop(g);
+                let resume = match handler {
+                    NativeFunction::GeneratorNext(_) => GeneratorResume::Next(val),
+                    NativeFunction::GeneratorReturn(_) => GeneratorResume::Return(val),
+                    _ => GeneratorResume::Throw(val),
+                };
+                let (value, done) = self.resume_generator(gc_gen, resume)?;
                 Ok(crate::runtime::value::iterator::iter_result(
-                    val,
-                    true,
+                    value,
+                    done,
                     &mut self.heap,
                 ))
             }
-            NativeFunction::GeneratorThrow => {
+            NativeFunction::AsyncGeneratorNext(gc_gen)
+            | NativeFunction::AsyncGeneratorReturn(gc_gen)
+            | NativeFunction::AsyncGeneratorThrow(gc_gen) => {
+                let val = args.first().cloned().unwrap_or(JsValue::Undefined);
+                let resume = match handler {
+                    NativeFunction::AsyncGeneratorNext(_) => GeneratorResume::Next(val),
+                    NativeFunction::AsyncGeneratorReturn(_) => GeneratorResume::Return(val),
+                    _ => GeneratorResume::Throw(val),
+                };
+                self.enqueue_async_generator_request(gc_gen, resume)
+            }
+            NativeFunction::AsyncFunctionResume(function) => {
+                let val = args.first().cloned().unwrap_or(JsValue::Undefined);
+                self.resume_async_function(function, GeneratorResume::Next(val))
+            }
+            NativeFunction::AsyncFunctionThrow(function) => {
                 let val = args.first().cloned().unwrap_or(JsValue::Undefined);
-                Err(RuntimeError::Thrown { value: val })
+                self.resume_async_function(function, GeneratorResume::Throw(val))
             }
             NativeFunction::GeneratorIterator => Ok(this.unwrap_or(JsValue::Undefined)),
+            NativeFunction::EventTargetAdd => {
+                let target = self.event_target_of(this.as_ref())?;
+                self.event_target_add(&target, args)
+            }
+            NativeFunction::EventTargetRemove => {
+                let target = self.event_target_of(this.as_ref())?;
+                self.event_target_remove(&target, args)
+            }
+            NativeFunction::EventTargetDispatch => {
+                let target = self.event_target_of(this.as_ref())?;
+                self.event_target_dispatch(&target, this, args)
+            }
+            NativeFunction::EventTargetListeners(_) => Err(RuntimeError::TypeError {
+                message: "event listener store is not a function".into(),
+            }),
+            NativeFunction::CollectionIteratorNext(iter) => {
+                let iter = *iter;
+                self.step_collection_iterator(&iter)
+            }
+            NativeFunction::IteratorMethod(method) => self.call_iterator_method(this, method, args),
+            NativeFunction::IteratorHelperNext(helper) => {
+                let helper = *helper;
+                self.step_iterator_helper(&helper)
+            }
+            NativeFunction::IteratorHelperReturn(helper) => {
+                let helper = *helper;
+                self.close_iterator_helper(&helper)
+            }
             NativeFunction::NativeClassConstructor(name) => {
                 self.construct_native_class(name, args, this)
             }
@@ -216,86 +380,79 @@ impl Interpreter {
                     .to_number()
                     .is_finite(),
             )),
-            NativeFunction::ParseInt => {
-                let s = args
-                    .first()
-                    .cloned()
-                    .unwrap_or(JsValue::Undefined)
-                    .to_js_string();
-                let radix = args.get(1).map(|v| v.to_number() as i32).unwrap_or(0);
-                Ok(JsValue::Number(parse_int(&s, radix)))
-            }
-            NativeFunction::ParseFloat => {
-                let s = args
-                    .first()
-                    .cloned()
-                    .unwrap_or(JsValue::Undefined)
-                    .to_js_string();
-                Ok(JsValue::Number(s.trim().parse::<f64>().unwrap_or(f64::NAN)))
-            }
-            NativeFunction::NumberCtor => Ok(JsValue::Number(
-                args.first()
-                    .cloned()
-                    .unwrap_or(JsValue::Number(0.0))
-                    .to_number(),
-            )),
+            NativeFunction::ParseInt => self.builtin_number_static("parseInt", args),
+            NativeFunction::ParseFloat => self.builtin_number_static("parseFloat", args),
+            NativeFunction::NumberCtor => Ok(JsValue::Number(match args.first() {
+                Some(value) => self.coerce_to_number(value)?,
+                None => 0.0,
+            })),
             NativeFunction::BooleanCtor => Ok(JsValue::Boolean(
                 args.first()
                     .cloned()
                     .unwrap_or(JsValue::Undefined)
                     .to_boolean(),
             )),
-            NativeFunction::StringCtor => Ok(JsValue::String(
-                args.first()
-                    .cloned()
-                    .unwrap_or(JsValue::String(String::new()))
-                    .to_js_string(),
-            )),
+            NativeFunction::StringCtor => Ok(JsValue::String(match args.first() {
+                Some(JsValue::Symbol(sym)) => sym.to_string().into(),
+                Some(value) => self.coerce_to_string(value)?.into(),
+                None => "".into(),
+            })),
             NativeFunction::ObjectCtor => {
                 let val = args.first().cloned().unwrap_or(JsValue::Undefined);
-                match val {
-                    JsValue::Object(_) => Ok(val),
-                    _ => Ok(JsValue::Object(self.heap.alloc_cell(JsObject::new()))),
-                }
+                Ok(self.object_from_value(val))
+            }
+            NativeFunction::CollectionCtor(kind) | NativeFunction::BinaryCtor(kind) => {
+                Err(self.throw_type_error(&format!("Constructor {kind} requires 'new'")))
             }
             NativeFunction::ErrorCtor(kind) => {
-                let msg = args
-                    .first()
-                    .cloned()
-                    .unwrap_or(JsValue::Undefined)
-                    .to_js_string();
-                let mut obj = JsObject::new();
-                obj.set("name".into(), JsValue::String(kind.clone()));
-                obj.set("message".into(), JsValue::String(msg));
-                let constructor = self.env.get(kind).unwrap_or(JsValue::Undefined);
-                obj.set("constructor".into(), constructor);
-                obj.set("[[ErrorType]]".into(), JsValue::String(kind.clone()));
-                Ok(JsValue::Object(self.heap.alloc_cell(obj)))
+                let kind = kind.clone();
+                self.construct_error(&kind, args)
             }
             NativeFunction::MathMethod(method) => {
                 let m = method.clone();
                 self.builtin_math_call(&m, args)
             }
+            NativeFunction::StringStatic(method) => {
+                let m = method.clone();
+                self.builtin_string_static(&m, args)
+            }
+            NativeFunction::PromiseCtor => {
+                Err(self.throw_type_error("Constructor Promise requires 'new'"))
+            }
+            NativeFunction::PromiseStatic(method) => {
+                let m = method.clone();
+                self.builtin_promise_static(&m, args)
+            }
+            NativeFunction::ProxyCtor => {
+                Err(self.throw_type_error("Constructor Proxy requires 'new'"))
+            }
+            NativeFunction::ProxyRevocable => self.builtin_proxy_revocable(args),
+            NativeFunction::JsonMethod(method) => {
+                let m = method.clone();
+                self.builtin_json_call(&m, args)
+            }
+            NativeFunction::ConsoleMethod(method) => {
+                let m = method.clone();
+                self.builtin_console_log(&m, args)
+            }
+            NativeFunction::PerformanceNow => Ok(self.builtin_performance_now()),
             NativeFunction::DateCtor => {
                 Ok(JsValue::String("Thu Jan 01 1970 00:00:00 GMT+0000".into()))
             }
             NativeFunction::RegExpCtor => {
-                let pattern = args
-                    .first()
-                    .cloned()
-                    .unwrap_or(JsValue::String(String::new()));
+                let pattern = args.first().cloned().unwrap_or(JsValue::String("".into()));
                 let flags_str = args.get(1).map(|v| v.to_js_string()).unwrap_or_default();
                 let flags = crate::runtime::value::regexp::RegExpFlags::from_str(&flags_str)
                     .map_err(|e| RuntimeError::TypeError { message: e })?;
                 let re =
                     crate::runtime::value::regexp::JsRegExp::new(&pattern.to_js_string(), flags)
-                        .map_err(|e| RuntimeError::TypeError { message: e })?;
+                        .map_err(|e| self.throw_syntax_error(&e))?;
                 Ok(JsValue::RegExp(self.heap.alloc_cell(re)))
             }
             NativeFunction::FunctionCtor => Ok(JsValue::Function {
                 name: "anonymous".into(),
-                params: vec![],
-                body: vec![],
+                params: Vec::new().into(),
+                body: Vec::new().into(),
                 closure_env: self.env.capture(),
                 is_async: false,
                 is_generator: false,
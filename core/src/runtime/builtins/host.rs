@@ -0,0 +1,69 @@
+use crate::errors::RuntimeError;
+use crate::parser::ast::Stmt;
+use crate::runtime::interpreter::{ControlFlow, Interpreter};
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::{JsValue, NativeFunction};
+
+impl Interpreter {
+    /// Builds the `$262` host object test262 tests use to reach into the
+    /// embedder: `global`, `evalScript` and `gc`.
+    pub(crate) fn create_test262_host(&mut self) -> JsValue {
+        let global = self.env.get("globalThis").unwrap_or(JsValue::Undefined);
+        let mut host = JsObject::new();
+        host.set("global", global.clone());
+        for (name, handler) in [
+            ("evalScript", NativeFunction::EvalScript),
+            ("gc", NativeFunction::CollectGarbage),
+            ("detachArrayBuffer", NativeFunction::DetachArrayBuffer),
+        ] {
+            host.set(
+                name,
+                JsValue::NativeFunction {
+                    name: name.into(),
+                    handler,
+                },
+            );
+        }
+        let host = JsValue::Object(self.heap.alloc_cell(host));
+        if let JsValue::Object(global) = &global {
+            global.borrow_mut().set("$262", host.clone());
+        }
+        host
+    }
+
+    /// Evaluates `source` as a separate script in the global scope and
+    /// returns the value of its last expression statement.
+    pub(crate) fn eval_script(&mut self, source: &str) -> Result<JsValue, RuntimeError> {
+        let program = crate::lexer::lex(source)
+            .and_then(|tokens| self.parse_tokens(tokens, source))
+            .map_err(|err| {
+                RuntimeError::thrown(self.create_typed_error_object("SyntaxError", &err.message))
+            })?;
+
+        let mut global_scope = self.env.capture();
+        global_scope.truncate(1);
+        let saved = self.env.replace_scopes(global_scope);
+        self.hoist_declarations(&program.body);
+        let mut completion = JsValue::Undefined;
+        let mut result = Ok(());
+        for stmt in &program.body {
+            let step = match stmt {
+                Stmt::ExprStmt(expr) => self.eval_expr(expr).map(|value| {
+                    completion = value;
+                    ControlFlow::None
+                }),
+                _ => self.eval_stmt(stmt),
+            };
+            match step {
+                Ok(ControlFlow::None) => {}
+                Ok(_) => break,
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+        self.env.replace_scopes(saved);
+        result.map(|()| completion)
+    }
+}
@@ -0,0 +1,395 @@
+use crate::errors::RuntimeError;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::iterator::{
+    FlatMapInner, HelperKind, IteratorRecord, JsIteratorHelper, iter_done, iter_result,
+};
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::{JsValue, NativeFunction, symbol};
+
+/// The helpers every engine iterator inherits from the realm's iterator
+/// prototype.
+pub(crate) const ITERATOR_METHODS: &[&str] = &[
+    "drop", "every", "filter", "find", "flatMap", "forEach", "map", "reduce", "some", "take",
+    "toArray",
+];
+
+impl Interpreter {
+    /// Runs one of [`ITERATOR_METHODS`] with `this` as the iterator.
+    /// `map`, `filter`, `take`, `drop` and `flatMap` return a lazy helper
+    /// iterator; the rest consume `this` right away.
+    pub(crate) fn call_iterator_method(
+        &mut self,
+        this: Option<JsValue>,
+        method: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let iterator = match this {
+            Some(value @ JsValue::Object(_)) => value,
+            _ => {
+                return Err(self.throw_type_error(&format!(
+                    "Iterator.prototype.{method} called on a non-object"
+                )));
+            }
+        };
+        let next = self.get_property(&iterator, "next")?;
+        let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
+        let kind = match method {
+            "map" => HelperKind::Map(self.iterator_callback(&iterator, method, arg)?),
+            "filter" => HelperKind::Filter(self.iterator_callback(&iterator, method, arg)?),
+            "flatMap" => HelperKind::FlatMap(self.iterator_callback(&iterator, method, arg)?),
+            "take" => HelperKind::Take(self.iterator_limit(&iterator, method, &arg)?),
+            "drop" => HelperKind::Drop(self.iterator_limit(&iterator, method, &arg)?),
+            _ => return self.consume_iterator(&iterator, &next, method, args),
+        };
+        let helper = self
+            .heap
+            .alloc_cell(JsIteratorHelper::new(kind, iterator, next));
+        let mut obj = JsObject::new();
+        obj.prototype = Some(self.prototypes.iterator);
+        obj.set(
+            "next",
+            JsValue::NativeFunction {
+                name: "next".into(),
+                handler: NativeFunction::IteratorHelperNext(helper),
+            },
+        );
+        obj.set(
+            "return",
+            JsValue::NativeFunction {
+                name: "return".into(),
+                handler: NativeFunction::IteratorHelperReturn(helper),
+            },
+        );
+        Ok(JsValue::Object(self.heap.alloc_cell(obj)))
+    }
+
+    fn consume_iterator(
+        &mut self,
+        iterator: &JsValue,
+        next: &JsValue,
+        method: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
+        match method {
+            "toArray" => {
+                let mut items = Vec::new();
+                while let Some(value) = self.iterator_step(iterator, next)? {
+                    items.push(value);
+                }
+                Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(items))))
+            }
+            "forEach" | "some" | "every" | "find" => {
+                let callback = self.iterator_callback(iterator, method, arg)?;
+                let mut counter = 0.0;
+                while let Some(value) = self.iterator_step(iterator, next)? {
+                    let result = self.call_iterator_callback(
+                        iterator,
+                        &callback,
+                        &[value.clone(), JsValue::Number(counter)],
+                    )?;
+                    counter += 1.0;
+                    let found = match method {
+                        "some" | "find" => result.to_boolean(),
+                        "every" => !result.to_boolean(),
+                        _ => false,
+                    };
+                    if found {
+                        self.close_iterator(iterator)?;
+                        return Ok(match method {
+                            "find" => value,
+                            _ => JsValue::Boolean(method == "some"),
+                        });
+                    }
+                }
+                Ok(match method {
+                    "some" => JsValue::Boolean(false),
+                    "every" => JsValue::Boolean(true),
+                    _ => JsValue::Undefined,
+                })
+            }
+            "reduce" => {
+                let reducer = self.iterator_callback(iterator, method, arg)?;
+                let (mut accumulator, mut counter) = match args.get(1) {
+                    Some(initial) => (initial.clone(), 0.0),
+                    None => match self.iterator_step(iterator, next)? {
+                        Some(first) => (first, 1.0),
+                        None => {
+                            return Err(self.throw_type_error(
+                                "Reduce of empty iterator with no initial value",
+                            ));
+                        }
+                    },
+                };
+                while let Some(value) = self.iterator_step(iterator, next)? {
+                    accumulator = self.call_iterator_callback(
+                        iterator,
+                        &reducer,
+                        &[accumulator, value, JsValue::Number(counter)],
+                    )?;
+                    counter += 1.0;
+                }
+                Ok(accumulator)
+            }
+            _ => Err(RuntimeError::TypeError {
+                message: format!("Iterator.prototype.{method} is not a function"),
+            }),
+        }
+    }
+
+    /// The `next` of a helper returned by `map`, `filter`, `take`, `drop`
+    /// or `flatMap`.
+    pub(crate) fn step_iterator_helper(
+        &mut self,
+        helper: &Gc<GcCell<JsIteratorHelper>>,
+    ) -> Result<JsValue, RuntimeError> {
+        if helper.borrow().done {
+            return Ok(iter_done(&mut self.heap));
+        }
+        match self.next_helper_value(helper) {
+            Ok(Some(value)) => Ok(iter_result(value, false, &mut self.heap)),
+            Ok(None) => {
+                helper.borrow_mut().done = true;
+                Ok(iter_done(&mut self.heap))
+            }
+            Err(err) => {
+                helper.borrow_mut().done = true;
+                Err(err)
+            }
+        }
+    }
+
+    /// The `return` of a helper: stops it and closes the iterators it was
+    /// reading from.
+    pub(crate) fn close_iterator_helper(
+        &mut self,
+        helper: &Gc<GcCell<JsIteratorHelper>>,
+    ) -> Result<JsValue, RuntimeError> {
+        let (was_done, underlying, inner) = {
+            let mut helper = helper.borrow_mut();
+            let was_done = std::mem::replace(&mut helper.done, true);
+            (was_done, helper.underlying.clone(), helper.inner.take())
+        };
+        if !was_done {
+            if let Some(FlatMapInner::Iterator(record)) = inner {
+                self.close_iterator(&record.iterator)?;
+            }
+            self.close_iterator(&underlying)?;
+        }
+        Ok(iter_done(&mut self.heap))
+    }
+
+    /// Pulls the helper's next value, or `None` once it is exhausted.
+    fn next_helper_value(
+        &mut self,
+        helper: &Gc<GcCell<JsIteratorHelper>>,
+    ) -> Result<Option<JsValue>, RuntimeError> {
+        let (kind, iterator, next) = {
+            let helper = helper.borrow();
+            (
+                helper.kind.clone(),
+                helper.underlying.clone(),
+                helper.next_method.clone(),
+            )
+        };
+        match kind {
+            HelperKind::Map(mapper) => {
+                let Some(value) = self.iterator_step(&iterator, &next)? else {
+                    return Ok(None);
+                };
+                let counter = bump_counter(helper);
+                self.call_iterator_callback(&iterator, &mapper, &[value, counter])
+                    .map(Some)
+            }
+            HelperKind::Filter(predicate) => loop {
+                let Some(value) = self.iterator_step(&iterator, &next)? else {
+                    return Ok(None);
+                };
+                let counter = bump_counter(helper);
+                if self
+                    .call_iterator_callback(&iterator, &predicate, &[value.clone(), counter])?
+                    .to_boolean()
+                {
+                    return Ok(Some(value));
+                }
+            },
+            HelperKind::Take(remaining) => {
+                if remaining <= 0.0 {
+                    self.close_iterator(&iterator)?;
+                    return Ok(None);
+                }
+                helper.borrow_mut().kind = HelperKind::Take(remaining - 1.0);
+                self.iterator_step(&iterator, &next)
+            }
+            HelperKind::Drop(mut remaining) => {
+                while remaining > 0.0 {
+                    remaining -= 1.0;
+                    helper.borrow_mut().kind = HelperKind::Drop(remaining);
+                    if self.iterator_step(&iterator, &next)?.is_none() {
+                        return Ok(None);
+                    }
+                }
+                self.iterator_step(&iterator, &next)
+            }
+            HelperKind::FlatMap(mapper) => loop {
+                let inner = helper.borrow_mut().inner.take();
+                match inner {
+                    Some(FlatMapInner::Values(mut values)) => {
+                        if let Some(value) = values.pop_front() {
+                            helper.borrow_mut().inner = Some(FlatMapInner::Values(values));
+                            return Ok(Some(value));
+                        }
+                    }
+                    Some(FlatMapInner::Iterator(record)) => {
+                        match self.iterator_step(&record.iterator, &record.next) {
+                            Ok(Some(value)) => {
+                                helper.borrow_mut().inner = Some(FlatMapInner::Iterator(record));
+                                return Ok(Some(value));
+                            }
+                            Ok(None) => {}
+                            Err(err) => return Err(self.close_after_error(&iterator, err)),
+                        }
+                    }
+                    None => {
+                        let Some(value) = self.iterator_step(&iterator, &next)? else {
+                            return Ok(None);
+                        };
+                        let counter = bump_counter(helper);
+                        let mapped =
+                            self.call_iterator_callback(&iterator, &mapper, &[value, counter])?;
+                        let inner = self
+                            .flatten_iterable(mapped)
+                            .map_err(|err| self.close_after_error(&iterator, err))?;
+                        helper.borrow_mut().inner = Some(inner);
+                    }
+                }
+            },
+        }
+    }
+
+    /// Calls `next` on `iterator`, returning the value or `None` when the
+    /// result reports `done`.
+    fn iterator_step(
+        &mut self,
+        iterator: &JsValue,
+        next: &JsValue,
+    ) -> Result<Option<JsValue>, RuntimeError> {
+        let result = self.call_function_with_this(next, &[], Some(iterator.clone()))?;
+        if !matches!(result, JsValue::Object(_)) {
+            return Err(
+                self.throw_type_error(&format!("Iterator result {result} is not an object"))
+            );
+        }
+        if self.get_property(&result, "done")?.to_boolean() {
+            return Ok(None);
+        }
+        self.get_property(&result, "value").map(Some)
+    }
+
+    /// Calls the iterator's `return` method, if it has one, to tell it
+    /// that no more values will be read.
+    pub(crate) fn close_iterator(&mut self, iterator: &JsValue) -> Result<(), RuntimeError> {
+        let method = self.get_property(iterator, "return")?;
+        if matches!(
+            method,
+            JsValue::Function { .. } | JsValue::NativeFunction { .. }
+        ) {
+            self.call_function_with_this(&method, &[], Some(iterator.clone()))?;
+        }
+        Ok(())
+    }
+
+    /// Closes `iterator` because `err` ended its consumer early, keeping
+    /// `err` over anything the close itself throws.
+    fn close_after_error(&mut self, iterator: &JsValue, err: RuntimeError) -> RuntimeError {
+        if err.is_catchable() {
+            let _ = self.close_iterator(iterator);
+        }
+        err
+    }
+
+    fn call_iterator_callback(
+        &mut self,
+        iterator: &JsValue,
+        callback: &JsValue,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        self.call_function(callback, args)
+            .map_err(|err| self.close_after_error(iterator, err))
+    }
+
+    /// Checks that a helper's callback is callable, closing `iterator`
+    /// when it is not.
+    fn iterator_callback(
+        &mut self,
+        iterator: &JsValue,
+        method: &str,
+        callback: JsValue,
+    ) -> Result<JsValue, RuntimeError> {
+        if matches!(
+            callback,
+            JsValue::Function { .. } | JsValue::NativeFunction { .. }
+        ) {
+            return Ok(callback);
+        }
+        let err = self.throw_type_error(&format!(
+            "Iterator.prototype.{method}: {callback} is not a function"
+        ));
+        Err(self.close_after_error(iterator, err))
+    }
+
+    /// The count given to `take` or `drop`, which must be a non-negative
+    /// number; infinity means no limit.
+    fn iterator_limit(
+        &mut self,
+        iterator: &JsValue,
+        method: &str,
+        limit: &JsValue,
+    ) -> Result<f64, RuntimeError> {
+        let limit = limit.to_number();
+        if limit.is_nan() || limit < 0.0 {
+            let error = self.create_typed_error_object(
+                "RangeError",
+                &format!("Iterator.prototype.{method}: {limit} is not a non-negative number"),
+            );
+            return Err(self.close_after_error(iterator, RuntimeError::thrown(error)));
+        }
+        Ok(limit.trunc())
+    }
+
+    /// The values a `flatMap` callback result stands for: an iterator, an
+    /// iterable object, or a built-in array or collection.
+    fn flatten_iterable(&mut self, value: JsValue) -> Result<FlatMapInner, RuntimeError> {
+        match value {
+            JsValue::Object(_) => {
+                let method = self.get_symbol_property(&value, &symbol::symbol_iterator())?;
+                let iterator = if matches!(method, JsValue::Undefined | JsValue::Null) {
+                    value
+                } else {
+                    self.call_function_with_this(&method, &[], Some(value))?
+                };
+                let next = self.get_property(&iterator, "next")?;
+                Ok(FlatMapInner::Iterator(Box::new(IteratorRecord {
+                    iterator,
+                    next,
+                })))
+            }
+            JsValue::Array(_) | JsValue::Map(_) | JsValue::Set(_) | JsValue::TypedArray(_) => {
+                Ok(FlatMapInner::Values(self.collect_iterable(&value)?.into()))
+            }
+            other => Err(self.throw_type_error(&format!(
+                "flatMap callback result {other} is not an iterator or iterable"
+            ))),
+        }
+    }
+}
+
+/// The helper's count of values pulled so far, advanced by one.
+fn bump_counter(helper: &Gc<GcCell<JsIteratorHelper>>) -> JsValue {
+    let mut helper = helper.borrow_mut();
+    let counter = helper.counter;
+    helper.counter += 1;
+    JsValue::Number(counter as f64)
+}
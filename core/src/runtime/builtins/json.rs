@@ -3,7 +3,7 @@ use crate::runtime::gc::Gc;
 use crate::runtime::interpreter::Interpreter;
 use crate::runtime::value::array::JsArray;
 use crate::runtime::value::object::JsObject;
-use crate::runtime::value::JsValue;
+use crate::runtime::value::{JsValue, number_to_string};
 use serde_json::Value as JsonValue;
 use std::collections::HashSet;
 
@@ -17,8 +17,10 @@ impl Interpreter {
             "stringify" => {
                 let value = args.first().cloned().unwrap_or(JsValue::Undefined);
                 let mut seen = HashSet::new();
-                let json = self.to_json_value(&value, &mut seen)?;
-                Ok(JsValue::String(json.to_string()))
+                let json = self.json_value_of(&value, &mut seen)?;
+                let mut out = String::new();
+                write_json(&json, &mut out);
+                Ok(JsValue::String(out.into()))
             }
             "parse" => {
                 let input = args
@@ -38,8 +40,8 @@ impl Interpreter {
         }
     }
 
-    pub(crate) fn to_json_value(
-        &self,
+    pub(crate) fn json_value_of(
+        &mut self,
         value: &JsValue,
         seen: &mut HashSet<usize>,
     ) -> Result<JsonValue, RuntimeError> {
@@ -49,7 +51,7 @@ impl Interpreter {
             JsValue::Number(n) => serde_json::Number::from_f64(*n)
                 .map(JsonValue::Number)
                 .unwrap_or(JsonValue::Null),
-            JsValue::String(s) => JsonValue::String(s.clone()),
+            JsValue::String(s) => JsonValue::String(s.to_string()),
             JsValue::Function { .. } | JsValue::NativeFunction { .. } => JsonValue::Null,
             JsValue::Symbol(_)
             | JsValue::Promise(_)
@@ -64,10 +66,10 @@ impl Interpreter {
                         message: "Converting circular structure to JSON".into(),
                     });
                 }
-                let elements = arr.borrow().elements.clone();
+                let elements = self.array_elements(arr)?;
                 let out = elements
                     .iter()
-                    .map(|el| self.to_json_value(el, seen))
+                    .map(|el| self.json_value_of(el, seen))
                     .collect::<Result<Vec<_>, _>>()?;
                 seen.remove(&ptr);
                 JsonValue::Array(out)
@@ -81,7 +83,7 @@ impl Interpreter {
                 }
                 let mut map = serde_json::Map::new();
                 for (k, p) in &obj.borrow().properties {
-                    map.insert(k.clone(), self.to_json_value(&p.value, seen)?);
+                    map.insert(k.to_string(), self.json_value_of(&p.value, seen)?);
                 }
                 seen.remove(&ptr);
                 JsonValue::Object(map)
@@ -93,7 +95,24 @@ impl Interpreter {
                 map.insert("flags".into(), JsonValue::String(re.flag_string()));
                 JsonValue::Object(map)
             }
-            JsValue::Proxy(_) => JsonValue::Object(serde_json::Map::new()),
+            // Typed arrays serialize as objects keyed by index.
+            JsValue::TypedArray(array) => JsonValue::Object(
+                array
+                    .borrow()
+                    .values()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, n)| {
+                        let n = serde_json::Number::from_f64(n)
+                            .map(JsonValue::Number)
+                            .unwrap_or(JsonValue::Null);
+                        (i.to_string(), n)
+                    })
+                    .collect(),
+            ),
+            JsValue::Proxy(_) | JsValue::ArrayBuffer(_) | JsValue::DataView(_) => {
+                JsonValue::Object(serde_json::Map::new())
+            }
         })
     }
 
@@ -102,7 +121,7 @@ impl Interpreter {
             JsonValue::Null => JsValue::Null,
             JsonValue::Bool(b) => JsValue::Boolean(*b),
             JsonValue::Number(n) => JsValue::Number(n.as_f64().unwrap_or(0.0)),
-            JsonValue::String(s) => JsValue::String(s.clone()),
+            JsonValue::String(s) => JsValue::String(s.into()),
             JsonValue::Array(items) => {
                 let elements: Vec<JsValue> =
                     items.iter().map(|v| self.from_json_value(v)).collect();
@@ -118,3 +137,34 @@ impl Interpreter {
         }
     }
 }
+
+/// Serializes `value` compactly, writing numbers the way `Number::toString`
+/// does rather than in `serde_json`'s float format.
+fn write_json(value: &JsonValue, out: &mut String) {
+    match value {
+        JsonValue::Number(n) => out.push_str(&number_to_string(n.as_f64().unwrap_or(f64::NAN))),
+        JsonValue::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_json(item, out);
+            }
+            out.push(']');
+        }
+        JsonValue::Object(map) => {
+            out.push('{');
+            for (i, (key, item)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&JsonValue::String(key.clone()).to_string());
+                out.push(':');
+                write_json(item, out);
+            }
+            out.push('}');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
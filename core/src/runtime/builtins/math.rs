@@ -1,8 +1,16 @@
 use crate::errors::RuntimeError;
 use crate::runtime::interpreter::Interpreter;
-use crate::runtime::value::JsValue;
+use crate::runtime::value::{JsValue, to_int32, to_uint32};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// `base ** exponent` with the cases where JS diverges from `powf`.
+pub(crate) fn js_pow(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return f64::NAN;
+    }
+    base.powf(exponent)
+}
+
 impl Interpreter {
     pub(crate) fn builtin_math_constant(&self, property: &str) -> Result<JsValue, RuntimeError> {
         use std::f64::consts;
@@ -55,10 +63,10 @@ impl Interpreter {
             "acos" => n(0).acos(),
             "atan" => n(0).atan(),
             "atan2" => n(0).atan2(n(1)),
-            "pow" => n(0).powf(n(1)),
+            "pow" => js_pow(n(0), n(1)),
             "fround" => (n(0) as f32) as f64,
-            "clz32" => (n(0) as u32).leading_zeros() as f64,
-            "imul" => ((n(0) as i32).wrapping_mul(n(1) as i32)) as f64,
+            "clz32" => to_uint32(n(0)).leading_zeros() as f64,
+            "imul" => to_int32(n(0)).wrapping_mul(to_int32(n(1))) as f64,
             "sign" => {
                 let v = n(0);
                 if v.is_nan() {
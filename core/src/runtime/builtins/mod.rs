@@ -1,13 +1,23 @@
 pub(crate) mod collections;
 pub(crate) mod console;
 pub(crate) mod date;
+pub(crate) mod event_target;
+#[cfg(feature = "http")]
+pub(crate) mod fetch;
 pub(crate) mod global;
+pub(crate) mod host;
+pub(crate) mod iterator_helpers;
 pub(crate) mod json;
 pub(crate) mod math;
 pub(crate) mod number;
 pub(crate) mod object;
 pub(crate) mod promise;
+pub(crate) mod prototypes;
 pub(crate) mod proxy;
 pub(crate) mod reflect;
 pub(crate) mod regexp;
+pub(crate) mod set_operations;
+pub(crate) mod string;
+pub(crate) mod structured_clone;
 pub(crate) mod symbol;
+pub(crate) mod typed_array;
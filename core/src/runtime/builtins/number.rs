@@ -1,57 +1,89 @@
 use crate::errors::RuntimeError;
 use crate::runtime::interpreter::Interpreter;
-use crate::runtime::value::JsValue;
+use crate::runtime::value::{JsValue, radix_digits_to_number, to_int32};
 
+/// Leading `StrWhiteSpaceChar`s, which also include the BOM.
+fn trim_js_start(s: &str) -> &str {
+    s.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{feff}')
+}
+
+fn split_sign(s: &str) -> (bool, &str) {
+    match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    }
+}
+
+/// `parseInt`: the integer spelled by the longest run of `radix` digits
+/// after optional whitespace, sign and (for radix 0 or 16) `0x` prefix.
+/// `NaN` only when no digit was consumed; `"-0"` yields `-0`.
 pub(crate) fn parse_int(s: &str, radix: i32) -> f64 {
-    let trimmed = s.trim();
-    if trimmed.is_empty() {
+    let (negative, rest) = split_sign(trim_js_start(s));
+    let (radix, strip_prefix) = match radix {
+        0 => (10, true),
+        2..=36 => (radix as u32, radix == 16),
+        _ => return f64::NAN,
+    };
+    let (radix, rest) = match rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+        Some(hex) if strip_prefix => (16, hex),
+        _ => (radix, rest),
+    };
+    let end = rest
+        .find(|c: char| !c.is_digit(radix))
+        .unwrap_or(rest.len());
+    let digits = &rest[..end];
+    if digits.is_empty() {
         return f64::NAN;
     }
-    let (negative, rest) = if let Some(s) = trimmed.strip_prefix('-') {
-        (true, s)
-    } else if let Some(s) = trimmed.strip_prefix('+') {
-        (false, s)
+    let value = if radix == 10 {
+        digits.parse::<f64>().unwrap_or(f64::NAN)
     } else {
-        (false, trimmed)
+        radix_digits_to_number(digits, radix)
     };
-    let radix = if radix == 0 {
-        if rest.starts_with("0x") || rest.starts_with("0X") {
-            16
+    if negative { -value } else { value }
+}
+
+/// `parseFloat`: the longest prefix (after whitespace) that is a
+/// `StrDecimalLiteral`, so trailing garbage is ignored. Hex, octal and
+/// binary prefixes are not recognised.
+pub(crate) fn parse_float(s: &str) -> f64 {
+    let trimmed = trim_js_start(s);
+    let (negative, rest) = split_sign(trimmed);
+    if rest.starts_with("Infinity") {
+        return if negative {
+            f64::NEG_INFINITY
         } else {
-            10
-        }
-    } else {
-        radix
-    };
-    if !(2..=36).contains(&radix) {
-        return f64::NAN;
+            f64::INFINITY
+        };
     }
-    let digits = if radix == 16 {
-        rest.strip_prefix("0x")
-            .or_else(|| rest.strip_prefix("0X"))
-            .unwrap_or(rest)
-    } else {
-        rest
+    let bytes = rest.as_bytes();
+    let digits_from = |mut i: usize| {
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        i
     };
-    let mut result = 0.0f64;
-    let mut found = false;
-    for ch in digits.chars() {
-        match ch.to_digit(radix as u32) {
-            Some(d) => {
-                found = true;
-                result = result * radix as f64 + d as f64;
-            }
-            None => break,
+    let int_end = digits_from(0);
+    let mut end = int_end;
+    if bytes.get(end) == Some(&b'.') {
+        let frac_end = digits_from(end + 1);
+        if int_end > 0 || frac_end > end + 1 {
+            end = frac_end;
         }
     }
-    if !found {
+    if end == 0 {
         return f64::NAN;
     }
-    if negative {
-        -result
-    } else {
-        result
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exp_end = digits_from(end + 1 + sign);
+        if exp_end > end + 1 + sign {
+            end = exp_end;
+        }
     }
+    let sign_len = trimmed.len() - rest.len();
+    trimmed[..sign_len + end].parse::<f64>().unwrap_or(f64::NAN)
 }
 
 impl Interpreter {
@@ -76,13 +108,10 @@ impl Interpreter {
             )),
             "parseInt" => {
                 let s = first().to_js_string();
-                let radix = args.get(1).map(|v| v.to_number() as i32).unwrap_or(0);
+                let radix = args.get(1).map_or(0, |v| to_int32(v.to_number()));
                 Ok(JsValue::Number(parse_int(&s, radix)))
             }
-            "parseFloat" => {
-                let s = first().to_js_string();
-                Ok(JsValue::Number(s.trim().parse::<f64>().unwrap_or(f64::NAN)))
-            }
+            "parseFloat" => Ok(JsValue::Number(parse_float(&first().to_js_string()))),
             _ => self.builtin_number_property(method),
         }
     }
@@ -1,8 +1,115 @@
+use super::same_value;
 use crate::errors::RuntimeError;
-use crate::runtime::interpreter::Interpreter;
-use crate::runtime::value::array::JsArray;
-use crate::runtime::value::object::JsObject;
-use crate::runtime::value::JsValue;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::interpreter::{Interpreter, PropKey};
+use crate::runtime::value::array::{JsArray, array_index};
+use crate::runtime::value::object::{JsObject, Property};
+use crate::runtime::value::{JsValue, function_length, function_name};
+
+/// The fields a property descriptor object spells out; absent ones leave
+/// the existing attribute alone.
+struct Descriptor {
+    value: Option<JsValue>,
+    get: Option<JsValue>,
+    set: Option<JsValue>,
+    writable: Option<bool>,
+    enumerable: Option<bool>,
+    configurable: Option<bool>,
+}
+
+impl Descriptor {
+    fn is_accessor(&self) -> bool {
+        self.get.is_some() || self.set.is_some()
+    }
+
+    fn is_data(&self) -> bool {
+        self.value.is_some() || self.writable.is_some()
+    }
+
+    fn apply(&self, prop: &mut Property) {
+        if self.is_accessor() {
+            prop.value = JsValue::Undefined;
+            prop.writable = false;
+        } else if self.is_data() {
+            prop.getter = None;
+            prop.setter = None;
+        }
+        if let Some(value) = &self.value {
+            prop.value = value.clone();
+        }
+        if let Some(get) = &self.get {
+            prop.getter = Some(get.clone());
+        }
+        if let Some(set) = &self.set {
+            prop.setter = Some(set.clone());
+        }
+        if let Some(writable) = self.writable {
+            prop.writable = writable;
+        }
+        if let Some(enumerable) = self.enumerable {
+            prop.enumerable = enumerable;
+        }
+        if let Some(configurable) = self.configurable {
+            prop.configurable = configurable;
+        }
+    }
+
+    /// Whether applying this descriptor to the non-configurable `current`
+    /// changes nothing that such a property forbids changing.
+    fn compatible_with(&self, current: &Property) -> bool {
+        if self.configurable == Some(true)
+            || self.enumerable.is_some_and(|e| e != current.enumerable)
+        {
+            return false;
+        }
+        if current.is_accessor() {
+            return !self.is_data()
+                && self
+                    .get
+                    .as_ref()
+                    .is_none_or(|g| Some(g) == current.getter.as_ref())
+                && self
+                    .set
+                    .as_ref()
+                    .is_none_or(|s| Some(s) == current.setter.as_ref());
+        }
+        if self.is_accessor() {
+            return false;
+        }
+        current.writable
+            || (self.writable != Some(true)
+                && self
+                    .value
+                    .as_ref()
+                    .is_none_or(|v| same_value(v, &current.value)))
+    }
+}
+
+/// Applies `descriptor` to the own property `key` of `obj`, creating the
+/// property when it is missing.
+fn define_on_object(obj: Gc<GcCell<JsObject>>, key: &PropKey, descriptor: &Descriptor) {
+    let mut obj = obj.borrow_mut();
+    match key {
+        PropKey::String(key) => {
+            let mut prop = obj
+                .properties
+                .get(key.as_str())
+                .cloned()
+                .unwrap_or_else(|| Property::new(JsValue::Undefined));
+            descriptor.apply(&mut prop);
+            obj.properties.insert(key.into(), prop);
+        }
+        PropKey::Symbol(sym) => {
+            let mut prop = obj
+                .symbol_properties
+                .get(&sym.id)
+                .map(|(_, prop)| prop.clone())
+                .unwrap_or_else(|| Property::new(JsValue::Undefined));
+            descriptor.apply(&mut prop);
+            obj.symbol_properties.insert(sym.id, (sym.clone(), prop));
+        }
+    }
+}
 
 impl Interpreter {
     pub(crate) fn object_get_own_property_names(
@@ -11,9 +118,13 @@ impl Interpreter {
     ) -> Result<JsValue, RuntimeError> {
         let keys =
             self.object_get_all_own_keys(args.first().cloned().unwrap_or(JsValue::Undefined))?;
-        Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(
-            keys.into_iter().map(JsValue::String).collect(),
-        ))))
+        Ok(JsValue::Array(
+            self.heap.alloc_cell(JsArray::new(
+                keys.into_iter()
+                    .map(|s| JsValue::String(s.into()))
+                    .collect(),
+            )),
+        ))
     }
 
     pub(crate) fn object_get_own_property_symbols(
@@ -39,39 +150,81 @@ impl Interpreter {
         args: &[JsValue],
     ) -> Result<JsValue, RuntimeError> {
         let target = args.first().cloned().unwrap_or(JsValue::Undefined);
-        let key = args
-            .get(1)
-            .cloned()
-            .unwrap_or(JsValue::Undefined)
-            .to_js_string();
+        let key = self.property_key(args.get(1).unwrap_or(&JsValue::Undefined))?;
+        let key = match key {
+            PropKey::String(key) => key,
+            PropKey::Symbol(sym) => {
+                let props = match &target {
+                    JsValue::Object(obj) => Some(*obj),
+                    JsValue::Function { properties, .. } => *properties,
+                    JsValue::Array(arr) => arr.borrow().properties,
+                    _ => None,
+                };
+                let prop = props.and_then(|props| {
+                    let props = props.borrow();
+                    props.symbol_properties.get(&sym.id).map(|(_, p)| p.clone())
+                });
+                return Ok(match prop {
+                    Some(prop) => self.object_descriptor_to_js_object(&prop),
+                    None => JsValue::Undefined,
+                });
+            }
+        };
 
         let descriptor = match target {
             JsValue::Object(obj) => {
-                let maybe_prop = obj.borrow().properties.get(&key).cloned();
+                let maybe_prop = obj.borrow().properties.get(key.as_str()).cloned();
                 match maybe_prop {
                     Some(prop) => self.object_descriptor_to_js_object(&prop),
                     None => JsValue::Undefined,
                 }
             }
             JsValue::Array(arr) => {
-                if key == "length" {
-                    let mut p = crate::runtime::value::object::Property::new(JsValue::Number(
-                        arr.borrow().len() as f64,
-                    ));
-                    p.writable = true;
-                    p.enumerable = false;
-                    p.configurable = false;
-                    self.object_descriptor_to_js_object(&p)
-                } else if let Ok(idx) = key.parse::<usize>() {
-                    if idx < arr.borrow().len() {
-                        self.object_descriptor_to_js_object(
-                            &crate::runtime::value::object::Property::new(arr.borrow().get(idx)),
-                        )
-                    } else {
-                        JsValue::Undefined
-                    }
+                let prop = if key == "length" {
+                    let borrowed = arr.borrow();
+                    Some(Property {
+                        writable: borrowed.length_writable,
+                        enumerable: false,
+                        configurable: false,
+                        ..Property::new(JsValue::Number(borrowed.len() as f64))
+                    })
                 } else {
-                    JsValue::Undefined
+                    let arr = arr.borrow();
+                    match array_index(&key) {
+                        Some(index) => arr.index_property(index),
+                        None => arr
+                            .properties
+                            .and_then(|props| props.borrow().properties.get(key.as_str()).cloned()),
+                    }
+                };
+                match prop {
+                    Some(prop) => self.object_descriptor_to_js_object(&prop),
+                    None => JsValue::Undefined,
+                }
+            }
+            JsValue::Function {
+                name,
+                params,
+                properties,
+                ..
+            } => {
+                let value = match key.as_str() {
+                    "name" => Some(JsValue::String(function_name(&name).into())),
+                    "length" => Some(JsValue::Number(function_length(&params) as f64)),
+                    _ => None,
+                };
+                let prop = match value {
+                    Some(value) => Some(Property {
+                        writable: false,
+                        enumerable: false,
+                        ..Property::new(value)
+                    }),
+                    None => properties
+                        .and_then(|props| props.borrow().properties.get(key.as_str()).cloned()),
+                };
+                match prop {
+                    Some(prop) => self.object_descriptor_to_js_object(&prop),
+                    None => JsValue::Undefined,
                 }
             }
             _ => JsValue::Undefined,
@@ -92,7 +245,7 @@ impl Interpreter {
                 .borrow()
                 .properties
                 .iter()
-                .map(|(k, p)| (k.clone(), p.clone()))
+                .map(|(k, p)| (k.to_string(), p.clone()))
                 .collect();
 
             for (name, prop) in props {
@@ -109,63 +262,125 @@ impl Interpreter {
         args: &[JsValue],
     ) -> Result<JsValue, RuntimeError> {
         let target = args.first().cloned().unwrap_or(JsValue::Undefined);
-        let key = args
-            .get(1)
-            .cloned()
-            .unwrap_or(JsValue::Undefined)
-            .to_js_string();
+        let key = self.property_key(args.get(1).unwrap_or(&JsValue::Undefined))?;
         let descriptor = args.get(2).cloned().unwrap_or(JsValue::Undefined);
 
-        let JsValue::Object(target_obj) = &target else {
+        if !matches!(target, JsValue::Object(_) | JsValue::Array(_)) {
             return Err(RuntimeError::TypeError {
                 message: "Object.defineProperty: target must be an object".into(),
             });
-        };
+        }
         let JsValue::Object(descriptor_obj) = descriptor else {
             return Err(RuntimeError::TypeError {
                 message: "Object.defineProperty: descriptor must be an object".into(),
             });
         };
+        let descriptor = self.read_descriptor(descriptor_obj)?;
 
-        let desc_value = self.get_property(&JsValue::Object(descriptor_obj), "value")?;
-        let desc_get = self.get_property(&JsValue::Object(descriptor_obj), "get")?;
-        let desc_set = self.get_property(&JsValue::Object(descriptor_obj), "set")?;
-        let desc_writable = self.get_property(&JsValue::Object(descriptor_obj), "writable")?;
-        let desc_enumerable = self.get_property(&JsValue::Object(descriptor_obj), "enumerable")?;
-        let desc_configurable =
-            self.get_property(&JsValue::Object(descriptor_obj), "configurable")?;
+        match (&target, key) {
+            (JsValue::Array(arr), PropKey::String(key)) => {
+                self.array_define_own_property(*arr, &key, &descriptor)?
+            }
+            (JsValue::Array(arr), key) => {
+                let props = self.array_named_properties(*arr);
+                define_on_object(props, &key, &descriptor);
+            }
+            (JsValue::Object(target_obj), key) => define_on_object(*target_obj, &key, &descriptor),
+            _ => unreachable!("target kinds are checked above"),
+        }
 
+        Ok(target)
+    }
+
+    fn read_descriptor(
+        &mut self,
+        descriptor: Gc<GcCell<JsObject>>,
+    ) -> Result<Descriptor, RuntimeError> {
+        let descriptor = JsValue::Object(descriptor);
+        let mut field = |name: &str| -> Result<Option<JsValue>, RuntimeError> {
+            let value = self.get_property(&descriptor, name)?;
+            Ok((!matches!(value, JsValue::Undefined)).then_some(value))
+        };
+        Ok(Descriptor {
+            value: field("value")?,
+            get: field("get")?,
+            set: field("set")?,
+            writable: field("writable")?.map(|v| v.to_boolean()),
+            enumerable: field("enumerable")?.map(|v| v.to_boolean()),
+            configurable: field("configurable")?.map(|v| v.to_boolean()),
+        })
+    }
+
+    /// `[[DefineOwnProperty]]` for arrays: `length` resizes the array and
+    /// integer keys land in `elements`, with attributes and accessors kept
+    /// in the array's side table.
+    fn array_define_own_property(
+        &mut self,
+        arr: Gc<GcCell<JsArray>>,
+        key: &str,
+        descriptor: &Descriptor,
+    ) -> Result<(), RuntimeError> {
+        if key == "length" {
+            return self.array_define_length(arr, descriptor);
+        }
+        let Some(index) = array_index(key) else {
+            let props = self.array_named_properties(arr);
+            define_on_object(props, &PropKey::String(key.to_owned()), descriptor);
+            return Ok(());
+        };
+
+        let (existing, length_writable, len) = {
+            let borrowed = arr.borrow();
+            (
+                borrowed.index_property(index),
+                borrowed.length_writable,
+                borrowed.len(),
+            )
+        };
+        if existing.is_none() && index >= len && !length_writable {
+            return Err(self.throw_type_error(&format!(
+                "Cannot add property {index}, array length is not writable"
+            )));
+        }
+        if let Some(current) = &existing
+            && !current.configurable
+            && !descriptor.compatible_with(current)
         {
-            let mut target_ref = target_obj.borrow_mut();
-            let mut prop = target_ref.properties.get(&key).cloned().unwrap_or_else(|| {
-                crate::runtime::value::object::Property::new(JsValue::Undefined)
-            });
+            return Err(self.throw_type_error(&format!("Cannot redefine property: {index}")));
+        }
 
-            if !matches!(desc_value, JsValue::Undefined) {
-                prop.value = desc_value;
-            }
-            if !matches!(desc_get, JsValue::Undefined) {
-                prop.getter = Some(desc_get);
-                prop.writable = false;
-            }
-            if !matches!(desc_set, JsValue::Undefined) {
-                prop.setter = Some(desc_set);
-                prop.writable = false;
-            }
-            if !matches!(desc_writable, JsValue::Undefined) {
-                prop.writable = desc_writable.to_boolean();
-            }
-            if !matches!(desc_enumerable, JsValue::Undefined) {
-                prop.enumerable = desc_enumerable.to_boolean();
+        let mut prop = existing.unwrap_or_else(|| Property::new(JsValue::Undefined));
+        descriptor.apply(&mut prop);
+        arr.borrow_mut().define_index(index, prop);
+        Ok(())
+    }
+
+    fn array_define_length(
+        &mut self,
+        arr: Gc<GcCell<JsArray>>,
+        descriptor: &Descriptor,
+    ) -> Result<(), RuntimeError> {
+        if descriptor.is_accessor()
+            || descriptor.enumerable == Some(true)
+            || descriptor.configurable == Some(true)
+            || (descriptor.writable == Some(true) && !arr.borrow().length_writable)
+        {
+            return Err(self.throw_type_error("Cannot redefine property: length"));
+        }
+        if let Some(value) = &descriptor.value {
+            let len = value.to_number();
+            if !(0.0..=u32::MAX as f64).contains(&len) || len.fract() != 0.0 {
+                let error = self.create_typed_error_object("RangeError", "Invalid array length");
+                return Err(RuntimeError::thrown(error));
             }
-            if !matches!(desc_configurable, JsValue::Undefined) {
-                prop.configurable = desc_configurable.to_boolean();
+            if !arr.borrow_mut().set_length(len as usize) {
+                return Err(self.throw_type_error("Cannot redefine property: length"));
             }
-
-            target_ref.properties.insert(key, prop);
         }
-
-        Ok(target)
+        if descriptor.writable == Some(false) {
+            arr.borrow_mut().length_writable = false;
+        }
+        Ok(())
     }
 
     pub(crate) fn object_define_properties(
@@ -185,11 +400,15 @@ impl Interpreter {
             .borrow()
             .properties
             .iter()
-            .map(|(k, p)| (k.clone(), p.value.clone()))
+            .map(|(k, p)| (k.to_string(), p.value.clone()))
             .collect();
 
         for (key, descriptor) in entries {
-            self.object_define_property(&[target.clone(), JsValue::String(key), descriptor])?;
+            self.object_define_property(&[
+                target.clone(),
+                JsValue::String(key.into()),
+                descriptor,
+            ])?;
         }
 
         Ok(target)
@@ -1,14 +1,19 @@
 use crate::errors::RuntimeError;
 use crate::runtime::interpreter::Interpreter;
-use crate::runtime::value::array::JsArray;
+use crate::runtime::value::array::{JsArray, array_index};
 use crate::runtime::value::JsValue;
+use crate::runtime::value::object::JsObject;
 
 impl Interpreter {
     pub(crate) fn object_keys(&mut self, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
         let keys = self.object_own_keys(args.first().cloned().unwrap_or(JsValue::Undefined))?;
-        Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(
-            keys.into_iter().map(JsValue::String).collect(),
-        ))))
+        Ok(JsValue::Array(
+            self.heap.alloc_cell(JsArray::new(
+                keys.into_iter()
+                    .map(|s| JsValue::String(s.into()))
+                    .collect(),
+            )),
+        ))
     }
 
     pub(crate) fn object_values(&mut self, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
@@ -28,60 +33,112 @@ impl Interpreter {
         let mut pairs = Vec::with_capacity(keys.len());
         for key in keys {
             let value = self.get_property(&obj, &key)?;
-            let pair = JsArray::new(vec![JsValue::String(key), value]);
+            let pair = JsArray::new(vec![JsValue::String(key.into()), value]);
             pairs.push(JsValue::Array(self.heap.alloc_cell(pair)));
         }
 
         Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(pairs))))
     }
 
-    /// Returns own enumerable string-keyed property names, respecting Proxy ownKeys trap.
+    /// Own enumerable string keys of `value` in property order, as
+    /// `Object.keys` lists them. Null and undefined cannot be converted
+    /// to an object and throw; strings list their indices and other
+    /// primitives have no keys. Proxies go through `ownKeys` and then
+    /// `getOwnPropertyDescriptor` for each key.
     pub(crate) fn object_own_keys(&mut self, value: JsValue) -> Result<Vec<String>, RuntimeError> {
         match &value {
-            JsValue::Object(obj) => Ok(obj
-                .borrow()
-                .properties
-                .iter()
-                .filter_map(|(k, p)| p.enumerable.then_some(k.clone()))
-                .collect()),
-            JsValue::Array(arr) => Ok((0..arr.borrow().len()).map(|i| i.to_string()).collect()),
+            JsValue::Null | JsValue::Undefined => Err(RuntimeError::TypeError {
+                message: "Cannot convert undefined or null to object".into(),
+            }),
+            JsValue::Object(obj) => {
+                let globals = self.env.global_property_names(*obj, true);
+                let obj = obj.borrow();
+                let mut keys = string_indices(wrapped_string_len(&obj));
+                keys.extend(globals.unwrap_or_default());
+                keys.extend(enumerable_keys(&obj));
+                Ok(keys)
+            }
+            JsValue::String(s) => Ok(string_indices(s.chars().count())),
+            JsValue::Array(arr) => {
+                let arr = arr.borrow();
+                let mut keys: Vec<String> = (0..arr.len())
+                    .filter(|&i| arr.index_property(i).is_some_and(|p| p.enumerable))
+                    .map(|i| i.to_string())
+                    .collect();
+                if let Some(props) = arr.properties {
+                    keys.extend(enumerable_keys(&props.borrow()));
+                }
+                Ok(keys)
+            }
             JsValue::Function { properties, .. } => Ok(properties
                 .as_ref()
-                .map(|props| {
-                    props
-                        .borrow()
-                        .properties
-                        .iter()
-                        .filter_map(|(k, p)| p.enumerable.then_some(k.clone()))
-                        .collect()
-                })
+                .map(|props| enumerable_keys(&props.borrow()))
                 .unwrap_or_default()),
+            JsValue::Proxy(_) => {
+                let mut keys = Vec::new();
+                for key in self.object_get_all_own_keys(value.clone())? {
+                    if self.own_property_enumerability(&value, &key)? == Some(true) {
+                        keys.push(key);
+                    }
+                }
+                Ok(keys)
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Whether `value` has `key` as an own property, and if so whether
+    /// it is enumerable. Proxies answer through `getOwnPropertyDescriptor`.
+    pub(crate) fn own_property_enumerability(
+        &mut self,
+        value: &JsValue,
+        key: &str,
+    ) -> Result<Option<bool>, RuntimeError> {
+        match value {
             JsValue::Proxy(proxy) => {
                 let (trap, target) = {
                     let p = proxy.borrow();
                     p.check_revoked()
                         .map_err(|msg| RuntimeError::TypeError { message: msg })?;
-                    (p.get_trap("ownKeys"), p.target.clone())
+                    (p.get_trap("getOwnPropertyDescriptor"), p.target.clone())
                 };
-                if let Some(trap_fn) = trap {
-                    let result = self.call_function(&trap_fn, &[target])?;
-                    if let JsValue::Array(arr) = result {
-                        Ok(arr
-                            .borrow()
-                            .elements
-                            .iter()
-                            .map(|v| v.to_js_string())
-                            .collect())
-                    } else {
-                        Err(RuntimeError::TypeError {
-                            message: "Proxy ownKeys trap must return an array".into(),
-                        })
-                    }
-                } else {
-                    self.object_own_keys(target)
+                let Some(trap_fn) = trap else {
+                    return self.own_property_enumerability(&target, key);
+                };
+                let descriptor =
+                    self.call_function(&trap_fn, &[target, JsValue::String(key.into())])?;
+                if matches!(descriptor, JsValue::Undefined) {
+                    return Ok(None);
                 }
+                let enumerable = self.get_property(&descriptor, "enumerable")?;
+                Ok(Some(enumerable.to_boolean()))
             }
-            _ => Ok(Vec::new()),
+            JsValue::Object(obj) => {
+                if let Some(enumerable) = self.env.global_property_enumerable(*obj, key) {
+                    return Ok(Some(enumerable));
+                }
+                let obj = obj.borrow();
+                if array_index(key).is_some_and(|i| i < wrapped_string_len(&obj)) {
+                    return Ok(Some(true));
+                }
+                Ok(obj.properties.get(key).map(|p| p.enumerable))
+            }
+            JsValue::Function { properties, .. } => Ok(properties
+                .and_then(|props| props.borrow().properties.get(key).map(|p| p.enumerable))),
+            JsValue::Array(_) | JsValue::String(_) if key == "length" => Ok(Some(false)),
+            JsValue::Array(arr) => {
+                let arr = arr.borrow();
+                Ok(match array_index(key) {
+                    Some(index) => arr.index_property(index).map(|prop| prop.enumerable),
+                    None => arr
+                        .properties
+                        .and_then(|props| props.borrow().properties.get(key).map(|p| p.enumerable)),
+                })
+            }
+            JsValue::String(s) => Ok(array_index(key)
+                .is_some_and(|i| i < s.chars().count())
+                .then_some(true)),
+            _ => Ok(None),
         }
     }
 
@@ -90,17 +147,32 @@ impl Interpreter {
         value: JsValue,
     ) -> Result<Vec<String>, RuntimeError> {
         match &value {
-            JsValue::Object(obj) => Ok(obj.borrow().properties.keys().cloned().collect()),
+            JsValue::Object(obj) => {
+                let globals = self.env.global_property_names(*obj, false);
+                let obj = obj.borrow();
+                let mut keys = string_indices(wrapped_string_len(&obj));
+                keys.extend(globals.unwrap_or_default());
+                keys.extend(obj.ordered_keys().into_iter().map(String::from));
+                Ok(keys)
+            }
+            JsValue::String(s) => {
+                let mut keys = string_indices(s.chars().count());
+                keys.push("length".to_string());
+                Ok(keys)
+            }
             JsValue::Array(arr) => {
-                let mut keys: Vec<String> =
-                    (0..arr.borrow().len()).map(|i| i.to_string()).collect();
+                let arr = arr.borrow();
+                let mut keys: Vec<String> = (0..arr.len()).map(|i| i.to_string()).collect();
                 keys.push("length".to_string());
+                if let Some(props) = arr.properties {
+                    keys.extend(props.borrow().ordered_keys().into_iter().map(String::from));
+                }
                 Ok(keys)
             }
             JsValue::Function { properties, .. } => {
                 let mut keys = vec!["name".to_string(), "length".to_string()];
                 if let Some(props) = properties {
-                    keys.extend(props.borrow().properties.keys().cloned());
+                    keys.extend(props.borrow().ordered_keys().into_iter().map(String::from));
                 }
                 Ok(keys)
             }
@@ -133,3 +205,28 @@ impl Interpreter {
         }
     }
 }
+
+fn enumerable_keys(obj: &JsObject) -> Vec<String> {
+    obj.ordered_keys()
+        .into_iter()
+        .filter(|key| obj.properties[key.as_str()].enumerable)
+        .map(String::from)
+        .collect()
+}
+
+/// The length of the string a `String` wrapper object holds, whose
+/// indices are own enumerable properties of the wrapper; 0 for any
+/// other object.
+fn wrapped_string_len(obj: &JsObject) -> usize {
+    match obj.properties.get("[[PrimitiveValue]]") {
+        Some(prop) => match &prop.value {
+            JsValue::String(s) => s.chars().count(),
+            _ => 0,
+        },
+        None => 0,
+    }
+}
+
+fn string_indices(len: usize) -> Vec<String> {
+    (0..len).map(|i| i.to_string()).collect()
+}
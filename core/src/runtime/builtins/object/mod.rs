@@ -45,6 +45,29 @@ impl Interpreter {
         }
     }
 
+    /// `Object(value)`: objects come back as they are, null and undefined
+    /// give a fresh object, and primitives are boxed.
+    pub(crate) fn object_from_value(&mut self, value: JsValue) -> JsValue {
+        match value {
+            JsValue::Undefined | JsValue::Null => {
+                JsValue::Object(self.heap.alloc_cell(JsObject::new()))
+            }
+            JsValue::Boolean(_) | JsValue::Number(_) | JsValue::String(_) | JsValue::Symbol(_) => {
+                self.wrap_primitive(value)
+            }
+            _ => value,
+        }
+    }
+
+    /// A wrapper object holding `value`, as `new String(..)` and the
+    /// other primitive constructors build.
+    pub(crate) fn wrap_primitive(&mut self, value: JsValue) -> JsValue {
+        let mut obj = JsObject::new();
+        obj.prototype = self.inherited_prototype(&value);
+        obj.set("[[PrimitiveValue]]", value);
+        JsValue::Object(self.heap.alloc_cell(obj))
+    }
+
     fn object_assign(&mut self, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
         let target = args
             .first()
@@ -57,6 +80,9 @@ impl Interpreter {
         };
 
         for source in args.iter().skip(1) {
+            if matches!(source, JsValue::Null | JsValue::Undefined) {
+                continue;
+            }
             let keys = self.object_own_keys(source.clone())?;
             for key in keys {
                 let value = self.get_property(source, &key)?;
@@ -157,8 +183,10 @@ impl Interpreter {
 
     fn object_freeze(&mut self, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
         let target = args.first().cloned().unwrap_or(JsValue::Undefined);
-        if let JsValue::Object(obj) = &target {
-            obj.borrow_mut().freeze();
+        match &target {
+            JsValue::Object(obj) => obj.borrow_mut().freeze(),
+            JsValue::Array(arr) => arr.borrow_mut().freeze(),
+            _ => {}
         }
         Ok(target)
     }
@@ -176,6 +204,7 @@ impl Interpreter {
         let target = args.first().cloned().unwrap_or(JsValue::Undefined);
         let result = match target {
             JsValue::Object(obj) => obj.borrow().frozen,
+            JsValue::Array(arr) => arr.borrow().is_frozen(),
             _ => false,
         };
         Ok(JsValue::Boolean(result))
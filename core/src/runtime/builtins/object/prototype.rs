@@ -1,5 +1,6 @@
 use crate::errors::RuntimeError;
 use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::array::array_index;
 use crate::runtime::value::object::JsObject;
 use crate::runtime::value::JsValue;
 
@@ -16,6 +17,7 @@ impl Interpreter {
                 });
             }
         };
+        obj.null_prototype = obj.prototype.is_none();
         Ok(JsValue::Object(self.heap.alloc_cell(obj)))
     }
 
@@ -41,6 +43,16 @@ impl Interpreter {
                 obj.borrow_mut().set_prototype(next_proto);
                 Ok(target)
             }
+            JsValue::Array(arr) => {
+                arr.borrow_mut().prototype = next_proto;
+                Ok(target)
+            }
+            JsValue::Function { properties, .. } => {
+                if let Some(props) = properties {
+                    props.borrow_mut().set_prototype(next_proto);
+                }
+                Ok(target)
+            }
             JsValue::Proxy(proxy) => {
                 let (trap, proxied_target) = {
                     let p = proxy.borrow();
@@ -94,7 +106,10 @@ impl Interpreter {
                     self.object_get_prototype_of(&target)
                 }
             }
-            _ => Ok(JsValue::Null),
+            _ => Ok(match self.inherited_prototype(value) {
+                Some(proto) => JsValue::Object(proto),
+                None => JsValue::Null,
+            }),
         }
     }
 
@@ -104,6 +119,18 @@ impl Interpreter {
         method: &str,
         args: &[JsValue],
     ) -> Option<Result<JsValue, RuntimeError>> {
+        const METHODS: &[&str] = &[
+            "hasOwnProperty",
+            "isPrototypeOf",
+            "propertyIsEnumerable",
+            "toLocaleString",
+            "toString",
+            "valueOf",
+        ];
+        // Objects made with a `null` prototype have none of these.
+        if !METHODS.contains(&method) || !self.inherits_object_prototype(receiver) {
+            return None;
+        }
         let result = match method {
             "hasOwnProperty" => self.object_proto_has_own_property(receiver, args),
             "isPrototypeOf" => self.object_proto_is_prototype_of(receiver, args),
@@ -142,17 +169,9 @@ impl Interpreter {
             .unwrap_or(JsValue::Undefined)
             .to_js_string();
         let is_enum = match receiver {
-            JsValue::Object(obj) => obj
-                .borrow()
-                .properties
-                .get(&key)
-                .map(|p| p.enumerable)
-                .unwrap_or(false),
-            JsValue::Array(arr) => key
-                .parse::<usize>()
-                .ok()
-                .map(|idx| idx < arr.borrow().len())
-                .unwrap_or(false),
+            JsValue::Object(_) | JsValue::Array(_) => {
+                self.own_property_enumerability(receiver, &key)? == Some(true)
+            }
             _ => false,
         };
         Ok(JsValue::Boolean(is_enum))
@@ -204,13 +223,16 @@ impl Interpreter {
             JsValue::WeakMap(_) => "WeakMap",
             JsValue::WeakSet(_) => "WeakSet",
             JsValue::RegExp(_) => "RegExp",
+            JsValue::ArrayBuffer(_) => "ArrayBuffer",
+            JsValue::TypedArray(array) => array.borrow().kind.constructor_name(),
+            JsValue::DataView(_) => "DataView",
             JsValue::Promise(_) => "Promise",
             JsValue::Object(_) | JsValue::Proxy(_) => "Object",
             JsValue::Null => "Null",
             JsValue::Undefined => "Undefined",
             _ => "Object",
         };
-        Ok(JsValue::String(format!("[object {tag}]")))
+        Ok(JsValue::String(format!("[object {tag}]").into()))
     }
 
     fn object_proto_to_locale_string(
@@ -222,15 +244,21 @@ impl Interpreter {
 
     pub(crate) fn object_has_own_named_property(&self, target: &JsValue, key: &str) -> bool {
         match target {
-            JsValue::Object(obj) => obj.borrow().properties.contains_key(key),
+            JsValue::Object(obj) => {
+                obj.borrow().properties.contains_key(key)
+                    || self.env.global_property(*obj, key).is_some()
+            }
             JsValue::Array(arr) => {
                 if key == "length" {
                     return true;
                 }
-                key.parse::<usize>()
-                    .ok()
-                    .map(|idx| idx < arr.borrow().len())
-                    .unwrap_or(false)
+                let arr = arr.borrow();
+                match array_index(key) {
+                    Some(idx) => idx < arr.len(),
+                    None => arr
+                        .properties
+                        .is_some_and(|props| props.borrow().properties.contains_key(key)),
+                }
             }
             JsValue::Function { properties, .. } => match key {
                 "name" => true,
@@ -3,15 +3,21 @@ use crate::parser::ast::Expr;
 use crate::runtime::event_loop::Microtask;
 use crate::runtime::gc::{Gc, GcCell};
 use crate::runtime::interpreter::Interpreter;
-use crate::runtime::value::promise::{JsPromise, PromiseReaction, PromiseState};
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::promise::{JsPromise, PromiseAnyState, PromiseReaction, PromiseState};
 use crate::runtime::value::{JsValue, NativeFunction};
 
 impl Interpreter {
     pub(crate) fn eval_new_promise(&mut self, args: &[Expr]) -> Result<JsValue, RuntimeError> {
-        let promise = self.heap.alloc_cell(JsPromise::pending());
         let executor = self.eval_expr(args.first().ok_or_else(|| RuntimeError::TypeError {
             message: "Promise constructor requires an executor".into(),
         })?)?;
+        self.construct_promise(executor)
+    }
+
+    /// `new Promise(executor)`, running the executor right away.
+    pub(crate) fn construct_promise(&mut self, executor: JsValue) -> Result<JsValue, RuntimeError> {
+        let promise = self.heap.alloc_cell(JsPromise::pending());
 
         let resolve = JsValue::NativeFunction {
             name: "resolve".into(),
@@ -50,12 +56,82 @@ impl Interpreter {
                 self.settle_promise(&p, true, val)?;
                 Ok(JsValue::Promise(p))
             }
+            "any" => {
+                let iterable = args.first().cloned().unwrap_or(JsValue::Undefined);
+                let inputs = self.collect_iterable(&iterable)?;
+                let result = self.heap.alloc_cell(JsPromise::pending());
+                let state = self.heap.alloc_cell(PromiseAnyState {
+                    result,
+                    errors: vec![JsValue::Undefined; inputs.len()],
+                    remaining: inputs.len(),
+                });
+                if inputs.is_empty() {
+                    self.reject_promise_any(&state)?;
+                }
+                for (index, input) in inputs.into_iter().enumerate() {
+                    let input = match input {
+                        JsValue::Promise(p) => p,
+                        value => {
+                            let p = self.heap.alloc_cell(JsPromise::pending());
+                            self.settle_promise(&p, false, value)?;
+                            p
+                        }
+                    };
+                    let resolve = JsValue::NativeFunction {
+                        name: "resolve".into(),
+                        handler: NativeFunction::PromiseResolve(result),
+                    };
+                    let reject = JsValue::NativeFunction {
+                        name: "reject".into(),
+                        handler: NativeFunction::PromiseAnyReject(state, index),
+                    };
+                    self.promise_then(&input, Some(resolve), Some(reject))?;
+                }
+                Ok(JsValue::Promise(result))
+            }
             _ => Err(RuntimeError::TypeError {
                 message: format!("Promise.{method} is not a function"),
             }),
         }
     }
 
+    /// Records that input `index` of a `Promise.any` rejected with
+    /// `reason`, rejecting the combined promise once every input has.
+    pub(crate) fn promise_any_reject(
+        &mut self,
+        state: &Gc<GcCell<PromiseAnyState>>,
+        index: usize,
+        reason: JsValue,
+    ) -> Result<JsValue, RuntimeError> {
+        let remaining = {
+            let mut state = state.borrow_mut();
+            state.errors[index] = reason;
+            state.remaining -= 1;
+            state.remaining
+        };
+        if remaining == 0 {
+            self.reject_promise_any(state)?;
+        }
+        Ok(JsValue::Undefined)
+    }
+
+    /// Rejects a `Promise.any` with an `AggregateError` of its inputs'
+    /// reasons.
+    fn reject_promise_any(
+        &mut self,
+        state: &Gc<GcCell<PromiseAnyState>>,
+    ) -> Result<(), RuntimeError> {
+        let (result, errors) = {
+            let state = state.borrow();
+            (state.result, state.errors.clone())
+        };
+        let errors = JsValue::Array(self.heap.alloc_cell(JsArray::new(errors)));
+        let message = JsValue::String("All promises were rejected".into());
+        let error = self.construct_error("AggregateError", &[errors, message])?;
+        self.settle_promise(&result, true, error)?;
+        Ok(())
+    }
+
     pub(crate) fn builtin_promise_instance(
         &mut self,
         promise: &Gc<GcCell<JsPromise>>,
@@ -118,6 +194,7 @@ impl Interpreter {
                         PromiseState::Rejected(v) => Some((true, v.clone())),
                     }
                 };
+                self.mark_rejection_handled(inner);
                 if let Some((rej, val)) = settled {
                     self.event_loop
                         .enqueue_microtask(Microtask::PromiseReaction {
@@ -144,6 +221,9 @@ impl Interpreter {
             };
             std::mem::take(&mut b.reactions)
         };
+        if is_reject && reactions.is_empty() {
+            self.unhandled_rejections.push(*promise);
+        }
 
         for reaction in reactions {
             self.event_loop
@@ -156,7 +236,7 @@ impl Interpreter {
         Ok(JsValue::Undefined)
     }
 
-    fn promise_then(
+    pub(crate) fn promise_then(
         &mut self,
         promise: &Gc<GcCell<JsPromise>>,
         on_fulfilled: Option<JsValue>,
@@ -178,6 +258,7 @@ impl Interpreter {
             }
         };
 
+        self.mark_rejection_handled(promise);
         if let Some((is_reject, val)) = settled {
             self.event_loop
                 .enqueue_microtask(Microtask::PromiseReaction {
@@ -237,6 +318,7 @@ impl Interpreter {
                     PromiseState::Rejected(v) => Some((true, v.clone())),
                 }
             };
+            self.mark_rejection_handled(&inner);
             if let Some((is_reject, val)) = settled {
                 self.run_promise_reaction(passthrough, is_reject, val)
             } else {
@@ -249,14 +331,31 @@ impl Interpreter {
         }
     }
 
+    /// Forgets `promise` as an unhandled rejection now that something
+    /// reacts to it.
+    pub(crate) fn mark_rejection_handled(&mut self, promise: &Gc<GcCell<JsPromise>>) {
+        self.unhandled_rejections
+            .retain(|pending| !Gc::ptr_eq(*pending, *promise));
+    }
+
+    /// Takes the values of promises that were rejected with nothing
+    /// handling them, oldest first.
+    pub(crate) fn take_unhandled_rejections(&mut self) -> Vec<JsValue> {
+        std::mem::take(&mut self.unhandled_rejections)
+            .into_iter()
+            .filter_map(|promise| match &promise.borrow().state {
+                PromiseState::Rejected(value) => Some(value.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn normalize_callback(handler: Option<JsValue>) -> Option<JsValue> {
         handler.filter(|v| matches!(v, JsValue::Function { .. } | JsValue::NativeFunction { .. }))
     }
 
-    pub(crate) fn error_to_value(&self, err: RuntimeError) -> JsValue {
-        match err {
-            RuntimeError::Thrown { value } => value,
-            other => JsValue::String(other.to_string()),
-        }
+    pub(crate) fn error_to_value(&mut self, err: RuntimeError) -> JsValue {
+        self.runtime_error_to_value(err)
+            .unwrap_or_else(|other| JsValue::String(other.to_string().into()))
     }
 }
@@ -0,0 +1,252 @@
+use crate::errors::RuntimeError;
+use super::iterator_helpers::ITERATOR_METHODS;
+use crate::runtime::gc::{Gc, GcCell, Heap};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::object::{JsObject, Property};
+use crate::runtime::value::{JsValue, NativeFunction, symbol};
+
+/// Realm-level prototype objects backing values that have no explicit
+/// `[[Prototype]]` slot of their own (arrays, functions, primitives and
+/// plain objects), and the `prototype` of the built-in constructors.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RealmPrototypes {
+    pub object: Gc<GcCell<JsObject>>,
+    pub function: Gc<GcCell<JsObject>>,
+    pub array: Gc<GcCell<JsObject>>,
+    pub string: Gc<GcCell<JsObject>>,
+    pub number: Gc<GcCell<JsObject>>,
+    pub boolean: Gc<GcCell<JsObject>>,
+    pub symbol: Gc<GcCell<JsObject>>,
+    pub date: Gc<GcCell<JsObject>>,
+    /// Shared by generator objects, collection iterators and iterator
+    /// helpers; holds the iterator helper methods.
+    pub iterator: Gc<GcCell<JsObject>>,
+    /// `EventTarget.prototype`, holding the listener methods.
+    pub event_target: Gc<GcCell<JsObject>>,
+}
+
+impl RealmPrototypes {
+    pub(crate) fn new(heap: &mut Heap) -> Self {
+        let object = heap.alloc_cell(JsObject::new());
+        let mut derived = || {
+            let mut proto = JsObject::new();
+            proto.prototype = Some(object);
+            heap.alloc_cell(proto)
+        };
+        let function = derived();
+        let array = derived();
+        let string = derived();
+        let number = derived();
+        let boolean = derived();
+        let symbol = derived();
+        let date = derived();
+        let iterator = derived();
+        let event_target = derived();
+        Self {
+            object,
+            function,
+            array,
+            string,
+            number,
+            boolean,
+            symbol,
+            date,
+            iterator,
+            event_target,
+        }
+    }
+}
+
+impl Interpreter {
+    pub(crate) fn init_prototypes(&mut self) {
+        let protos = self.prototypes;
+        for (proto, name, handler) in [
+            (protos.object, "Object", NativeFunction::ObjectCtor),
+            (protos.function, "Function", NativeFunction::FunctionCtor),
+            (protos.array, "Array", NativeFunction::ArrayCtor),
+            (protos.string, "String", NativeFunction::StringCtor),
+            (protos.number, "Number", NativeFunction::NumberCtor),
+            (protos.boolean, "Boolean", NativeFunction::BooleanCtor),
+            (protos.symbol, "Symbol", NativeFunction::SymbolConstructor),
+            (protos.date, "Date", NativeFunction::DateCtor),
+        ] {
+            let ctor = JsValue::NativeFunction {
+                name: name.into(),
+                handler,
+            };
+            proto.borrow_mut().properties.insert(
+                "constructor".into(),
+                Property {
+                    enumerable: false,
+                    ..Property::new(ctor)
+                },
+            );
+        }
+        let to_string = JsValue::NativeFunction {
+            name: "toString".into(),
+            handler: NativeFunction::FunctionToString,
+        };
+        protos.function.borrow_mut().properties.insert(
+            "toString".into(),
+            Property {
+                enumerable: false,
+                ..Property::new(to_string)
+            },
+        );
+
+        let mut iterator = protos.iterator.borrow_mut();
+        for &method in ITERATOR_METHODS {
+            let function = JsValue::NativeFunction {
+                name: method.into(),
+                handler: NativeFunction::IteratorMethod(method.into()),
+            };
+            iterator.properties.insert(
+                method.into(),
+                Property {
+                    enumerable: false,
+                    ..Property::new(function)
+                },
+            );
+        }
+        iterator.set_symbol(
+            symbol::symbol_iterator(),
+            JsValue::NativeFunction {
+                name: "[Symbol.iterator]".into(),
+                handler: NativeFunction::GeneratorIterator,
+            },
+        );
+    }
+
+    /// The `prototype` property exposed on the built-in constructors.
+    pub(crate) fn constructor_prototype(
+        &self,
+        handler: &NativeFunction,
+    ) -> Option<Gc<GcCell<JsObject>>> {
+        match handler {
+            NativeFunction::ObjectCtor => Some(self.prototypes.object),
+            NativeFunction::FunctionCtor => Some(self.prototypes.function),
+            NativeFunction::ArrayCtor => Some(self.prototypes.array),
+            NativeFunction::StringCtor => Some(self.prototypes.string),
+            NativeFunction::NumberCtor => Some(self.prototypes.number),
+            NativeFunction::BooleanCtor => Some(self.prototypes.boolean),
+            NativeFunction::SymbolConstructor => Some(self.prototypes.symbol),
+            NativeFunction::DateCtor => Some(self.prototypes.date),
+            NativeFunction::NativeClassConstructor(name) if name == "EventTarget" => {
+                Some(self.prototypes.event_target)
+            }
+            _ => None,
+        }
+    }
+
+    /// The object `value` inherits from, taking the realm prototypes
+    /// into account for values that don't carry their own.
+    pub(crate) fn inherited_prototype(&self, value: &JsValue) -> Option<Gc<GcCell<JsObject>>> {
+        match value {
+            JsValue::Object(obj) => obj.borrow().prototype,
+            JsValue::Array(arr) => Some(arr.borrow().prototype.unwrap_or(self.prototypes.array)),
+            JsValue::Function { properties, .. } => Some(
+                properties
+                    .and_then(|p| p.borrow().prototype)
+                    .unwrap_or(self.prototypes.function),
+            ),
+            JsValue::NativeFunction { .. } => Some(self.prototypes.function),
+            JsValue::String(_) => Some(self.prototypes.string),
+            JsValue::Number(_) => Some(self.prototypes.number),
+            JsValue::Boolean(_) => Some(self.prototypes.boolean),
+            JsValue::Symbol(_) => Some(self.prototypes.symbol),
+            _ => None,
+        }
+    }
+
+    /// Walks the prototype chain starting at `start`, invoking getters
+    /// with `receiver` as `this`. Chains that end without reaching
+    /// `Object.prototype` fall back to it, so methods registered there
+    /// are visible from every object, unless the chain ends at an object
+    /// whose prototype was set to `null`.
+    pub(crate) fn lookup_prototype_chain(
+        &mut self,
+        start: Option<Gc<GcCell<JsObject>>>,
+        key: &str,
+        receiver: &JsValue,
+    ) -> Result<Option<JsValue>, RuntimeError> {
+        let root = self.prototypes.object;
+        let mut reached_root = has_null_prototype(receiver);
+        let mut current = start;
+        loop {
+            let Some(candidate) = current.or(if reached_root { None } else { Some(root) }) else {
+                return Ok(None);
+            };
+            let (prop, next) = {
+                let borrowed = candidate.borrow();
+                reached_root |= Gc::ptr_eq(candidate, root) || borrowed.null_prototype;
+                (borrowed.properties.get(key).cloned(), borrowed.prototype)
+            };
+            if let Some(prop) = prop {
+                if let Some(getter) = prop.getter {
+                    return self
+                        .call_function_with_this(&getter, &[], Some(receiver.clone()))
+                        .map(Some);
+                }
+                return Ok(Some(prop.value));
+            }
+            current = next;
+        }
+    }
+
+    /// Whether `value` inherits from `Object.prototype`, which holds for
+    /// everything but chains that end in a `null` prototype set on purpose.
+    pub(crate) fn inherits_object_prototype(&self, value: &JsValue) -> bool {
+        if has_null_prototype(value) {
+            return false;
+        }
+        let mut current = self.inherited_prototype(value);
+        while let Some(proto) = current {
+            let proto = proto.borrow();
+            if proto.null_prototype {
+                return false;
+            }
+            current = proto.prototype;
+        }
+        true
+    }
+
+    /// Looks up a user-registered method on the realm prototype chain of
+    /// a built-in value, returning it only when it is callable.
+    pub(crate) fn inherited_method(
+        &mut self,
+        receiver: &JsValue,
+        key: &str,
+    ) -> Result<Option<JsValue>, RuntimeError> {
+        let start = self.inherited_prototype(receiver);
+        Ok(self
+            .lookup_prototype_chain(start, key, receiver)?
+            .filter(|v| {
+                matches!(
+                    v,
+                    JsValue::Function { .. } | JsValue::NativeFunction { .. }
+                )
+            }))
+    }
+}
+
+/// Whether `value` is an object whose prototype was set to `null`, so
+/// lookups on it must not fall back to `Object.prototype`.
+pub(crate) fn has_null_prototype(value: &JsValue) -> bool {
+    matches!(value, JsValue::Object(obj) if obj.borrow().null_prototype)
+}
+
+/// The static methods read as properties off built-in constructors that
+/// have no backing object, such as `globalThis.Promise.resolve`.
+/// `Number.parseInt` and `Number.parseFloat` are the global functions
+/// themselves.
+pub(crate) fn constructor_static(handler: &NativeFunction, key: &str) -> Option<NativeFunction> {
+    match (handler, key) {
+        (NativeFunction::PromiseCtor, "resolve" | "reject" | "any") => {
+            Some(NativeFunction::PromiseStatic(key.to_string()))
+        }
+        (NativeFunction::ProxyCtor, "revocable") => Some(NativeFunction::ProxyRevocable),
+        (NativeFunction::NumberCtor, "parseInt") => Some(NativeFunction::ParseInt),
+        (NativeFunction::NumberCtor, "parseFloat") => Some(NativeFunction::ParseFloat),
+        _ => None,
+    }
+}
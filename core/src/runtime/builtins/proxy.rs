@@ -13,7 +13,15 @@ impl Interpreter {
             });
         }
         let target = self.eval_expr(&args[0])?;
-        let handler_val = self.eval_expr(&args[1])?;
+        let handler = self.eval_expr(&args[1])?;
+        self.construct_proxy(target, handler)
+    }
+
+    pub(crate) fn construct_proxy(
+        &mut self,
+        target: JsValue,
+        handler_val: JsValue,
+    ) -> Result<JsValue, RuntimeError> {
         let handler = match &handler_val {
             JsValue::Object(obj) => *obj,
             _ => {
@@ -42,9 +50,9 @@ impl Interpreter {
         };
         let proxy_gc = self.heap.alloc_cell(JsProxy::new(target, handler));
         let mut result = JsObject::new();
-        result.set("proxy".into(), JsValue::Proxy(proxy_gc));
+        result.set("proxy", JsValue::Proxy(proxy_gc));
         result.set(
-            "revoke".into(),
+            "revoke",
             JsValue::NativeFunction {
                 name: "revoke".into(),
                 handler: NativeFunction::ProxyRevoke(proxy_gc),
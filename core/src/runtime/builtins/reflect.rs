@@ -1,7 +1,6 @@
 use crate::errors::RuntimeError;
 use crate::runtime::interpreter::Interpreter;
 use crate::runtime::value::array::JsArray;
-use crate::runtime::value::object::JsObject;
 use crate::runtime::value::JsValue;
 
 impl Interpreter {
@@ -11,53 +10,55 @@ impl Interpreter {
         args: &[JsValue],
     ) -> Result<JsValue, RuntimeError> {
         let target = || args.first().cloned().unwrap_or(JsValue::Undefined);
-        let prop = || {
-            args.get(1)
-                .cloned()
-                .unwrap_or(JsValue::Undefined)
-                .to_js_string()
-        };
+        let key =
+            |interp: &mut Self| interp.property_key(args.get(1).unwrap_or(&JsValue::Undefined));
 
         match method {
-            "get" => self.get_property(&target(), &prop()),
+            "get" => {
+                let key = key(self)?;
+                self.get_keyed(&target(), &key)
+            }
             "set" => {
+                let key = key(self)?;
                 let val = args.get(2).cloned().unwrap_or(JsValue::Undefined);
-                self.set_property(&target(), &prop(), val)?;
+                self.set_keyed(&target(), &key, val)?;
                 Ok(JsValue::Boolean(true))
             }
-            "has" => self.eval_in_value(&prop(), &target()),
-            "deleteProperty" => self.delete_property(&target(), &prop()),
+            "has" => {
+                let key = key(self)?;
+                Ok(JsValue::Boolean(self.has_keyed(&target(), &key)?))
+            }
+            "deleteProperty" => {
+                let key = key(self)?;
+                self.delete_keyed(&target(), &key)
+            }
             "ownKeys" => {
-                let keys = self.object_own_keys(target())?;
-                let arr = JsArray::new(keys.into_iter().map(JsValue::String).collect());
+                let keys = self.object_get_all_own_keys(target())?;
+                let arr = JsArray::new(
+                    keys.into_iter()
+                        .map(|s| JsValue::String(s.into()))
+                        .collect(),
+                );
                 Ok(JsValue::Array(self.heap.alloc_cell(arr)))
             }
             "apply" => {
                 let this_arg = args.get(1).cloned().unwrap_or(JsValue::Undefined);
                 let call_args = match args.get(2).cloned().unwrap_or(JsValue::Undefined) {
-                    JsValue::Array(arr) => arr.borrow().elements.clone(),
+                    JsValue::Array(arr) => self.array_elements(&arr)?,
                     _ => Vec::new(),
                 };
                 self.call_function_with_this(&target(), &call_args, Some(this_arg))
             }
             "construct" => {
                 let call_args = match args.get(1).cloned().unwrap_or(JsValue::Undefined) {
-                    JsValue::Array(arr) => arr.borrow().elements.clone(),
+                    JsValue::Array(arr) => self.array_elements(&arr)?,
                     _ => Vec::new(),
                 };
                 let t = target();
                 if let JsValue::Function { name, .. } = &t {
                     if let Some(class_name) = name.strip_suffix("::constructor") {
                         if let Some(class) = self.classes.get(class_name).cloned() {
-                            let mut instance = JsObject::new();
-                            instance.prototype = Some(class.prototype);
-                            let inst = JsValue::Object(self.heap.alloc_cell(instance));
-                            self.call_function_with_this(
-                                &class.constructor,
-                                &call_args,
-                                Some(inst.clone()),
-                            )?;
-                            return Ok(inst);
+                            return self.construct_class(class_name, &class, &call_args);
                         }
                     }
                 }
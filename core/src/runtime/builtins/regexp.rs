@@ -2,8 +2,7 @@ use crate::errors::RuntimeError;
 use crate::parser::ast::Expr;
 use crate::runtime::gc::{Gc, GcCell};
 use crate::runtime::interpreter::Interpreter;
-use crate::runtime::value::array::JsArray;
-use crate::runtime::value::regexp::{JsRegExp, MatchResult, RegExpFlags};
+use crate::runtime::value::regexp::{JsRegExp, RegExpFlags};
 use crate::runtime::value::JsValue;
 
 impl Interpreter {
@@ -21,11 +20,14 @@ impl Interpreter {
             "exec" => {
                 let input = args.first().map(|v| v.to_js_string()).unwrap_or_default();
                 match re.borrow_mut().exec(&input) {
-                    Some(m) => Ok(JsValue::Array(self.heap.alloc_cell(match_to_array(m)))),
+                    Some(m) => {
+                        let array = m.into_array(&input, &mut self.heap);
+                        Ok(JsValue::Array(self.heap.alloc_cell(array)))
+                    }
                     None => Ok(JsValue::Null),
                 }
             }
-            "toString" => Ok(JsValue::String(re.borrow().to_string())),
+            "toString" => Ok(JsValue::String(re.borrow().to_string().into())),
             _ => Err(RuntimeError::TypeError {
                 message: format!("RegExp.{method} is not a function"),
             }),
@@ -39,8 +41,8 @@ impl Interpreter {
     ) -> Result<JsValue, RuntimeError> {
         let re = re.borrow();
         Ok(match prop {
-            "source" => JsValue::String(re.pattern.clone()),
-            "flags" => JsValue::String(re.flag_string()),
+            "source" => JsValue::String(re.pattern.as_str().into()),
+            "flags" => JsValue::String(re.flag_string().into()),
             "global" => JsValue::Boolean(re.flags.global),
             "ignoreCase" => JsValue::Boolean(re.flags.ignore_case),
             "multiline" => JsValue::Boolean(re.flags.multiline),
@@ -79,23 +81,7 @@ impl Interpreter {
 
         let flags = RegExpFlags::from_str(&flags_str)
             .map_err(|e| RuntimeError::TypeError { message: e })?;
-        let re =
-            JsRegExp::new(&pattern, flags).map_err(|e| RuntimeError::TypeError { message: e })?;
+        let re = JsRegExp::new(&pattern, flags).map_err(|e| self.throw_syntax_error(&e))?;
         Ok(JsValue::RegExp(self.heap.alloc_cell(re)))
     }
 }
-
-fn match_to_array(m: MatchResult) -> JsArray {
-    let mut items: Vec<JsValue> = m
-        .captures
-        .iter()
-        .map(|c| match c {
-            Some(s) => JsValue::String(s.clone()),
-            None => JsValue::Undefined,
-        })
-        .collect();
-    if items.is_empty() {
-        items.push(JsValue::String(m.full_match));
-    }
-    JsArray::new(items)
-}
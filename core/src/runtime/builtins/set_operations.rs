@@ -0,0 +1,227 @@
+use crate::errors::RuntimeError;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::JsValue;
+use crate::runtime::value::collections::map::JsMap;
+use crate::runtime::value::collections::set::JsSet;
+
+pub(crate) const SET_OPERATIONS: &[&str] = &[
+    "difference",
+    "intersection",
+    "isDisjointFrom",
+    "isSubsetOf",
+    "isSupersetOf",
+    "symmetricDifference",
+    "union",
+];
+
+/// The argument of a set operation: a built-in Set or Map read directly,
+/// or any object with `size`, `has` and `keys`.
+enum SetLike {
+    Set(Gc<GcCell<JsSet>>),
+    Map(Gc<GcCell<JsMap>>),
+    Object(Box<SetRecord>),
+}
+
+struct SetRecord {
+    receiver: JsValue,
+    size: f64,
+    has: JsValue,
+    keys: JsValue,
+}
+
+impl SetLike {
+    fn size(&self) -> f64 {
+        match self {
+            SetLike::Set(set) => set.borrow().size() as f64,
+            SetLike::Map(map) => map.borrow().size() as f64,
+            SetLike::Object(record) => record.size,
+        }
+    }
+}
+
+impl Interpreter {
+    pub(crate) fn call_set_operation(
+        &mut self,
+        set_gc: &Gc<GcCell<JsSet>>,
+        method: &str,
+        other: &JsValue,
+    ) -> Result<JsValue, RuntimeError> {
+        let other = self.set_like(other)?;
+        let this_size = set_gc.borrow().size() as f64;
+        let own = || set_gc.borrow().iter().cloned().collect::<Vec<_>>();
+        let result = match method {
+            "union" => {
+                let mut result = set_gc.borrow().clone();
+                for key in self.set_like_keys(&other)? {
+                    result.add(key);
+                }
+                result
+            }
+            "intersection" => {
+                let mut result = JsSet::new();
+                if this_size <= other.size() {
+                    for value in own() {
+                        if self.set_like_has(&other, &value)? {
+                            result.add(value);
+                        }
+                    }
+                } else {
+                    for key in self.set_like_keys(&other)? {
+                        if set_gc.borrow().has(&key) {
+                            result.add(key);
+                        }
+                    }
+                }
+                result
+            }
+            "difference" => {
+                let mut result = set_gc.borrow().clone();
+                if this_size <= other.size() {
+                    for value in own() {
+                        if self.set_like_has(&other, &value)? {
+                            result.delete(&value);
+                        }
+                    }
+                } else {
+                    for key in self.set_like_keys(&other)? {
+                        result.delete(&key);
+                    }
+                }
+                result
+            }
+            "symmetricDifference" => {
+                let mut result = set_gc.borrow().clone();
+                for key in self.set_like_keys(&other)? {
+                    if set_gc.borrow().has(&key) {
+                        result.delete(&key);
+                    } else {
+                        result.add(key);
+                    }
+                }
+                result
+            }
+            "isSubsetOf" => {
+                if this_size > other.size() {
+                    return Ok(JsValue::Boolean(false));
+                }
+                for value in own() {
+                    if !self.set_like_has(&other, &value)? {
+                        return Ok(JsValue::Boolean(false));
+                    }
+                }
+                return Ok(JsValue::Boolean(true));
+            }
+            "isSupersetOf" => {
+                if this_size < other.size() {
+                    return Ok(JsValue::Boolean(false));
+                }
+                for key in self.set_like_keys(&other)? {
+                    if !set_gc.borrow().has(&key) {
+                        return Ok(JsValue::Boolean(false));
+                    }
+                }
+                return Ok(JsValue::Boolean(true));
+            }
+            "isDisjointFrom" => {
+                if this_size <= other.size() {
+                    for value in own() {
+                        if self.set_like_has(&other, &value)? {
+                            return Ok(JsValue::Boolean(false));
+                        }
+                    }
+                } else {
+                    for key in self.set_like_keys(&other)? {
+                        if set_gc.borrow().has(&key) {
+                            return Ok(JsValue::Boolean(false));
+                        }
+                    }
+                }
+                return Ok(JsValue::Boolean(true));
+            }
+            _ => {
+                return Err(RuntimeError::TypeError {
+                    message: format!("Set.{method} is not a function"),
+                });
+            }
+        };
+        Ok(JsValue::Set(self.heap.alloc_cell(result)))
+    }
+
+    /// Reads `size`, `has` and `keys` off `value` once, the way the set
+    /// methods' GetSetRecord does.
+    fn set_like(&mut self, value: &JsValue) -> Result<SetLike, RuntimeError> {
+        match value {
+            JsValue::Set(set) => return Ok(SetLike::Set(*set)),
+            JsValue::Map(map) => return Ok(SetLike::Map(*map)),
+            JsValue::Object(_) | JsValue::Proxy(_) | JsValue::Function { .. } => {}
+            other => {
+                return Err(RuntimeError::TypeError {
+                    message: format!("{other} is not a set-like object"),
+                });
+            }
+        }
+        let size = self.get_property(value, "size")?.to_number();
+        if size.is_nan() {
+            return Err(RuntimeError::TypeError {
+                message: "set-like size is not a number".to_string(),
+            });
+        }
+        if size < 0.0 {
+            let error = self.create_typed_error_object("RangeError", "set-like size is negative");
+            return Err(RuntimeError::thrown(error));
+        }
+        let has = self.get_property(value, "has")?;
+        let keys = self.get_property(value, "keys")?;
+        for (name, method) in [("has", &has), ("keys", &keys)] {
+            if !matches!(
+                method,
+                JsValue::Function { .. } | JsValue::NativeFunction { .. }
+            ) {
+                return Err(RuntimeError::TypeError {
+                    message: format!("set-like {name} is not a function"),
+                });
+            }
+        }
+        Ok(SetLike::Object(Box::new(SetRecord {
+            receiver: value.clone(),
+            size: size.trunc(),
+            has,
+            keys,
+        })))
+    }
+
+    fn set_like_has(&mut self, other: &SetLike, value: &JsValue) -> Result<bool, RuntimeError> {
+        match other {
+            SetLike::Set(set) => Ok(set.borrow().has(value)),
+            SetLike::Map(map) => Ok(map.borrow().has(value)),
+            SetLike::Object(record) => Ok(self
+                .call_function_with_this(
+                    &record.has,
+                    std::slice::from_ref(value),
+                    Some(record.receiver.clone()),
+                )?
+                .to_boolean()),
+        }
+    }
+
+    /// The keys of `other`, with `-0` read as `+0`.
+    fn set_like_keys(&mut self, other: &SetLike) -> Result<Vec<JsValue>, RuntimeError> {
+        let keys = match other {
+            SetLike::Set(set) => set.borrow().iter().cloned().collect(),
+            SetLike::Map(map) => map.borrow().iter().map(|(k, _)| k.clone()).collect(),
+            SetLike::Object(record) => {
+                let iterator =
+                    self.call_function_with_this(&record.keys, &[], Some(record.receiver.clone()))?;
+                self.drain_iterator(&iterator)?
+            }
+        };
+        Ok(keys
+            .into_iter()
+            .map(|key| match key {
+                JsValue::Number(0.0) => JsValue::Number(0.0),
+                key => key,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::errors::RuntimeError;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::{JsValue, NativeFunction};
+
+pub(crate) const STRING_STATIC_METHODS: &[&str] = &["raw", "fromCharCode", "fromCodePoint"];
+
+impl Interpreter {
+    pub(crate) fn builtin_string_static(
+        &mut self,
+        method: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        match method {
+            "raw" => self.string_raw(args),
+            "fromCharCode" => {
+                let units: Vec<u16> = args.iter().map(|arg| to_uint16(arg.to_number())).collect();
+                Ok(JsValue::String(from_utf16(&units).into()))
+            }
+            "fromCodePoint" => {
+                let mut units = Vec::new();
+                for arg in args {
+                    let n = arg.to_number();
+                    if n.fract() != 0.0 || !(0.0..=0x10FFFF as f64).contains(&n) {
+                        let message = format!("Invalid code point {}", arg.to_js_string());
+                        return Err(self.throw_range_error(&message));
+                    }
+                    push_code_point(&mut units, n as u32);
+                }
+                Ok(JsValue::String(from_utf16(&units).into()))
+            }
+            _ => Err(RuntimeError::TypeError {
+                message: format!("String.{method} is not a function"),
+            }),
+        }
+    }
+
+    /// `String.raw` and its siblings read as values, as when `String.raw`
+    /// tags a template.
+    pub(crate) fn builtin_string_property(&self, prop: &str) -> Option<JsValue> {
+        STRING_STATIC_METHODS
+            .contains(&prop)
+            .then(|| JsValue::NativeFunction {
+                name: prop.into(),
+                handler: NativeFunction::StringStatic(prop.into()),
+            })
+    }
+
+    /// `String.raw(strings, ...substitutions)`: the raw strings of a
+    /// template with the substitutions between them.
+    fn string_raw(&mut self, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
+        let strings = args.first().cloned().unwrap_or(JsValue::Undefined);
+        if matches!(strings, JsValue::Undefined | JsValue::Null) {
+            return Err(self.throw_type_error("String.raw requires a template object"));
+        }
+        let raw = self.get_property(&strings, "raw")?;
+        if matches!(raw, JsValue::Undefined | JsValue::Null) {
+            return Err(self.throw_type_error("String.raw requires a template object"));
+        }
+        let len = self.get_property(&raw, "length")?.to_number();
+        let len = if len.is_nan() || len <= 0.0 {
+            0
+        } else {
+            len.min(u32::MAX as f64) as usize
+        };
+        let mut out = String::new();
+        for i in 0..len {
+            let piece = self.get_property(&raw, &i.to_string())?;
+            out.push_str(&self.to_primitive(&piece, "string")?.to_js_string());
+            if i + 1 == len {
+                break;
+            }
+            if let Some(sub) = args.get(i + 1) {
+                out.push_str(&self.to_primitive(sub, "string")?.to_js_string());
+            }
+        }
+        Ok(JsValue::String(out.into()))
+    }
+}
+
+fn to_uint16(n: f64) -> u16 {
+    if !n.is_finite() {
+        return 0;
+    }
+    n.trunc().rem_euclid(65536.0) as u16
+}
+
+fn push_code_point(units: &mut Vec<u16>, code_point: u32) {
+    if code_point < 0x10000 {
+        units.push(code_point as u16);
+    } else {
+        let offset = code_point - 0x10000;
+        units.push(0xD800 | (offset >> 10) as u16);
+        units.push(0xDC00 | (offset & 0x3FF) as u16);
+    }
+}
+
+/// Strings hold UTF-8, so a surrogate without its pair becomes U+FFFD.
+fn from_utf16(units: &[u16]) -> String {
+    char::decode_utf16(units.iter().copied())
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
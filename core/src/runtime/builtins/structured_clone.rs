@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use crate::errors::RuntimeError;
+use crate::runtime::gc::Gc;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::JsValue;
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::collections::map::JsMap;
+use crate::runtime::value::collections::set::JsSet;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::regexp::JsRegExp;
+
+const DATE_SLOTS: [&str; 2] = ["[[DateValue]]", "[[PrimitiveValue]]"];
+
+impl Interpreter {
+    /// Copies `value` into this interpreter's heap following the HTML
+    /// structured clone algorithm. Shared and cyclic references in the
+    /// source graph map to shared and cyclic references in the copy, and
+    /// the source may live in another interpreter.
+    pub fn deep_clone(&mut self, value: &JsValue) -> Result<JsValue, RuntimeError> {
+        let mut memo = HashMap::new();
+        self.clone_value(value, &mut memo)
+    }
+
+    fn clone_value(
+        &mut self,
+        value: &JsValue,
+        memo: &mut HashMap<usize, JsValue>,
+    ) -> Result<JsValue, RuntimeError> {
+        let identity = match value {
+            JsValue::Undefined
+            | JsValue::Null
+            | JsValue::Boolean(_)
+            | JsValue::Number(_)
+            | JsValue::String(_) => return Ok(value.clone()),
+            JsValue::Object(obj) => Gc::as_usize(*obj),
+            JsValue::Array(arr) => Gc::as_usize(*arr),
+            JsValue::Map(map) => Gc::as_usize(*map),
+            JsValue::Set(set) => Gc::as_usize(*set),
+            JsValue::RegExp(re) => Gc::as_usize(*re),
+            _ => return Err(data_clone_error(value)),
+        };
+        if let Some(copy) = memo.get(&identity) {
+            return Ok(copy.clone());
+        }
+
+        match value {
+            JsValue::Object(obj) => {
+                let source = obj.borrow();
+                if source.properties.contains_key(DATE_SLOTS[0]) {
+                    let mut date = JsObject::new();
+                    date.prototype = Some(self.prototypes.date);
+                    for slot in DATE_SLOTS {
+                        if let Some(time) = source.get(slot) {
+                            date.set(slot, time);
+                        }
+                    }
+                    drop(source);
+                    let copy = JsValue::Object(self.heap.alloc_cell(date));
+                    memo.insert(identity, copy.clone());
+                    return Ok(copy);
+                }
+                let entries: Vec<_> = source
+                    .properties
+                    .iter()
+                    .filter(|(_, prop)| prop.enumerable)
+                    .map(|(key, prop)| (key.clone(), prop.clone()))
+                    .collect();
+                drop(source);
+                let target = self.heap.alloc_cell(JsObject::new());
+                memo.insert(identity, JsValue::Object(target));
+                for (key, prop) in entries {
+                    let field = match prop.getter {
+                        Some(getter) => {
+                            self.call_function_with_this(&getter, &[], Some(value.clone()))?
+                        }
+                        None => prop.value,
+                    };
+                    let field = self.clone_value(&field, memo)?;
+                    target.borrow_mut().set(key, field);
+                }
+                Ok(JsValue::Object(target))
+            }
+            JsValue::Array(arr) => {
+                let elements = arr.borrow().elements.clone();
+                let target = self.heap.alloc_cell(JsArray::new(Vec::new()));
+                memo.insert(identity, JsValue::Array(target));
+                for element in elements {
+                    let element = self.clone_value(&element, memo)?;
+                    target.borrow_mut().elements.push(element);
+                }
+                Ok(JsValue::Array(target))
+            }
+            JsValue::Map(map) => {
+                let entries: Vec<_> = map.borrow().iter().cloned().collect();
+                let target = self.heap.alloc_cell(JsMap::new());
+                memo.insert(identity, JsValue::Map(target));
+                for (key, entry) in entries {
+                    let key = self.clone_value(&key, memo)?;
+                    let entry = self.clone_value(&entry, memo)?;
+                    target.borrow_mut().set(key, entry);
+                }
+                Ok(JsValue::Map(target))
+            }
+            JsValue::Set(set) => {
+                let members: Vec<_> = set.borrow().iter().cloned().collect();
+                let target = self.heap.alloc_cell(JsSet::new());
+                memo.insert(identity, JsValue::Set(target));
+                for member in members {
+                    let member = self.clone_value(&member, memo)?;
+                    target.borrow_mut().add(member);
+                }
+                Ok(JsValue::Set(target))
+            }
+            JsValue::RegExp(re) => {
+                let (pattern, flags) = {
+                    let re = re.borrow();
+                    (re.pattern.clone(), re.flags.clone())
+                };
+                let regexp = JsRegExp::new(&pattern, flags)
+                    .map_err(|message| RuntimeError::TypeError { message })?;
+                let copy = JsValue::RegExp(self.heap.alloc_cell(regexp));
+                memo.insert(identity, copy.clone());
+                Ok(copy)
+            }
+            _ => unreachable!("non-cloneable values are rejected above"),
+        }
+    }
+}
+
+fn data_clone_error(value: &JsValue) -> RuntimeError {
+    let what = match value {
+        JsValue::Function { name, .. } | JsValue::NativeFunction { name, .. } => {
+            format!("function {name}")
+        }
+        JsValue::Symbol(_) => "Symbol".to_string(),
+        JsValue::Promise(_) => "#<Promise>".to_string(),
+        JsValue::Proxy(_) => "#<Proxy>".to_string(),
+        JsValue::WeakMap(_) => "#<WeakMap>".to_string(),
+        JsValue::WeakSet(_) => "#<WeakSet>".to_string(),
+        _ => "value".to_string(),
+    };
+    RuntimeError::TypeError {
+        message: format!("DataCloneError: {what} could not be cloned"),
+    }
+}
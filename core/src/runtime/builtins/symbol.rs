@@ -20,7 +20,7 @@ impl Interpreter {
                 })?;
                 match sym {
                     JsValue::Symbol(s) => Ok(match self.symbol_registry.key_for(s) {
-                        Some(k) => JsValue::String(k),
+                        Some(k) => JsValue::String(k.into()),
                         None => JsValue::Undefined,
                     }),
                     _ => Err(RuntimeError::TypeError {
@@ -37,6 +37,7 @@ impl Interpreter {
     pub(crate) fn builtin_symbol_property(&self, prop: &str) -> Result<JsValue, RuntimeError> {
         match prop {
             "iterator" => Ok(JsValue::Symbol(symbol::symbol_iterator())),
+            "asyncIterator" => Ok(JsValue::Symbol(symbol::symbol_async_iterator())),
             "toPrimitive" => Ok(JsValue::Symbol(symbol::symbol_to_primitive())),
             "hasInstance" => Ok(JsValue::Symbol(symbol::symbol_has_instance())),
             "toStringTag" => Ok(JsValue::Symbol(symbol::symbol_to_string_tag())),
@@ -0,0 +1,384 @@
+use crate::errors::RuntimeError;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::JsValue;
+use crate::runtime::value::array::array_index;
+use crate::runtime::value::array::methods::normalize_index;
+use crate::runtime::value::symbol;
+use crate::runtime::value::typed_array::{JsArrayBuffer, JsDataView, JsTypedArray, TypedArrayKind};
+
+/// The largest buffer a script may allocate, so that a huge length is a
+/// RangeError rather than an abort.
+const MAX_BYTE_LENGTH: usize = 1 << 30;
+
+impl Interpreter {
+    /// `new ArrayBuffer(...)`, `new DataView(...)` or `new Uint8Array(...)`
+    /// and the other typed arrays.
+    pub(crate) fn construct_binary(
+        &mut self,
+        kind: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        match kind {
+            "ArrayBuffer" => {
+                let byte_length = self.coerce_index(args.first(), "Invalid array buffer length")?;
+                if byte_length > MAX_BYTE_LENGTH {
+                    return Err(self.throw_range_error("Array buffer allocation failed"));
+                }
+                let buffer = JsArrayBuffer::new(vec![0; byte_length]);
+                Ok(JsValue::ArrayBuffer(self.heap.alloc_cell(buffer)))
+            }
+            "DataView" => self.construct_data_view(args),
+            _ => {
+                let kind = TypedArrayKind::from_constructor(kind)
+                    .expect("binary constructors are ArrayBuffer, DataView or a typed array");
+                self.construct_typed_array(kind, args)
+            }
+        }
+    }
+
+    fn construct_typed_array(
+        &mut self,
+        kind: TypedArrayKind,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let name = kind.constructor_name();
+        let size = kind.element_size();
+        let source = args.first().cloned().unwrap_or(JsValue::Undefined);
+        match source {
+            JsValue::ArrayBuffer(buffer) => {
+                let byte_offset =
+                    self.coerce_index(args.get(1), "Start offset is out of bounds")?;
+                if !byte_offset.is_multiple_of(size) {
+                    return Err(self.throw_range_error(&format!(
+                        "start offset of {name} should be a multiple of {size}"
+                    )));
+                }
+                let (detached, buffer_length) = {
+                    let buffer = buffer.borrow();
+                    (buffer.detached, buffer.bytes.len())
+                };
+                if detached {
+                    return Err(
+                        self.throw_type_error("Cannot perform Construct on a detached ArrayBuffer")
+                    );
+                }
+                let length = match args.get(2) {
+                    None | Some(JsValue::Undefined) => {
+                        if !buffer_length.is_multiple_of(size) {
+                            return Err(self.throw_range_error(&format!(
+                                "byte length of {name} should be a multiple of {size}"
+                            )));
+                        }
+                        if byte_offset > buffer_length {
+                            return Err(self.throw_range_error(&format!(
+                                "Start offset {byte_offset} is outside the bounds of the buffer"
+                            )));
+                        }
+                        (buffer_length - byte_offset) / size
+                    }
+                    length => {
+                        let length = self.coerce_index(length, "Invalid typed array length")?;
+                        if byte_offset + length * size > buffer_length {
+                            return Err(self.throw_range_error(&format!(
+                                "Invalid typed array length: {length}"
+                            )));
+                        }
+                        length
+                    }
+                };
+                let array = JsTypedArray::new(kind, buffer, byte_offset, length);
+                Ok(JsValue::TypedArray(self.heap.alloc_cell(array)))
+            }
+            JsValue::TypedArray(other) => {
+                let values = other.borrow().values();
+                Ok(self.typed_array_from(kind, &values))
+            }
+            JsValue::Undefined
+            | JsValue::Null
+            | JsValue::Boolean(_)
+            | JsValue::Number(_)
+            | JsValue::String(_)
+            | JsValue::Symbol(_) => {
+                let length = self.coerce_index(Some(&source), "Invalid typed array length")?;
+                if length.saturating_mul(size) > MAX_BYTE_LENGTH {
+                    return Err(
+                        self.throw_range_error(&format!("Invalid typed array length: {length}"))
+                    );
+                }
+                Ok(self.typed_array_from(kind, &vec![0.0; length]))
+            }
+            source => {
+                let items = self.array_like_items(&source)?;
+                let mut values = Vec::with_capacity(items.len());
+                for item in &items {
+                    values.push(self.coerce_to_number(item)?);
+                }
+                Ok(self.typed_array_from(kind, &values))
+            }
+        }
+    }
+
+    /// A typed array of `kind` over a new buffer holding `values`.
+    pub(crate) fn typed_array_from(&mut self, kind: TypedArrayKind, values: &[f64]) -> JsValue {
+        let buffer = JsArrayBuffer::new(vec![0; values.len() * kind.element_size()]);
+        let buffer = self.heap.alloc_cell(buffer);
+        let array = JsTypedArray::new(kind, buffer, 0, values.len());
+        for (i, n) in values.iter().enumerate() {
+            array.set(i, *n);
+        }
+        JsValue::TypedArray(self.heap.alloc_cell(array))
+    }
+
+    /// The elements of an iterable, or of an array-like object with a
+    /// `length` when it has no `[Symbol.iterator]`.
+    fn array_like_items(&mut self, source: &JsValue) -> Result<Vec<JsValue>, RuntimeError> {
+        if let JsValue::Object(_) = source {
+            let iter_fn = self.get_symbol_property(source, &symbol::symbol_iterator())?;
+            if matches!(iter_fn, JsValue::Undefined | JsValue::Null) {
+                let length = self.get_property(source, "length")?;
+                let length = self.coerce_index(Some(&length), "Invalid typed array length")?;
+                return (0..length)
+                    .map(|i| self.get_property(source, &i.to_string()))
+                    .collect();
+            }
+        }
+        self.collect_iterable(source)
+    }
+
+    fn construct_data_view(&mut self, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
+        let Some(JsValue::ArrayBuffer(buffer)) = args.first() else {
+            return Err(self.throw_type_error(
+                "First argument to DataView constructor must be an ArrayBuffer",
+            ));
+        };
+        let buffer = *buffer;
+        let byte_offset = self.coerce_index(args.get(1), "Start offset is out of bounds")?;
+        if buffer.borrow().detached {
+            return Err(self.throw_type_error("Cannot perform Construct on a detached ArrayBuffer"));
+        }
+        let buffer_length = buffer.borrow().bytes.len();
+        if byte_offset > buffer_length {
+            return Err(self.throw_range_error(&format!(
+                "Start offset {byte_offset} is outside the bounds of the buffer"
+            )));
+        }
+        let byte_length = match args.get(2) {
+            None | Some(JsValue::Undefined) => buffer_length - byte_offset,
+            length => {
+                let length = self.coerce_index(length, "Invalid DataView length")?;
+                if byte_offset + length > buffer_length {
+                    return Err(
+                        self.throw_range_error(&format!("Invalid DataView length {length}"))
+                    );
+                }
+                length
+            }
+        };
+        let view = JsDataView::new(buffer, byte_offset, byte_length);
+        Ok(JsValue::DataView(self.heap.alloc_cell(view)))
+    }
+
+    /// ToIndex: a non-negative integer, with `undefined` as 0. Anything
+    /// negative or past 2^53 - 1 is a RangeError carrying `message`.
+    fn coerce_index(
+        &mut self,
+        value: Option<&JsValue>,
+        message: &str,
+    ) -> Result<usize, RuntimeError> {
+        let n = match value {
+            None | Some(JsValue::Undefined) => return Ok(0),
+            Some(value) => self.coerce_to_number(value)?,
+        };
+        let n = if n.is_nan() { 0.0 } else { n.trunc() };
+        if !(0.0..=9007199254740991.0).contains(&n) {
+            return Err(self.throw_range_error(message));
+        }
+        Ok(n as usize)
+    }
+
+    /// Properties read from an `ArrayBuffer`, a typed array or a
+    /// `DataView`. Anything else is `undefined`.
+    pub(crate) fn get_binary_property(&self, value: &JsValue, key: &str) -> JsValue {
+        let number = |n: usize| JsValue::Number(n as f64);
+        match value {
+            JsValue::ArrayBuffer(buffer) => match key {
+                "byteLength" => number(buffer.borrow().bytes.len()),
+                "detached" => JsValue::Boolean(buffer.borrow().detached),
+                _ => JsValue::Undefined,
+            },
+            JsValue::TypedArray(array) => {
+                let array = array.borrow();
+                match key {
+                    "length" => number(array.len()),
+                    "byteLength" => number(array.byte_length()),
+                    "byteOffset" if array.buffer.borrow().detached => number(0),
+                    "byteOffset" => number(array.byte_offset),
+                    "buffer" => JsValue::ArrayBuffer(array.buffer),
+                    "BYTES_PER_ELEMENT" => number(array.kind.element_size()),
+                    _ => array_index(key)
+                        .and_then(|idx| array.get(idx))
+                        .map_or(JsValue::Undefined, JsValue::Number),
+                }
+            }
+            JsValue::DataView(view) => {
+                let view = view.borrow();
+                match key {
+                    "byteLength" => number(view.byte_length()),
+                    "byteOffset" => number(view.byte_offset),
+                    "buffer" => JsValue::ArrayBuffer(view.buffer),
+                    _ => JsValue::Undefined,
+                }
+            }
+            _ => JsValue::Undefined,
+        }
+    }
+
+    /// Stores `value` at an index of a typed array, converted to its
+    /// element type. Typed arrays keep no other properties, so writes to
+    /// any other key are dropped, as writes to a read-only property are.
+    pub(crate) fn set_typed_array_property(
+        &mut self,
+        array: &Gc<GcCell<JsTypedArray>>,
+        key: &str,
+        value: &JsValue,
+    ) -> Result<(), RuntimeError> {
+        if let Some(idx) = array_index(key) {
+            let n = self.coerce_to_number(value)?;
+            array.borrow().set(idx, n);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn call_typed_array_method(
+        &mut self,
+        array: &Gc<GcCell<JsTypedArray>>,
+        method: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let (kind, len) = {
+            let array = array.borrow();
+            (array.kind, array.len() as i64)
+        };
+        match method {
+            "set" => {
+                let source = args.first().cloned().unwrap_or(JsValue::Undefined);
+                let offset = self.coerce_index(args.get(1), "offset is out of bounds")?;
+                // Read the source in full first, so that copying between
+                // overlapping views of one buffer is not affected by the
+                // writes.
+                let values = match &source {
+                    JsValue::TypedArray(other) => other.borrow().values(),
+                    _ => {
+                        let items = self.array_like_items(&source)?;
+                        let mut values = Vec::with_capacity(items.len());
+                        for item in &items {
+                            values.push(self.coerce_to_number(item)?);
+                        }
+                        values
+                    }
+                };
+                if offset + values.len() > len as usize {
+                    return Err(self.throw_range_error("offset is out of bounds"));
+                }
+                let array = array.borrow();
+                for (i, n) in values.into_iter().enumerate() {
+                    array.set(offset + i, n);
+                }
+                Ok(JsValue::Undefined)
+            }
+            "subarray" => {
+                let start = normalize_index(args.first(), 0, len);
+                let end = normalize_index(args.get(1), len, len).max(start);
+                let view = {
+                    let array = array.borrow();
+                    JsTypedArray::new(
+                        kind,
+                        array.buffer,
+                        array.byte_offset + start * kind.element_size(),
+                        end - start,
+                    )
+                };
+                Ok(JsValue::TypedArray(self.heap.alloc_cell(view)))
+            }
+            "slice" => {
+                let start = normalize_index(args.first(), 0, len);
+                let end = normalize_index(args.get(1), len, len).max(start);
+                let values = array.borrow().values()[start..end].to_vec();
+                Ok(self.typed_array_from(kind, &values))
+            }
+            "fill" => {
+                let n = match args.first() {
+                    Some(value) => self.coerce_to_number(value)?,
+                    None => f64::NAN,
+                };
+                let start = normalize_index(args.get(1), 0, len);
+                let end = normalize_index(args.get(2), len, len);
+                let borrowed = array.borrow();
+                for i in start..end {
+                    borrowed.set(i, n);
+                }
+                Ok(JsValue::TypedArray(*array))
+            }
+            _ => Err(self.throw_type_error(&format!("'{method}' is not a function"))),
+        }
+    }
+
+    pub(crate) fn call_array_buffer_method(
+        &mut self,
+        buffer: &Gc<GcCell<JsArrayBuffer>>,
+        method: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        match method {
+            "slice" => {
+                let bytes = {
+                    let buffer = buffer.borrow();
+                    let len = buffer.bytes.len() as i64;
+                    let start = normalize_index(args.first(), 0, len);
+                    let end = normalize_index(args.get(1), len, len).max(start);
+                    buffer.bytes[start..end].to_vec()
+                };
+                Ok(JsValue::ArrayBuffer(
+                    self.heap.alloc_cell(JsArrayBuffer::new(bytes)),
+                ))
+            }
+            _ => Err(self.throw_type_error(&format!("'{method}' is not a function"))),
+        }
+    }
+
+    /// `getUint16(offset, littleEndian)`, `setFloat64(offset, value,
+    /// littleEndian)` and the rest. Values are big-endian unless
+    /// `littleEndian` is true.
+    pub(crate) fn call_data_view_method(
+        &mut self,
+        view: &Gc<GcCell<JsDataView>>,
+        method: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let (verb, type_name) = method.split_at(3);
+        let Some(kind) = TypedArrayKind::from_accessor(type_name) else {
+            return Err(self.throw_type_error(&format!("'{method}' is not a function")));
+        };
+        const OUT_OF_BOUNDS: &str = "Offset is outside the bounds of the DataView";
+        let offset = self.coerce_index(args.first(), OUT_OF_BOUNDS)?;
+        let little_endian = |at: usize| args.get(at).is_some_and(JsValue::to_boolean);
+        match verb {
+            "get" => match view.borrow().get(kind, offset, little_endian(1)) {
+                Some(n) => Ok(JsValue::Number(n)),
+                None => Err(self.throw_range_error(OUT_OF_BOUNDS)),
+            },
+            "set" => {
+                let n = match args.get(1) {
+                    Some(value) => self.coerce_to_number(value)?,
+                    None => f64::NAN,
+                };
+                if !view.borrow().set(kind, offset, n, little_endian(2)) {
+                    return Err(self.throw_range_error(OUT_OF_BOUNDS));
+                }
+                Ok(JsValue::Undefined)
+            }
+            _ => Err(self.throw_type_error(&format!("'{method}' is not a function"))),
+        }
+    }
+}
@@ -1,8 +1,10 @@
 mod scope;
 
 use crate::errors::RuntimeError;
+use crate::parser::ast::Slot;
 use crate::runtime::gc::{Gc, GcCell, Heap, Trace, Tracer};
-use crate::runtime::value::JsValue;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::{JsValue, PropName};
 pub(crate) use scope::Binding;
 pub(crate) use scope::{BindingKind, Scope};
 use std::mem;
@@ -41,11 +43,49 @@ impl Environment {
         }
     }
 
-    pub fn define(&mut self, name: String, value: JsValue) {
+    /// Replaces the innermost scope with a copy of it. A `for` loop with a
+    /// `let` head does this between iterations, so that closures made in
+    /// one iteration keep the bindings they saw.
+    pub(crate) fn copy_innermost_scope(&mut self, heap: &mut Heap) {
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("environment must have at least one scope");
+        let copy = scope.borrow().clone();
+        *scope = heap.alloc_cell(copy);
+    }
+
+    /// How many scopes are open, for [`Environment::unwind_to`].
+    pub(crate) fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Closes the scopes opened since the environment was `depth` deep,
+    /// which an error thrown out of them left behind.
+    pub(crate) fn unwind_to(&mut self, depth: usize) {
+        self.scopes.truncate(depth.max(1));
+    }
+
+    pub fn define(&mut self, name: impl Into<PropName>, value: JsValue) {
         self.define_with_kind(name, value, BindingKind::Let);
     }
 
-    pub fn define_with_kind(&mut self, name: String, value: JsValue, kind: BindingKind) {
+    /// Defines `name` in the current scope. A `var` assigns to the binding
+    /// hoisted for it instead when one is visible from here without
+    /// leaving the enclosing function.
+    pub fn define_with_kind(
+        &mut self,
+        name: impl Into<PropName>,
+        value: JsValue,
+        kind: BindingKind,
+    ) {
+        let name = name.into();
+        if kind == BindingKind::Var
+            && let Some(scope) = self.hoisted_var_scope(&name)
+        {
+            scope.borrow_mut().set(&name, value);
+            return;
+        }
         self.scopes
             .last_mut()
             .expect("environment must have at least one scope")
@@ -53,6 +93,54 @@ impl Environment {
             .define_with_kind(name, value, kind);
     }
 
+    /// Declares a hoisted `var` as `undefined`, unless the current scope
+    /// already binds the name.
+    pub(crate) fn declare_var(&mut self, name: impl Into<PropName>) {
+        let name = name.into();
+        if !self.declares_locally(&name) {
+            self.scopes
+                .last_mut()
+                .expect("environment must have at least one scope")
+                .borrow_mut()
+                .define_with_kind(name, JsValue::Undefined, BindingKind::Var);
+        }
+    }
+
+    /// The kind of the nearest binding of `name`.
+    pub(crate) fn kind_of(&self, name: &str) -> Option<BindingKind> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.borrow().kind_of(name))
+    }
+
+    pub(crate) fn declares_locally(&self, name: &str) -> bool {
+        self.scopes
+            .last()
+            .is_some_and(|scope| scope.borrow().contains(name))
+    }
+
+    fn hoisted_var_scope(&self, name: &str) -> Option<Gc<GcCell<Scope>>> {
+        for scope in self.scopes.iter().rev() {
+            let borrowed = scope.borrow();
+            if let Some(kind) = borrowed.kind_of(name) {
+                return (kind == BindingKind::Var).then_some(*scope);
+            }
+            if borrowed.this_binding.is_some() {
+                return None;
+            }
+        }
+        None
+    }
+
+    pub fn declare_uninitialized(&mut self, name: impl Into<PropName>) {
+        self.scopes
+            .last_mut()
+            .expect("environment must have at least one scope")
+            .borrow_mut()
+            .declare_uninitialized(name, BindingKind::Let);
+    }
+
     pub fn get(&self, name: &str) -> Result<JsValue, RuntimeError> {
         if name == "this" {
             for scope in self.scopes.iter().rev() {
@@ -65,9 +153,8 @@ impl Environment {
         }
 
         for scope in self.scopes.iter().rev() {
-            let borrowed = scope.borrow();
-            if let Some(value) = borrowed.get(name) {
-                return Ok(value.clone());
+            if let Some(binding) = scope.borrow().binding(name) {
+                return read_binding(binding, name);
             }
         }
         Err(RuntimeError::UndefinedVariable {
@@ -76,16 +163,9 @@ impl Environment {
     }
 
     pub fn set(&mut self, name: &str, value: JsValue) -> Result<(), RuntimeError> {
-        for scope in self.scopes.iter_mut().rev() {
-            let mut borrowed = scope.borrow_mut();
-            if borrowed.get(name).is_some() {
-                if matches!(borrowed.kind_of(name), Some(BindingKind::Const)) {
-                    return Err(RuntimeError::ConstReassignment {
-                        name: name.to_string(),
-                    });
-                }
-                borrowed.set(name, value.clone());
-                return Ok(());
+        for scope in self.scopes.iter().rev() {
+            if let Some(binding) = scope.borrow_mut().binding_mut(name) {
+                return write_binding(binding, name, value);
             }
         }
         Err(RuntimeError::UndefinedVariable {
@@ -93,6 +173,204 @@ impl Environment {
         })
     }
 
+    /// Reads `name` from the binding the resolver placed at `slot`: the
+    /// scope `slot.depth` below the innermost one, at `slot.index`. When
+    /// that binding is not there yet, say because its declaration has
+    /// not run, the name is looked up as [`Environment::get`] would.
+    pub(crate) fn get_slot(&self, slot: Slot, name: &str) -> Result<JsValue, RuntimeError> {
+        if let Some(scope) = self.scope_at(slot.depth)
+            && let Some(binding) = scope.borrow().slot(slot.index as usize, name)
+        {
+            self.debug_check_slot(scope, slot, name);
+            return read_binding(binding, name);
+        }
+        self.get(name)
+    }
+
+    /// [`Environment::set`] through the binding at `slot`, looking `name`
+    /// up by name when that binding is not there.
+    pub(crate) fn set_slot(
+        &mut self,
+        slot: Slot,
+        name: &str,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        if let Some(scope) = self.scope_at(slot.depth) {
+            let mut borrowed = scope.borrow_mut();
+            if let Some(binding) = borrowed.slot_mut(slot.index as usize, name) {
+                let result = write_binding(binding, name, value);
+                drop(borrowed);
+                self.debug_check_slot(scope, slot, name);
+                return result;
+            }
+        }
+        self.set(name, value)
+    }
+
+    fn scope_at(&self, depth: u32) -> Option<Gc<GcCell<Scope>>> {
+        let index = self.scopes.len().checked_sub(depth as usize + 1)?;
+        Some(self.scopes[index])
+    }
+
+    /// A slot is only right when no scope nearer than it binds the same
+    /// name. Debug builds check that against a lookup by name, which is
+    /// what keeps the resolver honest in the tests.
+    fn debug_check_slot(&self, scope: Gc<GcCell<Scope>>, slot: Slot, name: &str) {
+        if cfg!(debug_assertions) {
+            let nearest = self
+                .scopes
+                .iter()
+                .rev()
+                .find(|candidate| candidate.borrow().contains(name));
+            assert!(
+                nearest.is_some_and(|nearest| Gc::ptr_eq(*nearest, scope)),
+                "`{name}` resolved to {slot:?}, but a nearer scope binds it"
+            );
+        }
+    }
+
+    /// [`Environment::set`] from sloppy mode code, where a name no scope
+    /// binds becomes a global `var` instead of failing.
+    pub(crate) fn set_or_declare_global(
+        &mut self,
+        name: &str,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        match self.set(name, value.clone()) {
+            Err(RuntimeError::UndefinedVariable { .. }) => {
+                self.scopes[0]
+                    .borrow_mut()
+                    .define_property(name, value, true, true);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    pub(crate) fn global_scope(&self) -> Gc<GcCell<Scope>> {
+        self.scopes[0]
+    }
+
+    /// Binds a builtin in the global scope, hidden from the global
+    /// object's keys. Only `configurable` ones can be deleted.
+    pub(crate) fn define_builtin(
+        &mut self,
+        name: impl Into<PropName>,
+        value: JsValue,
+        configurable: bool,
+    ) {
+        self.scopes[0]
+            .borrow_mut()
+            .define_property(name, value, false, configurable);
+    }
+
+    /// Whether `obj` is the global object, the `this` of the global scope.
+    fn is_global_object(&self, obj: Gc<GcCell<JsObject>>) -> bool {
+        matches!(
+            &self.scopes[0].borrow().this_binding,
+            Some(JsValue::Object(global)) if Gc::ptr_eq(*global, obj)
+        )
+    }
+
+    /// Reads `name` as a property of the global object `obj`. The global
+    /// `var` bindings, which include the builtins and top-level function
+    /// declarations, double as its properties; `let`, `const` and classes
+    /// do not. `None` when `obj` is another object or there is no such
+    /// binding.
+    pub(crate) fn global_property(&self, obj: Gc<GcCell<JsObject>>, name: &str) -> Option<JsValue> {
+        self.global_binding(obj, name, |binding| binding.value.clone())
+    }
+
+    /// Whether the global property `name` of `obj` is enumerable, or
+    /// `None` when there is no such property.
+    pub(crate) fn global_property_enumerable(
+        &self,
+        obj: Gc<GcCell<JsObject>>,
+        name: &str,
+    ) -> Option<bool> {
+        self.global_binding(obj, name, |binding| binding.enumerable)
+    }
+
+    fn global_binding<T>(
+        &self,
+        obj: Gc<GcCell<JsObject>>,
+        name: &str,
+        read: impl FnOnce(&Binding) -> T,
+    ) -> Option<T> {
+        if !self.is_global_object(obj) {
+            return None;
+        }
+        let global = self.scopes[0].borrow();
+        global
+            .binding(name)
+            .filter(|binding| binding.kind == BindingKind::Var && binding.initialized)
+            .map(read)
+    }
+
+    /// The names of the global object's binding-backed properties, in the
+    /// order they were bound; with `enumerable_only`, leaving out the
+    /// builtins. `None` when `obj` is another object.
+    pub(crate) fn global_property_names(
+        &self,
+        obj: Gc<GcCell<JsObject>>,
+        enumerable_only: bool,
+    ) -> Option<Vec<String>> {
+        if !self.is_global_object(obj) {
+            return None;
+        }
+        let global = self.scopes[0].borrow();
+        let names = global
+            .bindings()
+            .filter(|(_, binding)| {
+                binding.kind == BindingKind::Var
+                    && binding.initialized
+                    && (binding.enumerable || !enumerable_only)
+            })
+            .map(|(name, _)| name.to_string())
+            .collect();
+        Some(names)
+    }
+
+    /// `delete` of the global property `name` of `obj`: removes a
+    /// configurable binding and refuses a declared one. `None` when `obj`
+    /// is another object or no `var` binding has that name.
+    pub(crate) fn delete_global_property(
+        &mut self,
+        obj: Gc<GcCell<JsObject>>,
+        name: &str,
+    ) -> Option<bool> {
+        let configurable = self.global_binding(obj, name, |binding| binding.configurable)?;
+        if configurable {
+            self.scopes[0].borrow_mut().remove(name);
+        }
+        Some(configurable)
+    }
+
+    /// Writes `name` as a property of the global object `obj` through to
+    /// the global `var` binding of that name, declaring it if there is no
+    /// global binding yet. Returns `false`, leaving the write to the
+    /// object itself, when `obj` is another object or the name is bound
+    /// by `let`, `const` or a class.
+    pub(crate) fn set_global_property(
+        &mut self,
+        obj: Gc<GcCell<JsObject>>,
+        name: &str,
+        value: JsValue,
+    ) -> bool {
+        if !self.is_global_object(obj) {
+            return false;
+        }
+        let mut global = self.scopes[0].borrow_mut();
+        match global.kind_of(name) {
+            Some(BindingKind::Var) => global.set(name, value),
+            Some(_) => false,
+            None => {
+                global.define_property(name, value, true, true);
+                true
+            }
+        }
+    }
+
     pub fn capture(&self) -> Vec<Gc<GcCell<Scope>>> {
         self.scopes.clone()
     }
@@ -101,16 +379,59 @@ impl Environment {
         mem::replace(&mut self.scopes, scopes)
     }
 
+    pub(crate) fn global_names(&self) -> Vec<String> {
+        self.scopes
+            .first()
+            .map(|scope| {
+                scope
+                    .borrow()
+                    .bindings()
+                    .map(|(name, _)| name.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub(crate) fn current_scope_bindings_snapshot(
         &self,
-    ) -> std::collections::HashMap<String, Binding> {
+    ) -> std::collections::HashMap<PropName, Binding> {
         self.scopes
             .last()
-            .map(|scope| scope.borrow().bindings.clone())
+            .map(|scope| {
+                scope
+                    .borrow()
+                    .bindings()
+                    .map(|(name, binding)| (name.clone(), binding.clone()))
+                    .collect()
+            })
             .unwrap_or_default()
     }
 }
 
+fn read_binding(binding: &Binding, name: &str) -> Result<JsValue, RuntimeError> {
+    if !binding.initialized {
+        return Err(RuntimeError::UninitializedBinding {
+            name: name.to_owned(),
+        });
+    }
+    Ok(binding.value.clone())
+}
+
+fn write_binding(binding: &mut Binding, name: &str, value: JsValue) -> Result<(), RuntimeError> {
+    if !binding.initialized {
+        return Err(RuntimeError::UninitializedBinding {
+            name: name.to_owned(),
+        });
+    }
+    if matches!(binding.kind, BindingKind::Const | BindingKind::FunctionName) {
+        return Err(RuntimeError::ConstReassignment {
+            name: name.to_string(),
+        });
+    }
+    binding.value = value;
+    Ok(())
+}
+
 impl Trace for Environment {
     fn trace(&self, tracer: &mut Tracer) {
         for scope in &self.scopes {
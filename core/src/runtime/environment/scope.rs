@@ -1,5 +1,6 @@
+use crate::parser::ast::VarDeclKind;
 use crate::runtime::gc::{Trace, Tracer};
-use crate::runtime::value::JsValue;
+use crate::runtime::value::{JsValue, PropName};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -7,18 +8,51 @@ pub enum BindingKind {
     Let,
     Const,
     Var,
+    /// The name a named function expression sees itself by. It is
+    /// read-only like a `const`, but sloppy mode code assigning to it is
+    /// ignored rather than throwing.
+    FunctionName,
 }
 
+impl From<&VarDeclKind> for BindingKind {
+    fn from(kind: &VarDeclKind) -> Self {
+        match kind {
+            VarDeclKind::Let => BindingKind::Let,
+            VarDeclKind::Const => BindingKind::Const,
+            VarDeclKind::Var => BindingKind::Var,
+        }
+    }
+}
+
+/// Most block and function scopes bind a handful of names, which are
+/// found faster by comparing them than by hashing.
+const SCANNED_BINDINGS: usize = 8;
+
 #[derive(Debug, Clone)]
 pub struct Binding {
     pub value: JsValue,
     pub kind: BindingKind,
+    /// `false` while the binding is in its temporal dead zone.
+    pub initialized: bool,
+    /// Whether the global object lists it among its keys. Only builtins
+    /// are hidden; other scopes never look.
+    pub enumerable: bool,
+    /// Whether `delete` on the global object removes it, as for builtins
+    /// and globals created by assignment but not for declarations.
+    pub configurable: bool,
 }
 
 /// A single scope frame in the environment chain.
+///
+/// Bindings are stored in the order they were first defined, so code the
+/// resolver annotated can reach one by its index. Other lookups scan the
+/// bindings of a small scope and go through `names` in a larger one.
 #[derive(Debug, Clone)]
 pub struct Scope {
-    pub(crate) bindings: HashMap<String, Binding>,
+    /// Each name's index, kept once the scope has more than
+    /// [`SCANNED_BINDINGS`] bindings.
+    names: HashMap<PropName, usize>,
+    slots: Vec<(PropName, Binding)>,
     pub(crate) this_binding: Option<JsValue>,
 }
 
@@ -29,21 +63,22 @@ impl Scope {
 
     pub fn new_with_this(this_binding: Option<JsValue>) -> Self {
         Self {
-            bindings: HashMap::new(),
+            names: HashMap::new(),
+            slots: Vec::new(),
             this_binding,
         }
     }
 
     pub fn get(&self, name: &str) -> Option<&JsValue> {
-        self.bindings.get(name).map(|b| &b.value)
+        self.binding(name).map(|b| &b.value)
     }
 
     pub fn kind_of(&self, name: &str) -> Option<BindingKind> {
-        self.bindings.get(name).map(|b| b.kind)
+        self.binding(name).map(|b| b.kind)
     }
 
     pub fn set(&mut self, name: &str, value: JsValue) -> bool {
-        if let Some(binding) = self.bindings.get_mut(name) {
+        if let Some(binding) = self.binding_mut(name) {
             binding.value = value;
             true
         } else {
@@ -51,12 +86,144 @@ impl Scope {
         }
     }
 
-    pub fn define(&mut self, name: String, value: JsValue) {
+    fn index_of(&self, name: &str) -> Option<usize> {
+        if self.slots.len() <= SCANNED_BINDINGS {
+            self.slots.iter().position(|(bound, _)| *bound == name)
+        } else {
+            self.names.get(name).copied()
+        }
+    }
+
+    pub(crate) fn binding(&self, name: &str) -> Option<&Binding> {
+        let index = self.index_of(name)?;
+        Some(&self.slots[index].1)
+    }
+
+    pub(crate) fn binding_mut(&mut self, name: &str) -> Option<&mut Binding> {
+        let index = self.index_of(name)?;
+        Some(&mut self.slots[index].1)
+    }
+
+    /// The binding at `index`, provided it is the one for `name`.
+    pub(crate) fn slot(&self, index: usize, name: &str) -> Option<&Binding> {
+        match self.slots.get(index) {
+            Some((slot_name, binding)) if *slot_name == name => Some(binding),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn slot_mut(&mut self, index: usize, name: &str) -> Option<&mut Binding> {
+        match self.slots.get_mut(index) {
+            Some((slot_name, binding)) if *slot_name == name => Some(binding),
+            _ => None,
+        }
+    }
+
+    /// Unbinds `name`. Later bindings move down one index, so slots the
+    /// resolver assigned to them fall back to a lookup by name.
+    pub(crate) fn remove(&mut self, name: &str) -> bool {
+        let Some(index) = self.index_of(name) else {
+            return false;
+        };
+        self.slots.remove(index);
+        self.names = if self.slots.len() > SCANNED_BINDINGS {
+            let names = self.slots.iter().enumerate();
+            names
+                .map(|(index, (name, _))| (name.clone(), index))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        true
+    }
+
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.index_of(name).is_some()
+    }
+
+    /// The bindings in the order they were defined.
+    pub(crate) fn bindings(&self) -> impl Iterator<Item = (&PropName, &Binding)> {
+        self.slots.iter().map(|(name, binding)| (name, binding))
+    }
+
+    /// Binds `name`, replacing the binding it already has in this scope
+    /// in place so that its index stays the same.
+    pub(crate) fn insert(&mut self, name: impl Into<PropName>, binding: Binding) {
+        let name = name.into();
+        if let Some(index) = self.index_of(&name) {
+            self.slots[index].1 = binding;
+            return;
+        }
+        self.slots.push((name, binding));
+        if self.slots.len() > SCANNED_BINDINGS {
+            if self.names.is_empty() {
+                let names = self.slots.iter().enumerate();
+                self.names = names
+                    .map(|(index, (name, _))| (name.clone(), index))
+                    .collect();
+            } else {
+                let index = self.slots.len() - 1;
+                self.names.insert(self.slots[index].0.clone(), index);
+            }
+        }
+    }
+
+    pub fn define(&mut self, name: impl Into<PropName>, value: JsValue) {
         self.define_with_kind(name, value, BindingKind::Let);
     }
 
-    pub fn define_with_kind(&mut self, name: String, value: JsValue, kind: BindingKind) {
-        self.bindings.insert(name, Binding { value, kind });
+    pub fn define_with_kind(
+        &mut self,
+        name: impl Into<PropName>,
+        value: JsValue,
+        kind: BindingKind,
+    ) {
+        self.insert(
+            name,
+            Binding {
+                value,
+                kind,
+                initialized: true,
+                enumerable: true,
+                configurable: false,
+            },
+        );
+    }
+
+    /// Binds `name` as a `var` with the attributes it has as a property
+    /// of the global object.
+    pub(crate) fn define_property(
+        &mut self,
+        name: impl Into<PropName>,
+        value: JsValue,
+        enumerable: bool,
+        configurable: bool,
+    ) {
+        self.insert(
+            name,
+            Binding {
+                value,
+                kind: BindingKind::Var,
+                initialized: true,
+                enumerable,
+                configurable,
+            },
+        );
+    }
+
+    /// Declares `name` without a value; reading it before it is defined
+    /// throws a ReferenceError.
+    pub fn declare_uninitialized(&mut self, name: impl Into<PropName>, kind: BindingKind) {
+        self.insert(
+            name,
+            Binding {
+                value: JsValue::Undefined,
+                kind,
+                initialized: false,
+                enumerable: true,
+                configurable: false,
+            },
+        );
     }
 }
 
@@ -68,7 +235,7 @@ impl Trace for Binding {
 
 impl Trace for Scope {
     fn trace(&self, tracer: &mut Tracer) {
-        for binding in self.bindings.values() {
+        for (_, binding) in &self.slots {
             binding.trace(tracer);
         }
         self.this_binding.trace(tracer);
@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 
 use super::Microtask;
+use crate::runtime::gc::{Trace, Tracer};
 
 #[derive(Default)]
 pub struct MicrotaskQueue {
@@ -20,3 +21,19 @@ impl MicrotaskQueue {
         self.queue.is_empty()
     }
 }
+
+impl Trace for MicrotaskQueue {
+    fn trace(&self, tracer: &mut Tracer) {
+        for task in &self.queue {
+            match task {
+                Microtask::PromiseReaction {
+                    reaction, value, ..
+                } => {
+                    reaction.trace(tracer);
+                    value.trace(tracer);
+                }
+                Microtask::Callback { callback } => callback.trace(tracer),
+            }
+        }
+    }
+}
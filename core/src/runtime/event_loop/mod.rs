@@ -2,8 +2,9 @@ mod microtask_queue;
 mod task_queue;
 
 use std::collections::HashSet;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use crate::runtime::gc::{Trace, Tracer};
 use crate::runtime::value::JsValue;
 use crate::runtime::value::promise::PromiseReaction;
 
@@ -27,6 +28,13 @@ pub struct EventLoop {
     next_timer_id: u64,
     next_animation_id: u64,
     realtime: bool,
+    /// Set by manual timer mode: the clock then only moves when told to,
+    /// even in realtime mode.
+    manual: bool,
+    /// When the clock read 0, for following real time.
+    started: Instant,
+    /// Drives the blocking sleeps when the host runs no tokio runtime of
+    /// its own, built the first time one is needed.
     runtime: Option<tokio::runtime::Runtime>,
     microtasks: MicrotaskQueue,
     tasks: TaskQueue,
@@ -47,21 +55,14 @@ impl EventLoop {
     }
 
     pub fn new_with_realtime(realtime: bool) -> Self {
-        let runtime = if realtime {
-            tokio::runtime::Builder::new_current_thread()
-                .enable_time()
-                .build()
-                .ok()
-        } else {
-            None
-        };
-
         Self {
             now_ms: 0,
             next_timer_id: 1,
             next_animation_id: 1,
             realtime,
-            runtime,
+            manual: false,
+            started: Instant::now(),
+            runtime: None,
             microtasks: MicrotaskQueue::default(),
             tasks: TaskQueue::default(),
             canceled_timer_ids: HashSet::new(),
@@ -74,6 +75,20 @@ impl EventLoop {
         self.now_ms
     }
 
+    pub fn set_manual(&mut self, manual: bool) {
+        self.manual = manual;
+    }
+
+    /// Catches the clock up with the time really spent, in realtime mode,
+    /// so a long synchronous run counts towards timers already waiting
+    /// and new delays start from now.
+    pub fn sync_clock(&mut self) {
+        if self.realtime && !self.manual {
+            let elapsed = self.started.elapsed().as_millis() as u64;
+            self.now_ms = self.now_ms.max(elapsed);
+        }
+    }
+
     pub fn enqueue_microtask(&mut self, task: Microtask) {
         self.microtasks.enqueue(task);
     }
@@ -83,6 +98,7 @@ impl EventLoop {
     }
 
     pub fn schedule_timer(&mut self, callback: JsValue, delay_ms: u64, interval: bool) -> u64 {
+        self.sync_clock();
         let id = self.next_timer_id;
         self.next_timer_id += 1;
         let task = TimerTask {
@@ -105,20 +121,70 @@ impl EventLoop {
         self.tasks.clear(id);
     }
 
+    /// Sleeps until the next timer is due, in realtime mode, and moves
+    /// the clock to it. Inside a host's tokio runtime, where blocking on
+    /// a runtime of our own would panic, the thread sleeps instead.
     pub fn advance_to_next_task(&mut self) {
-        if let Some(next_due) = self.tasks.next_due_time() {
-            if self.realtime && next_due > self.now_ms {
-                let sleep_for = Duration::from_millis(next_due - self.now_ms);
-                if let Some(rt) = &self.runtime {
-                    rt.block_on(async {
-                        tokio::time::sleep(sleep_for).await;
-                    });
-                } else {
-                    std::thread::sleep(sleep_for);
+        if let Some(wait) = self.realtime_wait() {
+            if tokio::runtime::Handle::try_current().is_ok() {
+                std::thread::sleep(wait);
+            } else {
+                if self.runtime.is_none() {
+                    self.runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_time()
+                        .build()
+                        .ok();
+                }
+                match &self.runtime {
+                    Some(rt) => rt.block_on(async { tokio::time::sleep(wait).await }),
+                    None => std::thread::sleep(wait),
                 }
             }
-            self.now_ms = next_due;
         }
+        self.skip_to_next_task();
+    }
+
+    /// How long realtime mode has to wait for the next timer, or `None`
+    /// when it is due already or the clock is virtual.
+    pub fn realtime_wait(&mut self) -> Option<Duration> {
+        self.sync_clock();
+        let next_due = self.tasks.next_due_time()?;
+        (self.realtime && next_due > self.now_ms)
+            .then(|| Duration::from_millis(next_due - self.now_ms))
+    }
+
+    /// Moves the clock to the next timer without sleeping, once the
+    /// caller has waited out `realtime_wait`.
+    pub fn skip_to_next_task(&mut self) {
+        if let Some(next_due) = self.tasks.next_due_time() {
+            self.now_ms = self.now_ms.max(next_due);
+        }
+    }
+
+    /// Moves the clock to the next timer due no later than `limit_ms`,
+    /// or to `limit_ms` itself when there is none, without sleeping.
+    /// Returns whether a timer is due.
+    pub fn advance_within(&mut self, limit_ms: u64) -> bool {
+        match self.tasks.next_due_time() {
+            Some(next_due) if next_due <= limit_ms => {
+                self.now_ms = self.now_ms.max(next_due);
+                true
+            }
+            _ => {
+                self.now_ms = self.now_ms.max(limit_ms);
+                false
+            }
+        }
+    }
+
+    pub fn pending_timer_count(&self) -> usize {
+        self.tasks.active_count()
+    }
+
+    pub fn next_timer_due_in(&self) -> Option<u64> {
+        self.tasks
+            .next_due_time()
+            .map(|due| due.saturating_sub(self.now_ms))
     }
 
     pub fn pop_ready_task(&mut self) -> Option<TimerTask> {
@@ -167,3 +233,13 @@ impl EventLoop {
         callbacks
     }
 }
+
+impl Trace for EventLoop {
+    fn trace(&self, tracer: &mut Tracer) {
+        self.microtasks.trace(tracer);
+        self.tasks.trace(tracer);
+        for (_, callback) in &self.animation_callbacks {
+            callback.trace(tracer);
+        }
+    }
+}
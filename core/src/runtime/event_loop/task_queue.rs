@@ -1,3 +1,4 @@
+use crate::runtime::gc::{Trace, Tracer};
 use crate::runtime::value::JsValue;
 
 #[derive(Debug, Clone)]
@@ -53,7 +54,19 @@ impl TaskQueue {
         self.tasks.remove(idx)
     }
 
+    pub fn active_count(&self) -> usize {
+        self.tasks.iter().filter(|task| task.active).count()
+    }
+
     pub fn is_empty(&self) -> bool {
         !self.tasks.iter().any(|task| task.active)
     }
 }
+
+impl Trace for TaskQueue {
+    fn trace(&self, tracer: &mut Tracer) {
+        for task in &self.tasks {
+            task.callback.trace(tracer);
+        }
+    }
+}
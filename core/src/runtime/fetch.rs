@@ -0,0 +1,253 @@
+//! The HTTP side of the `fetch` global.
+//!
+//! Each request runs on its own worker thread with a blocking client, so
+//! a slow server never stalls the script. Finished requests come back
+//! through a channel that the event loop reads between tasks, settling
+//! the promise `fetch` returned for each one. Bodies are read in full on
+//! the worker before the response is handed over. Redirects are followed
+//! by hand so that every hop is checked against the fetch policy.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crate::embedding::promise_resolver::PromiseResolver;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// As many redirects as browsers follow before giving up.
+const MAX_REDIRECTS: usize = 20;
+
+/// Which hosts scripts may `fetch` from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FetchPolicy {
+    #[default]
+    AllowAll,
+    /// Only these hosts, compared without their port and ignoring case.
+    AllowHosts(Vec<String>),
+    /// Every call rejects without touching the network.
+    Disabled,
+}
+
+impl FetchPolicy {
+    /// Checks `url` against the policy, giving the reason it may not be
+    /// fetched otherwise.
+    pub(crate) fn check(&self, url: &str) -> Result<(), String> {
+        let host = url_host(url).ok_or_else(|| format!("invalid URL '{url}'"))?;
+        match self {
+            Self::AllowAll => Ok(()),
+            Self::AllowHosts(hosts) if hosts.iter().any(|h| h.eq_ignore_ascii_case(host)) => Ok(()),
+            Self::AllowHosts(_) => Err(format!("host '{host}' is not allowed")),
+            Self::Disabled => Err("fetch is disabled".to_string()),
+        }
+    }
+}
+
+/// The host of an `http` or `https` URL, without userinfo or port.
+fn url_host(url: &str) -> Option<&str> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return None;
+    }
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host_port.strip_prefix('[') {
+        Some(bracketed) => bracketed.split_once(']')?.0,
+        None => host_port.split(':').next()?,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FetchRequest {
+    pub url: String,
+    pub method: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// A response with its body read. HTTP error statuses are responses too;
+/// only failing to get one at all is an error.
+#[derive(Debug, Clone)]
+pub(crate) struct FetchedResponse {
+    pub url: String,
+    pub status: u16,
+    pub status_text: String,
+    /// Names lowercased, repeated headers joined with `", "`.
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+type Completion = (u64, Result<FetchedResponse, String>);
+
+/// Requests in flight and the promises waiting on them.
+pub(crate) struct FetchQueue {
+    pub policy: FetchPolicy,
+    next_id: u64,
+    pending: HashMap<u64, PromiseResolver>,
+    sender: Sender<Completion>,
+    receiver: Receiver<Completion>,
+}
+
+impl Default for FetchQueue {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            policy: FetchPolicy::default(),
+            next_id: 0,
+            pending: HashMap::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl FetchQueue {
+    /// Starts `request` on a worker thread; `resolver` is handed back by
+    /// `next_finished` once it completes.
+    pub fn start(&mut self, request: FetchRequest, resolver: PromiseResolver) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.insert(id, resolver);
+        let sender = self.sender.clone();
+        let policy = self.policy.clone();
+        thread::spawn(move || {
+            // The context may be gone by the time the request finishes.
+            let _ = sender.send((id, send_request(request, &policy)));
+        });
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// A request that has finished, waiting up to `timeout` for one when
+    /// none has yet. `None` waits for as long as it takes.
+    pub fn next_finished(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> Option<(PromiseResolver, Result<FetchedResponse, String>)> {
+        if !self.has_pending() {
+            return None;
+        }
+        let received = match timeout {
+            Some(timeout) => self.receiver.recv_timeout(timeout),
+            None => self
+                .receiver
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let (id, result) = received.ok()?;
+        let resolver = self.pending.remove(&id)?;
+        Some((resolver, result))
+    }
+}
+
+/// Sends `request`, following redirects as long as `policy` allows each
+/// new location.
+fn send_request(
+    mut request: FetchRequest,
+    policy: &FetchPolicy,
+) -> Result<FetchedResponse, String> {
+    let agent = ureq::AgentBuilder::new()
+        .redirects(0)
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(READ_TIMEOUT)
+        .build();
+    let mut redirects = 0;
+    let response = loop {
+        let response = send_once(&agent, &request)?;
+        let status = response.status();
+        let Some(location) = response
+            .header("location")
+            .filter(|_| matches!(status, 301 | 302 | 303 | 307 | 308))
+        else {
+            break response;
+        };
+        redirects += 1;
+        if redirects > MAX_REDIRECTS {
+            return Err("too many redirects".to_string());
+        }
+        let next = resolve_location(&request.url, location)
+            .ok_or_else(|| format!("invalid redirect location '{location}'"))?;
+        policy.check(&next)?;
+        // As in browsers, only 307 and 308 resend the method and body.
+        if status == 303 || (matches!(status, 301 | 302) && request.method == "POST") {
+            request.method = "GET".to_string();
+            request.body = None;
+        }
+        request.url = next;
+    };
+    let url = request.url;
+    let status = response.status();
+    let status_text = response.status_text().to_string();
+    let headers = response
+        .headers_names()
+        .into_iter()
+        .map(|name| {
+            let value = response.all(&name).join(", ");
+            (name.to_ascii_lowercase(), value)
+        })
+        .collect();
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|err| err.to_string())?;
+    Ok(FetchedResponse {
+        url,
+        status,
+        status_text,
+        headers,
+        body: String::from_utf8_lossy(&bytes).into_owned(),
+    })
+}
+
+fn send_once(agent: &ureq::Agent, request: &FetchRequest) -> Result<ureq::Response, String> {
+    let mut call = agent.request(&request.method, &request.url);
+    for (name, value) in &request.headers {
+        call = call.set(name, value);
+    }
+    let result = match &request.body {
+        Some(body) => call.send_string(body),
+        None => call.call(),
+    };
+    match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => Ok(response),
+        Err(ureq::Error::Transport(err)) => Err(err.to_string()),
+    }
+}
+
+/// The absolute URL a `Location` header names, relative to `base`.
+fn resolve_location(base: &str, location: &str) -> Option<String> {
+    let has_scheme = location.split_once("://").is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'))
+    });
+    if has_scheme {
+        // The policy check rejects schemes other than http and https.
+        return Some(location.to_string());
+    }
+    let (scheme, rest) = base.split_once("://")?;
+    if let Some(network_path) = location.strip_prefix("//") {
+        return Some(format!("{scheme}://{network_path}"));
+    }
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let origin = &base[..scheme.len() + 3 + authority_end];
+    if location.starts_with('/') {
+        return Some(format!("{origin}{location}"));
+    }
+    let path = rest[authority_end..].split(['?', '#']).next().unwrap_or("");
+    if location.starts_with('?') {
+        return Some(format!("{origin}{path}{location}"));
+    }
+    let directory = &path[..path.rfind('/').map_or(0, |slash| slash + 1)];
+    let directory = if directory.is_empty() { "/" } else { directory };
+    Some(format!("{origin}{directory}{location}"))
+}
@@ -3,14 +3,27 @@ use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::ptr::NonNull;
 
+use super::incremental::{Cycle, PauseHistogram};
 use super::marker;
 use super::sweeper;
 use super::trace::Trace;
 
 pub type GcCell<T> = RefCell<T>;
 
-struct GcHeader {
-    marked: Cell<bool>,
+/// Tri-colour mark state. Outside a collection every object is white.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Color {
+    White,
+    /// Reached but not traced yet.
+    Grey,
+    Black,
+    /// Traced, then mutated while an incremental mark was under way, so
+    /// it is traced again before sweeping.
+    Dirty,
+}
+
+pub(super) struct GcHeader {
+    pub(super) color: Cell<Color>,
     value: Box<dyn TraceAny>,
 }
 
@@ -75,6 +88,11 @@ impl<T: Any> Gc<GcCell<T>> {
 
     pub fn borrow_mut(&self) -> RefMut<'_, T> {
         let header = unsafe { self.ptr.as_ref() };
+        // Write barrier: a traced object may be about to take a reference
+        // the incremental marker has not seen.
+        if header.color.get() == Color::Black {
+            header.color.set(Color::Dirty);
+        }
         let cell = header
             .value
             .as_any()
@@ -85,7 +103,7 @@ impl<T: Any> Gc<GcCell<T>> {
 }
 
 pub struct ErasedGc {
-    ptr: NonNull<GcHeader>,
+    pub(super) ptr: NonNull<GcHeader>,
 }
 
 impl Copy for ErasedGc {}
@@ -116,7 +134,7 @@ impl std::hash::Hash for ErasedGc {
 }
 
 impl ErasedGc {
-    fn header(&self) -> &GcHeader {
+    pub(super) fn header(&self) -> &GcHeader {
         unsafe { self.ptr.as_ref() }
     }
 }
@@ -136,13 +154,19 @@ pub struct CollectStats {
     pub before: usize,
     pub after: usize,
     pub collected: usize,
+    /// The longest single pause spent on this collection.
+    pub pause_micros: u64,
+    /// Every pause this heap has taken so far, full or budgeted.
+    pub pauses: PauseHistogram,
 }
 
 pub struct Heap {
-    objects: Vec<Box<GcHeader>>,
-    live_count: usize,
-    alloc_count: usize,
-    collection_threshold: usize,
+    pub(super) objects: Vec<Box<GcHeader>>,
+    pub(super) live_count: usize,
+    pub(super) alloc_count: usize,
+    pub(super) collection_threshold: usize,
+    pub(super) cycle: Option<Cycle>,
+    pub(super) pauses: PauseHistogram,
 }
 
 impl Heap {
@@ -152,15 +176,18 @@ impl Heap {
             live_count: 0,
             alloc_count: 0,
             collection_threshold: 1024,
+            cycle: None,
+            pauses: PauseHistogram::default(),
         }
     }
 
     pub fn alloc<T: Trace + Any>(&mut self, value: T) -> Gc<T> {
         let mut boxed = Box::new(GcHeader {
-            marked: Cell::new(false),
+            color: Cell::new(Color::White),
             value: Box::new(value),
         });
         let ptr = NonNull::from(boxed.as_mut());
+        self.shade_new(&ErasedGc { ptr });
         self.objects.push(boxed);
         self.live_count += 1;
         self.alloc_count += 1;
@@ -194,26 +221,76 @@ impl Heap {
         self.alloc_count >= self.collection_threshold
     }
 
+    /// Whether allocation has run `factor` times past the collection
+    /// threshold, too far for budgeted steps to catch up.
+    pub fn exceeds_threshold(&self, factor: usize) -> bool {
+        self.alloc_count >= self.collection_threshold.saturating_mul(factor)
+    }
+
     pub fn collect(&mut self, roots: &[ErasedGc]) -> CollectStats {
+        self.collect_with(roots, |_| {})
+    }
+
+    /// Like [`Heap::collect`], but gives `before_sweep` a look at the
+    /// marked heap first so weak references to unmarked objects can be
+    /// cleared before those objects are freed.
+    pub fn collect_with(
+        &mut self,
+        roots: &[ErasedGc],
+        before_sweep: impl FnOnce(&Heap),
+    ) -> CollectStats {
+        let started = std::time::Instant::now();
+        self.abandon_cycle();
         let before = self.live_count;
         marker::mark_from_roots(self, roots);
+        before_sweep(self);
         let collected = sweeper::sweep(self);
         self.live_count -= collected;
+        self.finish_collection(before, collected, started)
+    }
+
+    /// Resets the allocation trigger and records the pause that ended a
+    /// collection.
+    pub(super) fn finish_collection(
+        &mut self,
+        before: usize,
+        collected: usize,
+        started: std::time::Instant,
+    ) -> CollectStats {
         self.alloc_count = self.live_count;
         self.collection_threshold = (self.live_count.max(1)) * 2;
+        let pause_micros = self.record_pause(started);
+        let longest = self
+            .cycle
+            .take()
+            .map_or(pause_micros, |cycle| cycle.longest_pause.max(pause_micros));
         CollectStats {
             before,
             after: self.live_count,
             collected,
+            pause_micros: longest,
+            pauses: self.pauses,
         }
     }
 
+    pub(super) fn record_pause(&mut self, started: std::time::Instant) -> u64 {
+        let micros = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+        self.pauses.record(micros);
+        micros
+    }
+
+    pub(crate) fn cells<T: Any>(&self) -> impl Iterator<Item = &GcCell<T>> {
+        self.objects
+            .iter()
+            .filter_map(|header| header.value.as_any().downcast_ref::<GcCell<T>>())
+    }
+
     pub(crate) fn mark_erased(&self, gc: &ErasedGc) {
-        gc.header().marked.set(true);
+        gc.header().color.set(Color::Black);
     }
 
     pub(crate) fn is_marked_erased(&self, gc: &ErasedGc) -> bool {
-        gc.header().marked.get()
+        gc.header().color.get() != Color::White
     }
 
     pub(crate) fn trace_erased(&self, gc: &ErasedGc, tracer: &mut super::trace::Tracer) {
@@ -223,8 +300,8 @@ impl Heap {
     pub(crate) fn sweep_unmarked(&mut self) -> usize {
         let before = self.objects.len();
         self.objects.retain(|header| {
-            if header.marked.get() {
-                header.marked.set(false);
+            if header.color.get() != Color::White {
+                header.color.set(Color::White);
                 true
             } else {
                 false
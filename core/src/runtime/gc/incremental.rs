@@ -0,0 +1,242 @@
+use std::time::{Duration, Instant};
+
+use super::heap::{CollectStats, Color, ErasedGc, Heap};
+use super::trace::Tracer;
+
+/// Objects traced or swept between clock reads.
+const CLOCK_STRIDE: usize = 64;
+
+/// How an interpreter collects on its own between tasks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GcMode {
+    /// Only explicit `collect_garbage` calls and `$262.gc()` collect.
+    #[default]
+    Manual,
+    /// Once the allocation threshold is crossed, spend at most
+    /// `budget_micros` per task on an incremental collection. When
+    /// allocation outruns it by `full_collect_factor` times the
+    /// threshold, stop the world and collect in one go instead.
+    Budgeted {
+        budget_micros: u64,
+        full_collect_factor: usize,
+    },
+}
+
+/// Collector pauses counted by length.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PauseHistogram {
+    counts: [u64; 6],
+    max_micros: u64,
+    total_micros: u64,
+}
+
+impl PauseHistogram {
+    /// Upper bounds of every bucket but the last, which takes the rest.
+    pub const BUCKET_LIMITS_MICROS: [u64; 5] = [100, 500, 1_000, 4_000, 16_000];
+
+    pub fn record(&mut self, micros: u64) {
+        let bucket = Self::BUCKET_LIMITS_MICROS
+            .iter()
+            .position(|&limit| micros < limit)
+            .unwrap_or(Self::BUCKET_LIMITS_MICROS.len());
+        self.counts[bucket] += 1;
+        self.max_micros = self.max_micros.max(micros);
+        self.total_micros += micros;
+    }
+
+    pub fn counts(&self) -> [u64; 6] {
+        self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn max_micros(&self) -> u64 {
+        self.max_micros
+    }
+
+    /// Every pause added together.
+    pub fn total_micros(&self) -> u64 {
+        self.total_micros
+    }
+}
+
+pub(super) enum Phase {
+    Marking { worklist: Vec<ErasedGc> },
+    Sweeping { cursor: usize },
+}
+
+/// An incremental collection in progress between budgeted steps.
+pub(super) struct Cycle {
+    phase: Phase,
+    before: usize,
+    collected: usize,
+    pub(super) longest_pause: u64,
+}
+
+impl Heap {
+    /// Whether a budgeted collection has started and not yet finished.
+    pub fn is_collecting(&self) -> bool {
+        self.cycle.is_some()
+    }
+
+    pub fn pause_histogram(&self) -> PauseHistogram {
+        self.pauses
+    }
+
+    /// Advances an incremental collection for about `max_micros`,
+    /// starting one if none is under way. Returns the stats once the
+    /// collection completes.
+    ///
+    /// Marking is resumed from a grey work list kept between calls, with
+    /// the roots re-scanned each time. Objects allocated meanwhile are
+    /// treated as live and objects mutated after being traced are traced
+    /// again, so nothing reachable when a step ends is freed.
+    pub fn collect_budgeted(
+        &mut self,
+        roots: &[ErasedGc],
+        max_micros: u64,
+    ) -> Option<CollectStats> {
+        self.collect_budgeted_with(roots, max_micros, |_| {})
+    }
+
+    /// Like [`Heap::collect_budgeted`], with the hook of
+    /// [`Heap::collect_with`] run once marking is complete.
+    pub fn collect_budgeted_with(
+        &mut self,
+        roots: &[ErasedGc],
+        max_micros: u64,
+        before_sweep: impl FnOnce(&Heap),
+    ) -> Option<CollectStats> {
+        let started = Instant::now();
+        let deadline = started + Duration::from_micros(max_micros);
+        let mut cycle = self.cycle.take().unwrap_or_else(|| Cycle {
+            phase: Phase::Marking {
+                worklist: Vec::new(),
+            },
+            before: self.live_count,
+            collected: 0,
+            longest_pause: 0,
+        });
+
+        if let Phase::Marking { worklist } = &mut cycle.phase {
+            for root in roots {
+                shade(root, worklist);
+            }
+            if !self.drain(worklist, Some(deadline)) {
+                return self.suspend(cycle, started);
+            }
+            self.remark(roots, worklist);
+            before_sweep(self);
+            cycle.phase = Phase::Sweeping { cursor: 0 };
+        }
+
+        let Phase::Sweeping { cursor } = &mut cycle.phase else {
+            unreachable!("marking always ends in sweeping");
+        };
+        let mut stride = 0;
+        while *cursor < self.objects.len() {
+            stride += 1;
+            if stride % CLOCK_STRIDE == 0 && Instant::now() >= deadline {
+                return self.suspend(cycle, started);
+            }
+            let color = &self.objects[*cursor].color;
+            if color.get() == Color::White {
+                self.objects.swap_remove(*cursor);
+                cycle.collected += 1;
+                self.live_count -= 1;
+            } else {
+                color.set(Color::White);
+                *cursor += 1;
+            }
+        }
+
+        let (before, collected) = (cycle.before, cycle.collected);
+        self.cycle = Some(cycle);
+        Some(self.finish_collection(before, collected, started))
+    }
+
+    /// Colours a freshly allocated object so the collection under way
+    /// keeps it: grey while marking, so its contents are traced, and black
+    /// while sweeping.
+    pub(super) fn shade_new(&mut self, gc: &ErasedGc) {
+        match &mut self.cycle {
+            Some(Cycle {
+                phase: Phase::Marking { worklist },
+                ..
+            }) => {
+                gc.header().color.set(Color::Grey);
+                worklist.push(*gc);
+            }
+            Some(Cycle {
+                phase: Phase::Sweeping { .. },
+                ..
+            }) => gc.header().color.set(Color::Black),
+            None => {}
+        }
+    }
+
+    /// Drops a budgeted collection in favour of a full one, which starts
+    /// from all-white marks.
+    pub(super) fn abandon_cycle(&mut self) {
+        if self.cycle.take().is_some() {
+            for header in &self.objects {
+                header.color.set(Color::White);
+            }
+        }
+    }
+
+    fn suspend(&mut self, mut cycle: Cycle, started: Instant) -> Option<CollectStats> {
+        let pause = self.record_pause(started);
+        cycle.longest_pause = cycle.longest_pause.max(pause);
+        self.cycle = Some(cycle);
+        None
+    }
+
+    /// Traces grey objects until the work list is empty, or returns
+    /// false when `deadline` passes first.
+    fn drain(&self, worklist: &mut Vec<ErasedGc>, deadline: Option<Instant>) -> bool {
+        let mut tracer = Tracer::new();
+        let mut stride = 0;
+        while let Some(gc) = worklist.pop() {
+            gc.header().color.set(Color::Black);
+            self.trace_erased(&gc, &mut tracer);
+            for child in tracer.take_discovered() {
+                shade(&child, worklist);
+            }
+            stride += 1;
+            if stride % CLOCK_STRIDE == 0
+                && deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return worklist.is_empty();
+            }
+        }
+        true
+    }
+
+    /// Finishes marking without a budget: roots may have changed since
+    /// the last step and objects mutated after tracing need another look.
+    fn remark(&self, roots: &[ErasedGc], worklist: &mut Vec<ErasedGc>) {
+        for root in roots {
+            shade(root, worklist);
+        }
+        for header in &self.objects {
+            if header.color.get() == Color::Dirty {
+                header.color.set(Color::Grey);
+                worklist.push(ErasedGc {
+                    ptr: std::ptr::NonNull::from(header.as_ref()),
+                });
+            }
+        }
+        self.drain(worklist, None);
+    }
+}
+
+fn shade(gc: &ErasedGc, worklist: &mut Vec<ErasedGc>) {
+    let header = gc.header();
+    if header.color.get() == Color::White {
+        header.color.set(Color::Grey);
+        worklist.push(*gc);
+    }
+}
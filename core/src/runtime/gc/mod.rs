@@ -1,7 +1,9 @@
 pub mod heap;
+pub mod incremental;
 pub mod marker;
 pub mod sweeper;
 pub mod trace;
 
 pub use heap::{CollectStats, ErasedGc, Gc, GcCell, Heap};
+pub use incremental::{GcMode, PauseHistogram};
 pub use trace::{Trace, Tracer};
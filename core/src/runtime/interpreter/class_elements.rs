@@ -0,0 +1,266 @@
+use super::Interpreter;
+use super::RuntimeClass;
+use super::eval_class::is_object_like;
+use super::eval_expr_helpers::logical_assigns;
+use super::property_access::PropKey;
+use crate::errors::RuntimeError;
+use crate::parser::ast::{AssignOp, ClassMethodKind, Expr};
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::value::JsValue;
+use crate::runtime::value::object::{JsObject, Property};
+use crate::runtime::value::symbol::JsSymbol;
+
+/// Where a class field is defined.
+#[derive(Debug, Clone)]
+pub(crate) enum FieldKey {
+    Public(PropKey),
+    Private(JsSymbol),
+}
+
+impl FieldKey {
+    /// The key as function names and error messages show it.
+    pub(crate) fn display(&self) -> String {
+        match self {
+            FieldKey::Public(key) => key.display(),
+            FieldKey::Private(sym) => sym.description.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl Interpreter {
+    /// The symbol the innermost class declaring `#name` made for it when
+    /// it was evaluated.
+    pub(crate) fn private_name(&mut self, name: &str) -> Result<JsSymbol, RuntimeError> {
+        match self.env.get(&format!("#{name}")) {
+            Ok(JsValue::Symbol(sym)) => Ok(sym),
+            _ => Err(self.throw_syntax_error(&format!(
+                "Private field '#{name}' must be declared in an enclosing class"
+            ))),
+        }
+    }
+
+    /// Gives a new instance of `class` its private methods and fields,
+    /// once its parent class, if any, has initialized it.
+    pub(crate) fn initialize_instance(
+        &mut self,
+        class: &RuntimeClass,
+        this: &JsValue,
+    ) -> Result<(), RuntimeError> {
+        for (sym, property) in class.private_methods.iter() {
+            self.define_private(this, sym, property.clone())?;
+        }
+        for (key, init) in class.fields.iter() {
+            let value = match init {
+                Some(init) => self.call_function_with_this(init, &[], Some(this.clone()))?,
+                None => JsValue::Undefined,
+            };
+            self.define_field(this, key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Defines a field on `target` as an own data property, without
+    /// running any setter it inherits.
+    pub(crate) fn define_field(
+        &mut self,
+        target: &JsValue,
+        key: &FieldKey,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        let Some(holder) = private_holder(target) else {
+            return Err(self.throw_type_error("Cannot define a class field on a primitive"));
+        };
+        match key {
+            FieldKey::Public(PropKey::String(key)) => {
+                let mut obj = holder.borrow_mut();
+                obj.revision += 1;
+                obj.properties
+                    .insert(key.as_str().into(), Property::new(value));
+            }
+            FieldKey::Public(PropKey::Symbol(sym)) => {
+                holder
+                    .borrow_mut()
+                    .symbol_properties
+                    .insert(sym.id, (sym.clone(), Property::new(value)));
+            }
+            FieldKey::Private(sym) => self.define_private(target, sym, Property::new(value))?,
+        }
+        Ok(())
+    }
+
+    fn define_private(
+        &mut self,
+        target: &JsValue,
+        sym: &JsSymbol,
+        property: Property,
+    ) -> Result<(), RuntimeError> {
+        let Some(holder) = private_holder(target) else {
+            return Err(self.throw_type_error("Cannot define a private member on a primitive"));
+        };
+        if holder.borrow().private_elements.contains_key(&sym.id) {
+            return Err(self.throw_type_error(&format!(
+                "Cannot initialize {} twice on the same object",
+                FieldKey::Private(sym.clone()).display()
+            )));
+        }
+        holder
+            .borrow_mut()
+            .private_elements
+            .insert(sym.id, (sym.clone(), property));
+        Ok(())
+    }
+
+    /// `target.#name`
+    pub(crate) fn get_private(
+        &mut self,
+        target: &JsValue,
+        name: &str,
+    ) -> Result<JsValue, RuntimeError> {
+        let sym = self.private_name(name)?;
+        let Some(property) = private_element(target, &sym) else {
+            return Err(self.throw_type_error(&format!(
+                "Cannot read private member #{name} from an object whose class did not declare it"
+            )));
+        };
+        if !property.is_accessor() {
+            return Ok(property.value);
+        }
+        match property.getter {
+            Some(getter) => self.call_function_with_this(&getter, &[], Some(target.clone())),
+            None => Err(self.throw_type_error(&format!("'#{name}' was defined without a getter"))),
+        }
+    }
+
+    /// `target.#name = value`. Private methods cannot be assigned to.
+    pub(crate) fn set_private(
+        &mut self,
+        target: &JsValue,
+        name: &str,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        let sym = self.private_name(name)?;
+        let Some(property) = private_element(target, &sym) else {
+            return Err(self.throw_type_error(&format!(
+                "Cannot write private member #{name} to an object whose class did not declare it"
+            )));
+        };
+        if property.is_accessor() {
+            return match property.setter {
+                Some(setter) => self
+                    .call_function_with_this(&setter, &[value], Some(target.clone()))
+                    .map(|_| ()),
+                None => {
+                    Err(self.throw_type_error(&format!("'#{name}' was defined without a setter")))
+                }
+            };
+        }
+        if !property.writable {
+            return Err(self.throw_type_error(&format!("Private method '#{name}' is not writable")));
+        }
+        if let Some(holder) = private_holder(target)
+            && let Some((_, property)) = holder.borrow_mut().private_elements.get_mut(&sym.id)
+        {
+            property.value = value;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn eval_private_assign(
+        &mut self,
+        object: &Expr,
+        name: &str,
+        value: &Expr,
+    ) -> Result<JsValue, RuntimeError> {
+        let target = self.eval_expr(object)?;
+        let value = self.eval_expr(value)?;
+        self.set_private(&target, name, value.clone())?;
+        Ok(value)
+    }
+
+    /// `target.#name op= value`, reading the member once.
+    pub(crate) fn eval_private_compound_assign(
+        &mut self,
+        object: &Expr,
+        name: &str,
+        op: &AssignOp,
+        value: &Expr,
+    ) -> Result<JsValue, RuntimeError> {
+        let target = self.eval_expr(object)?;
+        let current = self.get_private(&target, name)?;
+        let next = match op.logical() {
+            Some(logical) if !logical_assigns(&logical, &current) => return Ok(current),
+            Some(_) => self.eval_expr(value)?,
+            None => {
+                let rhs = self.eval_expr(value)?;
+                self.eval_compound(current, op, rhs)?
+            }
+        };
+        self.set_private(&target, name, next.clone())?;
+        Ok(next)
+    }
+
+    /// `#name in object`: whether the object was initialized by the class
+    /// that declared `#name`.
+    pub(crate) fn eval_private_in(
+        &mut self,
+        name: &str,
+        object: &Expr,
+    ) -> Result<JsValue, RuntimeError> {
+        let target = self.eval_expr(object)?;
+        let sym = self.private_name(name)?;
+        if !is_object_like(&target) {
+            return Err(self.throw_type_error(&format!(
+                "Cannot use 'in' operator to search for '#{name}' in {}",
+                target.to_js_string()
+            )));
+        }
+        Ok(JsValue::Boolean(private_element(&target, &sym).is_some()))
+    }
+}
+
+/// Adds a private method or one half of a private accessor to `elements`,
+/// joining a getter and setter declared for the same name.
+pub(crate) fn add_private_method(
+    elements: &mut Vec<(JsSymbol, Property)>,
+    sym: JsSymbol,
+    kind: ClassMethodKind,
+    function: JsValue,
+) {
+    if let Some((_, property)) = elements.iter_mut().find(|(other, _)| *other == sym) {
+        match kind {
+            ClassMethodKind::Getter => property.getter = Some(function),
+            ClassMethodKind::Setter => property.setter = Some(function),
+            ClassMethodKind::Method => property.value = function,
+        }
+        return;
+    }
+    let property = match kind {
+        ClassMethodKind::Method => Property {
+            writable: false,
+            enumerable: false,
+            configurable: false,
+            ..Property::new(function)
+        },
+        ClassMethodKind::Getter => Property::with_getter(function),
+        ClassMethodKind::Setter => Property::with_setter(function),
+    };
+    elements.push((sym, property));
+}
+
+/// The object holding a value's private elements: the object itself, or
+/// the properties of a class constructor for its static members.
+fn private_holder(value: &JsValue) -> Option<Gc<GcCell<JsObject>>> {
+    match value {
+        JsValue::Object(obj) => Some(*obj),
+        JsValue::Function { properties, .. } => *properties,
+        _ => None,
+    }
+}
+
+fn private_element(target: &JsValue, sym: &JsSymbol) -> Option<Property> {
+    let holder = private_holder(target)?;
+    let obj = holder.borrow();
+    obj.private_elements
+        .get(&sym.id)
+        .map(|(_, property)| property.clone())
+}
@@ -1,8 +1,16 @@
 use super::Interpreter;
+use super::property_access::nullish_read_error;
 use crate::errors::RuntimeError;
 use crate::parser::ast::Expr;
-use crate::runtime::value::array::methods::call_array_method;
+use crate::runtime::builtins::string::STRING_STATIC_METHODS;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::array::methods::{
+    ARRAY_CALLBACK_METHODS, ARRAY_SHIFTING_METHODS, call_array_method,
+};
+use crate::runtime::value::number_methods::{self, NUMBER_METHODS};
 use crate::runtime::value::string_methods;
+use crate::runtime::value::typed_array::{DATA_VIEW_METHODS, TYPED_ARRAY_METHODS, TypedArrayKind};
 use crate::runtime::value::JsValue;
 
 impl Interpreter {
@@ -13,14 +21,17 @@ impl Interpreter {
         args: &[Expr],
         is_call: bool,
     ) -> Result<JsValue, RuntimeError> {
-        if let Expr::Identifier(name) = object {
-            let name = name.clone();
-            if let Some(result) = self.dispatch_static(&name, property, args, is_call)? {
-                return Ok(result);
-            }
+        if let Expr::Identifier(name) = object
+            && let Some(result) = self.dispatch_static(name, property, args, is_call)?
+        {
+            return Ok(result);
         }
 
         let receiver = self.eval_expr(object)?;
+        // The method is read before its arguments are evaluated.
+        if matches!(receiver, JsValue::Undefined | JsValue::Null) {
+            return Err(nullish_read_error(&receiver, property, Some(object)));
+        }
         let vals = if is_call {
             Some(self.eval_call_args(args)?)
         } else {
@@ -36,10 +47,18 @@ impl Interpreter {
         arg_exprs: &[Expr],
         is_call: bool,
     ) -> Result<Option<JsValue>, RuntimeError> {
+        // A profiled call is timed from once its arguments are evaluated.
+        #[cfg(feature = "profiling")]
+        let mut started = std::time::Instant::now();
         macro_rules! args {
-            () => {
-                self.eval_call_args(arg_exprs)?
-            };
+            () => {{
+                let args = self.eval_call_args(arg_exprs)?;
+                #[cfg(feature = "profiling")]
+                {
+                    started = std::time::Instant::now();
+                }
+                args
+            }};
         }
 
         let v = match name {
@@ -47,7 +66,7 @@ impl Interpreter {
                 if is_call && matches!(property, "log" | "info" | "warn" | "error" | "debug") =>
             {
                 let a = args!();
-                self.builtin_console_log(&a)?
+                self.builtin_console_log(property, &a)?
             }
             "Object" if is_call => {
                 let a = args!();
@@ -75,7 +94,10 @@ impl Interpreter {
                 let a = args!();
                 self.builtin_symbol_static(property, &a)?
             }
-            "Symbol" => self.builtin_symbol_property(property)?,
+            "Symbol" => self.builtin_symbol_property(property).or_else(|_| {
+                let ctor = self.env.get("Symbol")?;
+                self.get_property(&ctor, property)
+            })?,
             "Proxy" if is_call && property == "revocable" => {
                 let a = args!();
                 self.builtin_proxy_revocable(&a)?
@@ -88,7 +110,31 @@ impl Interpreter {
                 let a = args!();
                 self.builtin_number_static(property, &a)?
             }
-            "Number" => self.builtin_number_property(property)?,
+            "Number" => self.builtin_number_property(property).or_else(|_| {
+                let ctor = self.env.get("Number")?;
+                self.get_property(&ctor, property)
+            })?,
+            "String" if is_call && STRING_STATIC_METHODS.contains(&property) => {
+                let a = args!();
+                self.builtin_string_static(property, &a)?
+            }
+            "String" => match self.builtin_string_property(property) {
+                Some(method) => method,
+                None => return Ok(None),
+            },
+            "ArrayBuffer" if is_call && property == "isView" => {
+                let a = args!();
+                JsValue::Boolean(matches!(
+                    a.first(),
+                    Some(JsValue::TypedArray(_) | JsValue::DataView(_))
+                ))
+            }
+            name if property == "BYTES_PER_ELEMENT" => {
+                match TypedArrayKind::from_constructor(name) {
+                    Some(kind) => JsValue::Number(kind.element_size() as f64),
+                    None => return Ok(None),
+                }
+            }
             "Array" if is_call && property == "isArray" => {
                 let a = args!();
                 let val = a.into_iter().next().unwrap_or(JsValue::Undefined);
@@ -96,6 +142,10 @@ impl Interpreter {
             }
             _ => return Ok(None),
         };
+        #[cfg(feature = "profiling")]
+        if is_call {
+            self.profile.record_builtin(name, property, started);
+        }
         Ok(Some(v))
     }
 
@@ -108,11 +158,12 @@ impl Interpreter {
         let is_call = vals.is_some();
 
         match receiver.clone() {
+            JsValue::Undefined | JsValue::Null => Err(nullish_read_error(receiver, property, None)),
             JsValue::Symbol(sym) => match property {
-                "toString" if is_call => Ok(JsValue::String(sym.to_string())),
+                "toString" if is_call => Ok(JsValue::String(sym.to_string().into())),
                 "description" => Ok(sym
                     .description
-                    .map(JsValue::String)
+                    .map(|s| JsValue::String(s.into()))
                     .unwrap_or(JsValue::Undefined)),
                 _ => Err(RuntimeError::TypeError {
                     message: format!("cannot access '{property}' on Symbol"),
@@ -120,68 +171,140 @@ impl Interpreter {
             },
             JsValue::String(s) => {
                 if is_call {
-                    string_methods::call_string_method(&s, property, &vals.unwrap(), &mut self.heap)
+                    let a = vals.unwrap();
+                    let result = self.profiled("String", property, |this| {
+                        string_methods::call_string_method(&s, property, &a, &mut this.heap)
+                    });
+                    match result {
+                        Ok(result) => Ok(result),
+                        Err(err) => match self.inherited_method(receiver, property)? {
+                            Some(method) => {
+                                self.call_function_with_this(&method, &a, Some(receiver.clone()))
+                            }
+                            None => Err(err),
+                        },
+                    }
                 } else {
-                    string_methods::resolve_string_property(&s, property)
+                    self.get_property(receiver, property)
                 }
             }
+            JsValue::Number(n) if is_call && NUMBER_METHODS.contains(&property) => {
+                let a = vals.unwrap();
+                self.profiled("Number", property, |_| {
+                    number_methods::call_number_method(n, property, &a)
+                })
+            }
             JsValue::Array(arr) => {
-                if is_call {
-                    let a = vals.unwrap();
-                    if let Some(r) = call_array_method(&arr, property, &a, &mut self.heap)? {
+                if !is_call {
+                    return self.get_property(receiver, property);
+                }
+                let a = vals.unwrap();
+                if let Some(method) = self.array_method_override(&arr, receiver, property)? {
+                    return self.call_function_with_this(&method, &a, Some(receiver.clone()));
+                }
+                if !ARRAY_CALLBACK_METHODS.contains(&property) {
+                    let (source, plain_args) = self.plain_array_operands(&arr, property, &a)?;
+                    let result = self.profiled("Array", property, |this| {
+                        call_array_method(&source, property, &plain_args, &mut this.heap)
+                    });
+                    if let Some(r) = result? {
                         return Ok(r);
                     }
-                    self.eval_array_callback_method(&arr, property, &a)
-                } else {
-                    self.get_property(receiver, property)
                 }
+                self.profiled("Array", property, |this| {
+                    this.eval_array_callback_method(&arr, property, &a)
+                })
             }
             JsValue::Promise(promise) => {
                 if is_call {
-                    self.builtin_promise_instance(&promise, property, &vals.unwrap())
+                    let a = vals.unwrap();
+                    self.profiled("Promise", property, |this| {
+                        this.builtin_promise_instance(&promise, property, &a)
+                    })
                 } else {
                     Ok(JsValue::Undefined)
                 }
             }
             JsValue::Map(map) => {
                 if is_call {
-                    self.call_map_method(&map, property, &vals.unwrap())
+                    let a = vals.unwrap();
+                    self.profiled("Map", property, |this| {
+                        this.call_map_method(&map, property, &a)
+                    })
                 } else if property == "size" {
-                    Ok(JsValue::Number(map.borrow().entries.len() as f64))
+                    Ok(JsValue::Number(map.borrow().size() as f64))
                 } else {
                     self.get_property(receiver, property)
                 }
             }
             JsValue::Set(set) => {
                 if is_call {
-                    self.call_set_method(&set, property, &vals.unwrap())
+                    let a = vals.unwrap();
+                    self.profiled("Set", property, |this| {
+                        this.call_set_method(&set, property, &a)
+                    })
                 } else if property == "size" {
-                    Ok(JsValue::Number(set.borrow().entries.len() as f64))
+                    Ok(JsValue::Number(set.borrow().size() as f64))
                 } else {
                     self.get_property(receiver, property)
                 }
             }
             JsValue::WeakMap(wm) => {
                 if is_call {
-                    self.call_weak_map_method(&wm, property, &vals.unwrap())
+                    let a = vals.unwrap();
+                    self.profiled("WeakMap", property, |this| {
+                        this.call_weak_map_method(&wm, property, &a)
+                    })
                 } else {
                     Ok(JsValue::Undefined)
                 }
             }
             JsValue::WeakSet(ws) => {
                 if is_call {
-                    self.call_weak_set_method(&ws, property, &vals.unwrap())
+                    let a = vals.unwrap();
+                    self.profiled("WeakSet", property, |this| {
+                        this.call_weak_set_method(&ws, property, &a)
+                    })
                 } else {
                     Ok(JsValue::Undefined)
                 }
             }
+            JsValue::TypedArray(array) if is_call && TYPED_ARRAY_METHODS.contains(&property) => {
+                let a = vals.unwrap();
+                self.profiled("TypedArray", property, |this| {
+                    this.call_typed_array_method(&array, property, &a)
+                })
+            }
+            JsValue::ArrayBuffer(buffer) if is_call && property == "slice" => {
+                let a = vals.unwrap();
+                self.profiled("ArrayBuffer", property, |this| {
+                    this.call_array_buffer_method(&buffer, property, &a)
+                })
+            }
+            JsValue::DataView(view) if is_call && DATA_VIEW_METHODS.contains(&property) => {
+                let a = vals.unwrap();
+                self.profiled("DataView", property, |this| {
+                    this.call_data_view_method(&view, property, &a)
+                })
+            }
             JsValue::RegExp(re) => {
                 if is_call {
-                    self.call_regexp_method(&re, property, &vals.unwrap())
+                    let a = vals.unwrap();
+                    self.profiled("RegExp", property, |this| {
+                        this.call_regexp_method(&re, property, &a)
+                    })
                 } else {
                     self.get_regexp_property(&re, property)
                 }
             }
+            // Function.prototype.toString shadows the Object.prototype one
+            // the fallback below would call.
+            JsValue::Function { .. } | JsValue::NativeFunction { .. }
+                if is_call && property == "toString" =>
+            {
+                let method = self.get_property(receiver, property)?;
+                self.call_function_with_this(&method, &vals.unwrap(), Some(receiver.clone()))
+            }
             JsValue::Proxy(_) | _ => {
                 if is_call {
                     let call_args = vals.unwrap();
@@ -198,4 +321,74 @@ impl Interpreter {
             }
         }
     }
+
+    /// The method `key` defined on the array itself or on a prototype up
+    /// to and including `Array.prototype`, where the builtin methods
+    /// conceptually live. Such a method wins over the builtin of the same
+    /// name; one on `Object.prototype` comes after the builtins and does
+    /// not.
+    fn array_method_override(
+        &mut self,
+        arr: &Gc<GcCell<JsArray>>,
+        receiver: &JsValue,
+        key: &str,
+    ) -> Result<Option<JsValue>, RuntimeError> {
+        let own = arr.borrow().properties;
+        if let Some(own) = own
+            && own.borrow().properties.contains_key(key)
+        {
+            return self.lookup_prototype_chain(Some(own), key, receiver);
+        }
+        let home = self.prototypes.array;
+        let mut current = self.inherited_prototype(receiver);
+        while let Some(candidate) = current {
+            let (found, next) = {
+                let borrowed = candidate.borrow();
+                (borrowed.properties.contains_key(key), borrowed.prototype)
+            };
+            if found {
+                return self.lookup_prototype_chain(Some(candidate), key, receiver);
+            }
+            if Gc::ptr_eq(candidate, home) {
+                break;
+            }
+            current = next;
+        }
+        Ok(None)
+    }
+
+    /// The receiver and arguments `call_array_method` works on. It reads
+    /// `elements` directly, so arrays with accessor indices are handed
+    /// over as copies holding what their getters return. The shifting
+    /// methods get the original, which they refuse.
+    fn plain_array_operands(
+        &mut self,
+        arr: &Gc<GcCell<JsArray>>,
+        method: &str,
+        args: &[JsValue],
+    ) -> Result<(Gc<GcCell<JsArray>>, Vec<JsValue>), RuntimeError> {
+        if ARRAY_SHIFTING_METHODS.contains(&method) {
+            return Ok((*arr, args.to_vec()));
+        }
+        let source = self.plain_array(arr)?;
+        let mut plain_args = Vec::with_capacity(args.len());
+        for arg in args {
+            plain_args.push(match arg {
+                JsValue::Array(arg) => JsValue::Array(self.plain_array(arg)?),
+                other => other.clone(),
+            });
+        }
+        Ok((source, plain_args))
+    }
+
+    fn plain_array(
+        &mut self,
+        arr: &Gc<GcCell<JsArray>>,
+    ) -> Result<Gc<GcCell<JsArray>>, RuntimeError> {
+        if !arr.borrow().has_index_accessors() {
+            return Ok(*arr);
+        }
+        let elements = self.array_elements(arr)?;
+        Ok(self.heap.alloc_cell(JsArray::new(elements)))
+    }
 }
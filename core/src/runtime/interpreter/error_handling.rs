@@ -1,6 +1,6 @@
 use crate::errors::RuntimeError;
-use crate::runtime::gc::Heap;
-use crate::runtime::value::object::JsObject;
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::object::{JsObject, Property};
 use crate::runtime::value::JsValue;
 
 use super::Interpreter;
@@ -16,41 +16,149 @@ impl JsException {
     }
 
     pub fn into_runtime_error(self) -> RuntimeError {
-        RuntimeError::Thrown { value: self.value }
+        RuntimeError::thrown(self.value)
     }
 }
 
-pub fn create_error_object(message: JsValue, heap: &mut Heap) -> JsValue {
-    let mut obj = JsObject::new();
-    obj.set("name".to_string(), JsValue::String("Error".to_string()));
-    obj.set(
-        "message".to_string(),
-        JsValue::String(message.to_js_string()),
-    );
-    obj.set("constructor".to_string(), JsValue::Undefined);
-    JsValue::Object(heap.alloc_cell(obj))
-}
-
 impl Interpreter {
+    /// Builds the error `kind` called or constructed with `args`:
+    /// `(message, options)`, or `(errors, message, options)` for
+    /// `AggregateError`. An `options.cause` becomes the error's `cause`,
+    /// and an aggregate's errors its `errors` array, neither enumerable.
+    pub(crate) fn construct_error(
+        &mut self,
+        kind: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let (errors, args) = if kind == "AggregateError" {
+            let iterable = args.first().cloned().unwrap_or(JsValue::Undefined);
+            let errors = self.collect_iterable(&iterable)?;
+            (Some(errors), args.get(1..).unwrap_or_default())
+        } else {
+            (None, args)
+        };
+        let message = match args.first() {
+            None | Some(JsValue::Undefined) => String::new(),
+            Some(value) => self.coerce_to_string(value)?,
+        };
+        let cause = match args.get(1) {
+            Some(options @ JsValue::Object(obj))
+                if obj.borrow().properties.contains_key("cause") =>
+            {
+                Some(self.get_property(options, "cause")?)
+            }
+            _ => None,
+        };
+
+        let mut obj = JsObject::new();
+        obj.set("name", JsValue::String(kind.into()));
+        obj.set("message", JsValue::String(message.into()));
+        obj.set("[[ErrorType]]", JsValue::String(kind.into()));
+        let constructor = self.env.get(kind).unwrap_or(JsValue::Undefined);
+        let hidden = [
+            ("constructor", Some(constructor)),
+            ("cause", cause),
+            (
+                "errors",
+                errors.map(|errors| JsValue::Array(self.heap.alloc_cell(JsArray::new(errors)))),
+            ),
+        ];
+        for (key, value) in hidden {
+            if let Some(value) = value {
+                obj.properties.insert(
+                    key.into(),
+                    Property {
+                        enumerable: false,
+                        ..Property::new(value)
+                    },
+                );
+            }
+        }
+        Ok(JsValue::Object(self.heap.alloc_cell(obj)))
+    }
+
     /// Create a typed error object (TypeError, ReferenceError, etc.)
     /// suitable for use as a Thrown value that can be caught by try/catch.
     pub(crate) fn create_typed_error_object(&mut self, error_type: &str, message: &str) -> JsValue {
         let mut obj = JsObject::new();
-        obj.set("name".to_string(), JsValue::String(error_type.to_string()));
-        obj.set("message".to_string(), JsValue::String(message.to_string()));
+        obj.set("name".to_string(), JsValue::String(error_type.into()));
+        obj.set("message".to_string(), JsValue::String(message.into()));
         let constructor = self.env.get(error_type).unwrap_or(JsValue::Undefined);
         obj.set("constructor".to_string(), constructor);
         // Set the constructor name so instanceof checks can work
         obj.set(
             "[[ErrorType]]".to_string(),
-            JsValue::String(error_type.to_string()),
+            JsValue::String(error_type.into()),
         );
         JsValue::Object(self.heap.alloc_cell(obj))
     }
 
+    /// The value a `catch` clause binds for `err`: thrown values as they
+    /// are, and host-level errors as `ReferenceError`/`TypeError` objects
+    /// with `name`, `message` and `stack` set. Errors that are not
+    /// [catchable](RuntimeError::is_catchable) come back unchanged.
+    pub(crate) fn runtime_error_to_value(
+        &mut self,
+        err: RuntimeError,
+    ) -> Result<JsValue, RuntimeError> {
+        self.error_frame = None;
+        let (error_type, message) = match err {
+            RuntimeError::Thrown { value } => return Ok(*value),
+            RuntimeError::UndefinedVariable { name } => {
+                ("ReferenceError", format!("'{name}' is not defined"))
+            }
+            RuntimeError::UninitializedBinding { name } => (
+                "ReferenceError",
+                format!("Cannot access '{name}' before initialization"),
+            ),
+            RuntimeError::NotAFunction { name } => {
+                ("TypeError", format!("'{name}' is not a function"))
+            }
+            RuntimeError::ArityMismatch { expected, got } => (
+                "TypeError",
+                format!("expected {expected} arguments but got {got}"),
+            ),
+            RuntimeError::ConstReassignment { name } => (
+                "TypeError",
+                format!("Assignment to constant variable '{name}'"),
+            ),
+            RuntimeError::TypeError { message } => ("TypeError", message),
+            other => return Err(other),
+        };
+
+        let trace = self
+            .error_trace
+            .take()
+            .unwrap_or_else(|| self.call_stack.format_trace());
+        let value = self.create_typed_error_object(error_type, &message);
+        if let JsValue::Object(obj) = &value {
+            let stack = format!("{error_type}: {message}\n{}", trace.trim_end());
+            obj.borrow_mut().properties.insert(
+                "stack".into(),
+                Property {
+                    enumerable: false,
+                    ..Property::new(JsValue::String(stack.trim_end().to_string().into()))
+                },
+            );
+        }
+        Ok(value)
+    }
+
     /// Throw a catchable TypeError
     pub(crate) fn throw_type_error(&mut self, message: &str) -> RuntimeError {
         let err_obj = self.create_typed_error_object("TypeError", message);
-        RuntimeError::Thrown { value: err_obj }
+        RuntimeError::thrown(err_obj)
+    }
+
+    /// Throw a catchable RangeError, as for an invalid code point.
+    pub(crate) fn throw_range_error(&mut self, message: &str) -> RuntimeError {
+        let err_obj = self.create_typed_error_object("RangeError", message);
+        RuntimeError::thrown(err_obj)
+    }
+
+    /// Throw a catchable SyntaxError, as for an invalid RegExp pattern.
+    pub(crate) fn throw_syntax_error(&mut self, message: &str) -> RuntimeError {
+        let err_obj = self.create_typed_error_object("SyntaxError", message);
+        RuntimeError::thrown(err_obj)
     }
 }
@@ -1,9 +1,12 @@
+use std::rc::Rc;
+
 use super::Interpreter;
 use crate::errors::RuntimeError;
 use crate::parser::ast::{Expr, Param, Stmt};
 use crate::runtime::gc::{Gc, GcCell};
-use crate::runtime::value::JsValue;
+use crate::runtime::value::generator::{GeneratorResume, JsGenerator};
 use crate::runtime::value::promise::{JsPromise, PromiseState};
+use crate::runtime::value::{JsValue, NativeFunction};
 
 impl Interpreter {
     pub(crate) fn execute_function_body(
@@ -14,25 +17,16 @@ impl Interpreter {
         this_binding: Option<JsValue>,
         args: &[JsValue],
     ) -> Result<JsValue, RuntimeError> {
-        let params = params.to_vec();
-        let body = body.to_vec();
         let captured = closure_env.to_vec();
         let saved_scopes = self.env.replace_scopes(captured);
 
         self.env.push_scope_with_this(&mut self.heap, this_binding);
-        for (idx, param) in params.iter().enumerate() {
-            let mut value = args.get(idx).cloned().unwrap_or(JsValue::Undefined);
-            if matches!(value, JsValue::Undefined)
-                && let Some(default_expr) = &param.default
-            {
-                value = self.eval_expr(default_expr)?;
-            }
-            self.eval_pattern_binding(&param.pattern, value)?;
-        }
 
         let mut result = JsValue::Undefined;
         let call_result = (|| -> Result<(), RuntimeError> {
-            for stmt in &body {
+            self.bind_parameters(params, args)?;
+            self.hoist_declarations(body);
+            for stmt in body {
                 match self.eval_stmt(stmt)? {
                     super::ControlFlow::Return(val) => {
                         result = val;
@@ -54,41 +48,72 @@ impl Interpreter {
             Ok(())
         })();
 
-        self.env.pop_scope();
         self.env.replace_scopes(saved_scopes);
         call_result?;
         Ok(result)
     }
 
+    /// Calls an async function, returning the promise of its result. The
+    /// body runs like a generator's, so an `await` it cannot finish yet
+    /// pauses it until the awaited promise settles.
     pub(crate) fn execute_async_function_body(
         &mut self,
-        params: &[Param],
-        body: &[Stmt],
+        params: &Rc<[Param]>,
+        body: &Rc<[Stmt]>,
         closure_env: &[Gc<GcCell<crate::runtime::environment::Scope>>],
         this_binding: Option<JsValue>,
         args: &[JsValue],
     ) -> Result<JsValue, RuntimeError> {
         let promise = self.heap.alloc_cell(JsPromise::pending());
-        self.async_depth += 1;
-        let result = self.execute_function_body(params, body, closure_env, this_binding, args);
-        self.async_depth = self.async_depth.saturating_sub(1);
+        let mut function = JsGenerator::new(
+            Rc::clone(params),
+            Rc::clone(body),
+            closure_env.to_vec(),
+            this_binding,
+            args.to_vec(),
+            false,
+        );
+        function.async_function = Some(promise);
+        let function = self.heap.alloc_cell(function);
+        self.resume_async_function(&function, GeneratorResume::Next(JsValue::Undefined))?;
+        Ok(JsValue::Promise(promise))
+    }
 
-        match result {
-            Ok(value) => {
+    /// Runs an async function body from its start or the `await` it is
+    /// paused on until it pauses again or finishes and settles its
+    /// promise.
+    pub(crate) fn resume_async_function(
+        &mut self,
+        function: &Gc<GcCell<JsGenerator>>,
+        resume: GeneratorResume,
+    ) -> Result<JsValue, RuntimeError> {
+        let promise = function
+            .borrow()
+            .async_function
+            .expect("resumed body belongs to an async function");
+        match self.resume_generator(function, resume) {
+            Ok((JsValue::Promise(awaited), false)) => {
+                let on_fulfilled = JsValue::NativeFunction {
+                    name: String::new(),
+                    handler: NativeFunction::AsyncFunctionResume(*function),
+                };
+                let on_rejected = JsValue::NativeFunction {
+                    name: String::new(),
+                    handler: NativeFunction::AsyncFunctionThrow(*function),
+                };
+                self.promise_then(&awaited, Some(on_fulfilled), Some(on_rejected))?;
+            }
+            Ok((value, _)) => {
                 self.settle_promise(&promise, false, value)?;
             }
             Err(err) => {
                 let trace = self.call_stack.format_trace();
                 let err = self.attach_stack_to_error(err, &trace);
-                let rejected = match err {
-                    RuntimeError::Thrown { value } => value,
-                    other => JsValue::String(other.to_string()),
-                };
+                let rejected = self.runtime_error_to_value(err)?;
                 self.settle_promise(&promise, true, rejected)?;
             }
         }
-
-        Ok(JsValue::Promise(promise))
+        Ok(JsValue::Undefined)
     }
 
     pub(crate) fn eval_await_expr(&mut self, expr: &Expr) -> Result<JsValue, RuntimeError> {
@@ -99,15 +124,22 @@ impl Interpreter {
         }
 
         let value = self.eval_expr(expr)?;
+        self.await_in_body(value)
+    }
+
+    /// Blocks on `value` if it is a promise, running the event loop until
+    /// it settles.
+    pub(crate) fn await_value(&mut self, value: JsValue) -> Result<JsValue, RuntimeError> {
         match value {
             JsValue::Promise(promise) => {
                 self.run_event_loop_until_promise_settled(&promise)?;
+                self.mark_rejection_handled(&promise);
                 match &promise.borrow().state {
                     PromiseState::Pending => Err(RuntimeError::TypeError {
                         message: "awaited promise did not settle".to_string(),
                     }),
                     PromiseState::Fulfilled(v) => Ok(v.clone()),
-                    PromiseState::Rejected(v) => Err(RuntimeError::Thrown { value: v.clone() }),
+                    PromiseState::Rejected(v) => Err(RuntimeError::thrown(v.clone())),
                 }
             }
             other => Ok(other),
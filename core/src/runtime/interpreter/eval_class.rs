@@ -1,30 +1,79 @@
+use std::rc::Rc;
+
 use super::Interpreter;
+use super::property_access::PropKey;
 use crate::errors::RuntimeError;
-use crate::parser::ast::{ClassDecl, ClassMethod, ClassMethodKind, Expr, Param, Pattern};
+use crate::parser::ast::{
+    ClassDecl, ClassMethod, ClassMethodKind, Expr, FunctionBody, Param, Pattern, PropertyKey, Stmt,
+};
+use super::class_elements::{FieldKey, add_private_method};
+use crate::runtime::builtins::prototypes::has_null_prototype;
+use crate::runtime::environment::BindingKind;
 use crate::runtime::gc::{Gc, GcCell};
-use crate::runtime::value::object::JsObject;
-use crate::runtime::value::JsValue;
+use crate::runtime::value::object::{JsObject, Property};
+use crate::runtime::value::symbol::{JsSymbol, symbol_has_instance};
+use crate::runtime::value::{JsValue, NativeFunction};
+
+/// A field's key and the function computing its initial value, if any.
+type FieldInit = (FieldKey, Option<JsValue>);
 
 #[derive(Clone)]
 pub(crate) struct RuntimeClass {
     pub constructor: JsValue,
     pub prototype: Gc<GcCell<JsObject>>,
     pub parent: Option<String>,
+    /// Whether the class declares a constructor. A derived class without
+    /// one runs its parent's with the arguments it was given.
+    pub has_constructor: bool,
+    /// Private methods and accessors every instance gets, before its
+    /// fields.
+    pub private_methods: Rc<[(JsSymbol, Property)]>,
+    /// Instance fields in source order, each with the function computing
+    /// its initial value when it has an initializer.
+    pub fields: Rc<[FieldInit]>,
 }
 
 impl Interpreter {
     pub(crate) fn eval_class_decl(&mut self, class_decl: &ClassDecl) -> Result<(), RuntimeError> {
+        // Every evaluation of the class body makes new symbols for its
+        // private names, bound where its methods and initializers close
+        // over them, so no other class can reach its private members.
+        self.env.push_scope(&mut self.heap);
+        for name in private_names(class_decl) {
+            let sym = JsSymbol::new(Some(format!("#{name}")));
+            self.env
+                .define_with_kind(format!("#{name}"), JsValue::Symbol(sym), BindingKind::Const);
+        }
+        let built = self.build_class(class_decl);
+        self.env.pop_scope();
+        let (class, static_fields) = built?;
+
+        let constructor = class.constructor.clone();
+        self.classes.insert(class_decl.name.clone(), class);
+        self.env.define(&class_decl.name, constructor.clone());
+
+        for (key, init) in static_fields {
+            let value = match init {
+                Some(init) => {
+                    self.call_function_with_this(&init, &[], Some(constructor.clone()))?
+                }
+                None => JsValue::Undefined,
+            };
+            self.define_field(&constructor, &key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Builds the class, returning it along with its static fields, which
+    /// are only initialized once the class is bound.
+    fn build_class(
+        &mut self,
+        class_decl: &ClassDecl,
+    ) -> Result<(RuntimeClass, Vec<FieldInit>), RuntimeError> {
         let parent = class_decl
             .parent
             .as_ref()
-            .map(|name| {
-                self.classes
-                    .get(name)
-                    .cloned()
-                    .ok_or_else(|| RuntimeError::TypeError {
-                        message: format!("unknown parent class '{name}'"),
-                    })
-            })
+            .map(|name| self.parent_class(name))
             .transpose()?;
 
         let mut prototype = JsObject::new();
@@ -32,25 +81,100 @@ impl Interpreter {
             prototype.prototype = Some(parent_class.prototype);
         }
 
+        // Static members live on the constructor's own properties, next to
+        // `prototype`.
+        let mut statics = JsObject::new();
+        let mut private_methods = Vec::new();
+        let mut static_private_methods = Vec::new();
+
         for method in &class_decl.methods {
-            if method.is_static {
-                continue;
-            }
-            let method_value = self.method_to_function(method, &class_decl.name);
+            let key = match &method.key {
+                PropertyKey::Identifier(name) => PropKey::String(name.clone()),
+                PropertyKey::Computed(expr) => self.eval_property_key_expr(expr)?,
+                PropertyKey::Private(name) => {
+                    let sym = self.private_name(name)?;
+                    let method_value =
+                        self.method_to_function(method, &class_decl.name, &format!("#{name}"));
+                    let elements = if method.is_static {
+                        &mut static_private_methods
+                    } else {
+                        &mut private_methods
+                    };
+                    add_private_method(elements, sym, method.kind, method_value);
+                    continue;
+                }
+            };
+            let target = if method.is_static {
+                &mut statics
+            } else {
+                &mut prototype
+            };
+            let key = match key {
+                PropKey::Symbol(sym) => {
+                    let method_value =
+                        self.method_to_function(method, &class_decl.name, &sym.to_string());
+                    match method.kind {
+                        ClassMethodKind::Method => target.set_symbol(sym, method_value),
+                        ClassMethodKind::Getter => target.set_symbol_getter(sym, method_value),
+                        ClassMethodKind::Setter => target.set_symbol_setter(sym, method_value),
+                    }
+                    continue;
+                }
+                PropKey::String(key) => key,
+            };
+            let method_value = self.method_to_function(method, &class_decl.name, &key);
             match method.kind {
-                ClassMethodKind::Method => prototype.set(method.name.clone(), method_value),
-                ClassMethodKind::Getter => prototype.set_getter(method.name.clone(), method_value),
-                ClassMethodKind::Setter => prototype.set_setter(method.name.clone(), method_value),
+                ClassMethodKind::Method => target.set(key, method_value),
+                ClassMethodKind::Getter => target.set_getter(key, method_value),
+                ClassMethodKind::Setter => target.set_setter(key, method_value),
+            }
+        }
+
+        let mut fields = Vec::new();
+        let mut static_fields = Vec::new();
+        for field in &class_decl.fields {
+            let key = match &field.key {
+                PropertyKey::Private(name) => FieldKey::Private(self.private_name(name)?),
+                key => FieldKey::Public(self.eval_property_key(key)?),
+            };
+            let init = field
+                .value
+                .as_ref()
+                .map(|value| self.field_initializer(&class_decl.name, &key, value));
+            if field.is_static {
+                static_fields.push((key, init));
+            } else {
+                fields.push((key, init));
+            }
+        }
+        // Methods and accessors are non-enumerable, on the prototype and
+        // on the constructor alike, so for-in over an instance skips them.
+        for object in [&mut prototype, &mut statics] {
+            let props = object.properties.values_mut();
+            let symbol_props = object.symbol_properties.values_mut().map(|(_, p)| p);
+            for prop in props.chain(symbol_props) {
+                prop.enumerable = false;
             }
         }
+        for (sym, property) in static_private_methods {
+            statics.private_elements.insert(sym.id, (sym, property));
+        }
 
         let prototype = self.heap.alloc_cell(prototype);
-        let constructor = match &class_decl.constructor {
-            Some(method) => self.method_to_function(method, &class_decl.name),
+        statics.properties.insert(
+            "prototype".into(),
+            Property {
+                enumerable: false,
+                ..Property::new(JsValue::Object(prototype))
+            },
+        );
+        let statics = self.heap.alloc_cell(statics);
+        let mut constructor = match &class_decl.constructor {
+            Some(method) => self.method_to_function(method, &class_decl.name, "constructor"),
             None => JsValue::Function {
                 name: format!("{}::constructor", class_decl.name),
-                params: Vec::new(),
-                body: Vec::new(),
+                params: Vec::new().into(),
+                body: Vec::new().into(),
                 closure_env: self.env.capture(),
                 is_async: false,
                 is_generator: false,
@@ -59,18 +183,82 @@ impl Interpreter {
                 properties: None,
             },
         };
-
-        self.classes.insert(
-            class_decl.name.clone(),
-            RuntimeClass {
-                constructor: constructor.clone(),
-                prototype,
-                parent: class_decl.parent.clone(),
+        if let JsValue::Function {
+            properties, body, ..
+        } = &mut constructor
+        {
+            *properties = Some(statics);
+            // The constructor stands for the class, so it shows the text of
+            // the whole declaration.
+            *body = body.clone().with_source(class_decl.source.clone());
+        }
+        prototype.borrow_mut().properties.insert(
+            "constructor".into(),
+            Property {
+                enumerable: false,
+                ..Property::new(constructor.clone())
             },
         );
 
-        self.env.define(class_decl.name.clone(), constructor);
-        Ok(())
+        let class = RuntimeClass {
+            constructor,
+            prototype,
+            parent: class_decl.parent.clone(),
+            has_constructor: class_decl.constructor.is_some(),
+            private_methods: private_methods.into(),
+            fields: fields.into(),
+        };
+        Ok((class, static_fields))
+    }
+
+    /// Constructs an instance of the class registered as `name`.
+    pub(crate) fn construct_class(
+        &mut self,
+        name: &str,
+        class: &RuntimeClass,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let mut instance = JsObject::new();
+        instance.prototype = Some(class.prototype);
+        let instance = JsValue::Object(self.heap.alloc_cell(instance));
+        self.run_class_constructor(name, class, args, &instance)?;
+        Ok(instance)
+    }
+
+    /// Runs the constructor of the class registered as `name` on `this`.
+    /// A base class initializes its instance members first; a derived
+    /// class does so once its parent's constructor returns, from `super()`
+    /// or, when it declares no constructor, right after running the
+    /// parent's with the same arguments.
+    fn run_class_constructor(
+        &mut self,
+        name: &str,
+        class: &RuntimeClass,
+        args: &[JsValue],
+        this: &JsValue,
+    ) -> Result<(), RuntimeError> {
+        match &class.parent {
+            None => self.initialize_instance(class, this)?,
+            Some(parent_name) if !class.has_constructor => {
+                let parent = self.parent_class(parent_name)?;
+                self.run_class_constructor(parent_name, &parent, args, this)?;
+                return self.initialize_instance(class, this);
+            }
+            Some(_) => {}
+        }
+        self.super_stack.push(name.to_string());
+        let result = self.call_function_with_this(&class.constructor, args, Some(this.clone()));
+        self.super_stack.pop();
+        result.map(|_| ())
+    }
+
+    fn parent_class(&self, name: &str) -> Result<RuntimeClass, RuntimeError> {
+        self.classes
+            .get(name)
+            .cloned()
+            .ok_or_else(|| RuntimeError::TypeError {
+                message: format!("unknown parent class '{name}'"),
+            })
     }
 
     pub(crate) fn eval_new(
@@ -88,48 +276,15 @@ impl Interpreter {
             });
         }
 
-        if matches!(callee, crate::parser::ast::Expr::Identifier(name) if name == "Error") {
-            let message = args
-                .first()
-                .map(|expr| self.eval_expr(expr))
-                .transpose()?
-                .unwrap_or(JsValue::Undefined);
-            return Ok(super::error_handling::create_error_object(
-                message,
-                &mut self.heap,
-            ));
-        }
-
         if let crate::parser::ast::Expr::Identifier(name) = callee {
             match name.as_str() {
-                "TypeError" | "ReferenceError" | "SyntaxError" | "RangeError" | "URIError"
-                | "EvalError" => {
-                    let message = args
-                        .first()
-                        .map(|expr| self.eval_expr(expr))
-                        .transpose()?
-                        .unwrap_or(JsValue::Undefined);
-                    let mut obj = JsObject::new();
-                    obj.set("name".to_string(), JsValue::String(name.clone()));
-                    obj.set(
-                        "message".to_string(),
-                        JsValue::String(message.to_js_string()),
-                    );
-                    obj.set("[[ErrorType]]".to_string(), JsValue::String(name.clone()));
-                    return Ok(JsValue::Object(self.heap.alloc_cell(obj)));
-                }
                 "Number" => {
                     let val = args
                         .first()
                         .map(|expr| self.eval_expr(expr))
                         .transpose()?
                         .unwrap_or(JsValue::Number(0.0));
-                    let mut obj = JsObject::new();
-                    obj.set(
-                        "[[PrimitiveValue]]".to_string(),
-                        JsValue::Number(val.to_number()),
-                    );
-                    return Ok(JsValue::Object(self.heap.alloc_cell(obj)));
+                    return Ok(self.wrap_primitive(JsValue::Number(val.to_number())));
                 }
                 "Boolean" => {
                     let val = args
@@ -137,25 +292,15 @@ impl Interpreter {
                         .map(|expr| self.eval_expr(expr))
                         .transpose()?
                         .unwrap_or(JsValue::Boolean(false));
-                    let mut obj = JsObject::new();
-                    obj.set(
-                        "[[PrimitiveValue]]".to_string(),
-                        JsValue::Boolean(val.to_boolean()),
-                    );
-                    return Ok(JsValue::Object(self.heap.alloc_cell(obj)));
+                    return Ok(self.wrap_primitive(JsValue::Boolean(val.to_boolean())));
                 }
                 "String" => {
                     let val = args
                         .first()
                         .map(|expr| self.eval_expr(expr))
                         .transpose()?
-                        .unwrap_or(JsValue::String(String::new()));
-                    let mut obj = JsObject::new();
-                    obj.set(
-                        "[[PrimitiveValue]]".to_string(),
-                        JsValue::String(val.to_js_string()),
-                    );
-                    return Ok(JsValue::Object(self.heap.alloc_cell(obj)));
+                        .unwrap_or(JsValue::String("".into()));
+                    return Ok(self.wrap_primitive(JsValue::String(val.to_js_string().into())));
                 }
                 "Object" => {
                     let val = args
@@ -163,32 +308,16 @@ impl Interpreter {
                         .map(|expr| self.eval_expr(expr))
                         .transpose()?
                         .unwrap_or(JsValue::Undefined);
-                    return match val {
-                        JsValue::Object(_) => Ok(val),
-                        JsValue::Null | JsValue::Undefined => {
-                            Ok(JsValue::Object(self.heap.alloc_cell(JsObject::new())))
-                        }
-                        _ => Ok(JsValue::Object(self.heap.alloc_cell(JsObject::new()))),
-                    };
+                    return Ok(self.object_from_value(val));
                 }
                 _ => {}
             }
         }
 
-        if matches!(callee, crate::parser::ast::Expr::Identifier(name) if name == "Map") {
-            return self.eval_new_map(args);
-        }
-
-        if matches!(callee, crate::parser::ast::Expr::Identifier(name) if name == "Set") {
-            return self.eval_new_set(args);
-        }
-
-        if matches!(callee, crate::parser::ast::Expr::Identifier(name) if name == "WeakMap") {
-            return self.eval_new_weak_map(args);
-        }
-
-        if matches!(callee, crate::parser::ast::Expr::Identifier(name) if name == "WeakSet") {
-            return self.eval_new_weak_set(args);
+        if let crate::parser::ast::Expr::Identifier(name) = callee
+            && matches!(name.as_str(), "Map" | "Set" | "WeakMap" | "WeakSet")
+        {
+            return self.eval_new_collection(name, args);
         }
 
         if matches!(callee, crate::parser::ast::Expr::Identifier(name) if name == "RegExp") {
@@ -207,6 +336,7 @@ impl Interpreter {
                 val.to_number()
             };
             let mut obj = JsObject::new();
+            obj.prototype = Some(self.prototypes.date);
             obj.set("[[PrimitiveValue]]".to_string(), JsValue::Number(timestamp));
             obj.set("[[DateValue]]".to_string(), JsValue::Number(timestamp));
             return Ok(JsValue::Object(self.heap.alloc_cell(obj)));
@@ -234,8 +364,8 @@ impl Interpreter {
             // new Function() - stub
             return Ok(JsValue::Function {
                 name: "anonymous".to_string(),
-                params: vec![],
-                body: vec![],
+                params: Vec::new().into(),
+                body: Vec::new().into(),
                 closure_env: self.env.capture(),
                 is_async: false,
                 is_generator: false,
@@ -249,136 +379,149 @@ impl Interpreter {
             return self.eval_new_proxy(args);
         }
 
-        if let crate::parser::ast::Expr::Identifier(name) = callee {
-            if let Ok(val) = self.env.get(name) {
-                if let JsValue::Proxy(proxy) = &val {
-                    let (trap, target) = {
-                        let p = proxy.borrow();
-                        p.check_revoked()
-                            .map_err(|msg| RuntimeError::TypeError { message: msg })?;
-                        (p.get_trap("construct"), p.target.clone())
-                    };
-                    let arg_values: Vec<JsValue> = args
-                        .iter()
-                        .map(|arg| self.eval_expr(arg))
-                        .collect::<Result<_, _>>()?;
-                    if let Some(trap_fn) = trap {
-                        let args_array = JsValue::Array(
-                            self.heap
-                                .alloc_cell(crate::runtime::value::array::JsArray::new(arg_values)),
-                        );
-                        return self.call_function(&trap_fn, &[target, args_array, val]);
-                    }
-                    if let JsValue::Function { name: fn_name, .. } = &target {
-                        if let Some(class_name) = fn_name.strip_suffix("::constructor") {
-                            if let Some(class) = self.classes.get(class_name).cloned() {
-                                let mut instance = crate::runtime::value::object::JsObject::new();
-                                instance.prototype = Some(class.prototype);
-                                let instance_value =
-                                    JsValue::Object(self.heap.alloc_cell(instance));
-                                self.call_function_with_this(
-                                    &class.constructor,
-                                    &arg_values,
-                                    Some(instance_value.clone()),
-                                )?;
-                                return Ok(instance_value);
-                            }
-                        }
-                    }
-                    return self.call_function(&target, &arg_values);
+        let constructor = match callee {
+            Expr::Identifier(class_name) => {
+                // A class or binding constructed before its declaration ran.
+                if let Err(err @ RuntimeError::UninitializedBinding { .. }) =
+                    self.env.get(class_name)
+                {
+                    return Err(err);
+                }
+                // First try self.classes (class declarations)
+                if let Some(class) = self.classes.get(class_name).cloned() {
+                    let arg_values = self.eval_call_args(args)?;
+                    return self.construct_class(class_name, &class, &arg_values);
                 }
+                self.env
+                    .get(class_name)
+                    .map_err(|_| RuntimeError::TypeError {
+                        message: format!("'{class_name}' is not a class constructor"),
+                    })?
             }
-        }
-
-        let class_name = if let crate::parser::ast::Expr::Identifier(name) = callee {
-            name
-        } else {
-            return Err(RuntimeError::TypeError {
-                message: "new currently supports named classes and Error".to_string(),
-            });
+            // A member path such as `new ns.Widget()`, a parenthesized
+            // expression or a nested `new`.
+            other => self.eval_expr(other)?,
         };
-
-        // First try self.classes (class declarations)
-        if let Some(class) = self.classes.get(class_name).cloned() {
-            let arg_values: Vec<JsValue> = args
-                .iter()
-                .map(|arg| self.eval_expr(arg))
-                .collect::<Result<_, _>>()?;
-
-            let mut instance = JsObject::new();
-            instance.prototype = Some(class.prototype);
-            let instance_value = JsValue::Object(self.heap.alloc_cell(instance));
-
-            self.super_stack.push(class.parent.clone());
-            let ctor_result = self.call_function_with_this(
-                &class.constructor,
-                &arg_values,
-                Some(instance_value.clone()),
-            );
-            self.super_stack.pop();
-            ctor_result?;
-
-            return Ok(instance_value);
+        let arg_values = self.eval_call_args(args)?;
+        match self.construct_value(&constructor, &arg_values)? {
+            Some(instance) => Ok(instance),
+            None => Err(RuntimeError::TypeError {
+                message: match callee {
+                    Expr::Identifier(class_name) => {
+                        format!("'{class_name}' is not a class constructor")
+                    }
+                    _ => format!(
+                        "'{}' is not a constructor",
+                        Self::non_callable_label(&constructor)
+                    ),
+                },
+            }),
         }
+    }
 
-        // Try plain function constructor
-        if let Ok(func_val) = self.env.get(class_name) {
-            if let JsValue::Function { ref properties, .. } = func_val {
-                let arg_values: Vec<JsValue> = args
-                    .iter()
-                    .map(|arg| self.eval_expr(arg))
-                    .collect::<Result<_, _>>()?;
+    /// Constructs the value a `new` callee evaluated to, or gives `None`
+    /// when it is not a constructor.
+    pub(crate) fn construct_value(
+        &mut self,
+        constructor: &JsValue,
+        args: &[JsValue],
+    ) -> Result<Option<JsValue>, RuntimeError> {
+        let handler = match constructor {
+            JsValue::Proxy(proxy) => {
+                let (trap, target) = {
+                    let p = proxy.borrow();
+                    p.check_revoked()
+                        .map_err(|msg| RuntimeError::TypeError { message: msg })?;
+                    (p.get_trap("construct"), p.target.clone())
+                };
+                if let Some(trap_fn) = trap {
+                    let args_array = JsValue::Array(
+                        self.heap
+                            .alloc_cell(crate::runtime::value::array::JsArray::new(args.to_vec())),
+                    );
+                    return self
+                        .call_function(&trap_fn, &[target, args_array, constructor.clone()])
+                        .map(Some);
+                }
+                return self.construct_value(&target, args);
+            }
+            JsValue::NativeFunction { handler, .. } => handler,
+            JsValue::Function {
+                name, properties, ..
+            } => {
+                if let Some(class_name) = name.strip_suffix("::constructor")
+                    && let Some(class) = self.classes.get(class_name).cloned()
+                {
+                    return self.construct_class(class_name, &class, args).map(Some);
+                }
 
                 let mut instance = JsObject::new();
                 if let Some(props) = properties {
                     let borrowed = props.borrow();
-                    if let Some(proto_prop) = borrowed.properties.get("prototype") {
-                        if let JsValue::Object(proto_obj) = &proto_prop.value {
-                            instance.prototype = Some(*proto_obj);
-                        }
+                    if let Some(proto_prop) = borrowed.properties.get("prototype")
+                        && let JsValue::Object(proto_obj) = &proto_prop.value
+                    {
+                        instance.prototype = Some(*proto_obj);
                     }
                 }
                 let instance_value = JsValue::Object(self.heap.alloc_cell(instance));
 
-                let result = self.call_function_with_this(
-                    &func_val,
-                    &arg_values,
-                    Some(instance_value.clone()),
-                )?;
+                let result =
+                    self.call_function_with_this(constructor, args, Some(instance_value.clone()))?;
 
                 // If the constructor returns an object, use that instead
-                if matches!(result, JsValue::Object(_)) {
-                    return Ok(result);
+                if is_object_like(&result) {
+                    return Ok(Some(result));
                 }
-                return Ok(instance_value);
+                return Ok(Some(instance_value));
             }
-        }
-
-        Err(RuntimeError::TypeError {
-            message: format!("'{class_name}' is not a class constructor"),
-        })
+            _ => return Ok(None),
+        };
+        let instance = match handler {
+            NativeFunction::NativeClassConstructor(native_name) => {
+                self.construct_native_class(native_name, args, None)?
+            }
+            NativeFunction::CollectionCtor(kind) => {
+                let iterable = args.first().cloned().unwrap_or(JsValue::Undefined);
+                self.construct_collection(kind, iterable)?
+            }
+            NativeFunction::BinaryCtor(kind) => self.construct_binary(kind, args)?,
+            NativeFunction::ErrorCtor(kind) => self.construct_error(kind, args)?,
+            NativeFunction::PromiseCtor => match args.first() {
+                Some(executor) => self.construct_promise(executor.clone())?,
+                None => {
+                    return Err(
+                        self.throw_type_error("Promise resolver undefined is not a function")
+                    );
+                }
+            },
+            NativeFunction::ProxyCtor => {
+                let target = args.first().cloned().unwrap_or(JsValue::Undefined);
+                let handler = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+                self.construct_proxy(target, handler)?
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(instance))
     }
 
     pub(crate) fn eval_super_call(
         &mut self,
         args: &[crate::parser::ast::Expr],
     ) -> Result<JsValue, RuntimeError> {
-        let parent_name =
-            self.super_stack
-                .last()
-                .cloned()
-                .flatten()
-                .ok_or_else(|| RuntimeError::TypeError {
-                    message: "super() is only valid inside class constructors".to_string(),
-                })?;
-
-        let parent_class =
-            self.classes
-                .get(&parent_name)
-                .cloned()
-                .ok_or_else(|| RuntimeError::TypeError {
-                    message: format!("unknown parent class '{parent_name}'"),
-                })?;
+        let class = self
+            .super_stack
+            .last()
+            .and_then(|name| self.classes.get(name))
+            .cloned();
+        let Some((class, parent_name)) =
+            class.and_then(|class| class.parent.clone().map(|parent| (class, parent)))
+        else {
+            return Err(RuntimeError::TypeError {
+                message: "super() is only valid inside class constructors".to_string(),
+            });
+        };
+        let parent_class = self.parent_class(&parent_name)?;
 
         let this_value = self.env.get("this")?;
         let arg_values: Vec<JsValue> = args
@@ -386,11 +529,8 @@ impl Interpreter {
             .map(|arg| self.eval_expr(arg))
             .collect::<Result<_, _>>()?;
 
-        self.super_stack.push(parent_class.parent.clone());
-        let result =
-            self.call_function_with_this(&parent_class.constructor, &arg_values, Some(this_value));
-        self.super_stack.pop();
-        result?;
+        self.run_class_constructor(&parent_name, &parent_class, &arg_values, &this_value)?;
+        self.initialize_instance(&class, &this_value)?;
         Ok(JsValue::Undefined)
     }
 
@@ -400,39 +540,60 @@ impl Interpreter {
         right: &Expr,
     ) -> Result<JsValue, RuntimeError> {
         let instance = self.eval_expr(left)?;
-        let class_name = match right {
-            Expr::Identifier(name) => name,
-            _ => return Ok(JsValue::Boolean(false)),
-        };
+        let target = self.eval_expr(right)?;
+        self.instance_of(&instance, &target).map(JsValue::Boolean)
+    }
 
-        // Check for error type objects created by the runtime
-        if let JsValue::Object(obj) = &instance {
-            let borrowed = obj.borrow();
-            if let Some(prop) = borrowed.properties.get("[[ErrorType]]") {
-                if let JsValue::String(error_type) = &prop.value {
-                    return Ok(JsValue::Boolean(error_type == class_name));
-                }
+    /// `instance instanceof target`: a `Symbol.hasInstance` method on the
+    /// target decides when present, otherwise the target's `prototype`
+    /// is looked for on the instance's prototype chain.
+    pub(crate) fn instance_of(
+        &mut self,
+        instance: &JsValue,
+        target: &JsValue,
+    ) -> Result<bool, RuntimeError> {
+        if matches!(target, JsValue::Object(_) | JsValue::Function { .. }) {
+            let checker = self.get_symbol_property(target, &symbol_has_instance())?;
+            if !matches!(checker, JsValue::Undefined | JsValue::Null) {
+                let result = self.call_function_with_this(
+                    &checker,
+                    std::slice::from_ref(instance),
+                    Some(target.clone()),
+                )?;
+                return Ok(result.to_boolean());
             }
         }
+        if !is_callable(target) {
+            return Err(self.throw_type_error("Right-hand side of 'instanceof' is not callable"));
+        }
+        if !is_object_like(instance) {
+            return Ok(false);
+        }
+        if let JsValue::NativeFunction { handler, .. } = target
+            && let Some(result) = self.native_instance_of(instance, handler)
+        {
+            return Ok(result);
+        }
 
-        let class = match self.classes.get(class_name) {
-            Some(class) => class,
-            None => return Ok(JsValue::Boolean(false)),
-        };
-
-        let JsValue::Object(object) = instance else {
-            return Ok(JsValue::Boolean(false));
+        let JsValue::Object(prototype) = self.get_property(target, "prototype")? else {
+            return Err(
+                self.throw_type_error("Function has non-object prototype in instanceof check")
+            );
         };
-
-        let mut current = object.borrow().prototype;
+        let mut null_terminated = has_null_prototype(instance);
+        let mut current = self.inherited_prototype(instance);
         while let Some(proto) = current {
-            if Gc::ptr_eq(proto, class.prototype) {
-                return Ok(JsValue::Boolean(true));
+            if Gc::ptr_eq(proto, prototype) {
+                return Ok(true);
             }
-            current = proto.borrow().prototype;
+            let proto = proto.borrow();
+            null_terminated = proto.null_prototype;
+            current = proto.prototype;
         }
-
-        Ok(JsValue::Boolean(false))
+        // Chains that stop short of Object.prototype still inherit from it,
+        // as in `lookup_prototype_chain`, unless they end in a `null`
+        // prototype set on purpose.
+        Ok(!null_terminated && Gc::ptr_eq(prototype, self.prototypes.object))
     }
 
     pub(crate) fn eval_in_expr(
@@ -440,98 +601,33 @@ impl Interpreter {
         left: &Expr,
         right: &Expr,
     ) -> Result<JsValue, RuntimeError> {
-        let key = self.eval_expr(left)?.to_js_string();
+        if let Expr::PrivateName(name) = left {
+            return self.eval_private_in(name, right);
+        }
+        let key_val = self.eval_expr(left)?;
         let target = self.eval_expr(right)?;
-        match &target {
-            JsValue::Object(obj) => {
-                let mut current = Some(*obj);
-                while let Some(candidate) = current {
-                    let borrowed = candidate.borrow();
-                    if borrowed.properties.contains_key(&key) {
-                        return Ok(JsValue::Boolean(true));
-                    }
-                    current = borrowed.prototype;
-                }
-                Ok(JsValue::Boolean(false))
-            }
-            JsValue::Proxy(proxy) => {
-                let (trap, proxy_target) = {
-                    let p = proxy.borrow();
-                    p.check_revoked()
-                        .map_err(|msg| RuntimeError::TypeError { message: msg })?;
-                    (p.get_trap("has"), p.target.clone())
-                };
-                if let Some(trap_fn) = trap {
-                    let result =
-                        self.call_function(&trap_fn, &[proxy_target, JsValue::String(key)])?;
-                    Ok(JsValue::Boolean(result.to_boolean()))
-                } else {
-                    self.eval_in_value(&key, &proxy_target)
-                }
-            }
-            JsValue::Array(arr) => {
-                if let Ok(idx) = key.parse::<usize>() {
-                    Ok(JsValue::Boolean(idx < arr.borrow().len()))
-                } else {
-                    Ok(JsValue::Boolean(false))
-                }
-            }
-            _ => Err(RuntimeError::TypeError {
+        if matches!(
+            target,
+            JsValue::Undefined
+                | JsValue::Null
+                | JsValue::Boolean(_)
+                | JsValue::Number(_)
+                | JsValue::String(_)
+                | JsValue::Symbol(_)
+        ) {
+            return Err(RuntimeError::TypeError {
                 message: format!(
                     "Cannot use 'in' operator to search for '{}' in {}",
-                    key,
+                    key_val.to_js_string(),
                     target.to_js_string()
                 ),
-            }),
-        }
-    }
-
-    pub(crate) fn eval_in_value(
-        &mut self,
-        key: &str,
-        target: &JsValue,
-    ) -> Result<JsValue, RuntimeError> {
-        match target {
-            JsValue::Object(obj) => {
-                let mut current = Some(*obj);
-                while let Some(candidate) = current {
-                    let borrowed = candidate.borrow();
-                    if borrowed.properties.contains_key(key) {
-                        return Ok(JsValue::Boolean(true));
-                    }
-                    current = borrowed.prototype;
-                }
-                Ok(JsValue::Boolean(false))
-            }
-            JsValue::Array(arr) => {
-                if let Ok(idx) = key.parse::<usize>() {
-                    Ok(JsValue::Boolean(idx < arr.borrow().len()))
-                } else {
-                    Ok(JsValue::Boolean(false))
-                }
-            }
-            JsValue::Proxy(proxy) => {
-                let (trap, proxy_target) = {
-                    let p = proxy.borrow();
-                    p.check_revoked()
-                        .map_err(|msg| RuntimeError::TypeError { message: msg })?;
-                    (p.get_trap("has"), p.target.clone())
-                };
-                if let Some(trap_fn) = trap {
-                    let result = self.call_function(
-                        &trap_fn,
-                        &[proxy_target, JsValue::String(key.to_string())],
-                    )?;
-                    Ok(JsValue::Boolean(result.to_boolean()))
-                } else {
-                    self.eval_in_value(key, &proxy_target)
-                }
-            }
-            _ => Ok(JsValue::Boolean(false)),
+            });
         }
+        let key = self.property_key(&key_val)?;
+        Ok(JsValue::Boolean(self.has_keyed(&target, &key)?))
     }
 
-    fn method_to_function(&self, method: &ClassMethod, class_name: &str) -> JsValue {
+    fn method_to_function(&self, method: &ClassMethod, class_name: &str, name: &str) -> JsValue {
         let params = method
             .params
             .iter()
@@ -539,11 +635,11 @@ impl Interpreter {
                 pattern: Pattern::Identifier(name.clone()),
                 default: None,
             })
-            .collect();
+            .collect::<Vec<_>>();
         JsValue::Function {
-            name: format!("{class_name}::{}", method.name),
-            params,
-            body: method.body.clone(),
+            name: format!("{class_name}::{name}"),
+            params: params.into(),
+            body: method.body.clone().with_source(method.source.clone()),
             closure_env: self.env.capture(),
             is_async: false,
             is_generator: false,
@@ -552,4 +648,96 @@ impl Interpreter {
             properties: None,
         }
     }
+
+    /// A function returning the initial value of a field, called with the
+    /// instance (or, for a static field, the class) as `this`.
+    fn field_initializer(&self, class_name: &str, key: &FieldKey, value: &Expr) -> JsValue {
+        JsValue::Function {
+            name: format!("{class_name}::{}", key.display()),
+            params: Vec::new().into(),
+            body: FunctionBody::from(vec![Stmt::Return(Some(value.clone()))]),
+            closure_env: self.env.capture(),
+            is_async: false,
+            is_generator: false,
+            source_path: self.module_stack.last().map(|p| p.display().to_string()),
+            source_offset: 0,
+            properties: None,
+        }
+    }
+
+    /// Built-in and host constructors whose instances are told apart by an
+    /// internal marker or their Rust representation, not a prototype.
+    fn native_instance_of(&self, instance: &JsValue, handler: &NativeFunction) -> Option<bool> {
+        let marker = |key: &str| match instance {
+            JsValue::Object(obj) => match obj.borrow().get(key) {
+                Some(JsValue::String(name)) => Some(name.to_string()),
+                _ => None,
+            },
+            _ => None,
+        };
+        match handler {
+            NativeFunction::ErrorCtor(kind) => Some(
+                marker("[[ErrorType]]")
+                    .is_some_and(|error_type| kind == "Error" || error_type == *kind),
+            ),
+            NativeFunction::NativeClassConstructor(class_name) => Some(
+                marker("[[NativeClass]]")
+                    .is_some_and(|name| self.native_class_extends(&name, class_name)),
+            ),
+            NativeFunction::CollectionCtor(kind) => Some(matches!(
+                (instance, kind.as_str()),
+                (JsValue::Map(_), "Map")
+                    | (JsValue::Set(_), "Set")
+                    | (JsValue::WeakMap(_), "WeakMap")
+                    | (JsValue::WeakSet(_), "WeakSet")
+            )),
+            NativeFunction::BinaryCtor(kind) => Some(match instance {
+                JsValue::ArrayBuffer(_) => kind == "ArrayBuffer",
+                JsValue::DataView(_) => kind == "DataView",
+                JsValue::TypedArray(array) => array.borrow().kind.constructor_name() == kind,
+                _ => false,
+            }),
+            NativeFunction::RegExpCtor => Some(matches!(instance, JsValue::RegExp(_))),
+            NativeFunction::PromiseCtor => Some(matches!(instance, JsValue::Promise(_))),
+            _ => None,
+        }
+    }
+}
+
+/// The private names a class declares, each once.
+fn private_names(class_decl: &ClassDecl) -> Vec<&str> {
+    let keys = class_decl
+        .methods
+        .iter()
+        .map(|method| &method.key)
+        .chain(class_decl.fields.iter().map(|field| &field.key));
+    let mut names = Vec::new();
+    for key in keys {
+        if let PropertyKey::Private(name) = key
+            && !names.contains(&name.as_str())
+        {
+            names.push(name.as_str());
+        }
+    }
+    names
+}
+
+fn is_callable(value: &JsValue) -> bool {
+    match value {
+        JsValue::Function { .. } | JsValue::NativeFunction { .. } => true,
+        JsValue::Proxy(proxy) => is_callable(&proxy.borrow().target),
+        _ => false,
+    }
+}
+
+pub(super) fn is_object_like(value: &JsValue) -> bool {
+    !matches!(
+        value,
+        JsValue::Undefined
+            | JsValue::Null
+            | JsValue::Boolean(_)
+            | JsValue::Number(_)
+            | JsValue::String(_)
+            | JsValue::Symbol(_)
+    )
 }
@@ -1,18 +1,24 @@
 use super::Interpreter;
+use super::eval_class::is_object_like;
+use super::eval_expr_helpers::logical_assigns;
+use super::property_access::{nullish_read_error, nullish_write_error};
 use crate::errors::RuntimeError;
-use crate::parser::ast::{
-    ArrowBody, BinOp, Expr, LogicalOp, ObjectProperty, OptionalOp, Stmt, TemplatePart, UpdateOp,
-};
-use crate::runtime::value::array::JsArray;
-use crate::runtime::value::object::JsObject;
-use crate::runtime::value::regexp::{JsRegExp, RegExpFlags};
+use crate::parser::ast::{BinOp, Expr, LogicalOp, UnaryOp, UpdateOp};
 use crate::runtime::value::JsValue;
 use crate::runtime::value::{eval_literal, eval_unary};
 impl Interpreter {
     pub(crate) fn eval_expr(&mut self, expr: &Expr) -> Result<JsValue, RuntimeError> {
+        if !self.generator_stack.is_empty()
+            && let Some(result) = self.eval_generator_operand(expr)
+        {
+            return result;
+        }
+        #[cfg(feature = "profiling")]
+        self.profile.count_expression(expr.kind());
         match expr {
             Expr::Literal(lit) => Ok(eval_literal(lit)),
             Expr::Identifier(name) => self.env.get(name),
+            Expr::Local { name, slot } => self.env.get_slot(*slot, name),
             Expr::Binary { left, op, right } => {
                 if matches!(op, BinOp::Instanceof) {
                     return self.eval_instanceof_expr(left, right);
@@ -25,30 +31,61 @@ impl Interpreter {
                 self.eval_binary(lhs, op, rhs)
             }
             Expr::Unary { op, operand } => {
-                let val = self.eval_expr(operand)?;
+                let mut val = self.eval_expr(operand)?;
+                if is_object_like(&val)
+                    && matches!(op, UnaryOp::Neg | UnaryOp::Pos | UnaryOp::BitNot)
+                {
+                    val = JsValue::Number(self.coerce_to_number(&val)?);
+                }
                 eval_unary(op, val)
             }
             Expr::Call { callee, args } => self.eval_call(callee, args),
-            Expr::Assign { name, value } => {
+            Expr::Assign {
+                name,
+                value,
+                strict,
+                slot,
+            } => {
+                let val = self.eval_named(value, name)?;
+                self.assign_name(name, *slot, *strict, val.clone())?;
+                Ok(val)
+            }
+            Expr::DestructuringAssign { pattern, value } => {
                 let val = self.eval_expr(value)?;
-                self.env.set(name, val.clone())?;
+                self.eval_pattern_assignment(pattern, val.clone())?;
                 Ok(val)
             }
-            Expr::CompoundAssign { name, op, value } => {
-                let current = self.env.get(name)?;
-                let rhs = self.eval_expr(value)?;
-                let next = self.eval_compound(current, op, rhs)?;
-                self.env.set(name, next.clone())?;
+            Expr::CompoundAssign {
+                name,
+                op,
+                value,
+                slot,
+            } => {
+                let current = self.read_name(name, *slot)?;
+                let next = match op.logical() {
+                    Some(logical) if !logical_assigns(&logical, &current) => return Ok(current),
+                    Some(_) => self.eval_expr(value)?,
+                    None => {
+                        let rhs = self.eval_expr(value)?;
+                        self.eval_compound_binding(name, *slot, current, op, rhs)?
+                    }
+                };
+                self.write_name(name, *slot, next.clone())?;
                 Ok(next)
             }
-            Expr::UpdateExpr { name, op, prefix } => {
-                let current = self.env.get(name)?;
+            Expr::UpdateExpr {
+                name,
+                op,
+                prefix,
+                slot,
+            } => {
+                let current = self.read_name(name, *slot)?;
                 let num = current.to_number();
                 let next = match op {
                     UpdateOp::Inc => JsValue::Number(num + 1.0),
                     UpdateOp::Dec => JsValue::Number(num - 1.0),
                 };
-                self.env.set(name, next.clone())?;
+                self.write_name(name, *slot, next.clone())?;
                 if *prefix {
                     Ok(next)
                 } else {
@@ -58,128 +95,47 @@ impl Interpreter {
             Expr::MemberAccess { object, property } => {
                 self.eval_member_call(object, property, &[], false)
             }
-            Expr::TemplateLiteral { parts } => {
-                let mut result = String::new();
-                for part in parts {
-                    match part {
-                        TemplatePart::Str(s) => result.push_str(s),
-                        TemplatePart::Expression(expr) => {
-                            let val = self.eval_expr(expr)?;
-                            result.push_str(&val.to_js_string());
-                        }
-                    }
-                }
-                Ok(JsValue::String(result))
+            Expr::TemplateLiteral { parts } => self.eval_template_literal(parts),
+            Expr::ObjectLiteral { properties } => self.eval_object_literal(properties),
+            Expr::ArrayLiteral { elements } => self.eval_array_literal(elements),
+            Expr::ComputedMemberAccess { object, property } => self
+                .eval_computed_member(object, property)
+                .map(|(_, value)| value),
+            Expr::PrivateMember { object, name } => {
+                let target = self.eval_expr(object)?;
+                self.get_private(&target, name)
             }
-            Expr::ObjectLiteral { properties } => {
-                let mut obj = JsObject::new();
-                for property in properties {
-                    match property {
-                        ObjectProperty::KeyValue(key, val_expr) => {
-                            let val = self.eval_expr(val_expr)?;
-                            let key = self.eval_property_key(key)?;
-                            obj.set(key, val);
-                        }
-                        ObjectProperty::Getter(key, body) => {
-                            let key = self.eval_property_key(key)?;
-                            let getter = JsValue::Function {
-                                name: format!("get {key}"),
-                                params: Vec::new(),
-                                body: body.clone(),
-                                closure_env: self.env.capture(),
-                                is_async: false,
-                                is_generator: false,
-                                source_path: self
-                                    .module_stack
-                                    .last()
-                                    .map(|p| p.display().to_string()),
-                                source_offset: 0,
-                                properties: None,
-                            };
-                            obj.set_getter(key, getter);
-                        }
-                        ObjectProperty::Setter(key, param, body) => {
-                            let key = self.eval_property_key(key)?;
-                            let setter = JsValue::Function {
-                                name: format!("set {key}"),
-                                params: vec![crate::parser::ast::Param {
-                                    pattern: crate::parser::ast::Pattern::Identifier(param.clone()),
-                                    default: None,
-                                }],
-                                body: body.clone(),
-                                closure_env: self.env.capture(),
-                                is_async: false,
-                                is_generator: false,
-                                source_path: self
-                                    .module_stack
-                                    .last()
-                                    .map(|p| p.display().to_string()),
-                                source_offset: 0,
-                                properties: None,
-                            };
-                            obj.set_setter(key, setter);
-                        }
-                        ObjectProperty::Spread(expr) => {
-                            let spread_val = self.eval_expr(expr)?;
-                            match spread_val {
-                                JsValue::Object(source) => {
-                                    let borrowed = source.borrow();
-                                    for (k, p) in &borrowed.properties {
-                                        obj.set(k.clone(), p.value.clone());
-                                    }
-                                }
-                                JsValue::Undefined | JsValue::Null => {}
-                                other => {
-                                    return Err(RuntimeError::TypeError {
-                                        message: format!(
-                                            "cannot spread non-object value {other} in object literal"
-                                        ),
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-                Ok(JsValue::Object(self.heap.alloc_cell(obj)))
-            }
-            Expr::ArrayLiteral { elements } => {
-                let mut vals: Vec<JsValue> = Vec::new();
-                for element in elements {
-                    match element {
-                        Expr::Spread(inner) => {
-                            let spread_val = self.eval_expr(inner)?;
-                            vals.extend(self.collect_iterable(&spread_val)?);
-                        }
-                        other => vals.push(self.eval_expr(other)?),
-                    }
-                }
-                Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(vals))))
-            }
-            Expr::ComputedMemberAccess { object, property } => {
-                let obj_val = self.eval_expr(object)?;
-                let key_val = self.eval_expr(property)?;
-                if let JsValue::Symbol(ref sym) = key_val {
-                    return self.get_symbol_property(&obj_val, sym);
-                }
-                let key = key_val.to_js_string();
-                self.get_property(&obj_val, &key)
+            Expr::ImportMeta => self.eval_import_meta(),
+            Expr::PrivateName(name) => {
+                Err(self.throw_syntax_error(&format!("unexpected private name '#{name}'")))
             }
             Expr::MemberAssign {
                 object,
                 property,
                 value,
+                strict,
             } => {
+                if let Expr::PrivateName(name) = &**property {
+                    return self.eval_private_assign(object, name, value);
+                }
                 let obj_val = self.eval_expr(object)?;
                 let key_val = self.eval_expr(property)?;
                 let val = self.eval_expr(value)?;
-                if let JsValue::Symbol(ref sym) = key_val {
-                    self.set_symbol_property(&obj_val, sym, val.clone())?;
-                    return Ok(val);
+                if matches!(obj_val, JsValue::Undefined | JsValue::Null) {
+                    let key = key_val.to_js_string();
+                    return Err(nullish_write_error(&obj_val, &key, Some(object)));
                 }
-                let key = key_val.to_js_string();
-                self.set_property(&obj_val, &key, val.clone())?;
+                let key = self.property_key(&key_val)?;
+                self.assign_keyed(&obj_val, &key, val.clone(), *strict)?;
                 Ok(val)
             }
+            Expr::MemberCompoundAssign {
+                object,
+                property,
+                op,
+                value,
+                strict,
+            } => self.eval_member_compound_assign(object, property, op, value, *strict),
             Expr::Logical { left, op, right } => {
                 let lhs = self.eval_expr(left)?;
                 match op {
@@ -217,115 +173,17 @@ impl Interpreter {
                     self.eval_expr(else_expr)
                 }
             }
-            Expr::Typeof(expr) => {
-                let val = match expr.as_ref() {
-                    Expr::Identifier(name) => self.env.get(name).unwrap_or(JsValue::Undefined),
-                    other => self.eval_expr(other)?,
-                };
-                let t = match val {
-                    JsValue::Undefined => "undefined",
-                    JsValue::Null => "object",
-                    JsValue::Boolean(_) => "boolean",
-                    JsValue::Number(_) => "number",
-                    JsValue::String(_) => "string",
-                    JsValue::Function { .. } => "function",
-                    JsValue::NativeFunction { .. } => "function",
-                    JsValue::Symbol(_) => "symbol",
-                    JsValue::Object(_)
-                    | JsValue::Array(_)
-                    | JsValue::Promise(_)
-                    | JsValue::Map(_)
-                    | JsValue::Set(_)
-                    | JsValue::WeakMap(_)
-                    | JsValue::WeakSet(_)
-                    | JsValue::RegExp(_)
-                    | JsValue::Proxy(_) => "object",
-                };
-                Ok(JsValue::String(t.to_string()))
-            }
+            Expr::Typeof(expr) => self.eval_typeof(expr),
             Expr::Spread(_) => Err(RuntimeError::TypeError {
                 message: "spread syntax is only valid in calls and array literals".to_string(),
             }),
             Expr::New { callee, args } => self.eval_new(callee, args),
             Expr::SuperCall { args } => self.eval_super_call(args),
             Expr::Await(expr) => self.eval_await_expr(expr),
-            Expr::Yield { value, delegate } => {
-                if self.generator_depth == 0 {
-                    return Err(RuntimeError::TypeError {
-                        message: "yield is only valid inside generator functions".to_string(),
-                    });
-                }
-                if *delegate {
-                    // yield* iterable — collect all values from the sub-iterable
-                    let inner = match value {
-                        Some(expr) => self.eval_expr(expr)?,
-                        None => JsValue::Undefined,
-                    };
-                    let items = self.collect_iterable(&inner)?;
-                    self.generator_yields.extend(items);
-                } else {
-                    let val = match value {
-                        Some(expr) => self.eval_expr(expr)?,
-                        None => JsValue::Undefined,
-                    };
-                    self.generator_yields.push(val);
-                }
-                Ok(JsValue::Undefined)
-            }
-            Expr::ArrowFunction {
-                params,
-                body,
-                is_async,
-            } => {
-                let body = match body {
-                    ArrowBody::Block(stmts) => stmts.clone(),
-                    ArrowBody::Expr(expr) => vec![Stmt::Return(Some(*expr.clone()))],
-                };
-                Ok(JsValue::Function {
-                    name: "<arrow>".to_string(),
-                    params: params.clone(),
-                    body,
-                    closure_env: self.env.capture(),
-                    is_async: *is_async,
-                    is_generator: false,
-                    source_path: self.module_stack.last().map(|p| p.display().to_string()),
-                    source_offset: 0,
-                    properties: None,
-                })
-            }
-            Expr::OptionalChain { base, chain } => {
-                let mut current = self.eval_expr(base)?;
-                for op in chain {
-                    if matches!(current, JsValue::Null | JsValue::Undefined) {
-                        return Ok(JsValue::Undefined);
-                    }
-
-                    current = match op {
-                        OptionalOp::PropertyAccess(name) => self.get_property(&current, name)?,
-                        OptionalOp::ComputedAccess(expr) => {
-                            let key = self.eval_expr(expr)?.to_js_string();
-                            self.get_property(&current, &key)?
-                        }
-                        OptionalOp::Call(args) => {
-                            let arg_values = args
-                                .iter()
-                                .map(|arg| self.eval_expr(arg))
-                                .collect::<Result<Vec<_>, _>>()?;
-                            self.call_function(&current, &arg_values)?
-                        }
-                    };
-                }
-
-                Ok(current)
-            }
-            Expr::RegexLiteral { pattern, flags } => {
-                let fl = RegExpFlags::from_str(flags)
-                    .map_err(|msg| RuntimeError::TypeError { message: msg })?;
-                let re = JsRegExp::new(pattern, fl)
-                    .map_err(|msg| RuntimeError::TypeError { message: msg })?;
-                Ok(JsValue::RegExp(self.heap.alloc_cell(re)))
-            }
-            Expr::Delete(operand) => self.eval_delete_expr(operand),
+            Expr::Yield { value, delegate } => self.eval_yield(value.as_deref(), *delegate),
+            Expr::OptionalChain { base, chain } => self.eval_optional_chain(base, chain),
+            Expr::RegexLiteral { pattern, flags } => self.eval_regex_literal(pattern, flags),
+            Expr::Delete { operand, strict } => self.eval_delete_expr(operand, *strict),
             Expr::Sequence(exprs) => {
                 let mut result = JsValue::Undefined;
                 for e in exprs {
@@ -333,57 +191,10 @@ impl Interpreter {
                 }
                 Ok(result)
             }
-            Expr::FunctionExpr {
-                name,
-                params,
-                body,
-                is_async,
-                is_generator,
-            } => {
-                let proto = crate::runtime::value::object::JsObject::new();
-                let proto_gc = self.heap.alloc_cell(proto);
-                let mut fn_props = crate::runtime::value::object::JsObject::new();
-                fn_props.set("prototype".to_string(), JsValue::Object(proto_gc));
-                Ok(JsValue::Function {
-                    name: name.clone().unwrap_or_else(|| "<anonymous>".to_string()),
-                    params: params.clone(),
-                    body: body.clone(),
-                    closure_env: self.env.capture(),
-                    is_async: *is_async,
-                    is_generator: *is_generator,
-                    source_path: self.module_stack.last().map(|p| p.display().to_string()),
-                    source_offset: 0,
-                    properties: Some(self.heap.alloc_cell(fn_props)),
-                })
-            }
-            Expr::TaggedTemplate { tag, parts } => {
-                let func = self.eval_expr(tag)?;
-                let mut strings = Vec::new();
-                let mut raw_strings = Vec::new();
-                let mut exprs = Vec::new();
-                for part in parts {
-                    match part {
-                        TemplatePart::Str(s) => {
-                            raw_strings.push(JsValue::String(s.clone()));
-                            let cooked = s
-                                .replace("\\n", "\n")
-                                .replace("\\t", "\t")
-                                .replace("\\\\", "\\");
-                            strings.push(JsValue::String(cooked));
-                        }
-                        TemplatePart::Expression(expr) => {
-                            exprs.push(self.eval_expr(expr)?);
-                        }
-                    }
-                }
-                let raw_arr = JsValue::Array(self.heap.alloc_cell(JsArray::new(raw_strings)));
-                let tmpl_arr_gc = self.heap.alloc_cell(JsArray::new(strings));
-                let tmpl_obj = JsValue::Array(tmpl_arr_gc);
-                self.set_property(&tmpl_obj, "raw", raw_arr)?;
-                let mut call_args = vec![tmpl_obj];
-                call_args.extend(exprs);
-                self.call_function(&func, &call_args)
+            Expr::ArrowFunction { .. } | Expr::FunctionExpr { .. } => {
+                self.eval_function_literal(expr)
             }
+            Expr::TaggedTemplate { tag, parts, raw } => self.eval_tagged_template(tag, parts, raw),
         }
     }
 
@@ -391,10 +202,39 @@ impl Interpreter {
         if let Expr::MemberAccess { object, property } = callee {
             return self.eval_member_call(object, property, args, true);
         }
+        if let Expr::PrivateMember { object, name } = callee {
+            let this = self.eval_expr(object)?;
+            let func = self.get_private(&this, name)?;
+            let arg_values = self.eval_call_args(args)?;
+            return self.call_function_with_this(&func, &arg_values, Some(this));
+        }
+        if let Expr::ComputedMemberAccess { object, property } = callee {
+            let (this, func) = self.eval_computed_member(object, property)?;
+            let arg_values = self.eval_call_args(args)?;
+            return self.call_function_with_this(&func, &arg_values, Some(this));
+        }
 
         let func = self.eval_expr(callee)?;
         let arg_values = self.eval_call_args(args)?;
 
         self.call_function(&func, &arg_values)
     }
+
+    /// Reads `object[property]`, giving back the object as well for calls
+    /// that bind it as `this`.
+    fn eval_computed_member(
+        &mut self,
+        object: &Expr,
+        property: &Expr,
+    ) -> Result<(JsValue, JsValue), RuntimeError> {
+        let obj_val = self.eval_expr(object)?;
+        let key_val = self.eval_expr(property)?;
+        if matches!(obj_val, JsValue::Undefined | JsValue::Null) {
+            let key = key_val.to_js_string();
+            return Err(nullish_read_error(&obj_val, &key, Some(object)));
+        }
+        let key = self.property_key(&key_val)?;
+        let value = self.get_keyed(&obj_val, &key)?;
+        Ok((obj_val, value))
+    }
 }
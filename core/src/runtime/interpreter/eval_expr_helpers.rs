@@ -1,20 +1,22 @@
-use super::Interpreter;
+use super::{ControlFlow, Interpreter};
+use super::eval_class::is_object_like;
+use super::property_access::{PropKey, nullish_read_error};
 use crate::errors::RuntimeError;
-use crate::parser::ast::{AssignOp, BinOp, PropertyKey};
-use crate::runtime::value::abstract_equals;
-use crate::runtime::value::iterator::get_property_simple;
+use crate::parser::ast::{AssignOp, BinOp, Expr, LogicalOp, PropertyKey, Slot};
+use crate::runtime::builtins::math::js_pow;
+use crate::runtime::environment::BindingKind;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::collections::iterator::{
+    IteratedCollection, IterationKind, JsCollectionIterator,
+};
+use crate::runtime::value::{
+    abstract_equals, add_primitives, primitive_less_than, shift_count, to_int32, to_uint32,
+};
+use crate::runtime::value::iterator::{IterationCursor, IteratorRecord, get_property_simple};
 use crate::runtime::value::symbol;
 use crate::runtime::value::JsValue;
 
-fn to_int32(value: f64) -> i32 {
-    if !value.is_finite() || value == 0.0 {
-        return 0;
-    }
-    let truncated = value.trunc();
-    let int = truncated as i64;
-    int as i32
-}
-
 impl Interpreter {
     pub(crate) fn eval_call_args(
         &mut self,
@@ -33,71 +35,98 @@ impl Interpreter {
         Ok(values)
     }
 
-    pub(crate) fn eval_property_key(&mut self, key: &PropertyKey) -> Result<String, RuntimeError> {
+    /// Evaluates `expr` as the value of the binding or property `name`. An
+    /// anonymous function or arrow takes that name, as NamedEvaluation
+    /// gives it.
+    pub(crate) fn eval_named(&mut self, expr: &Expr, name: &str) -> Result<JsValue, RuntimeError> {
+        let mut value = self.eval_expr(expr)?;
+        if let (
+            Expr::ArrowFunction { .. } | Expr::FunctionExpr { name: None, .. },
+            JsValue::Function { name: fn_name, .. },
+        ) = (expr, &mut value)
+        {
+            *fn_name = name.to_string();
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn eval_property_key(&mut self, key: &PropertyKey) -> Result<PropKey, RuntimeError> {
         match key {
-            PropertyKey::Identifier(name) => Ok(name.clone()),
-            PropertyKey::Computed(expr) => Ok(self.eval_expr(expr)?.to_js_string()),
+            PropertyKey::Identifier(name) => Ok(PropKey::String(name.clone())),
+            PropertyKey::Computed(expr) => self.eval_property_key_expr(expr),
+            PropertyKey::Private(name) => {
+                Err(self.throw_syntax_error(&format!("unexpected private name '#{name}'")))
+            }
         }
     }
 
+    /// Spreads `value` into the values it iterates.
     pub(crate) fn collect_iterable(
         &mut self,
         value: &JsValue,
     ) -> Result<Vec<JsValue>, RuntimeError> {
+        let mut cursor = self.open_iteration(value)?;
+        let mut values = Vec::new();
+        while let Some(value) = self.step_iteration(&mut cursor)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Calls `next()` on `iterator` until it reports `done`, collecting
+    /// each value.
+    pub(crate) fn drain_iterator(
+        &mut self,
+        iterator: &JsValue,
+    ) -> Result<Vec<JsValue>, RuntimeError> {
+        let mut cursor =
+            IterationCursor::Protocol(Box::new(self.iterator_record(iterator.clone())?));
+        let mut values = Vec::new();
+        while let Some(value) = self.step_iteration(&mut cursor)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// GetIterator: starts iterating `value` for a `for...of` loop,
+    /// spread or `yield*`.
+    pub(crate) fn open_iteration(
+        &mut self,
+        value: &JsValue,
+    ) -> Result<IterationCursor, RuntimeError> {
         match value {
-            JsValue::Array(arr) => Ok(arr.borrow().elements.clone()),
-            JsValue::String(s) => Ok(s
-                .chars()
-                .map(|ch| JsValue::String(ch.to_string()))
-                .collect()),
-            JsValue::Map(map) => {
-                let borrowed = map.borrow();
-                let entries: Vec<Vec<JsValue>> = borrowed
-                    .entries
-                    .iter()
-                    .map(|(k, v)| vec![k.clone(), v.clone()])
-                    .collect::<Vec<_>>();
-                drop(borrowed);
-                let entries: Vec<JsValue> = entries
+            JsValue::Array(arr) => Ok(IterationCursor::Values(self.array_elements(arr)?.into())),
+            JsValue::String(s) => Ok(IterationCursor::Values(
+                s.chars().map(|ch| JsValue::String(ch.into())).collect(),
+            )),
+            JsValue::Map(map) => Ok(IterationCursor::Collection(JsCollectionIterator::new(
+                IteratedCollection::Map(*map),
+                IterationKind::Entries,
+            ))),
+            JsValue::Set(set) => Ok(IterationCursor::Collection(JsCollectionIterator::new(
+                IteratedCollection::Set(*set),
+                IterationKind::Values,
+            ))),
+            JsValue::TypedArray(array) => Ok(IterationCursor::Values(
+                array
+                    .borrow()
+                    .values()
                     .into_iter()
-                    .map(|pair| {
-                        JsValue::Array(
-                            self.heap
-                                .alloc_cell(crate::runtime::value::array::JsArray::new(pair)),
-                        )
-                    })
-                    .collect();
-                Ok(entries)
-            }
-            JsValue::Set(set) => Ok(set.borrow().entries.clone()),
-            JsValue::Object(obj) => {
+                    .map(JsValue::Number)
+                    .collect(),
+            )),
+            JsValue::Object(_) => {
                 let iter_sym = symbol::symbol_iterator();
-                let method = obj.borrow().get_symbol(&iter_sym);
-                let Some(iter_fn) = method else {
+                let iter_fn = self.get_symbol_property(value, &iter_sym)?;
+                if matches!(iter_fn, JsValue::Undefined | JsValue::Null) {
                     return Err(RuntimeError::TypeError {
                         message: "object is not iterable".to_string(),
                     });
-                };
-                let iterator = self.call_function_with_this(&iter_fn, &[], Some(value.clone()))?;
-                let mut results = Vec::new();
-                loop {
-                    let next_fn = get_property_simple(&iterator, "next").ok_or_else(|| {
-                        RuntimeError::TypeError {
-                            message: "iterator has no next method".to_string(),
-                        }
-                    })?;
-                    let result =
-                        self.call_function_with_this(&next_fn, &[], Some(iterator.clone()))?;
-                    let done = get_property_simple(&result, "done")
-                        .map(|v| v.to_boolean())
-                        .unwrap_or(false);
-                    if done {
-                        break;
-                    }
-                    let val = get_property_simple(&result, "value").unwrap_or(JsValue::Undefined);
-                    results.push(val);
                 }
-                Ok(results)
+                let iterator = self.call_function_with_this(&iter_fn, &[], Some(value.clone()))?;
+                Ok(IterationCursor::Protocol(Box::new(
+                    self.iterator_record(iterator)?,
+                )))
             }
             _ => Err(RuntimeError::TypeError {
                 message: format!("{value} is not iterable"),
@@ -105,8 +134,80 @@ impl Interpreter {
         }
     }
 
-    /// ToPrimitive: convert an object to a primitive value by calling
-    /// valueOf() and toString() methods.
+    fn iterator_record(&mut self, iterator: JsValue) -> Result<IteratorRecord, RuntimeError> {
+        let next = self.get_property(&iterator, "next")?;
+        if matches!(next, JsValue::Undefined | JsValue::Null) {
+            return Err(RuntimeError::TypeError {
+                message: "iterator has no next method".to_string(),
+            });
+        }
+        Ok(IteratorRecord { iterator, next })
+    }
+
+    /// The next value of `cursor`, or `None` once it is exhausted.
+    pub(crate) fn step_iteration(
+        &mut self,
+        cursor: &mut IterationCursor,
+    ) -> Result<Option<JsValue>, RuntimeError> {
+        match cursor {
+            IterationCursor::Values(values) => Ok(values.pop_front()),
+            IterationCursor::Collection(iter) => Ok(self.next_collection_item(iter)),
+            IterationCursor::Protocol(record) => {
+                let result =
+                    self.call_function_with_this(&record.next, &[], Some(record.iterator.clone()))?;
+                let done = get_property_simple(&result, "done")
+                    .map(|v| v.to_boolean())
+                    .unwrap_or(false);
+                if done {
+                    return Ok(None);
+                }
+                Ok(Some(
+                    get_property_simple(&result, "value").unwrap_or(JsValue::Undefined),
+                ))
+            }
+        }
+    }
+
+    /// IteratorClose: calls `return()` on an iterator that a loop leaves
+    /// before it is exhausted.
+    pub(crate) fn close_iteration(&mut self, cursor: &IterationCursor) -> Result<(), RuntimeError> {
+        let IterationCursor::Protocol(record) = cursor else {
+            return Ok(());
+        };
+        let return_fn = self.get_property(&record.iterator, "return")?;
+        if !matches!(return_fn, JsValue::Undefined | JsValue::Null) {
+            self.call_function_with_this(&return_fn, &[], Some(record.iterator.clone()))?;
+        }
+        Ok(())
+    }
+
+    /// Closes `cursor` when `flow`, the outcome of one pass through a
+    /// `for...of` body, leaves the loop. A throw keeps its own error over
+    /// any the iterator's `return()` raises.
+    pub(crate) fn close_iteration_on_exit(
+        &mut self,
+        cursor: &IterationCursor,
+        flow: Result<ControlFlow, RuntimeError>,
+    ) -> Result<ControlFlow, RuntimeError> {
+        match &flow {
+            Ok(ControlFlow::None | ControlFlow::Continue(None)) => {}
+            Ok(_) => self.close_iteration(cursor)?,
+            Err(
+                RuntimeError::GeneratorSuspend
+                | RuntimeError::StepLimitExceeded
+                | RuntimeError::MicrotaskLimitExceeded { .. }
+                | RuntimeError::Interrupted,
+            ) => {}
+            Err(_) => {
+                let _ = self.close_iteration(cursor);
+            }
+        }
+        flow
+    }
+
+    /// ToPrimitive: convert an object to a primitive value by calling its
+    /// `[Symbol.toPrimitive]` with the hint, or else valueOf() and
+    /// toString().
     /// `preferred_type`: "number" calls valueOf first, "string" calls toString first.
     /// "default" behaves like "number".
     pub(crate) fn to_primitive(
@@ -123,6 +224,21 @@ impl Interpreter {
             | JsValue::String(_)
             | JsValue::Symbol(_) => Ok(val.clone()),
             JsValue::Object(_) => {
+                let exotic = self.get_symbol_property(val, &symbol::symbol_to_primitive())?;
+                if matches!(
+                    exotic,
+                    JsValue::Function { .. } | JsValue::NativeFunction { .. }
+                ) {
+                    let hint = JsValue::String(preferred_type.into());
+                    let result = self.call_function_with_this(&exotic, &[hint], Some(val.clone()))?;
+                    if matches!(result, JsValue::Object(_) | JsValue::Array(_)) {
+                        return Err(
+                            self.throw_type_error("Cannot convert object to primitive value")
+                        );
+                    }
+                    return Ok(result);
+                }
+
                 // Check [[PrimitiveValue]] first (wrapper objects)
                 if let Some(prim) = val.get_primitive_value() {
                     return Ok(prim);
@@ -159,20 +275,109 @@ impl Interpreter {
 
                 // No valueOf/toString methods found (no prototype chain) - use defaults
                 // Default: valueOf() returns `this` (not primitive), then toString() returns "[object Object]"
-                Ok(JsValue::String("[object Object]".to_string()))
+                Ok(JsValue::String("[object Object]".into()))
             }
             JsValue::Array(arr) => {
                 // Arrays: ToPrimitive calls toString which joins elements
-                let borrowed = arr.borrow();
-                let items: Vec<String> =
-                    borrowed.elements.iter().map(|v| v.to_js_string()).collect();
-                Ok(JsValue::String(items.join(",")))
+                Ok(JsValue::String(self.join_array(arr, ",")?.into()))
             }
             // For other types, just return as-is (they'll be coerced by to_number/to_js_string)
             _ => Ok(val.clone()),
         }
     }
 
+    /// ToString, calling `toString()` or `valueOf()` on objects the way
+    /// `String(value)` does. Symbols cannot be converted implicitly.
+    pub(crate) fn coerce_to_string(&mut self, val: &JsValue) -> Result<String, RuntimeError> {
+        match self.to_primitive(val, "string")? {
+            JsValue::Symbol(_) => {
+                Err(self.throw_type_error("Cannot convert a Symbol value to a string"))
+            }
+            primitive => Ok(primitive.to_js_string()),
+        }
+    }
+
+    /// ToNumber, calling `valueOf()` or `toString()` on objects.
+    pub(crate) fn coerce_to_number(&mut self, val: &JsValue) -> Result<f64, RuntimeError> {
+        match self.to_primitive(val, "number")? {
+            JsValue::Symbol(_) => {
+                Err(self.throw_type_error("Cannot convert a Symbol value to a number"))
+            }
+            primitive => Ok(primitive.to_number()),
+        }
+    }
+
+    /// `Array.prototype.join`: `null` and `undefined` elements are empty,
+    /// anything else goes through ToString. An array already being joined
+    /// further up, as in a cyclic array, is empty too.
+    pub(crate) fn join_array(
+        &mut self,
+        arr: &Gc<GcCell<JsArray>>,
+        separator: &str,
+    ) -> Result<String, RuntimeError> {
+        let ptr = Gc::as_usize(*arr);
+        if !self.joining.insert(ptr) {
+            return Ok(String::new());
+        }
+        let elements = match self.array_elements(arr) {
+            Ok(elements) => elements,
+            Err(err) => {
+                self.joining.remove(&ptr);
+                return Err(err);
+            }
+        };
+        let mut out = String::new();
+        let mut result = Ok(());
+        for (index, element) in elements.iter().enumerate() {
+            if index > 0 {
+                out.push_str(separator);
+            }
+            if !matches!(element, JsValue::Undefined | JsValue::Null) {
+                match self.coerce_to_string(element) {
+                    Ok(s) => out.push_str(&s),
+                    Err(err) => {
+                        result = Err(err);
+                        break;
+                    }
+                }
+            }
+        }
+        self.joining.remove(&ptr);
+        result.map(|()| out)
+    }
+
+    /// `==`. An object compared with a primitive is converted with the
+    /// default hint first, which runs its `valueOf()` or `toString()`.
+    pub(crate) fn loose_equals(
+        &mut self,
+        lhs: &JsValue,
+        rhs: &JsValue,
+    ) -> Result<bool, RuntimeError> {
+        let primitive = |value: &JsValue| !is_object_like(value);
+        match (lhs, rhs) {
+            (JsValue::Boolean(_), _) => self.loose_equals(&JsValue::Number(lhs.to_number()), rhs),
+            (_, JsValue::Boolean(_)) => self.loose_equals(lhs, &JsValue::Number(rhs.to_number())),
+            _ if primitive(lhs) == primitive(rhs) => Ok(abstract_equals(lhs, rhs)),
+            (JsValue::Undefined | JsValue::Null, _) | (_, JsValue::Undefined | JsValue::Null) => {
+                Ok(false)
+            }
+            _ if primitive(lhs) => {
+                let converted = self.to_primitive(rhs, "default")?;
+                if is_object_like(&converted) {
+                    return Ok(false);
+                }
+                self.loose_equals(lhs, &converted)
+            }
+            _ => {
+                let converted = self.to_primitive(lhs, "default")?;
+                if is_object_like(&converted) {
+                    return Ok(false);
+                }
+                self.loose_equals(&converted, rhs)
+            }
+        }
+    }
+
     pub(crate) fn eval_binary(
         &mut self,
         lhs: JsValue,
@@ -184,40 +389,20 @@ impl Interpreter {
             let lhs_prim = self.to_primitive(&lhs, "default")?;
             let rhs_prim = self.to_primitive(&rhs, "default")?;
 
-            // If either side is a string after ToPrimitive, do string concatenation
-            if matches!(&lhs_prim, JsValue::String(_)) || matches!(&rhs_prim, JsValue::String(_)) {
-                let a = lhs_prim.to_js_string();
-                let b = rhs_prim.to_js_string();
-                return Ok(JsValue::String(format!("{a}{b}")));
-            }
-
-            let ln = lhs_prim.to_number();
-            let rn = rhs_prim.to_number();
-            return Ok(JsValue::Number(ln + rn));
+            return Ok(add_primitives(&lhs_prim, &rhs_prim));
+        }
+        match op {
+            BinOp::EqEqEq => return Ok(JsValue::Boolean(lhs == rhs)),
+            BinOp::NotEqEq => return Ok(JsValue::Boolean(lhs != rhs)),
+            BinOp::EqEq => return Ok(JsValue::Boolean(self.loose_equals(&lhs, &rhs)?)),
+            BinOp::NotEq => return Ok(JsValue::Boolean(!self.loose_equals(&lhs, &rhs)?)),
+            _ => {}
         }
 
         // For comparison/arithmetic, apply ToPrimitive with "number" hint
         let lhs_prim = self.to_primitive(&lhs, "number")?;
         let rhs_prim = self.to_primitive(&rhs, "number")?;
 
-        // Comparison operators need special handling for string comparison
-        if matches!(
-            op,
-            BinOp::Less | BinOp::LessEq | BinOp::Greater | BinOp::GreaterEq
-        ) {
-            if matches!(&lhs_prim, JsValue::String(_)) && matches!(&rhs_prim, JsValue::String(_)) {
-                let ls = lhs_prim.to_js_string();
-                let rs = rhs_prim.to_js_string();
-                return match op {
-                    BinOp::Less => Ok(JsValue::Boolean(ls < rs)),
-                    BinOp::LessEq => Ok(JsValue::Boolean(ls <= rs)),
-                    BinOp::Greater => Ok(JsValue::Boolean(ls > rs)),
-                    BinOp::GreaterEq => Ok(JsValue::Boolean(ls >= rs)),
-                    _ => unreachable!(),
-                };
-            }
-        }
-
         let ln = lhs_prim.to_number();
         let rn = rhs_prim.to_number();
 
@@ -227,20 +412,145 @@ impl Interpreter {
             BinOp::Mul => Ok(JsValue::Number(ln * rn)),
             BinOp::Div => Ok(JsValue::Number(ln / rn)),
             BinOp::Mod => Ok(JsValue::Number(ln % rn)),
-            BinOp::Less => Ok(JsValue::Boolean(ln < rn)),
-            BinOp::LessEq => Ok(JsValue::Boolean(ln <= rn)),
-            BinOp::Greater => Ok(JsValue::Boolean(ln > rn)),
-            BinOp::GreaterEq => Ok(JsValue::Boolean(ln >= rn)),
+            BinOp::Exp => Ok(JsValue::Number(js_pow(ln, rn))),
+            BinOp::Less => Ok(JsValue::Boolean(
+                primitive_less_than(&lhs_prim, &rhs_prim) == Some(true),
+            )),
+            BinOp::LessEq => Ok(JsValue::Boolean(
+                primitive_less_than(&rhs_prim, &lhs_prim) == Some(false),
+            )),
+            BinOp::Greater => Ok(JsValue::Boolean(
+                primitive_less_than(&rhs_prim, &lhs_prim) == Some(true),
+            )),
+            BinOp::GreaterEq => Ok(JsValue::Boolean(
+                primitive_less_than(&lhs_prim, &rhs_prim) == Some(false),
+            )),
             BinOp::BitAnd => Ok(JsValue::Number((to_int32(ln) & to_int32(rn)) as f64)),
-            BinOp::EqEqEq => Ok(JsValue::Boolean(lhs == rhs)),
-            BinOp::NotEqEq => Ok(JsValue::Boolean(lhs != rhs)),
-            BinOp::EqEq => Ok(JsValue::Boolean(abstract_equals(&lhs, &rhs))),
-            BinOp::NotEq => Ok(JsValue::Boolean(!abstract_equals(&lhs, &rhs))),
+            BinOp::BitOr => Ok(JsValue::Number((to_int32(ln) | to_int32(rn)) as f64)),
+            BinOp::BitXor => Ok(JsValue::Number((to_int32(ln) ^ to_int32(rn)) as f64)),
+            BinOp::Shl => Ok(JsValue::Number((to_int32(ln) << shift_count(rn)) as f64)),
+            BinOp::Shr => Ok(JsValue::Number((to_int32(ln) >> shift_count(rn)) as f64)),
+            BinOp::UShr => Ok(JsValue::Number((to_uint32(ln) >> shift_count(rn)) as f64)),
+            BinOp::EqEqEq | BinOp::NotEqEq | BinOp::EqEq | BinOp::NotEq => {
+                unreachable!("equality handled above")
+            }
             BinOp::Instanceof => unreachable!("instanceof handled before eval_binary"),
             BinOp::In => unreachable!("in handled before eval_binary"),
         }
     }
 
+    /// Reads `name`, through the slot the resolver found for it if any.
+    pub(crate) fn read_name(
+        &self,
+        name: &str,
+        slot: Option<Slot>,
+    ) -> Result<JsValue, RuntimeError> {
+        match slot {
+            Some(slot) => self.env.get_slot(slot, name),
+            None => self.env.get(name),
+        }
+    }
+
+    /// Assigns to the existing binding of `name`, through its slot if any.
+    pub(crate) fn write_name(
+        &mut self,
+        name: &str,
+        slot: Option<Slot>,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        match slot {
+            Some(slot) => self.env.set_slot(slot, name, value),
+            None => self.env.set(name, value),
+        }
+    }
+
+    /// `name = value`. Sloppy mode code assigning to a name no scope
+    /// binds declares a global instead, and one assigning to a function
+    /// expression's own name does nothing.
+    pub(crate) fn assign_name(
+        &mut self,
+        name: &str,
+        slot: Option<Slot>,
+        strict: bool,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        match self.write_name(name, slot, value.clone()) {
+            Err(RuntimeError::UndefinedVariable { .. }) if !strict => {
+                self.env.set_or_declare_global(name, value)
+            }
+            Err(RuntimeError::ConstReassignment { .. })
+                if !strict && self.env.kind_of(name) == Some(BindingKind::FunctionName) =>
+            {
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// `name op= rhs` once both operands are evaluated. Appending a
+    /// primitive to a string first drops the binding's share of the text,
+    /// so a string built up with `+=` grows one buffer in place.
+    pub(crate) fn eval_compound_binding(
+        &mut self,
+        name: &str,
+        slot: Option<Slot>,
+        lhs: JsValue,
+        op: &AssignOp,
+        rhs: JsValue,
+    ) -> Result<JsValue, RuntimeError> {
+        match (op, lhs) {
+            (AssignOp::Add, JsValue::String(mut text))
+                if matches!(
+                    rhs,
+                    JsValue::String(_)
+                        | JsValue::Number(_)
+                        | JsValue::Boolean(_)
+                        | JsValue::Undefined
+                        | JsValue::Null
+                ) =>
+            {
+                self.write_name(name, slot, JsValue::Undefined)?;
+                text.push_str(&rhs.to_js_string());
+                Ok(JsValue::String(text))
+            }
+            (_, lhs) => self.eval_compound(lhs, op, rhs),
+        }
+    }
+
+    /// `obj.prop op= value` and `obj[key] op= value`. The object and key
+    /// are evaluated once; a logical operator that short-circuits skips
+    /// both the right-hand side and the write, so no setter runs.
+    pub(crate) fn eval_member_compound_assign(
+        &mut self,
+        object: &Expr,
+        property: &Expr,
+        op: &AssignOp,
+        value: &Expr,
+        strict: bool,
+    ) -> Result<JsValue, RuntimeError> {
+        if let Expr::PrivateName(name) = property {
+            return self.eval_private_compound_assign(object, name, op, value);
+        }
+        let obj_val = self.eval_expr(object)?;
+        let key_val = self.eval_expr(property)?;
+        if matches!(obj_val, JsValue::Undefined | JsValue::Null) {
+            let key = key_val.to_js_string();
+            return Err(nullish_read_error(&obj_val, &key, Some(object)));
+        }
+        let key = self.property_key(&key_val)?;
+        let current = self.get_keyed(&obj_val, &key)?;
+        let next = match op.logical() {
+            Some(logical) if !logical_assigns(&logical, &current) => return Ok(current),
+            Some(_) => self.eval_expr(value)?,
+            None => {
+                let rhs = self.eval_expr(value)?;
+                self.eval_compound(current, op, rhs)?
+            }
+        };
+        self.assign_keyed(&obj_val, &key, next.clone(), strict)?;
+        Ok(next)
+    }
+
     pub(crate) fn eval_compound(
         &mut self,
         lhs: JsValue,
@@ -253,7 +563,27 @@ impl Interpreter {
             AssignOp::Mul => BinOp::Mul,
             AssignOp::Div => BinOp::Div,
             AssignOp::Mod => BinOp::Mod,
+            AssignOp::Exp => BinOp::Exp,
+            AssignOp::BitAnd => BinOp::BitAnd,
+            AssignOp::BitOr => BinOp::BitOr,
+            AssignOp::BitXor => BinOp::BitXor,
+            AssignOp::Shl => BinOp::Shl,
+            AssignOp::Shr => BinOp::Shr,
+            AssignOp::UShr => BinOp::UShr,
+            AssignOp::And | AssignOp::Or | AssignOp::Nullish => {
+                unreachable!("logical assignment handled before eval_compound")
+            }
         };
         self.eval_binary(lhs, &bin, rhs)
     }
 }
+
+/// Whether `lhs op= rhs` goes on to evaluate `rhs` and assign, which is
+/// exactly when `lhs op rhs` would evaluate `rhs`.
+pub(crate) fn logical_assigns(op: &LogicalOp, current: &JsValue) -> bool {
+    match op {
+        LogicalOp::And => current.to_boolean(),
+        LogicalOp::Or => !current.to_boolean(),
+        LogicalOp::Nullish => matches!(current, JsValue::Null | JsValue::Undefined),
+    }
+}
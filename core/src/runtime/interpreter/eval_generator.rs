@@ -0,0 +1,932 @@
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use super::eval_stmt::loop_body;
+use super::hoisting::declares_let;
+use super::{ControlFlow, Interpreter};
+use crate::errors::RuntimeError;
+use crate::parser::ast::{Expr, Param, Pattern, Stmt, SwitchCase, VarDeclKind};
+use crate::runtime::gc::{Gc, GcCell, Trace, Tracer};
+use crate::runtime::value::JsValue;
+use crate::runtime::value::generator::{
+    AsyncGeneratorRequest, GeneratorResume, GeneratorState, JsGenerator, LoopPosition,
+    PendingCompletion, ResumeFrame, TryPosition,
+};
+use crate::runtime::value::iterator::{IterationCursor, get_property_simple, iter_result};
+use crate::runtime::value::promise::{JsPromise, PromiseState};
+
+/// Bookkeeping for a generator body currently running on the Rust stack.
+///
+/// A `yield` unwinds the body with `RuntimeError::GeneratorSuspend`, and
+/// every statement it passes through records where it was. Resuming walks
+/// back down that path; the expression holding the paused `yield` is
+/// evaluated again with its earlier yields and awaits answered from
+/// `replay` and its other operands from `operands`, so none of them runs
+/// twice.
+pub(crate) struct GeneratorActivation {
+    call_depth: usize,
+    is_async: bool,
+    /// Frames still to descend through, outermost last.
+    resume: Vec<ResumeFrame>,
+    /// Frames recorded while unwinding, innermost first.
+    suspended: Vec<ResumeFrame>,
+    replay: VecDeque<GeneratorResume>,
+    history: Vec<JsValue>,
+    operands: VecDeque<JsValue>,
+    evaluated: Vec<JsValue>,
+    /// Set while an operand is evaluated, so its own subexpressions are
+    /// not recorded as well.
+    in_operand: bool,
+    /// Whether an `await` on a promise still pending pauses the body, as
+    /// it does in an async function.
+    suspends_on_await: bool,
+    yielded: JsValue,
+    delegate: Option<IterationCursor>,
+}
+
+impl Trace for GeneratorActivation {
+    fn trace(&self, tracer: &mut Tracer) {
+        for frame in self.resume.iter().chain(&self.suspended) {
+            frame.trace(tracer);
+        }
+        for resume in &self.replay {
+            resume.trace(tracer);
+        }
+        for value in self
+            .history
+            .iter()
+            .chain(&self.operands)
+            .chain(&self.evaluated)
+        {
+            value.trace(tracer);
+        }
+        self.yielded.trace(tracer);
+        self.delegate.trace(tracer);
+    }
+}
+
+/// How a `yield*` answers a resumption of its generator.
+enum Delegation {
+    /// The inner iterator produced another value to yield.
+    Yield(JsValue),
+    /// The delegation is over, and the `yield*` completes as a `yield`
+    /// resumed this way would.
+    Complete(GeneratorResume),
+}
+
+impl Interpreter {
+    /// Runs `generator` from its start or paused `yield` until it yields
+    /// again or finishes, returning the iterator result's value and `done`.
+    pub(crate) fn resume_generator(
+        &mut self,
+        generator: &Gc<GcCell<JsGenerator>>,
+        mut resume: GeneratorResume,
+    ) -> Result<(JsValue, bool), RuntimeError> {
+        let (state, is_async, async_function) = {
+            let g = generator.borrow();
+            (g.state.clone(), g.is_async, g.async_function.is_some())
+        };
+        match state {
+            GeneratorState::Executing => {
+                return Err(RuntimeError::TypeError {
+                    message: "Generator is already running".to_string(),
+                });
+            }
+            GeneratorState::Completed => {
+                return match resume {
+                    GeneratorResume::Next(_) => Ok((JsValue::Undefined, true)),
+                    GeneratorResume::Return(value) => Ok((value, true)),
+                    GeneratorResume::Throw(value) => Err(RuntimeError::thrown(value)),
+                };
+            }
+            GeneratorState::SuspendedStart => match resume {
+                GeneratorResume::Next(_) => {}
+                GeneratorResume::Return(value) => {
+                    generator.borrow_mut().complete();
+                    return Ok((value, true));
+                }
+                GeneratorResume::Throw(value) => {
+                    generator.borrow_mut().complete();
+                    return Err(RuntimeError::thrown(value));
+                }
+            },
+            GeneratorState::SuspendedYield => {
+                let delegate = generator.borrow_mut().delegate.take();
+                if let Some(mut cursor) = delegate {
+                    match self.resume_delegate(&mut cursor, resume) {
+                        Ok(Delegation::Yield(value)) => {
+                            generator.borrow_mut().delegate = Some(cursor);
+                            let value = if is_async {
+                                self.await_value(value)?
+                            } else {
+                                value
+                            };
+                            return Ok((value, false));
+                        }
+                        Ok(Delegation::Complete(completion)) => resume = completion,
+                        Err(err) => match self.catchable_error(err) {
+                            RuntimeError::Thrown { value } => {
+                                resume = GeneratorResume::Throw(*value);
+                            }
+                            err => return Err(err),
+                        },
+                    }
+                }
+            }
+        }
+
+        let started = state == GeneratorState::SuspendedYield;
+        let (params, body, scopes, frames, replay, operands, this_binding, args) = {
+            let mut g = generator.borrow_mut();
+            g.state = GeneratorState::Executing;
+            let scopes = if started {
+                std::mem::take(&mut g.scopes)
+            } else {
+                g.captured_env.clone()
+            };
+            (
+                Rc::clone(&g.params),
+                Rc::clone(&g.body),
+                scopes,
+                std::mem::take(&mut g.frames),
+                std::mem::take(&mut g.replay),
+                std::mem::take(&mut g.operands),
+                g.this_binding.clone(),
+                g.args.clone(),
+            )
+        };
+
+        let mut replay: VecDeque<GeneratorResume> =
+            replay.into_iter().map(GeneratorResume::Next).collect();
+        replay.push_back(resume);
+        self.generator_stack.push(GeneratorActivation {
+            call_depth: self.call_depth,
+            is_async,
+            resume: frames,
+            suspended: Vec::new(),
+            replay,
+            history: Vec::new(),
+            operands: operands.into(),
+            evaluated: Vec::new(),
+            in_operand: false,
+            suspends_on_await: async_function,
+            yielded: JsValue::Undefined,
+            delegate: None,
+        });
+        if is_async || async_function {
+            self.async_depth += 1;
+        }
+        let saved_scopes = self.env.replace_scopes(scopes);
+        let result = if started {
+            self.gen_statements(&body, true)
+        } else {
+            self.start_generator_body(&params, &body, this_binding, &args)
+        };
+        let live_scopes = self.env.replace_scopes(saved_scopes);
+        if is_async || async_function {
+            self.async_depth = self.async_depth.saturating_sub(1);
+        }
+        let activation = self
+            .generator_stack
+            .pop()
+            .expect("generator activation pushed above");
+
+        let value = match result {
+            Err(RuntimeError::GeneratorSuspend) => {
+                let mut g = generator.borrow_mut();
+                g.state = GeneratorState::SuspendedYield;
+                g.scopes = live_scopes;
+                g.frames = activation.suspended;
+                g.replay = activation.history;
+                g.operands = activation.evaluated;
+                g.delegate = activation.delegate;
+                return Ok((activation.yielded, false));
+            }
+            Ok(ControlFlow::Return(value)) => value,
+            Err(RuntimeError::GeneratorReturn { value }) => *value,
+            Ok(ControlFlow::None) => JsValue::Undefined,
+            Ok(ControlFlow::Break(_)) | Ok(ControlFlow::Continue(_)) => {
+                generator.borrow_mut().complete();
+                return Err(RuntimeError::TypeError {
+                    message: "illegal break statement".to_string(),
+                });
+            }
+            Err(err) => {
+                generator.borrow_mut().complete();
+                return Err(err);
+            }
+        };
+        generator.borrow_mut().complete();
+        let value = if is_async {
+            self.await_value(value)?
+        } else {
+            value
+        };
+        Ok((value, true))
+    }
+
+    /// `next`/`return`/`throw` on an async generator. Calls made while the
+    /// body is running are queued and settled in order once it pauses.
+    pub(crate) fn enqueue_async_generator_request(
+        &mut self,
+        generator: &Gc<GcCell<JsGenerator>>,
+        resume: GeneratorResume,
+    ) -> Result<JsValue, RuntimeError> {
+        let promise = self.heap.alloc_cell(JsPromise::pending());
+        let running = {
+            let mut g = generator.borrow_mut();
+            g.queue.push_back(AsyncGeneratorRequest { resume, promise });
+            g.state == GeneratorState::Executing
+        };
+        if !running {
+            self.drain_async_generator_queue(generator)?;
+        }
+        Ok(JsValue::Promise(promise))
+    }
+
+    fn drain_async_generator_queue(
+        &mut self,
+        generator: &Gc<GcCell<JsGenerator>>,
+    ) -> Result<(), RuntimeError> {
+        loop {
+            let Some(request) = generator.borrow_mut().queue.pop_front() else {
+                return Ok(());
+            };
+            match self.resume_generator(generator, request.resume) {
+                Ok((value, done)) => {
+                    let result = iter_result(value, done, &mut self.heap);
+                    self.settle_promise(&request.promise, false, result)?;
+                }
+                Err(err) => {
+                    let reason = self.error_to_value(err);
+                    self.settle_promise(&request.promise, true, reason)?;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn eval_yield(
+        &mut self,
+        value: Option<&Expr>,
+        delegate: bool,
+    ) -> Result<JsValue, RuntimeError> {
+        let call_depth = self.call_depth;
+        if self
+            .generator_stack
+            .last()
+            .is_none_or(|activation| activation.call_depth != call_depth)
+        {
+            return Err(RuntimeError::TypeError {
+                message: "yield is only valid inside generator functions".to_string(),
+            });
+        }
+
+        // The operand comes first, so a yield inside it is answered first.
+        let operand = match value {
+            Some(expr) => self.eval_expr(expr)?,
+            None => JsValue::Undefined,
+        };
+        if let Some(resume) = self.current_generator().replay.pop_front() {
+            return self.complete_yield(resume);
+        }
+        let yielded = if delegate {
+            let mut cursor = self.open_iteration(&operand)?;
+            match self.resume_delegate(&mut cursor, GeneratorResume::Next(JsValue::Undefined))? {
+                Delegation::Yield(value) => {
+                    self.current_generator().delegate = Some(cursor);
+                    value
+                }
+                Delegation::Complete(resume) => return self.complete_yield(resume),
+            }
+        } else {
+            operand
+        };
+        let yielded = if self.current_generator().is_async {
+            self.await_value(yielded)?
+        } else {
+            yielded
+        };
+        self.current_generator().yielded = yielded;
+        Err(RuntimeError::GeneratorSuspend)
+    }
+
+    /// What a `yield` evaluates to once its generator is resumed.
+    fn complete_yield(&mut self, resume: GeneratorResume) -> Result<JsValue, RuntimeError> {
+        match resume {
+            GeneratorResume::Next(sent) => {
+                self.current_generator().history.push(sent.clone());
+                Ok(sent)
+            }
+            GeneratorResume::Return(value) => Err(RuntimeError::GeneratorReturn {
+                value: Box::new(value),
+            }),
+            GeneratorResume::Throw(value) => Err(RuntimeError::thrown(value)),
+        }
+    }
+
+    /// Passes a resumption of a generator paused in `yield*` on to the
+    /// iterator it delegates to. Arrays, strings and collections have no
+    /// `return` or `throw` to forward, so those end the delegation as is.
+    fn resume_delegate(
+        &mut self,
+        cursor: &mut IterationCursor,
+        resume: GeneratorResume,
+    ) -> Result<Delegation, RuntimeError> {
+        let IterationCursor::Protocol(record) = cursor else {
+            return Ok(match resume {
+                GeneratorResume::Next(_) => match self.step_iteration(cursor)? {
+                    Some(value) => Delegation::Yield(value),
+                    None => Delegation::Complete(GeneratorResume::Next(JsValue::Undefined)),
+                },
+                resume => Delegation::Complete(resume),
+            });
+        };
+        let record = (**record).clone();
+        let (method, arg) = match &resume {
+            GeneratorResume::Next(sent) => (record.next.clone(), sent.clone()),
+            GeneratorResume::Return(value) => (
+                self.get_property(&record.iterator, "return")?,
+                value.clone(),
+            ),
+            GeneratorResume::Throw(value) => {
+                (self.get_property(&record.iterator, "throw")?, value.clone())
+            }
+        };
+        if matches!(method, JsValue::Undefined | JsValue::Null) {
+            if let GeneratorResume::Throw(_) = resume {
+                self.close_iteration(cursor)?;
+                return Err(RuntimeError::TypeError {
+                    message: "The iterator does not provide a 'throw' method".to_string(),
+                });
+            }
+            return Ok(Delegation::Complete(resume));
+        }
+        let result = self.call_function_with_this(&method, &[arg], Some(record.iterator))?;
+        let done = get_property_simple(&result, "done").is_some_and(|v| v.to_boolean());
+        let value = get_property_simple(&result, "value").unwrap_or(JsValue::Undefined);
+        Ok(match resume {
+            _ if !done => Delegation::Yield(value),
+            GeneratorResume::Return(_) => Delegation::Complete(GeneratorResume::Return(value)),
+            _ => Delegation::Complete(GeneratorResume::Next(value)),
+        })
+    }
+
+    /// Awaits `value` in a generator or async function body, noting the
+    /// result so a resumed body answers the `await` from `replay`. In an
+    /// async function a promise the event loop cannot settle yet pauses
+    /// the body, to be resumed once the promise settles.
+    pub(crate) fn await_in_body(&mut self, value: JsValue) -> Result<JsValue, RuntimeError> {
+        let call_depth = self.call_depth;
+        let Some(activation) = self
+            .generator_stack
+            .last_mut()
+            .filter(|activation| activation.call_depth == call_depth)
+        else {
+            return self.await_value(value);
+        };
+        if let Some(resume) = activation.replay.pop_front() {
+            return self.complete_yield(resume);
+        }
+        if activation.suspends_on_await
+            && let JsValue::Promise(promise) = &value
+        {
+            self.run_event_loop_until_promise_settled(promise)?;
+            let pending = matches!(promise.borrow().state, PromiseState::Pending);
+            if pending {
+                self.current_generator().yielded = value;
+                return Err(RuntimeError::GeneratorSuspend);
+            }
+        }
+        let settled = self.await_value(value)?;
+        self.current_generator().history.push(settled.clone());
+        Ok(settled)
+    }
+
+    /// Evaluates an operand holding no `yield` or `await` of a body
+    /// expression, or hands back what it evaluated to before the body
+    /// paused so that resuming the expression does not run it again.
+    /// `None` when `expr` is not such an operand.
+    pub(crate) fn eval_generator_operand(
+        &mut self,
+        expr: &Expr,
+    ) -> Option<Result<JsValue, RuntimeError>> {
+        let call_depth = self.call_depth;
+        let activation = self
+            .generator_stack
+            .last_mut()
+            .filter(|activation| activation.call_depth == call_depth && !activation.in_operand)?;
+        if expr.can_suspend() {
+            return None;
+        }
+        if let Some(value) = activation.operands.pop_front() {
+            activation.evaluated.push(value.clone());
+            return Some(Ok(value));
+        }
+        activation.in_operand = true;
+        let result = self.eval_expr(expr);
+        let activation = self.current_generator();
+        activation.in_operand = false;
+        if let Ok(value) = &result {
+            activation.evaluated.push(value.clone());
+        }
+        Some(result)
+    }
+
+    fn current_generator(&mut self) -> &mut GeneratorActivation {
+        self.generator_stack
+            .last_mut()
+            .expect("yield evaluated outside a generator body")
+    }
+
+    fn start_generator_body(
+        &mut self,
+        params: &[Param],
+        body: &[Stmt],
+        this_binding: Option<JsValue>,
+        args: &[JsValue],
+    ) -> Result<ControlFlow, RuntimeError> {
+        self.env.push_scope_with_this(&mut self.heap, this_binding);
+        self.bind_parameters(params, args)?;
+        self.hoist_declarations(body);
+        self.gen_statements(body, false)
+    }
+
+    fn take_resume_frame(&mut self, resuming: bool) -> Option<ResumeFrame> {
+        if !resuming {
+            return None;
+        }
+        self.generator_stack.last_mut()?.resume.pop()
+    }
+
+    /// Notes `frame` as the way back to a `yield` that is unwinding
+    /// through `result`.
+    fn record<T>(
+        &mut self,
+        result: Result<T, RuntimeError>,
+        frame: impl FnOnce() -> ResumeFrame,
+    ) -> Result<T, RuntimeError> {
+        if matches!(result, Err(RuntimeError::GeneratorSuspend))
+            && let Some(activation) = self.generator_stack.last_mut()
+        {
+            activation.suspended.push(frame());
+        }
+        result
+    }
+
+    /// Evaluates an expression of the generator body. A fresh evaluation
+    /// forgets the yields of the previous one; a resumed one replays them.
+    fn gen_expr(&mut self, expr: &Expr, resuming: bool) -> Result<JsValue, RuntimeError> {
+        self.begin_yield_expression(resuming);
+        self.eval_expr(expr)
+    }
+
+    fn begin_yield_expression(&mut self, resuming: bool) {
+        if !resuming && let Some(activation) = self.generator_stack.last_mut() {
+            activation.replay.clear();
+            activation.history.clear();
+            activation.operands.clear();
+            activation.evaluated.clear();
+        }
+    }
+
+    fn gen_statements(
+        &mut self,
+        stmts: &[Stmt],
+        resuming: bool,
+    ) -> Result<ControlFlow, RuntimeError> {
+        let start = match self.take_resume_frame(resuming) {
+            Some(ResumeFrame::Statement(idx)) => Some(idx),
+            _ => None,
+        };
+        for (idx, stmt) in stmts.iter().enumerate().skip(start.unwrap_or(0)) {
+            let flow = self.gen_stmt(stmt, start == Some(idx));
+            let flow = self.record(flow, || ResumeFrame::Statement(idx))?;
+            if !matches!(flow, ControlFlow::None) {
+                return Ok(flow);
+            }
+        }
+        Ok(ControlFlow::None)
+    }
+
+    fn gen_scoped_block(
+        &mut self,
+        stmts: &[Stmt],
+        resuming: bool,
+    ) -> Result<ControlFlow, RuntimeError> {
+        if !resuming {
+            self.env.push_scope(&mut self.heap);
+            self.hoist_block_declarations(stmts);
+        }
+        let flow = self.gen_statements(stmts, resuming)?;
+        self.env.pop_scope();
+        Ok(flow)
+    }
+
+    /// The generator counterpart of `eval_stmt`: statements that can hold
+    /// a `yield` are run here so they can be re-entered; the rest are
+    /// handed to `eval_stmt`.
+    fn gen_stmt(&mut self, stmt: &Stmt, resuming: bool) -> Result<ControlFlow, RuntimeError> {
+        match stmt {
+            Stmt::Block(stmts) => {
+                self.check_step_limit()?;
+                self.gen_scoped_block(stmts, resuming)
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.check_step_limit()?;
+                let (taken, resume_branch) = match self.take_resume_frame(resuming) {
+                    Some(ResumeFrame::Branch(Some(taken))) => (taken, true),
+                    frame => {
+                        let value = self.gen_expr(condition, frame.is_some());
+                        let value = self.record(value, || ResumeFrame::Branch(None))?;
+                        (value.to_boolean(), false)
+                    }
+                };
+                let flow = if taken {
+                    self.gen_stmt(then_branch, resume_branch)
+                } else if let Some(branch) = else_branch {
+                    self.gen_stmt(branch, resume_branch)
+                } else {
+                    Ok(ControlFlow::None)
+                };
+                self.record(flow, || ResumeFrame::Branch(Some(taken)))
+            }
+            Stmt::While { condition, body } => {
+                self.check_step_limit()?;
+                let mut position = self.take_loop_position(resuming);
+                loop {
+                    let resume_at = position.take();
+                    if resume_at != Some(LoopPosition::Body) {
+                        let value = self.gen_expr(condition, resume_at.is_some());
+                        let value =
+                            self.record(value, || ResumeFrame::Loop(LoopPosition::Condition))?;
+                        if !value.to_boolean() {
+                            break;
+                        }
+                    }
+                    let flow = self.gen_stmt(body, resume_at == Some(LoopPosition::Body));
+                    loop_body!(self.record(flow, || ResumeFrame::Loop(LoopPosition::Body))?);
+                }
+                Ok(ControlFlow::None)
+            }
+            Stmt::DoWhile { body, condition } => {
+                self.check_step_limit()?;
+                let mut position = self.take_loop_position(resuming);
+                loop {
+                    let resume_at = position.take();
+                    if resume_at != Some(LoopPosition::Condition) {
+                        let flow = self.gen_stmt(body, resume_at == Some(LoopPosition::Body));
+                        loop_body!(self.record(flow, || ResumeFrame::Loop(LoopPosition::Body))?);
+                    }
+                    let value =
+                        self.gen_expr(condition, resume_at == Some(LoopPosition::Condition));
+                    let value =
+                        self.record(value, || ResumeFrame::Loop(LoopPosition::Condition))?;
+                    if !value.to_boolean() {
+                        break;
+                    }
+                }
+                Ok(ControlFlow::None)
+            }
+            Stmt::ForLoop {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.check_step_limit()?;
+                let mut position = self.take_loop_position(resuming);
+                let per_iteration = init.as_deref().is_some_and(declares_let);
+                if matches!(position, None | Some(LoopPosition::Init)) {
+                    let resume_init = position.take().is_some();
+                    if !resume_init {
+                        self.env.push_scope(&mut self.heap);
+                    }
+                    if let Some(init) = init {
+                        if !resume_init {
+                            self.hoist_lexical(std::slice::from_ref(&**init));
+                        }
+                        let flow = self.gen_stmt(init, resume_init);
+                        self.record(flow, || ResumeFrame::Loop(LoopPosition::Init))?;
+                    }
+                    if per_iteration {
+                        self.env.copy_innermost_scope(&mut self.heap);
+                    }
+                }
+                loop {
+                    let resume_at = position.take();
+                    if matches!(resume_at, None | Some(LoopPosition::Condition))
+                        && let Some(condition) = condition
+                    {
+                        let value = self.gen_expr(condition, resume_at.is_some());
+                        let value =
+                            self.record(value, || ResumeFrame::Loop(LoopPosition::Condition))?;
+                        if !value.to_boolean() {
+                            break;
+                        }
+                    }
+                    if resume_at != Some(LoopPosition::Update) {
+                        let flow = self.gen_stmt(body, resume_at == Some(LoopPosition::Body));
+                        loop_body!(
+                            self.record(flow, || ResumeFrame::Loop(LoopPosition::Body))?,
+                            scope: self
+                        );
+                        if per_iteration {
+                            self.env.copy_innermost_scope(&mut self.heap);
+                        }
+                    }
+                    if let Some(update) = update {
+                        let value = self.gen_expr(update, resume_at == Some(LoopPosition::Update));
+                        self.record(value, || ResumeFrame::Loop(LoopPosition::Update))?;
+                    }
+                }
+                self.env.pop_scope();
+                Ok(ControlFlow::None)
+            }
+            Stmt::ForOf {
+                kind,
+                pattern,
+                iterable,
+                body,
+            } => {
+                self.check_step_limit()?;
+                let cursor = match self.take_resume_frame(resuming) {
+                    Some(ResumeFrame::ForEach(cursor)) => {
+                        return self.gen_for_each(kind, pattern, cursor, body, true);
+                    }
+                    frame => {
+                        let value = self.gen_expr(iterable, frame.is_some());
+                        let value =
+                            self.record(value, || ResumeFrame::Loop(LoopPosition::Condition))?;
+                        self.open_iteration(&value)?
+                    }
+                };
+                self.gen_for_each(kind, pattern, cursor, body, false)
+            }
+            Stmt::ForIn {
+                kind,
+                pattern,
+                object,
+                body,
+            } => {
+                self.check_step_limit()?;
+                let source = match self.take_resume_frame(resuming) {
+                    Some(ResumeFrame::ForEach(cursor)) => {
+                        return self.gen_for_each(kind, pattern, cursor, body, true);
+                    }
+                    frame => {
+                        let value = self.gen_expr(object, frame.is_some());
+                        self.record(value, || ResumeFrame::Loop(LoopPosition::Condition))?
+                    }
+                };
+                let keys: VecDeque<JsValue> = match source {
+                    JsValue::Object(obj) => obj
+                        .borrow()
+                        .properties
+                        .keys()
+                        .map(|k| JsValue::String(k.as_str().into()))
+                        .collect(),
+                    JsValue::Array(arr) => (0..arr.borrow().len())
+                        .map(|i| JsValue::String(i.to_string().into()))
+                        .collect(),
+                    JsValue::String(s) => (0..s.chars().count())
+                        .map(|i| JsValue::String(i.to_string().into()))
+                        .collect(),
+                    _ => VecDeque::new(),
+                };
+                self.gen_for_each(kind, pattern, IterationCursor::Values(keys), body, false)
+            }
+            Stmt::Labeled { label, body } => {
+                self.check_step_limit()?;
+                match self.gen_stmt(body, resuming)? {
+                    ControlFlow::Break(Some(ref l)) if l == label => Ok(ControlFlow::None),
+                    other => Ok(other),
+                }
+            }
+            Stmt::TryCatch {
+                try_block,
+                catch_param,
+                catch_block,
+                finally_block,
+            } => {
+                self.check_step_limit()?;
+                self.gen_try(try_block, catch_param, catch_block, finally_block, resuming)
+            }
+            Stmt::Switch {
+                discriminant,
+                cases,
+            } => {
+                self.check_step_limit()?;
+                self.gen_switch(discriminant, cases, resuming)
+            }
+            _ => {
+                self.begin_yield_expression(resuming);
+                self.eval_stmt(stmt)
+            }
+        }
+    }
+
+    fn take_loop_position(&mut self, resuming: bool) -> Option<LoopPosition> {
+        match self.take_resume_frame(resuming) {
+            Some(ResumeFrame::Loop(position)) => Some(position),
+            _ => None,
+        }
+    }
+
+    /// Runs the body of a `for-of` or `for-in` loop for each value
+    /// `cursor` has still to give, each bound to the loop head in a scope
+    /// of its own. A resumed iteration is already inside the scope it
+    /// suspended in.
+    fn gen_for_each(
+        &mut self,
+        kind: &Option<VarDeclKind>,
+        pattern: &Pattern,
+        mut cursor: IterationCursor,
+        body: &Stmt,
+        mut resuming: bool,
+    ) -> Result<ControlFlow, RuntimeError> {
+        loop {
+            let flow = if resuming {
+                resuming = false;
+                self.gen_stmt(body, true)
+            } else {
+                let Some(value) = self.step_iteration(&mut cursor)? else {
+                    break;
+                };
+                self.env.push_scope(&mut self.heap);
+                self.bind_for_head(kind, pattern, value)
+                    .and_then(|()| self.gen_stmt(body, false))
+            };
+            let flow = self.record(flow, || ResumeFrame::ForEach(cursor.clone()));
+            let flow = self.close_iteration_on_exit(&cursor, flow)?;
+            self.env.pop_scope();
+            loop_body!(flow);
+        }
+        Ok(ControlFlow::None)
+    }
+
+    fn gen_try(
+        &mut self,
+        try_block: &[Stmt],
+        catch_param: &Option<Pattern>,
+        catch_block: &Option<Vec<Stmt>>,
+        finally_block: &Option<Vec<Stmt>>,
+        resuming: bool,
+    ) -> Result<ControlFlow, RuntimeError> {
+        let frame = self.take_resume_frame(resuming);
+        let depth = match &frame {
+            Some(ResumeFrame::Try(TryPosition::Try { depth } | TryPosition::Catch { depth })) => {
+                *depth
+            }
+            _ => self.env.depth(),
+        };
+        let pending = match frame {
+            Some(ResumeFrame::Try(TryPosition::Finally(pending))) => {
+                return self.gen_finally(finally_block, pending, true);
+            }
+            Some(ResumeFrame::Try(TryPosition::Catch { .. })) => {
+                let catch_stmts = catch_block.as_deref().unwrap_or_default();
+                let flow = self.gen_catch(catch_param, catch_stmts, None);
+                let flow = self.record(flow, || ResumeFrame::Try(TryPosition::Catch { depth }));
+                self.pending_completion(flow)?
+            }
+            frame => {
+                let flow = self.gen_scoped_block(try_block, frame.is_some());
+                match self.record(flow, || ResumeFrame::Try(TryPosition::Try { depth })) {
+                    Err(RuntimeError::GeneratorSuspend) => {
+                        return Err(RuntimeError::GeneratorSuspend);
+                    }
+                    Err(err) => match (self.catchable_error(err), catch_block) {
+                        (RuntimeError::Thrown { value }, Some(catch_stmts)) => {
+                            self.env.unwind_to(depth);
+                            let flow = self.gen_catch(catch_param, catch_stmts, Some(*value));
+                            let flow = self
+                                .record(flow, || ResumeFrame::Try(TryPosition::Catch { depth }));
+                            self.pending_completion(flow)?
+                        }
+                        (err, _) => self.pending_completion(Err(err))?,
+                    },
+                    flow => self.pending_completion(flow)?,
+                }
+            }
+        };
+        self.env.unwind_to(depth);
+        self.gen_finally(finally_block, pending, false)
+    }
+
+    /// Runs a catch block, binding `thrown` in a fresh scope unless the
+    /// block is being resumed.
+    fn gen_catch(
+        &mut self,
+        catch_param: &Option<Pattern>,
+        catch_stmts: &[Stmt],
+        thrown: Option<JsValue>,
+    ) -> Result<ControlFlow, RuntimeError> {
+        let resuming = thrown.is_none();
+        if let Some(value) = thrown {
+            self.env.push_scope(&mut self.heap);
+            if let Some(pattern) = catch_param {
+                self.eval_pattern_binding(pattern, value)?;
+            }
+        }
+        let flow = self.gen_statements(catch_stmts, resuming)?;
+        self.env.pop_scope();
+        Ok(flow)
+    }
+
+    /// Turns the outcome of a `try` or `catch` block into the completion
+    /// a `finally` block holds back. Only a suspension passes straight
+    /// through.
+    fn pending_completion(
+        &mut self,
+        flow: Result<ControlFlow, RuntimeError>,
+    ) -> Result<PendingCompletion, RuntimeError> {
+        Ok(match flow {
+            Ok(ControlFlow::None) => PendingCompletion::Normal,
+            Ok(ControlFlow::Return(value)) => PendingCompletion::Return(value),
+            Ok(ControlFlow::Break(label)) => PendingCompletion::Break(label),
+            Ok(ControlFlow::Continue(label)) => PendingCompletion::Continue(label),
+            Err(RuntimeError::GeneratorSuspend) => return Err(RuntimeError::GeneratorSuspend),
+            Err(RuntimeError::GeneratorReturn { value }) => PendingCompletion::Return(*value),
+            Err(err) => PendingCompletion::Throw(self.error_to_value(err)),
+        })
+    }
+
+    fn gen_finally(
+        &mut self,
+        finally_block: &Option<Vec<Stmt>>,
+        pending: PendingCompletion,
+        resuming: bool,
+    ) -> Result<ControlFlow, RuntimeError> {
+        if let Some(finally_stmts) = finally_block {
+            let flow = self.gen_scoped_block(finally_stmts, resuming);
+            let flow = self.record(flow, || {
+                ResumeFrame::Try(TryPosition::Finally(pending.clone()))
+            })?;
+            if !matches!(flow, ControlFlow::None) {
+                return Ok(flow);
+            }
+        }
+        match pending {
+            PendingCompletion::Normal => Ok(ControlFlow::None),
+            PendingCompletion::Return(value) => Ok(ControlFlow::Return(value)),
+            PendingCompletion::Break(label) => Ok(ControlFlow::Break(label)),
+            PendingCompletion::Continue(label) => Ok(ControlFlow::Continue(label)),
+            PendingCompletion::Throw(value) => Err(RuntimeError::thrown(value)),
+        }
+    }
+
+    fn gen_switch(
+        &mut self,
+        discriminant: &Expr,
+        cases: &[SwitchCase],
+        resuming: bool,
+    ) -> Result<ControlFlow, RuntimeError> {
+        let (mut idx, mut resume_case) = match self.take_resume_frame(resuming) {
+            Some(ResumeFrame::Switch(Some(case))) => (Some(case), true),
+            frame => {
+                let selected = self.select_switch_case(discriminant, cases, frame.is_some());
+                (self.record(selected, || ResumeFrame::Switch(None))?, false)
+            }
+        };
+        while let Some(i) = idx {
+            let flow = self.gen_statements(&cases[i].body, resume_case);
+            resume_case = false;
+            match self.record(flow, || ResumeFrame::Switch(Some(i)))? {
+                ControlFlow::None => {}
+                ControlFlow::Break(_) => return Ok(ControlFlow::None),
+                ControlFlow::Continue(label) => return Ok(ControlFlow::Continue(label)),
+                ControlFlow::Return(v) => return Ok(ControlFlow::Return(v)),
+            }
+            idx = if i + 1 < cases.len() {
+                Some(i + 1)
+            } else {
+                None
+            };
+        }
+        Ok(ControlFlow::None)
+    }
+
+    fn select_switch_case(
+        &mut self,
+        discriminant: &Expr,
+        cases: &[SwitchCase],
+        resuming: bool,
+    ) -> Result<Option<usize>, RuntimeError> {
+        let value = self.gen_expr(discriminant, resuming)?;
+        let mut default_idx = None;
+        for (idx, case) in cases.iter().enumerate() {
+            let Some(test) = &case.test else {
+                default_idx = Some(idx);
+                continue;
+            };
+            if self.eval_expr(test)? == value {
+                return Ok(Some(idx));
+            }
+        }
+        Ok(default_idx)
+    }
+}
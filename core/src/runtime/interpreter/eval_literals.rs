@@ -0,0 +1,290 @@
+//! Expressions that build values: literals, templates, closures, optional
+//! chains and `typeof`. They live apart from `eval_expr` so that its frame
+//! stays small, as every nested call in a script recurses through it.
+
+use super::Interpreter;
+use super::property_access::PropKey;
+use crate::errors::RuntimeError;
+use crate::parser::ast::{Expr, ObjectProperty, OptionalOp, TemplatePart};
+use crate::runtime::environment::{BindingKind, Scope};
+use crate::runtime::value::JsValue;
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::regexp::{JsRegExp, RegExpFlags};
+
+impl Interpreter {
+    pub(super) fn eval_template_literal(
+        &mut self,
+        parts: &[TemplatePart],
+    ) -> Result<JsValue, RuntimeError> {
+        let mut result = String::new();
+        for part in parts {
+            match part {
+                TemplatePart::Str(s) => result.push_str(s),
+                TemplatePart::Expression(expr) => {
+                    let val = self.eval_expr(expr)?;
+                    result.push_str(&self.coerce_to_string(&val)?);
+                }
+            }
+        }
+        Ok(JsValue::String(result.into()))
+    }
+
+    pub(super) fn eval_object_literal(
+        &mut self,
+        properties: &[ObjectProperty],
+    ) -> Result<JsValue, RuntimeError> {
+        let mut obj = JsObject::new();
+        for property in properties {
+            match property {
+                ObjectProperty::KeyValue(key, val_expr) => {
+                    let key = self.eval_property_key(key)?;
+                    let val = self.eval_named(val_expr, &key.display())?;
+                    match key {
+                        PropKey::String(key) => obj.set(key, val),
+                        PropKey::Symbol(sym) => obj.set_symbol(sym, val),
+                    }
+                }
+                ObjectProperty::Getter(key, body) => {
+                    let key = self.eval_property_key(key)?;
+                    let getter = JsValue::Function {
+                        name: format!("get {}", key.display()),
+                        params: Vec::new().into(),
+                        body: body.clone(),
+                        closure_env: self.env.capture(),
+                        is_async: false,
+                        is_generator: false,
+                        source_path: self.module_stack.last().map(|p| p.display().to_string()),
+                        source_offset: 0,
+                        properties: None,
+                    };
+                    match key {
+                        PropKey::String(key) => obj.set_getter(key, getter),
+                        PropKey::Symbol(sym) => obj.set_symbol_getter(sym, getter),
+                    }
+                }
+                ObjectProperty::Setter(key, param, body) => {
+                    let key = self.eval_property_key(key)?;
+                    let setter = JsValue::Function {
+                        name: format!("set {}", key.display()),
+                        params: vec![crate::parser::ast::Param {
+                            pattern: crate::parser::ast::Pattern::Identifier(param.clone()),
+                            default: None,
+                        }]
+                        .into(),
+                        body: body.clone(),
+                        closure_env: self.env.capture(),
+                        is_async: false,
+                        is_generator: false,
+                        source_path: self.module_stack.last().map(|p| p.display().to_string()),
+                        source_offset: 0,
+                        properties: None,
+                    };
+                    match key {
+                        PropKey::String(key) => obj.set_setter(key, setter),
+                        PropKey::Symbol(sym) => obj.set_symbol_setter(sym, setter),
+                    }
+                }
+                ObjectProperty::Spread(expr) => {
+                    let spread_val = self.eval_expr(expr)?;
+                    match spread_val {
+                        JsValue::Object(source) => {
+                            let borrowed = source.borrow();
+                            for (k, p) in &borrowed.properties {
+                                obj.set(k.clone(), p.value.clone());
+                            }
+                        }
+                        JsValue::Undefined | JsValue::Null => {}
+                        other => {
+                            return Err(RuntimeError::TypeError {
+                                message: format!(
+                                    "cannot spread non-object value {other} in object literal"
+                                ),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        Ok(JsValue::Object(self.heap.alloc_cell(obj)))
+    }
+
+    pub(super) fn eval_array_literal(
+        &mut self,
+        elements: &[Expr],
+    ) -> Result<JsValue, RuntimeError> {
+        let mut vals: Vec<JsValue> = Vec::new();
+        for element in elements {
+            match element {
+                Expr::Spread(inner) => {
+                    let spread_val = self.eval_expr(inner)?;
+                    vals.extend(self.collect_iterable(&spread_val)?);
+                }
+                other => vals.push(self.eval_expr(other)?),
+            }
+        }
+        Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(vals))))
+    }
+
+    pub(super) fn eval_typeof(&mut self, expr: &Expr) -> Result<JsValue, RuntimeError> {
+        let val = match expr {
+            // Only a name nothing declares is `undefined`; one in its
+            // temporal dead zone still throws.
+            Expr::Identifier(name) => match self.env.get(name) {
+                Err(RuntimeError::UndefinedVariable { .. }) => JsValue::Undefined,
+                value => value?,
+            },
+            other => self.eval_expr(other)?,
+        };
+        let t = match val {
+            JsValue::Undefined => "undefined",
+            JsValue::Null => "object",
+            JsValue::Boolean(_) => "boolean",
+            JsValue::Number(_) => "number",
+            JsValue::String(_) => "string",
+            JsValue::Function { .. } => "function",
+            JsValue::NativeFunction { .. } => "function",
+            JsValue::Symbol(_) => "symbol",
+            JsValue::Object(_)
+            | JsValue::Array(_)
+            | JsValue::Promise(_)
+            | JsValue::Map(_)
+            | JsValue::Set(_)
+            | JsValue::WeakMap(_)
+            | JsValue::WeakSet(_)
+            | JsValue::RegExp(_)
+            | JsValue::ArrayBuffer(_)
+            | JsValue::TypedArray(_)
+            | JsValue::DataView(_)
+            | JsValue::Proxy(_) => "object",
+        };
+        Ok(JsValue::String(t.into()))
+    }
+
+    pub(super) fn eval_optional_chain(
+        &mut self,
+        base: &Expr,
+        chain: &[OptionalOp],
+    ) -> Result<JsValue, RuntimeError> {
+        let mut current = self.eval_expr(base)?;
+        for op in chain {
+            if matches!(current, JsValue::Null | JsValue::Undefined) {
+                return Ok(JsValue::Undefined);
+            }
+
+            current = match op {
+                OptionalOp::PropertyAccess(name) => self.get_property(&current, name)?,
+                OptionalOp::ComputedAccess(expr) => {
+                    let key = self.eval_property_key_expr(expr)?;
+                    self.get_keyed(&current, &key)?
+                }
+                OptionalOp::Call(args) => {
+                    let arg_values = args
+                        .iter()
+                        .map(|arg| self.eval_expr(arg))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    self.call_function(&current, &arg_values)?
+                }
+            };
+        }
+
+        Ok(current)
+    }
+
+    pub(super) fn eval_regex_literal(
+        &mut self,
+        pattern: &str,
+        flags: &str,
+    ) -> Result<JsValue, RuntimeError> {
+        let fl =
+            RegExpFlags::from_str(flags).map_err(|msg| RuntimeError::TypeError { message: msg })?;
+        let re = JsRegExp::new(pattern, fl).map_err(|msg| self.throw_syntax_error(&msg))?;
+        Ok(JsValue::RegExp(self.heap.alloc_cell(re)))
+    }
+
+    pub(super) fn eval_tagged_template(
+        &mut self,
+        tag: &Expr,
+        parts: &[TemplatePart],
+        raw: &[String],
+    ) -> Result<JsValue, RuntimeError> {
+        let func = self.eval_expr(tag)?;
+        let mut strings = Vec::new();
+        let mut exprs = Vec::new();
+        for part in parts {
+            match part {
+                TemplatePart::Str(s) => strings.push(JsValue::String(s.into())),
+                TemplatePart::Expression(expr) => {
+                    exprs.push(self.eval_expr(expr)?);
+                }
+            }
+        }
+        let raw_strings = raw.iter().map(|s| JsValue::String(s.into())).collect();
+        let raw_arr = JsValue::Array(self.heap.alloc_cell(JsArray::new(raw_strings)));
+        let tmpl_arr_gc = self.heap.alloc_cell(JsArray::new(strings));
+        let tmpl_obj = JsValue::Array(tmpl_arr_gc);
+        self.set_property(&tmpl_obj, "raw", raw_arr)?;
+        let mut call_args = vec![tmpl_obj];
+        call_args.extend(exprs);
+        self.call_function(&func, &call_args)
+    }
+
+    /// Creates the closure for an arrow function or function expression.
+    pub(super) fn eval_function_literal(&mut self, expr: &Expr) -> Result<JsValue, RuntimeError> {
+        match expr {
+            Expr::ArrowFunction {
+                params,
+                body,
+                is_async,
+                source,
+            } => Ok(JsValue::Function {
+                name: "<arrow>".to_string(),
+                params: params.as_slice().into(),
+                body: body.clone().with_source(source.clone()),
+                closure_env: self.env.capture(),
+                is_async: *is_async,
+                is_generator: false,
+                source_path: self.module_stack.last().map(|p| p.display().to_string()),
+                source_offset: 0,
+                properties: None,
+            }),
+            Expr::FunctionExpr {
+                name,
+                params,
+                body,
+                is_async,
+                is_generator,
+            } => {
+                let proto = crate::runtime::value::object::JsObject::new();
+                let proto_gc = self.heap.alloc_cell(proto);
+                let mut fn_props = crate::runtime::value::object::JsObject::new();
+                fn_props.set("prototype".to_string(), JsValue::Object(proto_gc));
+                // A named expression sees its own name through a scope of
+                // its own between the closure and the scopes around it.
+                let mut closure_env = self.env.capture();
+                let self_scope = name.as_ref().map(|_| self.heap.alloc_cell(Scope::new()));
+                closure_env.extend(self_scope);
+                let function = JsValue::Function {
+                    name: name.clone().unwrap_or_else(|| "<anonymous>".to_string()),
+                    params: params.as_slice().into(),
+                    body: body.clone(),
+                    closure_env,
+                    is_async: *is_async,
+                    is_generator: *is_generator,
+                    source_path: self.module_stack.last().map(|p| p.display().to_string()),
+                    source_offset: 0,
+                    properties: Some(self.heap.alloc_cell(fn_props)),
+                };
+                if let (Some(scope), Some(name)) = (self_scope, name) {
+                    scope.borrow_mut().define_with_kind(
+                        name.as_str(),
+                        function.clone(),
+                        BindingKind::FunctionName,
+                    );
+                }
+                Ok(function)
+            }
+            _ => unreachable!("not a function literal: {}", expr.kind()),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use super::Interpreter;
+use super::property_access::PropKey;
 use crate::errors::RuntimeError;
-use crate::parser::ast::Pattern;
+use crate::parser::ast::{Expr, Param, Pattern, VarDeclKind};
 use crate::runtime::environment::BindingKind;
 use crate::runtime::value::JsValue;
 use crate::runtime::value::array::JsArray;
@@ -8,6 +9,45 @@ use crate::runtime::value::object::JsObject;
 use std::collections::HashSet;
 
 impl Interpreter {
+    /// Binds `params` to `args` left to right in the current scope. Every
+    /// parameter starts out uninitialized, so a default can read the
+    /// parameters before it while touching a later one throws. A rest
+    /// parameter collects the remaining arguments into an array.
+    ///
+    /// When any parameter has a default, a fresh scope is pushed for the
+    /// body so its `var`s don't shadow what closures in the defaults
+    /// captured.
+    pub(crate) fn bind_parameters(
+        &mut self,
+        params: &[Param],
+        args: &[JsValue],
+    ) -> Result<(), RuntimeError> {
+        for param in params {
+            for name in param.pattern.bound_names() {
+                self.env.declare_uninitialized(name);
+            }
+        }
+        for (idx, param) in params.iter().enumerate() {
+            if let Pattern::Rest(inner) = &param.pattern {
+                let rest = args.get(idx..).unwrap_or_default().to_vec();
+                let rest = JsValue::Array(self.heap.alloc_cell(JsArray::new(rest)));
+                self.eval_pattern_binding(inner, rest)?;
+                break;
+            }
+            let mut value = args.get(idx).cloned().unwrap_or(JsValue::Undefined);
+            if matches!(value, JsValue::Undefined)
+                && let Some(default_expr) = &param.default
+            {
+                value = self.eval_expr(default_expr)?;
+            }
+            self.eval_pattern_binding(&param.pattern, value)?;
+        }
+        if params.iter().any(|param| param.default.is_some()) {
+            self.env.push_scope(&mut self.heap);
+        }
+        Ok(())
+    }
+
     pub(crate) fn eval_pattern_binding(
         &mut self,
         pattern: &Pattern,
@@ -22,20 +62,63 @@ impl Interpreter {
         value: JsValue,
         kind: BindingKind,
     ) -> Result<(), RuntimeError> {
-        match pattern {
-            Pattern::Identifier(name) => {
-                self.env.define_with_kind(name.clone(), value, kind);
-                Ok(())
+        self.destructure(pattern, value, Some(kind))
+    }
+
+    /// Binds the value a `for-of` or `for-in` loop reached to the loop's
+    /// head, declaring it with the head's kind or assigning to an existing
+    /// binding when the head declares nothing.
+    pub(crate) fn bind_for_head(
+        &mut self,
+        kind: &Option<VarDeclKind>,
+        pattern: &Pattern,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        match kind {
+            Some(kind) => {
+                self.eval_pattern_binding_with_kind(pattern, value, BindingKind::from(kind))
             }
+            None => self.eval_pattern_assignment(pattern, value),
+        }
+    }
+
+    /// Destructuring assignment: stores into existing bindings and
+    /// properties instead of declaring names.
+    pub(crate) fn eval_pattern_assignment(
+        &mut self,
+        pattern: &Pattern,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        self.destructure(pattern, value, None)
+    }
+
+    /// Declares the names in `pattern` with `kind`, or assigns to them
+    /// when `kind` is `None`.
+    fn destructure(
+        &mut self,
+        pattern: &Pattern,
+        value: JsValue,
+        kind: Option<BindingKind>,
+    ) -> Result<(), RuntimeError> {
+        match pattern {
+            Pattern::Identifier(name) => match kind {
+                Some(kind) => {
+                    self.env.define_with_kind(name, value, kind);
+                    Ok(())
+                }
+                None => self.env.set(name, value),
+            },
             Pattern::ArrayPattern { elements } => {
+                // Elements are read as each target is reached, so earlier
+                // targets that write into the source are seen by later ones.
                 let source = match value {
-                    JsValue::Array(arr) => arr.borrow().elements.clone(),
+                    JsValue::Array(arr) => Some(arr),
                     JsValue::Undefined | JsValue::Null => {
                         return Err(RuntimeError::TypeError {
-                            message: "cannot destructure array from nullish value".to_string(),
+                            message: format!("{value} is not iterable"),
                         });
                     }
-                    _ => Vec::new(),
+                    _ => None,
                 };
 
                 let mut idx = 0usize;
@@ -45,22 +128,25 @@ impl Interpreter {
                             idx += 1;
                         }
                         Some(Pattern::Rest(inner)) => {
-                            let rest = if idx >= source.len() {
-                                Vec::new()
-                            } else {
-                                source[idx..].to_vec()
+                            let rest = match source {
+                                Some(arr) => {
+                                    let len = arr.borrow().len();
+                                    (idx..len)
+                                        .map(|index| self.array_element(&arr, index))
+                                        .collect::<Result<Vec<_>, _>>()?
+                                }
+                                None => Vec::new(),
                             };
                             let rest_val = JsValue::Array(self.heap.alloc_cell(JsArray::new(rest)));
-                            self.eval_pattern_binding_with_kind(
-                                inner,
-                                rest_val,
-                                kind,
-                            )?;
+                            self.destructure(inner, rest_val, kind)?;
                             break;
                         }
                         Some(inner) => {
-                            let val = source.get(idx).cloned().unwrap_or(JsValue::Undefined);
-                            self.eval_pattern_binding_with_kind(inner, val, kind)?;
+                            let val = match source {
+                                Some(arr) => self.array_element(&arr, idx)?,
+                                None => JsValue::Undefined,
+                            };
+                            self.destructure(inner, val, kind)?;
                             idx += 1;
                         }
                     }
@@ -69,14 +155,24 @@ impl Interpreter {
                 Ok(())
             }
             Pattern::ObjectPattern { properties } => {
-                let object = match value {
-                    JsValue::Object(obj) => obj,
+                // Keys are read through `get_property`, so getters and
+                // proxy traps run as they would for `source.key`.
+                let source = match value {
+                    JsValue::Object(_)
+                    | JsValue::Array(_)
+                    | JsValue::Function { .. }
+                    | JsValue::Proxy(_) => value,
                     JsValue::Undefined | JsValue::Null => {
-                        return Err(RuntimeError::TypeError {
-                            message: "cannot destructure object from nullish value".to_string(),
-                        });
+                        let message = match properties.iter().find(|prop| !prop.is_rest) {
+                            Some(prop) => format!(
+                                "Cannot destructure property '{}' of '{value}' as it is {value}.",
+                                prop.key
+                            ),
+                            None => format!("Cannot destructure '{value}' as it is {value}."),
+                        };
+                        return Err(RuntimeError::TypeError { message });
                     }
-                    _ => self.heap.alloc_cell(JsObject::new()),
+                    _ => JsValue::Object(self.heap.alloc_cell(JsObject::new())),
                 };
 
                 let mut used = HashSet::new();
@@ -86,8 +182,11 @@ impl Interpreter {
                         continue;
                     }
 
-                    let mut prop_value =
-                        object.borrow().get(&prop.key).unwrap_or(JsValue::Undefined);
+                    let key = match &prop.computed {
+                        Some(expr) => self.eval_property_key_expr(expr)?,
+                        None => PropKey::String(prop.key.clone()),
+                    };
+                    let mut prop_value = self.get_keyed(&source, &key)?;
                     if matches!(prop_value, JsValue::Undefined)
                         && let Some(default) = &prop.default
                     {
@@ -99,22 +198,25 @@ impl Interpreter {
                         .as_ref()
                         .cloned()
                         .unwrap_or(Pattern::Identifier(prop.key.clone()));
-                    self.eval_pattern_binding_with_kind(&target, prop_value, kind)?;
-                    used.insert(prop.key.clone());
+                    self.destructure(&target, prop_value, kind)?;
+                    if let PropKey::String(key) = key {
+                        used.insert(key);
+                    }
                 }
 
+                // The parser only accepts a rest element last. It gets a
+                // fresh object with the own enumerable string keys not
+                // named above; symbol keys are left out.
                 for prop in properties {
                     if !prop.is_rest {
                         continue;
                     }
 
                     let mut rest_obj = JsObject::new();
-                    {
-                        let borrowed = object.borrow();
-                        for (k, property) in &borrowed.properties {
-                            if !used.contains(k) {
-                                rest_obj.set(k.clone(), property.value.clone());
-                            }
+                    for key in self.object_own_keys(source.clone())? {
+                        if !used.contains(&key) {
+                            let value = self.get_property(&source, &key)?;
+                            rest_obj.set(key, value);
                         }
                     }
 
@@ -129,16 +231,38 @@ impl Interpreter {
                     };
 
                     let rest_val = JsValue::Object(self.heap.alloc_cell(rest_obj));
-                    self.eval_pattern_binding_with_kind(
-                        rest_target,
-                        rest_val,
-                        kind,
-                    )?;
+                    self.destructure(rest_target, rest_val, kind)?;
                 }
 
                 Ok(())
             }
-            Pattern::Rest(inner) => self.eval_pattern_binding_with_kind(inner, value, kind),
+            Pattern::Rest(inner) => self.destructure(inner, value, kind),
+            Pattern::Default {
+                target,
+                value: default,
+            } => {
+                let value = if matches!(value, JsValue::Undefined) {
+                    self.eval_expr(default)?
+                } else {
+                    value
+                };
+                self.destructure(target, value, kind)
+            }
+            Pattern::Member(target) => self.assign_member_target(target, value),
         }
     }
+
+    fn assign_member_target(&mut self, target: &Expr, value: JsValue) -> Result<(), RuntimeError> {
+        let (obj_val, key) = match target {
+            Expr::MemberAccess { object, property } => {
+                (self.eval_expr(object)?, PropKey::String(property.clone()))
+            }
+            Expr::ComputedMemberAccess { object, property } => {
+                let obj_val = self.eval_expr(object)?;
+                (obj_val, self.eval_property_key_expr(property)?)
+            }
+            _ => unreachable!("member patterns are built from member expressions"),
+        };
+        self.set_keyed(&obj_val, &key, value)
+    }
 }
@@ -1,6 +1,7 @@
+use super::hoisting::declares_let;
 use super::{ControlFlow, Interpreter};
 use crate::errors::RuntimeError;
-use crate::parser::ast::{Stmt, VarDeclKind};
+use crate::parser::ast::{Expr, Pattern, Stmt};
 use crate::runtime::environment::BindingKind;
 use crate::runtime::value::JsValue;
 
@@ -35,10 +36,21 @@ macro_rules! loop_body {
         }
     };
 }
+pub(super) use loop_body;
 
 impl Interpreter {
     pub(crate) fn eval_stmt(&mut self, stmt: &Stmt) -> Result<ControlFlow, RuntimeError> {
         self.check_step_limit()?;
+        #[cfg(feature = "profiling")]
+        self.profile.count_statement(stmt.kind());
+        if self.trace.is_some() {
+            self.trace_statement(stmt);
+        }
+        // Statement boundaries are the safe points where timers a yield
+        // hook asked for may run.
+        if self.timers_requested {
+            self.service_due_timers()?;
+        }
         match stmt {
             Stmt::Empty => Ok(ControlFlow::None),
             Stmt::ExprStmt(expr) => {
@@ -50,21 +62,15 @@ impl Interpreter {
                 pattern,
                 init,
             } => {
-                let value = match init {
-                    Some(e) => self.eval_expr(e)?,
-                    None => JsValue::Undefined,
-                };
-                self.eval_pattern_binding_with_kind(pattern, value, var_binding(kind))?;
+                let value = self.eval_initializer(pattern, init.as_ref())?;
+                self.eval_pattern_binding_with_kind(pattern, value, BindingKind::from(kind))?;
                 Ok(ControlFlow::None)
             }
             Stmt::Block(stmts) => self.eval_block(stmts),
             Stmt::VarDeclList { kind, declarations } => {
                 for (pattern, init) in declarations {
-                    let value = match init {
-                        Some(e) => self.eval_expr(e)?,
-                        None => JsValue::Undefined,
-                    };
-                    self.eval_pattern_binding_with_kind(pattern, value, var_binding(kind))?;
+                    let value = self.eval_initializer(pattern, init.as_ref())?;
+                    self.eval_pattern_binding_with_kind(pattern, value, BindingKind::from(kind))?;
                 }
                 Ok(ControlFlow::None)
             }
@@ -83,6 +89,7 @@ impl Interpreter {
             }
             Stmt::While { condition, body } => {
                 loop {
+                    self.check_step_limit()?;
                     if !self.eval_expr(condition)?.to_boolean() {
                         break;
                     }
@@ -93,6 +100,7 @@ impl Interpreter {
             Stmt::DoWhile { body, condition } => {
                 loop {
                     loop_body!(self.eval_stmt(body)?);
+                    self.check_step_limit()?;
                     if !self.eval_expr(condition)?.to_boolean() {
                         break;
                     }
@@ -106,16 +114,25 @@ impl Interpreter {
                 body,
             } => {
                 self.env.push_scope(&mut self.heap);
+                let per_iteration = init.as_deref().is_some_and(declares_let);
                 if let Some(s) = init {
+                    self.hoist_lexical(std::slice::from_ref(&**s));
                     self.eval_stmt(s)?;
                 }
+                if per_iteration {
+                    self.env.copy_innermost_scope(&mut self.heap);
+                }
                 loop {
+                    self.check_step_limit()?;
                     if let Some(c) = condition {
                         if !self.eval_expr(c)?.to_boolean() {
                             break;
                         }
                     }
                     loop_body!(self.eval_stmt(body)?, scope: self);
+                    if per_iteration {
+                        self.env.copy_innermost_scope(&mut self.heap);
+                    }
                     if let Some(u) = update {
                         self.eval_expr(u)?;
                     }
@@ -124,68 +141,55 @@ impl Interpreter {
                 Ok(ControlFlow::None)
             }
             Stmt::ForOf {
-                variable,
+                kind,
+                pattern,
                 iterable,
                 body,
             } => {
                 let iter_val = self.eval_expr(iterable)?;
-                let elements = self.collect_iterable(&iter_val)?;
-                self.env.push_scope(&mut self.heap);
-                self.env.define(variable.clone(), JsValue::Undefined);
-                for elem in &elements {
-                    self.env.set(variable, elem.clone())?;
-                    loop_body!(self.eval_stmt(body)?, scope: self);
+                let mut cursor = self.open_iteration(&iter_val)?;
+                while let Some(elem) = self.step_iteration(&mut cursor)? {
+                    // Each iteration binds the head in a scope of its own,
+                    // so closures made in the body keep their own value.
+                    self.env.push_scope(&mut self.heap);
+                    let flow = self
+                        .bind_for_head(kind, pattern, elem)
+                        .and_then(|()| self.eval_stmt(body));
+                    let flow = self.close_iteration_on_exit(&cursor, flow)?;
+                    self.env.pop_scope();
+                    loop_body!(flow);
                 }
-                self.env.pop_scope();
                 Ok(ControlFlow::None)
             }
             Stmt::ForIn {
-                variable,
+                kind,
+                pattern,
                 object,
                 body,
             } => {
                 let source = self.eval_expr(object)?;
-                let keys: Vec<String> = match source {
-                    JsValue::Object(obj) => obj.borrow().properties.keys().cloned().collect(),
-                    JsValue::Array(arr) => (0..arr.borrow().len()).map(|i| i.to_string()).collect(),
-                    JsValue::String(s) => (0..s.chars().count()).map(|i| i.to_string()).collect(),
-                    _ => Vec::new(),
-                };
-                self.env.push_scope(&mut self.heap);
-                self.env
-                    .define(variable.clone(), JsValue::String(String::new()));
-                for key in keys {
-                    self.env.set(variable, JsValue::String(key))?;
-                    loop_body!(self.eval_stmt(body)?, scope: self);
+                let keys = self.for_in_keys(&source)?;
+                for entry in keys {
+                    // The body may have deleted a key still to come.
+                    if !self.for_in_key_present(&entry.holder, &entry.key)? {
+                        continue;
+                    }
+                    self.env.push_scope(&mut self.heap);
+                    self.bind_for_head(kind, pattern, JsValue::String(entry.key.into()))?;
+                    let flow = self.eval_stmt(body)?;
+                    self.env.pop_scope();
+                    loop_body!(flow);
                 }
-                self.env.pop_scope();
                 Ok(ControlFlow::None)
             }
-            Stmt::FunctionDecl {
-                name,
-                params,
-                body,
-                is_async,
-                is_generator,
-                decl_offset,
-            } => {
-                let proto_gc = self
-                    .heap
-                    .alloc_cell(crate::runtime::value::object::JsObject::new());
-                let mut fn_props = crate::runtime::value::object::JsObject::new();
-                fn_props.set("prototype".into(), JsValue::Object(proto_gc));
-                let func = JsValue::Function {
-                    name: name.clone(),
-                    params: params.clone(),
-                    body: body.clone(),
-                    closure_env: self.env.capture(),
-                    is_async: *is_async,
-                    is_generator: *is_generator,
-                    source_path: self.module_stack.last().map(|p| p.display().to_string()),
-                    source_offset: *decl_offset,
-                    properties: Some(self.heap.alloc_cell(fn_props)),
-                };
-                self.env.define(name.clone(), func);
+            Stmt::FunctionDecl { name, .. } => {
+                // Declarations in a body, block or switch case were bound
+                // when it was entered; only ones in other positions, such
+                // as an `if` branch without braces, are bound here.
+                if !self.env.declares_locally(name) {
+                    let func = self.instantiate_function_decl(stmt);
+                    self.env.define(name, func);
+                }
                 Ok(ControlFlow::None)
             }
             Stmt::Return(expr) => {
@@ -233,6 +237,7 @@ impl Interpreter {
 
     pub(crate) fn eval_block(&mut self, stmts: &[Stmt]) -> Result<ControlFlow, RuntimeError> {
         self.env.push_scope(&mut self.heap);
+        self.hoist_block_declarations(stmts);
         let mut result = ControlFlow::None;
         for s in stmts {
             result = self.eval_stmt(s)?;
@@ -243,12 +248,18 @@ impl Interpreter {
         self.env.pop_scope();
         Ok(result)
     }
-}
 
-fn var_binding(kind: &VarDeclKind) -> BindingKind {
-    match kind {
-        VarDeclKind::Let => BindingKind::Let,
-        VarDeclKind::Const => BindingKind::Const,
-        VarDeclKind::Var => BindingKind::Var,
+    /// The value a declarator binds: its initializer, named after the
+    /// binding when that is a plain identifier, or `undefined`.
+    fn eval_initializer(
+        &mut self,
+        pattern: &Pattern,
+        init: Option<&Expr>,
+    ) -> Result<JsValue, RuntimeError> {
+        match (init, pattern) {
+            (Some(init), Pattern::Identifier(name)) => self.eval_named(init, name),
+            (Some(init), _) => self.eval_expr(init),
+            (None, _) => Ok(JsValue::Undefined),
+        }
     }
 }
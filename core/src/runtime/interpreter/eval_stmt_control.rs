@@ -1,65 +1,37 @@
 use super::error_handling::JsException;
 use super::{ControlFlow, Interpreter};
 use crate::errors::RuntimeError;
-use crate::parser::ast::{Expr, Stmt, SwitchCase};
+use crate::parser::ast::{Expr, Pattern, Stmt, SwitchCase};
+use crate::runtime::value::JsValue;
 
 impl Interpreter {
     pub(crate) fn eval_try_catch(
         &mut self,
         try_block: &[Stmt],
-        catch_param: &Option<String>,
+        catch_param: &Option<Pattern>,
         catch_block: &Option<Vec<Stmt>>,
         finally_block: &Option<Vec<Stmt>>,
     ) -> Result<ControlFlow, RuntimeError> {
         let mut flow = ControlFlow::None;
         let mut pending_error = None;
+        let depth = self.env.depth();
 
         match self.eval_block(try_block) {
             Ok(v) => flow = v,
             Err(err) => {
-                // Convert catchable runtime errors to Thrown values
-                let err = match err {
-                    RuntimeError::TypeError { ref message } => {
-                        let err_obj = self.create_typed_error_object("TypeError", message);
-                        RuntimeError::Thrown { value: err_obj }
-                    }
-                    RuntimeError::UndefinedVariable { ref name } => {
-                        let msg = format!("'{name}' is not defined");
-                        let err_obj = self.create_typed_error_object("ReferenceError", &msg);
-                        RuntimeError::Thrown { value: err_obj }
-                    }
-                    RuntimeError::NotAFunction { ref name } => {
-                        let msg = format!("'{name}' is not a function");
-                        let err_obj = self.create_typed_error_object("TypeError", &msg);
-                        RuntimeError::Thrown { value: err_obj }
-                    }
-                    RuntimeError::ConstReassignment { ref name } => {
-                        let msg = format!("Assignment to constant variable '{name}'");
-                        let err_obj = self.create_typed_error_object("TypeError", &msg);
-                        RuntimeError::Thrown { value: err_obj }
-                    }
-                    other => other,
-                };
+                self.env.unwind_to(depth);
+                let err = self.catchable_error(err);
                 if let RuntimeError::Thrown { value } = err {
                     if let Some(catch_stmts) = catch_block {
                         self.env.push_scope(&mut self.heap);
-                        if let Some(name) = catch_param {
-                            self.env.define(name.clone(), value);
+                        let caught = self.eval_catch_clause(catch_param, catch_stmts, *value);
+                        self.env.unwind_to(depth);
+                        // A throw from the catch body, including an awaited
+                        // rejection, still has to pass through `finally`.
+                        match caught {
+                            Ok(catch_flow) => flow = catch_flow,
+                            Err(err) => pending_error = Some(err),
                         }
-                        let mut catch_flow = ControlFlow::None;
-                        for stmt in catch_stmts {
-                            catch_flow = self.eval_stmt(stmt)?;
-                            if matches!(
-                                catch_flow,
-                                ControlFlow::Return(_)
-                                    | ControlFlow::Break(_)
-                                    | ControlFlow::Continue(_)
-                            ) {
-                                break;
-                            }
-                        }
-                        self.env.pop_scope();
-                        flow = catch_flow;
                     } else {
                         pending_error = Some(RuntimeError::Thrown { value });
                     }
@@ -83,6 +55,33 @@ impl Interpreter {
         Ok(flow)
     }
 
+    fn eval_catch_clause(
+        &mut self,
+        catch_param: &Option<Pattern>,
+        catch_stmts: &[Stmt],
+        value: JsValue,
+    ) -> Result<ControlFlow, RuntimeError> {
+        if let Some(pattern) = catch_param {
+            self.eval_pattern_binding(pattern, value)?;
+        }
+        self.hoist_block_declarations(catch_stmts);
+        for stmt in catch_stmts {
+            let flow = self.eval_stmt(stmt)?;
+            if !matches!(flow, ControlFlow::None) {
+                return Ok(flow);
+            }
+        }
+        Ok(ControlFlow::None)
+    }
+
+    /// Converts catchable runtime errors to `Thrown` error objects.
+    pub(crate) fn catchable_error(&mut self, err: RuntimeError) -> RuntimeError {
+        match self.runtime_error_to_value(err) {
+            Ok(value) => RuntimeError::thrown(value),
+            Err(err) => err,
+        }
+    }
+
     pub(crate) fn eval_switch(
         &mut self,
         discriminant: &Expr,
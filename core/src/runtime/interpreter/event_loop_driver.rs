@@ -1,20 +1,54 @@
+use std::time::Duration;
+
 use super::Interpreter;
 use crate::errors::RuntimeError;
-use crate::runtime::event_loop::Microtask;
+use crate::runtime::event_loop::{Microtask, TimerTask};
+use crate::runtime::report::RunEvent;
+use crate::runtime::value::JsValue;
+
+/// How often the async loop checks for finished fetches while it has
+/// nothing else to do.
+const FETCH_POLL_INTERVAL: Duration = Duration::from_millis(2);
 
 impl Interpreter {
     pub(crate) fn run_event_loop_until_idle(&mut self) -> Result<(), RuntimeError> {
+        if self.manual_timers {
+            return self.drain_microtasks();
+        }
         while self.event_loop_has_pending() {
             self.drain_microtasks()?;
+            self.receive_fetches()?;
             if self.event_loop.has_tasks() {
                 self.event_loop.advance_to_next_task();
-                if let Some(task) = self.event_loop.pop_ready_task()
-                    && task.active
-                {
-                    self.call_function(&task.callback, &[])?;
-                    self.event_loop.reschedule_interval(task);
+                if let Some(task) = self.event_loop.pop_ready_task() {
+                    self.fire_timer(task)?;
+                }
+            }
+            self.gc_safepoint();
+        }
+        Ok(())
+    }
+
+    /// `run_event_loop_until_idle` for async hosts: waiting for a timer
+    /// awaits a sleep on the tokio runtime polling this future rather
+    /// than blocking its thread.
+    pub(crate) async fn run_event_loop_async(&mut self) -> Result<(), RuntimeError> {
+        if self.manual_timers {
+            return self.drain_microtasks();
+        }
+        while self.event_loop_has_pending() {
+            self.drain_microtasks()?;
+            self.receive_fetches_async().await?;
+            if self.event_loop.has_tasks() {
+                if let Some(wait) = self.event_loop.realtime_wait() {
+                    tokio::time::sleep(wait).await;
+                }
+                self.event_loop.skip_to_next_task();
+                if let Some(task) = self.event_loop.pop_ready_task() {
+                    self.fire_timer(task)?;
                 }
             }
+            self.gc_safepoint();
         }
         Ok(())
     }
@@ -23,43 +57,177 @@ impl Interpreter {
         &mut self,
         promise: &crate::runtime::gc::Gc<crate::runtime::gc::GcCell<crate::runtime::value::promise::JsPromise>>,
     ) -> Result<(), RuntimeError> {
+        if self.manual_timers {
+            return self.drain_microtasks();
+        }
         while matches!(
             promise.borrow().state,
             crate::runtime::value::promise::PromiseState::Pending
         ) && self.event_loop_has_pending()
         {
             self.drain_microtasks()?;
+            self.receive_fetches()?;
             if self.event_loop.has_tasks() {
                 self.event_loop.advance_to_next_task();
-                if let Some(task) = self.event_loop.pop_ready_task()
-                    && task.active
-                {
-                    self.call_function(&task.callback, &[])?;
-                    self.event_loop.reschedule_interval(task);
+                if let Some(task) = self.event_loop.pop_ready_task() {
+                    self.fire_timer(task)?;
                 }
             }
         }
         Ok(())
     }
 
+    /// Moves the virtual clock forward by `ms`, firing every timer that
+    /// comes due on the way in due order and draining microtasks after
+    /// each one.
+    pub fn advance_time(&mut self, ms: u64) -> Result<(), RuntimeError> {
+        let limit = self.event_loop.now_ms().saturating_add(ms);
+        self.settle_finished_fetches(Some(Duration::ZERO))?;
+        self.drain_microtasks()?;
+        while self.event_loop.advance_within(limit) {
+            if let Some(task) = self.event_loop.pop_ready_task() {
+                self.fire_timer(task)?;
+            }
+            self.drain_microtasks()?;
+            self.gc_safepoint();
+        }
+        Ok(())
+    }
+
+    /// Fires the timers due by now in the middle of a script, as a yield
+    /// hook asked, draining microtasks after each one.
+    pub(crate) fn service_due_timers(&mut self) -> Result<(), RuntimeError> {
+        self.timers_requested = false;
+        if self.manual_timers {
+            return Ok(());
+        }
+        self.event_loop.sync_clock();
+        while let Some(task) = self.event_loop.pop_ready_task() {
+            self.fire_timer(task)?;
+            self.drain_microtasks()?;
+        }
+        Ok(())
+    }
+
+    pub fn pending_timer_count(&self) -> usize {
+        self.event_loop.pending_timer_count()
+    }
+
+    /// Milliseconds until the earliest pending timer is due.
+    pub fn next_timer_due_in(&self) -> Option<u64> {
+        self.event_loop.next_timer_due_in()
+    }
+
+    fn fire_timer(&mut self, task: TimerTask) -> Result<(), RuntimeError> {
+        if !task.active {
+            return Ok(());
+        }
+        self.record_event(RunEvent::TimerFired { id: task.id });
+        let result = self.call_function(&task.callback, &[]);
+        self.event_loop.reschedule_interval(task);
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => self.report_uncaught_exception(err),
+        }
+    }
+
+    /// Hands an error that escaped a timer or animation-frame callback to
+    /// the uncaught exception hook so the loop can go on to the next task.
+    /// Without a hook the error is written to the output like a console
+    /// line. Running out of steps still stops the loop.
+    fn report_uncaught_exception(&mut self, err: RuntimeError) -> Result<(), RuntimeError> {
+        if !err.is_catchable() {
+            return Err(err);
+        }
+        let err = self.catchable_error(err);
+        if let Some(hook) = &self.uncaught_exception_hook {
+            hook(&err);
+            return Ok(());
+        }
+        let stack = match &err {
+            RuntimeError::Thrown { value } if let JsValue::Object(obj) = &**value => {
+                obj.borrow().get("stack").map(|s| {
+                    // The stack holds the error's own name and message; its
+                    // causes follow the frames.
+                    let causes = value.error_chain().unwrap_or_default();
+                    match causes.split_once('\n') {
+                        Some((_, causes)) => format!("{}\n{causes}", s.to_js_string()),
+                        None => s.to_js_string(),
+                    }
+                })
+            }
+            _ => None,
+        };
+        // An error object's stack starts with its name and message.
+        let line = match stack {
+            Some(stack) => format!("Uncaught {stack}"),
+            None => err.to_string(),
+        };
+        let (message, frames) = match line.split_once("\n    at ") {
+            Some((message, frames)) => (message.to_string(), format!("    at {frames}")),
+            None => (line.clone(), String::new()),
+        };
+        self.record_event(RunEvent::UncaughtError {
+            message,
+            stack: frames,
+        });
+        self.output_mode.write_line(line, &mut self.output);
+        Ok(())
+    }
+
     fn event_loop_has_pending(&self) -> bool {
-        self.event_loop.has_microtasks() || self.event_loop.has_tasks()
+        self.event_loop.has_microtasks()
+            || self.event_loop.has_tasks()
+            || self.has_pending_fetches()
+    }
+
+    /// Settles the fetches that have finished. With nothing else queued
+    /// the loop would end, so it waits for one instead; with only timers
+    /// queued it waits until the next is due.
+    fn receive_fetches(&mut self) -> Result<(), RuntimeError> {
+        if !self.has_pending_fetches() {
+            return Ok(());
+        }
+        let wait = if self.event_loop.has_microtasks() {
+            Some(Duration::ZERO)
+        } else if self.event_loop.has_tasks() {
+            Some(self.event_loop.realtime_wait().unwrap_or_default())
+        } else {
+            None
+        };
+        self.settle_finished_fetches(wait)
     }
 
+    /// `receive_fetches` for the async loop, which polls rather than
+    /// blocking the runtime's thread while it waits.
+    async fn receive_fetches_async(&mut self) -> Result<(), RuntimeError> {
+        if !self.has_pending_fetches() {
+            return Ok(());
+        }
+        self.settle_finished_fetches(Some(Duration::ZERO))?;
+        if self.has_pending_fetches()
+            && !self.event_loop.has_microtasks()
+            && !self.event_loop.has_tasks()
+        {
+            tokio::time::sleep(FETCH_POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
+
+    /// Settles finished fetches and runs the microtasks queued so far.
     pub(crate) fn run_microtasks_only(&mut self) -> Result<(), RuntimeError> {
+        self.settle_finished_fetches(Some(Duration::ZERO))?;
         self.drain_microtasks()
     }
 
     pub(crate) fn run_pending_timers(&mut self) -> Result<(), RuntimeError> {
         while self.event_loop.has_tasks() {
             self.event_loop.advance_to_next_task();
-            if let Some(task) = self.event_loop.pop_ready_task()
-                && task.active
-            {
-                self.call_function(&task.callback, &[])?;
-                self.event_loop.reschedule_interval(task);
+            if let Some(task) = self.event_loop.pop_ready_task() {
+                self.fire_timer(task)?;
             }
             self.drain_microtasks()?;
+            self.gc_safepoint();
         }
         Ok(())
     }
@@ -70,17 +238,25 @@ impl Interpreter {
     ) -> Result<(), RuntimeError> {
         let callbacks = self.event_loop.take_animation_callbacks();
         for callback in callbacks {
-            self.call_function(
-                &callback,
-                &[crate::runtime::value::JsValue::Number(timestamp_ms)],
-            )?;
+            if let Err(err) = self.call_function(&callback, &[JsValue::Number(timestamp_ms)]) {
+                self.report_uncaught_exception(err)?;
+            }
         }
         self.drain_microtasks()?;
+        self.gc_safepoint();
         Ok(())
     }
 
     fn drain_microtasks(&mut self) -> Result<(), RuntimeError> {
+        let mut drained = 0;
         while let Some(task) = self.event_loop.pop_microtask() {
+            drained += 1;
+            if drained > self.microtask_limit {
+                return Err(RuntimeError::MicrotaskLimitExceeded {
+                    limit: self.microtask_limit,
+                });
+            }
+            self.poll_yield_hook()?;
             match task {
                 Microtask::PromiseReaction {
                     reaction,
@@ -95,3 +271,19 @@ impl Interpreter {
         Ok(())
     }
 }
+
+/// Without the `http` feature there is no `fetch`, so nothing is ever in
+/// flight.
+#[cfg(not(feature = "http"))]
+impl Interpreter {
+    pub(crate) fn has_pending_fetches(&self) -> bool {
+        false
+    }
+
+    pub(crate) fn settle_finished_fetches(
+        &mut self,
+        _timeout: Option<Duration>,
+    ) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use super::Interpreter;
+use crate::errors::RuntimeError;
+use crate::runtime::builtins::prototypes::has_null_prototype;
+use crate::runtime::gc::Gc;
+use crate::runtime::value::JsValue;
+
+/// A key `for-in` will visit, with the object that owned it when the
+/// loop started.
+pub(crate) struct ForInKey {
+    pub(crate) holder: JsValue,
+    pub(crate) key: String,
+}
+
+impl Interpreter {
+    /// The keys `for (k in value)` visits: the enumerable string keys of
+    /// `value` and then of each prototype, skipping any an object earlier
+    /// in the chain already has, enumerable or not.
+    pub(crate) fn for_in_keys(&mut self, value: &JsValue) -> Result<Vec<ForInKey>, RuntimeError> {
+        if matches!(value, JsValue::Null | JsValue::Undefined) {
+            return Ok(Vec::new());
+        }
+        let root = self.prototypes.object;
+        let mut reached_root = false;
+        let mut seen = HashSet::new();
+        let mut keys = Vec::new();
+        let mut current = Some(value.clone());
+        while let Some(holder) = current {
+            for (key, enumerable) in self.own_keys_for_in(&holder)? {
+                if seen.insert(key.clone()) && enumerable {
+                    keys.push(ForInKey {
+                        holder: holder.clone(),
+                        key,
+                    });
+                }
+            }
+            if let JsValue::Object(obj) = &holder
+                && Gc::ptr_eq(*obj, root)
+            {
+                reached_root = true;
+            }
+            reached_root |= has_null_prototype(&holder);
+            // Like property lookup, a chain that ends short of
+            // `Object.prototype` falls back to it.
+            current = match self.for_in_prototype(&holder)? {
+                Some(proto) => Some(proto),
+                None if !reached_root => {
+                    reached_root = true;
+                    Some(JsValue::Object(root))
+                }
+                None => None,
+            };
+        }
+        Ok(keys)
+    }
+
+    /// Whether `key` is still an own property of `holder`, so a key the
+    /// loop body deleted is not visited.
+    pub(crate) fn for_in_key_present(
+        &mut self,
+        holder: &JsValue,
+        key: &str,
+    ) -> Result<bool, RuntimeError> {
+        Ok(self.own_property_enumerability(holder, key)?.is_some())
+    }
+
+    /// Own string keys of `value` in property order, each with whether it
+    /// is enumerable.
+    fn own_keys_for_in(&mut self, value: &JsValue) -> Result<Vec<(String, bool)>, RuntimeError> {
+        let mut keys = Vec::new();
+        for key in self.object_get_all_own_keys(value.clone())? {
+            if let Some(enumerable) = self.own_property_enumerability(value, &key)? {
+                keys.push((key, enumerable));
+            }
+        }
+        Ok(keys)
+    }
+
+    fn for_in_prototype(&mut self, value: &JsValue) -> Result<Option<JsValue>, RuntimeError> {
+        if let JsValue::Proxy(_) = value {
+            return Ok(match self.object_get_prototype_of(value)? {
+                JsValue::Null => None,
+                proto => Some(proto),
+            });
+        }
+        Ok(self.inherited_prototype(value).map(JsValue::Object))
+    }
+}
@@ -1,12 +1,21 @@
+use std::rc::Rc;
+
 use super::Interpreter;
 use crate::diagnostics::stack_trace::CallFrame;
+use crate::diagnostics::trace::TraceEventKind;
+use crate::embedding::call_scope::CallScope;
+use crate::embedding::callback::NativeFunctionBoxed;
+use crate::embedding::function_args::FunctionArgs;
 use crate::errors::RuntimeError;
+use crate::parser::ast::{FunctionBody, Stmt};
 use crate::runtime::gc::{Gc, GcCell};
 use crate::runtime::value::array::JsArray;
-use crate::runtime::value::generator::{GeneratorState, JsGenerator};
-use crate::runtime::value::object::JsObject;
+use crate::runtime::value::array::methods::ARRAY_CALLBACK_METHODS;
+use crate::runtime::value::collation;
+use crate::runtime::value::generator::JsGenerator;
+use crate::runtime::value::object::{JsObject, Property};
 use crate::runtime::value::symbol;
-use crate::runtime::value::JsValue;
+use crate::runtime::value::{JsValue, NativeFunction, number_to_string};
 
 impl Interpreter {
     pub(crate) fn eval_array_callback_method(
@@ -15,71 +24,187 @@ impl Interpreter {
         method: &str,
         args: &[JsValue],
     ) -> Result<JsValue, RuntimeError> {
-        let callback = args.first().ok_or_else(|| RuntimeError::TypeError {
-            message: format!("{method} requires a callback argument"),
-        })?;
-        let elements = arr.borrow().elements.clone();
-
-        match method {
-            "map" => {
-                let mut result = Vec::new();
-                for elem in &elements {
-                    let val = self.call_function(callback, std::slice::from_ref(elem))?;
-                    result.push(val);
-                }
-                Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(result))))
+        if matches!(method, "sort" | "toSorted") {
+            let compare = args.first().filter(|c| !matches!(c, JsValue::Undefined));
+            if compare.is_some_and(|c| {
+                !matches!(c, JsValue::Function { .. } | JsValue::NativeFunction { .. })
+            }) {
+                return Err(RuntimeError::TypeError {
+                    message: format!(
+                        "The comparison function passed to {method} must be either a function or undefined"
+                    ),
+                });
             }
-            "filter" => {
-                let mut result = Vec::new();
-                for elem in &elements {
-                    let val = self.call_function(callback, std::slice::from_ref(elem))?;
-                    if val.to_boolean() {
-                        result.push(elem.clone());
-                    }
-                }
-                Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(result))))
+            let elements = self.array_elements(arr)?;
+            let sorted = self.sort_elements(elements, compare)?;
+            if method == "toSorted" {
+                return Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(sorted))));
             }
-            "forEach" => {
-                for elem in &elements {
-                    self.call_function(callback, std::slice::from_ref(elem))?;
+            // Accessor indices take the sorted values through their setters.
+            if arr.borrow().has_index_accessors() {
+                let array = JsValue::Array(*arr);
+                for (index, value) in sorted.into_iter().enumerate() {
+                    self.set_property(&array, &index.to_string(), value)?;
                 }
-                Ok(JsValue::Undefined)
+            } else {
+                arr.borrow_mut().elements = sorted;
             }
-            "reduce" => {
-                let init = args.get(1).cloned().unwrap_or(JsValue::Undefined);
-                let mut acc = init;
-                for elem in &elements {
-                    acc = self.call_function(callback, &[acc, elem.clone()])?;
+            return Ok(JsValue::Array(*arr));
+        }
+        if matches!(method, "join" | "toString") {
+            let separator = match args.first().filter(|_| method == "join") {
+                None | Some(JsValue::Undefined) => ",".to_string(),
+                Some(separator) => self.coerce_to_string(separator)?,
+            };
+            return Ok(JsValue::String(self.join_array(arr, &separator)?.into()));
+        }
+        if !ARRAY_CALLBACK_METHODS.contains(&method) {
+            return Err(RuntimeError::TypeError {
+                message: format!("array has no method '{method}'"),
+            });
+        }
+        let callback = args.first().ok_or_else(|| RuntimeError::TypeError {
+            message: format!("{method} requires a callback argument"),
+        })?;
+        // The callback sees writes made to the array as it goes, but the
+        // elements visited are fixed by the length at the start.
+        let len = arr.borrow().len();
+        if matches!(method, "reduce" | "reduceRight") {
+            return self.reduce_array(arr, method, callback, args.get(1), len);
+        }
+        let this_arg = args.get(1).cloned();
+        let array = JsValue::Array(*arr);
+        let finds = method.starts_with("find");
+        let indices: Box<dyn Iterator<Item = usize>> = if method.starts_with("findLast") {
+            Box::new((0..len).rev())
+        } else {
+            Box::new(0..len)
+        };
+        let mut result = Vec::new();
+        for index in indices {
+            // Elements removed during the iteration are skipped, except by
+            // the `find` family, which sees them as `undefined`.
+            let elem = if index < arr.borrow().len() {
+                Some(self.array_element(arr, index)?)
+            } else {
+                None
+            };
+            let Some(elem) = elem.or_else(|| finds.then_some(JsValue::Undefined)) else {
+                continue;
+            };
+            let val = self.call_function_with_this(
+                callback,
+                &[elem.clone(), JsValue::Number(index as f64), array.clone()],
+                this_arg.clone(),
+            )?;
+            match method {
+                "map" => result.push(val),
+                "filter" if val.to_boolean() => result.push(elem),
+                "some" if val.to_boolean() => return Ok(JsValue::Boolean(true)),
+                "every" if !val.to_boolean() => return Ok(JsValue::Boolean(false)),
+                "find" | "findLast" if val.to_boolean() => return Ok(elem),
+                "findIndex" | "findLastIndex" if val.to_boolean() => {
+                    return Ok(JsValue::Number(index as f64));
                 }
-                Ok(acc)
+                _ => {}
             }
-            "sort" => {
-                let mut sorted = arr.borrow().elements.clone();
-                if matches!(callback, JsValue::Undefined) {
-                    sorted.sort_by_key(|a| a.to_js_string());
-                } else {
-                    sorted.sort_by(|a, b| {
-                        let res = self
-                            .call_function(callback, &[a.clone(), b.clone()])
-                            .ok()
-                            .map(|v| v.to_number())
-                            .unwrap_or(0.0);
-                        if res < 0.0 {
-                            std::cmp::Ordering::Less
-                        } else if res > 0.0 {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            std::cmp::Ordering::Equal
-                        }
+        }
+        Ok(match method {
+            "map" => {
+                result.resize(len, JsValue::Undefined);
+                JsValue::Array(self.heap.alloc_cell(JsArray::new(result)))
+            }
+            "filter" => JsValue::Array(self.heap.alloc_cell(JsArray::new(result))),
+            "some" => JsValue::Boolean(false),
+            "every" => JsValue::Boolean(true),
+            "findIndex" | "findLastIndex" => JsValue::Number(-1.0),
+            _ => JsValue::Undefined,
+        })
+    }
+
+    /// `reduce` and `reduceRight`. Without an initial value the first
+    /// element visited seeds the accumulator, which an empty array cannot
+    /// do.
+    fn reduce_array(
+        &mut self,
+        arr: &Gc<GcCell<JsArray>>,
+        method: &str,
+        callback: &JsValue,
+        initial: Option<&JsValue>,
+        len: usize,
+    ) -> Result<JsValue, RuntimeError> {
+        let mut indices: Box<dyn Iterator<Item = usize>> = if method == "reduceRight" {
+            Box::new((0..len).rev())
+        } else {
+            Box::new(0..len)
+        };
+        let mut acc = match initial {
+            Some(initial) => initial.clone(),
+            None => match indices.next() {
+                Some(index) => self.array_element(arr, index)?,
+                None => {
+                    return Err(RuntimeError::TypeError {
+                        message: "Reduce of empty array with no initial value".to_string(),
                     });
                 }
-                arr.borrow_mut().elements = sorted.clone();
-                Ok(JsValue::Array(self.heap.alloc_cell(JsArray::new(sorted))))
+            },
+        };
+        let array = JsValue::Array(*arr);
+        for index in indices {
+            if index >= arr.borrow().len() {
+                continue;
             }
-            _ => Err(RuntimeError::TypeError {
-                message: format!("array has no method '{method}'"),
-            }),
+            let elem = self.array_element(arr, index)?;
+            acc = self.call_function(
+                callback,
+                &[acc, elem, JsValue::Number(index as f64), array.clone()],
+            )?;
         }
+        Ok(acc)
+    }
+
+    /// Stable sort for `Array.prototype.sort`. `undefined` elements go
+    /// to the end without reaching the comparator, the default order
+    /// converts each element to a string once and compares code points,
+    /// so `["b", "A"]` sorts as `["A", "b"]` in every locale, and the
+    /// first exception thrown by `compare` aborts the sort.
+    fn sort_elements(
+        &mut self,
+        elements: Vec<JsValue>,
+        compare: Option<&JsValue>,
+    ) -> Result<Vec<JsValue>, RuntimeError> {
+        let (defined, undefined): (Vec<_>, Vec<_>) = elements
+            .into_iter()
+            .partition(|v| !matches!(v, JsValue::Undefined));
+        let mut sorted = match compare {
+            Some(compare) => merge_sort(defined, &mut |a: &JsValue, b: &JsValue| {
+                let order = self
+                    .call_function(compare, &[a.clone(), b.clone()])?
+                    .to_number();
+                Ok(order > 0.0)
+            })?,
+            None => {
+                let mut keyed: Vec<_> =
+                    defined.into_iter().map(|v| (v.to_js_string(), v)).collect();
+                keyed.sort_by(|a, b| collation::compare_strings(&a.0, &b.0));
+                keyed.into_iter().map(|(_, v)| v).collect()
+            }
+        };
+        sorted.extend(undefined);
+        Ok(sorted)
+    }
+
+    /// Calls a host function, lending it the interpreter so that it can
+    /// call back into script.
+    pub(crate) fn call_host(
+        &mut self,
+        callback: &NativeFunctionBoxed,
+        this: JsValue,
+        args: &[JsValue],
+    ) -> Result<JsValue, RuntimeError> {
+        let data = Rc::clone(&self.context_data);
+        let args = FunctionArgs::new(this, args.to_vec()).with_data(&data);
+        callback.call(&mut CallScope::new(self), args)
     }
 
     pub(crate) fn call_function(
@@ -96,9 +221,8 @@ impl Interpreter {
         args: &[JsValue],
         this_binding: Option<JsValue>,
     ) -> Result<JsValue, RuntimeError> {
-        const MAX_CALL_DEPTH: usize = 256;
         self.call_depth += 1;
-        if self.call_depth > MAX_CALL_DEPTH {
+        if self.call_depth > self.max_call_depth {
             self.call_depth -= 1;
             return Err(RuntimeError::TypeError {
                 message: "Maximum call stack size exceeded".to_string(),
@@ -127,20 +251,22 @@ impl Interpreter {
                 source_offset,
                 ..
             } => {
+                let file = source_path
+                    .clone()
+                    .or_else(|| self.module_stack.last().map(|p| p.display().to_string()))
+                    .unwrap_or_else(|| "<script>".to_string());
+                let body = self.function_statements(body, &file)?;
                 if *is_generator {
                     return self.create_generator_object(
                         params,
-                        body,
+                        &body,
                         closure_env,
                         this_binding,
                         args,
+                        *is_async,
                     );
                 }
 
-                let file = source_path
-                    .clone()
-                    .or_else(|| self.module_stack.last().map(|p| p.display().to_string()))
-                    .unwrap_or_else(|| "<script>".to_string());
                 let pos = self.source_pos_for(&file, *source_offset);
                 self.call_stack.push_frame(CallFrame {
                     function_name: name.clone(),
@@ -148,20 +274,34 @@ impl Interpreter {
                     line: pos.line,
                     col: pos.col,
                 });
+                if self.trace.is_some() {
+                    self.trace_call(TraceEventKind::Call);
+                }
 
                 let result = if *is_async {
-                    self.execute_async_function_body(params, body, closure_env, this_binding, args)
+                    self.execute_async_function_body(params, &body, closure_env, this_binding, args)
                 } else {
-                    self.execute_function_body(params, body, closure_env, this_binding, args)
+                    self.execute_function_body(params, &body, closure_env, this_binding, args)
                 };
 
-                let trace = self.call_stack.format_trace();
+                if matches!(&result, Err(err) if err.is_catchable()) && self.error_frame.is_none() {
+                    self.error_frame = self.call_stack.top().cloned();
+                }
+                // The trace is only formatted for a failed call, while its
+                // frame is still on the stack.
+                let result = result.map_err(|err| {
+                    let trace = self.call_stack.format_trace();
+                    self.attach_stack_to_error(err, &trace)
+                });
+                if self.trace.is_some() {
+                    self.trace_call(TraceEventKind::Return);
+                }
                 self.call_stack.pop_frame();
-                result.map_err(|err| self.attach_stack_to_error(err, &trace))
-            }
-            JsValue::NativeFunction { handler, .. } => {
-                self.call_native_function(handler, args, this_binding)
+                result
             }
+            JsValue::NativeFunction { name, handler } => self.profiled("", name, |this| {
+                this.call_native_function(handler, args, this_binding)
+            }),
             JsValue::Proxy(proxy) => {
                 let (trap, target) = {
                     let p = proxy.borrow();
@@ -184,13 +324,39 @@ impl Interpreter {
         }
     }
 
-    fn non_callable_label(value: &JsValue) -> String {
+    /// The statements of a function about to run, parsing them if the
+    /// parser skipped the body. A syntax error in it is thrown as a
+    /// `SyntaxError` that names where in `file` it is.
+    fn function_statements(
+        &mut self,
+        body: &FunctionBody,
+        file: &str,
+    ) -> Result<Rc<[Stmt]>, RuntimeError> {
+        body.stmts().map_err(|err| {
+            let offset = err.span.offset();
+            let location = match self.source_maps.get(file) {
+                Some(map) => {
+                    let pos = map.byte_to_pos(offset);
+                    format!("{file}:{}:{}", pos.line, pos.col)
+                }
+                None => format!("{file}, offset {offset}"),
+            };
+            RuntimeError::thrown(
+                self.create_typed_error_object(
+                    "SyntaxError",
+                    &format!("{} ({location})", err.message),
+                ),
+            )
+        })
+    }
+
+    pub(super) fn non_callable_label(value: &JsValue) -> String {
         match value {
             JsValue::Undefined => "undefined".into(),
             JsValue::Null => "null".into(),
             JsValue::Boolean(v) => v.to_string(),
-            JsValue::Number(v) => v.to_string(),
-            JsValue::String(v) => v.clone(),
+            JsValue::Number(v) => number_to_string(*v),
+            JsValue::String(v) => v.to_string(),
             JsValue::Function { .. } | JsValue::NativeFunction { .. } => "function".into(),
             JsValue::Symbol(_) => "symbol".into(),
             JsValue::Object(_) => "object".into(),
@@ -201,161 +367,93 @@ impl Interpreter {
             JsValue::WeakMap(_) => "weakmap".into(),
             JsValue::WeakSet(_) => "weakset".into(),
             JsValue::RegExp(_) => "regexp".into(),
+            JsValue::ArrayBuffer(_) => "arraybuffer".into(),
+            JsValue::TypedArray(_) => "typedarray".into(),
+            JsValue::DataView(_) => "dataview".into(),
             JsValue::Proxy(_) => "proxy".into(),
         }
     }
 
-    pub(crate) fn attach_stack_to_error(&self, err: RuntimeError, trace: &str) -> RuntimeError {
-        if trace.is_empty() {
-            return err;
-        }
-
-        match err {
-            RuntimeError::TypeError { message } => {
-                if message.contains("\n    at ") {
-                    RuntimeError::TypeError { message }
-                } else {
-                    RuntimeError::TypeError {
-                        message: format!("{message}\n{trace}"),
-                    }
-                }
-            }
-            RuntimeError::UndefinedVariable { name } => RuntimeError::UndefinedVariable { name },
-            RuntimeError::NotAFunction { name } => RuntimeError::TypeError {
-                message: format!("TypeError: '{name}' is not a function\n{trace}"),
-            },
-            RuntimeError::ArityMismatch { expected, got } => RuntimeError::TypeError {
-                message: format!("TypeError: expected {expected} arguments but got {got}\n{trace}"),
-            },
-            RuntimeError::ConstReassignment { name } => RuntimeError::TypeError {
-                message: format!("TypeError: Assignment to constant variable '{name}'\n{trace}"),
-            },
-            RuntimeError::Thrown { .. } => err,
+    /// Keeps the innermost trace of an internal error for the error object
+    /// it may become, or for the host once it escapes.
+    pub(crate) fn attach_stack_to_error(&mut self, err: RuntimeError, trace: &str) -> RuntimeError {
+        if err.is_catchable()
+            && !matches!(err, RuntimeError::Thrown { .. })
+            && !trace.is_empty()
+            && self.error_trace.is_none()
+        {
+            self.error_trace = Some(trace.to_string());
         }
+        crate::diagnostics::stack_trace::attach_stack(err, trace)
     }
 
     fn create_generator_object(
         &mut self,
-        params: &[crate::parser::ast::Param],
-        body: &[crate::parser::ast::Stmt],
+        params: &Rc<[crate::parser::ast::Param]>,
+        body: &Rc<[crate::parser::ast::Stmt]>,
         closure_env: &[Gc<GcCell<crate::runtime::environment::Scope>>],
         this_binding: Option<JsValue>,
         args: &[JsValue],
+        is_async: bool,
     ) -> Result<JsValue, RuntimeError> {
         let gen_state = JsGenerator::new(
-            params.to_vec(),
-            body.to_vec(),
+            Rc::clone(params),
+            Rc::clone(body),
             closure_env.to_vec(),
             this_binding,
             args.to_vec(),
+            is_async,
         );
         let gen_gc = self.heap.alloc_cell(gen_state);
 
-        let mut obj = JsObject::new();
-
-        obj.set(
-            "next".to_string(),
-            JsValue::NativeFunction {
-                name: "next".to_string(),
-                handler: crate::runtime::value::NativeFunction::GeneratorNext(gen_gc),
-            },
-        );
-
-        obj.set(
-            "return".to_string(),
-            JsValue::NativeFunction {
-                name: "return".to_string(),
-                handler: crate::runtime::value::NativeFunction::GeneratorReturn(gen_gc),
-            },
-        );
+        let (methods, iter_sym, iter_name) = if is_async {
+            (
+                [
+                    NativeFunction::AsyncGeneratorNext(gen_gc),
+                    NativeFunction::AsyncGeneratorReturn(gen_gc),
+                    NativeFunction::AsyncGeneratorThrow(gen_gc),
+                ],
+                symbol::symbol_async_iterator(),
+                "[Symbol.asyncIterator]",
+            )
+        } else {
+            (
+                [
+                    NativeFunction::GeneratorNext(gen_gc),
+                    NativeFunction::GeneratorReturn(gen_gc),
+                    NativeFunction::GeneratorThrow(gen_gc),
+                ],
+                symbol::symbol_iterator(),
+                "[Symbol.iterator]",
+            )
+        };
 
-        obj.set(
-            "throw".to_string(),
-            JsValue::NativeFunction {
-                name: "throw".to_string(),
-                handler: crate::runtime::value::NativeFunction::GeneratorThrow,
-            },
-        );
+        let mut obj = JsObject::new();
+        if !is_async {
+            obj.prototype = Some(self.prototypes.iterator);
+        }
+        for (name, handler) in ["next", "return", "throw"].into_iter().zip(methods) {
+            obj.set(
+                name.to_string(),
+                JsValue::NativeFunction {
+                    name: name.to_string(),
+                    handler,
+                },
+            );
+        }
 
-        let iter_sym = symbol::symbol_iterator();
         let obj_gc = self.heap.alloc_cell(obj);
         obj_gc.borrow_mut().set_symbol(
             iter_sym,
             JsValue::NativeFunction {
-                name: "[Symbol.iterator]".to_string(),
-                handler: crate::runtime::value::NativeFunction::GeneratorIterator,
+                name: iter_name.to_string(),
+                handler: NativeFunction::GeneratorIterator,
             },
         );
 
         Ok(JsValue::Object(obj_gc))
     }
 
-    pub(crate) fn step_generator(
-        &mut self,
-        generator: &Gc<GcCell<JsGenerator>>,
-    ) -> Result<JsValue, RuntimeError> {
-        {
-            let g = generator.borrow();
-            if g.state == GeneratorState::Completed && g.yielded_values.is_empty() {
-                return Ok(crate::runtime::value::iterator::iter_result(
-                    g.return_value.clone(),
-                    true,
-                    &mut self.heap,
-                ));
-            }
-        }
-
-        let needs_execute = {
-            let g = generator.borrow();
-            g.state == GeneratorState::SuspendedStart
-        };
-
-        if needs_execute {
-            let (params, body, captured_env, this_binding, args) = {
-                let mut g = generator.borrow_mut();
-                g.state = GeneratorState::Executing;
-                (
-                    g.params.clone(),
-                    g.body.clone(),
-                    g.captured_env.clone(),
-                    g.this_binding.clone(),
-                    g.args.clone(),
-                )
-            };
-
-            let saved_yields = std::mem::take(&mut self.generator_yields);
-            self.generator_depth += 1;
-            let result =
-                self.execute_function_body(&params, &body, &captured_env, this_binding, &args);
-            self.generator_depth -= 1;
-            let yielded = std::mem::replace(&mut self.generator_yields, saved_yields);
-
-            let ret_val = result.unwrap_or(JsValue::Undefined);
-            let mut g = generator.borrow_mut();
-            g.yielded_values = yielded.into();
-            g.return_value = ret_val;
-            g.state = GeneratorState::Completed;
-        }
-
-        let mut g = generator.borrow_mut();
-        if let Some(value) = g.yielded_values.pop_front() {
-            drop(g);
-            Ok(crate::runtime::value::iterator::iter_result(
-                value,
-                false,
-                &mut self.heap,
-            ))
-        } else {
-            let ret = g.return_value.clone();
-            drop(g);
-            Ok(crate::runtime::value::iterator::iter_result(
-                ret,
-                true,
-                &mut self.heap,
-            ))
-        }
-    }
-
     pub(crate) fn construct_native_class(
         &mut self,
         class_name: &str,
@@ -371,12 +469,13 @@ impl Interpreter {
             })?;
 
         let this_obj = self.heap.alloc_cell(JsObject::new());
+        if self.native_class_extends(class_name, "EventTarget") {
+            self.attach_event_target(this_obj);
+        }
         let this = JsValue::Object(this_obj);
-        let fn_args =
-            crate::embedding::function_args::FunctionArgs::new(this.clone(), args.to_vec());
 
         let mut instance = if let Some(constructor) = &class_def.constructor {
-            let result = constructor.call(fn_args)?;
+            let result = self.call_host(constructor, this.clone(), args)?;
             match result {
                 JsValue::Undefined => this,
                 other => other,
@@ -387,6 +486,15 @@ impl Interpreter {
 
         if let JsValue::Object(object) = &mut instance {
             let mut obj = object.borrow_mut();
+            // Lets `instanceof` recognize instances, which carry their
+            // methods directly rather than through a prototype.
+            obj.properties.insert(
+                "[[NativeClass]]".into(),
+                Property {
+                    enumerable: false,
+                    ..Property::new(JsValue::String(class_name.into()))
+                },
+            );
             for (name, callback) in &class_def.methods {
                 obj.set(
                     name.clone(),
@@ -414,8 +522,62 @@ impl Interpreter {
                     },
                 );
             }
+            let hooks = &class_def.coercion;
+            for (name, hook) in [("toString", &hooks.to_string), ("valueOf", &hooks.value_of)] {
+                if let Some(callback) = hook {
+                    let method = JsValue::NativeFunction {
+                        name: name.to_string(),
+                        handler: crate::runtime::value::NativeFunction::Host(callback.clone()),
+                    };
+                    obj.properties.insert(
+                        name.into(),
+                        Property {
+                            enumerable: false,
+                            ..Property::new(method)
+                        },
+                    );
+                }
+            }
+            if let Some(callback) = &hooks.to_primitive {
+                obj.set_symbol(
+                    symbol::symbol_to_primitive(),
+                    JsValue::NativeFunction {
+                        name: "[Symbol.toPrimitive]".to_string(),
+                        handler: crate::runtime::value::NativeFunction::Host(callback.clone()),
+                    },
+                );
+            }
         }
 
         Ok(instance)
     }
 }
+
+/// Top-down merge sort that takes from the right half only when
+/// `greater(left, right)` holds, so equal elements keep their order. Unlike
+/// `slice::sort_by` it tolerates inconsistent comparators.
+fn merge_sort<F>(mut items: Vec<JsValue>, greater: &mut F) -> Result<Vec<JsValue>, RuntimeError>
+where
+    F: FnMut(&JsValue, &JsValue) -> Result<bool, RuntimeError>,
+{
+    if items.len() < 2 {
+        return Ok(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(items, greater)?;
+    let right = merge_sort(right, greater)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        let next = if greater(l, r)? {
+            right.next()
+        } else {
+            left.next()
+        };
+        merged.extend(next);
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
@@ -0,0 +1,126 @@
+use super::Interpreter;
+use crate::runtime::gc::{CollectStats, ErasedGc, GcMode, Heap, PauseHistogram, Trace, Tracer};
+use crate::runtime::value::collections::weak_map::JsWeakMap;
+use crate::runtime::value::collections::weak_set::JsWeakSet;
+
+impl Interpreter {
+    /// Runs a full mark/sweep over the interpreter heap, rooted at the
+    /// global environment, realm prototypes, module exports, pending
+    /// event-loop work, unhandled rejections and values the host has
+    /// persisted.
+    ///
+    /// Scripts ask for a collection through `$262.gc()`, which is
+    /// honoured once the current evaluation returns.
+    ///
+    /// # Safety
+    ///
+    /// Values held only on the Rust stack are not roots, so this must not
+    /// be called while a script is executing, and any value the host
+    /// still uses must be reachable from a root, for instance through a
+    /// `Persistent`.
+    pub unsafe fn collect_garbage(&mut self) -> CollectStats {
+        self.gc_requested = false;
+        let roots = self.gc_roots();
+        self.heap.collect_with(&roots, prune_weak_entries)
+    }
+
+    pub(crate) fn collect_garbage_if_requested(&mut self) {
+        if self.gc_requested {
+            // SAFETY: only a `gc` global requests a collection, and the
+            // host installs one through an unsafe call that accepts the
+            // contract.
+            unsafe { self.collect_garbage() };
+        }
+    }
+
+    /// # Safety
+    ///
+    /// A budgeted mode collects between event-loop tasks, so the contract
+    /// of `collect_garbage` holds across every run of the event loop.
+    pub unsafe fn set_gc_mode(&mut self, mode: GcMode) {
+        self.gc_mode = mode;
+    }
+
+    pub fn gc_pauses(&self) -> PauseHistogram {
+        self.heap.pause_histogram()
+    }
+
+    /// Lets a budgeted `GcMode` do its work. Called by the event loop
+    /// between tasks, where no script values are held on the Rust stack.
+    pub(crate) fn gc_safepoint(&mut self) {
+        let GcMode::Budgeted {
+            budget_micros,
+            full_collect_factor,
+        } = self.gc_mode
+        else {
+            return;
+        };
+        if !self.heap.is_collecting() && !self.heap.should_collect() {
+            return;
+        }
+        if self.heap.exceeds_threshold(full_collect_factor) {
+            // SAFETY: the host accepted the contract when it chose a
+            // budgeted mode, and no script values are on the stack here.
+            unsafe { self.collect_garbage() };
+            return;
+        }
+        let roots = self.gc_roots();
+        self.heap
+            .collect_budgeted_with(&roots, budget_micros, prune_weak_entries);
+    }
+
+    fn gc_roots(&self) -> Vec<ErasedGc> {
+        let mut tracer = Tracer::new();
+        self.env.trace(&mut tracer);
+        for proto in [
+            self.prototypes.object,
+            self.prototypes.function,
+            self.prototypes.array,
+            self.prototypes.string,
+            self.prototypes.number,
+            self.prototypes.boolean,
+            self.prototypes.symbol,
+            self.prototypes.date,
+            self.prototypes.iterator,
+            self.prototypes.event_target,
+        ] {
+            tracer.mark(proto);
+        }
+        self.event_loop.trace(&mut tracer);
+        self.module_cache.trace(&mut tracer);
+        for meta in self.import_metas.values() {
+            tracer.mark(*meta);
+        }
+        self.persistent_roots.trace(&mut tracer);
+        for promise in &self.unhandled_rejections {
+            tracer.mark(*promise);
+        }
+        for class in self.classes.values() {
+            class.constructor.trace(&mut tracer);
+            tracer.mark(class.prototype);
+            for (_, property) in class.private_methods.iter() {
+                property.trace(&mut tracer);
+            }
+            for (_, init) in class.fields.iter() {
+                init.trace(&mut tracer);
+            }
+        }
+        for activation in &self.generator_stack {
+            activation.trace(&mut tracer);
+        }
+        tracer.take_discovered()
+    }
+}
+
+fn prune_weak_entries(heap: &Heap) {
+    for map in heap.cells::<JsWeakMap>() {
+        map.borrow_mut()
+            .entries
+            .retain(|(key, _)| heap.is_marked_erased(key));
+    }
+    for set in heap.cells::<JsWeakSet>() {
+        set.borrow_mut()
+            .entries
+            .retain(|key| heap.is_marked_erased(key));
+    }
+}
@@ -0,0 +1,227 @@
+use super::Interpreter;
+use crate::parser::ast::{ExportDecl, Stmt, VarDeclKind};
+use crate::runtime::environment::BindingKind;
+use crate::runtime::value::JsValue;
+use crate::runtime::value::object::JsObject;
+
+impl Interpreter {
+    /// Declares the `var` names, function declarations and lexical names
+    /// of a program, module or function body in the current scope before
+    /// any of it runs.
+    ///
+    /// Nothing is evaluated: `var` names start out `undefined` and each
+    /// function closes over the scope as it is now, so functions declared
+    /// side by side can call each other whatever their order. A function
+    /// wins over a `var` of the same name until the `var` is assigned.
+    pub(crate) fn hoist_declarations(&mut self, body: &[Stmt]) {
+        let mut names = Vec::new();
+        for stmt in body {
+            collect_var_names(stmt, &mut names);
+        }
+        for name in names {
+            self.env.declare_var(name);
+        }
+        self.hoist_functions(body, BindingKind::Var);
+        self.hoist_lexical(body);
+    }
+
+    /// Binds the function declarations of a block and declares its lexical
+    /// names before it runs. Functions are bound in the block, like `let`,
+    /// which is how strict code treats them; the sloppy-mode copy into the
+    /// enclosing function scope is not made.
+    pub(crate) fn hoist_block_declarations(&mut self, stmts: &[Stmt]) {
+        self.hoist_functions(stmts, BindingKind::Let);
+        self.hoist_lexical(stmts);
+    }
+
+    /// Declares the `let`, `const` and class names `stmts` bind in the
+    /// current scope without a value. Until its declaration runs, reading
+    /// or writing one throws rather than reaching a binding further out,
+    /// which is the temporal dead zone.
+    pub(crate) fn hoist_lexical(&mut self, stmts: &[Stmt]) {
+        let mut names = Vec::new();
+        for stmt in stmts {
+            collect_lexical_names(stmt, &mut names);
+        }
+        for name in names {
+            self.env.declare_uninitialized(name);
+        }
+    }
+
+    /// Binds the function declarations of `stmts`, including those in the
+    /// cases of a `switch`, which share the scope `stmts` run in.
+    fn hoist_functions(&mut self, stmts: &[Stmt], kind: BindingKind) {
+        for stmt in stmts {
+            let decl = match stmt {
+                Stmt::Export(ExportDecl::NamedStmt(inner) | ExportDecl::DefaultStmt(inner)) => {
+                    inner.as_ref()
+                }
+                Stmt::Switch { cases, .. } => {
+                    for case in cases {
+                        self.hoist_functions(&case.body, kind);
+                    }
+                    continue;
+                }
+                other => other,
+            };
+            if let Stmt::FunctionDecl { name, .. } = decl {
+                let func = self.instantiate_function_decl(decl);
+                self.env.define_with_kind(name, func, kind);
+            }
+        }
+    }
+
+    /// Builds the function object for a `Stmt::FunctionDecl`, closing over
+    /// the current scope chain.
+    pub(crate) fn instantiate_function_decl(&mut self, decl: &Stmt) -> JsValue {
+        let Stmt::FunctionDecl {
+            name,
+            params,
+            body,
+            is_async,
+            is_generator,
+            decl_offset,
+        } = decl
+        else {
+            unreachable!("instantiate_function_decl takes a function declaration");
+        };
+        let proto_gc = self.heap.alloc_cell(JsObject::new());
+        let mut fn_props = JsObject::new();
+        fn_props.set("prototype", JsValue::Object(proto_gc));
+        JsValue::Function {
+            name: name.clone(),
+            params: params.as_slice().into(),
+            body: body.clone(),
+            closure_env: self.env.capture(),
+            is_async: *is_async,
+            is_generator: *is_generator,
+            source_path: self.module_stack.last().map(|p| p.display().to_string()),
+            source_offset: *decl_offset,
+            properties: Some(self.heap.alloc_cell(fn_props)),
+        }
+    }
+}
+
+/// Collects the names `stmt` declares with `let`, `const` or `class` in
+/// the scope it runs in. Only the cases of a `switch` share that scope
+/// among nested statements; blocks and loops push their own.
+fn collect_lexical_names(stmt: &Stmt, names: &mut Vec<String>) {
+    match stmt {
+        Stmt::VarDecl { kind, pattern, .. } if *kind != VarDeclKind::Var => {
+            names.extend(pattern.bound_names().into_iter().map(str::to_string));
+        }
+        Stmt::VarDeclList { kind, declarations } if *kind != VarDeclKind::Var => {
+            for (pattern, _) in declarations {
+                names.extend(pattern.bound_names().into_iter().map(str::to_string));
+            }
+        }
+        Stmt::Class(class) => names.push(class.name.clone()),
+        Stmt::Switch { cases, .. } => {
+            for stmt in cases.iter().flat_map(|case| &case.body) {
+                collect_lexical_names(stmt, names);
+            }
+        }
+        Stmt::Export(ExportDecl::NamedStmt(inner) | ExportDecl::DefaultStmt(inner)) => {
+            collect_lexical_names(inner, names)
+        }
+        _ => {}
+    }
+}
+
+/// Whether a `for` loop's head declares its counters with `let`, which
+/// gives each iteration bindings of its own.
+pub(super) fn declares_let(init: &Stmt) -> bool {
+    matches!(
+        init,
+        Stmt::VarDecl {
+            kind: VarDeclKind::Let,
+            ..
+        } | Stmt::VarDeclList {
+            kind: VarDeclKind::Let,
+            ..
+        }
+    )
+}
+
+/// Collects the names declared with `var` in `stmt`, looking through
+/// nested statements but not into functions or classes.
+fn collect_var_names(stmt: &Stmt, names: &mut Vec<String>) {
+    match stmt {
+        Stmt::VarDecl {
+            kind: VarDeclKind::Var,
+            pattern,
+            ..
+        } => names.extend(pattern.bound_names().into_iter().map(str::to_string)),
+        Stmt::VarDeclList {
+            kind: VarDeclKind::Var,
+            declarations,
+        } => {
+            for (pattern, _) in declarations {
+                names.extend(pattern.bound_names().into_iter().map(str::to_string));
+            }
+        }
+        Stmt::Block(stmts) => {
+            for stmt in stmts {
+                collect_var_names(stmt, names);
+            }
+        }
+        Stmt::If {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            collect_var_names(then_branch, names);
+            if let Some(else_branch) = else_branch {
+                collect_var_names(else_branch, names);
+            }
+        }
+        Stmt::ForLoop { init, body, .. } => {
+            if let Some(init) = init {
+                collect_var_names(init, names);
+            }
+            collect_var_names(body, names);
+        }
+        Stmt::ForOf {
+            kind,
+            pattern,
+            body,
+            ..
+        }
+        | Stmt::ForIn {
+            kind,
+            pattern,
+            body,
+            ..
+        } => {
+            if *kind == Some(VarDeclKind::Var) {
+                names.extend(pattern.bound_names().into_iter().map(str::to_string));
+            }
+            collect_var_names(body, names);
+        }
+        Stmt::While { body, .. }
+        | Stmt::DoWhile { body, .. }
+        | Stmt::Labeled { body, .. } => collect_var_names(body, names),
+        Stmt::TryCatch {
+            try_block,
+            catch_block,
+            finally_block,
+            ..
+        } => {
+            let blocks = [
+                Some(try_block),
+                catch_block.as_ref(),
+                finally_block.as_ref(),
+            ];
+            for stmt in blocks.into_iter().flatten().flatten() {
+                collect_var_names(stmt, names);
+            }
+        }
+        Stmt::Switch { cases, .. } => {
+            for stmt in cases.iter().flat_map(|case| &case.body) {
+                collect_var_names(stmt, names);
+            }
+        }
+        Stmt::Export(ExportDecl::NamedStmt(inner)) => collect_var_names(inner, names),
+        _ => {}
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeSet;
+
+use super::Interpreter;
+use crate::runtime::builtins::collections::{MAP_METHODS, SET_METHODS};
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::value::JsValue;
+use crate::runtime::value::array::methods::ARRAY_METHODS;
+use crate::runtime::value::object::{JsObject, is_internal_key};
+use crate::runtime::value::number_methods::NUMBER_METHODS;
+use crate::runtime::value::string_methods::STRING_METHODS;
+use crate::runtime::value::typed_array::{DATA_VIEW_METHODS, TYPED_ARRAY_METHODS};
+
+const MAX_PROTOTYPE_DEPTH: usize = 64;
+
+impl Interpreter {
+    /// Names bound in the global scope, sorted.
+    pub(crate) fn global_binding_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .env
+            .global_names()
+            .into_iter()
+            .filter(|name| !is_internal_key(name))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Resolves a bare identifier chain such as `a.b.c` by reading
+    /// bindings and data properties only, so no script code runs.
+    /// `None` when a segment is missing or sits behind a getter or proxy.
+    pub(crate) fn peek_path(&self, path: &str) -> Option<JsValue> {
+        let mut segments = path.split('.');
+        let mut value = self.env.get(segments.next()?).ok()?;
+        for segment in segments {
+            let start = match &value {
+                JsValue::Object(obj) => Some(*obj),
+                JsValue::Function { properties, .. } => *properties,
+                _ => return None,
+            };
+            value = peek_data_property(start, segment)?;
+        }
+        Some(value)
+    }
+
+    /// Property names `value` exposes: its own and inherited string keys
+    /// plus the built-in methods for its type. Symbols and internal slots
+    /// are left out.
+    pub(crate) fn visible_property_names(&self, value: &JsValue) -> Vec<String> {
+        let mut names = BTreeSet::new();
+        let builtins: &[&str] = match value {
+            JsValue::Array(_) => ARRAY_METHODS,
+            JsValue::String(_) => STRING_METHODS,
+            JsValue::Number(_) => NUMBER_METHODS,
+            JsValue::Map(_) => MAP_METHODS,
+            JsValue::Set(_) => SET_METHODS,
+            JsValue::TypedArray(_) => TYPED_ARRAY_METHODS,
+            JsValue::DataView(_) => DATA_VIEW_METHODS,
+            _ => &[],
+        };
+        names.extend(builtins.iter().map(|name| name.to_string()));
+        match value {
+            JsValue::Array(_) | JsValue::String(_) => {
+                names.insert("length".to_string());
+            }
+            JsValue::Map(_) | JsValue::Set(_) => {
+                names.insert("size".to_string());
+            }
+            JsValue::TypedArray(_) => {
+                names.extend(["buffer", "byteLength", "byteOffset", "length"].map(String::from));
+            }
+            _ => {}
+        }
+
+        let mut current = match value {
+            JsValue::Object(obj) => Some(*obj),
+            JsValue::Function {
+                properties: Some(props),
+                ..
+            } => Some(*props),
+            _ => self.inherited_prototype(value),
+        };
+        // Bounded so a prototype cycle cannot hang the caller.
+        for _ in 0..MAX_PROTOTYPE_DEPTH {
+            let Some(obj) = current else { break };
+            let borrowed = obj.borrow();
+            names.extend(
+                borrowed
+                    .properties
+                    .keys()
+                    .filter(|key| !is_internal_key(key))
+                    .map(|key| key.to_string()),
+            );
+            current = borrowed.prototype;
+        }
+        names.into_iter().collect()
+    }
+}
+
+fn peek_data_property(start: Option<Gc<GcCell<JsObject>>>, key: &str) -> Option<JsValue> {
+    let mut current = start;
+    for _ in 0..MAX_PROTOTYPE_DEPTH {
+        let obj = current?;
+        let borrowed = obj.borrow();
+        if let Some(prop) = borrowed.properties.get(key) {
+            return prop.getter.is_none().then(|| prop.value.clone());
+        }
+        current = borrowed.prototype;
+    }
+    None
+}
@@ -1,29 +1,66 @@
+mod class_elements;
 mod dispatch;
 mod error_handling;
 mod eval_async;
 mod eval_class;
 mod eval_expr;
 mod eval_expr_helpers;
+mod eval_generator;
+mod eval_literals;
 mod eval_pattern;
 mod eval_stmt;
 mod eval_stmt_control;
 mod event_loop_driver;
+mod for_in;
 mod function_call;
+mod garbage_collection;
+mod hoisting;
+mod introspection;
 mod module_runtime;
+mod profiling;
 mod property_access;
+mod strict_writes;
+mod tracing;
+mod yield_hook;
 
+pub(crate) use class_elements::FieldKey;
+pub(crate) use eval_class::RuntimeClass;
+pub use module_runtime::{ModuleImport, ModuleInfo};
+pub(crate) use property_access::PropKey;
+pub use yield_hook::{YieldCallback, YieldCtx, YieldDecision};
+
+use crate::embedding::callback::NativeFunctionBoxed;
 use crate::embedding::class_builder::NativeClassDef;
+use crate::embedding::context_data::ContextData;
+use crate::embedding::handle::PersistentRoots;
+use crate::embedding::object_builder::JsObjectBuilder;
+#[cfg(feature = "profiling")]
+use crate::diagnostics::profile::Profile;
 use crate::diagnostics::source_map::{SourceMap, SourcePos};
-use crate::diagnostics::stack_trace::CallStack;
-use crate::errors::RuntimeError;
-use crate::parser::ast::Program;
+use crate::diagnostics::stack_trace::{CallFrame, CallStack};
+use crate::diagnostics::trace::Tracer;
+use crate::errors::{RuntimeError, SyntaxError};
+use crate::lexer::token::Token;
+use crate::parser::ast::{Program, Stmt};
+use crate::runtime::builtins::prototypes::RealmPrototypes;
 use crate::runtime::environment::Environment;
 use crate::runtime::event_loop::EventLoop;
-use crate::runtime::gc::Heap;
+#[cfg(feature = "http")]
+use crate::runtime::fetch::{FetchPolicy, FetchQueue};
+use crate::runtime::gc::{Gc, GcCell, GcMode, Heap};
+#[cfg(feature = "ast-serde")]
+use crate::runtime::modules::ast_cache::{AstCache, AstCacheStats};
 use crate::runtime::modules::cache::ModuleCache;
+use crate::runtime::output::OutputMode;
+use crate::runtime::report::RunEvent;
+use crate::runtime::value::JsValue;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::promise::JsPromise;
 use crate::runtime::value::symbol::SymbolRegistry;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Instant;
 
 pub(crate) enum ControlFlow {
@@ -37,24 +74,108 @@ pub struct Interpreter {
     pub(crate) env: Environment,
     pub(crate) heap: Heap,
     pub(crate) output: Vec<String>,
+    pub(crate) output_mode: OutputMode,
     pub(crate) classes: HashMap<String, eval_class::RuntimeClass>,
     pub(crate) native_classes: HashMap<String, NativeClassDef>,
-    pub(crate) super_stack: Vec<Option<String>>,
+    /// Host functions by the global name they were registered under, for
+    /// `NativeFunction::HostRef` to find.
+    pub(crate) host_functions: HashMap<String, NativeFunctionBoxed>,
+    /// Shared with each `FunctionArgs` a host function is called with,
+    /// which holds it while the function has the interpreter.
+    pub(crate) context_data: Rc<ContextData>,
+    pub(crate) persistent_roots: PersistentRoots,
+    /// The classes whose constructors are running, innermost last, for
+    /// `super()` to find the parent of.
+    pub(crate) super_stack: Vec<String>,
     pub(crate) event_loop: EventLoop,
     pub(crate) async_depth: usize,
-    pub(crate) generator_depth: usize,
-    pub(crate) generator_yields: Vec<crate::runtime::value::JsValue>,
+    pub(crate) generator_stack: Vec<eval_generator::GeneratorActivation>,
     pub(crate) module_cache: ModuleCache,
+    pub(crate) module_graph: module_runtime::ModuleGraph,
     pub(crate) module_stack: Vec<PathBuf>,
     pub(crate) call_stack: CallStack,
+    /// Innermost function frame the error now unwinding was raised in.
+    pub(crate) error_frame: Option<CallFrame>,
+    /// Frames that error unwound through, kept apart from its message.
+    pub(crate) error_trace: Option<String>,
     pub(crate) source_maps: HashMap<String, SourceMap>,
     pub(crate) start_time: Instant,
     pub(crate) symbol_registry: SymbolRegistry,
+    pub(crate) prototypes: RealmPrototypes,
     pub(crate) call_depth: usize,
+    pub(crate) max_call_depth: usize,
+    pub(crate) microtask_limit: usize,
     pub(crate) step_count: usize,
     pub(crate) max_steps: Option<usize>,
+    pub(crate) yield_hook: Option<yield_hook::YieldHook>,
+    /// Set by a yield hook; due timers fire at the next statement.
+    pub(crate) timers_requested: bool,
+    pub(crate) gc_requested: bool,
+    pub(crate) gc_mode: GcMode,
+    pub(crate) error_hook: Option<ErrorHook>,
+    pub(crate) uncaught_exception_hook: Option<ErrorHook>,
+    pub(crate) module_resolver: Option<ModuleResolver>,
+    pub(crate) import_meta_provider: Option<ImportMetaProvider>,
+    /// Handlers for the `type` import attributes other than `"json"`.
+    pub(crate) import_type_handlers: HashMap<String, ImportTypeHandler>,
+    /// The `import.meta` object of each module that has read it.
+    pub(crate) import_metas: HashMap<PathBuf, Gc<GcCell<JsObject>>>,
+    pub(crate) diagnostics_hook: Option<DiagnosticsHook>,
+    #[cfg(feature = "ast-serde")]
+    pub(crate) ast_cache: Option<AstCache>,
+    /// Requests started by `fetch` that have not been settled yet.
+    #[cfg(feature = "http")]
+    pub(crate) fetches: FetchQueue,
+    /// Scripts and modules are parsed as strict mode code.
+    pub(crate) strict: bool,
+    /// Function bodies are parsed on first call rather than up front.
+    pub(crate) lazy_functions: bool,
+    /// Timers only fire through `advance_time`; `run` leaves them queued.
+    pub(crate) manual_timers: bool,
+    /// Filled in while `run_collect` is watching the script.
+    pub(crate) run_events: Option<Vec<RunEvent>>,
+    /// Promises rejected while nothing was listening for the rejection.
+    pub(crate) unhandled_rejections: Vec<Gc<GcCell<JsPromise>>>,
+    /// Arrays being joined, so that an array reached again through its own
+    /// elements joins as an empty string instead of recursing forever.
+    pub(crate) joining: HashSet<usize>,
+    pub(crate) trace: Option<Tracer>,
+    #[cfg(feature = "profiling")]
+    pub(crate) profile: Profile,
 }
 
+/// Receives errors that escape host-driven callbacks, such as a throwing
+/// event listener, instead of them aborting the dispatch.
+pub type ErrorHook = Arc<dyn Fn(&RuntimeError) + Send + Sync>;
+
+/// Maps an import specifier and the path of the importing module to the
+/// file to load, in place of the default relative-path resolution.
+pub type ModuleResolver = Arc<dyn Fn(&str, &Path) -> PathBuf + Send + Sync>;
+
+/// Loads a module imported `with { type: "..." }` for a type the engine
+/// does not handle itself. It is given the resolved path and every
+/// attribute of the import, and sets the module's exports on the builder
+/// or returns why it could not.
+pub type ImportTypeHandler = Arc<
+    dyn Fn(&Path, &[(String, String)], &mut JsObjectBuilder) -> Result<(), String> + Send + Sync,
+>;
+
+/// Adds host-defined properties to the `import.meta` of the module at
+/// the given path.
+pub type ImportMetaProvider = Arc<dyn Fn(&Path, &mut JsObjectBuilder) + Send + Sync>;
+
+/// Receives notes about things the engine worked around without failing,
+/// such as a corrupt AST cache entry.
+pub type DiagnosticsHook = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Nested calls allowed before a call throws "Maximum call stack size
+/// exceeded".
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 256;
+
+/// Microtasks run in one drain of the queue before the engine gives up on
+/// it as an endless microtask loop.
+pub const DEFAULT_MICROTASK_LIMIT: usize = 100_000;
+
 impl Default for Interpreter {
     fn default() -> Self {
         Self::new()
@@ -69,32 +190,69 @@ impl Interpreter {
     pub fn new_with_realtime_timers(realtime_timers: bool) -> Self {
         let mut heap = Heap::new();
         let env = Environment::new(&mut heap);
+        let prototypes = RealmPrototypes::new(&mut heap);
         let mut interp = Self {
             env,
             heap,
             output: Vec::new(),
+            output_mode: OutputMode::Both,
             classes: HashMap::new(),
             native_classes: HashMap::new(),
+            host_functions: HashMap::new(),
+            context_data: Rc::default(),
+            persistent_roots: PersistentRoots::default(),
             super_stack: Vec::new(),
             event_loop: EventLoop::new_with_realtime(realtime_timers),
             async_depth: 0,
-            generator_depth: 0,
-            generator_yields: Vec::new(),
+            generator_stack: Vec::new(),
             module_cache: ModuleCache::default(),
+            module_graph: module_runtime::ModuleGraph::default(),
             module_stack: Vec::new(),
             call_stack: CallStack::default(),
+            error_frame: None,
+            error_trace: None,
             source_maps: HashMap::new(),
             start_time: Instant::now(),
             symbol_registry: SymbolRegistry::new(),
+            prototypes,
             call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            microtask_limit: DEFAULT_MICROTASK_LIMIT,
             step_count: 0,
             max_steps: None,
+            yield_hook: None,
+            timers_requested: false,
+            gc_requested: false,
+            gc_mode: GcMode::Manual,
+            error_hook: None,
+            uncaught_exception_hook: None,
+            module_resolver: None,
+            import_meta_provider: None,
+            import_type_handlers: HashMap::new(),
+            import_metas: HashMap::new(),
+            diagnostics_hook: None,
+            #[cfg(feature = "ast-serde")]
+            ast_cache: None,
+            #[cfg(feature = "http")]
+            fetches: FetchQueue::default(),
+            strict: false,
+            lazy_functions: true,
+            manual_timers: false,
+            run_events: None,
+            unhandled_rejections: Vec::new(),
+            joining: HashSet::new(),
+            trace: None,
+            #[cfg(feature = "profiling")]
+            profile: Profile::default(),
         };
         interp.init_builtins();
         interp
     }
 
     pub fn run(&mut self, program: &Program) -> Result<(), RuntimeError> {
+        self.error_frame = None;
+        self.error_trace = None;
+        self.hoist_declarations(&program.body);
         for stmt in &program.body {
             if let ControlFlow::Return(_) = self.eval_stmt(stmt)? {
                 break;
@@ -104,20 +262,189 @@ impl Interpreter {
         Ok(())
     }
 
+    /// Like `run`, but yields the value of the last top-level expression
+    /// statement, the way a REPL or `eval` reports a script's result.
+    pub fn run_for_value(&mut self, program: &Program) -> Result<JsValue, RuntimeError> {
+        let completion = self.eval_program_for_value(program)?;
+        // The event loop may collect between tasks.
+        let completion = self.persistent_roots.persist(completion);
+        self.run_event_loop_until_idle()?;
+        Ok(completion.get())
+    }
+
+    /// Like `run_for_value`, but waits for timers by awaiting on the
+    /// tokio runtime that polls it, so other work on that runtime's
+    /// thread carries on in the meantime.
+    pub async fn run_for_value_async(
+        &mut self,
+        program: &Program,
+    ) -> Result<JsValue, RuntimeError> {
+        let completion = self.eval_program_for_value(program)?;
+        let completion = self.persistent_roots.persist(completion);
+        self.run_event_loop_async().await?;
+        Ok(completion.get())
+    }
+
+    /// Runs the statements of `program`, leaving the event loop alone,
+    /// and yields the value of the last expression statement.
+    fn eval_program_for_value(&mut self, program: &Program) -> Result<JsValue, RuntimeError> {
+        self.error_frame = None;
+        self.error_trace = None;
+        self.hoist_declarations(&program.body);
+        let mut completion = JsValue::Undefined;
+        for stmt in &program.body {
+            if let Stmt::ExprStmt(expr) = stmt {
+                self.check_step_limit()?;
+                completion = self.eval_expr(expr)?;
+                continue;
+            }
+            if let ControlFlow::Return(_) = self.eval_stmt(stmt)? {
+                break;
+            }
+        }
+        Ok(completion)
+    }
+
+    pub fn set_output_mode(&mut self, mode: OutputMode) {
+        self.output_mode = mode;
+    }
+
+    pub fn set_error_hook(&mut self, hook: ErrorHook) {
+        self.error_hook = Some(hook);
+    }
+
+    /// Installs a hook for errors thrown by timer and animation-frame
+    /// callbacks. The event loop carries on after each one either way;
+    /// without a hook they are written to the output.
+    pub fn on_uncaught_exception(&mut self, hook: ErrorHook) {
+        self.uncaught_exception_hook = Some(hook);
+    }
+
+    /// Hands `err` to the error hook, or gives it back when none is set.
+    pub(crate) fn report_error(&mut self, err: RuntimeError) -> Result<(), RuntimeError> {
+        match &self.error_hook {
+            Some(hook) => {
+                hook(&err);
+                Ok(())
+            }
+            None => Err(err),
+        }
+    }
+
     pub fn set_max_steps(&mut self, max: usize) {
         self.max_steps = Some(max);
     }
 
+    pub fn set_max_call_depth(&mut self, max: usize) {
+        self.max_call_depth = max;
+    }
+
+    /// Caps the microtasks run in one drain of the queue, so a microtask
+    /// that keeps queueing another cannot wedge the engine. Going over it
+    /// raises `RuntimeError::MicrotaskLimitExceeded`.
+    pub fn set_microtask_limit(&mut self, max: usize) {
+        self.microtask_limit = max;
+    }
+
+    /// Parses every script and module as strict mode code. When off, a
+    /// `"use strict"` directive still opts a file in.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Parses function bodies when first called, the default, or along
+    /// with the rest of the file. Lazy bodies load faster but only report
+    /// their syntax errors once they run.
+    pub fn set_lazy_functions(&mut self, lazy: bool) {
+        self.lazy_functions = lazy;
+    }
+
+    pub fn set_module_resolver(&mut self, resolver: ModuleResolver) {
+        self.module_resolver = Some(resolver);
+    }
+
+    /// Lets the host add properties to each module's `import.meta` before
+    /// it is frozen.
+    pub fn set_import_meta_provider(&mut self, provider: ImportMetaProvider) {
+        self.import_meta_provider = Some(provider);
+    }
+
+    /// Loads modules imported `with { type: import_type }` with `handler`.
+    /// `"json"` is always handled by the engine.
+    pub fn set_import_type_handler(
+        &mut self,
+        import_type: impl Into<String>,
+        handler: ImportTypeHandler,
+    ) {
+        self.import_type_handlers
+            .insert(import_type.into(), handler);
+    }
+
+    pub fn set_diagnostics_hook(&mut self, hook: DiagnosticsHook) {
+        self.diagnostics_hook = Some(hook);
+    }
+
+    #[cfg(feature = "ast-serde")]
+    pub(crate) fn diagnostic(&self, message: &str) {
+        if let Some(hook) = &self.diagnostics_hook {
+            hook(message);
+        }
+    }
+
+    /// Keeps parsed modules in `dir` so later runs skip lexing and
+    /// parsing files that have not changed.
+    #[cfg(feature = "ast-serde")]
+    pub fn set_ast_cache_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.ast_cache = Some(AstCache::new(dir));
+    }
+
+    #[cfg(feature = "ast-serde")]
+    pub fn ast_cache_stats(&self) -> Option<AstCacheStats> {
+        self.ast_cache.as_ref().map(AstCache::stats)
+    }
+
+    /// Limits the hosts `fetch` may reach, or turns it off. Requests
+    /// already started are left to finish.
+    #[cfg(feature = "http")]
+    pub fn set_fetch_policy(&mut self, policy: FetchPolicy) {
+        self.fetches.policy = policy;
+    }
+
+    /// Parses `tokens`, lexed from `source`, in this interpreter's
+    /// strictness and laziness.
+    pub(crate) fn parse_tokens(
+        &self,
+        tokens: Vec<Token>,
+        source: &str,
+    ) -> Result<Program, SyntaxError> {
+        crate::parser::parse_source_tokens(tokens, source, self.strict, self.lazy_functions, false)
+    }
+
+    /// [`Self::parse_tokens`] for module code, where `import.meta` may
+    /// appear.
+    pub(crate) fn parse_module_tokens(
+        &self,
+        tokens: Vec<Token>,
+        source: &str,
+    ) -> Result<Program, SyntaxError> {
+        crate::parser::parse_source_tokens(tokens, source, self.strict, self.lazy_functions, true)
+    }
+
+    /// Stops `run` from firing timers, so a test can step the virtual
+    /// clock itself with `advance_time`.
+    pub fn set_manual_timers(&mut self, manual: bool) {
+        self.manual_timers = manual;
+        self.event_loop.set_manual(manual);
+    }
+
     pub(crate) fn check_step_limit(&mut self) -> Result<(), RuntimeError> {
         self.step_count += 1;
         if let Some(max) = self.max_steps {
             if self.step_count > max {
-                return Err(RuntimeError::TypeError {
-                    message: "execution step limit exceeded (possible infinite loop)".into(),
-                });
+                return Err(RuntimeError::StepLimitExceeded);
             }
         }
-        Ok(())
+        self.poll_yield_hook()
     }
 
     pub fn run_with_path(
@@ -127,6 +454,8 @@ impl Interpreter {
     ) -> Result<(), RuntimeError> {
         let file = path.display().to_string();
         self.ensure_source_map_for_path(&path);
+        self.module_graph.record_entry(&path);
+        let started = Instant::now();
         self.module_stack.push(path);
         self.call_stack
             .push_frame(crate::diagnostics::stack_trace::CallFrame {
@@ -140,10 +469,21 @@ impl Interpreter {
             self.attach_stack_to_error(err, &trace)
         });
         self.call_stack.pop_frame();
-        self.module_stack.pop();
+        if let Some(path) = self.module_stack.pop()
+            && out.is_ok()
+        {
+            self.module_graph.record_evaluated(&path, started);
+        }
         out
     }
 
+    /// Appends `event` to the run log when one is being kept.
+    pub(crate) fn record_event(&mut self, event: RunEvent) {
+        if let Some(events) = &mut self.run_events {
+            events.push(event);
+        }
+    }
+
     pub fn output(&self) -> &[String] {
         &self.output
     }
@@ -152,6 +492,26 @@ impl Interpreter {
         self.call_stack.format_trace()
     }
 
+    /// The function the last uncaught error was raised in, or `None` when
+    /// it came from top-level code.
+    pub fn error_location(&self) -> Option<&CallFrame> {
+        self.error_frame.as_ref()
+    }
+
+    /// The stack trace of the last uncaught error, innermost frame first.
+    /// Thrown error objects carry theirs in `stack` instead.
+    pub fn error_trace(&self) -> Option<&str> {
+        self.error_trace.as_deref()
+    }
+
+    /// Paths of every source file loaded so far: the entry script and any
+    /// modules it imported.
+    pub fn loaded_source_paths(&self) -> Vec<std::path::PathBuf> {
+        let mut paths: Vec<_> = self.source_maps.keys().map(Into::into).collect();
+        paths.sort();
+        paths
+    }
+
     pub(crate) fn register_source_map(&mut self, path: &std::path::Path, source: &str) {
         self.source_maps
             .insert(path.display().to_string(), SourceMap::from_source(source));
@@ -1,13 +1,104 @@
 use super::{ControlFlow, Interpreter};
 use crate::diagnostics::source_map::SourceMap;
 use crate::errors::RuntimeError;
-use crate::parser::ast::{ExportDecl, ImportSpecifier, Pattern, Stmt};
-use crate::runtime::modules::resolver;
-use crate::runtime::value::JsValue;
+use crate::parser::ast::{ExportDecl, ImportSpecifier, Pattern, Program, Stmt};
+use crate::runtime::modules::{cache, resolver};
+use crate::embedding::object_builder::JsObjectBuilder;
+use crate::runtime::value::object::{JsObject, is_internal_key};
+use crate::runtime::value::{JsValue, NativeFunction};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Binds the path of an imported module in its scope, for `import.meta`
+/// to tell which module the code reading it belongs to.
+const IMPORT_META_PATH: &str = "[[ImportMetaPath]]";
+
+/// A module the interpreter loaded, as [`Interpreter::module_graph`]
+/// reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleInfo {
+    pub path: PathBuf,
+    /// Every import statement the module ran, in order, including ones
+    /// of modules that were already loaded.
+    pub imports: Vec<ModuleImport>,
+    /// Position among the modules whose evaluation finished, counting
+    /// from 0. A module finishes after everything it imports, so the
+    /// entry script comes last. `None` when it threw or is still running.
+    pub evaluation_order: Option<usize>,
+    /// How long evaluating the module took, including the modules it
+    /// loaded for the first time. `None` when it did not finish.
+    pub duration: Option<Duration>,
+}
+
+/// One edge of the module graph: an import and the module it resolved
+/// to, whose `path` is that module's [`ModuleInfo::path`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleImport {
+    pub specifier: String,
+    pub path: PathBuf,
+}
+
+/// The modules loaded so far in the order they were first seen, recorded
+/// next to the [`cache::ModuleCache`] that keeps their exports.
+#[derive(Default)]
+pub(crate) struct ModuleGraph {
+    modules: Vec<ModuleInfo>,
+    /// Index into `modules` by [`resolver::module_key`].
+    indices: HashMap<String, usize>,
+    evaluated: usize,
+}
+
+impl ModuleGraph {
+    fn index_of(&mut self, path: &Path) -> usize {
+        let key = resolver::module_key(path);
+        if let Some(&index) = self.indices.get(&key) {
+            return index;
+        }
+        self.modules.push(ModuleInfo {
+            path: path.to_path_buf(),
+            imports: Vec::new(),
+            evaluation_order: None,
+            duration: None,
+        });
+        self.indices.insert(key, self.modules.len() - 1);
+        self.modules.len() - 1
+    }
+
+    fn record_import(&mut self, from: &Path, specifier: &str, path: &Path) {
+        let to = self.index_of(path);
+        let path = self.modules[to].path.clone();
+        let from = self.index_of(from);
+        self.modules[from].imports.push(ModuleImport {
+            specifier: specifier.to_string(),
+            path,
+        });
+    }
+
+    /// Marks the module at `path` as evaluated in the time since
+    /// `started`.
+    pub(crate) fn record_evaluated(&mut self, path: &Path, started: Instant) {
+        let index = self.index_of(path);
+        let module = &mut self.modules[index];
+        module.evaluation_order = Some(self.evaluated);
+        module.duration = Some(started.elapsed());
+        self.evaluated += 1;
+    }
+
+    /// Adds the module at `path` before it runs, so the entry script
+    /// leads the list.
+    pub(crate) fn record_entry(&mut self, path: &Path) {
+        self.index_of(path);
+    }
+}
 
 impl Interpreter {
+    /// Every module loaded so far, the entry script first, with the
+    /// imports each ran and the order they finished evaluating in.
+    pub fn module_graph(&self) -> Vec<ModuleInfo> {
+        self.module_graph.modules.clone()
+    }
+
     pub(crate) fn eval_import_stmt(
         &mut self,
         decl: &crate::parser::ast::ImportDecl,
@@ -17,8 +108,10 @@ impl Interpreter {
             .last()
             .cloned()
             .unwrap_or_else(|| PathBuf::from("."));
-        let path = resolver::resolve(&decl.source, &current);
-        let exports = self.load_module_exports(path)?;
+        let path = self.resolve_module_path(&decl.source, &current)?;
+        self.module_graph
+            .record_import(&current, &decl.source, &path);
+        let exports = self.load_import(path, &decl.attributes)?;
 
         for spec in &decl.specifiers {
             match spec {
@@ -27,11 +120,11 @@ impl Interpreter {
                         .get("default")
                         .cloned()
                         .unwrap_or(JsValue::Undefined);
-                    self.env.define(local.clone(), value);
+                    self.env.define(local, value);
                 }
                 ImportSpecifier::Named { imported, local } => {
                     let value = exports.get(imported).cloned().unwrap_or(JsValue::Undefined);
-                    self.env.define(local.clone(), value);
+                    self.env.define(local, value);
                 }
                 ImportSpecifier::Namespace(local) => {
                     let mut obj = crate::runtime::value::object::JsObject::new();
@@ -39,7 +132,7 @@ impl Interpreter {
                         obj.set(k.clone(), v.clone());
                     }
                     self.env
-                        .define(local.clone(), JsValue::Object(self.heap.alloc_cell(obj)));
+                        .define(local, JsValue::Object(self.heap.alloc_cell(obj)));
                 }
             }
         }
@@ -47,6 +140,56 @@ impl Interpreter {
         Ok(ControlFlow::None)
     }
 
+    /// The file an import of `specifier` from the module at `from` loads,
+    /// as the embedder's resolver or else [`resolver::resolve`] finds it.
+    pub(crate) fn resolve_module_path(
+        &self,
+        specifier: &str,
+        from: &Path,
+    ) -> Result<PathBuf, RuntimeError> {
+        match &self.module_resolver {
+            Some(resolve) => Ok(resolve(specifier, from)),
+            None => resolver::resolve(specifier, from).map_err(|err| RuntimeError::TypeError {
+                message: err.to_string(),
+            }),
+        }
+    }
+
+    /// `import.meta`: made for a module the first time its code reads it
+    /// and frozen once the host has added its properties. Code outside
+    /// any imported module belongs to the entry module.
+    pub(crate) fn eval_import_meta(&mut self) -> Result<JsValue, RuntimeError> {
+        let path = match self.env.get(IMPORT_META_PATH) {
+            Ok(JsValue::String(path)) => PathBuf::from(path.as_str()),
+            _ => match self.module_stack.first() {
+                Some(path) => path.clone(),
+                None => {
+                    return Err(self.throw_type_error("import.meta is only available in a module"));
+                }
+            },
+        };
+        if let Some(meta) = self.import_metas.get(&path) {
+            return Ok(JsValue::Object(*meta));
+        }
+
+        let mut meta = JsObject::new();
+        meta.set("url", JsValue::String(resolver::file_url(&path).into()));
+        meta.set(
+            "resolve",
+            JsValue::NativeFunction {
+                name: "resolve".to_string(),
+                handler: NativeFunction::ImportMetaResolve(path.clone()),
+            },
+        );
+        if let Some(provider) = self.import_meta_provider.clone() {
+            provider(&path, &mut JsObjectBuilder::new(&mut meta));
+        }
+        meta.freeze();
+        let meta = self.heap.alloc_cell(meta);
+        self.import_metas.insert(path, meta);
+        Ok(JsValue::Object(meta))
+    }
+
     pub(crate) fn eval_export_stmt(
         &mut self,
         decl: &ExportDecl,
@@ -55,7 +198,7 @@ impl Interpreter {
             ExportDecl::NamedStmt(stmt) => self.eval_stmt(stmt),
             ExportDecl::Default(expr) => {
                 let value = self.eval_expr(expr)?;
-                self.env.define("__default_export".to_string(), value);
+                self.env.define("__default_export", value);
                 Ok(ControlFlow::None)
             }
             ExportDecl::DefaultStmt(stmt) => {
@@ -69,7 +212,7 @@ impl Interpreter {
                     }
                 };
                 let value = self.env.get(&name)?;
-                self.env.define("__default_export".to_string(), value);
+                self.env.define("__default_export", value);
                 Ok(ControlFlow::None)
             }
             ExportDecl::NamedList(specs) => {
@@ -83,17 +226,108 @@ impl Interpreter {
         }
     }
 
+    /// The exports of the module at `path` as imported with `attributes`.
+    /// Without a `type` it is a script module; a `.json` file has to be
+    /// imported with `type: "json"`.
+    fn load_import(
+        &mut self,
+        path: PathBuf,
+        attributes: &[(String, String)],
+    ) -> Result<HashMap<String, JsValue>, RuntimeError> {
+        let import_type = attributes
+            .iter()
+            .find(|(key, _)| key == "type")
+            .map(|(_, value)| value.as_str());
+        let Some(import_type) = import_type else {
+            if let Some((key, _)) = attributes.first() {
+                return Err(unsupported_attribute(key, &path));
+            }
+            if path.extension().is_some_and(|ext| ext == "json") {
+                return Err(RuntimeError::TypeError {
+                    message: format!(
+                        "'{}' is a JSON module and has to be imported with {{ type: \"json\" }}",
+                        path.display()
+                    ),
+                });
+            }
+            return self.load_module_exports(path);
+        };
+
+        // The same file imported with other attributes is another module.
+        let key = cache::key_with_attributes(resolver::module_key(&path), attributes);
+        if let Some(cached) = self.module_cache.get(&key) {
+            return Ok(cached);
+        }
+        let started = Instant::now();
+        let exports = match import_type {
+            "json" => {
+                if let Some((key, _)) = attributes.iter().find(|(key, _)| key != "type") {
+                    return Err(unsupported_attribute(key, &path));
+                }
+                self.load_json_module(&path)?
+            }
+            other => {
+                let Some(handler) = self.import_type_handlers.get(other).cloned() else {
+                    return Err(RuntimeError::TypeError {
+                        message: format!(
+                            "unsupported import attribute type: \"{other}\" in import of '{}'",
+                            path.display()
+                        ),
+                    });
+                };
+                let mut namespace = JsObject::new();
+                handler(&path, attributes, &mut JsObjectBuilder::new(&mut namespace)).map_err(
+                    |message| RuntimeError::TypeError {
+                        message: format!(
+                            "failed to load '{}' as {other}: {message}",
+                            path.display()
+                        ),
+                    },
+                )?;
+                namespace
+                    .properties
+                    .into_iter()
+                    .map(|(name, prop)| (name.to_string(), prop.value))
+                    .collect()
+            }
+        };
+        self.module_graph.record_evaluated(&path, started);
+        self.module_cache.insert(key, exports.clone());
+        Ok(exports)
+    }
+
+    /// A JSON module: the parsed file, frozen all the way down, as the
+    /// default export and nothing else.
+    fn load_json_module(&mut self, path: &Path) -> Result<HashMap<String, JsValue>, RuntimeError> {
+        let source = std::fs::read_to_string(path).map_err(|e| RuntimeError::TypeError {
+            message: format!("failed to read module '{}': {e}", path.display()),
+        })?;
+        let parsed: serde_json::Value =
+            serde_json::from_str(&source).map_err(|e| RuntimeError::TypeError {
+                message: format!("failed to parse JSON module '{}': {e}", path.display()),
+            })?;
+        let value = self.from_json_value(&parsed);
+        deep_freeze(&value);
+        Ok(HashMap::from([("default".to_string(), value)]))
+    }
+
     fn load_module_exports(
         &mut self,
         path: PathBuf,
     ) -> Result<HashMap<String, JsValue>, RuntimeError> {
-        if self.module_stack.iter().any(|p| p == &path) {
+        // Equivalent paths, say with different separators or case, name
+        // the same module.
+        let key = resolver::module_key(&path);
+        if self
+            .module_stack
+            .iter()
+            .any(|p| resolver::module_key(p) == key)
+        {
             return Err(RuntimeError::TypeError {
                 message: format!("circular import detected for '{}'", path.display()),
             });
         }
 
-        let key = path.to_string_lossy().to_string();
         if let Some(cached) = self.module_cache.get(&key) {
             return Ok(cached);
         }
@@ -102,15 +336,30 @@ impl Interpreter {
             message: format!("failed to read module '{}': {e}", path.display()),
         })?;
         self.register_source_map(&path, &source);
-        let tokens = crate::lexer::lex(&source).map_err(|e| RuntimeError::TypeError {
-            message: Self::format_syntax_error(&path, &source, "lex", &e),
-        })?;
-        let program = crate::parser::parse(tokens).map_err(|e| RuntimeError::TypeError {
-            message: Self::format_syntax_error(&path, &source, "parse", &e),
-        })?;
+        let program = match self.cached_program(&source) {
+            Some(program) => program,
+            None => {
+                let tokens = crate::lexer::lex(&source).map_err(|e| RuntimeError::TypeError {
+                    message: Self::format_syntax_error(&path, &source, "lex", &e),
+                })?;
+                let program = self.parse_module_tokens(tokens, &source).map_err(|e| {
+                    RuntimeError::TypeError {
+                        message: Self::format_syntax_error(&path, &source, "parse", &e),
+                    }
+                })?;
+                self.cache_program(&source, &program);
+                program
+            }
+        };
 
+        let started = Instant::now();
         self.module_stack.push(path.clone());
         self.env.push_scope(&mut self.heap);
+        self.env.define(
+            IMPORT_META_PATH,
+            JsValue::String(path.to_string_lossy().as_ref().into()),
+        );
+        self.hoist_declarations(&program.body);
         for stmt in &program.body {
             self.eval_stmt(stmt)?;
         }
@@ -119,21 +368,53 @@ impl Interpreter {
         let scope_bindings = self.env.current_scope_bindings_snapshot();
 
         for (name, binding) in scope_bindings {
+            if is_internal_key(&name) {
+                continue;
+            }
             if name == "__default_export" {
                 exports.insert("default".to_string(), binding.value);
             } else if let Some(export_name) = name.strip_prefix("__export_") {
                 exports.insert(export_name.to_string(), binding.value);
             } else {
-                exports.insert(name, binding.value);
+                exports.insert(name.to_string(), binding.value);
             }
         }
 
         self.env.pop_scope();
         self.module_stack.pop();
+        self.module_graph.record_evaluated(&path, started);
         self.module_cache.insert(key, exports.clone());
         Ok(exports)
     }
 
+    #[cfg(feature = "ast-serde")]
+    fn cached_program(&mut self, source: &str) -> Option<Program> {
+        let strict = self.strict;
+        let loaded = self.ast_cache.as_mut()?.load(source, strict);
+        loaded.unwrap_or_else(|reason| {
+            self.diagnostic(&reason);
+            None
+        })
+    }
+
+    #[cfg(not(feature = "ast-serde"))]
+    fn cached_program(&mut self, _source: &str) -> Option<Program> {
+        None
+    }
+
+    #[cfg(feature = "ast-serde")]
+    fn cache_program(&mut self, source: &str, program: &Program) {
+        let Some(cache) = &self.ast_cache else {
+            return;
+        };
+        if let Err(reason) = cache.store(source, self.strict, program) {
+            self.diagnostic(&reason);
+        }
+    }
+
+    #[cfg(not(feature = "ast-serde"))]
+    fn cache_program(&mut self, _source: &str, _program: &Program) {}
+
     pub(crate) fn export_names_from_stmt(stmt: &Stmt) -> Vec<String> {
         match stmt {
             Stmt::FunctionDecl { name, .. } => vec![name.clone()],
@@ -141,6 +422,10 @@ impl Interpreter {
                 pattern: Pattern::Identifier(name),
                 ..
             } => vec![name.clone()],
+            Stmt::VarDeclList { declarations, .. } => declarations
+                .iter()
+                .filter_map(|(pattern, _)| pattern.as_identifier().map(str::to_string))
+                .collect(),
             _ => Vec::new(),
         }
     }
@@ -163,3 +448,34 @@ impl Interpreter {
         )
     }
 }
+
+fn unsupported_attribute(key: &str, path: &Path) -> RuntimeError {
+    RuntimeError::TypeError {
+        message: format!(
+            "unsupported import attribute '{key}' in import of '{}'",
+            path.display()
+        ),
+    }
+}
+
+/// Freezes a value made from JSON and everything in it.
+fn deep_freeze(value: &JsValue) {
+    match value {
+        JsValue::Object(obj) => {
+            let children: Vec<JsValue> = obj
+                .borrow()
+                .properties
+                .values()
+                .map(|p| p.value.clone())
+                .collect();
+            children.iter().for_each(deep_freeze);
+            obj.borrow_mut().freeze();
+        }
+        JsValue::Array(arr) => {
+            let children = arr.borrow().elements.clone();
+            children.iter().for_each(deep_freeze);
+            arr.borrow_mut().freeze();
+        }
+        _ => {}
+    }
+}
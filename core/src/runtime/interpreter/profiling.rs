@@ -0,0 +1,43 @@
+#[cfg(feature = "profiling")]
+use std::time::Instant;
+
+use super::Interpreter;
+#[cfg(feature = "profiling")]
+use crate::diagnostics::profile::Profile;
+
+impl Interpreter {
+    /// Runs the builtin `owner.method`, counting the call and the time it
+    /// took when profiling.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn profiled<T>(
+        &mut self,
+        owner: &str,
+        method: &str,
+        run: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let started = Instant::now();
+        let result = run(self);
+        self.profile.record_builtin(owner, method, started);
+        result
+    }
+
+    #[cfg(not(feature = "profiling"))]
+    #[inline(always)]
+    pub(crate) fn profiled<T>(
+        &mut self,
+        _owner: &str,
+        _method: &str,
+        run: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        run(self)
+    }
+
+    /// The counters gathered since the profile was last taken, with every
+    /// collector pause this interpreter has had.
+    #[cfg(feature = "profiling")]
+    pub fn take_profile(&mut self) -> Profile {
+        let mut profile = std::mem::take(&mut self.profile);
+        profile.gc = self.heap.pause_histogram().into();
+        profile
+    }
+}
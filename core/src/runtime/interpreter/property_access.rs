@@ -1,11 +1,212 @@
 use super::Interpreter;
 use crate::errors::RuntimeError;
-use crate::parser::ast::Expr;
+use crate::parser::ast::{Expr, Literal};
+use crate::runtime::builtins::prototypes::{constructor_static, has_null_prototype};
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::value::array::{JsArray, array_index};
+use crate::runtime::value::object::JsObject;
 use crate::runtime::value::string_methods;
 use crate::runtime::value::symbol::JsSymbol;
-use crate::runtime::value::JsValue;
+use crate::runtime::value::{JsValue, function_length, function_name, number_to_string};
+
+/// A property key after ToPropertyKey: a string or a symbol.
+#[derive(Debug, Clone)]
+pub(crate) enum PropKey {
+    String(String),
+    Symbol(JsSymbol),
+}
+
+impl PropKey {
+    /// The key as a script value, as proxy traps receive it.
+    pub(crate) fn to_value(&self) -> JsValue {
+        match self {
+            PropKey::String(key) => JsValue::String(key.as_str().into()),
+            PropKey::Symbol(sym) => JsValue::Symbol(sym.clone()),
+        }
+    }
+
+    /// The key as error messages show it.
+    pub(crate) fn display(&self) -> String {
+        match self {
+            PropKey::String(key) => key.clone(),
+            PropKey::Symbol(sym) => sym.to_string(),
+        }
+    }
+}
 
 impl Interpreter {
+    /// ToPropertyKey: symbols stay symbols, numbers take their canonical
+    /// string form (so `-0` is `"0"`), and objects are converted with a
+    /// string hint first.
+    pub(crate) fn property_key(&mut self, value: &JsValue) -> Result<PropKey, RuntimeError> {
+        let primitive = match value {
+            JsValue::Undefined
+            | JsValue::Null
+            | JsValue::Boolean(_)
+            | JsValue::Number(_)
+            | JsValue::String(_)
+            | JsValue::Symbol(_) => value.clone(),
+            _ => self.to_primitive(value, "string")?,
+        };
+        Ok(match primitive {
+            JsValue::Symbol(sym) => PropKey::Symbol(sym),
+            JsValue::String(key) => PropKey::String(key.as_str().to_owned()),
+            JsValue::Number(n) => PropKey::String(number_to_string(n)),
+            other => PropKey::String(other.to_js_string()),
+        })
+    }
+
+    pub(crate) fn get_keyed(
+        &mut self,
+        obj_val: &JsValue,
+        key: &PropKey,
+    ) -> Result<JsValue, RuntimeError> {
+        match key {
+            PropKey::String(key) => self.get_property(obj_val, key),
+            PropKey::Symbol(sym) => self.get_symbol_property(obj_val, sym),
+        }
+    }
+
+    pub(crate) fn set_keyed(
+        &mut self,
+        obj_val: &JsValue,
+        key: &PropKey,
+        value: JsValue,
+    ) -> Result<(), RuntimeError> {
+        match key {
+            PropKey::String(key) => self.set_property(obj_val, key, value),
+            PropKey::Symbol(sym) => self.set_symbol_property(obj_val, sym, value),
+        }
+    }
+
+    pub(crate) fn delete_keyed(
+        &mut self,
+        obj_val: &JsValue,
+        key: &PropKey,
+    ) -> Result<JsValue, RuntimeError> {
+        match key {
+            PropKey::String(key) => self.delete_property(obj_val, key),
+            PropKey::Symbol(sym) => self.delete_symbol_property(obj_val, sym),
+        }
+    }
+
+    /// HasProperty: whether `key` is an own or inherited property of
+    /// `value`, as the `in` operator asks. Proxies answer through `has`.
+    pub(crate) fn has_keyed(
+        &mut self,
+        value: &JsValue,
+        key: &PropKey,
+    ) -> Result<bool, RuntimeError> {
+        if let JsValue::Proxy(proxy) = value {
+            let (trap, target) = {
+                let p = proxy.borrow();
+                p.check_revoked()
+                    .map_err(|msg| RuntimeError::TypeError { message: msg })?;
+                (p.get_trap("has"), p.target.clone())
+            };
+            let Some(trap_fn) = trap else {
+                return self.has_keyed(&target, key);
+            };
+            let result = self.call_function(&trap_fn, &[target, key.to_value()])?;
+            return Ok(result.to_boolean());
+        }
+        let own = match key {
+            PropKey::String(key) => {
+                matches!(
+                    value,
+                    JsValue::Function { .. } | JsValue::NativeFunction { .. }
+                ) && (key == "name" || key == "length")
+                    || self.own_property_enumerability(value, key)?.is_some()
+            }
+            PropKey::Symbol(sym) => {
+                let props = match value {
+                    JsValue::Object(obj) => Some(*obj),
+                    JsValue::Function { properties, .. } => *properties,
+                    JsValue::Array(arr) => arr.borrow().properties,
+                    _ => None,
+                };
+                props.is_some_and(|props| props.borrow().symbol_properties.contains_key(&sym.id))
+            }
+        };
+        if own {
+            return Ok(true);
+        }
+        // Like property lookup, a chain that ends short of
+        // `Object.prototype` falls back to it.
+        let root = self.prototypes.object;
+        let mut reached_root = has_null_prototype(value);
+        let mut current = self.inherited_prototype(value);
+        while let Some(candidate) = current.or((!reached_root).then_some(root)) {
+            let borrowed = candidate.borrow();
+            reached_root |= Gc::ptr_eq(candidate, root) || borrowed.null_prototype;
+            let found = match key {
+                PropKey::String(key) => borrowed.properties.contains_key(key.as_str()),
+                PropKey::Symbol(sym) => borrowed.symbol_properties.contains_key(&sym.id),
+            };
+            if found {
+                return Ok(true);
+            }
+            current = borrowed.prototype;
+        }
+        Ok(false)
+    }
+
+    /// Evaluates a computed key expression and applies ToPropertyKey.
+    pub(crate) fn eval_property_key_expr(&mut self, expr: &Expr) -> Result<PropKey, RuntimeError> {
+        let value = self.eval_expr(expr)?;
+        self.property_key(&value)
+    }
+
+    /// The object holding an array's non-index properties, allocated
+    /// on first use.
+    pub(crate) fn array_named_properties(
+        &mut self,
+        arr: Gc<GcCell<JsArray>>,
+    ) -> Gc<GcCell<JsObject>> {
+        if let Some(props) = arr.borrow().properties {
+            return props;
+        }
+        let mut props = JsObject::new();
+        // A frozen array takes no new properties.
+        if arr.borrow().is_frozen() {
+            props.freeze();
+        }
+        let props = self.heap.alloc_cell(props);
+        arr.borrow_mut().properties = Some(props);
+        props
+    }
+
+    /// The element at `index`, through its getter if the index was
+    /// redefined as an accessor, so that builtins see what `arr[i]` sees.
+    pub(crate) fn array_element(
+        &mut self,
+        arr: &Gc<GcCell<JsArray>>,
+        index: usize,
+    ) -> Result<JsValue, RuntimeError> {
+        let getter = match arr.borrow().index_accessor(index) {
+            Some(accessor) => accessor.getter.clone(),
+            None => return Ok(arr.borrow().get(index)),
+        };
+        match getter {
+            Some(getter) => self.call_function_with_this(&getter, &[], Some(JsValue::Array(*arr))),
+            None => Ok(JsValue::Undefined),
+        }
+    }
+
+    /// Every element of `arr`, each read as `array_element` reads it.
+    pub(crate) fn array_elements(
+        &mut self,
+        arr: &Gc<GcCell<JsArray>>,
+    ) -> Result<Vec<JsValue>, RuntimeError> {
+        if !arr.borrow().has_index_accessors() {
+            return Ok(arr.borrow().elements.clone());
+        }
+        let len = arr.borrow().len();
+        (0..len)
+            .map(|index| self.array_element(arr, index))
+            .collect()
+    }
+
     pub(crate) fn get_property(
         &mut self,
         obj_val: &JsValue,
@@ -13,37 +214,60 @@ impl Interpreter {
     ) -> Result<JsValue, RuntimeError> {
         match obj_val {
             JsValue::Object(obj) => {
-                let mut current = Some(*obj);
-                while let Some(candidate) = current {
-                    let (prop, next) = {
-                        let borrowed = candidate.borrow();
-                        (borrowed.properties.get(key).cloned(), borrowed.prototype)
-                    };
-                    if let Some(prop) = prop {
-                        if let Some(getter) = prop.getter {
-                            return self.call_function_with_this(
-                                &getter,
-                                &[],
-                                Some(obj_val.clone()),
-                            );
-                        }
-                        return Ok(prop.value.clone());
-                    }
-                    current = next;
+                // A String wrapper's indices read its characters.
+                if key.starts_with(|c: char| c.is_ascii_digit())
+                    && let Some(JsValue::String(s)) = obj_val.get_primitive_value()
+                    && let Ok(JsValue::String(ch)) =
+                        string_methods::resolve_string_property(&s, key)
+                {
+                    return Ok(JsValue::String(ch));
                 }
-                Ok(JsValue::Undefined)
+                if let Some(value) = self.env.global_property(*obj, key) {
+                    return Ok(value);
+                }
+                Ok(self
+                    .lookup_prototype_chain(Some(*obj), key, obj_val)?
+                    .unwrap_or(JsValue::Undefined))
             }
             JsValue::Array(arr) => {
-                let borrowed = arr.borrow();
-                if key == "length" {
-                    return Ok(JsValue::Number(borrowed.len() as f64));
-                }
-                if let Ok(idx) = key.parse::<usize>() {
-                    return Ok(borrowed.get(idx));
+                let getter = {
+                    let borrowed = arr.borrow();
+                    if key == "length" {
+                        return Ok(JsValue::Number(borrowed.len() as f64));
+                    }
+                    match array_index(key) {
+                        Some(idx) => match borrowed.index_accessor(idx) {
+                            Some(accessor) => accessor.getter.clone(),
+                            None => return Ok(borrowed.get(idx)),
+                        },
+                        None => match borrowed.properties {
+                            Some(props) => match props.borrow().properties.get(key) {
+                                Some(prop) if prop.getter.is_some() => prop.getter.clone(),
+                                Some(prop) => return Ok(prop.value.clone()),
+                                None => None,
+                            },
+                            None => None,
+                        },
+                    }
+                };
+                if let Some(getter) = getter {
+                    return self.call_function_with_this(&getter, &[], Some(obj_val.clone()));
                 }
-                Ok(JsValue::Undefined)
+                let proto = self.inherited_prototype(obj_val);
+                Ok(self
+                    .lookup_prototype_chain(proto, key, obj_val)?
+                    .unwrap_or(JsValue::Undefined))
             }
-            JsValue::String(s) => string_methods::resolve_string_property(s, key),
+            JsValue::String(s) => match string_methods::resolve_string_property(s, key) {
+                Ok(value) => Ok(value),
+                Err(err) => {
+                    let proto = self.inherited_prototype(obj_val);
+                    match self.lookup_prototype_chain(proto, key, obj_val)? {
+                        Some(value) => Ok(value),
+                        None => Err(err),
+                    }
+                }
+            },
             JsValue::Map(map) => {
                 if key == "size" {
                     Ok(JsValue::Number(map.borrow().size() as f64))
@@ -58,6 +282,9 @@ impl Interpreter {
                     Ok(JsValue::Undefined)
                 }
             }
+            JsValue::ArrayBuffer(_) | JsValue::TypedArray(_) | JsValue::DataView(_) => {
+                Ok(self.get_binary_property(obj_val, key))
+            }
             JsValue::Proxy(proxy) => {
                 let (trap, target) = {
                     let p = proxy.borrow();
@@ -66,7 +293,7 @@ impl Interpreter {
                     (p.get_trap("get"), p.target.clone())
                 };
                 if let Some(trap_fn) = trap {
-                    self.call_function(&trap_fn, &[target, JsValue::String(key.to_string())])
+                    self.call_function(&trap_fn, &[target, JsValue::String(key.into())])
                 } else {
                     self.get_property(&target, key)
                 }
@@ -78,25 +305,50 @@ impl Interpreter {
                 ..
             } => {
                 if key == "name" {
-                    return Ok(JsValue::String(name.clone()));
+                    return Ok(JsValue::String(function_name(name).into()));
                 }
                 if key == "length" {
-                    return Ok(JsValue::Number(params.len() as f64));
+                    return Ok(JsValue::Number(function_length(params) as f64));
                 }
                 if let Some(props) = properties {
-                    let borrowed = props.borrow();
-                    if let Some(prop) = borrowed.properties.get(key) {
-                        return Ok(prop.value.clone());
+                    let prop = props.borrow().properties.get(key).cloned();
+                    if let Some(prop) = prop {
+                        if let Some(getter) = prop.getter {
+                            return self.call_function_with_this(
+                                &getter,
+                                &[],
+                                Some(obj_val.clone()),
+                            );
+                        }
+                        return Ok(prop.value);
                     }
                 }
-                Ok(JsValue::Undefined)
+                let proto = self.inherited_prototype(obj_val);
+                Ok(self
+                    .lookup_prototype_chain(proto, key, obj_val)?
+                    .unwrap_or(JsValue::Undefined))
             }
-            JsValue::NativeFunction { name, .. } => {
+            JsValue::NativeFunction { name, handler } => {
                 if key == "name" {
-                    return Ok(JsValue::String(name.clone()));
+                    return Ok(JsValue::String(name.into()));
+                }
+                if key == "prototype"
+                    && let Some(proto) = self.constructor_prototype(handler)
+                {
+                    return Ok(JsValue::Object(proto));
                 }
-                Ok(JsValue::Undefined)
+                if let Some(method) = constructor_static(handler, key) {
+                    return Ok(JsValue::NativeFunction {
+                        name: key.into(),
+                        handler: method,
+                    });
+                }
+                let proto = self.inherited_prototype(obj_val);
+                Ok(self
+                    .lookup_prototype_chain(proto, key, obj_val)?
+                    .unwrap_or(JsValue::Undefined))
             }
+            JsValue::Undefined | JsValue::Null => Err(nullish_read_error(obj_val, key, None)),
             _ => Err(RuntimeError::TypeError {
                 message: format!("cannot access property '{key}' on {obj_val}"),
             }),
@@ -113,12 +365,15 @@ impl Interpreter {
             JsValue::Object(obj) => {
                 let mut current = Some(*obj);
                 while let Some(candidate) = current {
-                    let (prop, next) = {
+                    // Only the setter is needed, so the property is not
+                    // cloned on every write.
+                    let (setter, next) = {
                         let borrowed = candidate.borrow();
-                        (borrowed.properties.get(key).cloned(), borrowed.prototype)
+                        let setter = borrowed.properties.get(key).map(|prop| prop.setter.clone());
+                        (setter, borrowed.prototype)
                     };
-                    if let Some(prop) = prop {
-                        if let Some(setter) = prop.setter {
+                    match setter {
+                        Some(Some(setter)) => {
                             self.call_function_with_this(
                                 &setter,
                                 std::slice::from_ref(&value),
@@ -126,24 +381,63 @@ impl Interpreter {
                             )?;
                             return Ok(());
                         }
-                        break;
+                        Some(None) => break,
+                        None => current = next,
                     }
-                    current = next;
                 }
 
-                obj.borrow_mut().set(key.to_string(), value);
+                let own = obj.borrow().properties.contains_key(key);
+                if own || !self.env.set_global_property(*obj, key, value.clone()) {
+                    obj.borrow_mut().set(key, value);
+                }
                 Ok(())
             }
             JsValue::Array(arr) => {
-                if let Ok(idx) = key.parse::<usize>() {
-                    arr.borrow_mut().set(idx, value);
+                if key == "length" {
+                    let len = value.to_number();
+                    if !(0.0..=u32::MAX as f64).contains(&len) || len.fract() != 0.0 {
+                        let error =
+                            self.create_typed_error_object("RangeError", "Invalid array length");
+                        return Err(RuntimeError::thrown(error));
+                    }
+                    // A refused resize is silently ignored, as for any
+                    // write to a read-only property.
+                    arr.borrow_mut().set_length(len as usize);
+                    return Ok(());
+                }
+                if let Some(idx) = array_index(key) {
+                    let setter = arr.borrow().index_accessor(idx).map(|p| p.setter.clone());
+                    match setter {
+                        Some(Some(setter)) => {
+                            self.call_function_with_this(
+                                &setter,
+                                std::slice::from_ref(&value),
+                                Some(obj_val.clone()),
+                            )?;
+                        }
+                        Some(None) => {}
+                        None if arr.borrow().index_writable(idx) => {
+                            arr.borrow_mut().set(idx, value);
+                        }
+                        None => {}
+                    }
                     Ok(())
                 } else {
-                    Err(RuntimeError::TypeError {
-                        message: format!("cannot set property '{key}' on array"),
-                    })
+                    let props = self.array_named_properties(*arr);
+                    let setter = props.borrow().properties.get(key).map(|p| p.setter.clone());
+                    if let Some(Some(setter)) = setter {
+                        self.call_function_with_this(
+                            &setter,
+                            std::slice::from_ref(&value),
+                            Some(obj_val.clone()),
+                        )?;
+                    } else {
+                        props.borrow_mut().set(key, value);
+                    }
+                    Ok(())
                 }
             }
+            JsValue::TypedArray(array) => self.set_typed_array_property(array, key, &value),
             JsValue::Proxy(proxy) => {
                 let (trap, target) = {
                     let p = proxy.borrow();
@@ -152,21 +446,24 @@ impl Interpreter {
                     (p.get_trap("set"), p.target.clone())
                 };
                 if let Some(trap_fn) = trap {
-                    self.call_function(
-                        &trap_fn,
-                        &[target, JsValue::String(key.to_string()), value],
-                    )?;
+                    self.call_function(&trap_fn, &[target, JsValue::String(key.into()), value])?;
                     Ok(())
                 } else {
                     self.set_property(&target, key, value)
                 }
             }
             JsValue::Function { properties, .. } => {
+                // `name` and `length` are read-only, so writes to them are
+                // dropped.
+                if key == "name" || key == "length" {
+                    return Ok(());
+                }
                 if let Some(props) = properties {
-                    props.borrow_mut().set(key.to_string(), value);
+                    props.borrow_mut().set(key, value);
                 }
                 Ok(())
             }
+            JsValue::Undefined | JsValue::Null => Err(nullish_write_error(obj_val, key, None)),
             _ => Err(RuntimeError::TypeError {
                 message: format!("cannot set property '{key}' on {obj_val}"),
             }),
@@ -178,23 +475,32 @@ impl Interpreter {
         obj_val: &JsValue,
         sym: &JsSymbol,
     ) -> Result<JsValue, RuntimeError> {
-        match obj_val {
-            JsValue::Object(obj) => {
-                let mut current = Some(*obj);
-                while let Some(candidate) = current {
-                    let (val, next) = {
-                        let borrowed = candidate.borrow();
-                        (borrowed.get_symbol(sym), borrowed.prototype)
-                    };
-                    if let Some(v) = val {
-                        return Ok(v);
-                    }
-                    current = next;
+        let mut current = match obj_val {
+            JsValue::Object(obj) => Some(*obj),
+            // Static class members such as `[Symbol.hasInstance]` sit on
+            // the function's own properties.
+            JsValue::Function { properties, .. } => *properties,
+            JsValue::Array(arr) => arr.borrow().properties,
+            JsValue::Undefined | JsValue::Null => {
+                return Err(nullish_read_error(obj_val, &sym.to_string(), None));
+            }
+            _ => None,
+        };
+        while let Some(candidate) = current {
+            let (prop, next) = {
+                let borrowed = candidate.borrow();
+                let prop = borrowed.symbol_properties.get(&sym.id);
+                (prop.map(|(_, p)| p.clone()), borrowed.prototype)
+            };
+            if let Some(prop) = prop {
+                if let Some(getter) = prop.getter {
+                    return self.call_function_with_this(&getter, &[], Some(obj_val.clone()));
                 }
-                Ok(JsValue::Undefined)
+                return Ok(prop.value);
             }
-            _ => Ok(JsValue::Undefined),
+            current = next;
         }
+        Ok(JsValue::Undefined)
     }
 
     pub(crate) fn set_symbol_property(
@@ -205,9 +511,45 @@ impl Interpreter {
     ) -> Result<(), RuntimeError> {
         match obj_val {
             JsValue::Object(obj) => {
+                let mut current = Some(*obj);
+                while let Some(candidate) = current {
+                    let (setter, next) = {
+                        let borrowed = candidate.borrow();
+                        let prop = borrowed.symbol_properties.get(&sym.id);
+                        (prop.map(|(_, p)| p.setter.clone()), borrowed.prototype)
+                    };
+                    if let Some(setter) = setter {
+                        if let Some(setter) = setter {
+                            self.call_function_with_this(
+                                &setter,
+                                std::slice::from_ref(&value),
+                                Some(obj_val.clone()),
+                            )?;
+                            return Ok(());
+                        }
+                        break;
+                    }
+                    current = next;
+                }
+
                 obj.borrow_mut().set_symbol(sym.clone(), value);
                 Ok(())
             }
+            JsValue::Function {
+                properties: Some(props),
+                ..
+            } => {
+                props.borrow_mut().set_symbol(sym.clone(), value);
+                Ok(())
+            }
+            JsValue::Array(arr) => {
+                let props = self.array_named_properties(*arr);
+                props.borrow_mut().set_symbol(sym.clone(), value);
+                Ok(())
+            }
+            JsValue::Undefined | JsValue::Null => {
+                Err(nullish_write_error(obj_val, &sym.to_string(), None))
+            }
             _ => Err(RuntimeError::TypeError {
                 message: format!("cannot set symbol property on {obj_val}"),
             }),
@@ -221,9 +563,45 @@ impl Interpreter {
     ) -> Result<JsValue, RuntimeError> {
         match obj_val {
             JsValue::Object(obj) => {
+                if let Some(deleted) = self.env.delete_global_property(*obj, key) {
+                    return Ok(JsValue::Boolean(deleted));
+                }
                 let removed = obj.borrow_mut().delete(key);
                 Ok(JsValue::Boolean(removed))
             }
+            JsValue::Function {
+                properties: Some(props),
+                ..
+            } => Ok(JsValue::Boolean(props.borrow_mut().delete(key))),
+            JsValue::Array(arr) => {
+                if key == "length" {
+                    return Ok(JsValue::Boolean(false));
+                }
+                // Deleting an element leaves a hole rather than shifting
+                // the elements after it.
+                if let Some(idx) = array_index(key) {
+                    let mut borrowed = arr.borrow_mut();
+                    if borrowed
+                        .index_attributes
+                        .get(&idx)
+                        .is_some_and(|prop| !prop.configurable)
+                    {
+                        return Ok(JsValue::Boolean(false));
+                    }
+                    borrowed.index_attributes.remove(&idx);
+                    if let Some(slot) = borrowed.elements.get_mut(idx) {
+                        *slot = JsValue::Undefined;
+                    }
+                } else if let Some(props) = arr.borrow().properties {
+                    return Ok(JsValue::Boolean(props.borrow_mut().delete(key)));
+                }
+                Ok(JsValue::Boolean(true))
+            }
+            JsValue::String(s) => {
+                let fixed =
+                    key == "length" || array_index(key).is_some_and(|idx| idx < s.chars().count());
+                Ok(JsValue::Boolean(!fixed))
+            }
             JsValue::Proxy(proxy) => {
                 let (trap, target) = {
                     let p = proxy.borrow();
@@ -233,28 +611,142 @@ impl Interpreter {
                 };
                 if let Some(trap_fn) = trap {
                     let result =
-                        self.call_function(&trap_fn, &[target, JsValue::String(key.to_string())])?;
+                        self.call_function(&trap_fn, &[target, JsValue::String(key.into())])?;
                     Ok(JsValue::Boolean(result.to_boolean()))
                 } else {
                     self.delete_property(&target, key)
                 }
             }
+            JsValue::Undefined | JsValue::Null => Err(RuntimeError::TypeError {
+                message: "Cannot convert undefined or null to object".to_string(),
+            }),
             _ => Ok(JsValue::Boolean(true)),
         }
     }
 
-    pub(crate) fn eval_delete_expr(&mut self, operand: &Expr) -> Result<JsValue, RuntimeError> {
-        match operand {
+    pub(crate) fn delete_symbol_property(
+        &mut self,
+        obj_val: &JsValue,
+        sym: &JsSymbol,
+    ) -> Result<JsValue, RuntimeError> {
+        let props = match obj_val {
+            JsValue::Object(obj) => Some(*obj),
+            JsValue::Function { properties, .. } => *properties,
+            JsValue::Array(arr) => arr.borrow().properties,
+            JsValue::Proxy(proxy) => {
+                let (trap, target) = {
+                    let p = proxy.borrow();
+                    p.check_revoked()
+                        .map_err(|msg| RuntimeError::TypeError { message: msg })?;
+                    (p.get_trap("deleteProperty"), p.target.clone())
+                };
+                let Some(trap_fn) = trap else {
+                    return self.delete_symbol_property(&target, sym);
+                };
+                let result =
+                    self.call_function(&trap_fn, &[target, JsValue::Symbol(sym.clone())])?;
+                return Ok(JsValue::Boolean(result.to_boolean()));
+            }
+            JsValue::Undefined | JsValue::Null => {
+                return Err(RuntimeError::TypeError {
+                    message: "Cannot convert undefined or null to object".to_string(),
+                });
+            }
+            _ => None,
+        };
+        Ok(JsValue::Boolean(
+            props.is_none_or(|props| props.borrow_mut().delete_symbol(sym)),
+        ))
+    }
+
+    /// `delete operand`. Deleting a binding is refused; in strict code a
+    /// refused property delete throws instead of evaluating to false.
+    pub(crate) fn eval_delete_expr(
+        &mut self,
+        operand: &Expr,
+        strict: bool,
+    ) -> Result<JsValue, RuntimeError> {
+        let (obj_val, key) = match operand {
             Expr::MemberAccess { object, property } => {
-                let obj_val = self.eval_expr(object)?;
-                self.delete_property(&obj_val, property)
+                (self.eval_expr(object)?, PropKey::String(property.clone()))
             }
             Expr::ComputedMemberAccess { object, property } => {
                 let obj_val = self.eval_expr(object)?;
-                let key = self.eval_expr(property)?.to_js_string();
-                self.delete_property(&obj_val, &key)
+                let key = self.eval_property_key_expr(property)?;
+                (obj_val, key)
             }
-            _ => Ok(JsValue::Boolean(true)),
+            Expr::Identifier(_) => return Ok(JsValue::Boolean(false)),
+            other => {
+                self.eval_expr(other)?;
+                return Ok(JsValue::Boolean(true));
+            }
+        };
+        let deleted = self.delete_keyed(&obj_val, &key)?;
+        if strict && !deleted.to_boolean() {
+            return Err(self.throw_type_error(&format!(
+                "Cannot delete property '{}' of {}",
+                key.display(),
+                obj_val.to_js_string()
+            )));
         }
+        Ok(deleted)
     }
 }
+
+/// The TypeError for reading `key` from `null` or `undefined`. `object` is
+/// the expression that produced `base`, named in the message when it has a
+/// short source form.
+pub(crate) fn nullish_read_error(base: &JsValue, key: &str, object: Option<&Expr>) -> RuntimeError {
+    nullish_error(
+        format!("Cannot read properties of {base} (reading '{key}')"),
+        base,
+        object,
+    )
+}
+
+/// Like [`nullish_read_error`], for assigning to `key`.
+pub(crate) fn nullish_write_error(
+    base: &JsValue,
+    key: &str,
+    object: Option<&Expr>,
+) -> RuntimeError {
+    nullish_error(
+        format!("Cannot set properties of {base} (setting '{key}')"),
+        base,
+        object,
+    )
+}
+
+fn nullish_error(message: String, base: &JsValue, object: Option<&Expr>) -> RuntimeError {
+    let message = match object.and_then(expression_text) {
+        Some(text) => format!("{message}: {text} is {base}"),
+        None => message,
+    };
+    RuntimeError::TypeError { message }
+}
+
+/// The source form of the identifier, member and call chains that property
+/// reads usually go through, or `None` for anything longer or more complex.
+fn expression_text(expr: &Expr) -> Option<String> {
+    let text = match expr {
+        Expr::Identifier(name) | Expr::Local { name, .. } => name.clone(),
+        Expr::MemberAccess { object, property } => {
+            format!("{}.{property}", expression_text(object)?)
+        }
+        Expr::ComputedMemberAccess { object, property } => {
+            let key = match property.as_ref() {
+                Expr::Literal(Literal::Number(n)) => number_to_string(*n),
+                Expr::Literal(Literal::String(s)) => format!("{s:?}"),
+                Expr::Identifier(name) | Expr::Local { name, .. } => name.clone(),
+                _ => return None,
+            };
+            format!("{}[{key}]", expression_text(object)?)
+        }
+        Expr::Call { callee, args } => {
+            let args = if args.is_empty() { "" } else { "..." };
+            format!("{}({args})", expression_text(callee)?)
+        }
+        _ => return None,
+    };
+    (text.len() <= 60).then_some(text)
+}
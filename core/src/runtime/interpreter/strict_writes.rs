@@ -0,0 +1,105 @@
+use super::Interpreter;
+use super::property_access::PropKey;
+use crate::errors::RuntimeError;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::value::JsValue;
+use crate::runtime::value::array::{JsArray, array_index};
+use crate::runtime::value::object::JsObject;
+
+/// Why an ordinary assignment would store nothing.
+enum Refusal {
+    ReadOnly,
+    GetterOnly,
+    NotExtensible,
+}
+
+impl Interpreter {
+    /// `target[key] = value` from an assignment expression. Sloppy mode
+    /// code drops writes to read-only properties and non-extensible
+    /// objects; strict mode code throws a TypeError for them instead.
+    pub(crate) fn assign_keyed(
+        &mut self,
+        target: &JsValue,
+        key: &PropKey,
+        value: JsValue,
+        strict: bool,
+    ) -> Result<(), RuntimeError> {
+        if strict && let Some(refusal) = write_refusal(target, key) {
+            let key = key.display();
+            let message = match refusal {
+                Refusal::ReadOnly => {
+                    format!("Cannot assign to read only property '{key}' of object")
+                }
+                Refusal::GetterOnly => {
+                    format!("Cannot set property '{key}' of object, which has only a getter")
+                }
+                Refusal::NotExtensible => {
+                    format!("Cannot add property {key}, object is not extensible")
+                }
+            };
+            return Err(self.throw_type_error(&message));
+        }
+        self.set_keyed(target, key, value)
+    }
+}
+
+fn write_refusal(target: &JsValue, key: &PropKey) -> Option<Refusal> {
+    match target {
+        JsValue::Object(obj) => object_write_refusal(*obj, key),
+        JsValue::Array(arr) => match key {
+            PropKey::String(name) => match array_index(name) {
+                Some(index) => array_write_refusal(&arr.borrow(), index),
+                None if name == "length" && !arr.borrow().length_writable => {
+                    Some(Refusal::ReadOnly)
+                }
+                None => arr
+                    .borrow()
+                    .properties
+                    .and_then(|props| object_write_refusal(props, key)),
+            },
+            PropKey::Symbol(_) => None,
+        },
+        _ => None,
+    }
+}
+
+fn array_write_refusal(arr: &JsArray, index: usize) -> Option<Refusal> {
+    match arr.index_accessor(index) {
+        Some(prop) if prop.setter.is_none() => Some(Refusal::GetterOnly),
+        Some(_) => None,
+        None if !arr.index_writable(index) => Some(Refusal::ReadOnly),
+        None => None,
+    }
+}
+
+/// Looks `key` up along the prototype chain the way a write does: the
+/// first object with the property decides, and a key no object has is
+/// added to `obj` only while it is extensible.
+fn object_write_refusal(obj: Gc<GcCell<JsObject>>, key: &PropKey) -> Option<Refusal> {
+    let mut current = Some(obj);
+    while let Some(candidate) = current {
+        let borrowed = candidate.borrow();
+        let property = match key {
+            PropKey::String(name) => borrowed.properties.get(name.as_str()),
+            PropKey::Symbol(sym) => borrowed.symbol_properties.get(&sym.id).map(|(_, p)| p),
+        };
+        if let Some(property) = property {
+            if property.is_accessor() {
+                return property.setter.is_none().then_some(Refusal::GetterOnly);
+            }
+            if !property.writable || borrowed.frozen {
+                return Some(Refusal::ReadOnly);
+            }
+            break;
+        }
+        current = borrowed.prototype;
+    }
+    let own = {
+        let borrowed = obj.borrow();
+        match key {
+            PropKey::String(name) => borrowed.properties.contains_key(name.as_str()),
+            PropKey::Symbol(sym) => borrowed.symbol_properties.contains_key(&sym.id),
+        }
+    };
+    (!own && !obj.borrow().extensible).then_some(Refusal::NotExtensible)
+}
@@ -0,0 +1,62 @@
+use super::Interpreter;
+use crate::diagnostics::trace::{TraceConfig, TraceEvent, TraceEventKind, Tracer};
+use crate::parser::ast::Stmt;
+
+impl Interpreter {
+    /// Starts recording an execution trace, replacing any trace already
+    /// being recorded.
+    pub fn set_trace(&mut self, config: TraceConfig) {
+        self.trace = Some(Tracer::new(config));
+    }
+
+    pub fn clear_trace(&mut self) {
+        self.trace = None;
+    }
+
+    pub(crate) fn trace_statement(&mut self, stmt: &Stmt) {
+        if self.trace.as_ref().is_some_and(|t| t.config.statements) {
+            self.emit_trace(TraceEventKind::Statement(stmt.kind()), self.call_depth);
+        }
+    }
+
+    /// Records a call or a return, while the callee's frame is on the
+    /// call stack.
+    pub(crate) fn trace_call(&mut self, kind: TraceEventKind) {
+        if self.trace.as_ref().is_some_and(|t| t.config.calls) {
+            self.emit_trace(kind, self.call_depth.saturating_sub(1));
+        }
+    }
+
+    /// Emits an event placed at the innermost call frame, or at the top of
+    /// the running script outside any.
+    fn emit_trace(&mut self, kind: TraceEventKind, depth: usize) {
+        let Some(tracer) = &mut self.trace else {
+            return;
+        };
+        let event = match self.call_stack.top() {
+            Some(frame) => TraceEvent {
+                kind,
+                function: frame.function_name.clone(),
+                path: frame.file.clone(),
+                line: frame.line,
+                col: frame.col,
+                depth,
+            },
+            None => TraceEvent {
+                kind,
+                function: "<global>".to_string(),
+                path: self
+                    .module_stack
+                    .last()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| "<script>".to_string()),
+                line: 1,
+                col: 1,
+                depth,
+            },
+        };
+        if tracer.wants(&event.path) {
+            tracer.emit(&event);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use super::Interpreter;
+use crate::errors::RuntimeError;
+
+/// Called every so many evaluation steps while a script runs, giving the
+/// host control back during long synchronous work.
+pub type YieldCallback = Box<dyn FnMut(&mut YieldCtx) -> YieldDecision>;
+
+/// What the host wants done once a yield hook returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YieldDecision {
+    Continue,
+    /// Stop the script with `RuntimeError::Interrupted`.
+    Interrupt,
+    /// Fire the timers that are due at the next statement boundary, then
+    /// carry on with the script.
+    ServiceTimers,
+}
+
+/// The state of the run a yield hook is told about.
+#[derive(Debug)]
+pub struct YieldCtx {
+    steps: usize,
+    elapsed: Duration,
+    pending_timers: usize,
+    next_timer_due_in: Option<u64>,
+    next_interval: usize,
+}
+
+impl YieldCtx {
+    /// Evaluation steps taken so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Real time since the interpreter was created.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn pending_timers(&self) -> usize {
+        self.pending_timers
+    }
+
+    /// Milliseconds until the earliest pending timer is due, 0 when one
+    /// already is.
+    pub fn next_timer_due_in(&self) -> Option<u64> {
+        self.next_timer_due_in
+    }
+
+    /// Steps until the hook is called again; the interval it was set
+    /// with unless changed.
+    pub fn set_next_interval(&mut self, steps: usize) {
+        self.next_interval = steps.max(1);
+    }
+}
+
+pub(crate) struct YieldHook {
+    interval: usize,
+    /// Step count at which the hook is next called.
+    pub(crate) next_at: usize,
+    callback: YieldCallback,
+}
+
+impl Interpreter {
+    /// Calls `hook` every `interval_steps` evaluation steps. The hook can
+    /// let the script go on, stop it, or have due timers fire at the next
+    /// statement boundary so they are not starved by a long computation.
+    pub fn set_yield_hook(&mut self, interval_steps: usize, hook: YieldCallback) {
+        let interval = interval_steps.max(1);
+        self.yield_hook = Some(YieldHook {
+            interval,
+            next_at: self.step_count + interval,
+            callback: hook,
+        });
+    }
+
+    pub fn clear_yield_hook(&mut self) {
+        self.yield_hook = None;
+    }
+
+    /// Calls the yield hook if it is due, giving the host its chance to
+    /// interrupt.
+    pub(crate) fn poll_yield_hook(&mut self) -> Result<(), RuntimeError> {
+        if let Some(hook) = &self.yield_hook
+            && self.step_count >= hook.next_at
+        {
+            self.run_yield_hook()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn run_yield_hook(&mut self) -> Result<(), RuntimeError> {
+        let Some(mut hook) = self.yield_hook.take() else {
+            return Ok(());
+        };
+        self.event_loop.sync_clock();
+        let mut ctx = YieldCtx {
+            steps: self.step_count,
+            elapsed: self.start_time.elapsed(),
+            pending_timers: self.event_loop.pending_timer_count(),
+            next_timer_due_in: self.event_loop.next_timer_due_in(),
+            next_interval: hook.interval,
+        };
+        let decision = (hook.callback)(&mut ctx);
+        hook.next_at = self.step_count + ctx.next_interval;
+        self.yield_hook = Some(hook);
+        match decision {
+            YieldDecision::Continue => Ok(()),
+            YieldDecision::Interrupt => Err(RuntimeError::Interrupted),
+            YieldDecision::ServiceTimers => {
+                self.timers_requested = true;
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,7 +1,12 @@
 pub mod builtins;
 pub mod environment;
 pub mod event_loop;
+#[cfg(feature = "http")]
+pub mod fetch;
 pub mod gc;
 pub mod interpreter;
 pub mod modules;
+pub mod output;
+pub mod report;
+pub mod snapshot;
 pub mod value;
@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::ast::Program;
+
+/// Leads every entry so unrelated files in the directory are never read
+/// as a program.
+const MAGIC: &[u8; 8] = b"CJSAST\0\x01";
+
+/// Parsed programs stored on disk, named after a hash of the source, the
+/// crate version and the parse mode so an edit or upgrade misses.
+#[derive(Debug, Clone)]
+pub struct AstCache {
+    dir: PathBuf,
+    stats: AstCacheStats,
+}
+
+/// How often the cache spared a parse.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AstCacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    version: String,
+    source_hash: u64,
+    source_len: u64,
+    program: Program,
+}
+
+impl AstCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            stats: AstCacheStats::default(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn stats(&self) -> AstCacheStats {
+        self.stats
+    }
+
+    /// The program cached for `source`, or `None` on a miss. A stale or
+    /// unreadable entry is also a miss, reported through `Err` so the
+    /// caller can log it before parsing as usual.
+    pub fn load(&mut self, source: &str, strict: bool) -> Result<Option<Program>, String> {
+        let hash = source_hash(source, strict);
+        let path = self.entry_path(hash);
+        let Ok(bytes) = fs::read(&path) else {
+            self.stats.misses += 1;
+            return Ok(None);
+        };
+        match decode(&bytes, source, hash) {
+            Ok(program) => {
+                self.stats.hits += 1;
+                Ok(Some(program))
+            }
+            Err(reason) => {
+                self.stats.misses += 1;
+                Err(format!(
+                    "ignoring AST cache entry '{}': {reason}",
+                    path.display()
+                ))
+            }
+        }
+    }
+
+    /// Writes `program` as the entry for `source`, replacing any entry
+    /// already there.
+    pub fn store(&self, source: &str, strict: bool, program: &Program) -> Result<(), String> {
+        let hash = source_hash(source, strict);
+        let path = self.entry_path(hash);
+        let entry = Entry {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            source_hash: hash,
+            source_len: source.len() as u64,
+            program: program.clone(),
+        };
+        let mut bytes = MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, &entry).map_err(|e| e.to_string())?;
+
+        let write = || -> std::io::Result<()> {
+            fs::create_dir_all(&self.dir)?;
+            // Written aside and renamed so a concurrent reader never sees
+            // half an entry.
+            let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+            fs::write(&partial, &bytes)?;
+            fs::rename(&partial, &path)
+        };
+        write().map_err(|e| format!("could not write AST cache entry '{}': {e}", path.display()))
+    }
+
+    fn entry_path(&self, hash: u64) -> PathBuf {
+        self.dir.join(format!("{hash:016x}.ast"))
+    }
+}
+
+fn decode(bytes: &[u8], source: &str, hash: u64) -> Result<Program, String> {
+    let body = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or("not an AST cache entry")?;
+    let entry: Entry = bincode::deserialize(body).map_err(|e| format!("corrupt entry: {e}"))?;
+    if entry.version != env!("CARGO_PKG_VERSION") {
+        return Err(format!("written by version {}", entry.version));
+    }
+    if entry.source_hash != hash || entry.source_len != source.len() as u64 {
+        return Err("source does not match".to_string());
+    }
+    Ok(entry.program)
+}
+
+/// FNV-1a over the crate version, the parse mode and the source. Unlike
+/// `DefaultHasher` it gives the same key on every toolchain.
+fn source_hash(source: &str, strict: bool) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let version = env!("CARGO_PKG_VERSION").as_bytes();
+    version
+        .iter()
+        .chain(&[0, u8::from(strict)])
+        .chain(source.as_bytes())
+        .fold(OFFSET, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
@@ -1,3 +1,4 @@
+use crate::runtime::gc::{Trace, Tracer};
 use crate::runtime::value::JsValue;
 use std::collections::HashMap;
 
@@ -15,3 +16,20 @@ impl ModuleCache {
         self.exports.insert(key, exports);
     }
 }
+
+impl Trace for ModuleCache {
+    fn trace(&self, tracer: &mut Tracer) {
+        self.exports.trace(tracer);
+    }
+}
+
+/// The cache key of a module imported with `attributes`, which tells it
+/// apart from the same file imported with other ones. The order the
+/// attributes were written in does not matter.
+pub fn key_with_attributes(module_key: String, attributes: &[(String, String)]) -> String {
+    let mut sorted: Vec<&(String, String)> = attributes.iter().collect();
+    sorted.sort();
+    sorted.into_iter().fold(module_key, |key, (name, value)| {
+        format!("{key}\0{name}={value}")
+    })
+}
@@ -1,2 +1,4 @@
+#[cfg(feature = "ast-serde")]
+pub mod ast_cache;
 pub mod cache;
 pub mod resolver;
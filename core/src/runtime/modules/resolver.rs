@@ -1,24 +1,151 @@
-use std::path::{Path, PathBuf};
+//! How an import specifier becomes the file it loads, when the embedder
+//! has not installed a resolver of its own.
+//!
+//! Specifiers starting with `./` or `../` are relative to the directory
+//! of the importing module and absolute paths are taken as they are. A
+//! specifier without an extension names `<path>.js`, or failing that
+//! `<path>/index.js`. Specifiers always separate their segments with `/`,
+//! whatever the platform's separator is. Anything else, such as `lodash`,
+//! is a bare specifier, which only an embedder's resolver can map.
 
-pub fn resolve(specifier: &str, from_file: &Path) -> PathBuf {
-    let base = if from_file.is_dir() {
-        from_file.to_path_buf()
-    } else {
-        from_file
-            .parent()
-            .map(Path::to_path_buf)
-            .unwrap_or_else(|| PathBuf::from("."))
-    };
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+/// Why a specifier names no module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// A specifier that is neither relative nor absolute.
+    Bare { specifier: String },
+    /// None of the files the specifier could name exists.
+    NotFound {
+        specifier: String,
+        from: PathBuf,
+        tried: Vec<PathBuf>,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Bare { specifier } => write!(
+                f,
+                "cannot resolve '{specifier}': bare specifiers require a module resolver"
+            ),
+            ResolveError::NotFound {
+                specifier,
+                from,
+                tried,
+            } => {
+                let tried: Vec<_> = tried.iter().map(|p| p.display().to_string()).collect();
+                write!(
+                    f,
+                    "cannot find module '{specifier}' imported from '{}' (tried {})",
+                    from.display(),
+                    tried.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// The canonical path of the module `specifier` names when imported from
+/// `from_file`, or from a module in the directory `from_file`.
+pub fn resolve(specifier: &str, from_file: &Path) -> Result<PathBuf, ResolveError> {
+    let tried = candidates(specifier, from_file)?;
+    match tried.iter().find(|candidate| candidate.is_file()) {
+        Some(found) => Ok(std::fs::canonicalize(found).unwrap_or_else(|_| found.clone())),
+        None => Err(ResolveError::NotFound {
+            specifier: specifier.to_string(),
+            from: from_file.to_path_buf(),
+            tried,
+        }),
+    }
+}
 
-    let mut candidate = if specifier.starts_with("./") || specifier.starts_with("../") {
-        base.join(specifier)
+/// The files `specifier` may name, in the order they are tried.
+pub fn candidates(specifier: &str, from_file: &Path) -> Result<Vec<PathBuf>, ResolveError> {
+    let path = if specifier.starts_with("./") || specifier.starts_with("../") {
+        let base = if from_file.is_dir() {
+            from_file
+        } else {
+            from_file.parent().unwrap_or(Path::new("."))
+        };
+        join_segments(base, specifier)
+    } else if specifier.starts_with('/') || Path::new(specifier).is_absolute() {
+        join_segments(Path::new(""), specifier)
     } else {
-        PathBuf::from(specifier)
+        return Err(ResolveError::Bare {
+            specifier: specifier.to_string(),
+        });
     };
 
-    if candidate.extension().is_none() {
-        candidate.set_extension("js");
+    if path.extension().is_some() {
+        return Ok(vec![path]);
     }
+    let mut with_extension = path.clone().into_os_string();
+    with_extension.push(".js");
+    Ok(vec![PathBuf::from(with_extension), path.join("index.js")])
+}
+
+/// Appends the segments of `specifier` to `base`, folding `.` and `..`
+/// away so that the result uses the platform's separators throughout.
+fn join_segments(base: &Path, specifier: &str) -> PathBuf {
+    let mut path = base.to_path_buf();
+    for component in Path::new(specifier).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match path.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    path.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => path.push(".."),
+            },
+            other => path.push(other),
+        }
+    }
+    path
+}
+
+/// The key a module is cached under. Equivalent paths share one key: the
+/// path is made canonical, separators become `/`, and on platforms whose
+/// file systems ignore case by default the key is lowercased.
+pub fn module_key(path: &Path) -> String {
+    let canonical = std::fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let text = canonical.to_string_lossy();
+    let text = text
+        .strip_prefix(r"\\?\")
+        .unwrap_or(&text)
+        .replace('\\', "/");
+    if cfg!(any(windows, target_os = "macos")) {
+        text.to_lowercase()
+    } else {
+        text
+    }
+}
 
-    std::fs::canonicalize(&candidate).unwrap_or(candidate)
+/// The `file://` URL of `path`, made absolute. Bytes outside the
+/// characters a URL path may hold are percent-encoded.
+pub fn file_url(path: &Path) -> String {
+    let absolute = std::fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let text = absolute.to_string_lossy().replace('\\', "/");
+    let mut url = String::from("file://");
+    if !text.starts_with('/') {
+        url.push('/');
+    }
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    url
 }
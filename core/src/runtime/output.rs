@@ -0,0 +1,50 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// An embedder callback receiving each line of output.
+pub type OutputSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Where console output produced by a script is sent.
+#[derive(Clone)]
+pub enum OutputMode {
+    /// Print each line to the process stdout.
+    Stdout,
+    /// Keep each line in memory, readable through `output()`.
+    Capture,
+    /// Print to stdout and keep a copy in memory.
+    Both,
+    /// Hand each line to an embedder-supplied callback.
+    Sink(OutputSink),
+}
+
+impl OutputMode {
+    pub fn sink<F>(callback: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        Self::Sink(Arc::new(callback))
+    }
+
+    pub(crate) fn write_line(&self, line: String, captured: &mut Vec<String>) {
+        match self {
+            Self::Stdout => println!("{line}"),
+            Self::Capture => captured.push(line),
+            Self::Both => {
+                println!("{line}");
+                captured.push(line);
+            }
+            Self::Sink(callback) => callback(&line),
+        }
+    }
+}
+
+impl fmt::Debug for OutputMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdout => f.write_str("Stdout"),
+            Self::Capture => f.write_str("Capture"),
+            Self::Both => f.write_str("Both"),
+            Self::Sink(_) => f.write_str("Sink(..)"),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// The `console` method a line was written through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    Log,
+    Info,
+    Warn,
+    Error,
+    Debug,
+}
+
+impl ConsoleLevel {
+    pub(crate) fn from_method(method: &str) -> Option<Self> {
+        match method {
+            "log" => Some(Self::Log),
+            "info" => Some(Self::Info),
+            "warn" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            "debug" => Some(Self::Debug),
+            _ => None,
+        }
+    }
+}
+
+/// Something observable a script did, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum RunEvent {
+    ConsoleLog {
+        level: ConsoleLevel,
+        text: String,
+    },
+    /// An error that escaped the script, stopping the run, or escaped a
+    /// timer callback, after which the event loop carries on. `stack` is
+    /// empty when no frames were recorded.
+    UncaughtError {
+        message: String,
+        stack: String,
+    },
+    /// A promise still rejected without a handler once the event loop went
+    /// idle.
+    UnhandledRejection {
+        value_string: String,
+    },
+    TimerFired {
+        id: u64,
+    },
+}
+
+/// Settings for `run_collect`.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Sleep until timers are due instead of jumping the virtual clock.
+    pub realtime_timers: bool,
+    /// Stop the script with an error after this many evaluation steps.
+    pub max_steps: Option<usize>,
+    /// Globals whose final values are copied into the report.
+    pub globals: Vec<String>,
+}
+
+/// Everything `run_collect` saw while running a script.
+///
+/// `elapsed_ms` is wall-clock time; redact it when snapshotting.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunReport {
+    pub events: Vec<RunEvent>,
+    /// Requested globals that exist after the run, formatted the way
+    /// `console.log` prints them.
+    pub globals: BTreeMap<String, String>,
+    pub steps: usize,
+    /// Time on the event loop clock when the run ended.
+    pub virtual_time_ms: u64,
+    pub elapsed_ms: f64,
+}
@@ -0,0 +1,399 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::{
+    BindingNode, ClassNode, FunctionNode, Intrinsic, Node, PropertyNode, ScopeNode, ScopeRef,
+    SnapValue, Snapshot,
+};
+use crate::errors::RuntimeError;
+use crate::runtime::environment::Scope;
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::value::object::{JsObject, Property};
+use crate::runtime::value::{JsValue, NativeFunction};
+
+impl Interpreter {
+    /// Captures the global bindings, declared classes and every object
+    /// reachable from them. Fails, naming the global path that leads to
+    /// it, on a value whose state lives outside the heap graph: promises,
+    /// generators, weak collections, event targets and host functions
+    /// held by a proxy.
+    pub fn snapshot(&self) -> Result<Snapshot, RuntimeError> {
+        let mut capture = Capture {
+            interp: self,
+            global_scope: self.env.global_scope(),
+            nodes: Vec::new(),
+            node_ids: HashMap::new(),
+            scopes: Vec::new(),
+            scope_ids: HashMap::new(),
+            host_functions: BTreeSet::new(),
+            in_proxy: 0,
+        };
+
+        let global_scope = capture.global_scope;
+        let globals = capture.scope(&global_scope.borrow(), "")?;
+
+        let mut names: Vec<&String> = self.classes.keys().collect();
+        names.sort();
+        let mut classes = Vec::with_capacity(names.len());
+        for name in names {
+            let class = &self.classes[name];
+            let path = format!("class {name}");
+            let mut private_methods = Vec::with_capacity(class.private_methods.len());
+            for (sym, prop) in class.private_methods.iter() {
+                let prop = capture.property(prop, &format!("{path}.{sym}"))?;
+                private_methods.push((sym.clone(), prop));
+            }
+            let mut fields = Vec::with_capacity(class.fields.len());
+            for (key, init) in class.fields.iter() {
+                let init_path = format!("{path}.{}", key.display());
+                let init = init
+                    .as_ref()
+                    .map(|init| capture.value(init, &init_path))
+                    .transpose()?;
+                fields.push((key.clone(), init));
+            }
+            classes.push(ClassNode {
+                name: name.clone(),
+                constructor: capture.value(&class.constructor, &path)?,
+                prototype: capture.object(class.prototype, &format!("{path}.prototype"))?,
+                parent: class.parent.clone(),
+                has_constructor: class.has_constructor,
+                private_methods,
+                fields,
+            });
+        }
+
+        Ok(Snapshot {
+            globals,
+            nodes: capture
+                .nodes
+                .into_iter()
+                .map(|n| n.expect("node filled"))
+                .collect(),
+            scopes: capture
+                .scopes
+                .into_iter()
+                .map(|s| s.expect("scope filled"))
+                .collect(),
+            classes,
+            symbol_registry: self.symbol_registry.clone(),
+            host_functions: capture.host_functions.into_iter().collect(),
+        })
+    }
+}
+
+struct Capture<'a> {
+    interp: &'a Interpreter,
+    global_scope: Gc<GcCell<Scope>>,
+    /// `None` while a node's contents are still being captured, which
+    /// lets cycles refer back to it.
+    nodes: Vec<Option<Node>>,
+    node_ids: HashMap<usize, usize>,
+    scopes: Vec<Option<ScopeNode>>,
+    scope_ids: HashMap<usize, usize>,
+    host_functions: BTreeSet<String>,
+    /// Proxies being captured around the current value.
+    in_proxy: usize,
+}
+
+impl Capture<'_> {
+    fn value(&mut self, value: &JsValue, path: &str) -> Result<SnapValue, RuntimeError> {
+        match value {
+            JsValue::Undefined
+            | JsValue::Null
+            | JsValue::Boolean(_)
+            | JsValue::Number(_)
+            | JsValue::String(_)
+            | JsValue::Symbol(_) => Ok(SnapValue::Plain(value.clone())),
+            JsValue::Object(obj) => Ok(SnapValue::Node(self.object(*obj, path)?)),
+            JsValue::Array(arr) => {
+                let Some(id) = self.reserve(Gc::as_usize(*arr)) else {
+                    return Ok(SnapValue::Node(self.node_ids[&Gc::as_usize(*arr)]));
+                };
+                let arr = arr.borrow();
+                let mut elements = Vec::with_capacity(arr.elements.len());
+                for (i, element) in arr.elements.iter().enumerate() {
+                    elements.push(self.value(element, &format!("{path}[{i}]"))?);
+                }
+                let mut index_attributes = Vec::new();
+                for (i, prop) in &arr.index_attributes {
+                    index_attributes.push((*i, self.property(prop, &format!("{path}[{i}]"))?));
+                }
+                let prototype = self.prototype(arr.prototype, path)?;
+                let properties = match arr.properties {
+                    Some(props) => Some(self.object(props, path)?),
+                    None => None,
+                };
+                self.nodes[id] = Some(Node::Array {
+                    elements,
+                    prototype,
+                    index_attributes,
+                    length_writable: arr.length_writable,
+                    properties,
+                });
+                Ok(SnapValue::Node(id))
+            }
+            JsValue::Map(map) => {
+                let Some(id) = self.reserve(Gc::as_usize(*map)) else {
+                    return Ok(SnapValue::Node(self.node_ids[&Gc::as_usize(*map)]));
+                };
+                let mut entries = Vec::new();
+                for (key, entry) in map.borrow().iter() {
+                    let entry_path = format!("{path}.get({})", key.to_js_string());
+                    entries.push((self.value(key, path)?, self.value(entry, &entry_path)?));
+                }
+                self.nodes[id] = Some(Node::Map(entries));
+                Ok(SnapValue::Node(id))
+            }
+            JsValue::Set(set) => {
+                let Some(id) = self.reserve(Gc::as_usize(*set)) else {
+                    return Ok(SnapValue::Node(self.node_ids[&Gc::as_usize(*set)]));
+                };
+                let mut members = Vec::new();
+                for member in set.borrow().iter() {
+                    members.push(self.value(member, path)?);
+                }
+                self.nodes[id] = Some(Node::Set(members));
+                Ok(SnapValue::Node(id))
+            }
+            JsValue::RegExp(re) => {
+                let Some(id) = self.reserve(Gc::as_usize(*re)) else {
+                    return Ok(SnapValue::Node(self.node_ids[&Gc::as_usize(*re)]));
+                };
+                self.nodes[id] = Some(Node::RegExp(re.borrow().clone()));
+                Ok(SnapValue::Node(id))
+            }
+            JsValue::Proxy(proxy) => {
+                let Some(id) = self.reserve(Gc::as_usize(*proxy)) else {
+                    return Ok(SnapValue::Node(self.node_ids[&Gc::as_usize(*proxy)]));
+                };
+                let proxy = proxy.borrow();
+                self.in_proxy += 1;
+                let target = self.value(&proxy.target, &format!("{path}.<target>"));
+                let handler = self.object(proxy.handler, &format!("{path}.<handler>"));
+                self.in_proxy -= 1;
+                self.nodes[id] = Some(Node::Proxy {
+                    target: target?,
+                    handler: handler?,
+                    revoked: proxy.revoked,
+                });
+                Ok(SnapValue::Node(id))
+            }
+            JsValue::Function {
+                name,
+                params,
+                body,
+                closure_env,
+                is_async,
+                is_generator,
+                source_path,
+                source_offset,
+                properties,
+            } => {
+                let mut scopes = Vec::with_capacity(closure_env.len());
+                for scope in closure_env {
+                    scopes.push(self.scope_ref(*scope, path)?);
+                }
+                let properties = match properties {
+                    Some(props) => Some(self.object(*props, path)?),
+                    None => None,
+                };
+                Ok(SnapValue::Function(Box::new(FunctionNode {
+                    name: name.clone(),
+                    params: params.clone(),
+                    body: body.clone(),
+                    scopes,
+                    is_async: *is_async,
+                    is_generator: *is_generator,
+                    source_path: source_path.clone(),
+                    source_offset: *source_offset,
+                    properties,
+                })))
+            }
+            JsValue::NativeFunction {
+                name,
+                handler: NativeFunction::Host(_) | NativeFunction::HostRef(_),
+            } => {
+                if self.in_proxy > 0 {
+                    return Err(unserializable(path, "a proxy wrapping a host function"));
+                }
+                self.host_functions.insert(name.clone());
+                Ok(SnapValue::Host(name.clone()))
+            }
+            JsValue::NativeFunction { handler, .. } if handler.is_intrinsic() => {
+                Ok(SnapValue::Plain(value.clone()))
+            }
+            JsValue::NativeFunction { handler, .. } => Err(unserializable(
+                path,
+                match handler {
+                    NativeFunction::PromiseResolve(_)
+                    | NativeFunction::PromiseReject(_)
+                    | NativeFunction::PromiseAnyReject(..) => "a promise's resolving function",
+                    NativeFunction::EventTargetListeners(_) => "an event target",
+                    NativeFunction::ProxyRevoke(_) => "a proxy's revoke function",
+                    _ => "a generator or iterator",
+                },
+            )),
+            JsValue::Promise(_) => Err(unserializable(path, "a promise")),
+            JsValue::WeakMap(_) | JsValue::WeakSet(_) => {
+                Err(unserializable(path, "a weak collection"))
+            }
+            JsValue::ArrayBuffer(_) | JsValue::TypedArray(_) | JsValue::DataView(_) => {
+                Err(unserializable(path, "binary data"))
+            }
+        }
+    }
+
+    fn object(&mut self, obj: Gc<GcCell<JsObject>>, path: &str) -> Result<usize, RuntimeError> {
+        let Some(id) = self.reserve(Gc::as_usize(obj)) else {
+            return Ok(self.node_ids[&Gc::as_usize(obj)]);
+        };
+        let protos = self.interp.prototypes;
+        let intrinsic = [
+            (protos.object, Intrinsic::Object),
+            (protos.function, Intrinsic::Function),
+            (protos.array, Intrinsic::Array),
+            (protos.string, Intrinsic::String),
+            (protos.number, Intrinsic::Number),
+            (protos.boolean, Intrinsic::Boolean),
+            (protos.symbol, Intrinsic::Symbol),
+            (protos.date, Intrinsic::Date),
+            (protos.iterator, Intrinsic::Iterator),
+            (protos.event_target, Intrinsic::EventTarget),
+        ]
+        .into_iter()
+        .find_map(|(proto, kind)| Gc::ptr_eq(proto, obj).then_some(kind));
+
+        let obj = obj.borrow();
+        let mut properties = Vec::with_capacity(obj.properties.len());
+        for (key, prop) in &obj.properties {
+            properties.push((key.clone(), self.property(prop, &format!("{path}.{key}"))?));
+        }
+        let mut symbol_properties = Vec::new();
+        for (sym, prop) in obj.symbol_properties.values() {
+            let prop = self.property(prop, &format!("{path}[{sym}]"))?;
+            symbol_properties.push((sym.clone(), prop));
+        }
+        let mut private_elements = Vec::new();
+        for (sym, prop) in obj.private_elements.values() {
+            let prop = self.property(prop, &format!("{path}.{sym}"))?;
+            private_elements.push((sym.clone(), prop));
+        }
+        let prototype = self.prototype(obj.prototype, path)?;
+        self.nodes[id] = Some(Node::Object {
+            intrinsic,
+            properties,
+            symbol_properties,
+            private_elements,
+            prototype,
+            null_prototype: obj.null_prototype,
+            extensible: obj.extensible,
+            sealed: obj.sealed,
+            frozen: obj.frozen,
+        });
+        Ok(id)
+    }
+
+    fn prototype(
+        &mut self,
+        proto: Option<Gc<GcCell<JsObject>>>,
+        path: &str,
+    ) -> Result<Option<usize>, RuntimeError> {
+        proto
+            .map(|proto| self.object(proto, &format!("{path}.__proto__")))
+            .transpose()
+    }
+
+    fn property(&mut self, prop: &Property, path: &str) -> Result<PropertyNode, RuntimeError> {
+        Ok(PropertyNode {
+            value: self.value(&prop.value, path)?,
+            getter: prop
+                .getter
+                .as_ref()
+                .map(|g| self.value(g, path))
+                .transpose()?,
+            setter: prop
+                .setter
+                .as_ref()
+                .map(|s| self.value(s, path))
+                .transpose()?,
+            writable: prop.writable,
+            enumerable: prop.enumerable,
+            configurable: prop.configurable,
+        })
+    }
+
+    fn scope_ref(
+        &mut self,
+        scope: Gc<GcCell<Scope>>,
+        path: &str,
+    ) -> Result<ScopeRef, RuntimeError> {
+        if Gc::ptr_eq(scope, self.global_scope) {
+            return Ok(ScopeRef::Global);
+        }
+        let identity = Gc::as_usize(scope);
+        if let Some(&id) = self.scope_ids.get(&identity) {
+            return Ok(ScopeRef::Node(id));
+        }
+        let id = self.scopes.len();
+        self.scopes.push(None);
+        self.scope_ids.insert(identity, id);
+        let captured = self.scope(&scope.borrow(), &format!("{path}.<closure>"))?;
+        self.scopes[id] = Some(captured);
+        Ok(ScopeRef::Node(id))
+    }
+
+    /// The bindings of `scope`, each found at `path` followed by its name.
+    fn scope(&mut self, scope: &Scope, path: &str) -> Result<ScopeNode, RuntimeError> {
+        let mut entries: Vec<_> = scope.bindings().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        let mut bindings = Vec::with_capacity(entries.len());
+        for (name, binding) in entries {
+            let binding_path = if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{path}.{name}")
+            };
+            bindings.push(BindingNode {
+                name: name.clone(),
+                kind: binding.kind,
+                initialized: binding.initialized,
+                enumerable: binding.enumerable,
+                configurable: binding.configurable,
+                value: self.value(&binding.value, &binding_path)?,
+            });
+        }
+        let this_path = if path.is_empty() {
+            "globalThis".to_string()
+        } else {
+            format!("{path}.this")
+        };
+        let this_binding = scope
+            .this_binding
+            .as_ref()
+            .map(|this| self.value(this, &this_path))
+            .transpose()?;
+        Ok(ScopeNode {
+            bindings,
+            this_binding,
+        })
+    }
+
+    /// Claims a node for the heap cell at `identity`, or `None` when it
+    /// was captured already.
+    fn reserve(&mut self, identity: usize) -> Option<usize> {
+        if self.node_ids.contains_key(&identity) {
+            return None;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(None);
+        self.node_ids.insert(identity, id);
+        Some(id)
+    }
+}
+
+fn unserializable(path: &str, what: &str) -> RuntimeError {
+    RuntimeError::TypeError {
+        message: format!("cannot snapshot '{path}': it holds {what}"),
+    }
+}
@@ -0,0 +1,151 @@
+mod capture;
+mod restore;
+
+use std::rc::Rc;
+
+use crate::parser::ast::{FunctionBody, Param};
+use crate::runtime::environment::BindingKind;
+use crate::runtime::interpreter::FieldKey;
+use crate::runtime::value::regexp::JsRegExp;
+use crate::runtime::value::symbol::{JsSymbol, SymbolRegistry};
+use crate::runtime::value::{JsValue, PropName};
+
+/// The global state of an interpreter, detached from its heap so that any
+/// number of fresh interpreters can be started from it.
+///
+/// It holds the global bindings, the classes declared so far and every
+/// object reachable from them. Functions keep their parsed bodies and the
+/// scopes they closed over. Host functions are kept by name only: the
+/// restored context calls whatever the host registers under that name
+/// with `Context::set_global_function`. Pending timers and microtasks are
+/// not part of a snapshot.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    globals: ScopeNode,
+    nodes: Vec<Node>,
+    scopes: Vec<ScopeNode>,
+    classes: Vec<ClassNode>,
+    symbol_registry: SymbolRegistry,
+    host_functions: Vec<String>,
+}
+
+impl Snapshot {
+    /// Names of the host functions reachable from the snapshot, which the
+    /// host has to register again on each restored context.
+    pub fn host_functions(&self) -> &[String] {
+        &self.host_functions
+    }
+}
+
+/// A realm object that exists in every interpreter, restored by reusing
+/// the new interpreter's own rather than by making a copy. Each variant
+/// names the constructor whose prototype it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Intrinsic {
+    Object,
+    Function,
+    Array,
+    String,
+    Number,
+    Boolean,
+    Symbol,
+    Date,
+    /// The prototype shared by the engine's iterators.
+    Iterator,
+    EventTarget,
+}
+
+#[derive(Debug, Clone)]
+enum SnapValue {
+    /// A value that holds no heap reference, kept as is.
+    Plain(JsValue),
+    Node(usize),
+    Function(Box<FunctionNode>),
+    Host(String),
+}
+
+#[derive(Debug, Clone)]
+struct FunctionNode {
+    name: String,
+    params: Rc<[Param]>,
+    body: FunctionBody,
+    scopes: Vec<ScopeRef>,
+    is_async: bool,
+    is_generator: bool,
+    source_path: Option<String>,
+    source_offset: usize,
+    properties: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ScopeRef {
+    Global,
+    Node(usize),
+}
+
+#[derive(Debug, Clone)]
+struct PropertyNode {
+    value: SnapValue,
+    getter: Option<SnapValue>,
+    setter: Option<SnapValue>,
+    writable: bool,
+    enumerable: bool,
+    configurable: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Object {
+        intrinsic: Option<Intrinsic>,
+        properties: Vec<(PropName, PropertyNode)>,
+        symbol_properties: Vec<(JsSymbol, PropertyNode)>,
+        private_elements: Vec<(JsSymbol, PropertyNode)>,
+        prototype: Option<usize>,
+        null_prototype: bool,
+        extensible: bool,
+        sealed: bool,
+        frozen: bool,
+    },
+    Array {
+        elements: Vec<SnapValue>,
+        prototype: Option<usize>,
+        index_attributes: Vec<(usize, PropertyNode)>,
+        length_writable: bool,
+        properties: Option<usize>,
+    },
+    Map(Vec<(SnapValue, SnapValue)>),
+    Set(Vec<SnapValue>),
+    RegExp(JsRegExp),
+    Proxy {
+        target: SnapValue,
+        handler: usize,
+        revoked: bool,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+struct ScopeNode {
+    bindings: Vec<BindingNode>,
+    this_binding: Option<SnapValue>,
+}
+
+#[derive(Debug, Clone)]
+struct BindingNode {
+    name: PropName,
+    kind: BindingKind,
+    initialized: bool,
+    enumerable: bool,
+    configurable: bool,
+    value: SnapValue,
+}
+
+#[derive(Debug, Clone)]
+struct ClassNode {
+    name: String,
+    constructor: SnapValue,
+    prototype: usize,
+    parent: Option<String>,
+    has_constructor: bool,
+    private_methods: Vec<(JsSymbol, PropertyNode)>,
+    fields: Vec<(FieldKey, Option<SnapValue>)>,
+}
@@ -0,0 +1,292 @@
+use super::{
+    BindingNode, FunctionNode, Intrinsic, Node, PropertyNode, ScopeNode, ScopeRef, SnapValue,
+    Snapshot,
+};
+use crate::runtime::environment::{Binding, Scope};
+use crate::runtime::gc::{Gc, GcCell};
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::interpreter::RuntimeClass;
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::collections::map::JsMap;
+use crate::runtime::value::collections::set::JsSet;
+use crate::runtime::value::object::{JsObject, Property};
+use crate::runtime::value::proxy::JsProxy;
+use crate::runtime::value::regexp::JsRegExp;
+use crate::runtime::value::{JsValue, NativeFunction};
+
+/// A heap cell allocated for a snapshot node before its contents are
+/// filled in.
+#[derive(Clone, Copy)]
+enum Cell {
+    Object(Gc<GcCell<JsObject>>),
+    Array(Gc<GcCell<JsArray>>),
+    Map(Gc<GcCell<JsMap>>),
+    Set(Gc<GcCell<JsSet>>),
+    RegExp(Gc<GcCell<JsRegExp>>),
+    Proxy(Gc<GcCell<JsProxy>>),
+}
+
+impl Interpreter {
+    /// Rebuilds the state captured by [`Interpreter::snapshot`] on this
+    /// interpreter's heap, replacing its global bindings of the same name
+    /// and its `globalThis`.
+    pub fn restore_snapshot(&mut self, snapshot: &Snapshot) {
+        // Every node gets its cell first so that cycles and shared
+        // references resolve while the contents are filled in.
+        let mut cells = Vec::with_capacity(snapshot.nodes.len());
+        for node in &snapshot.nodes {
+            let cell = match node {
+                Node::Object {
+                    intrinsic: Some(intrinsic),
+                    ..
+                } => Cell::Object(match intrinsic {
+                    Intrinsic::Object => self.prototypes.object,
+                    Intrinsic::Function => self.prototypes.function,
+                    Intrinsic::Array => self.prototypes.array,
+                    Intrinsic::String => self.prototypes.string,
+                    Intrinsic::Number => self.prototypes.number,
+                    Intrinsic::Boolean => self.prototypes.boolean,
+                    Intrinsic::Symbol => self.prototypes.symbol,
+                    Intrinsic::Date => self.prototypes.date,
+                    Intrinsic::Iterator => self.prototypes.iterator,
+                    Intrinsic::EventTarget => self.prototypes.event_target,
+                }),
+                Node::Object { .. } => Cell::Object(self.heap.alloc_cell(JsObject::new())),
+                Node::Array { .. } => Cell::Array(self.heap.alloc_cell(JsArray::new(Vec::new()))),
+                Node::Map(_) => Cell::Map(self.heap.alloc_cell(JsMap::new())),
+                Node::Set(_) => Cell::Set(self.heap.alloc_cell(JsSet::new())),
+                Node::RegExp(re) => Cell::RegExp(self.heap.alloc_cell(re.clone())),
+                Node::Proxy { .. } => {
+                    let placeholder = JsProxy::new(JsValue::Undefined, self.prototypes.object);
+                    Cell::Proxy(self.heap.alloc_cell(placeholder))
+                }
+            };
+            cells.push(cell);
+        }
+        let scopes: Vec<_> = snapshot
+            .scopes
+            .iter()
+            .map(|_| self.heap.alloc_cell(Scope::new()))
+            .collect();
+
+        let restore = Restore {
+            cells: &cells,
+            scopes: &scopes,
+            global_scope: self.env.global_scope(),
+        };
+        for (node, cell) in snapshot.nodes.iter().zip(&cells) {
+            restore.fill(node, *cell);
+        }
+        for (node, scope) in snapshot.scopes.iter().zip(&scopes) {
+            restore.fill_scope(node, &mut scope.borrow_mut());
+        }
+        restore.fill_scope(&snapshot.globals, &mut restore.global_scope.borrow_mut());
+
+        for class in &snapshot.classes {
+            self.classes.insert(
+                class.name.clone(),
+                RuntimeClass {
+                    constructor: restore.value(&class.constructor),
+                    prototype: restore.object(class.prototype),
+                    parent: class.parent.clone(),
+                    has_constructor: class.has_constructor,
+                    private_methods: class
+                        .private_methods
+                        .iter()
+                        .map(|(sym, prop)| (sym.clone(), restore.property(prop)))
+                        .collect(),
+                    fields: class
+                        .fields
+                        .iter()
+                        .map(|(key, init)| (key.clone(), init.as_ref().map(|i| restore.value(i))))
+                        .collect(),
+                },
+            );
+        }
+        self.symbol_registry = snapshot.symbol_registry.clone();
+    }
+}
+
+struct Restore<'a> {
+    cells: &'a [Cell],
+    scopes: &'a [Gc<GcCell<Scope>>],
+    global_scope: Gc<GcCell<Scope>>,
+}
+
+impl Restore<'_> {
+    fn fill(&self, node: &Node, cell: Cell) {
+        match (node, cell) {
+            (
+                Node::Object {
+                    properties,
+                    symbol_properties,
+                    private_elements,
+                    prototype,
+                    null_prototype,
+                    extensible,
+                    sealed,
+                    frozen,
+                    ..
+                },
+                Cell::Object(obj),
+            ) => {
+                let mut obj = obj.borrow_mut();
+                for (key, prop) in properties {
+                    obj.properties.insert(key.clone(), self.property(prop));
+                }
+                for (sym, prop) in symbol_properties {
+                    obj.symbol_properties
+                        .insert(sym.id, (sym.clone(), self.property(prop)));
+                }
+                for (sym, prop) in private_elements {
+                    obj.private_elements
+                        .insert(sym.id, (sym.clone(), self.property(prop)));
+                }
+                obj.prototype = prototype.map(|id| self.object(id));
+                obj.null_prototype = *null_prototype;
+                obj.extensible = *extensible;
+                obj.sealed = *sealed;
+                obj.frozen = *frozen;
+            }
+            (
+                Node::Array {
+                    elements,
+                    prototype,
+                    index_attributes,
+                    length_writable,
+                    properties,
+                },
+                Cell::Array(arr),
+            ) => {
+                let mut arr = arr.borrow_mut();
+                arr.elements = elements.iter().map(|e| self.value(e)).collect();
+                arr.prototype = prototype.map(|id| self.object(id));
+                arr.index_attributes = index_attributes
+                    .iter()
+                    .map(|(i, prop)| (*i, self.property(prop)))
+                    .collect();
+                arr.length_writable = *length_writable;
+                arr.properties = properties.map(|id| self.object(id));
+            }
+            (Node::Map(entries), Cell::Map(map)) => {
+                let mut map = map.borrow_mut();
+                for (key, value) in entries {
+                    map.set(self.value(key), self.value(value));
+                }
+            }
+            (Node::Set(members), Cell::Set(set)) => {
+                let mut set = set.borrow_mut();
+                for member in members {
+                    set.add(self.value(member));
+                }
+            }
+            (Node::RegExp(_), Cell::RegExp(_)) => {}
+            (
+                Node::Proxy {
+                    target,
+                    handler,
+                    revoked,
+                },
+                Cell::Proxy(proxy),
+            ) => {
+                let mut proxy = proxy.borrow_mut();
+                proxy.target = self.value(target);
+                proxy.handler = self.object(*handler);
+                proxy.revoked = *revoked;
+            }
+            _ => unreachable!("snapshot cell allocated for a different node kind"),
+        }
+    }
+
+    fn fill_scope(&self, node: &ScopeNode, scope: &mut Scope) {
+        for BindingNode {
+            name,
+            kind,
+            initialized,
+            enumerable,
+            configurable,
+            value,
+        } in &node.bindings
+        {
+            scope.insert(
+                name.clone(),
+                Binding {
+                    value: self.value(value),
+                    kind: *kind,
+                    initialized: *initialized,
+                    enumerable: *enumerable,
+                    configurable: *configurable,
+                },
+            );
+        }
+        if let Some(this) = &node.this_binding {
+            scope.this_binding = Some(self.value(this));
+        }
+    }
+
+    fn value(&self, value: &SnapValue) -> JsValue {
+        match value {
+            SnapValue::Plain(value) => value.clone(),
+            SnapValue::Node(id) => match self.cells[*id] {
+                Cell::Object(obj) => JsValue::Object(obj),
+                Cell::Array(arr) => JsValue::Array(arr),
+                Cell::Map(map) => JsValue::Map(map),
+                Cell::Set(set) => JsValue::Set(set),
+                Cell::RegExp(re) => JsValue::RegExp(re),
+                Cell::Proxy(proxy) => JsValue::Proxy(proxy),
+            },
+            SnapValue::Function(function) => {
+                let FunctionNode {
+                    name,
+                    params,
+                    body,
+                    scopes,
+                    is_async,
+                    is_generator,
+                    source_path,
+                    source_offset,
+                    properties,
+                } = &**function;
+                JsValue::Function {
+                    name: name.clone(),
+                    params: params.clone(),
+                    body: body.clone(),
+                    closure_env: scopes
+                        .iter()
+                        .map(|scope| match scope {
+                            ScopeRef::Global => self.global_scope,
+                            ScopeRef::Node(id) => self.scopes[*id],
+                        })
+                        .collect(),
+                    is_async: *is_async,
+                    is_generator: *is_generator,
+                    source_path: source_path.clone(),
+                    source_offset: *source_offset,
+                    properties: properties.map(|id| self.object(id)),
+                }
+            }
+            SnapValue::Host(name) => JsValue::NativeFunction {
+                name: name.clone(),
+                handler: NativeFunction::HostRef(name.clone()),
+            },
+        }
+    }
+
+    fn object(&self, id: usize) -> Gc<GcCell<JsObject>> {
+        match self.cells[id] {
+            Cell::Object(obj) => obj,
+            _ => unreachable!("snapshot node {id} is not a plain object"),
+        }
+    }
+
+    fn property(&self, prop: &PropertyNode) -> Property {
+        Property {
+            value: self.value(&prop.value),
+            getter: prop.getter.as_ref().map(|g| self.value(g)),
+            setter: prop.setter.as_ref().map(|s| self.value(s)),
+            writable: prop.writable,
+            enumerable: prop.enumerable,
+            configurable: prop.configurable,
+        }
+    }
+}
@@ -1,14 +1,73 @@
 use crate::errors::RuntimeError;
 use crate::runtime::gc::{Gc, GcCell, Heap};
-use crate::runtime::value::array::JsArray;
+use crate::runtime::value::array::{JsArray, MAX_ARRAY_LENGTH};
 use crate::runtime::value::JsValue;
 
+/// Methods arrays answer to, including the callback-taking ones the
+/// interpreter handles itself.
+pub const ARRAY_METHODS: &[&str] = &[
+    "concat",
+    "every",
+    "filter",
+    "find",
+    "findIndex",
+    "findLast",
+    "findLastIndex",
+    "forEach",
+    "includes",
+    "indexOf",
+    "join",
+    "map",
+    "pop",
+    "push",
+    "reduce",
+    "reduceRight",
+    "shift",
+    "slice",
+    "some",
+    "sort",
+    "splice",
+    "toSorted",
+    "toSpliced",
+    "toString",
+    "unshift",
+];
+
+/// Methods that call back into script, run by the interpreter rather than
+/// by `call_array_method`.
+pub const ARRAY_CALLBACK_METHODS: &[&str] = &[
+    "every",
+    "filter",
+    "find",
+    "findIndex",
+    "findLast",
+    "findLastIndex",
+    "forEach",
+    "join",
+    "map",
+    "reduce",
+    "reduceRight",
+    "some",
+    "sort",
+    "toSorted",
+    "toString",
+];
+
+/// Methods that shift or resize the elements in place, which arrays with
+/// a fixed length or redefined indices refuse.
+pub const ARRAY_SHIFTING_METHODS: &[&str] = &["push", "pop", "shift", "unshift", "splice"];
+
 pub fn call_array_method(
     arr: &Gc<GcCell<JsArray>>,
     method: &str,
     args: &[JsValue],
     heap: &mut Heap,
 ) -> Result<Option<JsValue>, RuntimeError> {
+    if ARRAY_SHIFTING_METHODS.contains(&method) && arr.borrow().has_pinned_layout() {
+        return Err(RuntimeError::TypeError {
+            message: format!("Cannot {method} an array with a fixed length or redefined indices"),
+        });
+    }
     match method {
         "push" => {
             let mut borrowed = arr.borrow_mut();
@@ -21,6 +80,44 @@ pub fn call_array_method(
             let mut borrowed = arr.borrow_mut();
             Ok(Some(borrowed.elements.pop().unwrap_or(JsValue::Undefined)))
         }
+        "shift" => {
+            let mut borrowed = arr.borrow_mut();
+            if borrowed.is_empty() {
+                return Ok(Some(JsValue::Undefined));
+            }
+            Ok(Some(borrowed.elements.remove(0)))
+        }
+        "unshift" => {
+            let mut borrowed = arr.borrow_mut();
+            check_length(borrowed.len() + args.len())?;
+            borrowed.elements.splice(0..0, args.iter().cloned());
+            Ok(Some(JsValue::Number(borrowed.len() as f64)))
+        }
+        "splice" => {
+            let mut borrowed = arr.borrow_mut();
+            let (start, delete_count) = splice_range(args, borrowed.len());
+            let items = args.get(2..).unwrap_or_default();
+            check_length(borrowed.len() - delete_count + items.len())?;
+            let removed: Vec<JsValue> = borrowed
+                .elements
+                .splice(start..start + delete_count, items.iter().cloned())
+                .collect();
+            drop(borrowed);
+            Ok(Some(JsValue::Array(heap.alloc_cell(JsArray::new(removed)))))
+        }
+        "toSpliced" => {
+            let borrowed = arr.borrow();
+            let (start, skip) = splice_range(args, borrowed.len());
+            let items = args.get(2..).unwrap_or_default();
+            let new_len = borrowed.len() - skip + items.len();
+            check_length(new_len)?;
+            let mut result = Vec::with_capacity(new_len);
+            result.extend_from_slice(&borrowed.elements[..start]);
+            result.extend_from_slice(items);
+            result.extend_from_slice(&borrowed.elements[start + skip..]);
+            drop(borrowed);
+            Ok(Some(JsValue::Array(heap.alloc_cell(JsArray::new(result)))))
+        }
         "includes" => {
             let target = args.first().unwrap_or(&JsValue::Undefined);
             let borrowed = arr.borrow();
@@ -33,15 +130,6 @@ pub fn call_array_method(
             let idx = borrowed.elements.iter().position(|v| v == target);
             Ok(Some(JsValue::Number(idx.map_or(-1.0, |i| i as f64))))
         }
-        "join" => {
-            let sep = match args.first() {
-                Some(JsValue::String(s)) => s.clone(),
-                _ => ",".to_string(),
-            };
-            let borrowed = arr.borrow();
-            let items: Vec<String> = borrowed.elements.iter().map(|v| v.to_js_string()).collect();
-            Ok(Some(JsValue::String(items.join(&sep))))
-        }
         "slice" => {
             let borrowed = arr.borrow();
             let len = borrowed.len() as i64;
@@ -67,10 +155,32 @@ pub fn call_array_method(
     }
 }
 
-fn normalize_index(arg: Option<&JsValue>, default: i64, len: i64) -> usize {
+/// The start and delete count `splice` and `toSpliced` work on: a missing
+/// count reaches the end, and an explicit one is clamped to what is left.
+fn splice_range(args: &[JsValue], len: usize) -> (usize, usize) {
+    let start = normalize_index(args.first(), 0, len as i64);
+    let remaining = len - start;
+    let count = match args.get(1) {
+        None if args.is_empty() => 0,
+        None => remaining,
+        Some(count) => (count.to_number() as i64).clamp(0, remaining as i64) as usize,
+    };
+    (start, count)
+}
+
+fn check_length(len: usize) -> Result<(), RuntimeError> {
+    if len > MAX_ARRAY_LENGTH {
+        return Err(RuntimeError::TypeError {
+            message: "Invalid array length".to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn normalize_index(arg: Option<&JsValue>, default: i64, len: i64) -> usize {
     let val = match arg {
+        None | Some(JsValue::Undefined) => default,
         Some(v) => v.to_number() as i64,
-        None => default,
     };
     let idx = if val < 0 {
         (len + val).max(0)
@@ -1,16 +1,45 @@
 pub mod methods;
 
+use std::collections::BTreeMap;
+
 use super::JsValue;
-use crate::runtime::gc::{Trace, Tracer};
+use super::object::{JsObject, Property};
+use crate::runtime::gc::{Gc, GcCell, Trace, Tracer};
+
+/// Guards against absurdly large arrays that would exhaust memory.
+pub(crate) const MAX_ARRAY_LENGTH: usize = 4 * 1024 * 1024; // 4M elements
 
 #[derive(Debug, Clone)]
 pub struct JsArray {
     pub elements: Vec<JsValue>,
+    /// Explicit `[[Prototype]]`; `None` means the realm's `Array.prototype`.
+    pub prototype: Option<Gc<GcCell<JsObject>>>,
+    /// Indices redefined through `Object.defineProperty`. A data index
+    /// keeps its value in `elements` and only its attributes here; an
+    /// accessor index keeps its getter and setter here instead.
+    pub index_attributes: BTreeMap<usize, Property>,
+    pub length_writable: bool,
+    /// Own properties whose keys are not array indices, such as `"01"`
+    /// or a symbol. Allocated when the first one is stored.
+    pub properties: Option<Gc<GcCell<JsObject>>>,
+}
+
+/// The array index `key` names, if it is one in canonical form: `"1"` is
+/// an index while `"01"`, `"1.0"` and `" 1"` are ordinary keys.
+pub(crate) fn array_index(key: &str) -> Option<usize> {
+    let index: u32 = key.parse().ok()?;
+    (index != u32::MAX && index.to_string() == key).then_some(index as usize)
 }
 
 impl JsArray {
     pub fn new(elements: Vec<JsValue>) -> Self {
-        Self { elements }
+        Self {
+            elements,
+            prototype: None,
+            index_attributes: BTreeMap::new(),
+            length_writable: true,
+            properties: None,
+        }
     }
 
     pub fn get(&self, index: usize) -> JsValue {
@@ -21,8 +50,6 @@ impl JsArray {
     }
 
     pub fn set(&mut self, index: usize, value: JsValue) {
-        // Guard against absurdly large sparse arrays that would exhaust memory
-        const MAX_ARRAY_LENGTH: usize = 4 * 1024 * 1024; // 4M elements
         if index >= MAX_ARRAY_LENGTH {
             return;
         }
@@ -32,6 +59,124 @@ impl JsArray {
         self.elements[index] = value;
     }
 
+    /// The own property at `index` as `getOwnPropertyDescriptor` reports
+    /// it, with the current element as the value of a data index.
+    pub fn index_property(&self, index: usize) -> Option<Property> {
+        match self.index_attributes.get(&index) {
+            Some(prop) if prop.is_accessor() => Some(prop.clone()),
+            Some(prop) => Some(Property {
+                value: self.get(index),
+                ..prop.clone()
+            }),
+            None => (index < self.len()).then(|| Property::new(self.get(index))),
+        }
+    }
+
+    /// The getter and setter pair defined at `index`, if it is an
+    /// accessor rather than a data element.
+    pub fn index_accessor(&self, index: usize) -> Option<&Property> {
+        self.index_attributes
+            .get(&index)
+            .filter(|prop| prop.is_accessor())
+    }
+
+    /// Whether some index is an accessor, so that reading the elements
+    /// means calling its getter.
+    pub fn has_index_accessors(&self) -> bool {
+        self.index_attributes.values().any(Property::is_accessor)
+    }
+
+    /// Whether an ordinary assignment to `index` may store a value.
+    pub fn index_writable(&self, index: usize) -> bool {
+        match self.index_attributes.get(&index) {
+            Some(prop) => prop.writable && !prop.is_accessor(),
+            None => index < self.len() || self.length_writable,
+        }
+    }
+
+    /// Stores `prop` as the own property at `index`, growing the array
+    /// when the index lies past the end.
+    pub fn define_index(&mut self, index: usize, prop: Property) {
+        if index >= MAX_ARRAY_LENGTH {
+            return;
+        }
+        if prop.is_accessor() {
+            if index >= self.len() {
+                self.elements.resize(index + 1, JsValue::Undefined);
+            }
+            self.elements[index] = JsValue::Undefined;
+            self.index_attributes.insert(index, prop);
+            return;
+        }
+        self.set(index, prop.value.clone());
+        if prop.writable && prop.enumerable && prop.configurable {
+            self.index_attributes.remove(&index);
+        } else {
+            self.index_attributes.insert(
+                index,
+                Property {
+                    value: JsValue::Undefined,
+                    ..prop
+                },
+            );
+        }
+    }
+
+    /// Changes the length, dropping elements past the new end. Refuses,
+    /// leaving the array untouched, when that would remove a
+    /// non-configurable index or the length is not writable.
+    pub fn set_length(&mut self, len: usize) -> bool {
+        if len == self.len() {
+            return true;
+        }
+        if !self.length_writable || len > MAX_ARRAY_LENGTH {
+            return false;
+        }
+        if self
+            .index_attributes
+            .range(len..)
+            .any(|(_, prop)| !prop.configurable)
+        {
+            return false;
+        }
+        self.index_attributes.split_off(&len);
+        self.elements.resize(len, JsValue::Undefined);
+        true
+    }
+
+    /// Makes every index and the length read-only and non-configurable.
+    pub fn freeze(&mut self) {
+        for index in 0..self.len() {
+            let prop = self
+                .index_attributes
+                .entry(index)
+                .or_insert_with(|| Property::new(JsValue::Undefined));
+            prop.configurable = false;
+            if !prop.is_accessor() {
+                prop.writable = false;
+            }
+        }
+        self.length_writable = false;
+        if let Some(props) = self.properties {
+            props.borrow_mut().freeze();
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        !self.length_writable
+            && (0..self.len()).all(|index| {
+                self.index_attributes.get(&index).is_some_and(|prop| {
+                    !prop.configurable && (prop.is_accessor() || !prop.writable)
+                })
+            })
+    }
+
+    /// Whether the length is read-only or some index carries its own
+    /// attributes, which shifting elements would leave at the wrong index.
+    pub fn has_pinned_layout(&self) -> bool {
+        !self.length_writable || !self.index_attributes.is_empty()
+    }
+
     pub fn len(&self) -> usize {
         self.elements.len()
     }
@@ -44,5 +189,14 @@ impl JsArray {
 impl Trace for JsArray {
     fn trace(&self, tracer: &mut Tracer) {
         self.elements.trace(tracer);
+        for prop in self.index_attributes.values() {
+            prop.trace(tracer);
+        }
+        if let Some(proto) = &self.prototype {
+            tracer.mark(*proto);
+        }
+        if let Some(props) = &self.properties {
+            tracer.mark(*props);
+        }
     }
 }
@@ -1,6 +1,98 @@
 use super::JsValue;
 use crate::errors::RuntimeError;
-use crate::parser::ast::{Literal, UnaryOp};
+use crate::parser::ast::{Literal, Param, Pattern, UnaryOp};
+
+/// `ToNumber` applied to a string value.
+pub fn string_to_number(s: &str) -> f64 {
+    let t = s.trim();
+    if t.is_empty() {
+        0.0
+    } else if let Some(h) = t.strip_prefix("0x").or_else(|| t.strip_prefix("0X")) {
+        radix_digits_to_number(h, 16)
+    } else if let Some(o) = t.strip_prefix("0o").or_else(|| t.strip_prefix("0O")) {
+        radix_digits_to_number(o, 8)
+    } else if let Some(b) = t.strip_prefix("0b").or_else(|| t.strip_prefix("0B")) {
+        radix_digits_to_number(b, 2)
+    } else {
+        t.parse::<f64>().unwrap_or(f64::NAN)
+    }
+}
+
+/// The mathematical value of a run of `radix` digits, rounded to the
+/// nearest double. `NaN` when the run is empty or has a stray character.
+pub fn radix_digits_to_number(digits: &str, radix: u32) -> f64 {
+    if digits.is_empty() {
+        return f64::NAN;
+    }
+    digits
+        .chars()
+        .try_fold(0.0, |acc: f64, c| {
+            c.to_digit(radix).map(|d| acc * radix as f64 + d as f64)
+        })
+        .unwrap_or(f64::NAN)
+}
+
+/// `Number::toString(x)`: the shortest digits that round-trip, written
+/// positionally unless the decimal exponent is at least 21 or below -6.
+pub fn number_to_string(n: f64) -> String {
+    if n.is_nan() {
+        return "NaN".into();
+    }
+    if n == 0.0 {
+        return "0".into();
+    }
+    if n.is_infinite() {
+        return if n < 0.0 { "-Infinity" } else { "Infinity" }.into();
+    }
+    if n < 0.0 {
+        return format!("-{}", number_to_string(-n));
+    }
+
+    // `{:e}` yields the shortest round-trip digits as `d.ddde±x`.
+    let sci = format!("{n:e}");
+    let (mantissa, exponent) = sci.split_once('e').expect("exponent form");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let point = exponent.parse::<i32>().expect("integer exponent") + 1;
+
+    if k <= point && point <= 21 {
+        format!("{digits}{}", "0".repeat((point - k) as usize))
+    } else if 0 < point && point <= 21 {
+        let (int, frac) = digits.split_at(point as usize);
+        format!("{int}.{frac}")
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{digits}", "0".repeat(-point as usize))
+    } else {
+        let e = point - 1;
+        let sign = if e < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        if rest.is_empty() {
+            format!("{first}e{sign}{}", e.abs())
+        } else {
+            format!("{first}.{rest}e{sign}{}", e.abs())
+        }
+    }
+}
+
+/// `ToInt32`: the number truncated and wrapped modulo 2^32 into the
+/// signed 32-bit range. `NaN` and infinities become 0.
+pub fn to_int32(value: f64) -> i32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32 as i32
+}
+
+/// `ToUint32`: the same wrapping as [`to_int32`], read as unsigned.
+pub fn to_uint32(value: f64) -> u32 {
+    to_int32(value) as u32
+}
+
+/// The shift count of `<<`, `>>` and `>>>`: only the low five bits of
+/// the right operand are used.
+pub fn shift_count(value: f64) -> u32 {
+    to_uint32(value) & 0x1f
+}
 
 impl JsValue {
     pub fn get_primitive_value(&self) -> Option<JsValue> {
@@ -20,26 +112,7 @@ impl JsValue {
             JsValue::Boolean(true) => 1.0,
             JsValue::Boolean(false) => 0.0,
             JsValue::Number(n) => *n,
-            JsValue::String(s) => {
-                let t = s.trim();
-                if t.is_empty() {
-                    0.0
-                } else if let Some(h) = t.strip_prefix("0x").or_else(|| t.strip_prefix("0X")) {
-                    u64::from_str_radix(h, 16)
-                        .map(|v| v as f64)
-                        .unwrap_or(f64::NAN)
-                } else if let Some(o) = t.strip_prefix("0o").or_else(|| t.strip_prefix("0O")) {
-                    u64::from_str_radix(o, 8)
-                        .map(|v| v as f64)
-                        .unwrap_or(f64::NAN)
-                } else if let Some(b) = t.strip_prefix("0b").or_else(|| t.strip_prefix("0B")) {
-                    u64::from_str_radix(b, 2)
-                        .map(|v| v as f64)
-                        .unwrap_or(f64::NAN)
-                } else {
-                    t.parse::<f64>().unwrap_or(f64::NAN)
-                }
-            }
+            JsValue::String(s) => string_to_number(s),
             JsValue::Object(_) => self
                 .get_primitive_value()
                 .map(|p| p.to_number())
@@ -71,15 +144,13 @@ impl JsValue {
             JsValue::Undefined => "undefined".into(),
             JsValue::Null => "null".into(),
             JsValue::Boolean(b) => b.to_string(),
-            JsValue::Number(n) => {
-                if n.is_finite() && n.fract() == 0.0 {
-                    format!("{}", *n as i64)
-                } else {
-                    n.to_string()
-                }
-            }
-            JsValue::String(s) => s.clone(),
-            JsValue::Function { name, .. } | JsValue::NativeFunction { name, .. } => {
+            JsValue::Number(n) => number_to_string(*n),
+            JsValue::String(s) => s.to_string(),
+            JsValue::Function { name, body, .. } => match body.source_text() {
+                Some(text) => text.to_string(),
+                None => format!("function {}() {{ [native code] }}", function_name(name)),
+            },
+            JsValue::NativeFunction { name, .. } => {
                 format!("function {name}() {{ [native code] }}")
             }
             JsValue::Symbol(sym) => sym.to_string(),
@@ -100,11 +171,45 @@ impl JsValue {
             JsValue::WeakMap(_) => "[object WeakMap]".into(),
             JsValue::WeakSet(_) => "[object WeakSet]".into(),
             JsValue::RegExp(re) => re.borrow().to_string(),
+            JsValue::ArrayBuffer(_) => "[object ArrayBuffer]".into(),
+            JsValue::TypedArray(array) => array
+                .borrow()
+                .values()
+                .into_iter()
+                .map(number_to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+            JsValue::DataView(_) => "[object DataView]".into(),
             JsValue::Proxy(_) => "[object Object]".into(),
         }
     }
 }
 
+/// The `name` a script sees for a function. Class members are kept as
+/// `Class::member` and unnamed functions as `<arrow>` or `<anonymous>`;
+/// those read as the member name, the class name for its constructor,
+/// and the empty string.
+pub(crate) fn function_name(name: &str) -> &str {
+    match name.split_once("::") {
+        Some((class, "constructor")) => class,
+        Some((_, member)) => member,
+        None if name.starts_with('<') => "",
+        None => name,
+    }
+}
+
+/// A function's `length`: the parameters before the first one with a
+/// default value or the rest parameter.
+pub(crate) fn function_length(params: &[Param]) -> usize {
+    params
+        .iter()
+        .take_while(|param| {
+            param.default.is_none()
+                && !matches!(param.pattern, Pattern::Rest(_) | Pattern::Default { .. })
+        })
+        .count()
+}
+
 pub fn abstract_equals(a: &JsValue, b: &JsValue) -> bool {
     use JsValue::*;
     match (a, b) {
@@ -120,18 +225,44 @@ pub fn abstract_equals(a: &JsValue, b: &JsValue) -> bool {
 pub fn eval_literal(lit: &Literal) -> JsValue {
     match lit {
         Literal::Number(n) => JsValue::Number(*n),
-        Literal::String(s) => JsValue::String(s.clone()),
+        Literal::String(s) => JsValue::String(s.into()),
         Literal::Boolean(b) => JsValue::Boolean(*b),
         Literal::Null => JsValue::Null,
         Literal::Undefined => JsValue::Undefined,
     }
 }
 
+/// `+` once both operands are primitives: string concatenation when
+/// either side is a string, numeric addition otherwise.
+pub fn add_primitives(lhs: &JsValue, rhs: &JsValue) -> JsValue {
+    if matches!(lhs, JsValue::String(_)) || matches!(rhs, JsValue::String(_)) {
+        return JsValue::String(format!("{}{}", lhs.to_js_string(), rhs.to_js_string()).into());
+    }
+    JsValue::Number(lhs.to_number() + rhs.to_number())
+}
+
+/// The abstract relational comparison `lhs < rhs` on primitives. Two
+/// strings compare by UTF-16 code units, anything else numerically; `None`
+/// means a `NaN` left the operands unordered, which makes every relational
+/// operator false.
+pub fn primitive_less_than(lhs: &JsValue, rhs: &JsValue) -> Option<bool> {
+    if let (JsValue::String(a), JsValue::String(b)) = (lhs, rhs) {
+        return Some(a.encode_utf16().lt(b.encode_utf16()));
+    }
+    let (a, b) = (lhs.to_number(), rhs.to_number());
+    if a.is_nan() || b.is_nan() {
+        None
+    } else {
+        Some(a < b)
+    }
+}
+
 pub fn eval_unary(op: &UnaryOp, val: JsValue) -> Result<JsValue, RuntimeError> {
     match op {
         UnaryOp::Neg => Ok(JsValue::Number(-val.to_number())),
         UnaryOp::Not => Ok(JsValue::Boolean(!val.to_boolean())),
         UnaryOp::Void => Ok(JsValue::Undefined),
         UnaryOp::Pos => Ok(JsValue::Number(val.to_number())),
+        UnaryOp::BitNot => Ok(JsValue::Number(!to_int32(val.to_number()) as f64)),
     }
 }
@@ -0,0 +1,115 @@
+//! Locale-independent string collation for `localeCompare` and the
+//! default `Array.prototype.sort` order.
+//!
+//! There is no ICU here: every locale collates the same way. Strings are
+//! compared first by their letters with case and accents folded away, then
+//! by accents, then by case (lowercase first), and finally by code point,
+//! which is close enough to the root collation for Latin text.
+
+use std::cmp::Ordering;
+
+/// Which differences `localeCompare` treats as significant, after the
+/// `sensitivity` option of `Intl.Collator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Sensitivity {
+    /// Only base letters: `a = á = A`.
+    Base,
+    /// Base letters and accents: `a ≠ á`, `a = A`.
+    Accent,
+    /// Base letters and case: `a ≠ A`, `a = á`.
+    Case,
+    /// Every difference, down to the code points.
+    Variant,
+}
+
+impl Sensitivity {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "base" => Some(Self::Base),
+            "accent" => Some(Self::Accent),
+            "case" => Some(Self::Case),
+            "variant" => Some(Self::Variant),
+            _ => None,
+        }
+    }
+}
+
+/// The order of the default sort: code point by code point.
+pub(crate) fn compare_strings(a: &str, b: &str) -> Ordering {
+    a.cmp(b)
+}
+
+pub(crate) fn locale_compare(a: &str, b: &str, sensitivity: Sensitivity) -> Ordering {
+    let (a, b) = (CollationKey::new(a), CollationKey::new(b));
+    let mut order = a.base.cmp(&b.base);
+    if matches!(sensitivity, Sensitivity::Accent | Sensitivity::Variant) {
+        order = order.then_with(|| a.accented.cmp(&b.accented));
+    }
+    if matches!(sensitivity, Sensitivity::Case | Sensitivity::Variant) {
+        order = order.then_with(|| a.upper.cmp(&b.upper));
+    }
+    order
+}
+
+/// A string split into the levels `locale_compare` looks at in turn.
+struct CollationKey {
+    /// Lowercase letters with accents removed.
+    base: Vec<char>,
+    /// Whether each letter carried an accent.
+    accented: Vec<bool>,
+    /// Whether each letter was uppercase.
+    upper: Vec<bool>,
+}
+
+impl CollationKey {
+    fn new(s: &str) -> Self {
+        let mut key = Self {
+            base: Vec::new(),
+            accented: Vec::new(),
+            upper: Vec::new(),
+        };
+        for c in s.chars() {
+            if is_combining_mark(c) {
+                if let Some(last) = key.accented.last_mut() {
+                    *last = true;
+                }
+                continue;
+            }
+            let folded = strip_accent(c);
+            key.base.extend(folded.unwrap_or(c).to_lowercase());
+            key.accented.push(folded.is_some());
+            key.upper.push(c.is_uppercase());
+        }
+        key
+    }
+}
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}')
+}
+
+/// Base letters of Latin-1 Supplement `U+00C0..=U+00FF`; `.` marks
+/// characters that are letters of their own.
+const LATIN_1: &str = "AAAAAA.CEEEEIIII.NOOOOO.OUUUUY..aaaaaa.ceeeeiiii.nooooo.ouuuuy.y";
+/// Base letters of Latin Extended-A `U+0100..=U+017F`.
+const LATIN_EXTENDED_A: &str = concat!(
+    "AaAaAaCcCcCcCcDd",
+    "DdEeEeEeEeEeGgGg",
+    "GgGgHhHhIiIiIiIi",
+    "Ii..JjKk.LlLlLlL",
+    "lLlNnNnNn...OoOo",
+    "Oo..RrRrRrSsSsSs",
+    "SsTtTtTtUuUuUuUu",
+    "UuUuWwYyYZzZzZz.",
+);
+
+/// The unaccented letter for a precomposed Latin letter with an accent.
+fn strip_accent(c: char) -> Option<char> {
+    let (table, start) = match c {
+        '\u{00C0}'..='\u{00FF}' => (LATIN_1, 0xC0),
+        '\u{0100}'..='\u{017F}' => (LATIN_EXTENDED_A, 0x100),
+        _ => return None,
+    };
+    let base = table.as_bytes()[c as usize - start] as char;
+    (base != '.').then_some(base)
+}
@@ -0,0 +1,71 @@
+use crate::runtime::gc::{Gc, GcCell, Trace, Tracer};
+use crate::runtime::value::JsValue;
+use crate::runtime::value::collections::map::JsMap;
+use crate::runtime::value::collections::set::JsSet;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IterationKind {
+    Keys,
+    Values,
+    Entries,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum IteratedCollection {
+    Map(Gc<GcCell<JsMap>>),
+    Set(Gc<GcCell<JsSet>>),
+}
+
+/// A live Map or Set iterator. Rather than snapshotting the entries it
+/// remembers the sequence number of the next entry to visit, so entries
+/// added during iteration are visited and deleted ones are skipped.
+#[derive(Debug, Clone)]
+pub struct JsCollectionIterator {
+    /// `None` once exhausted; a finished iterator stays finished even if
+    /// the collection grows afterwards.
+    collection: Option<IteratedCollection>,
+    pub kind: IterationKind,
+    next_seq: u64,
+}
+
+impl JsCollectionIterator {
+    pub fn new(collection: IteratedCollection, kind: IterationKind) -> Self {
+        Self {
+            collection: Some(collection),
+            kind,
+            next_seq: 0,
+        }
+    }
+
+    /// Advances to the next live entry, returning its key and value
+    /// (a Set entry's key is its value).
+    pub fn next_entry(&mut self) -> Option<(JsValue, JsValue)> {
+        let next = match self.collection? {
+            IteratedCollection::Map(map) => map.borrow().entry_from(self.next_seq),
+            IteratedCollection::Set(set) => set
+                .borrow()
+                .entry_from(self.next_seq)
+                .map(|(seq, v)| (seq, v.clone(), v)),
+        };
+        match next {
+            Some((seq, key, value)) => {
+                self.next_seq = seq + 1;
+                Some((key, value))
+            }
+            None => {
+                self.collection = None;
+                None
+            }
+        }
+    }
+}
+
+impl Trace for JsCollectionIterator {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self.collection {
+            Some(IteratedCollection::Map(map)) => tracer.mark(map),
+            Some(IteratedCollection::Set(set)) => tracer.mark(set),
+            None => {}
+        }
+    }
+}
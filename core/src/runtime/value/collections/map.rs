@@ -3,16 +3,34 @@ use crate::runtime::value::JsValue;
 
 #[derive(Debug, Clone)]
 pub struct JsMap {
-    pub entries: Vec<(JsValue, JsValue)>,
+    entries: Vec<(JsValue, JsValue)>,
+    /// Insertion sequence number of each entry, strictly increasing, so
+    /// live iterators can resume after entries are deleted or added.
+    seqs: Vec<u64>,
+    next_seq: u64,
 }
 
 impl JsMap {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            seqs: Vec::new(),
+            next_seq: 0,
         }
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &(JsValue, JsValue)> {
+        self.entries.iter()
+    }
+
+    /// The first live entry inserted at or after sequence number `seq`,
+    /// along with its own sequence number.
+    pub fn entry_from(&self, seq: u64) -> Option<(u64, JsValue, JsValue)> {
+        let idx = self.seqs.partition_point(|&s| s < seq);
+        let (k, v) = self.entries.get(idx)?;
+        Some((self.seqs[idx], k.clone(), v.clone()))
+    }
+
     pub fn size(&self) -> usize {
         self.entries.len()
     }
@@ -38,16 +56,24 @@ impl JsMap {
             }
         }
         self.entries.push((key, value));
+        self.seqs.push(self.next_seq);
+        self.next_seq += 1;
     }
 
     pub fn delete(&mut self, key: &JsValue) -> bool {
-        let len = self.entries.len();
-        self.entries.retain(|(k, _)| !map_key_eq(k, key));
-        self.entries.len() != len
+        match self.entries.iter().position(|(k, _)| map_key_eq(k, key)) {
+            Some(idx) => {
+                self.entries.remove(idx);
+                self.seqs.remove(idx);
+                true
+            }
+            None => false,
+        }
     }
 
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.seqs.clear();
     }
 }
 
@@ -1,3 +1,4 @@
+pub mod iterator;
 pub mod map;
 pub mod set;
 pub mod weak_map;
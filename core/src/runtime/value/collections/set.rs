@@ -3,16 +3,33 @@ use crate::runtime::value::JsValue;
 
 #[derive(Debug, Clone)]
 pub struct JsSet {
-    pub entries: Vec<JsValue>,
+    entries: Vec<JsValue>,
+    /// Insertion sequence number of each entry; see `JsMap`.
+    seqs: Vec<u64>,
+    next_seq: u64,
 }
 
 impl JsSet {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            seqs: Vec::new(),
+            next_seq: 0,
         }
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &JsValue> {
+        self.entries.iter()
+    }
+
+    /// The first live value inserted at or after sequence number `seq`,
+    /// along with its own sequence number.
+    pub fn entry_from(&self, seq: u64) -> Option<(u64, JsValue)> {
+        let idx = self.seqs.partition_point(|&s| s < seq);
+        let value = self.entries.get(idx)?;
+        Some((self.seqs[idx], value.clone()))
+    }
+
     pub fn size(&self) -> usize {
         self.entries.len()
     }
@@ -24,17 +41,25 @@ impl JsSet {
     pub fn add(&mut self, value: JsValue) {
         if !self.has(&value) {
             self.entries.push(value);
+            self.seqs.push(self.next_seq);
+            self.next_seq += 1;
         }
     }
 
     pub fn delete(&mut self, value: &JsValue) -> bool {
-        let len = self.entries.len();
-        self.entries.retain(|v| !set_val_eq(v, value));
-        self.entries.len() != len
+        match self.entries.iter().position(|v| set_val_eq(v, value)) {
+            Some(idx) => {
+                self.entries.remove(idx);
+                self.seqs.remove(idx);
+                true
+            }
+            None => false,
+        }
     }
 
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.seqs.clear();
     }
 }
 
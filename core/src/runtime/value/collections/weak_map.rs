@@ -57,6 +57,9 @@ pub fn extract_weak_key(value: &JsValue) -> Option<ErasedGc> {
         JsValue::Array(gc) => Some(gc.erase()),
         JsValue::Map(gc) => Some(gc.erase()),
         JsValue::Set(gc) => Some(gc.erase()),
+        JsValue::ArrayBuffer(gc) => Some(gc.erase()),
+        JsValue::TypedArray(gc) => Some(gc.erase()),
+        JsValue::DataView(gc) => Some(gc.erase()),
         _ => None,
     }
 }
@@ -1,4 +1,4 @@
-use super::JsValue;
+use super::{JsValue, number_to_string};
 use std::fmt;
 
 impl fmt::Display for JsValue {
@@ -7,13 +7,7 @@ impl fmt::Display for JsValue {
             JsValue::Undefined => write!(f, "undefined"),
             JsValue::Null => write!(f, "null"),
             JsValue::Boolean(b) => write!(f, "{b}"),
-            JsValue::Number(n) => {
-                if n.fract() == 0.0 && n.is_finite() {
-                    write!(f, "{}", *n as i64)
-                } else {
-                    write!(f, "{n}")
-                }
-            }
+            JsValue::Number(n) => f.write_str(&number_to_string(*n)),
             JsValue::String(s) => write!(f, "{s}"),
             JsValue::Function { name, .. } => {
                 write!(f, "function {name}() {{ [native code] }}")
@@ -27,6 +21,7 @@ impl fmt::Display for JsValue {
                 let mut pairs: Vec<String> = obj
                     .properties
                     .iter()
+                    .filter(|(_, p)| p.enumerable)
                     .map(|(k, p)| format!("{k}: {}", p.value))
                     .collect();
                 pairs.sort();
@@ -60,7 +55,78 @@ impl fmt::Display for JsValue {
             JsValue::WeakMap(_) => write!(f, "WeakMap {{}}"),
             JsValue::WeakSet(_) => write!(f, "WeakSet {{}}"),
             JsValue::RegExp(re) => write!(f, "{}", re.borrow()),
+            JsValue::ArrayBuffer(buffer) => {
+                write!(f, "ArrayBuffer({})", buffer.borrow().bytes.len())
+            }
+            JsValue::TypedArray(array) => {
+                let array = array.borrow();
+                let items: Vec<String> = array.values().into_iter().map(number_to_string).collect();
+                write!(
+                    f,
+                    "{}({}) [{}]",
+                    array.kind.constructor_name(),
+                    items.len(),
+                    items.join(", ")
+                )
+            }
+            JsValue::DataView(view) => write!(f, "DataView({})", view.borrow().byte_length()),
             JsValue::Proxy(_) => write!(f, "Proxy {{}}"),
         }
     }
 }
+
+impl JsValue {
+    /// An error object as `Name: message`, followed by its `cause` chain
+    /// with each cause on its own line, indented under the error it
+    /// explains. A cause met earlier in the chain is shown as
+    /// `[Circular]`. `None` when the value is not an error object.
+    pub fn error_chain(&self) -> Option<String> {
+        let mut out = error_line(self)?;
+        let mut seen = vec![self.clone()];
+        let mut current = self.clone();
+        while let Some(cause) = error_cause(&current) {
+            out.push('\n');
+            out.push_str(&"  ".repeat(seen.len()));
+            out.push_str("caused by: ");
+            if seen.contains(&cause) {
+                out.push_str("[Circular]");
+                break;
+            }
+            match error_line(&cause) {
+                Some(line) => out.push_str(&line),
+                None => {
+                    out.push_str(&cause.to_string());
+                    break;
+                }
+            }
+            seen.push(cause.clone());
+            current = cause;
+        }
+        Some(out)
+    }
+}
+
+/// `Name: message` for an error object, or just the name when the
+/// message is empty.
+fn error_line(value: &JsValue) -> Option<String> {
+    let JsValue::Object(obj) = value else {
+        return None;
+    };
+    let obj = obj.borrow();
+    obj.get("[[ErrorType]]")?;
+    let name = obj
+        .get("name")
+        .map_or("Error".into(), |name| name.to_js_string());
+    let message = obj.get("message").map(|message| message.to_js_string());
+    Some(match message {
+        Some(message) if !message.is_empty() => format!("{name}: {message}"),
+        _ => name,
+    })
+}
+
+fn error_cause(value: &JsValue) -> Option<JsValue> {
+    match value {
+        JsValue::Object(obj) => obj.borrow().get("cause"),
+        _ => None,
+    }
+}
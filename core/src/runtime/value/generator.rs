@@ -1,75 +1,199 @@
 use std::collections::VecDeque;
+use std::rc::Rc;
 
 use crate::parser::ast::{Param, Stmt};
 use crate::runtime::environment::Scope;
 use crate::runtime::gc::{Gc, GcCell, Trace, Tracer};
 use crate::runtime::value::JsValue;
+use crate::runtime::value::iterator::IterationCursor;
+use crate::runtime::value::promise::JsPromise;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum GeneratorState {
     SuspendedStart,
+    SuspendedYield,
     Executing,
     Completed,
 }
 
+/// How a paused generator is resumed: `next(v)`, `return(v)` or `throw(v)`.
+#[derive(Debug, Clone)]
+pub enum GeneratorResume {
+    Next(JsValue),
+    Return(JsValue),
+    Throw(JsValue),
+}
+
+/// One level of the statement path from a generator body down to its
+/// paused `yield`. Frames are recorded innermost first while the body
+/// unwinds and consumed outermost first when it resumes.
+#[derive(Debug, Clone)]
+pub enum ResumeFrame {
+    /// Index of the paused statement in a statement list.
+    Statement(usize),
+    /// Paused in an `if` condition (`None`) or one of its branches.
+    Branch(Option<bool>),
+    /// Paused in part of a loop. `Condition` also covers the head
+    /// expression of `for...of` and `for...in`.
+    Loop(LoopPosition),
+    /// Paused in a `for...of`/`for...in` body, with where the loop has
+    /// got to in what it iterates.
+    ForEach(IterationCursor),
+    /// Paused in one block of a `try` statement.
+    Try(TryPosition),
+    /// Paused in a `switch` discriminant (`None`) or in a case body.
+    Switch(Option<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoopPosition {
+    Init,
+    Condition,
+    Body,
+    Update,
+}
+
+#[derive(Debug, Clone)]
+pub enum TryPosition {
+    /// The `try` block of a statement entered with `depth` scopes open,
+    /// which an error unwinds the environment back to.
+    Try {
+        depth: usize,
+    },
+    Catch {
+        depth: usize,
+    },
+    /// The `finally` block, with the completion it interrupted.
+    Finally(PendingCompletion),
+}
+
+/// A completion held back while a `finally` block runs.
+#[derive(Debug, Clone)]
+pub enum PendingCompletion {
+    Normal,
+    Return(JsValue),
+    Break(Option<String>),
+    Continue(Option<String>),
+    Throw(JsValue),
+}
+
+/// A queued `next`/`return`/`throw` call on an async generator, settled
+/// in order once the generator gets to it.
+#[derive(Debug, Clone)]
+pub struct AsyncGeneratorRequest {
+    pub resume: GeneratorResume,
+    pub promise: Gc<GcCell<JsPromise>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct JsGenerator {
     pub state: GeneratorState,
-    pub body: Vec<Stmt>,
-    pub params: Vec<Param>,
+    pub is_async: bool,
+    pub body: Rc<[Stmt]>,
+    pub params: Rc<[Param]>,
     pub captured_env: Vec<Gc<GcCell<Scope>>>,
     pub this_binding: Option<JsValue>,
     pub args: Vec<JsValue>,
-    pub yielded_values: VecDeque<JsValue>,
-    pub return_value: JsValue,
+    /// Scope chain live at the paused `yield`.
+    pub scopes: Vec<Gc<GcCell<Scope>>>,
+    /// Path to the paused `yield`, innermost frame first.
+    pub frames: Vec<ResumeFrame>,
+    /// What the earlier yields of the paused expression evaluated to, so
+    /// the expression can be re-evaluated up to the paused one.
+    pub replay: Vec<JsValue>,
+    /// What the operands of the paused expression holding no `yield` or
+    /// `await` evaluated to, in order, handed back instead of evaluating
+    /// them again.
+    pub operands: Vec<JsValue>,
+    /// The iterator a paused `yield*` is delegating to.
+    pub delegate: Option<IterationCursor>,
+    pub queue: VecDeque<AsyncGeneratorRequest>,
+    /// Set when the body is an async function's rather than a
+    /// generator's: the promise the call returned, settled once the body
+    /// finishes. An `await` on a promise still pending pauses such a body
+    /// until the promise settles.
+    pub async_function: Option<Gc<GcCell<JsPromise>>>,
 }
 
 impl JsGenerator {
     pub fn new(
-        params: Vec<Param>,
-        body: Vec<Stmt>,
+        params: Rc<[Param]>,
+        body: Rc<[Stmt]>,
         captured_env: Vec<Gc<GcCell<Scope>>>,
         this_binding: Option<JsValue>,
         args: Vec<JsValue>,
+        is_async: bool,
     ) -> Self {
         Self {
             state: GeneratorState::SuspendedStart,
+            is_async,
             body,
             params,
             captured_env,
             this_binding,
             args,
-            yielded_values: VecDeque::new(),
-            return_value: JsValue::Undefined,
+            scopes: Vec::new(),
+            frames: Vec::new(),
+            replay: Vec::new(),
+            operands: Vec::new(),
+            delegate: None,
+            queue: VecDeque::new(),
+            async_function: None,
         }
     }
 
-    pub fn from_values(items: VecDeque<JsValue>) -> Self {
-        Self {
-            state: GeneratorState::Completed,
-            body: Vec::new(),
-            params: Vec::new(),
-            captured_env: Vec::new(),
-            this_binding: None,
-            args: Vec::new(),
-            yielded_values: items,
-            return_value: JsValue::Undefined,
+    /// Drops everything kept alive for resumption once the body is done.
+    pub fn complete(&mut self) {
+        self.state = GeneratorState::Completed;
+        self.scopes.clear();
+        self.frames.clear();
+        self.replay.clear();
+        self.operands.clear();
+        self.delegate = None;
+    }
+}
+
+impl Trace for GeneratorResume {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self {
+            GeneratorResume::Next(v) | GeneratorResume::Return(v) | GeneratorResume::Throw(v) => {
+                v.trace(tracer)
+            }
+        }
+    }
+}
+
+impl Trace for ResumeFrame {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self {
+            ResumeFrame::ForEach(cursor) => cursor.trace(tracer),
+            ResumeFrame::Try(TryPosition::Finally(
+                PendingCompletion::Return(v) | PendingCompletion::Throw(v),
+            )) => v.trace(tracer),
+            _ => {}
         }
     }
 }
 
 impl Trace for JsGenerator {
     fn trace(&self, tracer: &mut Tracer) {
-        for scope in &self.captured_env {
+        for scope in self.captured_env.iter().chain(&self.scopes) {
             tracer.mark(*scope);
         }
         self.this_binding.trace(tracer);
-        for arg in &self.args {
-            arg.trace(tracer);
+        for value in self.args.iter().chain(&self.replay).chain(&self.operands) {
+            value.trace(tracer);
+        }
+        self.delegate.trace(tracer);
+        for frame in &self.frames {
+            frame.trace(tracer);
+        }
+        for request in &self.queue {
+            request.resume.trace(tracer);
+            tracer.mark(request.promise);
         }
-        for val in &self.yielded_values {
-            val.trace(tracer);
+        if let Some(promise) = self.async_function {
+            tracer.mark(promise);
         }
-        self.return_value.trace(tracer);
     }
 }
@@ -1,6 +1,9 @@
-use crate::runtime::gc::Heap;
-use crate::runtime::value::object::JsObject;
+use std::collections::VecDeque;
+
+use crate::runtime::gc::{Heap, Trace, Tracer};
 use crate::runtime::value::JsValue;
+use crate::runtime::value::collections::iterator::JsCollectionIterator;
+use crate::runtime::value::object::JsObject;
 
 pub fn iter_result(value: JsValue, done: bool, heap: &mut Heap) -> JsValue {
     let mut obj = JsObject::new();
@@ -19,3 +22,110 @@ pub fn get_property_simple(value: &JsValue, key: &str) -> Option<JsValue> {
         _ => None,
     }
 }
+
+/// Where a `for...of` loop, spread or `yield*` has got to in the value it
+/// iterates. Only arrays, strings and typed arrays are read up front;
+/// Maps and Sets are walked live and anything else is stepped through
+/// the iterator protocol, one `next()` per value.
+#[derive(Debug, Clone)]
+pub enum IterationCursor {
+    Values(VecDeque<JsValue>),
+    Collection(JsCollectionIterator),
+    Protocol(Box<IteratorRecord>),
+}
+
+/// What an iterator helper does with the values it pulls.
+#[derive(Debug, Clone)]
+pub enum HelperKind {
+    Map(JsValue),
+    Filter(JsValue),
+    /// Values still to hand out before closing the underlying iterator.
+    Take(f64),
+    /// Values still to skip before the first one handed out.
+    Drop(f64),
+    FlatMap(JsValue),
+}
+
+/// An iterator together with its `next` method.
+#[derive(Debug, Clone)]
+pub struct IteratorRecord {
+    pub iterator: JsValue,
+    pub next: JsValue,
+}
+
+/// The values of the current `flatMap` callback result.
+#[derive(Debug, Clone)]
+pub enum FlatMapInner {
+    Iterator(Box<IteratorRecord>),
+    /// An array or collection, read up front.
+    Values(VecDeque<JsValue>),
+}
+
+/// The iterator returned by `map`, `filter`, `take`, `drop` and `flatMap`.
+/// It pulls from the underlying iterator only when its own `next` is
+/// called, so helpers work on endless generators.
+#[derive(Debug, Clone)]
+pub struct JsIteratorHelper {
+    pub kind: HelperKind,
+    pub underlying: JsValue,
+    /// The underlying iterator's `next`, read once when the helper is made.
+    pub next_method: JsValue,
+    /// How many values have been pulled, passed to callbacks as their
+    /// second argument.
+    pub counter: usize,
+    pub inner: Option<FlatMapInner>,
+    pub done: bool,
+}
+
+impl JsIteratorHelper {
+    pub fn new(kind: HelperKind, underlying: JsValue, next_method: JsValue) -> Self {
+        Self {
+            kind,
+            underlying,
+            next_method,
+            counter: 0,
+            inner: None,
+            done: false,
+        }
+    }
+}
+
+impl Trace for IterationCursor {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self {
+            IterationCursor::Values(values) => {
+                for value in values {
+                    value.trace(tracer);
+                }
+            }
+            IterationCursor::Collection(iter) => iter.trace(tracer),
+            IterationCursor::Protocol(record) => {
+                record.iterator.trace(tracer);
+                record.next.trace(tracer);
+            }
+        }
+    }
+}
+
+impl Trace for JsIteratorHelper {
+    fn trace(&self, tracer: &mut Tracer) {
+        match &self.kind {
+            HelperKind::Map(f) | HelperKind::Filter(f) | HelperKind::FlatMap(f) => f.trace(tracer),
+            HelperKind::Take(_) | HelperKind::Drop(_) => {}
+        }
+        self.underlying.trace(tracer);
+        self.next_method.trace(tracer);
+        match &self.inner {
+            Some(FlatMapInner::Iterator(record)) => {
+                record.iterator.trace(tracer);
+                record.next.trace(tracer);
+            }
+            Some(FlatMapInner::Values(values)) => {
+                for value in values {
+                    value.trace(tracer);
+                }
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+
+/// The text of a `JsValue::String`. Clones share one buffer, and appending
+/// to a string nothing else shares grows that buffer in place, so building
+/// a string with `+=` in a loop stays linear.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsString(Rc<String>);
+
+impl JsString {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Appends `tail`, copying the buffer first only when it is shared.
+    pub fn push_str(&mut self, tail: &str) {
+        Rc::make_mut(&mut self.0).push_str(tail);
+    }
+
+    pub fn into_string(self) -> String {
+        Rc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl Deref for JsString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for JsString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for JsString {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for JsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for JsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl From<String> for JsString {
+    fn from(value: String) -> Self {
+        Self(Rc::new(value))
+    }
+}
+
+impl From<&str> for JsString {
+    fn from(value: &str) -> Self {
+        Self(Rc::new(value.to_owned()))
+    }
+}
+
+impl From<&String> for JsString {
+    fn from(value: &String) -> Self {
+        Self(Rc::new(value.clone()))
+    }
+}
+
+impl From<char> for JsString {
+    fn from(value: char) -> Self {
+        Self(Rc::new(value.to_string()))
+    }
+}
+
+impl From<JsString> for String {
+    fn from(value: JsString) -> Self {
+        value.into_string()
+    }
+}
+
+impl PartialEq<str> for JsString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for JsString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for JsString {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<JsString> for str {
+    fn eq(&self, other: &JsString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<JsString> for &str {
+    fn eq(&self, other: &JsString) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<JsString> for String {
+    fn eq(&self, other: &JsString) -> bool {
+        self == other.as_str()
+    }
+}
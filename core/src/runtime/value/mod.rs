@@ -1,38 +1,59 @@
 pub mod array;
 mod coercion;
 pub mod collections;
+pub(crate) mod collation;
 mod display;
 pub mod generator;
 pub mod iterator;
+mod js_string;
+pub mod number_methods;
 pub mod object;
 pub mod promise;
+mod prop_name;
 pub mod proxy;
 pub mod regexp;
 pub mod string_methods;
 pub mod symbol;
+pub mod typed_array;
 
-pub use coercion::{abstract_equals, eval_literal, eval_unary};
+pub use coercion::{
+    abstract_equals, add_primitives, eval_literal, eval_unary, number_to_string,
+    primitive_less_than, radix_digits_to_number, shift_count, string_to_number, to_int32,
+    to_uint32,
+};
+pub(crate) use coercion::{function_length, function_name};
+pub use js_string::JsString;
+pub use prop_name::PropName;
+
+use std::path::PathBuf;
+use std::rc::Rc;
 
 use crate::embedding::callback::NativeFunctionBoxed;
-use crate::parser::ast::{Param, Stmt};
+use crate::embedding::event_target::EventTarget;
+use crate::parser::ast::{FunctionBody, Param};
 use crate::runtime::environment::Scope;
 use crate::runtime::gc::{Gc, GcCell, Trace, Tracer};
 use array::JsArray;
+use collections::iterator::JsCollectionIterator;
 use collections::map::JsMap;
 use collections::set::JsSet;
 use collections::weak_map::JsWeakMap;
 use collections::weak_set::JsWeakSet;
+use iterator::JsIteratorHelper;
 use generator::JsGenerator;
 use object::JsObject;
-use promise::JsPromise;
+use promise::{JsPromise, PromiseAnyState};
 use proxy::JsProxy;
 use regexp::JsRegExp;
 use symbol::JsSymbol;
+use typed_array::{JsArrayBuffer, JsDataView, JsTypedArray};
 
 #[derive(Debug, Clone)]
 pub enum NativeFunction {
     PromiseResolve(Gc<GcCell<JsPromise>>),
     PromiseReject(Gc<GcCell<JsPromise>>),
+    /// Records the rejection of input `.1` of a `Promise.any`.
+    PromiseAnyReject(Gc<GcCell<PromiseAnyState>>, usize),
     SetTimeout,
     SetInterval,
     ClearTimeout,
@@ -43,10 +64,34 @@ pub enum NativeFunction {
     SymbolConstructor,
     GeneratorNext(Gc<GcCell<JsGenerator>>),
     GeneratorReturn(Gc<GcCell<JsGenerator>>),
-    GeneratorThrow,
+    GeneratorThrow(Gc<GcCell<JsGenerator>>),
     GeneratorIterator,
+    AsyncGeneratorNext(Gc<GcCell<JsGenerator>>),
+    AsyncGeneratorReturn(Gc<GcCell<JsGenerator>>),
+    AsyncGeneratorThrow(Gc<GcCell<JsGenerator>>),
+    /// Resumes an async function paused on an `await` once the awaited
+    /// promise fulfills, or throws its rejection reason into the body.
+    AsyncFunctionResume(Gc<GcCell<JsGenerator>>),
+    AsyncFunctionThrow(Gc<GcCell<JsGenerator>>),
+    CollectionIteratorNext(Gc<GcCell<JsCollectionIterator>>),
+    /// A method of the prototype shared by the engine's iterators, such
+    /// as `map` or `toArray`.
+    IteratorMethod(String),
+    IteratorHelperNext(Gc<GcCell<JsIteratorHelper>>),
+    IteratorHelperReturn(Gc<GcCell<JsIteratorHelper>>),
+    /// `EventTarget.prototype` methods, which find the listeners through
+    /// the receiver's `[[EventTarget]]` slot.
+    EventTargetAdd,
+    EventTargetRemove,
+    EventTargetDispatch,
+    /// The listener store kept in an event target's `[[EventTarget]]`
+    /// slot. Scripts cannot reach it, so it is never called.
+    EventTargetListeners(Gc<GcCell<EventTarget>>),
     ProxyRevoke(Gc<GcCell<JsProxy>>),
     Host(NativeFunctionBoxed),
+    /// A host function restored from a snapshot by name. Calls go to the
+    /// function the host registered under that name since.
+    HostRef(String),
     NativeClassConstructor(String),
     IsNaN,
     IsFinite,
@@ -57,11 +102,47 @@ pub enum NativeFunction {
     StringCtor,
     ObjectCtor,
     ErrorCtor(String),
+    /// `Map`, `Set`, `WeakMap` or `WeakSet`, which only work with `new`.
+    CollectionCtor(String),
+    /// `ArrayBuffer`, `DataView` or one of the typed array constructors.
+    BinaryCtor(String),
     MathMethod(String),
+    /// `String.raw`, `String.fromCharCode` or `String.fromCodePoint`.
+    StringStatic(String),
+    /// `Promise`, which only works with `new`.
+    PromiseCtor,
+    /// `Promise.resolve`, `Promise.reject` or `Promise.any`.
+    PromiseStatic(String),
+    /// `Proxy`, which only works with `new`.
+    ProxyCtor,
+    ProxyRevocable,
+    /// A method of the `JSON` object.
+    JsonMethod(String),
+    /// A method of the `console` object.
+    ConsoleMethod(String),
+    PerformanceNow,
     DateCtor,
     RegExpCtor,
     FunctionCtor,
+    /// `Function.prototype.toString`.
+    FunctionToString,
     ArrayCtor,
+    EvalScript,
+    CollectGarbage,
+    /// `$262.detachArrayBuffer`.
+    DetachArrayBuffer,
+    StructuredClone,
+    /// `import.meta.resolve` of the module at this path.
+    ImportMetaResolve(PathBuf),
+    #[cfg(feature = "http")]
+    Fetch,
+    /// `headers.get` of a fetched response.
+    #[cfg(feature = "http")]
+    FetchHeadersGet(Rc<[(String, String)]>),
+    #[cfg(feature = "http")]
+    FetchResponseText(Rc<str>),
+    #[cfg(feature = "http")]
+    FetchResponseJson(Rc<str>),
 }
 
 #[derive(Debug, Clone)]
@@ -70,11 +151,11 @@ pub enum JsValue {
     Null,
     Boolean(bool),
     Number(f64),
-    String(String),
+    String(JsString),
     Function {
         name: String,
-        params: Vec<Param>,
-        body: Vec<Stmt>,
+        params: Rc<[Param]>,
+        body: FunctionBody,
         closure_env: Vec<Gc<GcCell<Scope>>>,
         is_async: bool,
         is_generator: bool,
@@ -95,6 +176,9 @@ pub enum JsValue {
     WeakMap(Gc<GcCell<JsWeakMap>>),
     WeakSet(Gc<GcCell<JsWeakSet>>),
     RegExp(Gc<GcCell<JsRegExp>>),
+    ArrayBuffer(Gc<GcCell<JsArrayBuffer>>),
+    TypedArray(Gc<GcCell<JsTypedArray>>),
+    DataView(Gc<GcCell<JsDataView>>),
     Proxy(Gc<GcCell<JsProxy>>),
 }
 
@@ -135,7 +219,22 @@ impl PartialEq for JsValue {
             (JsValue::WeakMap(a), JsValue::WeakMap(b)) => Gc::ptr_eq(*a, *b),
             (JsValue::WeakSet(a), JsValue::WeakSet(b)) => Gc::ptr_eq(*a, *b),
             (JsValue::RegExp(a), JsValue::RegExp(b)) => Gc::ptr_eq(*a, *b),
+            (JsValue::ArrayBuffer(a), JsValue::ArrayBuffer(b)) => Gc::ptr_eq(*a, *b),
+            (JsValue::TypedArray(a), JsValue::TypedArray(b)) => Gc::ptr_eq(*a, *b),
+            (JsValue::DataView(a), JsValue::DataView(b)) => Gc::ptr_eq(*a, *b),
             (JsValue::Proxy(a), JsValue::Proxy(b)) => Gc::ptr_eq(*a, *b),
+            // Copies of a function share its property storage once it has
+            // one, as class constructors always do.
+            (
+                JsValue::Function {
+                    properties: Some(a),
+                    ..
+                },
+                JsValue::Function {
+                    properties: Some(b),
+                    ..
+                },
+            ) => Gc::ptr_eq(*a, *b),
             (
                 JsValue::NativeFunction {
                     handler: NativeFunction::Host(a),
@@ -156,17 +255,57 @@ impl PartialEq for JsValue {
                     ..
                 },
             ) => a == b,
+            (
+                JsValue::NativeFunction {
+                    name: a,
+                    handler: ha,
+                },
+                JsValue::NativeFunction {
+                    name: b,
+                    handler: hb,
+                },
+            ) if ha.is_intrinsic() && hb.is_intrinsic() => {
+                a == b && std::mem::discriminant(ha) == std::mem::discriminant(hb)
+            }
             _ => false,
         }
     }
 }
 
+impl NativeFunction {
+    /// Built-in globals that carry no per-instance state, so two values
+    /// with the same handler and name are the same function.
+    pub(crate) fn is_intrinsic(&self) -> bool {
+        !matches!(
+            self,
+            NativeFunction::PromiseResolve(_)
+                | NativeFunction::PromiseReject(_)
+                | NativeFunction::PromiseAnyReject(..)
+                | NativeFunction::GeneratorNext(_)
+                | NativeFunction::GeneratorReturn(_)
+                | NativeFunction::GeneratorThrow(_)
+                | NativeFunction::AsyncGeneratorNext(_)
+                | NativeFunction::AsyncGeneratorReturn(_)
+                | NativeFunction::AsyncGeneratorThrow(_)
+                | NativeFunction::AsyncFunctionResume(_)
+                | NativeFunction::AsyncFunctionThrow(_)
+                | NativeFunction::CollectionIteratorNext(_)
+                | NativeFunction::IteratorHelperNext(_)
+                | NativeFunction::IteratorHelperReturn(_)
+                | NativeFunction::EventTargetListeners(_)
+                | NativeFunction::ProxyRevoke(_)
+                | NativeFunction::Host(_)
+        )
+    }
+}
+
 impl Trace for NativeFunction {
     fn trace(&self, tracer: &mut Tracer) {
         match self {
             NativeFunction::PromiseResolve(p) | NativeFunction::PromiseReject(p) => {
                 tracer.mark(*p);
             }
+            NativeFunction::PromiseAnyReject(state, _) => tracer.mark(*state),
             NativeFunction::SetTimeout
             | NativeFunction::SetInterval
             | NativeFunction::ClearTimeout
@@ -175,9 +314,9 @@ impl Trace for NativeFunction {
             | NativeFunction::CancelAnimationFrame
             | NativeFunction::QueueMicrotask
             | NativeFunction::SymbolConstructor
-            | NativeFunction::GeneratorThrow
             | NativeFunction::GeneratorIterator
             | NativeFunction::Host(_)
+            | NativeFunction::HostRef(_)
             | NativeFunction::NativeClassConstructor(_)
             | NativeFunction::IsNaN
             | NativeFunction::IsFinite
@@ -188,14 +327,56 @@ impl Trace for NativeFunction {
             | NativeFunction::StringCtor
             | NativeFunction::ObjectCtor
             | NativeFunction::ErrorCtor(_)
+            | NativeFunction::CollectionCtor(_)
+            | NativeFunction::BinaryCtor(_)
             | NativeFunction::MathMethod(_)
+            | NativeFunction::StringStatic(_)
+            | NativeFunction::PromiseCtor
+            | NativeFunction::PromiseStatic(_)
+            | NativeFunction::ProxyCtor
+            | NativeFunction::ProxyRevocable
+            | NativeFunction::JsonMethod(_)
+            | NativeFunction::ConsoleMethod(_)
+            | NativeFunction::PerformanceNow
+            | NativeFunction::IteratorMethod(_)
+            | NativeFunction::EventTargetAdd
+            | NativeFunction::EventTargetRemove
+            | NativeFunction::EventTargetDispatch
             | NativeFunction::DateCtor
             | NativeFunction::RegExpCtor
             | NativeFunction::FunctionCtor
-            | NativeFunction::ArrayCtor => {}
-            NativeFunction::GeneratorNext(g) | NativeFunction::GeneratorReturn(g) => {
+            | NativeFunction::FunctionToString
+            | NativeFunction::ArrayCtor
+            | NativeFunction::EvalScript
+            | NativeFunction::CollectGarbage
+            | NativeFunction::DetachArrayBuffer
+            | NativeFunction::StructuredClone
+            | NativeFunction::ImportMetaResolve(_) => {}
+            #[cfg(feature = "http")]
+            NativeFunction::Fetch
+            | NativeFunction::FetchHeadersGet(_)
+            | NativeFunction::FetchResponseText(_)
+            | NativeFunction::FetchResponseJson(_) => {}
+            NativeFunction::GeneratorNext(g)
+            | NativeFunction::GeneratorReturn(g)
+            | NativeFunction::GeneratorThrow(g)
+            | NativeFunction::AsyncGeneratorNext(g)
+            | NativeFunction::AsyncGeneratorReturn(g)
+            | NativeFunction::AsyncGeneratorThrow(g)
+            | NativeFunction::AsyncFunctionResume(g)
+            | NativeFunction::AsyncFunctionThrow(g) => {
                 tracer.mark(*g);
             }
+            NativeFunction::CollectionIteratorNext(it) => {
+                tracer.mark(*it);
+            }
+            NativeFunction::IteratorHelperNext(helper)
+            | NativeFunction::IteratorHelperReturn(helper) => {
+                tracer.mark(*helper);
+            }
+            NativeFunction::EventTargetListeners(t) => {
+                tracer.mark(*t);
+            }
             NativeFunction::ProxyRevoke(p) => {
                 tracer.mark(*p);
             }
@@ -227,6 +408,9 @@ impl Trace for JsValue {
             JsValue::WeakMap(gc) => tracer.mark(*gc),
             JsValue::WeakSet(gc) => tracer.mark(*gc),
             JsValue::RegExp(gc) => tracer.mark(*gc),
+            JsValue::ArrayBuffer(gc) => tracer.mark(*gc),
+            JsValue::TypedArray(gc) => tracer.mark(*gc),
+            JsValue::DataView(gc) => tracer.mark(*gc),
             JsValue::Proxy(gc) => tracer.mark(*gc),
             JsValue::Undefined
             | JsValue::Null
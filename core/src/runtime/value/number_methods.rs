@@ -0,0 +1,232 @@
+//! Methods called on number primitives.
+//!
+//! `toLocaleString` is not backed by ICU: the locales argument is accepted
+//! and ignored, and every number is written the en-US way, with a comma
+//! between groups of three integer digits and a dot before the fraction.
+
+use crate::errors::RuntimeError;
+use crate::runtime::value::{JsValue, number_to_string};
+
+/// Methods handled by [`call_number_method`].
+pub const NUMBER_METHODS: &[&str] = &["toLocaleString", "toString", "valueOf"];
+
+/// The most fraction digits `toLocaleString` accepts in its options.
+const MAX_FRACTION_DIGITS: usize = 100;
+
+pub fn call_number_method(n: f64, method: &str, args: &[JsValue]) -> Result<JsValue, RuntimeError> {
+    match method {
+        "toString" => {
+            let radix = match args.first() {
+                None | Some(JsValue::Undefined) => 10.0,
+                Some(radix) => radix.to_number().trunc(),
+            };
+            if !(2.0..=36.0).contains(&radix) {
+                return Err(RuntimeError::TypeError {
+                    message: "toString() radix must be between 2 and 36".to_string(),
+                });
+            }
+            Ok(JsValue::String(to_radix_string(n, radix as u32).into()))
+        }
+        "toLocaleString" => {
+            let options = LocaleOptions::read(args.get(1))?;
+            Ok(JsValue::String(to_locale_string(n, &options).into()))
+        }
+        "valueOf" => Ok(JsValue::Number(n)),
+        _ => Err(RuntimeError::TypeError {
+            message: format!("'{method}' is not a function"),
+        }),
+    }
+}
+
+/// The options bag of `toLocaleString`, limited to the fraction digits and
+/// grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LocaleOptions {
+    minimum_fraction_digits: usize,
+    maximum_fraction_digits: usize,
+    use_grouping: bool,
+}
+
+impl Default for LocaleOptions {
+    fn default() -> Self {
+        Self {
+            minimum_fraction_digits: 0,
+            maximum_fraction_digits: 3,
+            use_grouping: true,
+        }
+    }
+}
+
+impl LocaleOptions {
+    /// Reads the options argument. The maximum defaults to at least the
+    /// minimum, as in `Intl.NumberFormat`.
+    fn read(options: Option<&JsValue>) -> Result<Self, RuntimeError> {
+        let Some(JsValue::Object(obj)) = options else {
+            return Ok(Self::default());
+        };
+        let obj = obj.borrow();
+        let digits = |name: &str| -> Result<Option<usize>, RuntimeError> {
+            match obj.get(name) {
+                None | Some(JsValue::Undefined) => Ok(None),
+                Some(value) => {
+                    let n = value.to_number();
+                    if !(0.0..=MAX_FRACTION_DIGITS as f64).contains(&n) {
+                        return Err(RuntimeError::TypeError {
+                            message: format!("{name} value is out of range"),
+                        });
+                    }
+                    Ok(Some(n.trunc() as usize))
+                }
+            }
+        };
+        let minimum = digits("minimumFractionDigits")?;
+        let maximum = digits("maximumFractionDigits")?;
+        let minimum_fraction_digits = minimum.unwrap_or(0);
+        let maximum_fraction_digits = maximum.unwrap_or(minimum_fraction_digits.max(3));
+        if maximum_fraction_digits < minimum_fraction_digits {
+            return Err(RuntimeError::TypeError {
+                message: "maximumFractionDigits value is out of range".to_string(),
+            });
+        }
+        let use_grouping = match obj.get("useGrouping") {
+            None | Some(JsValue::Undefined) => true,
+            Some(value) => value.to_boolean(),
+        };
+        Ok(Self {
+            minimum_fraction_digits,
+            maximum_fraction_digits,
+            use_grouping,
+        })
+    }
+}
+
+/// Formats `n` the way `toLocaleString` does for en-US. Rounding starts
+/// from the shortest digits that round-trip, like `number_to_string`, and
+/// ties round away from zero.
+fn to_locale_string(n: f64, options: &LocaleOptions) -> String {
+    if n.is_nan() {
+        return "NaN".into();
+    }
+    let sign = if n.is_sign_negative() { "-" } else { "" };
+    if n.is_infinite() {
+        return format!("{sign}∞");
+    }
+
+    let (mut int, mut frac) = decimal_parts(n.abs());
+    if frac.len() > options.maximum_fraction_digits {
+        let round_up = frac.as_bytes()[options.maximum_fraction_digits] >= b'5';
+        frac.truncate(options.maximum_fraction_digits);
+        if round_up {
+            let carried = increment(&format!("{int}{frac}"));
+            let split = carried.len() - frac.len();
+            frac = carried[split..].to_string();
+            int = carried[..split].to_string();
+        }
+    }
+    while frac.len() > options.minimum_fraction_digits && frac.ends_with('0') {
+        frac.pop();
+    }
+    while frac.len() < options.minimum_fraction_digits {
+        frac.push('0');
+    }
+
+    let int = if options.use_grouping {
+        group_thousands(&int)
+    } else {
+        int
+    };
+    if frac.is_empty() {
+        format!("{sign}{int}")
+    } else {
+        format!("{sign}{int}.{frac}")
+    }
+}
+
+/// The integer and fraction digits of a finite, non-negative `n`, written
+/// out positionally from its shortest round-trip digits.
+fn decimal_parts(n: f64) -> (String, String) {
+    if n == 0.0 {
+        return ("0".into(), String::new());
+    }
+    let sci = format!("{n:e}");
+    let (mantissa, exponent) = sci.split_once('e').expect("exponent form");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let point = exponent.parse::<i32>().expect("integer exponent") + 1;
+    if point <= 0 {
+        (
+            "0".into(),
+            format!("{}{digits}", "0".repeat(-point as usize)),
+        )
+    } else if point as usize >= digits.len() {
+        let zeros = "0".repeat(point as usize - digits.len());
+        (format!("{digits}{zeros}"), String::new())
+    } else {
+        let (int, frac) = digits.split_at(point as usize);
+        (int.to_string(), frac.to_string())
+    }
+}
+
+/// Adds one to a run of decimal digits, growing it when every digit was 9.
+fn increment(digits: &str) -> String {
+    let mut bytes = digits.as_bytes().to_vec();
+    for byte in bytes.iter_mut().rev() {
+        if *byte == b'9' {
+            *byte = b'0';
+        } else {
+            *byte += 1;
+            return String::from_utf8(bytes).expect("ascii digits");
+        }
+    }
+    bytes.insert(0, b'1');
+    String::from_utf8(bytes).expect("ascii digits")
+}
+
+fn group_thousands(int: &str) -> String {
+    let mut out = String::with_capacity(int.len() + int.len() / 3);
+    for (i, ch) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// `Number.prototype.toString(radix)`. Fractions in radixes other than 10
+/// stop after 52 digits rather than at the shortest round-trip form.
+fn to_radix_string(n: f64, radix: u32) -> String {
+    if radix == 10 || !n.is_finite() {
+        return number_to_string(n);
+    }
+    if n < 0.0 {
+        return format!("-{}", to_radix_string(-n, radix));
+    }
+    let base = radix as f64;
+    let mut int = n.trunc();
+    let mut frac = n - int;
+
+    let mut int_digits = Vec::new();
+    loop {
+        let digit = (int % base) as u32;
+        int_digits.push(std::char::from_digit(digit, radix).expect("digit below radix"));
+        int = (int / base).trunc();
+        if int < 1.0 {
+            break;
+        }
+    }
+    let mut out: String = int_digits.into_iter().rev().collect();
+
+    if frac > 0.0 {
+        out.push('.');
+        for _ in 0..52 {
+            frac *= base;
+            let digit = frac.trunc();
+            out.push(std::char::from_digit(digit as u32, radix).expect("digit below radix"));
+            frac -= digit;
+            if frac == 0.0 {
+                break;
+            }
+        }
+    }
+    out
+}
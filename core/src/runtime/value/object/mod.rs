@@ -8,15 +8,27 @@ pub use property::Property;
 
 use std::collections::HashMap;
 
+use indexmap::IndexMap;
+
+use super::array::array_index;
 use super::symbol::JsSymbol;
-use super::JsValue;
+use super::{JsValue, PropName};
 use crate::runtime::gc::{Gc, GcCell, Trace, Tracer};
 
 #[derive(Debug, Clone)]
 pub struct JsObject {
-    pub properties: HashMap<String, Property>,
+    /// String-keyed properties in insertion order.
+    pub properties: IndexMap<PropName, Property>,
     pub symbol_properties: HashMap<u64, (JsSymbol, Property)>,
+    /// Private fields, methods and accessors, keyed by the symbol the
+    /// declaring class made for each `#name`. Only that class's `#name`
+    /// expressions reach them.
+    pub private_elements: HashMap<u64, (JsSymbol, Property)>,
     pub prototype: Option<Gc<GcCell<JsObject>>>,
+    /// Set when the prototype was made `null` on purpose, as by
+    /// `Object.create(null)`. Other objects without a stored prototype
+    /// inherit from `Object.prototype`.
+    pub null_prototype: bool,
     pub extensible: bool,
     pub sealed: bool,
     pub frozen: bool,
@@ -32,9 +44,11 @@ impl Default for JsObject {
 impl JsObject {
     pub fn new() -> Self {
         Self {
-            properties: HashMap::new(),
+            properties: IndexMap::new(),
             symbol_properties: HashMap::new(),
+            private_elements: HashMap::new(),
             prototype: None,
+            null_prototype: false,
             extensible: true,
             sealed: false,
             frozen: false,
@@ -52,9 +66,11 @@ impl JsObject {
             .map(|(_, p)| p.value.clone())
     }
 
-    pub fn set(&mut self, key: String, value: JsValue) {
+    pub fn set<K: AsRef<str> + Into<PropName>>(&mut self, key: K, value: JsValue) {
         self.revision += 1;
-        if let Some(existing) = self.properties.get_mut(&key) {
+        // Overwrites are the hot path, and finding the slot by text skips
+        // the intern table entirely.
+        if let Some(existing) = self.properties.get_mut(key.as_ref()) {
             if !existing.writable || self.frozen {
                 return;
             }
@@ -64,7 +80,7 @@ impl JsObject {
         if !self.extensible {
             return;
         }
-        self.properties.insert(key, Property::new(value));
+        self.properties.insert(key.into(), Property::new(value));
     }
 
     pub fn set_symbol(&mut self, sym: JsSymbol, value: JsValue) {
@@ -75,7 +91,8 @@ impl JsObject {
             .insert(sym.id, (sym, Property::new(value)));
     }
 
-    pub fn set_getter(&mut self, key: String, getter: JsValue) {
+    pub fn set_getter(&mut self, key: impl Into<PropName>, getter: JsValue) {
+        let key = key.into();
         if let Some(existing) = self.properties.get_mut(&key) {
             if !existing.configurable || self.frozen {
                 return;
@@ -90,7 +107,8 @@ impl JsObject {
         self.properties.insert(key, Property::with_getter(getter));
     }
 
-    pub fn set_setter(&mut self, key: String, setter: JsValue) {
+    pub fn set_setter(&mut self, key: impl Into<PropName>, setter: JsValue) {
+        let key = key.into();
         if let Some(existing) = self.properties.get_mut(&key) {
             if !existing.configurable || self.frozen {
                 return;
@@ -105,6 +123,60 @@ impl JsObject {
         self.properties.insert(key, Property::with_setter(setter));
     }
 
+    pub fn set_symbol_getter(&mut self, sym: JsSymbol, getter: JsValue) {
+        if let Some((_, existing)) = self.symbol_properties.get_mut(&sym.id) {
+            if !existing.configurable || self.frozen {
+                return;
+            }
+            existing.getter = Some(getter);
+            existing.writable = false;
+            return;
+        }
+        if !self.extensible {
+            return;
+        }
+        self.symbol_properties
+            .insert(sym.id, (sym, Property::with_getter(getter)));
+    }
+
+    pub fn set_symbol_setter(&mut self, sym: JsSymbol, setter: JsValue) {
+        if let Some((_, existing)) = self.symbol_properties.get_mut(&sym.id) {
+            if !existing.configurable || self.frozen {
+                return;
+            }
+            existing.setter = Some(setter);
+            existing.writable = false;
+            return;
+        }
+        if !self.extensible {
+            return;
+        }
+        self.symbol_properties
+            .insert(sym.id, (sym, Property::with_setter(setter)));
+    }
+
+    /// Own string keys in property order: integer keys ascending, then
+    /// the rest in the order they were added. Internal slots such as
+    /// `[[PrimitiveValue]]` are left out.
+    pub fn ordered_keys(&self) -> Vec<PropName> {
+        let mut indices: Vec<(usize, PropName)> = Vec::new();
+        let mut names = Vec::with_capacity(self.properties.len());
+        for key in self.properties.keys().filter(|key| !is_internal_key(key)) {
+            match array_index(key) {
+                Some(index) => indices.push((index, key.clone())),
+                None => names.push(key.clone()),
+            }
+        }
+        indices.sort_unstable_by_key(|(index, _)| *index);
+        indices
+            .into_iter()
+            .map(|(_, key)| key)
+            .chain(names)
+            .collect()
+    }
+
+    /// Removes `key`, returning false only when the property exists and
+    /// may not be removed.
     pub fn delete(&mut self, key: &str) -> bool {
         self.revision += 1;
         if let Some(prop) = self.properties.get(key)
@@ -112,7 +184,19 @@ impl JsObject {
         {
             return false;
         }
-        self.properties.remove(key).is_some()
+        self.properties.shift_remove(key);
+        true
+    }
+
+    pub fn delete_symbol(&mut self, sym: &JsSymbol) -> bool {
+        self.revision += 1;
+        if let Some((_, prop)) = self.symbol_properties.get(&sym.id)
+            && (!prop.configurable || self.sealed || self.frozen)
+        {
+            return false;
+        }
+        self.symbol_properties.remove(&sym.id);
+        true
     }
 
     pub fn set_prototype(&mut self, proto: Option<Gc<GcCell<JsObject>>>) {
@@ -120,6 +204,7 @@ impl JsObject {
             return;
         }
         self.revision += 1;
+        self.null_prototype = proto.is_none();
         self.prototype = proto;
     }
 
@@ -154,7 +239,10 @@ impl Trace for JsObject {
             property.setter.trace(tracer);
         }
         for (_, property) in self.symbol_properties.values() {
-            property.value.trace(tracer);
+            property.trace(tracer);
+        }
+        for (_, property) in self.private_elements.values() {
+            property.trace(tracer);
         }
 
         if let Some(proto) = &self.prototype {
@@ -162,3 +250,9 @@ impl Trace for JsObject {
         }
     }
 }
+
+/// Slots like `[[DateValue]]` live among the properties but are not
+/// visible to scripts.
+pub(crate) fn is_internal_key(key: &str) -> bool {
+    key.starts_with("[[")
+}
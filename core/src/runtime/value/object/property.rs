@@ -44,6 +44,10 @@ impl Property {
             configurable: true,
         }
     }
+
+    pub fn is_accessor(&self) -> bool {
+        self.getter.is_some() || self.setter.is_some()
+    }
 }
 
 impl Trace for Property {
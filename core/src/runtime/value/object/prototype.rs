@@ -16,5 +16,5 @@ pub fn get_property(obj: &Gc<GcCell<JsObject>>, key: &str) -> Option<JsValue> {
 }
 
 pub fn set_property(obj: &Gc<GcCell<JsObject>>, key: &str, value: JsValue) {
-    obj.borrow_mut().set(key.to_string(), value);
+    obj.borrow_mut().set(key, value);
 }
@@ -27,6 +27,23 @@ impl JsPromise {
     }
 }
 
+/// What `Promise.any` tracks while its inputs settle: the reasons of the
+/// ones rejected so far, by input position, and how many are still
+/// pending.
+#[derive(Debug, Clone)]
+pub struct PromiseAnyState {
+    pub result: Gc<GcCell<JsPromise>>,
+    pub errors: Vec<JsValue>,
+    pub remaining: usize,
+}
+
+impl Trace for PromiseAnyState {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.mark(self.result);
+        self.errors.trace(tracer);
+    }
+}
+
 impl Trace for PromiseReaction {
     fn trace(&self, tracer: &mut Tracer) {
         self.on_fulfilled.trace(tracer);
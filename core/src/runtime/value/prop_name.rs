@@ -0,0 +1,156 @@
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::rc::Rc;
+
+/// Interned table sizes below this are never swept.
+const MIN_SWEEP_LEN: usize = 1024;
+
+/// A property or binding name. Every `PropName` with the same text shares
+/// one interned buffer, so clones are a reference-count bump and equality
+/// is a pointer comparison. Hashing goes by the text, which lets maps
+/// keyed by `PropName` still be queried with a `&str`.
+#[derive(Clone)]
+pub struct PropName(Rc<str>);
+
+struct Interner {
+    names: HashSet<Rc<str>>,
+    /// Table length that triggers the next sweep of unused names.
+    sweep_at: usize,
+}
+
+thread_local! {
+    static INTERNER: RefCell<Interner> = RefCell::new(Interner {
+        names: HashSet::new(),
+        sweep_at: MIN_SWEEP_LEN,
+    });
+}
+
+impl PropName {
+    pub fn new(name: &str) -> Self {
+        INTERNER.with_borrow_mut(|interner| {
+            if let Some(existing) = interner.names.get(name) {
+                return Self(Rc::clone(existing));
+            }
+            // Names only the table still holds are garbage, e.g. keys of
+            // collected objects. Sweeping when the table doubles keeps
+            // interning amortized O(1) without growing forever.
+            if interner.names.len() >= interner.sweep_at {
+                interner.names.retain(|name| Rc::strong_count(name) > 1);
+                interner.sweep_at = (interner.names.len() * 2).max(MIN_SWEEP_LEN);
+            }
+            let name: Rc<str> = Rc::from(name);
+            interner.names.insert(Rc::clone(&name));
+            Self(name)
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for PropName {
+    fn eq(&self, other: &Self) -> bool {
+        // Interning makes equal text and equal pointers the same thing.
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for PropName {}
+
+impl PartialEq<str> for PropName {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for PropName {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl Hash for PropName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl PartialOrd for PropName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PropName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Deref for PropName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for PropName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for PropName {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PropName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for PropName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl From<&str> for PropName {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for PropName {
+    fn from(value: String) -> Self {
+        Self::new(&value)
+    }
+}
+
+impl From<&String> for PropName {
+    fn from(value: &String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&PropName> for PropName {
+    fn from(value: &PropName) -> Self {
+        value.clone()
+    }
+}
+
+impl From<PropName> for String {
+    fn from(value: PropName) -> Self {
+        value.as_str().to_owned()
+    }
+}
@@ -1,4 +1,8 @@
 use super::JsRegExp;
+use crate::runtime::gc::Heap;
+use crate::runtime::value::array::JsArray;
+use crate::runtime::value::object::JsObject;
+use crate::runtime::value::JsValue;
 
 /// Result of a single regex exec/match operation.
 #[derive(Debug, Clone)]
@@ -7,13 +11,37 @@ pub struct MatchResult {
     pub full_match: String,
     /// Capture group strings (index 0 = full match).
     pub captures: Vec<Option<String>>,
-    /// Start index of the match in the input.
+    /// Start index of the match in the input, in UTF-16 code units.
     pub index: usize,
 }
 
+impl MatchResult {
+    /// The array `exec` returns: the match and its captures, with the
+    /// match's `index` and the `input` string as named properties.
+    pub fn into_array(self, input: &str, heap: &mut Heap) -> JsArray {
+        let mut items: Vec<JsValue> = self
+            .captures
+            .into_iter()
+            .map(|c| c.map_or(JsValue::Undefined, |s| JsValue::String(s.into())))
+            .collect();
+        if items.is_empty() {
+            items.push(JsValue::String(self.full_match.into()));
+        }
+        let mut named = JsObject::new();
+        named.set("index".to_string(), JsValue::Number(self.index as f64));
+        named.set("input".to_string(), JsValue::String(input.into()));
+        let mut array = JsArray::new(items);
+        array.properties = Some(heap.alloc_cell(named));
+        array
+    }
+}
+
 impl JsRegExp {
     /// Execute the regex against the string, starting at `last_index`
     /// for global/sticky regexps. Returns `None` on no match.
+    ///
+    /// `last_index` and the reported index count UTF-16 code units, as JS
+    /// sees them; the regex crate searches by byte offset.
     pub fn exec(&mut self, input: &str) -> Option<MatchResult> {
         let start = if self.flags.global || self.flags.sticky {
             self.last_index
@@ -21,12 +49,12 @@ impl JsRegExp {
             0
         };
 
-        if start > input.len() {
+        let Some(start) = byte_offset(input, start) else {
             if self.flags.global || self.flags.sticky {
                 self.last_index = 0;
             }
             return None;
-        }
+        };
 
         let haystack = &input[start..];
         let captures = self.compiled().captures(haystack)?;
@@ -41,7 +69,7 @@ impl JsRegExp {
         let match_end = start + full.end();
 
         if self.flags.global || self.flags.sticky {
-            self.last_index = match_end;
+            self.last_index = utf16_len(&input[..match_end]);
         }
 
         let caps: Vec<Option<String>> = captures
@@ -52,7 +80,7 @@ impl JsRegExp {
         Some(MatchResult {
             full_match: full.as_str().to_string(),
             captures: caps,
-            index: match_start,
+            index: utf16_len(&input[..match_start]),
         })
     }
 
@@ -70,11 +98,15 @@ impl JsRegExp {
         loop {
             match self.exec(input) {
                 Some(m) => {
-                    results.push(m.full_match);
-                    // Prevent infinite loop on zero-length match
-                    if self.last_index == 0 {
-                        break;
+                    // Step past an empty match so the next search moves on.
+                    if m.full_match.is_empty() {
+                        let rest = byte_offset(input, self.last_index).map_or("", |b| &input[b..]);
+                        self.last_index += match rest.chars().next() {
+                            Some(c) if self.flags.unicode => c.len_utf16(),
+                            _ => 1,
+                        };
                     }
+                    results.push(m.full_match);
                 }
                 None => break,
             }
@@ -83,3 +115,22 @@ impl JsRegExp {
         results
     }
 }
+
+/// The byte offset of the UTF-16 index `units` in `input`, or `None` past
+/// its end. An index that falls inside a surrogate pair resolves to the
+/// character after the pair.
+pub(crate) fn byte_offset(input: &str, units: usize) -> Option<usize> {
+    let mut seen = 0;
+    for (offset, c) in input.char_indices() {
+        if seen >= units {
+            return Some(offset);
+        }
+        seen += c.len_utf16();
+    }
+    (seen >= units).then_some(input.len())
+}
+
+/// The length of `s` in UTF-16 code units.
+pub(crate) fn utf16_len(s: &str) -> usize {
+    s.chars().map(char::len_utf16).sum()
+}
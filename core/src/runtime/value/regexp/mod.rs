@@ -1,6 +1,7 @@
 mod engine;
 
 pub use engine::MatchResult;
+pub(crate) use engine::utf16_len;
 
 use regex::Regex;
 
@@ -133,11 +134,247 @@ fn compile_regex(pattern: &str, flags: &RegExpFlags) -> Result<Regex, String> {
         }
         rust_pattern.push(')');
     }
-    rust_pattern.push_str(pattern);
+    if flags.unicode {
+        rust_pattern.push_str(&translate_unicode_pattern(pattern, flags.dotall)?);
+    } else {
+        rust_pattern.push_str(pattern);
+    }
 
     Regex::new(&rust_pattern).map_err(|e| format!("invalid regex: {e}"))
 }
 
+/// A class that matches every code point, for JS's `[^]`.
+const ANY_CHAR: &str = r"[\x00-\x{10FFFF}]";
+/// A class that matches nothing, for JS's `[]` and for lone surrogates,
+/// which cannot occur in a Rust string.
+const NO_CHAR: &str = r"[^\x00-\x{10FFFF}]";
+/// The code points JS's `\s` matches.
+const JS_SPACE: &str =
+    r"\t\n\x0B\f\r \x{A0}\x{1680}\x{2000}-\x{200A}\x{2028}\x{2029}\x{202F}\x{205F}\x{3000}\x{FEFF}";
+
+/// Rewrites a pattern compiled with the `u` flag into the regex crate's
+/// syntax. The crate already matches whole code points, so this mostly
+/// spells JS escapes the way the crate understands them and rejects the
+/// escapes the `u` flag makes early errors.
+fn translate_unicode_pattern(pattern: &str, dotall: bool) -> Result<String, String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let groups = count_capture_groups(&chars);
+    let mut out = String::with_capacity(pattern.len());
+    let mut in_class = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '\\' => i = translate_escape(&chars, i, in_class, groups, &mut out)?,
+            '[' if !in_class => {
+                if chars[i..].starts_with(&['^', ']']) {
+                    out.push_str(ANY_CHAR);
+                    i += 2;
+                } else if chars.get(i) == Some(&']') {
+                    out.push_str(NO_CHAR);
+                    i += 1;
+                } else {
+                    in_class = true;
+                    out.push('[');
+                    if chars.get(i) == Some(&'^') {
+                        out.push('^');
+                        i += 1;
+                    }
+                }
+            }
+            ']' if in_class => {
+                in_class = false;
+                out.push(']');
+            }
+            // Nested classes and set operations are regex crate syntax;
+            // in JS these are plain characters.
+            '[' | '&' | '~' if in_class => {
+                out.push('\\');
+                out.push(c);
+            }
+            '-' if in_class && chars.get(i) == Some(&'-') => out.push_str(r"\-"),
+            '.' if !in_class && !dotall => out.push_str(r"[^\n\r\x{2028}\x{2029}]"),
+            _ => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// Translates the escape whose backslash ends just before `chars[i]`,
+/// returning the index after it.
+fn translate_escape(
+    chars: &[char],
+    mut i: usize,
+    in_class: bool,
+    groups: usize,
+    out: &mut String,
+) -> Result<usize, String> {
+    let Some(&c) = chars.get(i) else {
+        return Err("\\ at end of pattern".to_string());
+    };
+    i += 1;
+    match c {
+        'd' => out.push_str("[0-9]"),
+        'D' => out.push_str("[^0-9]"),
+        'w' => out.push_str("[0-9A-Za-z_]"),
+        'W' => out.push_str("[^0-9A-Za-z_]"),
+        's' => out.push_str(&format!("[{JS_SPACE}]")),
+        'S' => out.push_str(&format!("[^{JS_SPACE}]")),
+        'b' if in_class => out.push_str(r"\x08"),
+        'b' => out.push_str(r"(?-u:\b)"),
+        'B' if !in_class => out.push_str(r"(?-u:\B)"),
+        'f' | 'n' | 'r' | 't' => {
+            out.push('\\');
+            out.push(c);
+        }
+        'v' => out.push_str(r"\x0B"),
+        'c' => match chars.get(i) {
+            Some(letter) if letter.is_ascii_alphabetic() => {
+                push_code_point(out, *letter as u32 % 32);
+                i += 1;
+            }
+            _ => return Err("invalid unicode escape '\\c'".to_string()),
+        },
+        '0' if chars.get(i).is_some_and(char::is_ascii_digit) => {
+            return Err("invalid decimal escape in unicode pattern".to_string());
+        }
+        '0' => out.push_str(r"\x00"),
+        '1'..='9' => {
+            let start = i - 1;
+            while chars.get(i).is_some_and(char::is_ascii_digit) {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            if in_class || digits.parse::<usize>().map_or(true, |n| n > groups) {
+                return Err(format!("invalid escape '\\{digits}' in unicode pattern"));
+            }
+            out.push('\\');
+            out.push_str(&digits);
+        }
+        'x' => {
+            let value = hex_digits(chars, i, 2)
+                .ok_or_else(|| "invalid unicode escape '\\x'".to_string())?;
+            push_code_point(out, value);
+            i += 2;
+        }
+        'u' => i = translate_unicode_escape(chars, i, out)?,
+        'p' | 'P' => {
+            let close = (chars.get(i) == Some(&'{'))
+                .then(|| chars[i..].iter().position(|&c| c == '}'))
+                .flatten()
+                .ok_or_else(|| format!("invalid property escape '\\{c}'"))?;
+            let body: String = chars[i + 1..i + close].iter().collect();
+            check_unicode_property(&body)?;
+            out.push('\\');
+            out.push(c);
+            out.push('{');
+            out.push_str(&body);
+            out.push('}');
+            i += close + 1;
+        }
+        // `\k<name>` is left for the regex crate, which has no
+        // backreferences and reports it.
+        'k' => out.push_str(r"\k"),
+        '/' => out.push('/'),
+        '-' if in_class => out.push_str(r"\-"),
+        '^' | '$' | '\\' | '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' => {
+            out.push('\\');
+            out.push(c);
+        }
+        _ => return Err(format!("invalid escape '\\{c}' in unicode pattern")),
+    }
+    Ok(i)
+}
+
+/// Translates `\u{...}` and `\uXXXX`, joining a surrogate pair written as
+/// two escapes into the code point it encodes.
+fn translate_unicode_escape(chars: &[char], i: usize, out: &mut String) -> Result<usize, String> {
+    let invalid = || "invalid unicode escape '\\u'".to_string();
+    if chars.get(i) == Some(&'{') {
+        let close = chars[i..].iter().position(|&c| c == '}').ok_or_else(invalid)?;
+        let value = hex_digits(chars, i + 1, close - 1)
+            .filter(|&v| close > 1 && v <= 0x10FFFF)
+            .ok_or_else(invalid)?;
+        push_code_point(out, value);
+        return Ok(i + close + 1);
+    }
+    let value = hex_digits(chars, i, 4).ok_or_else(invalid)?;
+    let next = i + 4;
+    if (0xD800..0xDC00).contains(&value)
+        && chars[next..].starts_with(&['\\', 'u'])
+        && let Some(low @ 0xDC00..0xE000) = hex_digits(chars, next + 2, 4)
+    {
+        push_code_point(out, 0x10000 + ((value - 0xD800) << 10) + (low - 0xDC00));
+        return Ok(next + 6);
+    }
+    push_code_point(out, value);
+    Ok(next)
+}
+
+/// Parses exactly `len` hex digits starting at `chars[i]`.
+fn hex_digits(chars: &[char], i: usize, len: usize) -> Option<u32> {
+    let digits = chars.get(i..i + len)?;
+    if len > 6 || !digits.iter().all(char::is_ascii_hexdigit) {
+        return None;
+    }
+    u32::from_str_radix(&digits.iter().collect::<String>(), 16).ok()
+}
+
+fn push_code_point(out: &mut String, value: u32) {
+    if char::from_u32(value).is_some() {
+        out.push_str(&format!(r"\x{{{value:X}}}"));
+    } else {
+        out.push_str(NO_CHAR);
+    }
+}
+
+/// Accepts the `\p{...}` forms JS allows, `Name`, `Name=Value` for the
+/// general category and script properties, when the regex crate knows
+/// the property.
+fn check_unicode_property(body: &str) -> Result<(), String> {
+    let well_formed = match body.split_once('=') {
+        Some((name, value)) => {
+            matches!(
+                name,
+                "General_Category" | "gc" | "Script" | "sc" | "Script_Extensions" | "scx"
+            ) && !value.is_empty()
+                && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => !body.is_empty() && body.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+    };
+    if well_formed && Regex::new(&format!(r"\p{{{body}}}")).is_ok() {
+        Ok(())
+    } else {
+        Err(format!("invalid property name '{body}'"))
+    }
+}
+
+/// Counts the capturing groups, so decimal escapes can be told apart from
+/// the legacy octal escapes the `u` flag forbids.
+fn count_capture_groups(chars: &[char]) -> usize {
+    let mut count = 0;
+    let mut in_class = false;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '(' if !in_class => {
+                let named = chars[i + 1..].starts_with(&['?', '<'])
+                    && !matches!(chars.get(i + 3), Some('=' | '!'));
+                if chars.get(i + 1) != Some(&'?') || named {
+                    count += 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    count
+}
+
 impl std::fmt::Display for JsRegExp {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "/{}/{}", self.pattern, self.flag_string())
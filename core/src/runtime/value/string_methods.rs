@@ -1,15 +1,38 @@
 use crate::errors::RuntimeError;
 use crate::runtime::gc::{Gc, GcCell, Heap};
 use crate::runtime::value::array::JsArray;
-use crate::runtime::value::regexp::JsRegExp;
+use crate::runtime::value::collation::{self, Sensitivity};
+use crate::runtime::value::regexp::{utf16_len, JsRegExp};
 use crate::runtime::value::JsValue;
 
+/// Methods handled by [`call_string_method`].
+pub const STRING_METHODS: &[&str] = &[
+    "includes",
+    "indexOf",
+    "localeCompare",
+    "match",
+    "replace",
+    "replaceAll",
+    "search",
+    "slice",
+    "split",
+    "toLowerCase",
+    "toUpperCase",
+    "trim",
+];
+
 pub fn resolve_string_property(s: &str, property: &str) -> Result<JsValue, RuntimeError> {
     match property {
         "length" => Ok(JsValue::Number(s.len() as f64)),
-        _ => Err(RuntimeError::TypeError {
-            message: format!("cannot access property '{property}' on string"),
-        }),
+        _ => match property.parse::<usize>() {
+            Ok(index) if index.to_string() == property => Ok(s
+                .chars()
+                .nth(index)
+                .map_or(JsValue::Undefined, |ch| JsValue::String(ch.into()))),
+            _ => Err(RuntimeError::TypeError {
+                message: format!("cannot access property '{property}' on string"),
+            }),
+        },
     }
 }
 
@@ -20,9 +43,9 @@ pub fn call_string_method(
     heap: &mut Heap,
 ) -> Result<JsValue, RuntimeError> {
     match method {
-        "toUpperCase" => Ok(JsValue::String(s.to_uppercase())),
-        "toLowerCase" => Ok(JsValue::String(s.to_lowercase())),
-        "trim" => Ok(JsValue::String(s.trim().to_string())),
+        "toUpperCase" => Ok(JsValue::String(s.to_uppercase().into())),
+        "toLowerCase" => Ok(JsValue::String(s.to_lowercase().into())),
+        "trim" => Ok(JsValue::String(s.trim().to_string().into())),
         "includes" => {
             let substr = args.first().map(|a| a.to_js_string()).unwrap_or_default();
             Ok(JsValue::Boolean(s.contains(&substr)))
@@ -32,29 +55,48 @@ pub fn call_string_method(
             let idx = s.find(&substr).map(|i| i as f64).unwrap_or(-1.0);
             Ok(JsValue::Number(idx))
         }
+        // Locales are accepted and ignored; see `collation` for the order.
+        "localeCompare" => {
+            let that = args.first().map(|a| a.to_js_string()).unwrap_or_default();
+            let sensitivity = collation_sensitivity(args.get(2))?;
+            let order = collation::locale_compare(s, &that, sensitivity);
+            Ok(JsValue::Number(order as i8 as f64))
+        }
         "slice" => {
             let len = s.len() as i64;
             let start = normalize_index(args.first(), len);
             let end = args.get(1).map_or(len, |a| normalize_index(Some(a), len));
             if start >= end || start >= len {
-                return Ok(JsValue::String(String::new()));
+                return Ok(JsValue::String("".into()));
             }
             let result: String = s
                 .chars()
                 .skip(start as usize)
                 .take((end - start) as usize)
                 .collect();
-            Ok(JsValue::String(result))
+            Ok(JsValue::String(result.into()))
         }
         "split" => {
-            if let Some(JsValue::RegExp(re)) = args.first() {
-                return split_with_regex(s, re, heap);
-            }
-            let sep = args.first().map(|a| a.to_js_string()).unwrap_or_default();
-            let parts: Vec<JsValue> = s
-                .split(&sep)
-                .map(|part| JsValue::String(part.to_string()))
-                .collect();
+            let limit = split_limit(args.get(1));
+            let parts: Vec<JsValue> = match args.first() {
+                _ if limit == 0 => Vec::new(),
+                None | Some(JsValue::Undefined) => vec![JsValue::String(s.into())],
+                Some(JsValue::RegExp(re)) => split_with_regex(s, re, limit),
+                Some(sep) => {
+                    let sep = sep.to_js_string();
+                    if sep.is_empty() {
+                        s.chars()
+                            .take(limit)
+                            .map(|c| JsValue::String(c.into()))
+                            .collect()
+                    } else {
+                        s.split(sep.as_str())
+                            .take(limit)
+                            .map(|part| JsValue::String(part.into()))
+                            .collect()
+                    }
+                }
+            };
             Ok(JsValue::Array(heap.alloc_cell(JsArray::new(parts))))
         }
         "match" => {
@@ -64,7 +106,7 @@ pub fn call_string_method(
             let pattern = args.first().map(|a| a.to_js_string()).unwrap_or_default();
             match s.find(&pattern) {
                 Some(_) => {
-                    let arr = heap.alloc_cell(JsArray::new(vec![JsValue::String(pattern)]));
+                    let arr = heap.alloc_cell(JsArray::new(vec![JsValue::String(pattern.into())]));
                     Ok(JsValue::Array(arr))
                 }
                 None => Ok(JsValue::Null),
@@ -77,7 +119,9 @@ pub fn call_string_method(
             }
             let pattern = args.first().map(|a| a.to_js_string()).unwrap_or_default();
             let replacement = args.get(1).map(|a| a.to_js_string()).unwrap_or_default();
-            Ok(JsValue::String(s.replacen(&pattern, &replacement, 1)))
+            Ok(JsValue::String(
+                s.replacen(&pattern, &replacement, 1).into(),
+            ))
         }
         "replaceAll" => {
             if let Some(JsValue::RegExp(re)) = args.first() {
@@ -86,7 +130,7 @@ pub fn call_string_method(
             }
             let pattern = args.first().map(|a| a.to_js_string()).unwrap_or_default();
             let replacement = args.get(1).map(|a| a.to_js_string()).unwrap_or_default();
-            Ok(JsValue::String(s.replace(&pattern, &replacement)))
+            Ok(JsValue::String(s.replace(&pattern, &replacement).into()))
         }
         "search" => {
             if let Some(JsValue::RegExp(re)) = args.first() {
@@ -102,6 +146,25 @@ pub fn call_string_method(
     }
 }
 
+/// Reads `sensitivity` from the options argument of `localeCompare`.
+fn collation_sensitivity(options: Option<&JsValue>) -> Result<Sensitivity, RuntimeError> {
+    let value = match options {
+        Some(JsValue::Object(obj)) => obj.borrow().get("sensitivity"),
+        _ => None,
+    };
+    match value {
+        None | Some(JsValue::Undefined) => Ok(Sensitivity::Variant),
+        Some(value) => {
+            let name = value.to_js_string();
+            Sensitivity::from_name(&name).ok_or_else(|| RuntimeError::TypeError {
+                message: format!(
+                    "Value {name} out of range for localeCompare options property sensitivity"
+                ),
+            })
+        }
+    }
+}
+
 fn normalize_index(arg: Option<&JsValue>, len: i64) -> i64 {
     let n = arg.map(|a| a.to_number() as i64).unwrap_or(0);
     if n < 0 {
@@ -122,20 +185,16 @@ fn match_with_regex(
         if matches.is_empty() {
             return Ok(JsValue::Null);
         }
-        let vals: Vec<JsValue> = matches.into_iter().map(JsValue::String).collect();
+        let vals: Vec<JsValue> = matches
+            .into_iter()
+            .map(|s| JsValue::String(s.into()))
+            .collect();
         Ok(JsValue::Array(heap.alloc_cell(JsArray::new(vals))))
     } else {
         match re.exec(s) {
             Some(m) => {
-                let vals: Vec<JsValue> = m
-                    .captures
-                    .iter()
-                    .map(|c| match c {
-                        Some(s) => JsValue::String(s.clone()),
-                        None => JsValue::Undefined,
-                    })
-                    .collect();
-                Ok(JsValue::Array(heap.alloc_cell(JsArray::new(vals))))
+                let array = m.into_array(s, heap);
+                Ok(JsValue::Array(heap.alloc_cell(array)))
             }
             None => Ok(JsValue::Null),
         }
@@ -152,11 +211,11 @@ fn replace_with_regex(
     let compiled = re.compiled();
     if re.flags.global || replace_all {
         Ok(JsValue::String(
-            compiled.replace_all(s, replacement).into_owned(),
+            compiled.replace_all(s, replacement).into_owned().into(),
         ))
     } else {
         Ok(JsValue::String(
-            compiled.replace(s, replacement).into_owned(),
+            compiled.replace(s, replacement).into_owned().into(),
         ))
     }
 }
@@ -164,21 +223,72 @@ fn replace_with_regex(
 fn search_with_regex(s: &str, re: &Gc<GcCell<JsRegExp>>) -> Result<JsValue, RuntimeError> {
     let re = re.borrow();
     match re.compiled().find(s) {
-        Some(m) => Ok(JsValue::Number(m.start() as f64)),
+        Some(m) => Ok(JsValue::Number(utf16_len(&s[..m.start()]) as f64)),
         None => Ok(JsValue::Number(-1.0)),
     }
 }
 
-fn split_with_regex(
-    s: &str,
-    re: &Gc<GcCell<JsRegExp>>,
-    heap: &mut Heap,
-) -> Result<JsValue, RuntimeError> {
+/// `ToUint32` of the split limit; an absent limit means "unbounded".
+fn split_limit(arg: Option<&JsValue>) -> usize {
+    match arg {
+        None | Some(JsValue::Undefined) => u32::MAX as usize,
+        Some(value) => {
+            let n = value.to_number();
+            if n.is_finite() {
+                n.trunc().rem_euclid(4_294_967_296.0) as usize
+            } else {
+                0
+            }
+        }
+    }
+}
+
+/// Splits on each regex match, splicing capture groups into the result.
+/// Empty matches at the current split point advance by one character so
+/// patterns like `/(?:)/` cannot loop forever.
+fn split_with_regex(s: &str, re: &Gc<GcCell<JsRegExp>>, limit: usize) -> Vec<JsValue> {
     let re = re.borrow();
-    let parts: Vec<JsValue> = re
-        .compiled()
-        .split(s)
-        .map(|part| JsValue::String(part.to_string()))
-        .collect();
-    Ok(JsValue::Array(heap.alloc_cell(JsArray::new(parts))))
+    let compiled = re.compiled();
+    if s.is_empty() {
+        return match compiled.find(s) {
+            Some(_) => Vec::new(),
+            None => vec![JsValue::String("".into())],
+        };
+    }
+
+    let mut parts = Vec::new();
+    let mut last_end = 0;
+    let mut search_from = 0;
+    while search_from < s.len() {
+        let Some(caps) = compiled.captures_at(s, search_from) else {
+            break;
+        };
+        let whole = caps.get(0).expect("group 0 always participates");
+        if whole.start() >= s.len() {
+            break;
+        }
+        if whole.end() == last_end {
+            search_from += s[search_from..].chars().next().map_or(1, char::len_utf8);
+            continue;
+        }
+        parts.push(JsValue::String(
+            s[last_end..whole.start()].to_string().into(),
+        ));
+        if parts.len() == limit {
+            return parts;
+        }
+        for group in caps.iter().skip(1) {
+            parts.push(match group {
+                Some(m) => JsValue::String(m.as_str().to_string().into()),
+                None => JsValue::Undefined,
+            });
+            if parts.len() == limit {
+                return parts;
+            }
+        }
+        last_end = whole.end();
+        search_from = last_end;
+    }
+    parts.push(JsValue::String(s[last_end..].to_string().into()));
+    parts
 }
@@ -49,6 +49,7 @@ pub mod well_known {
     pub const TO_PRIMITIVE: u64 = u64::MAX - 1;
     pub const HAS_INSTANCE: u64 = u64::MAX - 2;
     pub const TO_STRING_TAG: u64 = u64::MAX - 3;
+    pub const ASYNC_ITERATOR: u64 = u64::MAX - 4;
 }
 
 /// Get the well-known Symbol.iterator.
@@ -59,6 +60,14 @@ pub fn symbol_iterator() -> JsSymbol {
     }
 }
 
+/// Get the well-known Symbol.asyncIterator.
+pub fn symbol_async_iterator() -> JsSymbol {
+    JsSymbol {
+        id: well_known::ASYNC_ITERATOR,
+        description: Some("Symbol.asyncIterator".to_string()),
+    }
+}
+
 /// Get the well-known Symbol.toPrimitive.
 pub fn symbol_to_primitive() -> JsSymbol {
     JsSymbol {
@@ -84,7 +93,7 @@ pub fn symbol_to_string_tag() -> JsSymbol {
 }
 
 /// Global symbol registry for Symbol.for() / Symbol.keyFor().
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct SymbolRegistry {
     by_key: HashMap<String, JsSymbol>,
 }
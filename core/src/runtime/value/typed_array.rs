@@ -0,0 +1,333 @@
+//! Binary data: `ArrayBuffer`, the typed array views over one and
+//! `DataView`.
+//!
+//! A buffer owns its bytes; views only hold a reference to it plus the
+//! range they cover, so writes through one view are seen by every other
+//! view of the same buffer. Buffers cannot be resized, only detached,
+//! which leaves them and every view over them empty. Typed arrays store
+//! their elements little-endian whatever the host's byte order.
+
+use crate::runtime::gc::{Gc, GcCell, Trace, Tracer};
+use crate::runtime::value::{to_int32, to_uint32};
+
+/// Constructors handled by `NativeFunction::BinaryCtor`.
+pub const BINARY_CONSTRUCTORS: &[&str] = &[
+    "ArrayBuffer",
+    "DataView",
+    "Int8Array",
+    "Uint8Array",
+    "Uint8ClampedArray",
+    "Int16Array",
+    "Uint16Array",
+    "Int32Array",
+    "Uint32Array",
+    "Float32Array",
+    "Float64Array",
+];
+
+pub const TYPED_ARRAY_METHODS: &[&str] = &["fill", "set", "slice", "subarray"];
+
+pub const DATA_VIEW_METHODS: &[&str] = &[
+    "getFloat32",
+    "getFloat64",
+    "getInt16",
+    "getInt32",
+    "getInt8",
+    "getUint16",
+    "getUint32",
+    "getUint8",
+    "setFloat32",
+    "setFloat64",
+    "setInt16",
+    "setInt32",
+    "setInt8",
+    "setUint16",
+    "setUint32",
+    "setUint8",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct JsArrayBuffer {
+    pub bytes: Vec<u8>,
+    pub detached: bool,
+}
+
+impl JsArrayBuffer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            detached: false,
+        }
+    }
+
+    /// Drops the bytes, as transferring the buffer away would.
+    pub fn detach(&mut self) {
+        self.bytes = Vec::new();
+        self.detached = true;
+    }
+}
+
+impl Trace for JsArrayBuffer {
+    fn trace(&self, _tracer: &mut Tracer) {}
+}
+
+/// The element type of a typed array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypedArrayKind {
+    Int8,
+    Uint8,
+    Uint8Clamped,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
+}
+
+impl TypedArrayKind {
+    /// The kind a constructor such as `Uint8Array` creates.
+    pub fn from_constructor(name: &str) -> Option<Self> {
+        Some(match name {
+            "Int8Array" => Self::Int8,
+            "Uint8Array" => Self::Uint8,
+            "Uint8ClampedArray" => Self::Uint8Clamped,
+            "Int16Array" => Self::Int16,
+            "Uint16Array" => Self::Uint16,
+            "Int32Array" => Self::Int32,
+            "Uint32Array" => Self::Uint32,
+            "Float32Array" => Self::Float32,
+            "Float64Array" => Self::Float64,
+            _ => return None,
+        })
+    }
+
+    /// The kind a `DataView` accessor such as `getUint16` reads.
+    pub fn from_accessor(type_name: &str) -> Option<Self> {
+        Self::from_constructor(&format!("{type_name}Array"))
+            .filter(|kind| *kind != Self::Uint8Clamped)
+    }
+
+    pub fn constructor_name(self) -> &'static str {
+        match self {
+            Self::Int8 => "Int8Array",
+            Self::Uint8 => "Uint8Array",
+            Self::Uint8Clamped => "Uint8ClampedArray",
+            Self::Int16 => "Int16Array",
+            Self::Uint16 => "Uint16Array",
+            Self::Int32 => "Int32Array",
+            Self::Uint32 => "Uint32Array",
+            Self::Float32 => "Float32Array",
+            Self::Float64 => "Float64Array",
+        }
+    }
+
+    pub fn element_size(self) -> usize {
+        match self {
+            Self::Int8 | Self::Uint8 | Self::Uint8Clamped => 1,
+            Self::Int16 | Self::Uint16 => 2,
+            Self::Int32 | Self::Uint32 | Self::Float32 => 4,
+            Self::Float64 => 8,
+        }
+    }
+
+    /// Decodes one element from the first `element_size` bytes of `bytes`.
+    pub fn decode(self, bytes: &[u8], little_endian: bool) -> f64 {
+        let mut raw = [0u8; 8];
+        raw[..self.element_size()].copy_from_slice(&bytes[..self.element_size()]);
+        if !little_endian {
+            raw[..self.element_size()].reverse();
+        }
+        match self {
+            Self::Int8 => raw[0] as i8 as f64,
+            Self::Uint8 | Self::Uint8Clamped => raw[0] as f64,
+            Self::Int16 => i16::from_le_bytes([raw[0], raw[1]]) as f64,
+            Self::Uint16 => u16::from_le_bytes([raw[0], raw[1]]) as f64,
+            Self::Int32 => i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            Self::Uint32 => u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            Self::Float32 => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            Self::Float64 => f64::from_le_bytes(raw),
+        }
+    }
+
+    /// Encodes `n` into the first `element_size` bytes of `out`. Integer
+    /// kinds wrap modulo their range, except `Uint8Clamped`, which clamps
+    /// and rounds half to even.
+    pub fn encode(self, n: f64, little_endian: bool, out: &mut [u8]) {
+        let raw: [u8; 8] = match self {
+            Self::Int8 | Self::Uint8 | Self::Int16 | Self::Uint16 | Self::Int32 | Self::Uint32 => {
+                let mut raw = [0u8; 8];
+                let wrapped = if matches!(self, Self::Int32) {
+                    to_int32(n) as u32
+                } else {
+                    to_uint32(n)
+                };
+                raw[..4].copy_from_slice(&wrapped.to_le_bytes());
+                raw
+            }
+            Self::Uint8Clamped => {
+                let clamped = if n.is_nan() {
+                    0.0
+                } else {
+                    n.clamp(0.0, 255.0).round_ties_even()
+                };
+                [clamped as u8, 0, 0, 0, 0, 0, 0, 0]
+            }
+            Self::Float32 => {
+                let mut raw = [0u8; 8];
+                raw[..4].copy_from_slice(&(n as f32).to_le_bytes());
+                raw
+            }
+            Self::Float64 => n.to_le_bytes(),
+        };
+        let size = self.element_size();
+        out[..size].copy_from_slice(&raw[..size]);
+        if !little_endian {
+            out[..size].reverse();
+        }
+    }
+}
+
+/// A view of `length` elements of `kind`, starting `byte_offset` bytes
+/// into `buffer`.
+#[derive(Debug, Clone)]
+pub struct JsTypedArray {
+    pub kind: TypedArrayKind,
+    pub buffer: Gc<GcCell<JsArrayBuffer>>,
+    pub byte_offset: usize,
+    length: usize,
+}
+
+impl JsTypedArray {
+    pub fn new(
+        kind: TypedArrayKind,
+        buffer: Gc<GcCell<JsArrayBuffer>>,
+        byte_offset: usize,
+        length: usize,
+    ) -> Self {
+        Self {
+            kind,
+            buffer,
+            byte_offset,
+            length,
+        }
+    }
+
+    /// The number of elements, zero once the buffer is detached.
+    pub fn len(&self) -> usize {
+        if self.buffer.borrow().detached {
+            0
+        } else {
+            self.length
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn byte_length(&self) -> usize {
+        self.len() * self.kind.element_size()
+    }
+
+    /// The element at `index`, or `None` past the end.
+    pub fn get(&self, index: usize) -> Option<f64> {
+        if index >= self.len() {
+            return None;
+        }
+        let start = self.byte_offset + index * self.kind.element_size();
+        Some(self.kind.decode(&self.buffer.borrow().bytes[start..], true))
+    }
+
+    /// Stores `n` at `index`. Writes past the end are ignored.
+    pub fn set(&self, index: usize, n: f64) {
+        if index >= self.len() {
+            return;
+        }
+        let start = self.byte_offset + index * self.kind.element_size();
+        self.kind
+            .encode(n, true, &mut self.buffer.borrow_mut().bytes[start..]);
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        (0..self.len()).filter_map(|i| self.get(i)).collect()
+    }
+
+    /// The bytes the view covers.
+    pub fn bytes(&self) -> Vec<u8> {
+        let start = self.byte_offset;
+        self.buffer.borrow().bytes[start..start + self.byte_length()].to_vec()
+    }
+}
+
+impl Trace for JsTypedArray {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.mark(self.buffer);
+    }
+}
+
+/// A view for reading and writing values of any type at any byte offset
+/// of `buffer`, in either byte order.
+#[derive(Debug, Clone)]
+pub struct JsDataView {
+    pub buffer: Gc<GcCell<JsArrayBuffer>>,
+    pub byte_offset: usize,
+    byte_length: usize,
+}
+
+impl JsDataView {
+    pub fn new(buffer: Gc<GcCell<JsArrayBuffer>>, byte_offset: usize, byte_length: usize) -> Self {
+        Self {
+            buffer,
+            byte_offset,
+            byte_length,
+        }
+    }
+
+    /// The number of bytes covered, zero once the buffer is detached.
+    pub fn byte_length(&self) -> usize {
+        if self.buffer.borrow().detached {
+            0
+        } else {
+            self.byte_length
+        }
+    }
+
+    /// Reads a `kind` at `offset` bytes into the view, or `None` when it
+    /// does not fit.
+    pub fn get(&self, kind: TypedArrayKind, offset: usize, little_endian: bool) -> Option<f64> {
+        let start = self.element_start(kind, offset)?;
+        Some(kind.decode(&self.buffer.borrow().bytes[start..], little_endian))
+    }
+
+    /// Writes `n` as a `kind` at `offset`, returning false when it does
+    /// not fit.
+    pub fn set(&self, kind: TypedArrayKind, offset: usize, n: f64, little_endian: bool) -> bool {
+        let Some(start) = self.element_start(kind, offset) else {
+            return false;
+        };
+        kind.encode(
+            n,
+            little_endian,
+            &mut self.buffer.borrow_mut().bytes[start..],
+        );
+        true
+    }
+
+    /// The bytes the view covers.
+    pub fn bytes(&self) -> Vec<u8> {
+        let start = self.byte_offset;
+        self.buffer.borrow().bytes[start..start + self.byte_length()].to_vec()
+    }
+
+    fn element_start(&self, kind: TypedArrayKind, offset: usize) -> Option<usize> {
+        let end = offset.checked_add(kind.element_size())?;
+        (end <= self.byte_length()).then_some(self.byte_offset + offset)
+    }
+}
+
+impl Trace for JsDataView {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.mark(self.buffer);
+    }
+}
@@ -1,10 +1,19 @@
+use std::collections::BTreeMap;
+
 use super::{Opcode, VmValue};
+use crate::diagnostics::source_map::SourceMap;
+use crate::runtime::value::PropName;
 
 #[derive(Debug, Clone)]
 pub struct Chunk {
     pub instructions: Vec<Opcode>,
     pub constants: Vec<VmValue>,
-    pub lines: Vec<usize>,
+    /// Interned copy of each string constant, parallel to `constants`,
+    /// so global and property lookups need not allocate a key.
+    pub names: Vec<Option<PropName>>,
+    /// Source byte offset of the statement each instruction came from,
+    /// parallel to `instructions`.
+    pub offsets: Vec<u32>,
 }
 
 impl Default for Chunk {
@@ -18,32 +27,162 @@ impl Chunk {
         Self {
             instructions: Vec::new(),
             constants: Vec::new(),
-            lines: Vec::new(),
+            names: Vec::new(),
+            offsets: Vec::new(),
         }
     }
 
-    pub fn write(&mut self, op: Opcode, line: usize) {
+    pub fn write(&mut self, op: Opcode, offset: u32) {
         self.instructions.push(op);
-        self.lines.push(line);
+        self.offsets.push(offset);
     }
 
     pub fn add_constant(&mut self, value: VmValue) -> u16 {
+        self.names.push(match &value {
+            VmValue::String(name) => Some(PropName::new(name)),
+            _ => None,
+        });
         self.constants.push(value);
         (self.constants.len() - 1) as u16
     }
 
+    pub fn offset_at(&self, ip: usize) -> u32 {
+        self.offsets.get(ip).copied().unwrap_or(0)
+    }
+
     pub fn disassemble(&self) -> String {
+        self.render(None)
+    }
+
+    /// Like [`Chunk::disassemble`], with each instruction's source line.
+    pub fn disassemble_with_source(&self, source: &str) -> String {
+        self.render(Some(&SourceMap::from_source(source)))
+    }
+
+    /// The instruction at `offset` with the constant it names or the
+    /// instruction it jumps to, without a source line.
+    pub fn disassemble_instruction(&self, offset: usize) -> String {
+        format!("{offset:04} | {}", self.describe(offset, &BTreeMap::new()))
+    }
+
+    fn render(&self, source_map: Option<&SourceMap>) -> String {
         let mut out = String::new();
-        for (idx, op) in self.instructions.iter().enumerate() {
-            let line = self.lines.get(idx).copied().unwrap_or(0);
-            out.push_str(&format!("{:04} | {:>3} | {:?}\n", idx, line, op));
+        self.render_into(&mut out, source_map, 0);
+        out
+    }
+
+    /// Lists the instructions, then the constant pool, then each function
+    /// in the pool, indented by `depth` levels.
+    fn render_into(&self, out: &mut String, source_map: Option<&SourceMap>, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let labels = self.jump_labels();
+        for idx in 0..=self.instructions.len() {
+            if let Some(label) = labels.get(&idx) {
+                out.push_str(&format!("{indent}L{label}:\n"));
+            }
+            if idx == self.instructions.len() {
+                break;
+            }
+            let line = match source_map {
+                Some(map) => map
+                    .byte_to_pos(self.offset_at(idx) as usize)
+                    .line
+                    .to_string(),
+                None => "-".to_string(),
+            };
+            let text = self.describe(idx, &labels);
+            out.push_str(&format!("{indent}{idx:04} | {line:>3} | {text}\n"));
         }
         if !self.constants.is_empty() {
-            out.push_str("-- constants --\n");
-            for (idx, val) in self.constants.iter().enumerate() {
-                out.push_str(&format!("{:04} | {:?}\n", idx, val));
+            out.push_str(&format!("{indent}-- constants --\n"));
+            for (idx, value) in self.constants.iter().enumerate() {
+                let kind = type_name(value);
+                let value = preview(value);
+                out.push_str(&format!("{indent}{idx:04} | {kind:<8} | {value}\n"));
             }
         }
-        out
+        for value in &self.constants {
+            if let VmValue::Function(function) = value {
+                out.push_str(&format!(
+                    "{indent}-- function {} (arity {}) --\n",
+                    function.name, function.arity
+                ));
+                function.chunk.render_into(out, source_map, depth + 1);
+            }
+        }
+    }
+
+    /// Numbers the instructions jumps land on, L1 first, in the order
+    /// they appear. A jump may land one past the last instruction.
+    fn jump_labels(&self) -> BTreeMap<usize, usize> {
+        let mut targets: Vec<usize> = self
+            .instructions
+            .iter()
+            .filter_map(|op| match op {
+                Opcode::Jump(target) | Opcode::JumpIfFalse(target) | Opcode::Loop(target) => {
+                    Some(*target as usize)
+                }
+                _ => None,
+            })
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+        targets
+            .into_iter()
+            .enumerate()
+            .map(|(idx, target)| (target, idx + 1))
+            .collect()
+    }
+
+    fn describe(&self, offset: usize, labels: &BTreeMap<usize, usize>) -> String {
+        let op = &self.instructions[offset];
+        let note = match op {
+            Opcode::Jump(target) | Opcode::JumpIfFalse(target) | Opcode::Loop(target) => {
+                let target = *target as usize;
+                match labels.get(&target) {
+                    Some(label) => format!("-> {target:04} (L{label})"),
+                    None => format!("-> {target:04}"),
+                }
+            }
+            Opcode::Constant(idx)
+            | Opcode::GetGlobal(idx)
+            | Opcode::SetGlobal(idx)
+            | Opcode::DefineGlobal(idx, _)
+            | Opcode::TypeofGlobal(idx)
+            | Opcode::GetPropertyIC(idx) => match self.constants.get(*idx as usize) {
+                Some(value) => format!("; {}", preview(value)),
+                None => "; <missing constant>".to_string(),
+            },
+            _ => return format!("{op:?}"),
+        };
+        format!("{:<24} {note}", format!("{op:?}"))
+    }
+}
+
+fn type_name(value: &VmValue) -> &'static str {
+    match value {
+        VmValue::Undefined => "undefined",
+        VmValue::Null => "null",
+        VmValue::Boolean(_) => "boolean",
+        VmValue::Number(_) => "number",
+        VmValue::String(_) => "string",
+        VmValue::Function(_) => "function",
+        VmValue::Object(_) => "object",
+    }
+}
+
+/// A constant as it would be written in source, long strings cut short.
+fn preview(value: &VmValue) -> String {
+    const MAX_CHARS: usize = 32;
+    match value {
+        VmValue::String(s) if s.chars().count() > MAX_CHARS => {
+            let head: String = s.chars().take(MAX_CHARS).collect();
+            format!("{:?}...", head)
+        }
+        VmValue::String(s) => format!("{s:?}"),
+        VmValue::Function(function) => {
+            format!("<function {}/{}>", function.name, function.arity)
+        }
+        other => other.to_output(),
     }
 }
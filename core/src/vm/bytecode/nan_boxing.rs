@@ -147,7 +147,9 @@ impl NanBoxedValue {
                     Self::from_f64(*n)
                 }
             }
-            VmValue::String(_) | VmValue::Function(_) => heap.alloc(value.clone()),
+            VmValue::String(_) | VmValue::Function(_) | VmValue::Object(_) => {
+                heap.alloc(value.clone())
+            }
         }
     }
 
@@ -1,3 +1,5 @@
+use crate::runtime::environment::BindingKind;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Opcode {
     Constant(u16),
@@ -6,20 +8,40 @@ pub enum Opcode {
     Mul,
     Div,
     Mod,
+    Exp,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    UnsignedShiftRight,
     Negate,
+    BitNot,
     Not,
     Equal,
     StrictEqual,
     LessThan,
     GreaterThan,
+    LessEqual,
+    GreaterEqual,
     GetLocal(u16),
     SetLocal(u16),
     GetGlobal(u16),
+    /// Assigns to a global, which must not be `const`.
     SetGlobal(u16),
+    /// Declares a global of the given kind, replacing any earlier one.
+    DefineGlobal(u16, BindingKind),
     JumpIfFalse(u16),
     Jump(u16),
     Loop(u16),
     Call(u8),
+    /// Calls the function below the arguments with the value below it as
+    /// `this`.
+    CallMethod(u8),
+    /// Pushes the current frame's `this`.
+    This,
+    /// Pushes a copy of the top of the stack.
+    Dup,
     Return,
     Pop,
     Print,
@@ -31,6 +53,68 @@ pub enum Opcode {
     CreateObject,
     CreateArray,
     Typeof,
+    /// `typeof name` for a global that may not be declared.
+    TypeofGlobal(u16),
+    ToNumber,
+    Undefined,
     Nop,
     GetPropertyIC(u16),
 }
+
+impl Opcode {
+    /// The name of the instruction, without its operands.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Opcode::Constant(..) => "Constant",
+            Opcode::Add => "Add",
+            Opcode::Sub => "Sub",
+            Opcode::Mul => "Mul",
+            Opcode::Div => "Div",
+            Opcode::Mod => "Mod",
+            Opcode::Exp => "Exp",
+            Opcode::BitAnd => "BitAnd",
+            Opcode::BitOr => "BitOr",
+            Opcode::BitXor => "BitXor",
+            Opcode::ShiftLeft => "ShiftLeft",
+            Opcode::ShiftRight => "ShiftRight",
+            Opcode::UnsignedShiftRight => "UnsignedShiftRight",
+            Opcode::Negate => "Negate",
+            Opcode::BitNot => "BitNot",
+            Opcode::Not => "Not",
+            Opcode::Equal => "Equal",
+            Opcode::StrictEqual => "StrictEqual",
+            Opcode::LessThan => "LessThan",
+            Opcode::GreaterThan => "GreaterThan",
+            Opcode::LessEqual => "LessEqual",
+            Opcode::GreaterEqual => "GreaterEqual",
+            Opcode::GetLocal(..) => "GetLocal",
+            Opcode::SetLocal(..) => "SetLocal",
+            Opcode::GetGlobal(..) => "GetGlobal",
+            Opcode::SetGlobal(..) => "SetGlobal",
+            Opcode::DefineGlobal(..) => "DefineGlobal",
+            Opcode::JumpIfFalse(..) => "JumpIfFalse",
+            Opcode::Jump(..) => "Jump",
+            Opcode::Loop(..) => "Loop",
+            Opcode::Call(..) => "Call",
+            Opcode::CallMethod(..) => "CallMethod",
+            Opcode::This => "This",
+            Opcode::Dup => "Dup",
+            Opcode::Return => "Return",
+            Opcode::Pop => "Pop",
+            Opcode::Print => "Print",
+            Opcode::Nil => "Nil",
+            Opcode::True => "True",
+            Opcode::False => "False",
+            Opcode::GetProperty => "GetProperty",
+            Opcode::SetProperty => "SetProperty",
+            Opcode::CreateObject => "CreateObject",
+            Opcode::CreateArray => "CreateArray",
+            Opcode::Typeof => "Typeof",
+            Opcode::TypeofGlobal(..) => "TypeofGlobal",
+            Opcode::ToNumber => "ToNumber",
+            Opcode::Undefined => "Undefined",
+            Opcode::Nop => "Nop",
+            Opcode::GetPropertyIC(..) => "GetPropertyIC",
+        }
+    }
+}
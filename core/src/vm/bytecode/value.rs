@@ -1,10 +1,15 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use super::Chunk;
+use crate::runtime::value::{JsValue, number_to_string, string_to_number};
 
 #[derive(Debug, Clone)]
 pub struct VmFunction {
     pub name: String,
     pub arity: usize,
-    pub chunk: Box<Chunk>,
+    pub chunk: Rc<Chunk>,
 }
 
 #[derive(Debug, Clone)]
@@ -15,6 +20,8 @@ pub enum VmValue {
     Number(f64),
     String(String),
     Function(Box<VmFunction>),
+    /// A plain object, shared by reference like in the interpreter.
+    Object(Rc<RefCell<HashMap<String, VmValue>>>),
 }
 
 impl VmValue {
@@ -23,8 +30,38 @@ impl VmValue {
             VmValue::Number(n) => *n,
             VmValue::Boolean(true) => 1.0,
             VmValue::Boolean(false) | VmValue::Null => 0.0,
-            VmValue::String(s) => s.parse::<f64>().unwrap_or(f64::NAN),
-            VmValue::Undefined | VmValue::Function(_) => f64::NAN,
+            VmValue::String(s) => string_to_number(s),
+            VmValue::Undefined | VmValue::Function(_) | VmValue::Object(_) => f64::NAN,
+        }
+    }
+
+    /// `ToPrimitive` as an operator sees it, in the interpreter's value
+    /// type so both engines coerce with the same helpers. VM objects and
+    /// functions have no user-visible `valueOf` or `toString`, so they
+    /// take the default string forms.
+    pub fn to_primitive(&self) -> JsValue {
+        match self {
+            VmValue::Undefined => JsValue::Undefined,
+            VmValue::Null => JsValue::Null,
+            VmValue::Boolean(b) => JsValue::Boolean(*b),
+            VmValue::Number(n) => JsValue::Number(*n),
+            VmValue::String(s) => JsValue::String(s.into()),
+            VmValue::Function(f) => {
+                JsValue::String(format!("function {}() {{ [native code] }}", f.name).into())
+            }
+            VmValue::Object(_) => JsValue::String("[object Object]".into()),
+        }
+    }
+
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            VmValue::Undefined => "undefined",
+            VmValue::Null => "object",
+            VmValue::Boolean(_) => "boolean",
+            VmValue::Number(_) => "number",
+            VmValue::String(_) => "string",
+            VmValue::Function(_) => "function",
+            VmValue::Object(_) => "object",
         }
     }
 
@@ -34,7 +71,7 @@ impl VmValue {
             VmValue::Boolean(b) => *b,
             VmValue::Number(n) => *n != 0.0 && !n.is_nan(),
             VmValue::String(s) => !s.is_empty(),
-            VmValue::Function(_) => true,
+            VmValue::Function(_) | VmValue::Object(_) => true,
         }
     }
 
@@ -43,15 +80,32 @@ impl VmValue {
             VmValue::Undefined => "undefined".to_string(),
             VmValue::Null => "null".to_string(),
             VmValue::Boolean(b) => b.to_string(),
-            VmValue::Number(n) => {
-                if n.fract() == 0.0 {
-                    (*n as i64).to_string()
-                } else {
-                    n.to_string()
-                }
-            }
+            VmValue::Number(n) => number_to_string(*n),
             VmValue::String(s) => s.clone(),
             VmValue::Function(f) => format!("[Function: {}]", f.name),
+            VmValue::Object(properties) => {
+                let mut pairs: Vec<String> = properties
+                    .borrow()
+                    .iter()
+                    .map(|(k, v)| format!("{k}: {}", v.to_output()))
+                    .collect();
+                pairs.sort();
+                format!("{{ {} }}", pairs.join(", "))
+            }
+        }
+    }
+
+    /// `===`: values of different types are never equal, and `NaN` is
+    /// not equal to itself.
+    pub fn strict_equals(&self, other: &VmValue) -> bool {
+        match (self, other) {
+            (VmValue::Undefined, VmValue::Undefined) | (VmValue::Null, VmValue::Null) => true,
+            (VmValue::Boolean(a), VmValue::Boolean(b)) => a == b,
+            (VmValue::Number(a), VmValue::Number(b)) => a == b,
+            (VmValue::String(a), VmValue::String(b)) => a == b,
+            (VmValue::Function(a), VmValue::Function(b)) => Rc::ptr_eq(&a.chunk, &b.chunk),
+            (VmValue::Object(a), VmValue::Object(b)) => Rc::ptr_eq(a, b),
+            _ => false,
         }
     }
 }
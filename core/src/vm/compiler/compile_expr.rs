@@ -1,4 +1,4 @@
-use crate::parser::ast::{BinOp, Expr, Literal, UnaryOp};
+use crate::parser::ast::{AssignOp, BinOp, Expr, Literal, UnaryOp};
 
 use super::Compiler;
 use crate::vm::bytecode::{Opcode, VmValue};
@@ -7,12 +7,13 @@ impl Compiler {
     pub fn compile_expr(&mut self, expr: &Expr) {
         match expr {
             Expr::Literal(lit) => self.compile_literal(lit),
-            Expr::Identifier(name) => {
+            Expr::Identifier(name) if name == "this" => self.emit(Opcode::This),
+            Expr::Identifier(name) | Expr::Local { name, .. } => {
                 if let Some(local_idx) = self.resolve_local(name) {
-                    self.chunk.write(Opcode::GetLocal(local_idx), 0);
+                    self.emit(Opcode::GetLocal(local_idx));
                 } else {
                     let idx = self.chunk.add_constant(VmValue::String(name.clone()));
-                    self.chunk.write(Opcode::GetGlobal(idx), 0);
+                    self.emit(Opcode::GetGlobal(idx));
                 }
             }
             Expr::Binary { left, op, right } => {
@@ -23,100 +24,124 @@ impl Compiler {
             Expr::Unary { op, operand } => {
                 self.compile_expr(operand);
                 match op {
-                    UnaryOp::Neg => self.chunk.write(Opcode::Negate, 0),
-                    UnaryOp::Not => self.chunk.write(Opcode::Not, 0),
-                    UnaryOp::Void | UnaryOp::Pos => {
-                        self.require_tree_walk();
-                        return;
+                    UnaryOp::Neg => self.emit(Opcode::Negate),
+                    UnaryOp::Not => self.emit(Opcode::Not),
+                    UnaryOp::Pos => self.emit(Opcode::ToNumber),
+                    UnaryOp::BitNot => self.emit(Opcode::BitNot),
+                    UnaryOp::Void => {
+                        self.emit(Opcode::Pop);
+                        self.emit(Opcode::Undefined);
                     }
                 }
             }
+            Expr::Sequence(exprs) => {
+                for (idx, expr) in exprs.iter().enumerate() {
+                    if idx > 0 {
+                        self.emit(Opcode::Pop);
+                    }
+                    self.compile_expr(expr);
+                }
+            }
             Expr::Call { callee, args } => {
+                if let Expr::MemberAccess { object, property } = &**callee {
+                    self.compile_expr(object);
+                    self.emit(Opcode::Dup);
+                    let idx = self.chunk.add_constant(VmValue::String(property.clone()));
+                    self.emit(Opcode::GetPropertyIC(idx));
+                    for arg in args {
+                        self.compile_expr(arg);
+                    }
+                    self.emit(Opcode::CallMethod(args.len() as u8));
+                    return;
+                }
                 self.compile_expr(callee);
                 for arg in args {
                     self.compile_expr(arg);
                 }
-                self.chunk.write(Opcode::Call(args.len() as u8), 0);
+                self.emit(Opcode::Call(args.len() as u8));
             }
-            Expr::Assign { name, value } => {
+            Expr::Assign { name, value, .. } => {
                 self.compile_expr(value);
                 if let Some(local_idx) = self.resolve_local(name) {
-                    self.chunk.write(Opcode::SetLocal(local_idx), 0);
-                    self.chunk.write(Opcode::GetLocal(local_idx), 0);
+                    self.check_local_assignment(local_idx);
+                    self.emit(Opcode::SetLocal(local_idx));
+                    self.emit(Opcode::GetLocal(local_idx));
                 } else {
                     let idx = self.chunk.add_constant(VmValue::String(name.clone()));
-                    self.chunk.write(Opcode::SetGlobal(idx), 0);
-                    self.chunk.write(Opcode::GetGlobal(idx), 0);
+                    self.emit(Opcode::SetGlobal(idx));
+                    self.emit(Opcode::GetGlobal(idx));
                 }
             }
-            Expr::CompoundAssign { name, op, value } => {
+            Expr::CompoundAssign { op, .. } if op.logical().is_some() => {
+                // The short-circuit needs jumps around the store; leave it
+                // to the interpreter for now.
+                self.require_tree_walk();
+            }
+            Expr::CompoundAssign {
+                name, op, value, ..
+            } => {
                 if let Some(local_idx) = self.resolve_local(name) {
-                    self.chunk.write(Opcode::GetLocal(local_idx), 0);
+                    self.check_local_assignment(local_idx);
+                    self.emit(Opcode::GetLocal(local_idx));
                     self.compile_expr(value);
-                    match op {
-                        crate::parser::ast::AssignOp::Add => self.chunk.write(Opcode::Add, 0),
-                        crate::parser::ast::AssignOp::Sub => self.chunk.write(Opcode::Sub, 0),
-                        crate::parser::ast::AssignOp::Mul => self.chunk.write(Opcode::Mul, 0),
-                        crate::parser::ast::AssignOp::Div => self.chunk.write(Opcode::Div, 0),
-                        crate::parser::ast::AssignOp::Mod => self.chunk.write(Opcode::Mod, 0),
-                    }
-                    self.chunk.write(Opcode::SetLocal(local_idx), 0);
-                    self.chunk.write(Opcode::GetLocal(local_idx), 0);
+                    self.emit(assign_opcode(op));
+                    self.emit(Opcode::SetLocal(local_idx));
+                    self.emit(Opcode::GetLocal(local_idx));
                 } else {
                     let idx = self.chunk.add_constant(VmValue::String(name.clone()));
-                    self.chunk.write(Opcode::GetGlobal(idx), 0);
+                    self.emit(Opcode::GetGlobal(idx));
                     self.compile_expr(value);
-                    match op {
-                        crate::parser::ast::AssignOp::Add => self.chunk.write(Opcode::Add, 0),
-                        crate::parser::ast::AssignOp::Sub => self.chunk.write(Opcode::Sub, 0),
-                        crate::parser::ast::AssignOp::Mul => self.chunk.write(Opcode::Mul, 0),
-                        crate::parser::ast::AssignOp::Div => self.chunk.write(Opcode::Div, 0),
-                        crate::parser::ast::AssignOp::Mod => self.chunk.write(Opcode::Mod, 0),
-                    }
-                    self.chunk.write(Opcode::SetGlobal(idx), 0);
-                    self.chunk.write(Opcode::GetGlobal(idx), 0);
+                    self.emit(assign_opcode(op));
+                    self.emit(Opcode::SetGlobal(idx));
+                    self.emit(Opcode::GetGlobal(idx));
                 }
             }
-            Expr::UpdateExpr { name, op, prefix } => {
-                let one = self.chunk.add_constant(VmValue::Number(1.0));
-                if let Some(local_idx) = self.resolve_local(name) {
-                    self.chunk.write(Opcode::GetLocal(local_idx), 0);
-                    if !prefix {
-                        self.require_tree_walk();
-                        return;
-                    }
-                    self.chunk.write(Opcode::Constant(one), 0);
-                    match op {
-                        crate::parser::ast::UpdateOp::Inc => self.chunk.write(Opcode::Add, 0),
-                        crate::parser::ast::UpdateOp::Dec => self.chunk.write(Opcode::Sub, 0),
-                    }
-                    self.chunk.write(Opcode::SetLocal(local_idx), 0);
-                    self.chunk.write(Opcode::GetLocal(local_idx), 0);
-                } else {
-                    let idx = self.chunk.add_constant(VmValue::String(name.clone()));
-                    self.chunk.write(Opcode::GetGlobal(idx), 0);
-                    if !prefix {
-                        self.require_tree_walk();
-                        return;
+            Expr::UpdateExpr {
+                name, op, prefix, ..
+            } => {
+                let (get, set) = match self.resolve_local(name) {
+                    Some(local_idx) => {
+                        self.check_local_assignment(local_idx);
+                        (Opcode::GetLocal(local_idx), Opcode::SetLocal(local_idx))
                     }
-                    self.chunk.write(Opcode::Constant(one), 0);
-                    match op {
-                        crate::parser::ast::UpdateOp::Inc => self.chunk.write(Opcode::Add, 0),
-                        crate::parser::ast::UpdateOp::Dec => self.chunk.write(Opcode::Sub, 0),
+                    None => {
+                        let idx = self.chunk.add_constant(VmValue::String(name.clone()));
+                        (Opcode::GetGlobal(idx), Opcode::SetGlobal(idx))
                     }
-                    self.chunk.write(Opcode::SetGlobal(idx), 0);
-                    self.chunk.write(Opcode::GetGlobal(idx), 0);
+                };
+                if !prefix {
+                    // Postfix forms evaluate to the old value, coerced to a number.
+                    self.emit(get.clone());
+                    self.emit(Opcode::ToNumber);
+                }
+                let one = self.chunk.add_constant(VmValue::Number(1.0));
+                self.emit(get.clone());
+                self.emit(Opcode::ToNumber);
+                self.emit(Opcode::Constant(one));
+                match op {
+                    crate::parser::ast::UpdateOp::Inc => self.emit(Opcode::Add),
+                    crate::parser::ast::UpdateOp::Dec => self.emit(Opcode::Sub),
+                }
+                self.emit(set);
+                if *prefix {
+                    self.emit(get);
                 }
             }
             Expr::MemberAccess { object, property } => {
                 self.compile_expr(object);
                 let idx = self.chunk.add_constant(VmValue::String(property.clone()));
-                self.chunk.write(Opcode::GetPropertyIC(idx), 0);
-            }
-            Expr::Typeof(inner) => {
-                self.compile_expr(inner);
-                self.chunk.write(Opcode::Typeof, 0);
+                self.emit(Opcode::GetPropertyIC(idx));
             }
+            Expr::Typeof(inner) => match &**inner {
+                Expr::Identifier(name) if name != "this" && self.resolve_local(name).is_none() => {
+                    let idx = self.chunk.add_constant(VmValue::String(name.clone()));
+                    self.emit(Opcode::TypeofGlobal(idx));
+                }
+                _ => {
+                    self.compile_expr(inner);
+                    self.emit(Opcode::Typeof);
+                }
+            },
             Expr::Ternary {
                 condition,
                 then_expr,
@@ -124,10 +149,10 @@ impl Compiler {
             } => {
                 self.compile_expr(condition);
                 let jump_false_pos = self.chunk.instructions.len();
-                self.chunk.write(Opcode::JumpIfFalse(0), 0);
+                self.emit(Opcode::JumpIfFalse(0));
                 self.compile_expr(then_expr);
                 let jump_end_pos = self.chunk.instructions.len();
-                self.chunk.write(Opcode::Jump(0), 0);
+                self.emit(Opcode::Jump(0));
                 let else_start = self.chunk.instructions.len() as u16;
                 self.compile_expr(else_expr);
                 let end = self.chunk.instructions.len() as u16;
@@ -147,41 +172,63 @@ impl Compiler {
         match lit {
             Literal::Number(n) => {
                 let idx = self.chunk.add_constant(VmValue::Number(*n));
-                self.chunk.write(Opcode::Constant(idx), 0);
+                self.emit(Opcode::Constant(idx));
             }
             Literal::String(s) => {
                 let idx = self.chunk.add_constant(VmValue::String(s.clone()));
-                self.chunk.write(Opcode::Constant(idx), 0);
+                self.emit(Opcode::Constant(idx));
             }
-            Literal::Boolean(true) => self.chunk.write(Opcode::True, 0),
-            Literal::Boolean(false) => self.chunk.write(Opcode::False, 0),
-            Literal::Null | Literal::Undefined => self.chunk.write(Opcode::Nil, 0),
+            Literal::Boolean(true) => self.emit(Opcode::True),
+            Literal::Boolean(false) => self.emit(Opcode::False),
+            Literal::Null => self.emit(Opcode::Nil),
+            Literal::Undefined => self.emit(Opcode::Undefined),
         }
     }
 
     fn compile_binop(&mut self, op: &BinOp) {
         match op {
-            BinOp::Add => self.chunk.write(Opcode::Add, 0),
-            BinOp::Sub => self.chunk.write(Opcode::Sub, 0),
-            BinOp::Mul => self.chunk.write(Opcode::Mul, 0),
-            BinOp::Div => self.chunk.write(Opcode::Div, 0),
-            BinOp::Mod => self.chunk.write(Opcode::Mod, 0),
-            BinOp::Less => self.chunk.write(Opcode::LessThan, 0),
-            BinOp::LessEq => {
-                self.chunk.write(Opcode::GreaterThan, 0);
-                self.chunk.write(Opcode::Not, 0);
-            }
-            BinOp::Greater => self.chunk.write(Opcode::GreaterThan, 0),
-            BinOp::GreaterEq => {
-                self.chunk.write(Opcode::LessThan, 0);
-                self.chunk.write(Opcode::Not, 0);
-            }
-            BinOp::EqEq | BinOp::EqEqEq => self.chunk.write(Opcode::Equal, 0),
+            BinOp::Add => self.emit(Opcode::Add),
+            BinOp::Sub => self.emit(Opcode::Sub),
+            BinOp::Mul => self.emit(Opcode::Mul),
+            BinOp::Div => self.emit(Opcode::Div),
+            BinOp::Mod => self.emit(Opcode::Mod),
+            BinOp::Exp => self.emit(Opcode::Exp),
+            BinOp::BitAnd => self.emit(Opcode::BitAnd),
+            BinOp::BitOr => self.emit(Opcode::BitOr),
+            BinOp::BitXor => self.emit(Opcode::BitXor),
+            BinOp::Shl => self.emit(Opcode::ShiftLeft),
+            BinOp::Shr => self.emit(Opcode::ShiftRight),
+            BinOp::UShr => self.emit(Opcode::UnsignedShiftRight),
+            BinOp::Less => self.emit(Opcode::LessThan),
+            BinOp::LessEq => self.emit(Opcode::LessEqual),
+            BinOp::Greater => self.emit(Opcode::GreaterThan),
+            BinOp::GreaterEq => self.emit(Opcode::GreaterEqual),
+            BinOp::EqEq | BinOp::EqEqEq => self.emit(Opcode::Equal),
             BinOp::NotEq | BinOp::NotEqEq => {
-                self.chunk.write(Opcode::Equal, 0);
-                self.chunk.write(Opcode::Not, 0);
+                self.emit(Opcode::Equal);
+                self.emit(Opcode::Not);
             }
             _ => self.require_tree_walk(),
         }
     }
 }
+
+fn assign_opcode(op: &AssignOp) -> Opcode {
+    match op {
+        AssignOp::Add => Opcode::Add,
+        AssignOp::Sub => Opcode::Sub,
+        AssignOp::Mul => Opcode::Mul,
+        AssignOp::Div => Opcode::Div,
+        AssignOp::Mod => Opcode::Mod,
+        AssignOp::Exp => Opcode::Exp,
+        AssignOp::BitAnd => Opcode::BitAnd,
+        AssignOp::BitOr => Opcode::BitOr,
+        AssignOp::BitXor => Opcode::BitXor,
+        AssignOp::Shl => Opcode::ShiftLeft,
+        AssignOp::Shr => Opcode::ShiftRight,
+        AssignOp::UShr => Opcode::UnsignedShiftRight,
+        AssignOp::And | AssignOp::Or | AssignOp::Nullish => {
+            unreachable!("logical assignment is left to the interpreter")
+        }
+    }
+}
@@ -1,29 +1,24 @@
-use crate::parser::ast::{Expr, Stmt};
+use std::rc::Rc;
+
+use crate::parser::ast::{Expr, Pattern, Stmt};
 
 use super::Compiler;
+use crate::runtime::environment::BindingKind;
 use crate::vm::bytecode::{Opcode, VmFunction, VmValue};
 
 impl Compiler {
     pub fn compile_stmt(&mut self, stmt: &Stmt) {
         match stmt {
-            Stmt::VarDecl { pattern, init, .. } => {
-                let Some(name) = pattern.as_identifier() else {
-                    self.require_tree_walk();
-                    return;
-                };
-
-                if let Some(expr) = init {
-                    self.compile_expr(expr);
-                } else {
-                    self.chunk.write(Opcode::Nil, 0);
-                }
-
-                if self.scope_depth > 0 {
-                    let local_idx = self.define_local(name.to_string());
-                    self.chunk.write(Opcode::SetLocal(local_idx), 0);
-                } else {
-                    let idx = self.chunk.add_constant(VmValue::String(name.to_string()));
-                    self.chunk.write(Opcode::SetGlobal(idx), 0);
+            Stmt::VarDecl {
+                kind,
+                pattern,
+                init,
+            } => {
+                self.compile_var_declarator(kind.into(), pattern, init.as_ref());
+            }
+            Stmt::VarDeclList { kind, declarations } => {
+                for (pattern, init) in declarations {
+                    self.compile_var_declarator(kind.into(), pattern, init.as_ref());
                 }
             }
             Stmt::ExprStmt(expr) => {
@@ -35,11 +30,11 @@ impl Compiler {
                     && args.len() == 1
                 {
                     self.compile_expr(&args[0]);
-                    self.chunk.write(Opcode::Print, 0);
+                    self.emit(Opcode::Print);
                     return;
                 }
                 self.compile_expr(expr);
-                self.chunk.write(Opcode::Pop, 0);
+                self.emit(Opcode::Pop);
             }
             Stmt::Block(stmts) => {
                 self.begin_scope();
@@ -55,10 +50,10 @@ impl Compiler {
             } => {
                 self.compile_expr(condition);
                 let jump_false_pos = self.chunk.instructions.len();
-                self.chunk.write(Opcode::JumpIfFalse(0), 0);
+                self.emit(Opcode::JumpIfFalse(0));
                 self.compile_stmt(then_branch);
                 let jump_end_pos = self.chunk.instructions.len();
-                self.chunk.write(Opcode::Jump(0), 0);
+                self.emit(Opcode::Jump(0));
                 let else_start = self.chunk.instructions.len() as u16;
                 if let Some(else_stmt) = else_branch {
                     self.compile_stmt(else_stmt);
@@ -71,55 +66,96 @@ impl Compiler {
                 let loop_start = self.chunk.instructions.len() as u16;
                 self.compile_expr(condition);
                 let jump_out_pos = self.chunk.instructions.len();
-                self.chunk.write(Opcode::JumpIfFalse(0), 0);
+                self.emit(Opcode::JumpIfFalse(0));
                 self.compile_stmt(body);
-                self.chunk.write(Opcode::Loop(loop_start), 0);
+                self.emit(Opcode::Loop(loop_start));
                 let end = self.chunk.instructions.len() as u16;
                 self.chunk.instructions[jump_out_pos] = Opcode::JumpIfFalse(end);
             }
             Stmt::FunctionDecl {
-                name, params, body, ..
+                name,
+                params,
+                body,
+                decl_offset,
+                ..
             } => {
                 let mut fn_compiler = Compiler::new();
                 fn_compiler.scope_depth = 1;
+                fn_compiler.current_offset = *decl_offset as u32;
                 for param in params {
                     let Some(param_name) = param.pattern.as_identifier() else {
                         self.require_tree_walk();
                         return;
                     };
-                    fn_compiler.define_local(param_name.to_string());
+                    fn_compiler.define_local(param_name.to_string(), false);
                 }
-                for stmt in body {
+                // Compiling needs the statements now, skipped or not. A
+                // body that fails to parse is left for the tree-walker to
+                // report when it is called.
+                let Ok(body) = body.stmts() else {
+                    self.require_tree_walk();
+                    return;
+                };
+                for stmt in body.iter() {
                     fn_compiler.compile_stmt(stmt);
                 }
+                self.diagnostics.append(&mut fn_compiler.diagnostics);
                 if fn_compiler.requires_tree_walk {
                     self.require_tree_walk();
                     return;
                 }
-                fn_compiler.chunk.write(Opcode::Nil, 0);
-                fn_compiler.chunk.write(Opcode::Return, 0);
+                fn_compiler.emit(Opcode::Undefined);
+                fn_compiler.emit(Opcode::Return);
                 let function = VmFunction {
                     name: name.clone(),
                     arity: params.len(),
-                    chunk: Box::new(fn_compiler.chunk),
+                    chunk: Rc::new(fn_compiler.chunk),
                 };
                 let fn_idx = self
                     .chunk
                     .add_constant(VmValue::Function(Box::new(function)));
-                self.chunk.write(Opcode::Constant(fn_idx), 0);
+                self.emit(Opcode::Constant(fn_idx));
                 let name_idx = self.chunk.add_constant(VmValue::String(name.clone()));
-                self.chunk.write(Opcode::SetGlobal(name_idx), 0);
+                self.emit(Opcode::DefineGlobal(name_idx, BindingKind::Var));
             }
             Stmt::Return(value) => {
                 if let Some(expr) = value {
                     self.compile_expr(expr);
                 } else {
-                    self.chunk.write(Opcode::Nil, 0);
+                    self.emit(Opcode::Undefined);
+                }
+                self.emit(Opcode::Return);
+            }
+            Stmt::ForLoop {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.begin_scope();
+                if let Some(init) = init {
+                    self.compile_stmt(init);
+                }
+                let loop_start = self.chunk.instructions.len() as u16;
+                let jump_out_pos = condition.as_ref().map(|condition| {
+                    self.compile_expr(condition);
+                    let pos = self.chunk.instructions.len();
+                    self.emit(Opcode::JumpIfFalse(0));
+                    pos
+                });
+                self.compile_stmt(body);
+                if let Some(update) = update {
+                    self.compile_expr(update);
+                    self.emit(Opcode::Pop);
+                }
+                self.emit(Opcode::Loop(loop_start));
+                if let Some(pos) = jump_out_pos {
+                    let end = self.chunk.instructions.len() as u16;
+                    self.chunk.instructions[pos] = Opcode::JumpIfFalse(end);
                 }
-                self.chunk.write(Opcode::Return, 0);
+                self.end_scope();
             }
-            Stmt::ForLoop { .. }
-            | Stmt::ForOf { .. }
+            Stmt::ForOf { .. }
             | Stmt::ForIn { .. }
             | Stmt::Break { .. }
             | Stmt::Continue { .. }
@@ -130,11 +166,45 @@ impl Compiler {
             | Stmt::Class(_)
             | Stmt::Import(_)
             | Stmt::Export(_)
-            | Stmt::DoWhile { .. }
-            | Stmt::VarDeclList { .. } => {
+            | Stmt::DoWhile { .. } => {
                 self.require_tree_walk();
             }
             Stmt::Empty => {}
         }
     }
+
+    fn compile_var_declarator(
+        &mut self,
+        kind: BindingKind,
+        pattern: &Pattern,
+        init: Option<&Expr>,
+    ) {
+        let Some(name) = pattern.as_identifier() else {
+            self.require_tree_walk();
+            return;
+        };
+
+        if let Some(expr) = init {
+            self.compile_expr(expr);
+        } else {
+            self.emit(Opcode::Undefined);
+        }
+
+        if self.scope_depth > 0 {
+            // A fresh local lives in the stack slot its initializer was
+            // pushed to; only a redeclaration needs an explicit store.
+            let redeclared = self
+                .resolve_local(name)
+                .filter(|&idx| self.locals[idx as usize].depth == self.scope_depth);
+            if let Some(local_idx) = redeclared {
+                self.locals[local_idx as usize].is_const = kind == BindingKind::Const;
+                self.emit(Opcode::SetLocal(local_idx));
+            } else {
+                self.define_local(name.to_string(), kind == BindingKind::Const);
+            }
+        } else {
+            let idx = self.chunk.add_constant(VmValue::String(name.to_string()));
+            self.emit(Opcode::DefineGlobal(idx, kind));
+        }
+    }
 }
@@ -1,14 +1,25 @@
 mod compile_expr;
 mod compile_stmt;
 
+use crate::errors::SyntaxError;
 use crate::parser::ast::Program;
 
-use super::bytecode::Chunk;
+use super::bytecode::{Chunk, Opcode};
 
 #[derive(Debug, Clone)]
 pub struct Local {
     pub name: String,
     pub depth: usize,
+    pub is_const: bool,
+}
+
+/// A problem found while compiling, such as an assignment to a `const`
+/// local, which stops the program from running on the VM.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileDiagnostic {
+    pub message: String,
+    /// Source offset of the statement it was found in.
+    pub offset: u32,
 }
 
 pub struct Compiler {
@@ -16,6 +27,9 @@ pub struct Compiler {
     pub locals: Vec<Local>,
     pub scope_depth: usize,
     pub requires_tree_walk: bool,
+    /// Source offset recorded for instructions emitted next.
+    pub current_offset: u32,
+    pub diagnostics: Vec<CompileDiagnostic>,
 }
 
 impl Default for Compiler {
@@ -31,21 +45,39 @@ impl Compiler {
             locals: Vec::new(),
             scope_depth: 0,
             requires_tree_walk: false,
+            current_offset: 0,
+            diagnostics: Vec::new(),
         }
     }
 
-    pub fn compile(&mut self, program: Program) -> Chunk {
+    /// Compiles `program`, failing with the first of its
+    /// [`diagnostics`](Self::diagnostics) if there are any.
+    pub fn compile(&mut self, program: Program) -> Result<Chunk, SyntaxError> {
         self.compile_program(&program);
+        if let Some(diagnostic) = self.diagnostics.first() {
+            return Err(SyntaxError::new(
+                diagnostic.message.clone(),
+                diagnostic.offset as usize,
+                0,
+            ));
+        }
         crate::vm::optimizer::optimize(&mut self.chunk);
-        self.chunk.clone()
+        Ok(self.chunk.clone())
     }
 
     pub fn compile_program(&mut self, program: &Program) {
-        for stmt in &program.body {
+        for (idx, stmt) in program.body.iter().enumerate() {
+            if let Some(&offset) = program.offsets.get(idx) {
+                self.current_offset = offset as u32;
+            }
             self.compile_stmt(stmt);
         }
     }
 
+    pub(crate) fn emit(&mut self, op: Opcode) {
+        self.chunk.write(op, self.current_offset);
+    }
+
     pub(crate) fn resolve_local(&self, name: &str) -> Option<u16> {
         self.locals
             .iter()
@@ -53,14 +85,27 @@ impl Compiler {
             .map(|idx| idx as u16)
     }
 
-    pub(crate) fn define_local(&mut self, name: String) -> u16 {
+    pub(crate) fn define_local(&mut self, name: String, is_const: bool) -> u16 {
         self.locals.push(Local {
             name,
             depth: self.scope_depth,
+            is_const,
         });
         (self.locals.len() - 1) as u16
     }
 
+    /// Reports an assignment to a `const` local; the interpreter throws
+    /// the same message when it runs the assignment.
+    pub(crate) fn check_local_assignment(&mut self, local_idx: u16) {
+        let local = &self.locals[local_idx as usize];
+        if local.is_const {
+            self.diagnostics.push(CompileDiagnostic {
+                message: format!("Assignment to constant variable '{}'", local.name),
+                offset: self.current_offset,
+            });
+        }
+    }
+
     pub(crate) fn begin_scope(&mut self) {
         self.scope_depth += 1;
     }
@@ -73,6 +118,7 @@ impl Compiler {
         while let Some(local) = self.locals.last() {
             if local.depth > self.scope_depth {
                 self.locals.pop();
+                self.emit(Opcode::Pop);
             } else {
                 break;
             }
@@ -1,18 +1,28 @@
+use std::rc::Rc;
+
 use crate::vm::bytecode::Chunk;
+use crate::vm::bytecode::nan_boxing::NanBoxedValue;
 
 #[derive(Clone)]
 pub struct CallFrame {
-    pub chunk: Chunk,
+    pub function_name: String,
+    pub chunk: Rc<Chunk>,
     pub ip: usize,
     pub slot: usize,
+    /// The receiver of a method call, `undefined` for a plain call and
+    /// the global object at top level. Kept per frame rather than on the
+    /// stack so arrow functions can read it from their enclosing frame.
+    pub this_value: NanBoxedValue,
 }
 
 impl CallFrame {
-    pub fn new(chunk: Chunk) -> Self {
+    pub fn new(chunk: Rc<Chunk>, this_value: NanBoxedValue) -> Self {
         Self {
+            function_name: "<global>".to_string(),
             chunk,
             ip: 0,
             slot: 0,
+            this_value,
         }
     }
 }
@@ -1,19 +1,48 @@
 mod call_frame;
 mod stack;
 
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
 
+#[cfg(feature = "profiling")]
+use crate::diagnostics::profile::Profile;
+use crate::diagnostics::source_map::SourceMap;
+use crate::diagnostics::stack_trace::{CallFrame as StackFrame, CallStack, attach_stack};
 use crate::errors::RuntimeError;
-use crate::vm::bytecode::nan_boxing::{Decoded, NanBoxedValue};
+use crate::runtime::environment::BindingKind;
+use crate::runtime::builtins::math::js_pow;
+use crate::runtime::output::{OutputMode, OutputSink};
+use crate::runtime::value::{
+    JsValue, PropName, add_primitives, primitive_less_than, shift_count, to_int32, to_uint32,
+};
+use crate::vm::bytecode::nan_boxing::NanBoxedValue;
 use crate::vm::bytecode::{Chunk, Opcode, VmValue};
 
 use call_frame::CallFrame;
 use stack::Stack;
 
+/// A global binding and the kind of declaration that made it.
+#[derive(Clone, Copy)]
+struct Global {
+    value: NanBoxedValue,
+    kind: BindingKind,
+}
+
 pub struct VM {
     stack: Stack,
     frames: Vec<CallFrame>,
-    globals: HashMap<String, NanBoxedValue>,
+    globals: HashMap<PropName, Global>,
+    /// The object top-level `this` refers to, also bound as `globalThis`.
+    global_this: NanBoxedValue,
+    output_mode: OutputMode,
+    output: Vec<String>,
+    /// Frames the last error from `run` unwound through, unless it was a
+    /// thrown object that keeps them in its `stack`.
+    error_trace: Option<String>,
+    #[cfg(feature = "profiling")]
+    profile: Profile,
 }
 
 impl Default for VM {
@@ -24,21 +53,93 @@ impl Default for VM {
 
 impl VM {
     pub fn new() -> Self {
+        let mut stack = Stack::new();
+        let global_this = stack
+            .heap
+            .alloc(VmValue::Object(Rc::new(RefCell::new(HashMap::new()))));
+        let mut globals = HashMap::new();
+        globals.insert(
+            "globalThis".into(),
+            Global {
+                value: global_this,
+                kind: BindingKind::Var,
+            },
+        );
         Self {
-            stack: Stack::new(),
+            stack,
             frames: Vec::new(),
-            globals: HashMap::new(),
+            globals,
+            global_this,
+            output_mode: OutputMode::Stdout,
+            output: Vec::new(),
+            error_trace: None,
+            #[cfg(feature = "profiling")]
+            profile: Profile::default(),
         }
     }
 
+    /// A VM whose printed lines go to `sink` rather than stdout.
+    pub fn new_with_sink(sink: OutputSink) -> Self {
+        let mut vm = Self::new();
+        vm.output_mode = OutputMode::Sink(sink);
+        vm
+    }
+
+    pub fn set_output_mode(&mut self, mode: OutputMode) {
+        self.output_mode = mode;
+    }
+
+    /// Binds `name` as a global for scripts this VM runs, the way
+    /// `Context::set_global` seeds the interpreter.
+    pub fn define_global(&mut self, name: impl Into<PropName>, value: VmValue) {
+        let value = NanBoxedValue::encode(&value, &mut self.stack.heap);
+        self.globals.insert(
+            name.into(),
+            Global {
+                value,
+                kind: BindingKind::Var,
+            },
+        );
+    }
+
+    pub fn output(&self) -> &[String] {
+        &self.output
+    }
+
+    /// The stack trace of the error the last `run` failed with.
+    pub fn error_trace(&self) -> Option<&str> {
+        self.error_trace.as_deref()
+    }
+
+    /// The opcodes executed since the profile was last taken.
+    #[cfg(feature = "profiling")]
+    pub fn take_profile(&mut self) -> Profile {
+        std::mem::take(&mut self.profile)
+    }
+
     pub fn run(
         &mut self,
         chunk: Chunk,
-        _source: Option<String>,
-        _source_path: Option<std::path::PathBuf>,
+        source: Option<String>,
+        source_path: Option<std::path::PathBuf>,
     ) -> Result<(), RuntimeError> {
-        self.frames.push(CallFrame::new(chunk));
+        self.error_trace = None;
+        self.frames
+            .push(CallFrame::new(Rc::new(chunk), self.global_this));
+        let result = self.execute();
+        if let Err(err) = result {
+            let trace = self.stack_trace(source.as_deref(), source_path.as_deref());
+            if !matches!(err, RuntimeError::Thrown { .. }) {
+                self.error_trace = Some(trace.clone());
+            }
+            self.frames.clear();
+            self.stack.truncate(0);
+            return Err(attach_stack(err, &trace));
+        }
+        Ok(())
+    }
 
+    fn execute(&mut self) -> Result<(), RuntimeError> {
         while !self.frames.is_empty() {
             let op = {
                 let frame = self.frames.last_mut().expect("frame should exist");
@@ -50,6 +151,8 @@ impl VM {
                 frame.ip += 1;
                 op
             };
+            #[cfg(feature = "profiling")]
+            self.profile.count_opcode(op.name());
 
             match op {
                 Opcode::Constant(idx) => {
@@ -57,12 +160,26 @@ impl VM {
                     self.stack.push_vm(val)?;
                 }
                 Opcode::Add => self.exec_add()?,
-                Opcode::Sub | Opcode::Mul | Opcode::Div | Opcode::Mod => {
+                Opcode::Sub
+                | Opcode::Mul
+                | Opcode::Div
+                | Opcode::Mod
+                | Opcode::Exp
+                | Opcode::BitAnd
+                | Opcode::BitOr
+                | Opcode::BitXor
+                | Opcode::ShiftLeft
+                | Opcode::ShiftRight
+                | Opcode::UnsignedShiftRight => {
                     self.exec_numeric_binary(&op)?;
                 }
                 Opcode::Negate => {
-                    let val = self.stack.pop_boxed()?;
-                    let result = NanBoxedValue::from_f64(-val.to_f64());
+                    let val = self.pop_number()?;
+                    self.stack.push_boxed(NanBoxedValue::from_f64(-val))?;
+                }
+                Opcode::BitNot => {
+                    let val = self.pop_number()?;
+                    let result = NanBoxedValue::from_f64(!to_int32(val) as f64);
                     self.stack.push_boxed(result)?;
                 }
                 Opcode::Not => {
@@ -73,33 +190,45 @@ impl VM {
                 Opcode::Equal | Opcode::StrictEqual => {
                     let rhs = self.stack.pop_vm()?;
                     let lhs = self.stack.pop_vm()?;
-                    let equal = lhs.to_output() == rhs.to_output();
-                    self.stack.push_boxed(NanBoxedValue::from_bool(equal))?;
-                }
-                Opcode::LessThan => {
-                    let rhs = self.stack.pop_boxed()?;
-                    let lhs = self.stack.pop_boxed()?;
-                    let result = lhs.to_f64() < rhs.to_f64();
-                    self.stack.push_boxed(NanBoxedValue::from_bool(result))?;
-                }
-                Opcode::GreaterThan => {
-                    let rhs = self.stack.pop_boxed()?;
-                    let lhs = self.stack.pop_boxed()?;
-                    let result = lhs.to_f64() > rhs.to_f64();
-                    self.stack.push_boxed(NanBoxedValue::from_bool(result))?;
+                    self.stack
+                        .push_boxed(NanBoxedValue::from_bool(lhs.strict_equals(&rhs)))?;
                 }
+                Opcode::LessThan
+                | Opcode::GreaterThan
+                | Opcode::LessEqual
+                | Opcode::GreaterEqual => self.exec_comparison(&op)?,
                 Opcode::SetGlobal(name_idx) => {
                     let key = self.constant_name(name_idx)?;
-                    let val = self.stack.pop_boxed()?;
-                    self.globals.insert(key, val);
+                    let value = self.stack.pop_boxed()?;
+                    match self.globals.get_mut(&key) {
+                        Some(global) if global.kind == BindingKind::Const => {
+                            return Err(RuntimeError::ConstReassignment {
+                                name: key.as_str().to_string(),
+                            });
+                        }
+                        Some(global) => global.value = value,
+                        None => {
+                            self.globals.insert(
+                                key,
+                                Global {
+                                    value,
+                                    kind: BindingKind::Var,
+                                },
+                            );
+                        }
+                    }
+                }
+                Opcode::DefineGlobal(name_idx, kind) => {
+                    let key = self.constant_name(name_idx)?;
+                    let value = self.stack.pop_boxed()?;
+                    self.globals.insert(key, Global { value, kind });
                 }
                 Opcode::GetGlobal(name_idx) => {
                     let key = self.constant_name(name_idx)?;
                     let val = self
                         .globals
                         .get(&key)
-                        .copied()
-                        .unwrap_or(NanBoxedValue::undefined());
+                        .map_or(NanBoxedValue::undefined(), |global| global.value);
                     self.stack.push_boxed(val)?;
                 }
                 Opcode::SetLocal(slot) => {
@@ -113,7 +242,23 @@ impl VM {
                     self.stack.push_boxed(val)?;
                 }
                 Opcode::Call(arg_count) => {
-                    self.exec_call(arg_count)?;
+                    self.exec_call(arg_count, None)?;
+                }
+                Opcode::CallMethod(arg_count) => {
+                    let receiver_depth = arg_count as usize + 1;
+                    let receiver = self.stack.peek_at_boxed(receiver_depth)?;
+                    self.exec_call(arg_count, Some(receiver))?;
+                }
+                Opcode::This => {
+                    let this_value = self
+                        .frames
+                        .last()
+                        .map_or(NanBoxedValue::undefined(), |f| f.this_value);
+                    self.stack.push_boxed(this_value)?;
+                }
+                Opcode::Dup => {
+                    let top = self.stack.peek_at_boxed(0)?;
+                    self.stack.push_boxed(top)?;
                 }
                 Opcode::Return => {
                     let result = self.stack.pop_boxed().unwrap_or(NanBoxedValue::undefined());
@@ -124,9 +269,29 @@ impl VM {
                 }
                 Opcode::Print => {
                     let value = self.stack.pop_vm()?;
-                    println!("{}", value.to_output());
+                    self.output_mode
+                        .write_line(value.to_output(), &mut self.output);
+                }
+                Opcode::ToNumber => {
+                    let val = self.stack.pop_vm()?;
+                    self.stack
+                        .push_boxed(NanBoxedValue::from_f64(val.to_number()))?;
+                }
+                Opcode::Typeof => {
+                    let val = self.stack.pop_vm()?;
+                    self.stack
+                        .push_vm(VmValue::String(val.type_of().to_string()))?;
+                }
+                Opcode::TypeofGlobal(name_idx) => {
+                    let key = self.constant_name(name_idx)?;
+                    let type_name = match self.globals.get(&key) {
+                        Some(global) => global.value.decode_to_vm(&self.stack.heap).type_of(),
+                        None => "undefined",
+                    };
+                    self.stack.push_vm(VmValue::String(type_name.to_string()))?;
                 }
                 Opcode::Nil => self.stack.push_boxed(NanBoxedValue::null())?,
+                Opcode::Undefined => self.stack.push_boxed(NanBoxedValue::undefined())?,
                 Opcode::True => self.stack.push_boxed(NanBoxedValue::from_bool(true))?,
                 Opcode::False => self.stack.push_boxed(NanBoxedValue::from_bool(false))?,
                 Opcode::JumpIfFalse(target) => {
@@ -158,45 +323,114 @@ impl VM {
         Ok(())
     }
 
+    /// Formats the active frames, innermost first, using the source
+    /// offset of the instruction each frame is currently executing.
+    fn stack_trace(&self, source: Option<&str>, source_path: Option<&Path>) -> String {
+        let source_map = SourceMap::from_source(source.unwrap_or(""));
+        let file = source_path
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "<script>".to_string());
+        let mut call_stack = CallStack::default();
+        for frame in &self.frames {
+            let offset = frame.chunk.offset_at(frame.ip.saturating_sub(1));
+            let pos = source_map.byte_to_pos(offset as usize);
+            call_stack.push_frame(StackFrame {
+                function_name: frame.function_name.clone(),
+                file: file.clone(),
+                line: pos.line,
+                col: pos.col,
+            });
+        }
+        call_stack.format_trace()
+    }
+
+    /// Pops a value and applies `ToNumber`, decoding only values that
+    /// are not numbers already.
+    fn pop_number(&mut self) -> Result<f64, RuntimeError> {
+        let boxed = self.stack.pop_boxed()?;
+        if boxed.is_number() {
+            return Ok(boxed.to_f64());
+        }
+        Ok(boxed
+            .decode_to_vm(&self.stack.heap)
+            .to_primitive()
+            .to_number())
+    }
+
+    /// Pops the right then the left operand of a binary operator as
+    /// primitives.
+    fn pop_primitive_operands(&mut self) -> Result<(JsValue, JsValue), RuntimeError> {
+        let rhs = self.stack.pop_vm()?.to_primitive();
+        let lhs = self.stack.pop_vm()?.to_primitive();
+        Ok((lhs, rhs))
+    }
+
     fn exec_add(&mut self) -> Result<(), RuntimeError> {
-        let rhs_b = self.stack.pop_boxed()?;
-        let lhs_b = self.stack.pop_boxed()?;
-        let lhs_is_str = matches!(lhs_b.decode(), Decoded::Pointer(_))
-            && matches!(lhs_b.decode_to_vm(&self.stack.heap), VmValue::String(_));
-        let rhs_is_str = matches!(rhs_b.decode(), Decoded::Pointer(_))
-            && matches!(rhs_b.decode_to_vm(&self.stack.heap), VmValue::String(_));
-        if lhs_is_str || rhs_is_str {
-            let lhs = lhs_b.decode_to_vm(&self.stack.heap);
-            let rhs = rhs_b.decode_to_vm(&self.stack.heap);
-            let s = format!("{}{}", lhs.to_output(), rhs.to_output());
-            self.stack.push_vm(VmValue::String(s))?;
-        } else {
+        let rhs_b = self.stack.peek_at_boxed(0)?;
+        let lhs_b = self.stack.peek_at_boxed(1)?;
+        if lhs_b.is_number() && rhs_b.is_number() {
+            self.stack.pop_boxed()?;
+            self.stack.pop_boxed()?;
             let result = NanBoxedValue::from_f64(lhs_b.to_f64() + rhs_b.to_f64());
-            self.stack.push_boxed(result)?;
+            return self.stack.push_boxed(result);
         }
-        Ok(())
+        let (lhs, rhs) = self.pop_primitive_operands()?;
+        match add_primitives(&lhs, &rhs) {
+            JsValue::String(s) => self.stack.push_vm(VmValue::String(s.into_string())),
+            sum => self
+                .stack
+                .push_boxed(NanBoxedValue::from_f64(sum.to_number())),
+        }
+    }
+
+    fn exec_comparison(&mut self, op: &Opcode) -> Result<(), RuntimeError> {
+        let (lhs, rhs) = self.pop_primitive_operands()?;
+        let result = match op {
+            Opcode::LessThan => primitive_less_than(&lhs, &rhs) == Some(true),
+            Opcode::GreaterThan => primitive_less_than(&rhs, &lhs) == Some(true),
+            Opcode::LessEqual => primitive_less_than(&rhs, &lhs) == Some(false),
+            Opcode::GreaterEqual => primitive_less_than(&lhs, &rhs) == Some(false),
+            _ => unreachable!(),
+        };
+        self.stack.push_boxed(NanBoxedValue::from_bool(result))
     }
 
     fn exec_numeric_binary(&mut self, op: &Opcode) -> Result<(), RuntimeError> {
-        let rhs = self.stack.pop_boxed()?.to_f64();
-        let lhs = self.stack.pop_boxed()?.to_f64();
+        let rhs = self.pop_number()?;
+        let lhs = self.pop_number()?;
         let result = match op {
             Opcode::Sub => lhs - rhs,
             Opcode::Mul => lhs * rhs,
             Opcode::Div => lhs / rhs,
             Opcode::Mod => lhs % rhs,
+            Opcode::Exp => js_pow(lhs, rhs),
+            Opcode::BitAnd => (to_int32(lhs) & to_int32(rhs)) as f64,
+            Opcode::BitOr => (to_int32(lhs) | to_int32(rhs)) as f64,
+            Opcode::BitXor => (to_int32(lhs) ^ to_int32(rhs)) as f64,
+            Opcode::ShiftLeft => (to_int32(lhs) << shift_count(rhs)) as f64,
+            Opcode::ShiftRight => (to_int32(lhs) >> shift_count(rhs)) as f64,
+            Opcode::UnsignedShiftRight => (to_uint32(lhs) >> shift_count(rhs)) as f64,
             _ => unreachable!(),
         };
         self.stack.push_boxed(NanBoxedValue::from_f64(result))
     }
 
-    fn exec_call(&mut self, arg_count: u8) -> Result<(), RuntimeError> {
+    /// Calls the function under `arg_count` arguments. A method call also
+    /// pops the receiver under the function and binds it as `this`.
+    fn exec_call(
+        &mut self,
+        arg_count: u8,
+        receiver: Option<NanBoxedValue>,
+    ) -> Result<(), RuntimeError> {
         let mut args = Vec::new();
         for _ in 0..arg_count {
             args.push(self.stack.pop_boxed()?);
         }
         args.reverse();
         let callee = self.stack.pop_vm()?;
+        if receiver.is_some() {
+            self.stack.pop_boxed()?;
+        }
         match callee {
             VmValue::Function(func) => {
                 if func.arity != arg_count as usize {
@@ -210,9 +444,11 @@ impl VM {
                     self.stack.push_boxed(arg)?;
                 }
                 self.frames.push(CallFrame {
-                    chunk: (*func.chunk).clone(),
+                    function_name: func.name.clone(),
+                    chunk: Rc::clone(&func.chunk),
                     ip: 0,
                     slot,
+                    this_value: receiver.unwrap_or(NanBoxedValue::undefined()),
                 });
             }
             _ => {
@@ -239,7 +475,7 @@ impl VM {
     fn current_chunk(&self) -> Result<&Chunk, RuntimeError> {
         self.frames
             .last()
-            .map(|f| &f.chunk)
+            .map(|f| f.chunk.as_ref())
             .ok_or_else(|| RuntimeError::TypeError {
                 message: "VM has no active frame".to_string(),
             })
@@ -257,9 +493,9 @@ impl VM {
             })
     }
 
-    fn constant_name(&self, idx: u16) -> Result<String, RuntimeError> {
-        match self.current_chunk()?.constants.get(idx as usize) {
-            Some(VmValue::String(name)) => Ok(name.clone()),
+    fn constant_name(&self, idx: u16) -> Result<PropName, RuntimeError> {
+        match self.current_chunk()?.names.get(idx as usize) {
+            Some(Some(name)) => Ok(name.clone()),
             _ => Err(RuntimeError::TypeError {
                 message: "global name constant must be a string".to_string(),
             }),
@@ -272,6 +508,11 @@ impl VM {
                 "length" => VmValue::Number(s.len() as f64),
                 _ => VmValue::Undefined,
             },
+            VmValue::Object(properties) => properties
+                .borrow()
+                .get(prop)
+                .cloned()
+                .unwrap_or(VmValue::Undefined),
             _ => VmValue::Undefined,
         }
     }
@@ -49,7 +49,6 @@ impl Stack {
         Ok(boxed.decode_to_vm(&self.heap))
     }
 
-    #[allow(dead_code)]
     pub fn peek_at_boxed(&self, offset: usize) -> Result<NanBoxedValue, RuntimeError> {
         if offset >= self.values.len() {
             return Err(RuntimeError::TypeError {
@@ -3,28 +3,83 @@ pub mod compiler;
 pub mod machine;
 pub mod optimizer;
 
+#[cfg(feature = "profiling")]
+use crate::diagnostics::profile::Profile;
 use crate::errors::CrustyError;
 use crate::lexer;
 use crate::parser;
 use crate::runtime::interpreter::Interpreter;
+use crate::runtime::output::{OutputMode, OutputSink};
 use std::path::PathBuf;
 
-pub fn run_vm(source: &str) -> Result<(), CrustyError> {
-    run_vm_with_path(source, None)
+/// Runs `source` on the VM and returns the lines it printed.
+pub fn run_vm(source: &str) -> Result<Vec<String>, CrustyError> {
+    run_vm_with_path(source, None, None)
 }
 
-pub fn run_vm_with_path(source: &str, path: Option<PathBuf>) -> Result<(), CrustyError> {
+/// Runs `source` on the VM, falling back to the interpreter for what the
+/// compiler cannot handle. Printed lines go to `sink` when one is given
+/// and are returned otherwise.
+pub fn run_vm_with_path(
+    source: &str,
+    path: Option<PathBuf>,
+    sink: Option<OutputSink>,
+) -> Result<Vec<String>, CrustyError> {
+    let output_mode = sink.map_or(OutputMode::Capture, OutputMode::Sink);
     let tokens = lexer::lex(source)?;
     let program = parser::parse(tokens)?;
     let mut compiler = compiler::Compiler::new();
     let chunk = compiler.compile(program.clone());
     if compiler.requires_tree_walk {
         let mut interp = Interpreter::new_with_realtime_timers(true);
+        interp.set_output_mode(output_mode);
         let exec_path = path.unwrap_or_else(|| PathBuf::from("."));
         interp.run_with_path(&program, exec_path)?;
-        return Ok(());
+        return Ok(interp.output().to_vec());
     }
     let mut vm = machine::VM::new();
-    vm.run(chunk, Some(source.to_string()), path)?;
-    Ok(())
+    vm.set_output_mode(output_mode);
+    vm.run(chunk?, Some(source.to_string()), path)?;
+    Ok(vm.output().to_vec())
+}
+
+/// `run_vm_with_path`, also returning what the engine that ran the
+/// script counted: the interpreter's profile when the compiler fell back
+/// to it, the VM's opcode counts otherwise.
+#[cfg(feature = "profiling")]
+pub fn run_vm_profiled(
+    source: &str,
+    path: Option<PathBuf>,
+    sink: Option<OutputSink>,
+) -> (Result<Vec<String>, CrustyError>, Profile) {
+    let output_mode = sink.map_or(OutputMode::Capture, OutputMode::Sink);
+    let program = match lexer::lex(source).and_then(parser::parse) {
+        Ok(program) => program,
+        Err(err) => return (Err(err.into()), Profile::default()),
+    };
+    let mut compiler = compiler::Compiler::new();
+    let chunk = compiler.compile(program.clone());
+    if compiler.requires_tree_walk {
+        let mut interp = Interpreter::new_with_realtime_timers(true);
+        interp.set_output_mode(output_mode);
+        let exec_path = path.unwrap_or_else(|| PathBuf::from("."));
+        let result = interp.run_with_path(&program, exec_path);
+        let output = interp.output().to_vec();
+        return (
+            result.map(|()| output).map_err(Into::into),
+            interp.take_profile(),
+        );
+    }
+    let chunk = match chunk {
+        Ok(chunk) => chunk,
+        Err(err) => return (Err(err.into()), Profile::default()),
+    };
+    let mut vm = machine::VM::new();
+    vm.set_output_mode(output_mode);
+    let result = vm.run(chunk, Some(source.to_string()), path);
+    let output = vm.output().to_vec();
+    (
+        result.map(|()| output).map_err(Into::into),
+        vm.take_profile(),
+    )
 }
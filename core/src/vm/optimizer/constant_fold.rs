@@ -1,3 +1,4 @@
+use crate::runtime::builtins::math::js_pow;
 use crate::vm::bytecode::{Chunk, Opcode, VmValue};
 
 pub fn constant_fold(chunk: &mut Chunk) {
@@ -32,6 +33,7 @@ fn try_fold(chunk: &Chunk, i: usize) -> Option<(VmValue, usize)> {
         Opcode::Add => a + b,
         Opcode::Sub => a - b,
         Opcode::Mul => a * b,
+        Opcode::Exp => js_pow(a, b),
         Opcode::Div => {
             if b == 0.0 {
                 return None;
@@ -0,0 +1,54 @@
+use crustyjs::analyze;
+use crustyjs::lexer::lex_with_comments;
+use crustyjs::lexer::token::TokenKind;
+
+#[test]
+fn every_syntax_error_is_reported_with_its_span() {
+    let source = "let a = ;\nfunction f() {\n  return 1 +;\n}\nlet ok = 2;\nif (ok { ok++; }\nconsole.log(ok);\n";
+    let result = analyze(source);
+
+    let found: Vec<(usize, &str)> = result
+        .errors
+        .iter()
+        .map(|err| {
+            let start = err.span.offset();
+            (start, &source[start..start + err.span.len()])
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (source.find(';').unwrap(), ";"),
+            (source.find("+;").unwrap() + 1, ";"),
+            (source.find("{ ok").unwrap(), "{"),
+        ]
+    );
+    assert!(result.program.is_none());
+    assert!(result.bytecode.is_none());
+}
+
+#[test]
+fn a_valid_program_comes_back_parsed_and_compiled() {
+    let result = analyze("// total\nlet x = 1 + 2;\nconsole.log(x);\n");
+    assert!(result.is_ok());
+    assert_eq!(result.program.expect("program").body.len(), 2);
+    assert_eq!(
+        result.tokens[0].kind,
+        TokenKind::LineComment(" total".into())
+    );
+    let bytecode = result.bytecode.expect("the VM compiles this program");
+    assert!(!bytecode.chunk.instructions.is_empty());
+    // The listing numbers lines from the source, comment included.
+    assert!(bytecode.disassembly.starts_with("0000 |   2 |"));
+}
+
+#[test]
+fn comments_are_kept_in_source_order() {
+    let tokens = lex_with_comments("a /* x */ / b; // end\n/re/.test(c)").expect("lexes");
+    let kinds: Vec<TokenKind> = tokens.into_iter().map(|t| t.kind).collect();
+    assert_eq!(kinds[0], TokenKind::Ident("a".into()));
+    assert_eq!(kinds[1], TokenKind::BlockComment(" x ".into()));
+    assert_eq!(kinds[2], TokenKind::Slash);
+    assert_eq!(kinds[5], TokenKind::LineComment(" end".into()));
+    assert!(matches!(kinds[6], TokenKind::RegexLiteral { .. }));
+}
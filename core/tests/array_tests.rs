@@ -166,6 +166,61 @@ fn array_for_each_with_function() {
     assert_eq!(out, vec!["10", "20", "30"]);
 }
 
+#[test]
+fn array_callbacks_get_index_array_and_this_arg() {
+    let src = r#"
+        const arr = [10, 20, 30];
+        console.log(arr.map((x, i, a) => x + i * a.length).join());
+        console.log(arr.filter(function (x) { return x > this.min; }, { min: 15 }).join());
+        arr.forEach((x, i, a) => { if (i === 0) a[2] = 99; console.log(x); });
+        console.log(arr.some((x, i) => i === 2), arr.every(x => x > 5));
+        console.log(arr.find((x, i) => i === 1), arr.findIndex(x => x === 99));
+        console.log(arr.findLast(x => x < 50), arr.findLastIndex(x => x > 100));
+        console.log(arr.map((x, i, a) => { a.push(0); return i; }).length);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec![
+            "10,23,36",
+            "20,30",
+            "10",
+            "20",
+            "99",
+            "true true",
+            "20 2",
+            "20 -1",
+            "3"
+        ]
+    );
+}
+
+#[test]
+fn array_reduce_seeds_from_the_first_element() {
+    let src = r#"
+        console.log([1, 2, 3].reduce((acc, x, i) => acc + x * i));
+        console.log([1, 2, 3].reduceRight((acc, x, i, a) => acc + x + a.length, ""));
+        console.log([].reduce((acc, x) => acc + x, "init"));
+        let message = "";
+        try {
+            [].reduce((acc, x) => acc + x);
+        } catch (e) {
+            message = e.name + ": " + e.message;
+        }
+        console.log(message);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec![
+            "9",
+            "332313",
+            "init",
+            "TypeError: Reduce of empty array with no initial value",
+        ]
+    );
+}
+
 #[test]
 fn for_loop_basic() {
     let src = r#"
@@ -218,3 +273,311 @@ fn for_of_with_console_log() {
     let out = run_and_capture(src);
     assert_eq!(out, vec!["a", "b", "c"]);
 }
+
+#[test]
+fn array_sort_is_stable_and_moves_undefined_last() {
+    let out = run_and_capture(
+        r#"
+        const a = [3, undefined, 1, 10, 2];
+        console.log(a.sort() === a, a);
+        const seen = [];
+        const pairs = [[1, "a"], [0, "b"], undefined, [1, "c"], [0, "d"]];
+        pairs.sort((x, y) => { seen.push(x, y); return x[0] - y[0]; });
+        console.log(pairs.map(p => p === undefined ? "-" : p[1]).join(""), seen.includes(undefined));
+        "#,
+    );
+    assert_eq!(out, vec!["true [1, 10, 2, 3, undefined]", "bdac- false"]);
+}
+
+#[test]
+fn array_sort_propagates_comparator_exceptions() {
+    let out = run_and_capture(
+        r#"
+        const a = [2, 1, 3];
+        try {
+            a.sort((x, y) => { if (x === 3 || y === 3) throw new Error("cmp"); return x - y; });
+        } catch (e) {
+            console.log(e.message, a);
+        }
+        try { a.sort(5); } catch (e) { console.log(e.name); }
+        "#,
+    );
+    assert_eq!(out, vec!["cmp [2, 1, 3]", "TypeError"]);
+}
+
+#[test]
+fn array_to_sorted_leaves_source_untouched() {
+    let out = run_and_capture(
+        "const src = [5, 1, 4]; const copy = src.toSorted((x, y) => y - x); console.log(src, copy, copy === src);",
+    );
+    assert_eq!(out, vec!["[5, 1, 4] [5, 4, 1] false"]);
+}
+
+#[test]
+fn array_splice_removes_inserts_and_returns_removed() {
+    let out = run_and_capture(
+        r#"
+        const arr = [1, 2, 3, 4];
+        const alias = arr;
+        console.log(arr.splice(1, 1, "a", "b", "c"));
+        console.log(alias);
+        console.log(arr.splice(3, 100));
+        console.log(arr);
+        console.log(arr.splice(-10, 1));
+        console.log(arr);
+        console.log(arr.splice(-1));
+        console.log(arr.splice());
+        console.log(arr);
+        const empty = [];
+        console.log(empty.splice(0, 2, "x"));
+        console.log(empty);
+    "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "[2]",
+            "[1, a, b, c, 3, 4]",
+            "[c, 3, 4]",
+            "[1, a, b]",
+            "[1]",
+            "[a, b]",
+            "[b]",
+            "[]",
+            "[a]",
+            "[]",
+            "[x]",
+        ]
+    );
+}
+
+#[test]
+fn array_shift_unshift_and_to_spliced() {
+    let out = run_and_capture(
+        r#"
+        const arr = [2, 3];
+        console.log(arr.unshift(0, 1));
+        console.log(arr);
+        console.log(arr.shift());
+        console.log(arr);
+        console.log([].shift());
+        const copy = arr.toSpliced(-2, 1, "x", "y");
+        console.log(copy);
+        console.log(arr);
+        console.log(arr.toSpliced(1));
+        console.log(arr.toSpliced("1", "1.9"));
+    "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "4",
+            "[0, 1, 2, 3]",
+            "0",
+            "[1, 2, 3]",
+            "undefined",
+            "[1, x, y, 3]",
+            "[1, 2, 3]",
+            "[1]",
+            "[1, 3]",
+        ]
+    );
+}
+
+#[test]
+fn define_property_on_array_indices() {
+    let out = run_and_capture(
+        r#"
+        const a = [1, 2, 3];
+        let reads = 0;
+        Object.defineProperty(a, "0", { get() { reads++; return "lazy"; } });
+        console.log(a[0], a[0], reads);
+
+        Object.defineProperty(a, "1", { value: 20, writable: false });
+        a[1] = 99;
+        const d = Object.getOwnPropertyDescriptor(a, "1");
+        console.log(a[1], d.value, d.writable, d.enumerable);
+
+        Object.defineProperty(a, "5", { value: "far" });
+        console.log(a.length, a[5]);
+
+        const got = Object.getOwnPropertyDescriptor(a, "0");
+        console.log(typeof got.get, got.value, Object.getOwnPropertyDescriptor(a, "9"));
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "lazy lazy 2",
+            "20 20 false true",
+            "6 far",
+            "function undefined undefined"
+        ]
+    );
+}
+
+#[test]
+fn array_builtins_read_accessor_indices_through_their_getters() {
+    let out = run_and_capture(
+        r#"
+        const b = [1, 2];
+        Object.defineProperty(b, 0, { get() { return "g"; } });
+        console.log(b.join(), String(b), JSON.stringify(b));
+        console.log(JSON.stringify(b.map((x) => x)), b.indexOf("g"), JSON.stringify(b.slice(0, 1)));
+        console.log(JSON.stringify([...b]), JSON.stringify([0].concat(b)));
+        for (const x of b) console.log(x);
+        const [first, ...rest] = b;
+        console.log(first, rest[0], b.reduce((acc, x) => acc + x, ""));
+
+        let stored = "z";
+        const c = [3, 1];
+        Object.defineProperty(c, 1, { get() { return stored; }, set(v) { stored = v; } });
+        c.sort();
+        console.log(c[0], stored);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "g,2 g,2 [\"g\",2]",
+            "[\"g\",2] 0 [\"g\"]",
+            "[\"g\",2] [0,\"g\",2]",
+            "g",
+            "2",
+            "g 2 g2",
+            "3 z",
+        ]
+    );
+}
+
+#[test]
+fn array_length_truncates_and_respects_pinned_indices() {
+    let out = run_and_capture(
+        r#"
+        const a = [1, 2, 3, 4, 5];
+        a.length = 3;
+        console.log(a.length, a[3]);
+        Object.defineProperty(a, "length", { value: 2 });
+        console.log(a.length);
+
+        Object.defineProperty(a, "1", { value: "pinned", configurable: false });
+        try {
+          Object.defineProperty(a, "length", { value: 0 });
+        } catch (e) {
+          console.log(e.name, a.length);
+        }
+        try {
+          Object.defineProperty(a, "length", { value: -1 });
+        } catch (e) {
+          console.log(e.name);
+        }
+
+        Object.defineProperty(a, "length", { writable: false });
+        a[4] = "ignored";
+        console.log(a.length, Object.getOwnPropertyDescriptor(a, "length").writable);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec!["3 undefined", "2", "TypeError 2", "RangeError", "2 false"]
+    );
+}
+
+#[test]
+fn frozen_arrays_reject_writes() {
+    let out = run_and_capture(
+        r#"
+        const f = Object.freeze([1, 2]);
+        f[0] = 9;
+        try {
+          f.push(3);
+        } catch (e) {
+          console.log(e instanceof TypeError);
+        }
+        try {
+          Object.defineProperty(f, "0", { value: 7 });
+        } catch (e) {
+          console.log(e.message);
+        }
+        console.log(f[0], f.length, Object.isFrozen(f), Object.isFrozen([1]));
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec!["true", "Cannot redefine property: 0", "1 2 true false"]
+    );
+}
+
+#[test]
+fn only_canonical_index_strings_address_elements() {
+    let out = run_and_capture(
+        r#"
+        const arr = [10, 20];
+        console.log(arr["0"], arr["1"]);
+        arr["01"] = "a";
+        arr["1.0"] = "b";
+        arr[" 1"] = "c";
+        console.log(arr.length, arr[1], arr["01"], arr["1.0"], arr[" 1"]);
+        console.log(Object.keys(arr).join("|"));
+        console.log("01" in arr, "2" in arr, delete arr["01"], "01" in arr);
+        console.log(Object.hasOwn(arr, "1.0"), Object.hasOwn(arr, "1"));
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "10 20",
+            "2 20 a b c",
+            "0|1|01|1.0| 1",
+            "true false true false",
+            "true true",
+        ]
+    );
+}
+
+#[test]
+fn join_and_to_string_render_nested_and_cyclic_arrays() {
+    let out = run_and_capture(
+        r#"
+        console.log([1, [2, [3]]].toString());
+        console.log([1, null, undefined, 2].join("-"), [1, 2].join(undefined), [1, 2].join(0));
+        console.log([{ toString() { return "obj"; } }, 1].join("+"));
+        const a = [1, 2];
+        a.push(a);
+        console.log(a.join(), String(a));
+        const b = [1];
+        b.push([b, 2]);
+        console.log(b.join("|"));
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec!["1,2,3", "1---2 1,2 102", "obj+1", "1,2, 1,2,", "1|,2"]
+    );
+}
+
+#[test]
+fn concat_spreads_array_arguments_one_level() {
+    let out = run_and_capture(
+        r#"
+        const a = [1];
+        const b = [2, [3]];
+        const c = a.concat(b, 4, "x", [[5]]);
+        console.log(JSON.stringify(c), a.length, b.length, c === a);
+        "#,
+    );
+    assert_eq!(out, vec![r#"[1,2,[3],4,"x",[5]] 1 2 false"#]);
+}
+
+#[test]
+fn slice_clamps_negative_and_out_of_range_bounds() {
+    let out = run_and_capture(
+        r#"
+        const o = [1, 2, 3, 4, 5];
+        console.log(o.slice(-2).join(), o.slice(1, -1).join(), o.slice(-10, 2).join());
+        console.log(o.slice(3, 1).length, o.slice(2, 100).join(), o.slice(-2, -10).length);
+        console.log(o.slice(1, undefined).join(), o.slice().join());
+        "#,
+    );
+    assert_eq!(out, vec!["4,5 2,3,4 1,2", "0 3,4,5 0", "2,3,4,5 1,2,3,4,5"]);
+}
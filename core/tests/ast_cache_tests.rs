@@ -0,0 +1,86 @@
+#![cfg(feature = "ast-serde")]
+
+use crustyjs::Context;
+use crustyjs::runtime::modules::ast_cache::AstCacheStats;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+fn fresh_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("crustyjs_ast_{}_{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+    dir
+}
+
+/// Runs `main` in a new context, as a fresh process would.
+fn run(main: &Path, cache: &Path) -> (Vec<String>, AstCacheStats, Vec<String>) {
+    let notes = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&notes);
+    let mut ctx = Context::new_with_realtime(false);
+    ctx.set_ast_cache_dir(cache);
+    ctx.set_diagnostics_hook(move |note| sink.lock().unwrap().push(note.to_string()));
+    ctx.eval_module(main).expect("run should succeed");
+    let stats = ctx.ast_cache_stats().expect("cache is set");
+    let notes = notes.lock().unwrap().clone();
+    (ctx.output().to_vec(), stats, notes)
+}
+
+#[test]
+fn second_load_of_a_large_module_skips_the_parser() {
+    let dir = fresh_dir("large");
+    let mut module: String = (0..5000)
+        .map(|i| format!("function f{i}(x) {{ return x + {i}; }}\n"))
+        .collect();
+    module.push_str("export function total() { return f0(1) + f4999(1); }\n");
+    fs::write(dir.join("big.js"), module).unwrap();
+    let main = dir.join("main.js");
+    fs::write(
+        &main,
+        "import { total } from \"./big.js\";\nconsole.log(total());\n",
+    )
+    .unwrap();
+    let cache = dir.join("cache");
+
+    let (first, stats, _) = run(&main, &cache);
+    assert_eq!(first, vec!["5001"]);
+    assert_eq!(stats, AstCacheStats { hits: 0, misses: 1 });
+
+    let (second, stats, notes) = run(&main, &cache);
+    assert_eq!(second, first);
+    assert_eq!(stats, AstCacheStats { hits: 1, misses: 0 });
+    assert!(notes.is_empty(), "{notes:?}");
+}
+
+#[test]
+fn edited_and_corrupt_entries_fall_back_to_parsing() {
+    let dir = fresh_dir("stale");
+    let module = dir.join("m.js");
+    let main = dir.join("main.js");
+    fs::write(&module, "export const answer = 41;\n").unwrap();
+    fs::write(
+        &main,
+        "import { answer } from \"./m.js\";\nconsole.log(answer);\n",
+    )
+    .unwrap();
+    let cache = dir.join("cache");
+    run(&main, &cache);
+
+    fs::write(&module, "export const answer = 42;\n").unwrap();
+    let (out, stats, notes) = run(&main, &cache);
+    assert_eq!(out, vec!["42"]);
+    assert_eq!(stats, AstCacheStats { hits: 0, misses: 1 });
+    assert!(notes.is_empty(), "{notes:?}");
+
+    for entry in fs::read_dir(&cache).unwrap() {
+        fs::write(entry.unwrap().path(), b"CJSAST\0\x01 not bincode").unwrap();
+    }
+    let (out, stats, notes) = run(&main, &cache);
+    assert_eq!(out, vec!["42"]);
+    assert_eq!(stats, AstCacheStats { hits: 0, misses: 1 });
+    assert!(notes[0].contains("corrupt entry"), "{notes:?}");
+
+    let (out, stats, _) = run(&main, &cache);
+    assert_eq!(out, vec!["42"]);
+    assert_eq!(stats, AstCacheStats { hits: 1, misses: 0 });
+}
@@ -0,0 +1,131 @@
+use crustyjs::lexer::lex;
+use crustyjs::parser::parse;
+use crustyjs::runtime::interpreter::Interpreter;
+use crustyjs::vm::compiler::Compiler;
+use crustyjs::vm::machine::VM;
+
+/// `(a, op, b, expected)`, with the expected results taken from V8.
+const VECTORS: &[(&str, &str, &str, &str)] = &[
+    ("2147483648", "|", "0", "-2147483648"),
+    ("4294967295", "|", "0", "-1"),
+    ("4294967296", "|", "0", "0"),
+    ("-2147483649", "|", "0", "2147483647"),
+    ("1e21", "|", "0", "-559939584"),
+    ("1.9", "|", "0", "1"),
+    ("-1.9", "|", "0", "-1"),
+    ("0/0", "|", "0", "0"),
+    ("1/0", "|", "0", "0"),
+    ("-1/0", "&", "-1", "0"),
+    ("0xff", "&", "0x0f", "15"),
+    ("0xf0", "^", "0xff", "15"),
+    ("5", "^", "-1", "-6"),
+    ("6", "&", "3", "2"),
+    ("6", "|", "3", "7"),
+    ("1", "<<", "31", "-2147483648"),
+    ("1", "<<", "32", "1"),
+    ("1", "<<", "33", "2"),
+    ("1", "<<", "-1", "-2147483648"),
+    ("3", "<<", "4294967297", "6"),
+    ("-1", ">>", "31", "-1"),
+    ("-16", ">>", "2", "-4"),
+    ("-2147483648", ">>", "1", "-1073741824"),
+    ("2147483648", ">>", "0", "-2147483648"),
+    ("-1", ">>>", "0", "4294967295"),
+    ("-1", ">>>", "31", "1"),
+    ("-16", ">>>", "28", "15"),
+    ("-2147483648", ">>>", "1", "1073741824"),
+    ("2147483648", ">>>", "0", "2147483648"),
+];
+
+fn vector_source() -> String {
+    VECTORS
+        .iter()
+        .map(|(a, op, b, _)| format!("console.log(({a}) {op} ({b}));\n"))
+        .collect()
+}
+
+fn expected_output() -> Vec<String> {
+    VECTORS
+        .iter()
+        .map(|(_, _, _, expected)| expected.to_string())
+        .collect()
+}
+
+fn run_interpreter(source: &str) -> Vec<String> {
+    let program = parse(lex(source).expect("lex failed")).expect("parse failed");
+    let mut interp = Interpreter::new();
+    interp
+        .run(&program)
+        .expect("interpreter run should succeed");
+    interp.output().to_vec()
+}
+
+fn run_vm(source: &str) -> Vec<String> {
+    let program = parse(lex(source).expect("lex failed")).expect("parse failed");
+    let mut compiler = Compiler::new();
+    let chunk = compiler.compile(program).expect("compile failed");
+    assert!(
+        !compiler.requires_tree_walk,
+        "should compile without fallback"
+    );
+    let mut vm = VM::new();
+    vm.set_output_mode(crustyjs::OutputMode::Capture);
+    vm.run(chunk, None, None).expect("vm run should succeed");
+    vm.output().to_vec()
+}
+
+#[test]
+fn interpreter_matches_bitwise_vectors() {
+    assert_eq!(run_interpreter(&vector_source()), expected_output());
+}
+
+#[test]
+fn vm_matches_bitwise_vectors() {
+    assert_eq!(run_vm(&vector_source()), expected_output());
+}
+
+#[test]
+fn bitwise_not_and_compound_assignment() {
+    let source = r#"
+        let x = 0xf0;
+        x &= 0x3c; console.log(x);
+        x |= 1; console.log(x);
+        x ^= 0xff; console.log(x);
+        x <<= 28; console.log(x);
+        x >>= 30; console.log(x);
+        x >>>= 1; console.log(x);
+        console.log(~0);
+        console.log(~-1);
+        console.log(~2147483648);
+        console.log(~(0/0));
+    "#;
+    let expected = [
+        "48",
+        "49",
+        "206",
+        "-536870912",
+        "-1",
+        "2147483647",
+        "-1",
+        "0",
+        "2147483647",
+        "-1",
+    ];
+    assert_eq!(run_interpreter(source), expected);
+    assert_eq!(run_vm(source), expected);
+}
+
+#[test]
+fn bitwise_operators_follow_js_precedence() {
+    let source = r#"
+        console.log(1 | 2 ^ 3 & 4);
+        console.log(1 + 2 << 3);
+        console.log(1 << 2 < 5);
+        console.log(6 & 3 == 3);
+        console.log(~1 + 1);
+        console.log(-8 >> 1 >>> 28);
+    "#;
+    let expected = ["3", "24", "true", "0", "-1", "15"];
+    assert_eq!(run_interpreter(source), expected);
+    assert_eq!(run_vm(source), expected);
+}
@@ -52,7 +52,7 @@ fn json_stringify_and_parse() {
         "#,
     );
 
-    assert_eq!(output[0], "{\"a\":1.0,\"b\":[2.0,3.0]}");
+    assert_eq!(output[0], "{\"a\":1,\"b\":[2,3]}");
     assert_eq!(output[1], "1");
     assert_eq!(output[2], "3");
 }
@@ -244,3 +244,217 @@ fn console_alias_methods_and_performance_now() {
     assert_eq!(output[3], "d");
     assert_eq!(output[4], "true");
 }
+
+#[test]
+fn structured_clone_preserves_cycles() {
+    let output = run_and_capture(
+        r#"
+        const node = { name: "a", children: [1, 2] };
+        node.self = node;
+        node.children.push({ parent: node });
+        const copy = structuredClone(node);
+        console.log(copy !== node, copy.self === copy, copy.name);
+        console.log(copy.children.length, copy.children[2].parent === copy);
+        "#,
+    );
+
+    assert_eq!(output, vec!["true true a", "3 true"]);
+}
+
+#[test]
+fn structured_clone_keeps_shared_substructure_identity() {
+    let output = run_and_capture(
+        r#"
+        const shared = { n: 1 };
+        const source = {
+            left: shared,
+            right: shared,
+            map: new Map([[shared, "v"]]),
+            set: new Set([shared]),
+            re: /a+b/gi,
+            when: new Date(86400000),
+        };
+        const copy = structuredClone(source);
+        console.log(copy.left === copy.right, copy.left !== shared, copy.left.n);
+        console.log(copy.map.get(copy.left), copy.set.has(copy.right), copy.set.has(shared));
+        console.log(copy.re !== source.re, copy.re.source, copy.re.flags);
+        console.log(copy.when !== source.when, +copy.when);
+        "#,
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "true true 1",
+            "v true false",
+            "true a+b gi",
+            "true 86400000"
+        ]
+    );
+}
+
+#[test]
+fn structured_clone_rejects_uncloneable_values() {
+    let output = run_and_capture(
+        r#"
+        for (const value of [() => 1, Symbol("s"), Promise.resolve(1), { nested: [function f() {}] }]) {
+            try {
+                structuredClone(value);
+                console.log("cloned");
+            } catch (e) {
+                console.log(e.name, e.message.includes("DataCloneError"));
+            }
+        }
+        "#,
+    );
+
+    assert_eq!(output, vec!["TypeError true"; 4]);
+}
+
+#[test]
+fn parse_int_and_parse_float_edge_cases() {
+    let cases = [
+        ("parseFloat('3.14abc')", "3.14"),
+        ("parseFloat('  -.5e-3x')", "-0.0005"),
+        ("parseFloat('1e')", "1"),
+        ("parseFloat('1e+')", "1"),
+        ("parseFloat('2E+2.5')", "200"),
+        ("parseFloat('.')", "NaN"),
+        ("parseFloat('-Infinityx') === -Infinity", "true"),
+        ("parseFloat('infinity')", "NaN"),
+        ("parseFloat('0x10')", "0"),
+        ("1 / parseFloat('-0') < 0", "true"),
+        ("parseInt('')", "NaN"),
+        ("parseInt('0x')", "NaN"),
+        ("parseInt(' 12 34')", "12"),
+        ("parseInt('-0x1A')", "-26"),
+        ("1 / parseInt('-0') < 0", "true"),
+        ("parseInt('0x1F', 16)", "31"),
+        ("parseInt('0x1F', 10)", "0"),
+        ("parseInt('0b11')", "0"),
+        ("parseInt('11', 2)", "3"),
+        ("parseInt('11', 1)", "NaN"),
+        ("parseInt('11', 37)", "NaN"),
+        ("parseInt('z', 36)", "35"),
+        ("parseInt('12', 4294967306)", "12"),
+        ("parseInt('-Infinity')", "NaN"),
+        ("Number.parseInt('08')", "8"),
+        ("Number.parseFloat('2.5px')", "2.5"),
+        ("Number.parseInt === parseInt", "true"),
+        ("Number.parseFloat === parseFloat", "true"),
+        ("Object.is(Number.parseInt, globalThis.parseInt)", "true"),
+        ("Number('12px')", "NaN"),
+    ];
+    for (expr, expected) in cases {
+        let output = run_and_capture(&format!("console.log({expr});"));
+        assert_eq!(output, vec![expected], "{expr}");
+    }
+}
+
+#[test]
+fn global_object_reads_builtins_and_var_bindings() {
+    let output = run_and_capture(
+        r#"
+        var v = 1;
+        function f() { return 2; }
+        let l = 3;
+        const c = 4;
+        console.log(typeof globalThis.setTimeout, globalThis.Math === Math);
+        console.log(globalThis.globalThis === globalThis, this === globalThis);
+        console.log(globalThis.v, globalThis.f(), globalThis.l, globalThis.c);
+        console.log("v" in globalThis, "l" in globalThis, globalThis.hasOwnProperty("f"));
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "function true",
+            "true true",
+            "1 2 undefined undefined",
+            "true false true",
+        ]
+    );
+}
+
+#[test]
+fn global_object_writes_define_and_update_globals() {
+    let output = run_and_capture(
+        r#"
+        globalThis.created = 1;
+        console.log(created);
+        created = 2;
+        console.log(globalThis.created);
+        var v = 3;
+        globalThis.v = 4;
+        console.log(v);
+        let l = 5;
+        globalThis.l = 6;
+        console.log(l, globalThis.l);
+        (function () {
+          "use strict";
+          try {
+            undeclared = 7;
+          } catch (e) {
+            console.log(e.name, "undeclared" in globalThis);
+          }
+        })();
+        "#,
+    );
+    assert_eq!(output, vec!["1", "2", "4", "5 6", "ReferenceError false"]);
+}
+
+#[test]
+fn global_object_exposes_every_realm_builtin() {
+    let output = run_and_capture(
+        r#"
+        console.log(typeof globalThis.Promise, typeof globalThis.JSON, typeof globalThis.console);
+        console.log(globalThis.Promise === Promise, globalThis.JSON.stringify([1]));
+        globalThis.console.log("logged");
+        const p = new globalThis.Promise((resolve) => resolve(1));
+        console.log(p instanceof Promise, {} instanceof Promise);
+        globalThis.Promise.resolve(2).then((v) => console.log("resolved", v));
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "function object object",
+            "true [1]",
+            "logged",
+            "true false",
+            "resolved 2",
+        ]
+    );
+}
+
+#[test]
+fn global_object_deletes_only_configurable_bindings() {
+    let output = run_and_capture(
+        r#"
+        globalThis.foo = 1;
+        console.log(delete globalThis.foo, "foo" in globalThis, typeof foo);
+        var v = 2;
+        console.log(delete globalThis.v, v);
+        console.log(delete globalThis.NaN, typeof NaN);
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec!["true false undefined", "false 2", "false number"]
+    );
+}
+
+#[test]
+fn global_object_keys_list_only_enumerable_globals() {
+    let output = run_and_capture(
+        r#"
+        var v = 1;
+        function f() {}
+        let l = 2;
+        globalThis.created = 3;
+        console.log(JSON.stringify(Object.keys(globalThis)));
+        console.log(Object.getOwnPropertyNames(globalThis).includes("Math"));
+        "#,
+    );
+    assert_eq!(output, vec![r#"["v","f","created"]"#, "true"]);
+}
@@ -87,6 +87,84 @@ fn instanceof_checks_prototype_chain() {
     assert_eq!(out, vec!["true", "true", "false"]);
 }
 
+#[test]
+fn instanceof_accepts_function_constructors_and_expressions() {
+    let src = r#"
+        function Point(x) {
+          this.x = x;
+        }
+        const p = new Point(1);
+        const ctors = { Point };
+        class A {}
+        class B extends A {}
+        class C extends B {}
+        const c = new C();
+        console.log(p instanceof Point, p instanceof ctors.Point, {} instanceof Point);
+        console.log(c instanceof A, c instanceof B, new A() instanceof C, c instanceof Object);
+        console.log(5 instanceof Point, new Map() instanceof Map, new Error("e") instanceof Error);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec!["true true false", "true true false true", "false true true"]
+    );
+}
+
+#[test]
+fn instanceof_defers_to_static_has_instance() {
+    let src = r#"
+        class Duck {
+          static [Symbol.hasInstance](value) {
+            return typeof value.quack === "function";
+          }
+        }
+        console.log({ quack() {} } instanceof Duck, {} instanceof Duck);
+        class Shape {
+          static create() { return new Shape(); }
+        }
+        console.log(Shape.create() instanceof Shape);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["true false", "true"]);
+}
+
+#[test]
+fn instanceof_works_with_every_builtin_constructor() {
+    let src = r#"
+        const cases = [
+          [new Date(0), Date], [new Number(1), Number], [new Boolean(false), Boolean],
+          [new String("s"), String], [Object(Symbol("s")), Symbol], [new Error("e"), Error],
+          [new TypeError("t"), TypeError], [/a/, RegExp], [new Map(), Map], [new Set(), Set],
+          [new WeakMap(), WeakMap], [new WeakSet(), WeakSet], [new ArrayBuffer(1), ArrayBuffer],
+          [new Uint8Array(1), Uint8Array], [[], Array], [() => 1, Function], [{}, Object],
+        ];
+        const matched = cases.filter(([value, ctor]) => value instanceof ctor).length;
+        console.log(matched === cases.length);
+        const misses = cases.filter(([value, ctor]) => ({}) instanceof ctor && ctor !== Object);
+        console.log(misses.length);
+        console.log(1 instanceof Number, true instanceof Boolean, Symbol("p") instanceof Symbol);
+        console.log(new Date(0).constructor === Date, typeof Number.prototype);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["true", "0", "false false false", "true object"]);
+}
+
+#[test]
+fn instanceof_rejects_non_callable_right_operand() {
+    let src = r#"
+        try {
+          ({}) instanceof {};
+        } catch (e) {
+          console.log(e instanceof TypeError, e.message);
+        }
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec!["true Right-hand side of 'instanceof' is not callable"]
+    );
+}
+
 #[test]
 fn class_getter_and_setter_accessors_work() {
     let src = r#"
@@ -109,3 +187,268 @@ fn class_getter_and_setter_accessors_work() {
     let out = run_and_capture(src);
     assert_eq!(out, vec!["0", "5"]);
 }
+
+#[test]
+fn class_members_accept_computed_string_and_numeric_keys() {
+    let src = r#"
+        const order = [];
+        function key(name) { order.push(name); return name; }
+        class Config {
+          constructor() { this._max = 10; }
+          get ["max" + "Size"]() { return this._max; }
+          set [key("maxSize")](v) { this._max = v; }
+          "quoted-name"() { return "quoted"; }
+          42() { return "answer"; }
+          [key("late")]() { return "late"; }
+        }
+        const c = new Config();
+        c.maxSize = 20;
+        console.log(c.maxSize);
+        console.log(c["quoted-name"]());
+        console.log(c[42]());
+        console.log(c.late());
+        console.log(order.join(","));
+        try {
+          class Broken { [(() => { throw new Error("bad key"); })()]() {} }
+        } catch (e) {
+          console.log(e.message);
+        }
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec!["20", "quoted", "answer", "late", "maxSize,late", "bad key"]
+    );
+}
+
+#[test]
+fn symbol_iterator_method_makes_instances_iterable() {
+    let src = r#"
+        class Range {
+          constructor(start, end) { this.start = start; this.end = end; }
+          [Symbol.iterator]() {
+            let current = this.start;
+            const end = this.end;
+            return {
+              next: () => current < end
+                ? { value: current++, done: false }
+                : { value: undefined, done: true },
+            };
+          }
+        }
+        console.log([...new Range(1, 4)].join(","));
+        console.log(Object.keys(Range.prototype).length);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["1,2,3", "0"]);
+}
+
+#[test]
+fn same_private_name_in_two_classes_does_not_collide() {
+    let src = r#"
+        class A {
+          #value = "a";
+          constructor() {}
+          readA() { return this.#value; }
+          static peek(o) { return o.#value; }
+        }
+        class B extends A {
+          #value = "b";
+          constructor() { super(); }
+          readB() { return this.#value; }
+        }
+        const b = new B();
+        console.log(b.readA(), b.readB());
+        try {
+          A.peek({ value: "plain" });
+        } catch (e) {
+          console.log(e instanceof TypeError, e.message);
+        }
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec![
+            "a b",
+            "true Cannot read private member #value from an object whose class did not declare it"
+        ]
+    );
+}
+
+#[test]
+fn private_members_are_invisible_to_reflection_and_json() {
+    let src = r##"
+        class Wallet {
+          #balance = 0;
+          owner = "ann";
+          deposit(n) { this.#balance += n; return this.#total; }
+          get #total() { return this.#balance; }
+          #audit() { return "ok"; }
+          audit() { return this.#audit(); }
+          static #count = 0;
+          static next() { Wallet.#count += 1; return Wallet.#count; }
+        }
+        const w = new Wallet();
+        console.log(w.deposit(5), w.deposit(7), w.audit());
+        console.log(JSON.stringify(w), Object.keys(w).join(","), w["#balance"]);
+        console.log(Wallet.next(), Wallet.next(), Object.keys(Wallet).includes("#count"));
+    "##;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec!["5 12 ok", r#"{"owner":"ann"} owner undefined"#, "1 2 false"]
+    );
+}
+
+#[test]
+fn private_in_checks_the_brand_before_use() {
+    let src = r#"
+        class Point {
+          #x;
+          constructor(x) { this.#x = x; }
+          static sum(a, b) {
+            if (!(#x in a) || !(#x in b)) return "not points";
+            return a.#x + b.#x;
+          }
+          static check(value) {
+            try { return #x in value; } catch (e) { return e instanceof TypeError; }
+          }
+        }
+        console.log(Point.sum(new Point(1), new Point(2)));
+        console.log(Point.sum(new Point(1), { x: 2 }));
+        console.log(Point.check(1));
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["3", "not points", "true"]);
+}
+
+#[test]
+fn undeclared_private_name_is_an_early_syntax_error() {
+    for src in [
+        "class A { m() { return this.#missing; } }",
+        "function read(o) { return o.#x; }",
+        "class A { #x; m() { return function () { return this.#y; }; } }",
+        "class A { #x; #x; }",
+        "class A { #x; m() { delete this.#x; } }",
+    ] {
+        let tokens = lex(src).expect("lex failed");
+        assert!(parse(tokens).is_err(), "expected a syntax error for {src}");
+    }
+}
+
+#[test]
+fn class_to_string_and_value_of_drive_conversions() {
+    let src = r#"
+        class Point {
+          constructor(x) { this.x = x; }
+          toString() { return "P" + this.x; }
+          valueOf() { return this.x; }
+        }
+        const p = new Point(3);
+        const keyed = {};
+        keyed[new Point(5)] = "five";
+        console.log(`at ${p}`);
+        console.log(Object.keys(keyed)[0], keyed.P5);
+        console.log(p == 3, p === 3, String(p));
+        console.log([p, null, new Point(4)].join("-"));
+        console.log(p + 1, -p);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec!["at P3", "P5 five", "true false P3", "P3--P4", "4 -3"]
+    );
+}
+
+#[test]
+fn exceptions_thrown_by_to_string_propagate() {
+    let src = r#"
+        class Broken { toString() { throw new Error("no string"); } }
+        for (const convert of [
+          (b) => `${b}`,
+          (b) => [b].join(),
+          (b) => String(b),
+          (b) => ({})[b],
+        ]) {
+          try { convert(new Broken()); console.log("converted"); }
+          catch (e) { console.log(e.message); }
+        }
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["no string"; 4]);
+}
+
+#[test]
+fn class_methods_are_not_enumerable() {
+    let out = run_and_capture(
+        r#"
+        class Foo {
+          constructor(x) { this.x = x; }
+          greet() { return "hi"; }
+          get double() { return this.x * 2; }
+          static make() { return new Foo(1); }
+          static count = 0;
+        }
+        class Bar extends Foo { bark() {} }
+        const keys = [];
+        for (const k in new Bar(2)) keys.push(k);
+        console.log(keys.join(), Object.keys(Foo.prototype).length, Object.keys(Foo).join());
+        console.log(Object.getOwnPropertyDescriptor(Foo.prototype, "greet").enumerable);
+        console.log(JSON.stringify(new Bar(3)));
+        "#,
+    );
+    assert_eq!(out, vec!["x 0 count", "false", r#"{"x":3}"#]);
+}
+
+#[test]
+fn class_prototypes_point_back_at_their_constructor() {
+    let out = run_and_capture(
+        r#"
+        class Foo {}
+        class Bar extends Foo {}
+        const bar = new Bar();
+        console.log(bar.constructor === Bar, bar.constructor.name, new Foo().constructor.name);
+        console.log(Foo.prototype.constructor === Foo, Bar.prototype.constructor === Bar);
+        console.log(Object.getPrototypeOf(Bar.prototype) === Foo.prototype);
+        const d = Object.getOwnPropertyDescriptor(Foo.prototype, "constructor");
+        console.log(d.enumerable, d.writable, d.configurable);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec!["true Bar Foo", "true true", "true", "false true true"]
+    );
+}
+
+#[test]
+fn new_constructs_member_paths_and_expression_callees() {
+    let out = run_and_capture(
+        r#"
+        class Foo {
+          constructor(x) { this.x = x; }
+          describe() { return "foo " + this.x; }
+        }
+        function Point(x) { this.x = x; }
+        function Outer() { return function Inner() { this.v = "inner"; }; }
+        const lib = { shapes: { Foo, Point }, errors: [TypeError], Map };
+        const getClass = () => Foo;
+        console.log(new Foo(1).describe(), new Foo(2)["describe"]());
+        console.log(new lib.shapes.Foo(3).x, new lib["shapes"].Point(4).x);
+        console.log(new (getClass())(5).describe(), new lib.shapes.Foo(6) instanceof Foo);
+        console.log(new new Outer()().v, new lib.errors[0]("bad").message);
+        console.log(new lib.Map([[1, "one"]]).get(1), new (new Proxy(Point, {}))(7).x);
+        try { new lib.shapes.Missing(); } catch (e) { console.log(e.name, e.message); }
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "foo 1 foo 2",
+            "3 4",
+            "foo 5 true",
+            "inner bad",
+            "one 7",
+            "TypeError 'undefined' is not a constructor",
+        ]
+    );
+}
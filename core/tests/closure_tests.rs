@@ -1,5 +1,5 @@
 use crustyjs::lexer::lex;
-use crustyjs::parser::parse;
+use crustyjs::parser::{parse, parse_with_source};
 use crustyjs::runtime::interpreter::Interpreter;
 
 fn run_and_capture(source: &str) -> Vec<String> {
@@ -121,3 +121,145 @@ fn higher_order_compose() {
     let out = run_and_capture(src);
     assert_eq!(out, vec!["11"]);
 }
+
+#[test]
+fn anonymous_functions_take_the_name_they_are_bound_to() {
+    let src = r#"
+        const arrow = () => {};
+        let expr = function () {};
+        let later;
+        later = (x) => x;
+        const named = function inner() {};
+        const obj = { key: () => {}, method() {} };
+        class Point { move() {} }
+        console.log([arrow.name, expr.name, later.name, named.name].join(","));
+        console.log([obj.key.name, obj.method.name, Point.name, new Point().move.name].join(","));
+        console.log(JSON.stringify([(() => {}).name]));
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec!["arrow,expr,later,inner", "key,method,Point,move", "[\"\"]"]
+    );
+}
+
+#[test]
+fn length_counts_params_before_the_first_default_or_rest() {
+    let src = r#"
+        function f(a, b = 1, c) {}
+        const g = (a, b, ...rest) => {};
+        const h = ([a], { b }) => {};
+        f.length = 10;
+        console.log([f.length, g.length, h.length].join(","));
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["1,2,2"]);
+}
+
+#[test]
+fn to_string_returns_source_text_that_parses_again() {
+    let src = r#"
+        function add(a, b) {
+            return a + b; // sum
+        }
+        console.log(add.toString());
+        console.log(String((x) => x * 2));
+        console.log(Math.max.toString());
+    "#;
+    let program = parse_with_source(lex(src).expect("lex failed"), src).expect("parse failed");
+    let mut interp = Interpreter::new();
+    interp.run(&program).expect("runtime error");
+    let out = interp.output().to_vec();
+    assert_eq!(
+        out,
+        vec![
+            "function add(a, b) {\n            return a + b; // sum\n        }",
+            "(x) => x * 2",
+            "function max() { [native code] }",
+        ]
+    );
+
+    let round_trip = format!("console.log(({})(2, 3));", out[0]);
+    assert_eq!(run_and_capture(&round_trip), vec!["5"]);
+}
+
+#[test]
+fn locals_resolve_to_the_nearest_binding() {
+    let src = r#"
+        function run(a, b = a + 1) {
+            const fns = [];
+            for (const i of [0, 1, 2]) {
+                let j = i * b;
+                fns.push(() => i + j);
+            }
+            let x = "outer";
+            {
+                let x = "inner";
+                fns.push(() => x);
+            }
+            if (a) { var v = a; }
+            { let w = v; v = w + 1; }
+            return fns.map(f => f()).join(",") + " " + x + " " + v;
+        }
+        console.log(run(1));
+        console.log(run(0));
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["0,3,6,inner outer 2", "0,2,4,inner outer NaN"]);
+}
+
+#[test]
+fn caught_errors_leave_no_block_scopes_behind() {
+    let src = r#"
+        function f() {
+            let x = "function";
+            try {
+                let x = "block";
+                { let y = x; throw new Error("boom"); }
+            } catch (e) {
+                return x + " " + e.message;
+            }
+        }
+        function* g() {
+            let a = "a";
+            try {
+                let b = "b";
+                yield a + b;
+                { let c = "c"; throw new Error(c); }
+            } catch (e) {
+                yield a + e.message;
+            }
+            yield a;
+        }
+        console.log(f());
+        const seen = [];
+        for (const value of g()) seen.push(value);
+        console.log(seen.join(","));
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["function boom", "ab,ac,a"]);
+}
+
+#[test]
+fn named_function_expressions_see_their_own_name() {
+    let src = r#"
+        const factorial = function fact(n) { return n <= 1 ? 1 : n * fact(n - 1); };
+        const countdown = function* down(n) { if (n > 0) { yield n; yield* down(n - 1); } };
+        const sum = async function total(n) { return n <= 0 ? 0 : n + await total(n - 1); };
+        console.log(factorial(5), typeof fact);
+        console.log([...countdown(3)].join(","));
+        sum(3).then((value) => console.log("sum " + value));
+        const sloppy = function keep() { keep = 1; return typeof keep; };
+        const strict = function locked() {
+            "use strict";
+            try { locked = 1; } catch (e) { return e instanceof TypeError; }
+        };
+        const shadowed = function name() { var name = "inner"; return name; };
+        console.log(sloppy(), strict(), shadowed());
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec!["120 undefined", "3,2,1", "function true inner", "sum 6"]
+    );
+}
@@ -267,3 +267,193 @@ fn map_typeof_is_object() {
     "#);
     assert_eq!(out, vec!["object", "object"]);
 }
+
+#[test]
+fn map_for_each_skips_deleted_and_visits_added_entries() {
+    let out = run(r#"
+        const m = new Map([["a", 1], ["b", 2], ["c", 3], ["d", 4]]);
+        const seen = [];
+        m.forEach((value, key) => {
+            seen.push(key + value);
+            if (key === "a") {
+                m.delete("a");
+                m.delete("c");
+                m.set("e", 5);
+            }
+            if (key === "b") {
+                m.set("a", 6);
+            }
+        });
+        console.log(seen.join(","));
+        console.log(m.size);
+    "#);
+    assert_eq!(out, vec!["a1,b2,d4,e5,a6", "3"]);
+}
+
+#[test]
+fn set_for_each_sees_live_mutation() {
+    let out = run(r#"
+        const s = new Set([1, 2, 3]);
+        const seen = [];
+        s.forEach((value) => {
+            seen.push(value);
+            s.delete(value);
+            if (value === 1) {
+                s.delete(3);
+                s.add(4);
+            }
+        });
+        console.log(seen.join(","));
+        console.log(s.size);
+    "#);
+    assert_eq!(out, vec!["1,2,4", "0"]);
+}
+
+#[test]
+fn map_iterator_reflects_mutation_between_next_calls() {
+    let out = run(r#"
+        const m = new Map([[1, "a"], [2, "b"], [3, "c"]]);
+        const it = m.entries();
+        const seen = [];
+        let step = it.next();
+        while (!step.done) {
+            const [key, value] = step.value;
+            seen.push(key + value);
+            if (key === 1) {
+                m.delete(1);
+                m.delete(2);
+                m.set(4, "d");
+            }
+            step = it.next();
+        }
+        console.log(seen.join(","));
+        m.set(5, "e");
+        console.log(it.next().done);
+    "#);
+    assert_eq!(out, vec!["1a,3c,4d", "true"]);
+}
+
+#[test]
+fn set_iterator_continues_after_clear() {
+    let out = run(r#"
+        const s = new Set(["x", "y"]);
+        const it = s.values();
+        console.log(it.next().value);
+        s.clear();
+        s.add("z");
+        console.log(it.next().value);
+        console.log(it.next().done);
+    "#);
+    assert_eq!(out, vec!["x", "z", "true"]);
+}
+
+#[test]
+fn for_of_sees_map_and_set_changes_made_during_iteration() {
+    let out = run(r#"
+        const m = new Map([["a", 1], ["b", 2]]);
+        const keys = [];
+        for (const [k] of m) {
+            keys.push(k);
+            if (k === "a") {
+                m.delete("b");
+                m.set("c", 3);
+            }
+        }
+        console.log(keys.join(","));
+
+        const s = new Set([1, 2, 3]);
+        const values = [];
+        for (const v of s) {
+            values.push(v);
+            if (v === 1) {
+                s.delete(2);
+                s.add(4);
+            }
+        }
+        console.log(values.join(","));
+    "#);
+    assert_eq!(out, vec!["a,c", "1,3,4"]);
+}
+
+#[test]
+fn collection_constructors_require_new_and_copy_iterables() {
+    let out = run(r#"
+        const m = new Map([[1, "a"], [2, "b"]]);
+        const copy = new Map(m);
+        console.log(copy.size, copy.get(2), copy === m);
+        console.log(new Set("abca").size, new Set(new Set([1, 2])).size);
+        for (const ctor of [Map, Set, WeakMap, WeakSet]) {
+            try { ctor(); } catch (e) { console.log(e instanceof TypeError, e.message); }
+        }
+        try { new Map([1, 2]); } catch (e) { console.log(e.message); }
+        const M = Map;
+        console.log(typeof Map, m instanceof Map, m instanceof Set, new M([[3, 4]]).get(3));
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "2 b false",
+            "3 2",
+            "true Constructor Map requires 'new'",
+            "true Constructor Set requires 'new'",
+            "true Constructor WeakMap requires 'new'",
+            "true Constructor WeakSet requires 'new'",
+            "Iterator value 1 is not an entry object",
+            "function true false 4",
+        ]
+    );
+}
+
+#[test]
+fn set_algebra_methods() {
+    let out = run(r#"
+        const a = new Set([1, 2, 3, 4]);
+        const b = new Set([3, 4, 5]);
+        console.log([...a.union(b)], [...a.intersection(b)]);
+        console.log([...a.difference(b)], [...a.symmetricDifference(b)]);
+        console.log(new Set([3]).isSubsetOf(a), a.isSubsetOf(b));
+        console.log(a.isSupersetOf(new Set([1, 2])), a.isSupersetOf(b));
+        console.log(a.isDisjointFrom(new Set([9])), a.isDisjointFrom(b));
+        console.log([...new Set([NaN, 0]).intersection(new Set([NaN, -0]))]);
+        console.log([...a.union(new Map([[7, "x"]]))], a.size);
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "[1, 2, 3, 4, 5] [3, 4]",
+            "[1, 2] [1, 2, 5]",
+            "true false",
+            "true false",
+            "true false",
+            "[NaN, 0]",
+            "[1, 2, 3, 4, 7] 4",
+        ]
+    );
+}
+
+#[test]
+fn set_methods_accept_set_like_objects() {
+    let out = run(r#"
+        const a = new Set([1, 2, 3]);
+        const like = {
+            size: 2,
+            has(v) { return v === 1 || v === 9; },
+            *keys() { yield 1; yield 9; },
+        };
+        console.log([...a.intersection(like)], [...a.union(like)], a.isDisjointFrom(like));
+        try { a.union({ size: NaN, has() {}, keys() {} }); } catch (e) { console.log(e.message); }
+        try { a.union({ size: -1, has() {}, keys() {} }); } catch (e) { console.log(e.name); }
+        try { a.difference({ size: 1, has: 1, keys() {} }); } catch (e) { console.log(e.message); }
+        try { a.union([1]); } catch (e) { console.log(e.message); }
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "[1] [1, 2, 3, 9] false",
+            "set-like size is not a number",
+            "RangeError",
+            "set-like has is not a function",
+            "[1] is not a set-like object",
+        ]
+    );
+}
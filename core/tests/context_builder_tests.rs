@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crustyjs::{Context, Value};
+
+#[test]
+fn strict_flag_applies_to_every_evaluation() {
+    let mut sloppy = Context::new();
+    sloppy
+        .eval("var x = 1; delete x; var static = 2;")
+        .expect("sloppy code should run");
+    let err = sloppy
+        .eval("'use strict'; var y = 1; delete y;")
+        .expect_err("a directive should still opt in");
+    assert!(err.to_string().contains("strict mode"), "{err}");
+
+    let mut strict = Context::builder().strict(true).build();
+    let err = strict.eval("var x = 1; delete x;").unwrap_err();
+    assert!(err.to_string().contains("strict mode"), "{err}");
+    assert!(strict.eval_value("var static = 2;").is_err());
+    assert!(strict.eval_value("010").is_err());
+}
+
+#[test]
+fn realtime_timers_and_step_limit() {
+    let mut ctx = Context::builder().realtime_timers(false).build();
+    let started = Instant::now();
+    ctx.eval("let fired = false; setTimeout(() => { fired = true; }, 60000);")
+        .unwrap();
+    assert_eq!(ctx.get_global("fired").unwrap(), Value::Boolean(true));
+    assert!(started.elapsed().as_secs() < 30);
+
+    let mut limited = Context::builder().max_steps(500).build();
+    let err = limited.eval("while (true) {}").unwrap_err();
+    assert!(err.to_string().contains("step limit"), "{err}");
+    Context::new()
+        .eval("for (let i = 0; i < 1000; i++) {}")
+        .expect("no step limit by default");
+}
+
+#[test]
+fn max_call_depth_limits_recursion() {
+    let source = "function down(n) { return n === 0 ? 0 : down(n - 1); } down(8);";
+    Context::new().eval(source).expect("default depth allows 8");
+    let err = Context::builder()
+        .max_call_depth(4)
+        .build()
+        .eval(source)
+        .unwrap_err();
+    assert!(err.to_string().contains("Maximum call stack"), "{err}");
+}
+
+#[test]
+fn lazy_functions_defer_body_errors_to_the_call() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_lazy_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let main = dir.join("main.js");
+    fs::write(
+        &main,
+        "var loaded = true;\nfunction unused() {\n  return 1 +;\n}\ntry { unused(); } catch (e) { var message = e.message; }\n",
+    )
+    .unwrap();
+
+    let mut lazy = Context::new();
+    lazy.eval_module(&main)
+        .expect("the broken body is never parsed");
+    let message = lazy.get_global("message").unwrap().to_string();
+    assert!(message.ends_with("main.js:3:13)"), "{message}");
+
+    let mut eager = Context::builder().lazy_functions(false).build();
+    let err = eager.eval_module(&main).unwrap_err();
+    assert!(
+        err.to_string().contains("unexpected token Semicolon"),
+        "{err}"
+    );
+    assert!(eager.get_global("loaded").is_err());
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn expose_gc_defines_a_global() {
+    let mut ctx = unsafe { Context::builder().expose_gc(true) }.build();
+    let value = ctx
+        .eval_value("let junk = [{}, {}]; junk = null; gc(); typeof gc")
+        .unwrap();
+    assert_eq!(value, Value::String("function".into()));
+
+    let value = Context::new().eval_value("typeof gc").unwrap();
+    assert_eq!(value, Value::String("undefined".into()));
+}
+
+#[test]
+fn console_sink_receives_output() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&lines);
+    let mut ctx = Context::builder()
+        .console_sink(move |line| sink.lock().unwrap().push(line.to_string()))
+        .build();
+    ctx.eval("console.log('one'); console.log(1 + 1);").unwrap();
+    assert_eq!(*lines.lock().unwrap(), vec!["one", "2"]);
+    assert!(ctx.output().is_empty());
+
+    let mut captured = Context::new();
+    captured.eval("console.log('kept');").unwrap();
+    assert_eq!(captured.output(), ["kept"]);
+}
+
+#[test]
+fn module_resolver_maps_specifiers() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_builder_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let lib = dir.join("lib.js");
+    let main = dir.join("main.js");
+    fs::write(&lib, "export const answer = 42;").unwrap();
+    fs::write(
+        &main,
+        "import { answer } from 'virtual:lib'; var result = answer;",
+    )
+    .unwrap();
+
+    let target = lib.clone();
+    let mut ctx = Context::builder()
+        .module_resolver(move |specifier, _from| match specifier {
+            "virtual:lib" => target.clone(),
+            other => PathBuf::from(other),
+        })
+        .build();
+    ctx.eval_module(&main).unwrap();
+    assert_eq!(ctx.get_global("result").unwrap(), Value::Number(42.0));
+
+    assert!(Context::new().eval_module(&main).is_err());
+    fs::remove_dir_all(&dir).ok();
+}
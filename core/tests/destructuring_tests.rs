@@ -1,5 +1,5 @@
 use crustyjs::lexer::lex;
-use crustyjs::parser::ast::{Expr, Literal, Pattern, Stmt};
+use crustyjs::parser::ast::{Expr, Literal, Pattern, Stmt, VarDeclKind};
 use crustyjs::parser::parse;
 use crustyjs::runtime::interpreter::Interpreter;
 
@@ -216,3 +216,274 @@ fn eval_arrow_param_destructuring() {
 
     assert_eq!(output, vec!["Rex"]);
 }
+
+#[test]
+fn eval_catch_parameter_destructuring() {
+    let output = run_and_capture(
+        r#"
+        try {
+            throw { message: "boom", details: [1, 2] };
+        } catch ({ message, code = 0, details: [first, ...others] }) {
+            console.log(message, code, first, others.length);
+        }
+        try {
+            null.field;
+        } catch ({ name }) {
+            console.log(name);
+        }
+        "#,
+    );
+
+    assert_eq!(output, vec!["boom 0 1 1", "TypeError"]);
+}
+
+#[test]
+fn default_parameters_bind_left_to_right() {
+    let output = run_and_capture(
+        r#"
+        function box(a, read = () => a, b = read() + 1, ...rest) {
+            a = a * 10;
+            return [read(), b, rest.length].join(",");
+        }
+        console.log(box(2));
+        console.log(box(2, undefined, undefined, "x", "y"));
+
+        var b = "outer";
+        function forward(a = b, b = 1) {
+            return a;
+        }
+        try {
+            forward();
+        } catch (err) {
+            console.log(err instanceof ReferenceError, err.message);
+        }
+
+        function shadow(a, read = () => a) {
+            var a = "body";
+            return a + " " + read();
+        }
+        console.log(shadow("param"));
+        "#,
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "20,3,0",
+            "20,3,2",
+            "true Cannot access 'b' before initialization",
+            "body param",
+        ]
+    );
+}
+
+fn parse_error_offset(source: &str) -> (String, usize) {
+    let tokens = lex(source).expect("lexing should succeed");
+    let err = parse(tokens).expect_err("parsing should fail");
+    (err.message.clone(), err.span.offset())
+}
+
+#[test]
+fn parse_array_literal_before_assign_as_pattern() {
+    let stmts = parse_source("[a, obj.x, ...rest] = list;");
+    let Stmt::ExprStmt(Expr::DestructuringAssign { pattern, value }) = &stmts[0] else {
+        panic!("expected destructuring assignment, got {:?}", stmts[0]);
+    };
+    assert_eq!(**value, Expr::Identifier("list".into()));
+    let Pattern::ArrayPattern { elements } = pattern else {
+        panic!("expected array pattern, got {pattern:?}");
+    };
+    assert_eq!(elements[0], Some(Pattern::Identifier("a".into())));
+    assert!(matches!(&elements[1], Some(Pattern::Member(_))));
+    assert!(matches!(
+        &elements[2],
+        Some(Pattern::Rest(inner)) if **inner == Pattern::Identifier("rest".into())
+    ));
+
+    assert!(matches!(
+        &parse_source("[a][0] = 1;")[0],
+        Stmt::ExprStmt(Expr::MemberAssign { .. })
+    ));
+}
+
+#[test]
+fn parse_rejects_invalid_destructuring_targets_at_the_element() {
+    for (source, offset) in [
+        ("[a, 1] = list;", 4),
+        ("[a, f()] = list;", 4),
+        ("[...a + b] = list;", 4),
+        ("({ k: 'x' } = obj);", 6),
+        ("({ a, b: [c, d()] } = obj);", 13),
+    ] {
+        let (message, at) = parse_error_offset(source);
+        assert_eq!(
+            message, "invalid destructuring assignment target",
+            "{source}"
+        );
+        assert_eq!(at, offset, "{source}");
+    }
+    // At statement start `{` opens a block, so object patterns need parens.
+    let (message, _) = parse_error_offset("{ a, b } = obj;");
+    assert!(message.contains("Assign"), "{message}");
+}
+
+#[test]
+fn destructuring_assignment_updates_existing_bindings() {
+    let output = run_and_capture(
+        r#"
+        let a = 1, b = 2;
+        [a, b] = [b, a];
+        console.log(a, b);
+
+        let m, n, rest;
+        const result = [m, , n = 7, ...rest] = [1, 2, undefined, 4, 5];
+        console.log(m, n, rest, result.length);
+
+        let p, q;
+        ({ p, q = 9, r: a } = { p: 3, r: "r" });
+        console.log(p, q, a);
+
+        const frozen = 1;
+        try {
+            [frozen] = [2];
+        } catch (err) {
+            console.log(err instanceof TypeError, frozen);
+        }
+
+        let [d1 = 4, d2 = 5] = [undefined, 0];
+        console.log(d1, d2);
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec!["2 1", "1 7 [4, 5] 5", "3 9 r", "true 1", "4 0"]
+    );
+}
+
+#[test]
+fn destructuring_assignment_writes_member_targets() {
+    let output = run_and_capture(
+        r#"
+        const obj = { x: 0 };
+        const arr = [0];
+        [obj.x, arr[0]] = [5, 6];
+        console.log(obj.x, arr[0]);
+
+        const deep = {};
+        ({ a: deep.x, b: [deep.y, deep.z = 3], ...deep.rest } = { a: 1, b: [2], c: 4 });
+        console.log(deep.x, deep.y, deep.z, deep.rest.c);
+
+        const pair = [1, 2];
+        [pair[1], pair[0]] = pair;
+        console.log(pair);
+
+        let i, j;
+        for ([i, j] = [0, 1]; i < 3; [i, j] = [i + 1, j * 2]) {}
+        console.log(i, j);
+        "#,
+    );
+    assert_eq!(output, vec!["5 6", "1 2 3 4", "[1, 1]", "3 8"]);
+}
+
+#[test]
+fn object_rest_copies_remaining_enumerable_keys_into_a_new_object() {
+    let output = run_and_capture(
+        r#"
+        const src = { id: 1, a: { b: 2 }, c: 3, get v() { return 7; } };
+        Object.defineProperty(src, "hidden", { value: 9, enumerable: false });
+        src[Symbol("s")] = 4;
+        const { id, ...attrs } = src;
+        console.log(id, Object.keys(attrs).sort().join(","), attrs === src);
+        console.log(attrs.v, Object.getOwnPropertySymbols(attrs).length);
+        attrs.c = 30;
+        console.log(src.c);
+        const { a: { b }, ...r } = src;
+        console.log(b, Object.keys(r).sort().join(","));
+        "#,
+    );
+    assert_eq!(output, vec!["1 a,c,v false", "7 0", "3", "2 c,id,v"]);
+}
+
+#[test]
+fn object_rest_in_params_catch_and_assignment() {
+    let output = run_and_capture(
+        r#"
+        function f({ x, ...more }) { return x + more.y + more.z; }
+        console.log(f({ x: 1, y: 2, z: 3 }));
+        try { throw { code: 5, msg: "m" }; } catch ({ code, ...info }) { console.log(code, info.msg); }
+        let p, q;
+        ({ p, ...q } = { p: 1, s: 2 });
+        console.log(p, q.s, q.p);
+        "#,
+    );
+    assert_eq!(output, vec!["6", "5 m", "1 2 undefined"]);
+}
+
+#[test]
+fn parse_rejects_object_rest_before_the_end() {
+    for (source, offset) in [
+        ("const { ...a, b } = o;", 12),
+        ("const { ...a, } = o;", 12),
+        ("({ ...a, b } = o);", 7),
+    ] {
+        let (message, at) = parse_error_offset(source);
+        assert_eq!(message, "Rest element must be last element", "{source}");
+        assert_eq!(at, offset, "{source}");
+    }
+}
+
+#[test]
+fn parse_for_of_heads_with_patterns_and_without_declarations() {
+    let stmts = parse_source("for (const [k, v] of m) {} for (x of xs) {}");
+    match &stmts[..] {
+        [
+            Stmt::ForOf {
+                kind: Some(VarDeclKind::Const),
+                pattern: Pattern::ArrayPattern { elements },
+                ..
+            },
+            Stmt::ForOf {
+                kind: None,
+                pattern: Pattern::Identifier(name),
+                ..
+            },
+        ] => {
+            assert_eq!(elements.len(), 2);
+            assert_eq!(name, "x");
+        }
+        other => panic!("expected two for-of loops, got {other:?}"),
+    }
+}
+
+#[test]
+fn for_of_destructures_map_entries_and_objects() {
+    let output = run_and_capture(
+        r#"
+        const scores = new Map([["ann", 3], ["bo", 5]]);
+        for (const [name, score] of scores) console.log(name, score);
+        for (let { id, tags: [first] } of [{ id: 1, tags: ["a", "b"] }]) console.log(id, first);
+        let last;
+        for (last of [1, 2, 3]) {}
+        for (var seen in { p: 1, q: 2 }) {}
+        console.log(last, seen);
+        "#,
+    );
+    assert_eq!(output, vec!["ann 3", "bo 5", "1 a", "3 q"]);
+}
+
+#[test]
+fn for_of_binds_a_fresh_const_per_iteration() {
+    let output = run_and_capture(
+        r#"
+        const readers = [];
+        for (const x of [1, 2, 3]) readers.push(() => x);
+        console.log(readers.map((read) => read()).join(","));
+        try {
+            for (const y of [1]) y = 2;
+        } catch (e) {
+            console.log(e instanceof TypeError);
+        }
+        "#,
+    );
+    assert_eq!(output, vec!["1,2,3", "true"]);
+}
@@ -0,0 +1,362 @@
+//! A seeded generator of small programs built from the constructs both
+//! engines claim to support: arithmetic, strings, comparisons, `if`,
+//! `while` and `for`, functions and calls. Arrays, object literals and
+//! logical operators are mixed in rarely, as the VM compiler hands
+//! programs using them to the interpreter and the harness skips those.
+
+/// SplitMix64, so that a seed produces the same program on every
+/// platform.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// A generated statement. Expressions are kept as source text: only
+/// whole statements are removed when a program is minimized.
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    Line(String),
+    If {
+        condition: String,
+        then_branch: Vec<Stmt>,
+        else_branch: Vec<Stmt>,
+    },
+    /// A `for` or `while` header that bounds its own iterations, so that
+    /// removing statements from the body never makes it run forever.
+    Loop {
+        header: String,
+        body: Vec<Stmt>,
+    },
+    Function {
+        header: String,
+        body: Vec<Stmt>,
+    },
+}
+
+impl Stmt {
+    pub fn children(&self) -> Vec<&Vec<Stmt>> {
+        match self {
+            Stmt::Line(_) => Vec::new(),
+            Stmt::If {
+                then_branch,
+                else_branch,
+                ..
+            } => vec![then_branch, else_branch],
+            Stmt::Loop { body, .. } | Stmt::Function { body, .. } => vec![body],
+        }
+    }
+
+    pub fn children_mut(&mut self) -> Vec<&mut Vec<Stmt>> {
+        match self {
+            Stmt::Line(_) => Vec::new(),
+            Stmt::If {
+                then_branch,
+                else_branch,
+                ..
+            } => vec![then_branch, else_branch],
+            Stmt::Loop { body, .. } | Stmt::Function { body, .. } => vec![body],
+        }
+    }
+}
+
+const MAX_DEPTH: usize = 3;
+const STRINGS: &[&str] = &["", "a", "ab", "xyz", "10", "3.5", " ", "null"];
+const NUMBERS: &[&str] = &[
+    "0", "1", "2", "3", "7", "10", "255", "(-1)", "(-4)", "0.5", "1.25", "1e3", "0.1",
+];
+const ARITHMETIC: &[&str] = &["+", "-", "*", "/", "%", "**"];
+const COMPARISON: &[&str] = &["<", "<=", ">", ">=", "===", "!=="];
+const BITWISE: &[&str] = &["&", "|", "^", "<<", ">>", ">>>"];
+
+/// Generates the program for `seed`.
+pub fn generate(seed: u64) -> Vec<Stmt> {
+    let mut generator = Generator {
+        rng: Rng::new(seed),
+        scopes: vec![Vec::new()],
+        counters: Vec::new(),
+        functions: Vec::new(),
+        next_id: 0,
+    };
+    let count = 4 + generator.rng.below(10);
+    let mut program = Vec::with_capacity(count);
+    for _ in 0..count {
+        if generator.rng.one_in(4) {
+            program.push(generator.function());
+        } else {
+            program.push(generator.stmt(0));
+        }
+    }
+    program
+}
+
+/// Renders statements as source, one per line.
+pub fn render(program: &[Stmt]) -> String {
+    let mut out = String::new();
+    render_block(program, 0, &mut out);
+    out
+}
+
+fn render_block(stmts: &[Stmt], depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    for stmt in stmts {
+        match stmt {
+            Stmt::Line(line) => {
+                out.push_str(&format!("{indent}{line}\n"));
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                out.push_str(&format!("{indent}if ({condition}) {{\n"));
+                render_block(then_branch, depth + 1, out);
+                if else_branch.is_empty() {
+                    out.push_str(&format!("{indent}}}\n"));
+                } else {
+                    out.push_str(&format!("{indent}}} else {{\n"));
+                    render_block(else_branch, depth + 1, out);
+                    out.push_str(&format!("{indent}}}\n"));
+                }
+            }
+            Stmt::Loop { header, body } | Stmt::Function { header, body } => {
+                for line in header.lines() {
+                    out.push_str(&format!("{indent}{line}"));
+                    out.push('\n');
+                }
+                out.pop();
+                out.push_str(" {\n");
+                render_block(body, depth + 1, out);
+                out.push_str(&format!("{indent}}}\n"));
+            }
+        }
+    }
+}
+
+struct Generator {
+    rng: Rng,
+    /// Variables a statement may read and assign, one list per block.
+    scopes: Vec<Vec<String>>,
+    /// Counters of the loops being generated, which may be read but never
+    /// assigned.
+    counters: Vec<String>,
+    /// Declared functions and how many parameters each takes. Functions
+    /// only call the ones declared before them, so nothing recurses.
+    functions: Vec<(String, usize)>,
+    next_id: usize,
+}
+
+impl Generator {
+    fn fresh(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{prefix}{}", self.next_id)
+    }
+
+    fn variables(&self) -> Vec<String> {
+        self.scopes.iter().flatten().cloned().collect()
+    }
+
+    fn stmt(&mut self, depth: usize) -> Stmt {
+        let choice = if depth >= MAX_DEPTH {
+            self.rng.below(4)
+        } else {
+            self.rng.below(7)
+        };
+        match choice {
+            0 => {
+                let value = self.expr(0);
+                let name = self.fresh("v");
+                self.scopes
+                    .last_mut()
+                    .expect("a scope is always open")
+                    .push(name.clone());
+                Stmt::Line(format!("let {name} = {value};"))
+            }
+            1 => {
+                let variables = self.variables();
+                if variables.is_empty() {
+                    return self.print();
+                }
+                let name = self.rng.pick(&variables).clone();
+                match self.rng.below(4) {
+                    0 => Stmt::Line(format!("{name}++;")),
+                    1 => {
+                        let op = self.rng.pick(&["+=", "-=", "*="]).to_string();
+                        let value = self.expr(1);
+                        Stmt::Line(format!("{name} {op} {value};"))
+                    }
+                    _ => {
+                        let value = self.expr(0);
+                        Stmt::Line(format!("{name} = {value};"))
+                    }
+                }
+            }
+            2 | 3 => self.print(),
+            4 => {
+                let condition = self.expr(0);
+                let then_branch = self.block(depth + 1);
+                let else_branch = if self.rng.one_in(2) {
+                    self.block(depth + 1)
+                } else {
+                    Vec::new()
+                };
+                Stmt::If {
+                    condition,
+                    then_branch,
+                    else_branch,
+                }
+            }
+            _ => {
+                let counter = self.fresh("i");
+                let bound = 1 + self.rng.below(4);
+                let header = if self.rng.one_in(2) {
+                    format!("for (let {counter} = 0; {counter} < {bound}; {counter}++)")
+                } else {
+                    // The counter is declared by the loop's own line, so
+                    // the loop stays bounded whatever is removed around it.
+                    format!("let {counter} = 0;\nwhile ({counter}++ < {bound})")
+                };
+                self.counters.push(counter);
+                let body = self.block(depth + 1);
+                self.counters.pop();
+                Stmt::Loop { header, body }
+            }
+        }
+    }
+
+    fn print(&mut self) -> Stmt {
+        let value = self.expr(0);
+        Stmt::Line(format!("console.log({value});"))
+    }
+
+    fn block(&mut self, depth: usize) -> Vec<Stmt> {
+        self.scopes.push(Vec::new());
+        let count = 1 + self.rng.below(3);
+        let stmts = (0..count).map(|_| self.stmt(depth)).collect();
+        self.scopes.pop();
+        stmts
+    }
+
+    fn function(&mut self) -> Stmt {
+        let name = self.fresh("f");
+        let arity = self.rng.below(3);
+        let params: Vec<String> = (0..arity).map(|_| self.fresh("p")).collect();
+        let header = format!("function {name}({})", params.join(", "));
+
+        // A function body sees its parameters and locals only, so that
+        // calling it never depends on when the globals were declared.
+        let outer = std::mem::replace(&mut self.scopes, vec![params]);
+        let mut body = self.block(1);
+        let result = self.expr(0);
+        body.push(Stmt::Line(format!("return {result};")));
+        self.scopes = outer;
+
+        self.functions.push((name, arity));
+        Stmt::Function { header, body }
+    }
+
+    fn expr(&mut self, depth: usize) -> String {
+        let leaf = depth >= 3 || self.rng.one_in(3);
+        if leaf {
+            return self.atom();
+        }
+        match self.rng.below(12) {
+            0..=2 => {
+                let op = *self.rng.pick(ARITHMETIC);
+                format!("({} {op} {})", self.expr(depth + 1), self.expr(depth + 1))
+            }
+            3 | 4 => {
+                let op = *self.rng.pick(COMPARISON);
+                format!("({} {op} {})", self.expr(depth + 1), self.expr(depth + 1))
+            }
+            5 => {
+                let op = *self.rng.pick(BITWISE);
+                format!("({} {op} {})", self.expr(depth + 1), self.expr(depth + 1))
+            }
+            6 => {
+                let op = *self.rng.pick(&["-", "!", "typeof"]);
+                format!("({op} {})", self.expr(depth + 1))
+            }
+            7 => format!(
+                "({} ? {} : {})",
+                self.expr(depth + 1),
+                self.expr(depth + 1),
+                self.expr(depth + 1)
+            ),
+            8 if !self.functions.is_empty() => {
+                let (name, arity) = self.rng.pick(&self.functions).clone();
+                let args: Vec<String> = (0..arity).map(|_| self.expr(depth + 1)).collect();
+                format!("{name}({})", args.join(", "))
+            }
+            // Only strings are measured: the interpreter throws reading a
+            // property of a number or boolean, which the VM does not.
+            9 => format!(
+                "({:?} + {}).length",
+                self.rng.pick(STRINGS),
+                self.expr(depth + 1)
+            ),
+            10 if self.rng.one_in(4) => match self.rng.below(3) {
+                0 => format!(
+                    "[{}, {}][{}]",
+                    self.expr(depth + 1),
+                    self.expr(depth + 1),
+                    self.rng.below(3)
+                ),
+                1 => format!("({{ k: {} }}).k", self.expr(depth + 1)),
+                _ => {
+                    let op = *self.rng.pick(&["&&", "||", "??"]);
+                    format!("({} {op} {})", self.expr(depth + 1), self.expr(depth + 1))
+                }
+            },
+            _ => format!(
+                "({} + {})",
+                self.string_expr(depth + 1),
+                self.expr(depth + 1)
+            ),
+        }
+    }
+
+    fn string_expr(&mut self, depth: usize) -> String {
+        if self.rng.one_in(2) {
+            format!("{:?}", self.rng.pick(STRINGS))
+        } else {
+            self.expr(depth)
+        }
+    }
+
+    fn atom(&mut self) -> String {
+        let mut variables = self.variables();
+        variables.extend(self.counters.iter().cloned());
+        match self.rng.below(8) {
+            0..=2 if !variables.is_empty() => self.rng.pick(&variables).clone(),
+            0..=4 => self.rng.pick(NUMBERS).to_string(),
+            5 | 6 => format!("{:?}", self.rng.pick(STRINGS)),
+            _ => self
+                .rng
+                .pick(&["true", "false", "null", "undefined"])
+                .to_string(),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Differential testing of the interpreter against the VM.
+//!
+//! Each case is a program generated from a seed and run by both engines,
+//! which have to print the same lines and agree on whether it fails.
+//! Programs the VM compiler hands to the interpreter are skipped. A
+//! diverging program is minimized and written to `fuzz-failures/`, named
+//! after its seed.
+//!
+//! Run with `cargo test -p core --features differential --test
+//! differential`. `CRUSTYJS_FUZZ_CASES` sets how many cases run, or
+//! `unbounded` to keep going until one diverges; `CRUSTYJS_FUZZ_SEED`
+//! sets the first seed.
+
+mod generator;
+mod shrink;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crustyjs::OutputMode;
+use crustyjs::lexer::lex;
+use crustyjs::parser::ast::Program;
+use crustyjs::parser::parse;
+use crustyjs::runtime::interpreter::Interpreter;
+use crustyjs::vm::compiler::Compiler;
+use crustyjs::vm::machine::VM;
+use generator::Stmt;
+
+const DEFAULT_CASES: u64 = 500;
+
+/// What running a program produced.
+#[derive(Debug)]
+struct Outcome {
+    output: Vec<String>,
+    error: Option<String>,
+}
+
+impl Outcome {
+    fn agrees_with(&self, other: &Outcome) -> bool {
+        self.output == other.output && self.error.is_some() == other.error.is_some()
+    }
+}
+
+/// Which engines failed, so that minimizing keeps the divergence it
+/// started from rather than drifting to another one, such as a read of a
+/// variable whose declaration was removed.
+fn failed(interp: &Outcome, vm: &Outcome) -> (bool, bool) {
+    (interp.error.is_some(), vm.error.is_some())
+}
+
+fn run_interpreter(program: &Program) -> Outcome {
+    let mut interp = Interpreter::new();
+    interp.set_output_mode(OutputMode::Capture);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| interp.run(program)));
+    let error = match result {
+        Ok(Ok(())) => None,
+        Ok(Err(err)) => Some(err.to_string()),
+        Err(_) => Some("the interpreter panicked".to_string()),
+    };
+    Outcome {
+        output: interp.output().to_vec(),
+        error,
+    }
+}
+
+/// Runs the program on the VM, or returns `None` when the compiler would
+/// fall back to the interpreter for it.
+fn run_vm(program: &Program) -> Option<Outcome> {
+    let mut compiler = Compiler::new();
+    let chunk = compiler.compile(program.clone());
+    if compiler.requires_tree_walk {
+        return None;
+    }
+    let chunk = match chunk {
+        Ok(chunk) => chunk,
+        Err(err) => {
+            return Some(Outcome {
+                output: Vec::new(),
+                error: Some(err.to_string()),
+            });
+        }
+    };
+    let mut vm = VM::new();
+    vm.set_output_mode(OutputMode::Capture);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| vm.run(chunk, None, None)));
+    let error = match result {
+        Ok(Ok(_)) => None,
+        Ok(Err(err)) => Some(err.to_string()),
+        Err(_) => Some("the VM panicked".to_string()),
+    };
+    Some(Outcome {
+        output: vm.output().to_vec(),
+        error,
+    })
+}
+
+/// Both outcomes when the engines disagree on `source`. Sources that do
+/// not parse or that the VM does not compile on its own have none.
+fn divergence(source: &str) -> Option<(Outcome, Outcome)> {
+    let program = parse(lex(source).ok()?).ok()?;
+    let vm = run_vm(&program)?;
+    let interp = run_interpreter(&program);
+    (!interp.agrees_with(&vm)).then_some((interp, vm))
+}
+
+/// A divergence found for a seed, after minimizing.
+struct Failure {
+    seed: u64,
+    path: PathBuf,
+}
+
+fn check_seed(seed: u64) -> Result<bool, Failure> {
+    let program = generator::generate(seed);
+    let source = generator::render(&program);
+    let Some(program) = parse(lex(&source).expect("generated programs lex")).ok() else {
+        panic!("seed {seed} generated a program that does not parse:\n{source}");
+    };
+    if run_vm(&program).is_none() {
+        return Ok(false);
+    }
+    let Some((interp, vm)) = divergence(&source) else {
+        return Ok(true);
+    };
+
+    let expected = failed(&interp, &vm);
+    let program = generator::generate(seed);
+    let minimized = shrink::minimize(program, |candidate: &[Stmt]| {
+        divergence(&generator::render(candidate))
+            .is_some_and(|(interp, vm)| failed(&interp, &vm) == expected)
+    });
+    let source = generator::render(&minimized);
+    let (interp, vm) = divergence(&source).expect("the minimized program still diverges");
+    let path = record_failure(seed, &source, &interp, &vm);
+    Err(Failure { seed, path })
+}
+
+fn record_failure(seed: u64, source: &str, interp: &Outcome, vm: &Outcome) -> PathBuf {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz-failures");
+    std::fs::create_dir_all(&dir).expect("create fuzz-failures directory");
+    let path = dir.join(format!("seed-{seed}.js"));
+    let describe = |outcome: &Outcome| {
+        let mut lines = format!("//   output: {:?}\n", outcome.output);
+        if let Some(error) = &outcome.error {
+            lines.push_str(&format!("//   error: {}\n", error.replace('\n', " ")));
+        }
+        lines
+    };
+    let contents = format!(
+        "// seed: {seed}\n// interpreter:\n{}// vm:\n{}\n{source}",
+        describe(interp),
+        describe(vm)
+    );
+    std::fs::write(&path, contents).expect("write fuzz failure");
+    path
+}
+
+/// The seeds to run, from `CRUSTYJS_FUZZ_SEED` and `CRUSTYJS_FUZZ_CASES`.
+/// `None` as the count means no limit.
+fn configured_seeds() -> (u64, Option<u64>) {
+    let first = std::env::var("CRUSTYJS_FUZZ_SEED")
+        .ok()
+        .map(|seed| seed.parse().expect("CRUSTYJS_FUZZ_SEED is a number"))
+        .unwrap_or(0);
+    let cases = match std::env::var("CRUSTYJS_FUZZ_CASES") {
+        Ok(cases) if cases == "unbounded" => None,
+        Ok(cases) => Some(cases.parse().expect("CRUSTYJS_FUZZ_CASES is a number")),
+        Err(_) => Some(DEFAULT_CASES),
+    };
+    (first, cases)
+}
+
+#[test]
+fn interpreter_and_vm_agree_on_generated_programs() {
+    let (first, cases) = configured_seeds();
+    let mut failures = Vec::new();
+    let mut compared = 0;
+    let mut seed = first;
+    while cases.is_none_or(|cases| seed - first < cases) {
+        match check_seed(seed) {
+            Ok(ran) => compared += usize::from(ran),
+            Err(failure) => {
+                failures.push(failure);
+                if cases.is_none() {
+                    break;
+                }
+            }
+        }
+        seed += 1;
+    }
+
+    assert!(
+        compared > 0 || !failures.is_empty(),
+        "no generated program compiled for the VM"
+    );
+    let report: Vec<String> = failures
+        .iter()
+        .map(|failure| format!("seed {}: {}", failure.seed, failure.path.display()))
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "the engines diverged on {} generated programs:\n{}",
+        failures.len(),
+        report.join("\n")
+    );
+}
+
+#[test]
+fn minimizing_keeps_only_the_statements_needed_to_diverge() {
+    let program = vec![
+        Stmt::Line("let a = 1;".into()),
+        Stmt::If {
+            condition: "a".into(),
+            then_branch: vec![Stmt::Line("keep();".into()), Stmt::Line("b();".into())],
+            else_branch: Vec::new(),
+        },
+        Stmt::Line("c();".into()),
+    ];
+    let minimized = shrink::minimize(program, |candidate: &[Stmt]| {
+        generator::render(candidate).contains("keep();")
+    });
+    assert_eq!(generator::render(&minimized), "if (a) {\n  keep();\n}\n");
+}
+
+#[test]
+fn the_same_seed_generates_the_same_program() {
+    assert_eq!(
+        generator::render(&generator::generate(42)),
+        generator::render(&generator::generate(42))
+    );
+    assert_ne!(
+        generator::render(&generator::generate(1)),
+        generator::render(&generator::generate(2))
+    );
+}
@@ -0,0 +1,60 @@
+//! Delta debugging for diverging programs: statements are removed one at
+//! a time, at any depth, for as long as the program still diverges.
+
+use crate::generator::Stmt;
+
+/// Returns the smallest program reachable from `program` by removing
+/// statements for which `diverges` still holds.
+pub fn minimize(program: Vec<Stmt>, diverges: impl Fn(&[Stmt]) -> bool) -> Vec<Stmt> {
+    let mut current = program;
+    loop {
+        let mut reduced = false;
+        let mut index = 0;
+        while index < count(&current) {
+            let candidate = without(&current, index);
+            if diverges(&candidate) {
+                current = candidate;
+                reduced = true;
+            } else {
+                index += 1;
+            }
+        }
+        if !reduced {
+            return current;
+        }
+    }
+}
+
+/// Statements in `stmts`, nested ones included.
+fn count(stmts: &[Stmt]) -> usize {
+    stmts
+        .iter()
+        .map(|stmt| 1 + stmt.children().iter().map(|c| count(c)).sum::<usize>())
+        .sum()
+}
+
+/// A copy of `stmts` without the statement at pre-order position `index`
+/// and everything nested in it.
+fn without(stmts: &[Stmt], index: usize) -> Vec<Stmt> {
+    let mut copy = stmts.to_vec();
+    remove(&mut copy, &mut { index });
+    copy
+}
+
+fn remove(stmts: &mut Vec<Stmt>, index: &mut usize) -> bool {
+    let mut i = 0;
+    while i < stmts.len() {
+        if *index == 0 {
+            stmts.remove(i);
+            return true;
+        }
+        *index -= 1;
+        for child in stmts[i].children_mut() {
+            if remove(child, index) {
+                return true;
+            }
+        }
+        i += 1;
+    }
+    false
+}
@@ -1,7 +1,9 @@
 use std::fs;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crustyjs::{ClassBuilder, Engine, EventTarget, Value};
+use std::sync::{Arc, Mutex};
+
+use crustyjs::{ClassBuilder, Engine, EventTarget, EventTargetHandle, OutputMode, Value};
 
 #[test]
 fn engine_context_eval_and_globals() {
@@ -59,6 +61,198 @@ fn register_and_call_native_function() {
     assert_eq!(result, Value::Number(42.0));
 }
 
+#[test]
+fn native_functions_share_context_data() {
+    struct Counter {
+        hits: u32,
+    }
+
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+    ctx.set_data(Counter { hits: 0 });
+
+    ctx.set_global_function("bump", |args| {
+        let mut counter = args.data_mut::<Counter>().expect("counter is set");
+        counter.hits += 1;
+        Ok(Value::Undefined)
+    });
+    ctx.set_global_function("hits", |args| {
+        let counter = args.data::<Counter>().expect("counter is set");
+        Ok(Value::Number(counter.hits as f64))
+    });
+    ctx.set_global_function("missing", |args| {
+        Ok(Value::Boolean(args.data::<String>().is_none()))
+    });
+
+    ctx.eval("bump(); bump(); bump(); let seen = hits(); let none = missing();")
+        .expect("natives should run");
+    assert_eq!(ctx.get_global("seen").unwrap(), Value::Number(3.0));
+    assert_eq!(ctx.get_global("none").unwrap(), Value::Boolean(true));
+    assert_eq!(ctx.data::<Counter>().map(|c| c.hits), Some(3));
+
+    let previous = ctx.set_data(Counter { hits: 10 });
+    assert_eq!(previous.map(|c| c.hits), Some(3));
+}
+
+#[test]
+fn native_function_calls_back_into_script() {
+    struct Calls(u32);
+
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+    ctx.set_data(Calls(0));
+
+    ctx.set_global_function_with_scope("repeat", |scope, args| {
+        let n = args.get(0).map_or(0.0, Value::to_number) as usize;
+        let Some(callback) = args.get(1) else {
+            return Err(scope.throw_type_error("repeat needs a callback"));
+        };
+        let mut results = Vec::with_capacity(n);
+        for i in 0..n {
+            args.data_mut::<Calls>().expect("calls is set").0 += 1;
+            results.push(scope.call(callback, &[Value::Number(i as f64)])?);
+        }
+        Ok(scope.alloc_array(results))
+    });
+
+    let value = ctx
+        .eval_value(
+            r#"
+            let offset = 10;
+            const squares = repeat(4, (i) => i * i + offset);
+            const nested = repeat(2, (i) => repeat(i + 1, (j) => j).length);
+            let caught;
+            try {
+                repeat(3, (i) => { if (i === 1) throw new Error("stop at " + i); });
+            } catch (e) {
+                caught = e.message;
+            }
+            let missing;
+            try { repeat(1); } catch (e) { missing = e instanceof TypeError; }
+            [squares.join(), nested.join(), caught, missing].join(" | ")
+            "#,
+        )
+        .expect("callbacks should run");
+    assert_eq!(value.to_string(), "10,11,14,19 | 1,2 | stop at 1 | true");
+    assert_eq!(ctx.data::<Calls>().map(|calls| calls.0), Some(11));
+}
+
+#[test]
+fn native_class_method_calls_methods_on_its_arguments() {
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+
+    let class_def = ClassBuilder::new("Table")
+        .constructor(|_| Ok(Value::Undefined))
+        .method_with_scope("forEachRow", |scope, args| {
+            let visitor = args.get(0).cloned().unwrap_or(Value::Undefined);
+            for row in ["a", "b"] {
+                scope.call_method(&visitor, "visit", &[Value::String(row.into())])?;
+            }
+            let summary = scope.alloc_object();
+            if let Value::Object(object) = &summary {
+                object
+                    .borrow_mut()
+                    .set("rows".to_string(), Value::Number(2.0));
+            }
+            Ok(summary)
+        })
+        .build();
+
+    ctx.register_class(class_def);
+    let value = ctx
+        .eval_value(
+            r#"
+            const seen = [];
+            const table = Table();
+            const summary = table.forEachRow({ visit(row) { seen.push(row + this.suffix); }, suffix: "!" });
+            seen.join() + " " + summary.rows
+            "#,
+        )
+        .expect("method should call back into script");
+    assert_eq!(value.to_string(), "a!,b! 2");
+}
+
+#[test]
+fn injected_json_and_readonly_globals() {
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+
+    ctx.set_global_readonly("VERSION", Value::String("1.2.0".into()));
+    ctx.set_global_json(
+        "config",
+        r#"{"name": "demo", "limits": {"retries": 3, "hosts": ["a", "b"]}}"#,
+    )
+    .expect("config should parse");
+
+    let value = ctx
+        .eval_value("`${VERSION} ${config.name} ${config.limits.retries} ${config.limits.hosts[1]}`")
+        .expect("globals should be readable");
+    assert_eq!(value, Value::String("1.2.0 demo 3 b".into()));
+
+    let value = ctx
+        .eval_value(
+            "let caught = false; try { VERSION = '2'; } catch (e) { caught = e instanceof TypeError; } caught",
+        )
+        .expect("eval should succeed");
+    assert_eq!(value, Value::Boolean(true));
+    assert_eq!(
+        ctx.get_global("VERSION").unwrap(),
+        Value::String("1.2.0".into())
+    );
+    assert!(ctx.set_global_json("broken", "{").is_err());
+}
+
+#[test]
+fn eval_value_returns_last_expression() {
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+
+    let value = ctx.eval_value("let a = 20; a + 1; a * 2;").unwrap();
+    assert_eq!(value, Value::Number(40.0));
+    let value = ctx.eval_value("let b = 1;").unwrap();
+    assert_eq!(value, Value::Undefined);
+}
+
+#[test]
+fn persistent_handles_survive_collection_until_dropped() {
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+
+    let value = ctx.eval_value("({ items: [1, 2, 3] })").unwrap();
+    let handle = ctx.persist(value);
+    unsafe { ctx.collect_garbage() };
+    assert_eq!(ctx.persistent_roots().len(), 1);
+
+    ctx.set_global("kept", handle.get());
+    ctx.eval("let total = kept.items.length; kept = undefined;")
+        .unwrap();
+    assert_eq!(ctx.get_global("total").unwrap(), Value::Number(3.0));
+
+    drop(handle);
+    assert!(ctx.persistent_roots().is_empty());
+    assert!(unsafe { ctx.collect_garbage() }.collected >= 2);
+}
+
+#[test]
+fn persisted_global_outlives_its_binding() {
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+
+    ctx.eval("var o = { name: 'kept', tags: ['a', 'b'] };")
+        .unwrap();
+    let value = ctx.get_global("o").unwrap();
+    let _handle = ctx.persist(value.clone());
+    ctx.eval("o = null;").unwrap();
+    unsafe { ctx.collect_garbage() };
+
+    ctx.set_global("back", value);
+    let got = ctx
+        .eval_value("back.name + ':' + back.tags.join('')")
+        .unwrap();
+    assert_eq!(got, Value::String("kept:ab".into()));
+}
+
 #[test]
 fn register_class_constructor() {
     let engine = Engine::new();
@@ -66,10 +260,7 @@ fn register_class_constructor() {
 
     let class_def = ClassBuilder::new("Element")
         .constructor(|args| {
-            let tag = args
-                .get(0)
-                .cloned()
-                .unwrap_or(Value::String("div".to_string()));
+            let tag = args.get(0).cloned().unwrap_or(Value::String("div".into()));
             Ok(tag)
         })
         .build();
@@ -80,7 +271,7 @@ fn register_class_constructor() {
     let created = ctx
         .get_global("created")
         .expect("created should be available");
-    assert_eq!(created, Value::String("section".to_string()));
+    assert_eq!(created, Value::String("section".into()));
 }
 
 #[test]
@@ -90,10 +281,7 @@ fn register_class_method_on_instances() {
 
     let class_def = ClassBuilder::new("Element")
         .constructor(|args| {
-            let tag = args
-                .get(0)
-                .cloned()
-                .unwrap_or(Value::String("div".to_string()));
+            let tag = args.get(0).cloned().unwrap_or(Value::String("div".into()));
             if let Value::Object(object) = args.this() {
                 object.borrow_mut().set("tag".to_string(), tag);
             }
@@ -113,7 +301,7 @@ fn register_class_method_on_instances() {
         .expect("class method invocation should succeed");
 
     let tag = ctx.get_global("tag").expect("tag should be set");
-    assert_eq!(tag, Value::String("article".to_string()));
+    assert_eq!(tag, Value::String("article".into()));
 }
 
 #[test]
@@ -133,13 +321,79 @@ fn context_exposes_event_loop_drivers() {
     assert_eq!(tick, Value::Number(1.0));
 }
 
+#[test]
+fn advance_time_fires_timers_in_chunks() {
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+    ctx.set_manual_timers(true);
+
+    ctx.eval(
+        r#"
+        let ticks = 0;
+        const id = setInterval(() => {
+            ticks = ticks + 1;
+            console.log("tick " + ticks);
+            if (ticks === 3) clearInterval(id);
+        }, 200);
+        setTimeout(() => {
+            console.log("outer");
+            setTimeout(() => console.log("inner"), 100);
+            Promise.resolve().then(() => console.log("micro"));
+        }, 250);
+        "#,
+    )
+    .expect("timer script should evaluate");
+    assert!(ctx.output().is_empty());
+    assert_eq!(ctx.pending_timer_count(), 2);
+    assert_eq!(ctx.next_timer_due_in(), Some(200));
+
+    ctx.advance_time(500).expect("first chunk should run");
+    assert_eq!(
+        ctx.output(),
+        ["tick 1", "outer", "micro", "inner", "tick 2"].as_slice()
+    );
+    assert_eq!(ctx.pending_timer_count(), 1);
+    assert_eq!(ctx.next_timer_due_in(), Some(100));
+
+    ctx.advance_time(99).expect("idle chunk should run");
+    assert_eq!(ctx.output().len(), 5);
+    assert_eq!(ctx.next_timer_due_in(), Some(1));
+
+    ctx.advance_time(1_000).expect("last chunk should run");
+    assert_eq!(&ctx.output()[5..], ["tick 3"].as_slice());
+    assert_eq!(ctx.pending_timer_count(), 0);
+    assert_eq!(ctx.next_timer_due_in(), None);
+    assert_eq!(ctx.get_global("ticks").unwrap(), Value::Number(3.0));
+}
+
+#[test]
+fn advance_time_orders_timers_due_at_the_same_moment() {
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+    ctx.set_manual_timers(true);
+
+    ctx.eval(
+        r#"
+        setTimeout(() => console.log("b"), 20);
+        setTimeout(() => {
+            console.log("a");
+            setTimeout(() => console.log("c"), 10);
+        }, 10);
+        setTimeout(() => console.log("b2"), 20);
+        "#,
+    )
+    .expect("timer script should evaluate");
+    ctx.advance_time(20).expect("timers should fire");
+    assert_eq!(ctx.output(), ["a", "b", "b2", "c"].as_slice());
+}
+
 #[test]
 fn register_class_getter_setter_and_inheritance() {
     let engine = Engine::new();
     let mut ctx = engine.new_context();
 
     let parent = ClassBuilder::new("Base")
-        .method("base", |_| Ok(Value::String("base".to_string())))
+        .method("base", |_| Ok(Value::String("base".into())))
         .build();
     ctx.register_class(parent);
 
@@ -149,7 +403,7 @@ fn register_class_getter_setter_and_inheritance() {
             if let Value::Object(object) = args.this() {
                 object
                     .borrow_mut()
-                    .set("_html".to_string(), Value::String("init".to_string()));
+                    .set("_html".to_string(), Value::String("init".into()));
             }
             Ok(Value::Undefined)
         })
@@ -174,8 +428,28 @@ fn register_class_getter_setter_and_inheritance() {
 
     let a = ctx.get_global("a").expect("a should exist");
     let b = ctx.get_global("b").expect("b should exist");
-    assert_eq!(a, Value::String("next".to_string()));
-    assert_eq!(b, Value::String("base".to_string()));
+    assert_eq!(a, Value::String("next".into()));
+    assert_eq!(b, Value::String("base".into()));
+}
+
+#[test]
+fn native_class_instances_satisfy_instanceof() {
+    let engine = Engine::new();
+    let mut ctx = engine.new_context();
+    ctx.register_class(ClassBuilder::new("Node").build());
+    ctx.register_class(ClassBuilder::new("Element").inherit("Node").build());
+    ctx.register_class(ClassBuilder::new("Text").build());
+
+    ctx.eval(
+        "let el = new Element(); \
+         let checks = [el instanceof Element, el instanceof Node, el instanceof Text, {} instanceof Element].join();",
+    )
+    .expect("instanceof should accept native classes");
+
+    assert_eq!(
+        ctx.get_global("checks").expect("checks should exist"),
+        Value::String("true,true,false,false".into())
+    );
 }
 
 #[test]
@@ -230,3 +504,281 @@ fn context_dispatches_event_target_listeners() {
     let seen_after = ctx.get_global("seen").expect("seen should exist");
     assert_eq!(seen_after, Value::Number(7.0));
 }
+
+#[test]
+fn context_captures_console_output_by_default() {
+    let mut ctx = Engine::new().new_context();
+    ctx.eval("console.log('captured', 1 + 1);")
+        .expect("eval should succeed");
+    assert_eq!(ctx.output(), ["captured 2"]);
+}
+
+#[test]
+fn output_sink_receives_console_lines() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let sink_lines = Arc::clone(&lines);
+    let mut ctx = Engine::new()
+        .with_output_mode(OutputMode::sink(move |line| {
+            sink_lines.lock().unwrap().push(line.to_string());
+        }))
+        .new_context();
+
+    ctx.eval("console.log('first'); Promise.resolve(2).then(v => console.log('second', v));")
+        .expect("eval should succeed");
+
+    assert_eq!(*lines.lock().unwrap(), ["first", "second 2"]);
+    assert!(
+        ctx.output().is_empty(),
+        "sink mode should not capture lines"
+    );
+}
+
+#[test]
+fn host_emits_events_to_script_listeners() {
+    let mut ctx = Engine::new().new_context();
+    let ticker = ctx.create_event_target();
+    ctx.set_global("ticker", ticker.object());
+    ctx.eval(
+        r#"
+        let total = 0;
+        let order = [];
+        ticker.addEventListener("tick", (ev) => { total = total + ev.detail; });
+        ticker.addEventListener("tick", (ev) => {
+            order.push(ev.type + ev.detail);
+            Promise.resolve().then(() => order.push("micro"));
+        });
+        "#,
+    )
+    .expect("listeners should register");
+
+    for n in 1..=3 {
+        ticker
+            .emit(&mut ctx, "tick", Value::Number(n as f64))
+            .expect("emit should succeed");
+    }
+    unsafe { ctx.collect_garbage() };
+    ticker
+        .emit(&mut ctx, "tick", Value::Number(4.0))
+        .expect("listeners should survive a collection");
+
+    assert_eq!(ctx.get_global("total").unwrap(), Value::Number(10.0));
+    ctx.eval("let joined = order.join(',');").unwrap();
+    assert_eq!(
+        ctx.get_global("joined").unwrap(),
+        Value::String("tick1,micro,tick2,micro,tick3,micro,tick4,micro".into())
+    );
+}
+
+#[test]
+fn script_event_target_supports_once_and_removal() {
+    let mut ctx = Engine::new().new_context();
+    ctx.eval(
+        r#"
+        const target = new EventTarget();
+        const seen = [];
+        function always(ev) { seen.push("always:" + ev.type); }
+        target.addEventListener("ping", () => seen.push("once"), { once: true });
+        target.addEventListener("ping", always);
+        target.addEventListener("ping", always);
+        target.dispatchEvent({ type: "ping" });
+        target.dispatchEvent({ type: "ping" });
+        target.removeEventListener("ping", always);
+        target.dispatchEvent({ type: "ping" });
+        const result = seen.join(",");
+        "#,
+    )
+    .expect("script should run");
+    assert_eq!(
+        ctx.get_global("result").unwrap(),
+        Value::String("once,always:ping,always:ping".into())
+    );
+
+    let target = ctx.get_global("target").unwrap();
+    let handle = EventTargetHandle::from_value(&ctx, &target).expect("script target should wrap");
+    ctx.eval("target.addEventListener('host', (ev) => seen.push(ev.detail));")
+        .unwrap();
+    handle
+        .emit(&mut ctx, "host", Value::String("from-rust".into()))
+        .unwrap();
+    ctx.eval("const last = seen[seen.length - 1];").unwrap();
+    assert_eq!(
+        ctx.get_global("last").unwrap(),
+        Value::String("from-rust".into())
+    );
+}
+
+#[test]
+fn throwing_listener_does_not_stop_later_listeners() {
+    let mut ctx = Engine::new().new_context();
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&errors);
+    ctx.set_error_hook(move |err| sink.lock().unwrap().push(err.to_string()));
+
+    let target = ctx.create_event_target();
+    ctx.set_global("target", target.object());
+    ctx.eval(
+        r#"
+        let reached = false;
+        target.addEventListener("go", () => { throw new Error("boom"); });
+        target.addEventListener("go", () => { reached = true; });
+        "#,
+    )
+    .unwrap();
+    target
+        .emit(&mut ctx, "go", Value::Undefined)
+        .expect("errors go to the hook");
+
+    assert_eq!(ctx.get_global("reached").unwrap(), Value::Boolean(true));
+    let errors = errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("boom"), "{errors:?}");
+}
+
+#[test]
+fn host_class_inheriting_event_target_gets_listener_methods() {
+    let mut ctx = Engine::new().new_context();
+    ctx.register_class(ClassBuilder::new("Button").inherit("EventTarget").build());
+    ctx.eval(
+        r#"
+        const button = new Button();
+        let clicks = 0;
+        button.addEventListener("click", () => { clicks = clicks + 1; });
+        button.dispatchEvent({ type: "click" });
+        "#,
+    )
+    .unwrap();
+    assert_eq!(ctx.get_global("clicks").unwrap(), Value::Number(1.0));
+}
+
+#[test]
+fn event_targets_share_non_enumerable_listener_methods() {
+    let mut ctx = Engine::new().new_context();
+    ctx.register_class(ClassBuilder::new("Button").inherit("EventTarget").build());
+    ctx.eval(
+        r#"
+        const a = new EventTarget();
+        const b = new Button();
+        const shared = a.addEventListener === b.addEventListener
+            && Object.getPrototypeOf(a) === EventTarget.prototype
+            && EventTarget.prototype.constructor === EventTarget;
+        const keys = Object.keys(a).length + Object.keys(b).length;
+        let threw = false;
+        try { a.dispatchEvent.call({}, { type: "x" }); } catch (e) { threw = e instanceof TypeError; }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(ctx.get_global("shared").unwrap(), Value::Boolean(true));
+    assert_eq!(ctx.get_global("keys").unwrap(), Value::Number(0.0));
+    assert_eq!(ctx.get_global("threw").unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn event_target_handle_keeps_its_target_alive() {
+    let mut ctx = Engine::new().new_context();
+    ctx.eval("var target = new EventTarget(); var seen = [];")
+        .unwrap();
+    let value = ctx.get_global("target").unwrap();
+    let handle = EventTargetHandle::from_value(&ctx, &value).expect("script target should wrap");
+    drop(value);
+    ctx.eval("target.addEventListener('tick', (ev) => seen.push(ev.detail)); target = null;")
+        .unwrap();
+    unsafe { ctx.collect_garbage() };
+    handle.emit(&mut ctx, "tick", Value::Number(7.0)).unwrap();
+    ctx.eval("const got = seen.join(',');").unwrap();
+    assert_eq!(ctx.get_global("got").unwrap(), Value::String("7".into()));
+}
+
+#[test]
+fn deep_clone_moves_values_between_contexts() {
+    let engine = Engine::new();
+    let mut source = engine.new_context();
+    source
+        .eval("const graph = { label: 'root', tags: new Set(['x']) }; graph.me = graph;")
+        .unwrap();
+    let graph = source.get_global("graph").unwrap();
+
+    let mut target = engine.new_context();
+    let copy = target
+        .deep_clone(&graph)
+        .expect("graph should be cloneable");
+    target.set_global("graph", copy);
+    target
+        .eval("const ok = graph.me === graph && graph.label === 'root' && graph.tags.has('x');")
+        .unwrap();
+    assert_eq!(target.get_global("ok").unwrap(), Value::Boolean(true));
+}
+
+fn temperature_class() -> ClassBuilder {
+    fn celsius(this: &Value) -> f64 {
+        match this {
+            Value::Object(object) => object
+                .borrow()
+                .get("celsius")
+                .map_or(f64::NAN, |value| value.to_number()),
+            _ => f64::NAN,
+        }
+    }
+    ClassBuilder::new("Temperature")
+        .constructor(|args| {
+            let degrees = args.get(0).cloned().unwrap_or(Value::Number(0.0));
+            if let Value::Object(object) = args.this() {
+                object.borrow_mut().set("celsius".to_string(), degrees);
+            }
+            Ok(Value::Undefined)
+        })
+        .value_of(|args| Ok(Value::Number(celsius(args.this()))))
+        .to_string(|args| Ok(Value::String(format!("{}°C", celsius(args.this())).into())))
+}
+
+#[test]
+fn host_class_coercion_hooks_drive_strings_and_comparisons() {
+    let mut ctx = Engine::new().new_context();
+    ctx.register_class(temperature_class().build());
+    ctx.eval(
+        r#"
+        const cold = new Temperature(4);
+        const warm = new Temperature(21.5);
+        const label = `now ${warm}`;
+        const joined = "was " + cold;
+        const warmer = warm > cold;
+        const ordered = cold <= 4 && !(warm < cold);
+        const delta = warm - cold;
+        const keys = Object.keys(warm).join(",");
+        "#,
+    )
+    .unwrap();
+    let global = |name: &str| ctx.get_global(name).unwrap();
+    assert_eq!(global("label"), Value::String("now 21.5°C".into()));
+    // `+` uses the "default" hint, which asks valueOf first.
+    assert_eq!(global("joined"), Value::String("was 4".into()));
+    assert_eq!(global("warmer"), Value::Boolean(true));
+    assert_eq!(global("ordered"), Value::Boolean(true));
+    assert_eq!(global("delta"), Value::Number(17.5));
+    assert_eq!(global("keys"), Value::String("celsius".into()));
+}
+
+#[test]
+fn host_class_to_primitive_receives_the_hint_and_is_inherited() {
+    let mut ctx = Engine::new().new_context();
+    ctx.register_class(
+        temperature_class()
+            .to_primitive(|args| match args.get(0) {
+                Some(Value::String(hint)) if hint == "number" => Ok(Value::Number(-1.0)),
+                Some(Value::String(hint)) => Ok(Value::String(hint.clone())),
+                _ => Ok(Value::Undefined),
+            })
+            .build(),
+    );
+    ctx.register_class(ClassBuilder::new("Reading").inherit("Temperature").build());
+    ctx.eval(
+        r#"
+        const t = new Reading(30);
+        const hints = `${t}|` + t + "|" + (t * 2) + "|" + (t < 0);
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        ctx.get_global("hints").unwrap(),
+        Value::String("string|default|-2|true".into())
+    );
+}
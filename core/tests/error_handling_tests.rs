@@ -48,6 +48,27 @@ fn finally_runs_on_success_and_throw() {
     assert_eq!(output, vec!["work", "cleanup-1", "bad", "cleanup-2"]);
 }
 
+#[test]
+fn finally_runs_when_catch_rethrows() {
+    let output = run_and_capture(
+        r#"
+        try {
+          try {
+            throw "first";
+          } catch (e) {
+            console.log("catch", e);
+            throw "second";
+          } finally {
+            console.log("finally");
+          }
+        } catch (e) {
+          console.log("outer", e);
+        }
+        "#,
+    );
+    assert_eq!(output, vec!["catch first", "finally", "outer second"]);
+}
+
 #[test]
 fn nested_try_catch_works() {
     let output = run_and_capture(
@@ -78,11 +99,11 @@ fn uncaught_exception_bubbles_out() {
 
     match err {
         RuntimeError::Thrown { value } => {
-            let JsValue::Object(obj) = value else {
+            let JsValue::Object(obj) = *value else {
                 panic!("expected thrown object");
             };
             let message = obj.borrow().get("message").expect("message should exist");
-            assert_eq!(message, JsValue::String("boom".to_string()));
+            assert_eq!(message, JsValue::String("boom".into()));
         }
         other => panic!("expected thrown error, got {other:?}"),
     }
@@ -107,3 +128,190 @@ fn catch_body_allows_missing_semicolon_before_brace() {
     );
     assert_eq!(output, vec!["boom"]);
 }
+
+#[test]
+fn host_errors_are_caught_as_error_objects() {
+    let output = run_and_capture(
+        r#"
+        try {
+          undefinedFn();
+        } catch (e) {
+          console.log(e instanceof ReferenceError, e.name);
+          console.log(e.message);
+          console.log(e.stack.split("\n")[0]);
+        }
+        function callMissing() { let o = {}; return o.method(); }
+        try {
+          callMissing();
+        } catch (e) {
+          console.log(e instanceof TypeError, e.message.includes("    at "));
+        }
+        try {
+          const fixed = 1;
+          fixed = 2;
+        } catch (e) {
+          console.log(e instanceof TypeError, e.message);
+        }
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "true ReferenceError",
+            "'undefinedFn' is not defined",
+            "ReferenceError: 'undefinedFn' is not defined",
+            "true false",
+            "true Assignment to constant variable 'fixed'",
+        ]
+    );
+}
+
+#[test]
+fn step_limit_is_not_catchable() {
+    let source = r#"
+        let caught = false;
+        try {
+          while (true) {}
+        } catch (e) {
+          caught = true;
+        }
+    "#;
+    let program = parse(lex(source).expect("lex failed")).expect("parse failed");
+    let mut interp = Interpreter::new();
+    interp.set_max_steps(1_000);
+    let err = interp
+        .run(&program)
+        .expect_err("step limit should escape try");
+    assert!(matches!(err, RuntimeError::StepLimitExceeded), "{err:?}");
+    assert!(!err.is_catchable());
+}
+
+#[test]
+fn nullish_property_access_throws_descriptive_type_errors() {
+    let output = run_and_capture(
+        r#"
+        function report(f) {
+          try {
+            f();
+            console.log("no error");
+          } catch (e) {
+            console.log(e instanceof TypeError, e.message);
+          }
+        }
+        let user = { profile: undefined };
+        let sideEffects = 0;
+        report(() => null.name);
+        report(() => user.profile.name);
+        report(() => user.profile[0]);
+        report(() => user.profile.greet(sideEffects++));
+        report(() => { user.profile.name = "x"; });
+        report(() => { const { a } = undefined; });
+        report(() => { const { b } = null; });
+        report(() => { const [c] = undefined; });
+        report(() => delete user.profile.name);
+        console.log(sideEffects, user.profile?.name);
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "true Cannot read properties of null (reading 'name')",
+            "true Cannot read properties of undefined (reading 'name'): user.profile is undefined",
+            "true Cannot read properties of undefined (reading '0'): user.profile is undefined",
+            "true Cannot read properties of undefined (reading 'greet'): user.profile is undefined",
+            "true Cannot set properties of undefined (setting 'name'): user.profile is undefined",
+            "true Cannot destructure property 'a' of 'undefined' as it is undefined.",
+            "true Cannot destructure property 'b' of 'null' as it is null.",
+            "true undefined is not iterable",
+            "true Cannot convert undefined or null to object",
+            "0 undefined",
+        ]
+    );
+}
+
+#[test]
+fn syntax_errors_in_function_bodies_are_thrown_on_first_call() {
+    let output = run_and_capture(
+        r#""use strict";
+        function broken() { let = 1; }
+        const later = function () { var static = 1; };
+        console.log("loaded");
+        for (const f of [broken, broken, later]) {
+          try {
+            f();
+          } catch (e) {
+            console.log(e instanceof SyntaxError, e.message);
+          }
+        }
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "loaded",
+            "true expected binding pattern, found Assign (<script>, offset 46)",
+            "true expected binding pattern, found Assign (<script>, offset 46)",
+            "true unexpected token 'static' (<script>, offset 93)",
+        ]
+    );
+}
+
+#[test]
+fn error_cause_round_trips_through_catch() {
+    let output = run_and_capture(
+        r#"
+        const inner = new RangeError("disk full");
+        try {
+          throw new Error("save failed", { cause: inner });
+        } catch (e) {
+          console.log(e.cause === inner, e.cause.message, Object.keys(e).includes("cause"));
+        }
+        console.log(TypeError("x", { cause: undefined }).cause, "cause" in new Error("y", {}));
+        "#,
+    );
+    assert_eq!(output, vec!["true disk full false", "undefined false"]);
+}
+
+#[test]
+fn nested_causes_render_as_an_indented_chain() {
+    let output = run_and_capture(
+        r#"
+        const root = new RangeError("disk full");
+        const middle = new Error("write failed", { cause: root });
+        console.error(new TypeError("save failed", { cause: middle }));
+        const a = new Error("a");
+        a.cause = new Error("b", { cause: a });
+        console.log(a);
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "TypeError: save failed\n  caused by: Error: write failed\n    caused by: RangeError: disk full",
+            "Error: a\n  caused by: Error: b\n    caused by: [Circular]",
+        ]
+    );
+
+    let tokens = lex(r#"throw new Error("outer", { cause: new Error("inner") });"#).unwrap();
+    let mut interp = Interpreter::new();
+    let err = interp.run(&parse(tokens).unwrap()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Uncaught Error: outer\n  caused by: Error: inner"
+    );
+}
+
+#[test]
+fn aggregate_error_collects_its_errors() {
+    let output = run_and_capture(
+        r#"
+        const err = new AggregateError(new Set([new Error("a"), "b"]), "both failed");
+        console.log(err.name, err.message, err.errors.length, err.errors[1]);
+        console.log(err instanceof AggregateError, err instanceof Error, Object.keys(err).includes("errors"));
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec!["AggregateError both failed 2 b", "true true false"]
+    );
+}
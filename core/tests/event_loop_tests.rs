@@ -1,6 +1,12 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use crustyjs::errors::{CrustyError, RuntimeError};
 use crustyjs::lexer::lex;
 use crustyjs::parser::parse;
-use crustyjs::runtime::interpreter::Interpreter;
+use crustyjs::runtime::interpreter::{Interpreter, YieldDecision};
+use crustyjs::{Context, Value};
 
 fn run_and_capture(source: &str) -> Vec<String> {
     let tokens = lex(source).expect("lexing should succeed");
@@ -52,6 +58,23 @@ fn interval_can_be_cleared() {
     assert_eq!(output, vec!["1", "2", "3"]);
 }
 
+#[test]
+fn realtime_timers_sleep_outside_a_tokio_runtime() {
+    let tokens = lex(r#"
+        setTimeout(() => console.log("t"), 10);
+        (async () => {
+          await new Promise(r => setTimeout(r, 5));
+          console.log("awaited");
+        })();
+        "#)
+    .expect("lexing should succeed");
+    let program = parse(tokens).expect("parsing should succeed");
+    let mut interp = Interpreter::new_with_realtime_timers(true);
+    interp.run(&program).expect("execution should succeed");
+
+    assert_eq!(interp.output(), ["awaited", "t"]);
+}
+
 #[test]
 fn queue_microtask_runs_before_timeout() {
     let output = run_and_capture(
@@ -63,3 +86,314 @@ fn queue_microtask_runs_before_timeout() {
 
     assert_eq!(output, vec!["microtask", "timeout"]);
 }
+
+#[test]
+fn throwing_timer_does_not_stop_later_timers() {
+    let output = run_and_capture(
+        r#"
+        function fail() { throw new Error("middle"); }
+        setTimeout(() => console.log("first"), 10);
+        setTimeout(() => fail(), 20);
+        setTimeout(() => console.log("third"), 30);
+        "#,
+    );
+
+    assert_eq!(output[0], "first");
+    assert!(
+        output[1].starts_with("Uncaught Error: middle\n    at fail"),
+        "{output:?}"
+    );
+    assert_eq!(output[2], "third");
+}
+
+#[test]
+fn uncaught_exception_hook_receives_timer_errors() {
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&errors);
+    let mut ctx = Context::new_with_realtime(false);
+    ctx.on_uncaught_exception(move |err| {
+        let RuntimeError::Thrown { value } = err else {
+            panic!("expected a thrown value, got {err}");
+        };
+        let Value::Object(obj) = &**value else {
+            panic!("expected an error object, got {err}");
+        };
+        let message = obj.borrow().get("message").unwrap();
+        seen.lock().unwrap().push(message.to_string());
+    });
+
+    ctx.eval(
+        r#"
+        let ran = 0;
+        setTimeout(() => { ran = ran + 1; throw new TypeError("bad"); }, 5);
+        setTimeout(() => { ran = ran + 1; }, 10);
+        "#,
+    )
+    .expect("timer errors should not fail the run");
+    assert_eq!(ctx.get_global("ran").unwrap(), Value::Number(2.0));
+    assert_eq!(*errors.lock().unwrap(), vec!["bad"]);
+    assert!(ctx.output().is_empty());
+
+    let err = ctx.eval("throw new Error('sync');").unwrap_err();
+    assert!(err.to_string().contains("sync"), "{err}");
+}
+
+#[test]
+fn yield_hook_runs_every_interval_and_can_interrupt() {
+    let calls = Rc::new(Cell::new(0));
+    let seen = Rc::clone(&calls);
+    let mut ctx = Context::new_with_realtime(false);
+    ctx.set_yield_hook(
+        100,
+        Box::new(move |yielded| {
+            seen.set(seen.get() + 1);
+            assert!(yielded.steps() >= 100);
+            YieldDecision::Continue
+        }),
+    );
+    ctx.eval("let n = 0; while (n < 1000) n++;").unwrap();
+    assert!(calls.get() >= 10, "{}", calls.get());
+    assert_eq!(calls.get(), ctx.step_count() / 100);
+
+    let calls = Rc::new(Cell::new(0));
+    let seen = Rc::clone(&calls);
+    ctx.set_yield_hook(
+        50,
+        Box::new(move |_| {
+            seen.set(seen.get() + 1);
+            if seen.get() == 3 {
+                YieldDecision::Interrupt
+            } else {
+                YieldDecision::Continue
+            }
+        }),
+    );
+    let err = ctx
+        .eval("try { for (;;) {} } catch (e) { console.log('caught'); }")
+        .unwrap_err();
+    assert!(
+        matches!(err, CrustyError::Runtime(RuntimeError::Interrupted)),
+        "{err}"
+    );
+    assert_eq!(calls.get(), 3);
+    assert!(ctx.output().is_empty());
+}
+
+#[test]
+fn self_queueing_microtask_hits_the_microtask_limit() {
+    let mut ctx = Context::builder()
+        .realtime_timers(false)
+        .microtask_limit(1_000)
+        .build();
+    let err = ctx
+        .eval("let runs = 0; function loop() { runs++; queueMicrotask(loop); } loop();")
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            CrustyError::Runtime(RuntimeError::MicrotaskLimitExceeded { limit: 1_000 })
+        ),
+        "{err}"
+    );
+    assert!(err.to_string().contains("infinite microtask loop"), "{err}");
+    assert_eq!(ctx.eval_value("runs").unwrap(), Value::Number(1_001.0));
+}
+
+#[test]
+fn long_promise_chains_stay_under_the_default_microtask_limit() {
+    let output = run_and_capture(
+        r#"
+        let p = Promise.resolve(0);
+        for (let i = 0; i < 10000; i++) p = p.then(n => n + 1);
+        p.then(n => console.log(n));
+        "#,
+    );
+    assert_eq!(output, ["10000"]);
+}
+
+const BURN: &str = r#"
+    setTimeout(() => {
+      const gap = performance.now() - burnEnd;
+      console.log(burnEnd === 0 ? "timer during burn" : gap < 10 ? "timer on time" : "timer late");
+    }, 10);
+    let burnEnd = 0;
+    const start = performance.now();
+    while (performance.now() - start < 50) {}
+    burnEnd = performance.now();
+    console.log("burn done");
+"#;
+
+#[test]
+fn timers_due_during_a_burn_fire_right_after_it() {
+    let mut ctx = Context::new();
+    ctx.eval(BURN).unwrap();
+    assert_eq!(ctx.output(), ["burn done", "timer on time"]);
+}
+
+#[test]
+fn yield_hook_can_service_timers_mid_script() {
+    let mut ctx = Context::new();
+    ctx.set_yield_hook(
+        200,
+        Box::new(|yielded| {
+            if yielded.next_timer_due_in() == Some(0) {
+                YieldDecision::ServiceTimers
+            } else {
+                YieldDecision::Continue
+            }
+        }),
+    );
+    ctx.eval(BURN).unwrap();
+    assert_eq!(ctx.output(), ["timer during burn", "burn done"]);
+}
+
+fn context_logging_to(log: &Arc<Mutex<Vec<String>>>) -> Context {
+    let mut ctx = Context::new();
+    let log = Arc::clone(log);
+    ctx.set_global_function("record", move |args| {
+        let entry = args.get(0).map(Value::to_js_string).unwrap_or_default();
+        log.lock().unwrap().push(entry);
+        Ok(Value::Undefined)
+    });
+    ctx
+}
+
+#[tokio::test]
+async fn eval_async_lets_contexts_share_a_runtime_thread() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut first = context_logging_to(&log);
+    let mut second = context_logging_to(&log);
+
+    let (first, second) = tokio::join!(
+        first.eval_async(
+            r#"
+            setTimeout(() => record("a 20"), 20);
+            new Promise((resolve) => setTimeout(resolve, 100)).then(() => record("a 100"));
+            "a done"
+            "#,
+        ),
+        second.eval_async(
+            r#"
+            setTimeout(() => record("b 60"), 60);
+            setTimeout(() => record("b 140"), 140);
+            "b done"
+            "#,
+        ),
+    );
+
+    assert_eq!(first.expect("first eval"), Value::String("a done".into()));
+    assert_eq!(second.expect("second eval"), Value::String("b done".into()));
+    assert_eq!(*log.lock().unwrap(), vec!["a 20", "b 60", "a 100", "b 140"]);
+}
+
+#[tokio::test]
+async fn blocking_eval_inside_a_runtime_waits_for_timers() {
+    let mut ctx = Context::new();
+    let value = ctx
+        .eval_value("let fired = false; setTimeout(() => { fired = true; }, 5); 1")
+        .expect("eval should succeed");
+    assert_eq!(value, Value::Number(1.0));
+    assert_eq!(ctx.get_global("fired").unwrap(), Value::Boolean(true));
+}
+
+#[tokio::test]
+async fn host_tasks_settle_promises_that_scripts_wait_on() {
+    let mut ctx = Context::new();
+    let (query, resolver) = ctx.create_promise();
+    ctx.set_global("query", query);
+    ctx.eval_async(
+        r#"
+        query.then(async (rows) => {
+            const doubled = await Promise.resolve(rows * 2);
+            console.log("rows", rows, doubled);
+        });
+        "#,
+    )
+    .await
+    .expect("eval should succeed");
+    ctx.set_global("query", Value::Undefined);
+    unsafe { ctx.collect_garbage() };
+    assert!(ctx.output().is_empty());
+
+    let rows = tokio::spawn(async {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        21.0
+    })
+    .await
+    .expect("task should finish");
+    resolver
+        .resolve(&mut ctx, Value::Number(rows))
+        .expect("resolve should succeed");
+    resolver
+        .reject(&mut ctx, Value::String("late".into()))
+        .expect("a second settle is ignored");
+    assert!(resolver.is_settled());
+    assert_eq!(ctx.output(), ["rows 21 42"]);
+}
+
+#[tokio::test]
+async fn async_functions_wait_on_host_promises_across_eval_calls() {
+    let mut ctx = Context::new();
+    let (query, resolver) = ctx.create_promise();
+    let (ping, rejecter) = ctx.create_promise();
+    ctx.set_global("query", query);
+    ctx.set_global("ping", ping);
+    ctx.eval_async(
+        r#"
+        let calls = 0;
+        async function load(query, ping) {
+            calls++;
+            const rows = [calls, await query, calls];
+            try {
+                await ping;
+            } catch (e) {
+                console.log("caught", e);
+            }
+            return rows;
+        }
+        load(query, ping).then((rows) => console.log("rows", rows.join(",")));
+        "#,
+    )
+    .await
+    .expect("eval should succeed");
+    ctx.set_global("query", Value::Undefined);
+    ctx.set_global("ping", Value::Undefined);
+    unsafe { ctx.collect_garbage() };
+    assert!(ctx.output().is_empty());
+
+    let rows = tokio::spawn(async {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        21.0
+    })
+    .await
+    .expect("task should finish");
+    resolver
+        .resolve(&mut ctx, Value::Number(rows))
+        .expect("resolve should succeed");
+    assert!(ctx.output().is_empty());
+    rejecter
+        .reject(&mut ctx, Value::String("offline".into()))
+        .expect("reject should succeed");
+    assert_eq!(ctx.output(), ["caught offline", "rows 1,21,1"]);
+}
+
+#[test]
+fn manual_timers_defer_host_settled_reactions() {
+    let mut ctx = Context::new_with_realtime(false);
+    ctx.set_manual_timers(true);
+    let (pending, resolver) = ctx.create_promise();
+    ctx.set_global("pending", pending);
+    ctx.eval("pending.catch((e) => console.log('caught', e));")
+        .expect("eval should succeed");
+
+    resolver
+        .clone()
+        .reject(&mut ctx, Value::String("timeout".into()))
+        .expect("reject should succeed");
+    assert!(ctx.output().is_empty());
+    resolver
+        .resolve(&mut ctx, Value::Number(1.0))
+        .expect("a second settle is ignored");
+    ctx.run_microtasks().expect("microtasks should run");
+    assert_eq!(ctx.output(), ["caught timeout"]);
+}
@@ -0,0 +1,230 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use crustyjs::{Context, ContextBuilder, FetchPolicy, OutputMode};
+
+/// Serves each connection on its own thread until the test process exits
+/// and gives back the server's base URL.
+fn start_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            thread::spawn(move || respond(stream));
+        }
+    });
+    base
+}
+
+fn respond(stream: TcpStream) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut token = String::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':').unwrap();
+        match name.to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().unwrap(),
+            "x-token" => token = value.trim().to_string(),
+            _ => {}
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    let body = String::from_utf8(body).unwrap();
+
+    let mut location = None;
+    let (status, content_type, payload) = match path.as_str() {
+        "/hello" => ("200 OK", "text/plain", "hello from rust".to_string()),
+        "/moved" => {
+            location = Some("/hello");
+            ("301 Moved Permanently", "text/plain", String::new())
+        }
+        "/elsewhere" => {
+            location = Some("http://example.com/hello");
+            ("302 Found", "text/plain", String::new())
+        }
+        "/echo" => (
+            "201 Created",
+            "application/json",
+            format!(r#"{{"method":"{method}","token":"{token}","body":{body}}}"#),
+        ),
+        _ => ("404 Not Found", "text/plain", "missing".to_string()),
+    };
+    let location = location.map_or(String::new(), |to| format!("Location: {to}\r\n"));
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nX-Served-By: test\r\n\
+         {location}Content-Length: {}\r\nConnection: close\r\n\r\n{payload}",
+        payload.len()
+    )
+    .unwrap();
+}
+
+fn context_with_base(base: &str) -> Context {
+    let mut ctx = Context::new();
+    ctx.set_output_mode(OutputMode::Capture);
+    ctx.set_global("base", crustyjs::Value::String(base.into()));
+    ctx
+}
+
+#[test]
+fn fetch_resolves_with_a_response() {
+    let mut ctx = context_with_base(&start_server());
+    ctx.eval(
+        r#"
+        async function main() {
+            const res = await fetch(base + "/hello");
+            console.log(res.status, res.ok, res.statusText);
+            console.log(res.headers.get("content-type"), res.headers.get("X-SERVED-BY"));
+            console.log(res.headers.get("x-missing"), await res.text());
+        }
+        main();
+        "#,
+    )
+    .expect("eval should succeed");
+    assert_eq!(
+        ctx.output(),
+        ["200 true OK", "text/plain test", "null hello from rust"]
+    );
+}
+
+#[test]
+fn fetch_sends_method_headers_and_body() {
+    let mut ctx = context_with_base(&start_server());
+    ctx.eval(
+        r#"
+        fetch(base + "/echo", {
+            method: "post",
+            headers: { "X-Token": "abc" },
+            body: JSON.stringify({ n: 1 }),
+        })
+            .then((res) => {
+                console.log(res.status, res.ok);
+                return res.json();
+            })
+            .then((data) => console.log(data.method, data.token, data.body.n));
+        fetch(base + "/nowhere").then((res) => console.log(res.status, res.ok));
+        "#,
+    )
+    .expect("eval should succeed");
+    let mut output = ctx.output().to_vec();
+    output.sort();
+    assert_eq!(output, ["201 true", "404 false", "POST abc 1"]);
+}
+
+#[test]
+fn failed_requests_reject_with_a_type_error() {
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", closed.local_addr().unwrap());
+    drop(closed);
+    let mut ctx = context_with_base(&url);
+    ctx.eval(
+        r#"
+        fetch(base).catch((e) => {
+            console.log(e instanceof TypeError, e.message.indexOf("fetch failed: ") === 0);
+            console.log(typeof e.cause, e.message === "fetch failed: " + e.cause);
+        });
+        fetch("ftp://example.com/file").catch((e) => console.log(e.message));
+        "#,
+    )
+    .expect("eval should succeed");
+    let mut output = ctx.output().to_vec();
+    output.sort();
+    assert_eq!(
+        output,
+        [
+            "fetch failed: invalid URL 'ftp://example.com/file'",
+            "string true",
+            "true true",
+        ]
+    );
+}
+
+#[test]
+fn fetch_policy_limits_hosts() {
+    let base = start_server();
+    let mut ctx = ContextBuilder::new()
+        .fetch_policy(FetchPolicy::AllowHosts(vec!["example.com".into()]))
+        .build();
+    ctx.set_global("base", crustyjs::Value::String(base.as_str().into()));
+    let script = r#"fetch(base + "/hello").then(
+        (res) => console.log("fetched", res.status),
+        (e) => console.log(e.name, e.message),
+    );"#;
+    ctx.eval(script).expect("eval should succeed");
+
+    ctx.set_fetch_policy(FetchPolicy::AllowHosts(vec!["127.0.0.1".into()]));
+    ctx.eval(script).expect("eval should succeed");
+    ctx.set_fetch_policy(FetchPolicy::Disabled);
+    ctx.eval(script).expect("eval should succeed");
+    assert_eq!(
+        ctx.output(),
+        [
+            "TypeError fetch failed: host '127.0.0.1' is not allowed",
+            "fetched 200",
+            "TypeError fetch failed: fetch is disabled",
+        ]
+    );
+}
+
+#[test]
+fn redirects_are_checked_against_the_policy() {
+    let base = start_server();
+    let mut ctx = ContextBuilder::new()
+        .fetch_policy(FetchPolicy::AllowHosts(vec!["127.0.0.1".into()]))
+        .build();
+    ctx.set_global("base", crustyjs::Value::String(base.as_str().into()));
+    ctx.eval(
+        r#"
+        fetch(base + "/moved").then((res) => {
+            console.log(res.status, res.url === base + "/hello");
+        });
+        fetch(base + "/elsewhere").then(
+            (res) => console.log("fetched", res.status),
+            (e) => console.log(e.name, e.message),
+        );
+        "#,
+    )
+    .expect("eval should succeed");
+    let mut output = ctx.output().to_vec();
+    output.sort();
+    assert_eq!(
+        output,
+        [
+            "200 true",
+            "TypeError fetch failed: host 'example.com' is not allowed",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn eval_async_waits_for_fetches() {
+    let mut ctx = context_with_base(&start_server());
+    let value = ctx
+        .eval_async(
+            r#"
+            fetch(base + "/hello")
+                .then((res) => res.text())
+                .then((text) => console.log(text.length));
+            "started"
+            "#,
+        )
+        .await
+        .expect("eval should succeed");
+    assert_eq!(value, crustyjs::Value::String("started".into()));
+    assert_eq!(ctx.output(), ["15"]);
+}
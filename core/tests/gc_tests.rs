@@ -1,5 +1,5 @@
 use crustyjs::embedding::handle::HandleScope;
-use crustyjs::runtime::gc::{Gc, GcCell, Heap, Trace, Tracer};
+use crustyjs::runtime::gc::{Gc, GcCell, GcMode, Heap, Trace, Tracer};
 
 #[derive(Default)]
 struct Node {
@@ -12,6 +12,17 @@ impl Trace for Node {
     }
 }
 
+#[derive(Default)]
+struct Links {
+    items: Vec<Gc<GcCell<Links>>>,
+}
+
+impl Trace for Links {
+    fn trace(&self, tracer: &mut Tracer) {
+        self.items.trace(tracer);
+    }
+}
+
 #[test]
 fn collects_unreachable_object() {
     let mut heap = Heap::new();
@@ -71,3 +82,149 @@ fn handle_scope_keeps_value_alive_until_dropped() {
     assert_eq!(freed_stats.collected, 1);
     assert!(!heap.contains(obj));
 }
+
+#[test]
+fn context_collection_keeps_reachable_state() {
+    let mut ctx = crustyjs::Context::new_with_realtime(false);
+    ctx.eval(
+        r#"
+        var kept = { list: [1, 2, { name: "deep" }] };
+        var cache = new WeakMap();
+        var key = {};
+        cache.set(key, "alive");
+        (function () {
+            cache.set({}, "dropped");
+            for (var i = 0; i < 50; i++) {
+                var garbage = { i: i };
+            }
+        })();
+        "#,
+    )
+    .expect("setup should succeed");
+
+    let stats = unsafe { ctx.collect_garbage() };
+    assert!(stats.collected > 0, "{stats:?}");
+
+    ctx.eval("console.log(kept.list[2].name, cache.get(key), cache.has(key));")
+        .expect("reachable values should survive collection");
+    assert_eq!(ctx.output(), ["deep alive true"]);
+}
+
+#[test]
+fn test262_host_gc_collects_after_eval() {
+    let mut ctx = crustyjs::Context::new_with_realtime(false);
+    unsafe { ctx.install_test262_host() };
+    ctx.eval("for (var i = 0; i < 50; i++) { var garbage = { i: i }; } $262.gc();")
+        .expect("eval should succeed");
+    assert_eq!(unsafe { ctx.collect_garbage() }.collected, 0);
+}
+
+#[test]
+fn budgeted_collection_keeps_objects_moved_or_allocated_between_steps() {
+    let mut heap = Heap::new();
+    let root = heap.alloc_cell(Links::default());
+    let mut leaves = Vec::new();
+    for _ in 0..500 {
+        let leaf = heap.alloc_cell(Links::default());
+        let child = heap.alloc_cell(Links { items: vec![leaf] });
+        root.borrow_mut().items.push(child);
+        leaves.push(leaf);
+    }
+    let roots = [root.erase()];
+
+    assert_eq!(heap.collect_budgeted(&roots, 0), None);
+    assert!(heap.is_collecting());
+
+    // Move every leaf onto the already traced root and drop the children,
+    // whether or not the marker has reached them yet.
+    let children = std::mem::take(&mut root.borrow_mut().items);
+    for child in &children {
+        child.borrow_mut().items.clear();
+    }
+    root.borrow_mut().items.extend(leaves.iter().copied());
+
+    let mut added = Vec::new();
+    let stats = loop {
+        let fresh = heap.alloc_cell(Links::default());
+        root.borrow_mut().items.push(fresh);
+        added.push(fresh);
+        if let Some(stats) = heap.collect_budgeted(&roots, 0) {
+            break stats;
+        }
+    };
+    assert!(added.len() > 1, "collection should take several steps");
+    assert!(!heap.is_collecting());
+    assert!(stats.pauses.total() >= added.len() as u64);
+    for gc in leaves.iter().chain(&added) {
+        assert!(heap.contains(*gc));
+    }
+
+    let stats = heap.collect(&roots);
+    assert_eq!(stats.collected, children.len());
+    for gc in leaves.iter().chain(&added) {
+        assert!(heap.contains(*gc));
+    }
+}
+
+#[test]
+fn full_collection_abandons_a_budgeted_one() {
+    let mut heap = Heap::new();
+    let root = heap.alloc_cell(Links::default());
+    for _ in 0..300 {
+        let item = heap.alloc_cell(Links::default());
+        root.borrow_mut().items.push(item);
+    }
+    let garbage = heap.alloc(String::from("garbage"));
+    assert_eq!(heap.collect_budgeted(&[root.erase()], 0), None);
+
+    let kept = root.borrow().items[0];
+    root.borrow_mut().items.clear();
+    root.borrow_mut().items.push(kept);
+    let stats = heap.collect(&[root.erase()]);
+    assert_eq!(stats.collected, 300);
+    assert!(!heap.is_collecting());
+    assert!(!heap.contains(garbage));
+    assert!(heap.contains(kept));
+}
+
+#[test]
+fn budgeted_mode_collects_between_timer_callbacks() {
+    let mut ctx = crustyjs::Context::new_with_realtime(false);
+    ctx.set_manual_timers(true);
+    unsafe {
+        ctx.set_gc_mode(GcMode::Budgeted {
+            budget_micros: 50,
+            full_collect_factor: 1_000,
+        })
+    };
+    ctx.eval(
+        r#"
+        var kept = [];
+        var ticks = 0;
+        setInterval(() => {
+            ticks++;
+            for (var i = 0; i < 200; i++) {
+                var garbage = { i: i, list: [i] };
+            }
+            kept.push({ tick: ticks, data: [ticks] });
+        }, 16);
+        "#,
+    )
+    .expect("setup should succeed");
+    for _ in 0..60 {
+        ctx.advance_time(16).expect("frame should run");
+    }
+    assert!(ctx.gc_pauses().total() > 0);
+
+    ctx.eval(
+        r#"
+        var intact = 0;
+        for (var n = 0; n < kept.length; n++) {
+            if (kept[n].tick === n + 1 && kept[n].data[0] === n + 1) intact++;
+        }
+        console.log(kept.length, intact);
+        "#,
+    )
+    .expect("kept state should survive budgeted collection");
+    assert_eq!(ctx.output(), ["60 60"]);
+}
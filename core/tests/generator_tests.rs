@@ -198,3 +198,347 @@ fn generator_for_of_with_break() {
     "#);
     assert_eq!(out, vec!["10", "20"]);
 }
+
+#[test]
+fn generator_body_runs_lazily_between_yields() {
+    let out = run(r#"
+        function* steps() {
+            console.log("start");
+            const sent = yield 1;
+            console.log("got " + sent);
+            for (let i = 0; i < 3; i++) {
+                if (i === 1) continue;
+                yield i * 10;
+            }
+        }
+        const g = steps();
+        console.log("created");
+        console.log(g.next().value);
+        console.log(g.next("hello").value);
+        console.log(g.next().value);
+        console.log(g.next().done);
+    "#);
+    assert_eq!(
+        out,
+        vec!["created", "start", "1", "got hello", "0", "20", "true"]
+    );
+}
+
+#[test]
+fn generator_return_runs_finally_blocks() {
+    let out = run(r#"
+        function* guarded() {
+            try {
+                yield 1;
+                yield 2;
+            } finally {
+                console.log("cleanup");
+            }
+        }
+        const g = guarded();
+        g.next();
+        const r = g.return(42);
+        console.log(r.value, r.done);
+        console.log(g.next().done);
+    "#);
+    assert_eq!(out, vec!["cleanup", "42 true", "true"]);
+}
+
+#[test]
+fn generator_return_can_yield_from_finally() {
+    let out = run(r#"
+        function* guarded() {
+            try {
+                yield 1;
+            } finally {
+                yield "closing";
+                console.log("closed");
+            }
+        }
+        const g = guarded();
+        g.next();
+        const first = g.return(7);
+        console.log(first.value, first.done);
+        const second = g.next();
+        console.log(second.value, second.done);
+    "#);
+    assert_eq!(out, vec!["closing false", "closed", "7 true"]);
+}
+
+#[test]
+fn generator_throw_is_caught_at_the_paused_yield() {
+    let out = run(r#"
+        function* resilient() {
+            for (const step of [1, 2, 3]) {
+                try {
+                    yield step;
+                } catch (e) {
+                    console.log("caught " + e);
+                    yield "recovered";
+                }
+            }
+        }
+        const g = resilient();
+        console.log(g.next().value);
+        console.log(g.throw("boom").value);
+        console.log(g.next().value);
+        console.log(g.next().value);
+        console.log(g.next().done);
+    "#);
+    assert_eq!(out, vec!["1", "caught boom", "recovered", "2", "3", "true"]);
+}
+
+#[test]
+fn generator_throw_propagates_when_uncaught() {
+    let out = run(r#"
+        function* counter() {
+            try {
+                yield 1;
+                yield 2;
+            } finally {
+                console.log("cleanup");
+            }
+        }
+        const g = counter();
+        g.next();
+        try {
+            g.throw(new Error("stop"));
+        } catch (e) {
+            console.log("rethrown " + e.message);
+        }
+        console.log(g.next().done);
+
+        const fresh = counter();
+        try {
+            fresh.throw("early");
+        } catch (e) {
+            console.log("before start " + e);
+        }
+        console.log(fresh.next().done);
+        try {
+            fresh.throw("late");
+        } catch (e) {
+            console.log("after end " + e);
+        }
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "cleanup",
+            "rethrown stop",
+            "true",
+            "before start early",
+            "true",
+            "after end late",
+        ]
+    );
+}
+
+#[test]
+fn async_generator_interleaves_awaits_with_consumer() {
+    let out = run(r#"
+        const sleep = (ms) => new Promise((resolve) => setTimeout(resolve, ms));
+        async function* stream() {
+            try {
+                for (let i = 0; i < 3; i++) {
+                    await sleep(10);
+                    console.log("produce " + i);
+                    yield i;
+                }
+            } finally {
+                console.log("closed");
+            }
+        }
+        setTimeout(() => console.log("timer"), 15);
+        async function consume() {
+            const it = stream();
+            while (true) {
+                const r = await it.next();
+                if (r.done) break;
+                console.log("consume " + r.value);
+            }
+        }
+        consume();
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "produce 0",
+            "consume 0",
+            "timer",
+            "produce 1",
+            "consume 1",
+            "produce 2",
+            "consume 2",
+            "closed",
+        ]
+    );
+}
+
+#[test]
+fn async_generator_settles_queued_requests_in_order() {
+    let out = run(r#"
+        async function* pair() {
+            try {
+                yield await Promise.resolve("a");
+                yield "b";
+                yield "c";
+            } finally {
+                console.log("finally");
+            }
+        }
+        async function main() {
+            const it = pair();
+            const first = it.next();
+            const second = it.next();
+            const closed = it.return("early");
+            const results = [await first, await second, await closed];
+            console.log(results.map((r) => r.value + ":" + r.done).join(" "));
+            console.log((await it.next()).done);
+
+            const failing = pair();
+            await failing.next();
+            try {
+                await failing.throw("boom");
+            } catch (e) {
+                console.log("caught " + e);
+            }
+        }
+        main();
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "finally",
+            "a:false b:false early:true",
+            "true",
+            "finally",
+            "caught boom",
+        ]
+    );
+}
+
+#[test]
+fn for_of_steps_an_endless_generator_and_closes_it_on_break() {
+    let out = run(r#"
+        let closed = false;
+        function* naturals() {
+            let i = 0;
+            try {
+                while (true) yield i++;
+            } finally {
+                closed = true;
+            }
+        }
+        const seen = [];
+        for (const n of naturals()) {
+            if (n > 3) break;
+            seen.push(n);
+        }
+        console.log(seen.join(","), closed);
+
+        let returned = 0;
+        const counter = {
+            [Symbol.iterator]() {
+                let i = 0;
+                return {
+                    next: () => ({ value: i++, done: false }),
+                    return: () => { returned++; return { done: true }; },
+                };
+            },
+        };
+        function firstOver(limit) {
+            for (const n of counter) {
+                if (n > limit) return n;
+            }
+        }
+        console.log(firstOver(2), returned);
+        try {
+            for (const n of counter) throw "stop";
+        } catch (e) {
+            console.log(e, returned);
+        }
+    "#);
+    assert_eq!(out, vec!["0,1,2,3 true", "3 1", "stop 2"]);
+}
+
+#[test]
+fn yield_star_delegates_one_value_at_a_time() {
+    let out = run(r#"
+        function* inner() {
+            const sent = yield "a";
+            console.log("inner got " + sent);
+            return "done";
+        }
+        function* outer() {
+            const result = yield* inner();
+            console.log("yield* gave " + result);
+            yield* [1, 2];
+        }
+        const it = outer();
+        console.log(it.next().value);
+        console.log(it.next("x").value);
+        console.log(it.next().value);
+        console.log(it.next().done);
+
+        function* endless() {
+            let i = 0;
+            while (true) yield i++;
+        }
+        function* firstTwo() {
+            const it = endless();
+            yield* { [Symbol.iterator]: () => it };
+        }
+        const two = firstTwo();
+        console.log(two.next().value, two.next().value);
+
+        function* guarded() {
+            try {
+                yield* inner();
+            } catch (e) {
+                console.log("caught " + e);
+            }
+        }
+        const g = guarded();
+        g.next();
+        g.throw("boom");
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "a",
+            "inner got x",
+            "yield* gave done",
+            "1",
+            "2",
+            "true",
+            "0 1",
+            "caught boom",
+        ]
+    );
+}
+
+#[test]
+fn resuming_an_expression_does_not_rerun_its_earlier_operands() {
+    let out = run(r#"
+        let calls = 0;
+        function side() {
+            calls++;
+            return calls * 10;
+        }
+        function* gen() {
+            const r = [side(), yield 1, side()];
+            return r;
+        }
+        const it = gen();
+        it.next();
+        console.log(JSON.stringify(it.next(5).value), calls);
+
+        function* nested() {
+            return yield yield 1;
+        }
+        const n = nested();
+        console.log(n.next().value, n.next("a").value, n.next("b").value);
+    "#);
+    assert_eq!(out, vec!["[10,5,20] 2", "1 a b"]);
+}
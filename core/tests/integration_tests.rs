@@ -47,5 +47,5 @@ fn engine_context_optional_chaining_flow() {
     ctx.eval("let data = null; let out = data?.nested?.value ?? 'none';")
         .expect("script should evaluate");
     let out = ctx.get_global("out").expect("out should exist");
-    assert_eq!(out, Value::String("none".to_string()));
+    assert_eq!(out, Value::String("none".into()));
 }
@@ -2,6 +2,7 @@ use crustyjs::errors::RuntimeError;
 use crustyjs::lexer::lex;
 use crustyjs::parser::parse;
 use crustyjs::runtime::interpreter::Interpreter;
+use crustyjs::runtime::value::{JsValue, primitive_less_than};
 
 fn run_and_capture(source: &str) -> Vec<String> {
     let tokens = lex(source).expect("lexing should succeed");
@@ -166,6 +167,52 @@ fn compound_assignment_and_modulo() {
     assert_eq!(output, vec!["19.5", "3.5"]);
 }
 
+#[test]
+fn logical_assignment_skips_rhs_when_short_circuited() {
+    let output = run_and_capture(
+        r#"
+        let calls = 0;
+        function f(v) { calls++; return v; }
+        let a = 1; a ||= f(2);
+        let b = 0; b ||= f(3);
+        let c = 0; c &&= f(4);
+        let d = 5; d &&= f(6);
+        let e = 0; e ??= f(7);
+        let g; g ??= f(8);
+        const k = "kept";
+        k ||= f("never");
+        k ??= f("never");
+        console.log(a, b, c, d, e, g, k, calls);
+        "#,
+    );
+    assert_eq!(output, vec!["1 3 0 6 0 8 kept 3"]);
+}
+
+#[test]
+fn logical_assignment_to_members_evaluates_target_once() {
+    let output = run_and_capture(
+        r#"
+        let sets = 0, lookups = 0, rhs = 0;
+        let config = {
+            _retries: 2,
+            get retries() { return this._retries; },
+            set retries(v) { sets++; this._retries = v; }
+        };
+        function target() { lookups++; return config; }
+        function value(v) { rhs++; return v; }
+        target().retries ??= value(3);
+        target()["retries"] ||= value(4);
+        target().retries &&= value(5);
+        let flags = {};
+        flags.verbose ||= true;
+        flags["level"] ??= 1;
+        flags.level += 2;
+        console.log(config.retries, sets, lookups, rhs, flags.verbose, flags.level);
+        "#,
+    );
+    assert_eq!(output, vec!["5 1 3 1 true 3"]);
+}
+
 #[test]
 fn prefix_and_postfix_updates() {
     let output = run_and_capture(
@@ -250,6 +297,65 @@ fn for_in_loop_over_object_keys() {
     assert!(output.contains(&"b".to_string()));
 }
 
+#[test]
+fn for_in_walks_the_prototype_chain_in_order() {
+    let output = run_and_capture(
+        r#"
+        const base = { a: 1, shared: "base", hidden: "base" };
+        const mid = Object.create(base);
+        mid.b = 2;
+        mid.shared = "mid";
+        Object.defineProperty(mid, "hidden", { value: 1, enumerable: false });
+        const obj = Object.create(mid);
+        obj[2] = "x";
+        obj.c = 3;
+        obj[1] = "y";
+        const keys = [];
+        for (const key in obj) keys.push(key);
+        console.log(keys.join(","));
+        "#,
+    );
+    assert_eq!(output, vec!["1,2,c,b,shared,a"]);
+}
+
+#[test]
+fn for_in_skips_keys_deleted_during_the_loop() {
+    let output = run_and_capture(
+        r#"
+        const proto = { inherited: true };
+        const obj = Object.create(proto);
+        obj.x = 1;
+        obj.y = 2;
+        obj.z = 3;
+        for (const key in obj) {
+            console.log(key);
+            delete obj.y;
+            delete proto.inherited;
+            obj.added = 4;
+        }
+        "#,
+    );
+    assert_eq!(output, vec!["x", "z"]);
+}
+
+#[test]
+fn for_in_over_a_proxy_checks_each_descriptor() {
+    let output = run_and_capture(
+        r#"
+        const target = { shown: 1, masked: 2 };
+        const proxy = new Proxy(target, {
+            getOwnPropertyDescriptor(t, key) {
+                const desc = Object.getOwnPropertyDescriptor(t, key);
+                if (key === "masked") desc.enumerable = false;
+                return desc;
+            }
+        });
+        for (const key in proxy) console.log(key);
+        "#,
+    );
+    assert_eq!(output, vec!["shown"]);
+}
+
 #[test]
 fn const_reassignment_throws() {
     let err = run_and_error("const x = 10; x = 20;");
@@ -300,3 +406,266 @@ fn performance_now_is_available() {
     let output = run_and_capture("console.log(performance.now() >= 0);");
     assert_eq!(output, vec!["true"]);
 }
+
+/// Times the microbenchmarks local-name resolution is measured by. Run
+/// with `cargo test --release -p core --test interpreter_tests --
+/// --ignored --nocapture local_lookup_benchmark`.
+#[test]
+#[ignore = "benchmark"]
+fn local_lookup_benchmark() {
+    let cases = [
+        (
+            "fib(25)",
+            "function fib(n) { if (n < 2) { return n; } return fib(n - 1) + fib(n - 2); }
+            console.log(fib(25));",
+            "75025",
+        ),
+        (
+            "loop(1e6)",
+            "function sum(limit) {
+                let total = 0;
+                for (let i = 0; i < limit; i++) { let sq = i * i; total = total + sq % 7; }
+                return total;
+            }
+            console.log(sum(1000000));",
+            "1999998",
+        ),
+    ];
+    for (name, source, expected) in cases {
+        let program =
+            parse(lex(source).expect("lexing should succeed")).expect("parsing should succeed");
+        let mut best = std::time::Duration::MAX;
+        for _ in 0..3 {
+            let mut interp = Interpreter::new();
+            let started = std::time::Instant::now();
+            interp.run(&program).expect("execution should succeed");
+            best = best.min(started.elapsed());
+            assert_eq!(interp.output(), [expected]);
+        }
+        println!("{name}: {best:?}");
+    }
+}
+
+#[test]
+fn closures_from_one_site_share_function_body() {
+    let body_of = |value: &crustyjs::Value| match value {
+        crustyjs::Value::Function { body, .. } => body.stmts().expect("body should parse"),
+        other => panic!("expected a function value, got {other:?}"),
+    };
+    let mut ctx = crustyjs::Context::new();
+    ctx.eval(
+        r#"
+        function make() {
+            return [(x) => x + 1, (x) => { return x * 2; }, { get v() { return 1; } }];
+        }
+        var first = make();
+        var second = make();
+        "#,
+    )
+    .expect("eval should succeed");
+    for site in [
+        "$[0]",
+        "$[1]",
+        "Object.getOwnPropertyDescriptor($[2], 'v').get",
+    ] {
+        let mut closure = |array: &str| {
+            ctx.eval_value(&site.replace('$', array))
+                .expect("closure should exist")
+        };
+        let (a, b) = (closure("first"), closure("second"));
+        assert!(std::rc::Rc::ptr_eq(&body_of(&a), &body_of(&b)));
+    }
+
+    let mut ctx = crustyjs::Context::new();
+    ctx.eval("function f(a, b) { return a + b; }")
+        .expect("eval should succeed");
+    let f = ctx.get_global("f").expect("f should be defined");
+    let copy = f.clone();
+    match (&f, &copy) {
+        (
+            crustyjs::Value::Function {
+                body: a,
+                params: pa,
+                ..
+            },
+            crustyjs::Value::Function {
+                body: b,
+                params: pb,
+                ..
+            },
+        ) => {
+            assert!(a.ptr_eq(b));
+            assert!(std::rc::Rc::ptr_eq(pa, pb));
+        }
+        _ => panic!("expected a function value"),
+    }
+}
+
+#[test]
+fn exponentiation_operator() {
+    let output = run_and_capture(
+        r#"
+        console.log(2 ** 10);
+        console.log(2 ** 3 ** 2);
+        console.log((-2) ** 2);
+        console.log(2 * 3 ** 2);
+        let x = 3;
+        x **= 2;
+        console.log(x);
+        console.log(1 ** Infinity, NaN ** 0, 2 ** NaN);
+        "#,
+    );
+    assert_eq!(output, vec!["1024", "512", "4", "18", "9", "NaN 1 NaN"]);
+}
+
+#[test]
+fn multiple_declarators_and_for_init() {
+    let output = run_and_capture(
+        r#"
+        const items = [1, 2, 3];
+        let total = 0;
+        for (let i = 0, n = items.length; i < n; i++) {
+            total += items[i];
+        }
+        let { p } = { p: 4 }, q = p + 1, [r] = [6];
+        var unset, set = 7;
+        console.log(total, p, q, r, unset, set);
+        "#,
+    );
+    assert_eq!(output, vec!["6 4 5 6 undefined 7"]);
+}
+
+#[test]
+fn function_declarations_are_callable_before_definition() {
+    let output = run_and_capture(
+        r#"
+        console.log(isEven(10), typeof later, early);
+        function isEven(n) { return n === 0 ? true : isOdd(n - 1); }
+        function isOdd(n) { return n === 0 ? false : isEven(n - 1); }
+        var early = 1;
+        function later() {}
+        function outer() { return inner(); function inner() { return "inner"; } }
+        console.log(outer());
+        { console.log(block()); function block() { return "block"; } }
+        console.log(typeof block);
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec!["true function undefined", "inner", "block", "undefined"]
+    );
+}
+
+#[test]
+fn var_and_function_sharing_a_name_hoist_together() {
+    let output = run_and_capture(
+        r#"
+        console.log(typeof shared);
+        var shared = 5;
+        function shared() {}
+        console.log(typeof shared);
+        function scoped(flag) {
+            if (flag) { var inside = "set"; }
+            return inside;
+        }
+        console.log(scoped(true), scoped(false));
+        "#,
+    );
+    assert_eq!(output, vec!["function", "number", "set undefined"]);
+}
+
+#[test]
+fn function_declarations_in_blocks_are_block_scoped() {
+    let output = run_and_capture(
+        r#"
+        if (true) {
+            console.log(early());
+            function early() { return "hoisted"; }
+        }
+        console.log(typeof early);
+        function choose(flag) {
+            if (flag) { function pick() { return "then"; } return pick(); }
+            else { function pick() { return "else"; } return pick(); }
+        }
+        console.log(choose(true), choose(false));
+        function unreached() {
+            if (false) { function never() {} }
+            return typeof never;
+        }
+        console.log(unreached());
+        switch (1) {
+            case 1:
+                console.log(inCase());
+                function inCase() { return "case"; }
+        }
+        const made = [];
+        for (let i = 0; i < 2; i++) { function item() { return i; } made.push(item); }
+        console.log(made[0](), made[1](), made[0] === made[1]);
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "hoisted",
+            "undefined",
+            "then else",
+            "undefined",
+            "case",
+            "0 1 false",
+        ]
+    );
+}
+
+#[test]
+fn relational_operators_use_abstract_relational_comparison() {
+    let output = run_and_capture(
+        r#"
+        console.log("apple" < "banana", "Z" < "a", "10" < "9", "abc" > "ab");
+        console.log("10" < 9, "x" < 1, "x" >= 1, " 2 " <= 2);
+        console.log(undefined < 1, undefined >= 0, undefined <= undefined);
+        console.log(null >= 0, null > 0, null <= 0, null < 1);
+        const five = { valueOf() { return 5; } };
+        const named = { valueOf() { return {}; }, toString() { return "b"; } };
+        console.log(five > 4, five < "6", five >= 5, named > "a", named < "a");
+        console.log([2] > 1, [1, 2] < 3, 1 < 2 < 3, 3 > 2 > 1);
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "true true true true",
+            "false false false true",
+            "false false false",
+            "true false true true",
+            "true true true true false",
+            "true false true false",
+        ]
+    );
+}
+
+#[test]
+fn strings_compare_by_utf16_code_units() {
+    let halfwidth = JsValue::String("\u{FF61}".into());
+    let emoji = JsValue::String("\u{1F600}".into());
+    // U+1F600 is the surrogate pair D83D DE00, which sorts before FF61.
+    assert_eq!(primitive_less_than(&emoji, &halfwidth), Some(true));
+    assert_eq!(primitive_less_than(&halfwidth, &emoji), Some(false));
+}
+
+#[test]
+fn comma_operator_in_loop_heads_conditions_and_returns() {
+    let output = run_and_capture(
+        r#"
+        const n = 6;
+        const pairs = [];
+        for (let i = 0, j = n; i < j; i++, j--) pairs.push(i + ":" + j);
+        console.log(pairs.join(" "));
+        function pick(a, b) { return (a, b); }
+        let calls = 0;
+        if (calls++, calls > 0) console.log(pick(1, 2), calls);
+        const o = { k: "v" };
+        console.log(o[calls, "k"], `${1, 2}`);
+        "#,
+    );
+    assert_eq!(output, vec!["0:6 1:5 2:4", "2 1", "v 2"]);
+}
@@ -101,3 +101,60 @@ fn spread_custom_iterable() {
     "#);
     assert_eq!(out, vec!["3", "1", "3"]);
 }
+
+#[test]
+fn iterator_helpers_chain_over_map_entries() {
+    let out = run(r#"
+        const m = new Map([["a", 1], ["b", 2], ["c", 3], ["d", 4]]);
+        const picked = m
+            .entries()
+            .map(([k, v]) => k + v)
+            .filter((s) => s !== "b2")
+            .take(2)
+            .toArray();
+        console.log(picked.join(","));
+        console.log(m.keys().flatMap((k) => [k, k.toUpperCase()]).toArray().join(""));
+        console.log(m.values().reduce((sum, v) => sum + v), m.values().some((v) => v > 3));
+    "#);
+    assert_eq!(out, vec!["a1,c3", "aAbBcCdD", "10 true"]);
+}
+
+#[test]
+fn iterator_helpers_are_lazy_over_endless_generators() {
+    let out = run(r#"
+        function* naturals() {
+            let i = 0;
+            while (true) yield i++;
+        }
+        console.log(naturals().drop(3).take(4).toArray().join(","));
+        console.log(naturals().map((x) => x * x).find((x) => x > 50));
+    "#);
+    assert_eq!(out, vec!["3,4,5,6", "64"]);
+}
+
+#[test]
+fn iterator_helpers_close_the_underlying_iterator() {
+    let out = run(r#"
+        let closed = 0;
+        function* letters() {
+            try {
+                yield "a";
+                yield "b";
+                yield "c";
+            } finally {
+                closed++;
+            }
+        }
+        console.log(letters().take(1).toArray().join(","), closed);
+        console.log(letters().every((l) => l === "a"), closed);
+        const helper = letters().map((l) => l.toUpperCase());
+        helper.next();
+        console.log(helper.return().done, helper.next().done, closed);
+        try {
+            letters().take(-1);
+        } catch (e) {
+            console.log(e.name);
+        }
+    "#);
+    assert_eq!(out, vec!["a 1", "false 2", "true true 3", "RangeError"]);
+}
@@ -81,6 +81,48 @@ fn lex_comparison_operators() {
     );
 }
 
+#[test]
+fn lex_bitwise_operators() {
+    let kinds = token_kinds("| ^ ~ << >> >>> &= |= ^= <<= >>= >>>= ||");
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Pipe,
+            TokenKind::Caret,
+            TokenKind::Tilde,
+            TokenKind::LessLess,
+            TokenKind::GreaterGreater,
+            TokenKind::GreaterGreaterGreater,
+            TokenKind::AmpEquals,
+            TokenKind::PipeEquals,
+            TokenKind::CaretEquals,
+            TokenKind::LessLessEquals,
+            TokenKind::GreaterGreaterEquals,
+            TokenKind::GreaterGreaterGreaterEquals,
+            TokenKind::PipePipe,
+            TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn lex_logical_assignment_operators() {
+    let kinds = token_kinds("a &&= b ||= c ??= d");
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Ident("a".into()),
+            TokenKind::AmpAmpEquals,
+            TokenKind::Ident("b".into()),
+            TokenKind::PipePipeEquals,
+            TokenKind::Ident("c".into()),
+            TokenKind::NullishEquals,
+            TokenKind::Ident("d".into()),
+            TokenKind::Eof,
+        ]
+    );
+}
+
 #[test]
 fn lex_string_literal() {
     let kinds = token_kinds(r#""hello world""#);
@@ -260,3 +302,172 @@ fn lex_typeof_and_loose_equality_tokens() {
         ]
     );
 }
+
+#[test]
+fn lex_numeric_separators_and_radix_literals() {
+    let kinds = token_kinds("1_000_000 0b1010 0o7_55 0xFF_FF 1_0.2_5 1.5e1_0 0.5");
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Number(1_000_000.0),
+            TokenKind::Number(10.0),
+            TokenKind::Number(493.0),
+            TokenKind::Number(65535.0),
+            TokenKind::Number(10.25),
+            TokenKind::Number(1.5e10),
+            TokenKind::Number(0.5),
+            TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn lex_legacy_octal_and_leading_zero_decimals() {
+    let tokens = lex("0755 089 0").expect("lexing should succeed");
+    let numbers: Vec<(TokenKind, bool)> = tokens
+        .into_iter()
+        .map(|t| (t.kind, t.legacy_octal))
+        .collect();
+    assert_eq!(
+        numbers,
+        vec![
+            (TokenKind::Number(493.0), true),
+            (TokenKind::Number(89.0), true),
+            (TokenKind::Number(0.0), false),
+            (TokenKind::Eof, false),
+        ]
+    );
+}
+
+#[test]
+fn lex_rejects_misplaced_numeric_separators_at_their_offset() {
+    for (source, offset) in [
+        ("1__0", 2),
+        ("1_", 1),
+        ("0x_1", 2),
+        ("1._5", 2),
+        ("1_.5", 1),
+        ("0_1", 1),
+        ("07_7", 2),
+        ("1e_5", 2),
+    ] {
+        let err = lex(source).expect_err(source);
+        assert_eq!(err.span.offset(), offset, "{source}: {}", err.message);
+        assert_eq!(err.span.len(), 1, "{source}");
+    }
+}
+
+#[test]
+fn lex_bigint_literal_reports_unsupported() {
+    let err = lex("let x = 10n;").expect_err("BigInt literal should not lex");
+    assert!(err.message.contains("BigInt"), "{}", err.message);
+    assert_eq!(err.span.offset(), 8);
+    assert_eq!(err.span.len(), 3);
+}
+
+#[test]
+fn lex_rejects_identifier_directly_after_number() {
+    let err = lex("3in x").expect_err("identifier after number should not lex");
+    assert_eq!(err.span.offset(), 1);
+    let err = lex("0x").expect_err("prefix without digits should not lex");
+    assert_eq!(err.span.offset(), 0);
+}
+
+fn regex(pattern: &str, flags: &str) -> TokenKind {
+    TokenKind::RegexLiteral {
+        pattern: pattern.into(),
+        flags: flags.into(),
+    }
+}
+
+fn count_regexes(source: &str) -> usize {
+    token_kinds(source)
+        .iter()
+        .filter(|kind| matches!(kind, TokenKind::RegexLiteral { .. }))
+        .count()
+}
+
+#[test]
+fn lex_slash_after_values_is_division() {
+    for source in [
+        "let ratio = a / b / c;",
+        "x = y\n/z/g.test(a)",
+        "f(a) / 2 / 1",
+        "arr[0] / 2 / 1",
+        "i++ / 2 / 1",
+        "obj.return / 2 / 1",
+        "obj?.typeof / 2 / 1",
+        "`t` / 2 / 1",
+        "`a${b}c` / 2 / 1",
+    ] {
+        assert_eq!(count_regexes(source), 0, "{source}");
+    }
+}
+
+#[test]
+fn lex_slash_after_operators_and_keywords_starts_regex() {
+    for source in [
+        "/re/.test(s)",
+        "return /abc/.test(x)",
+        "a = b / 2; /regex/.test(s)",
+        "if (a) /re/.test(b)",
+        "while (f(x)) /re/.exec(s)",
+        "typeof /x/",
+        "x = [/a/, (/b/), { k: /c/ }]",
+        "case /d/:",
+        "a = b ? /e/ : /f/",
+        "delete /g/.lastIndex",
+        "x instanceof /h/.constructor",
+    ] {
+        assert!(count_regexes(source) > 0, "{source}");
+    }
+    assert_eq!(
+        token_kinds("x = y\n/z/g.test(a); if (a) /re/i.test(b)")[16..18],
+        [TokenKind::RightParen, regex("re", "i")]
+    );
+}
+
+#[test]
+fn lex_template_substitutions_track_braces_and_regexes() {
+    let kinds = token_kinds("`${ {a: 1}.a } ${ /}`/.source }!`");
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::TemplateHead(String::new()),
+            TokenKind::LeftBrace,
+            TokenKind::Ident("a".into()),
+            TokenKind::Colon,
+            TokenKind::Number(1.0),
+            TokenKind::RightBrace,
+            TokenKind::Dot,
+            TokenKind::Ident("a".into()),
+            TokenKind::TemplateMiddle(" ".into()),
+            regex("}`", ""),
+            TokenKind::Dot,
+            TokenKind::Ident("source".into()),
+            TokenKind::TemplateTail("!".into()),
+            TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn lex_template_pieces_keep_their_raw_text() {
+    let tokens = lex("`a\\tb${x}c\\\\\r\nd${y}\\$e`").expect("lexing should succeed");
+    let raw: Vec<(&TokenKind, Option<&str>)> = tokens
+        .iter()
+        .filter(|t| t.raw.is_some())
+        .map(|t| (&t.kind, t.raw.as_deref()))
+        .collect();
+    assert_eq!(
+        raw,
+        vec![
+            (&TokenKind::TemplateHead("a\tb".into()), Some("a\\tb")),
+            (
+                &TokenKind::TemplateMiddle("c\\\r\nd".into()),
+                Some("c\\\\\nd")
+            ),
+            (&TokenKind::TemplateTail("$e".into()), Some("\\$e")),
+        ]
+    );
+}
@@ -1,6 +1,7 @@
 use crustyjs::lexer::lex;
 use crustyjs::parser::parse;
 use crustyjs::runtime::interpreter::Interpreter;
+use crustyjs::{Context, Value};
 use std::fs;
 
 fn run_file(path: &std::path::Path) -> Vec<String> {
@@ -132,3 +133,331 @@ fn module_parse_error_reports_file_line_and_column() {
     assert!(msg.contains("failed to parse module"));
     assert!(msg.contains(":1:"));
 }
+
+#[test]
+fn import_meta_describes_the_module_reading_it() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_f", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+
+    let lib = dir.join("lib.js");
+    let main = dir.join("main.js");
+
+    fs::write(
+        &lib,
+        r#"
+export function libUrl() { return import.meta.url; }
+export const cached = import.meta === import.meta;
+"#,
+    )
+    .expect("write lib");
+    fs::write(
+        &main,
+        r#"
+import { libUrl, cached } from "./lib.js";
+console.log(import.meta.url);
+console.log(libUrl());
+console.log(import.meta.resolve("./lib.js") === libUrl());
+console.log(cached);
+console.log(Object.isFrozen(import.meta));
+"#,
+    )
+    .expect("write main");
+
+    let mut ctx = Context::new();
+    ctx.eval_module(&main).expect("module should run");
+    let url = |path: &std::path::Path| {
+        let path = fs::canonicalize(path).expect("canonicalize");
+        format!("file://{}", path.display())
+    };
+    assert_eq!(
+        ctx.output(),
+        [
+            url(&main),
+            url(&lib),
+            "true".into(),
+            "true".into(),
+            "true".into()
+        ]
+    );
+}
+
+#[test]
+fn import_meta_provider_adds_host_fields() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_g", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+
+    let main = dir.join("main.js");
+    fs::write(&main, "console.log(import.meta.hot, import.meta.file);").expect("write main");
+
+    let mut ctx = Context::new();
+    ctx.set_import_meta_provider(|path, meta| {
+        let file = path.file_name().unwrap().to_string_lossy().to_string();
+        meta.set("hot", Value::Boolean(true))
+            .set("file", Value::String(file.into()));
+    });
+    ctx.eval_module(&main).expect("module should run");
+    assert_eq!(ctx.output(), ["true main.js"]);
+}
+
+#[test]
+fn import_meta_is_a_syntax_error_in_scripts() {
+    let mut ctx = Context::new();
+    for source in ["import.meta.url;", "function f() { return import.meta; }"] {
+        let err = ctx
+            .eval(source)
+            .expect_err("scripts cannot use import.meta");
+        assert!(
+            err.to_string()
+                .contains("Cannot use 'import.meta' outside a module"),
+            "{err}"
+        );
+    }
+}
+
+#[test]
+fn specifiers_resolve_extensionless_index_and_parent_paths() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_h", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("lib/util")).expect("create dirs");
+    fs::create_dir_all(dir.join("app")).expect("create dirs");
+
+    fs::write(dir.join("lib/math.js"), "export const two = 2;").expect("write math");
+    fs::write(
+        dir.join("lib/util/index.js"),
+        r#"import { two } from "../math"; export const four = two * 2;"#,
+    )
+    .expect("write util");
+    let absolute = dir.join("lib/math.js");
+    let main = dir.join("app/main.js");
+    fs::write(
+        &main,
+        format!(
+            r#"
+import {{ two }} from "../lib/math";
+import {{ four }} from "../lib/util";
+import {{ two as same }} from "{}";
+console.log(two, four, same);
+"#,
+            absolute.display().to_string().replace('\\', "/")
+        ),
+    )
+    .expect("write main");
+
+    assert_eq!(run_file(&main), vec!["2 4 2"]);
+}
+
+#[test]
+fn equivalent_specifiers_evaluate_a_module_once() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_i", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("nested")).expect("create dir");
+
+    fs::write(
+        dir.join("counter.js"),
+        r#"console.log("evaluated"); export const id = 7;"#,
+    )
+    .expect("write counter");
+    fs::write(
+        dir.join("nested/reexport.js"),
+        r#"import { id } from "../counter.js"; export const viaNested = id;"#,
+    )
+    .expect("write nested");
+    let main = dir.join("main.js");
+    fs::write(
+        &main,
+        r#"
+import { id } from "./counter.js";
+import { id as again } from "./counter";
+import { id as dotted } from "./nested/../counter.js";
+import { viaNested } from "./nested/reexport.js";
+console.log(id, again, dotted, viaNested);
+"#,
+    )
+    .expect("write main");
+
+    assert_eq!(run_file(&main), vec!["evaluated", "7 7 7 7"]);
+}
+
+#[test]
+fn bare_specifiers_need_a_module_resolver() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_j", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+
+    let main = dir.join("main.js");
+    fs::write(&main, r#"import { chunk } from "lodash";"#).expect("write main");
+
+    let err = run_file_result(&main).expect_err("bare specifier should fail");
+    assert!(
+        err.to_string()
+            .contains("cannot resolve 'lodash': bare specifiers require a module resolver"),
+        "{err}"
+    );
+}
+
+#[test]
+fn unresolved_imports_list_the_paths_tried() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_k", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+
+    let main = dir.join("main.js");
+    fs::write(&main, r#"import { x } from "./missing";"#).expect("write main");
+
+    let err = run_file_result(&main)
+        .expect_err("missing module should fail")
+        .to_string();
+    assert!(err.contains("cannot find module './missing'"), "{err}");
+    let js = dir.join("missing.js");
+    let index = dir.join("missing").join("index.js");
+    assert!(
+        err.contains(&format!("tried {}, {}", js.display(), index.display())),
+        "{err}"
+    );
+}
+
+#[test]
+fn json_modules_export_their_frozen_contents_as_default() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_l", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+
+    fs::write(
+        dir.join("config.json"),
+        r#"{ "name": "demo", "server": { "port": 8080, "hosts": ["a", "b"] } }"#,
+    )
+    .expect("write config");
+    let main = dir.join("main.js");
+    fs::write(
+        &main,
+        r#"
+        import config from "./config.json" with { type: "json" };
+        import raw from "./config.json" with { type: "text" };
+        console.log(config.name, config.server.port, config.server.hosts[1]);
+        config.server.port = 1;
+        console.log(config.server.port, Object.isFrozen(config), Object.isFrozen(config.server.hosts));
+        console.log(typeof raw, raw[0]);
+        "#,
+    )
+    .expect("write main");
+
+    let mut ctx = Context::new();
+    ctx.set_import_type_handler("text", |path, _attributes, exports| {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        exports.set("default", Value::String(text.into()));
+        Ok(())
+    });
+    ctx.eval_module(&main).expect("module should run");
+    assert_eq!(ctx.output(), ["demo 8080 b", "8080 true true", "string {"]);
+}
+
+#[test]
+fn import_attributes_must_match_the_module() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_m", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+
+    fs::write(dir.join("data.json"), r#"{ "a": 1 }"#).expect("write data");
+    fs::write(dir.join("lib.js"), "export const a = 1;").expect("write lib");
+    let cases = [
+        (
+            r#"import data from "./data.json";"#,
+            "has to be imported with { type: \"json\" }",
+        ),
+        (
+            r#"import lib from "./lib.js" with { type: "json" };"#,
+            "failed to parse JSON module",
+        ),
+        (
+            r#"import lib from "./lib.js" with { type: "wasm" };"#,
+            "unsupported import attribute type: \"wasm\"",
+        ),
+        (
+            r#"import data from "./data.json" with { type: "json", mode: "strict" };"#,
+            "unsupported import attribute 'mode'",
+        ),
+    ];
+    for (source, expected) in cases {
+        let main = dir.join("main.js");
+        fs::write(&main, source).expect("write main");
+        let err = run_file_result(&main)
+            .expect_err("mismatched import should fail")
+            .to_string();
+        assert!(err.contains(expected), "{source}: {err}");
+    }
+}
+
+#[test]
+fn module_graph_records_a_diamond_once() {
+    let dir = std::env::temp_dir().join(format!("crustyjs_mod_{}_n", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+
+    fs::write(dir.join("d.js"), "export const d = 1;").expect("write d");
+    fs::write(
+        dir.join("b.js"),
+        r#"import { d } from "./d.js"; export const b = d + 1;"#,
+    )
+    .expect("write b");
+    fs::write(
+        dir.join("c.js"),
+        r#"import { d } from "./d"; export const c = d + 2;"#,
+    )
+    .expect("write c");
+    let main = dir.join("a.js");
+    fs::write(
+        &main,
+        r#"import { b } from "./b.js"; import { c } from "./c.js"; console.log(b + c);"#,
+    )
+    .expect("write a");
+
+    let mut ctx = Context::new();
+    ctx.eval_module(&main).expect("diamond should run");
+    let graph = ctx.module_graph();
+
+    let name = |path: &std::path::Path| path.file_name().unwrap().to_string_lossy().into_owned();
+    let modules: Vec<String> = graph.iter().map(|module| name(&module.path)).collect();
+    assert_eq!(modules, vec!["a.js", "b.js", "d.js", "c.js"]);
+
+    let mut order: Vec<(usize, String)> = graph
+        .iter()
+        .map(|module| {
+            (
+                module.evaluation_order.expect("evaluated"),
+                name(&module.path),
+            )
+        })
+        .collect();
+    order.sort();
+    let order: Vec<&str> = order.iter().map(|(_, name)| name.as_str()).collect();
+    assert_eq!(order, vec!["d.js", "b.js", "c.js", "a.js"]);
+    assert!(graph.iter().all(|module| module.duration.is_some()));
+
+    let edges: Vec<(String, String, String)> = graph
+        .iter()
+        .flat_map(|module| {
+            module.imports.iter().map(|import| {
+                (
+                    name(&module.path),
+                    import.specifier.clone(),
+                    name(&import.path),
+                )
+            })
+        })
+        .collect();
+    let expected = [
+        ("a.js", "./b.js", "b.js"),
+        ("a.js", "./c.js", "c.js"),
+        ("b.js", "./d.js", "d.js"),
+        ("c.js", "./d", "d.js"),
+    ];
+    assert_eq!(
+        edges,
+        expected
+            .iter()
+            .map(|(from, spec, to)| (from.to_string(), spec.to_string(), to.to_string()))
+            .collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,589 @@
+use crustyjs::runtime::value::number_to_string;
+
+use crustyjs::lexer::lex;
+use crustyjs::parser::parse;
+use crustyjs::runtime::interpreter::Interpreter;
+
+fn run_and_capture(source: &str) -> Vec<String> {
+    let tokens = lex(source).expect("lexing should succeed");
+    let program = parse(tokens).expect("parsing should succeed");
+    let mut interp = Interpreter::new();
+    interp.run(&program).expect("execution should succeed");
+    interp.output().to_vec()
+}
+
+/// Bit patterns of doubles and what `String(x)` gives for them in V8.
+const EXPECTED: &[(u64, &str)] = &[
+    (0x3d719799812dea11, "1e-12"),
+    (0x3d7a636641c4df1a, "1.5e-12"),
+    (0x3d85fd7fe1796495, "2.5e-12"),
+    (0x3da5fcefc3fc6d5b, "9.999e-12"),
+    (0x3f202e85be111841, "0.000123456789"),
+    (0x3d3c25c268497682, "1e-13"),
+    (0x3da5fd7fe1796495, "1e-11"),
+    (0x3db07e1fe91b0b70, "1.5e-11"),
+    (0x3dbb7cdfd9d7bdba, "2.4999999999999998e-11"),
+    (0x3ddb7c2bb4fb88b1, "9.999e-11"),
+    (0x3f543a272d955e51, "0.00123456789"),
+    (0x3ddb7cdfd9d7bdbb, "1e-10"),
+    (0x3de49da7e361ce4c, "1.5e-10"),
+    (0x3df12e0be826d695, "2.5e-10"),
+    (0x3e112d9b511d356f, "9.999e-10"),
+    (0x3f8948b0f8fab5e6, "0.0123456789"),
+    (0x3da5fd7fe1796496, "1.0000000000000001e-11"),
+    (0x3e112e0be826d695, "1e-9"),
+    (0x3e19c511dc3a41e0, "1.5000000000000002e-9"),
+    (0x3e25798ee2308c3a, "2.5e-9"),
+    (0x3e457902256482cb, "9.999e-9"),
+    (0x3fbf9add37396360, "0.12345678900000001"),
+    (0x3ddb7cdfd9d7bdbc, "1.0000000000000002e-10"),
+    (0x3e45798ee2308c3a, "1e-8"),
+    (0x3e501b2b29a4692c, "1.5000000000000002e-8"),
+    (0x3e5ad7f29abcaf48, "2.5e-8"),
+    (0x3e7ad742aebda37e, "9.999000000000001e-8"),
+    (0x3ff3c0ca4283de1c, "1.2345678900000001"),
+    (0x3e7ad7f29abcaf48, "1e-7"),
+    (0x3e8421f5f40d8376, "1.5e-7"),
+    (0x3e90c6f7a0b5ed8d, "2.5e-7"),
+    (0x3eb0c689ad36862e, "9.999e-7"),
+    (0x4028b0fcd324d5a2, "12.3456789"),
+    (0x3eb0c6f7a0b5ed8d, "0.000001"),
+    (0x3eb92a737110e454, "0.0000015"),
+    (0x3ec4f8b588e368f0, "0.0000024999999999999998"),
+    (0x3ee4f82c188427ba, "0.000009999"),
+    (0x405edd3c07ee0b0b, "123.456789"),
+    (0x3ee4f8b588e368f1, "0.00001"),
+    (0x3eef75104d551d6a, "0.000015000000000000002"),
+    (0x3efa36e2eb1c432d, "0.000025"),
+    (0x3f1a36371ea531a9, "0.00009999000000000001"),
+    (0x40934a4584f4c6e7, "1234.56789"),
+    (0x3eb0c6f7a0b5ed8e, "0.0000010000000000000002"),
+    (0x3f1a36e2eb1c432d, "0.0001"),
+    (0x3f23a92a30553262, "0.00015000000000000001"),
+    (0x3f30624dd2f1a9fc, "0.00025"),
+    (0x3f5061e273273f0a, "0.0009999000000000002"),
+    (0x40c81cd6e631f8a1, "12345.6789"),
+    (0x3f50624dd2f1a9fc, "0.001"),
+    (0x3f589374bc6a7efa, "0.0015"),
+    (0x3f647ae147ae147b, "0.0025"),
+    (0x3f847a5b0ff10ecc, "0.009999000000000001"),
+    (0x40fe240c9fbe76c9, "123456.789"),
+    (0x3f847ae147ae147b, "0.01"),
+    (0x3f8eb851eb851eb8, "0.015"),
+    (0x3f9999999999999a, "0.025"),
+    (0x3fb998f1d3ed527f, "0.09999000000000001"),
+    (0x4132d687e3d70a3e, "1234567.8900000001"),
+    (0x3fb999999999999a, "0.1"),
+    (0x3fc3333333333334, "0.15000000000000002"),
+    (0x3fd0000000000000, "0.25"),
+    (0x3fefff2e48e8a71f, "0.9999000000000001"),
+    (0x41678c29dccccccd, "12345678.9"),
+    (0x3f847ae147ae147c, "0.010000000000000002"),
+    (0x3ff0000000000000, "1"),
+    (0x3ff8000000000000, "1.5"),
+    (0x4004000000000000, "2.5"),
+    (0x4023ff7ced916873, "9.999"),
+    (0x419d6f3454000000, "123456789"),
+    (0x4024000000000000, "10"),
+    (0x402e000000000000, "15"),
+    (0x4039000000000000, "25"),
+    (0x4058ff5c28f5c290, "99.99000000000001"),
+    (0x41d26580b4800000, "1234567890"),
+    (0x4059000000000000, "100"),
+    (0x4062c00000000000, "150"),
+    (0x406f400000000000, "250"),
+    (0x408f3f3333333334, "999.9000000000001"),
+    (0x4206fee0e1a00000, "12345678900"),
+    (0x408f400000000000, "1000"),
+    (0x4097700000000000, "1500"),
+    (0x40a3880000000000, "2500"),
+    (0x40c3878000000000, "9999"),
+    (0x423cbe991a080000, "123456789000"),
+    (0x40c3880000000000, "10000"),
+    (0x40cd4c0000000000, "15000"),
+    (0x40d86a0000000000, "25000"),
+    (0x40f8696000000000, "99990"),
+    (0x4271f71fb0450000, "1234567890000"),
+    (0x40f86a0000000000, "100000"),
+    (0x41024f8000000000, "150000"),
+    (0x410e848000000000, "250000"),
+    (0x412e83b800000000, "999900"),
+    (0x42a674e79c564000, "12345678900000"),
+    (0x412e848000000000, "1000000"),
+    (0x4136e36000000000, "1500000"),
+    (0x414312d000000000, "2500000"),
+    (0x4163125300000000, "9999000"),
+    (0x42dc1221836bd000, "123456789000000"),
+    (0x416312d000000000, "10000000"),
+    (0x416c9c3800000000, "15000000"),
+    (0x4177d78400000000, "25000000"),
+    (0x4197d6e7c0000000, "99990000"),
+    (0x43118b54f2236200, "1234567890000000"),
+    (0x4197d78400000000, "100000000"),
+    (0x41a1e1a300000000, "150000000"),
+    (0x41adcd6500000000, "250000000"),
+    (0x41cdcca1b0000000, "999900000"),
+    (0x4345ee2a2eac3a80, "12345678900000000"),
+    (0x41cdcd6500000000, "1000000000"),
+    (0x41d65a0bc0000000, "1500000000"),
+    (0x41e2a05f20000000, "2500000000"),
+    (0x42029fe50e000000, "9999000000"),
+    (0x437b69b4ba574920, "123456789000000000"),
+    (0x4202a05f20000000, "10000000000"),
+    (0x420bf08eb0000000, "15000000000"),
+    (0x42174876e8000000, "25000000000"),
+    (0x423747de51800000, "99990000000"),
+    (0x43b12210f4768db4, "1234567890000000000"),
+    (0x42374876e8000000, "100000000000"),
+    (0x424176592e000000, "150000000000"),
+    (0x424d1a94a2000000, "250000000000"),
+    (0x426d19d5e5e00000, "999900000000"),
+    (0x43e56a9531943121, "12345678900000000000"),
+    (0x426d1a94a2000000, "1000000000000"),
+    (0x4275d3ef79800000, "1500000000000"),
+    (0x4282309ce5400000, "2500000000000"),
+    (0x42a23025afac0000, "9999000000000"),
+    (0x441ac53a7df93d69, "123456789000000000000"),
+    (0x42a2309ce5400000, "10000000000000"),
+    (0x42ab48eb57e00000, "15000000000000"),
+    (0x42b6bcc41e900000, "25000000000000"),
+    (0x42d6bc2f1b970000, "99990000000000"),
+    (0x4450bb448ebbc662, "1.23456789e+21"),
+    (0x42d6bcc41e900000, "100000000000000"),
+    (0x42e10d9316ec0000, "150000000000000"),
+    (0x42ec6bf526340000, "250000000000000"),
+    (0x430c6b3ae27cc000, "999900000000000"),
+    (0x4484ea15b26ab7fa, "1.23456789e+22"),
+    (0x430c6bf526340000, "1000000000000000"),
+    (0x431550f7dca70000, "1500000000000000"),
+    (0x4321c37937e08000, "2500000000000000"),
+    (0x4341c304cd8df800, "9999000000000000"),
+    (0x44ba249b1f0565f9, "1.23456789e+23"),
+    (0x4341c37937e08000, "10000000000000000"),
+    (0x434aa535d3d0c000, "15000000000000000"),
+    (0x4356345785d8a000, "25000000000000000"),
+    (0x437633c600f17600, "99990000000000000"),
+    (0x44f056e0f3635fbb, "1.23456789e+24"),
+    (0x4376345785d8a000, "100000000000000000"),
+    (0x4380a741a4627800, "150000000000000000"),
+    (0x438bc16d674ec800, "250000000000000000"),
+    (0x43abc0b7812dd380, "999900000000000000"),
+    (0x45246c99303c37aa, "1.23456789e+25"),
+    (0x43abc16d674ec800, "1000000000000000000"),
+    (0x43b4d1120d7b1600, "1500000000000000000"),
+    (0x43c158e460913d00, "2500000000000000000"),
+    (0x43e15872b0bca430, "9999000000000000000"),
+    (0x455987bf7c4b4595, "1.23456789e+26"),
+    (0x43e158e460913d00, "10000000000000000000"),
+    (0x43ea055690d9db80, "15000000000000000000"),
+    (0x43f5af1d78b58c40, "25000000000000000000"),
+    (0x4415ae8f5cebcd3c, "99990000000000000000"),
+    (0x458fe9af5b5e16fa, "1.23456789e+27"),
+    (0x4415af1d78b58c40, "100000000000000000000"),
+    (0x442043561a882930, "150000000000000000000"),
+    (0x442b1ae4d6e2ef50, "250000000000000000000"),
+    (0x444b1a333426c08b, "999900000000000000000"),
+    (0x45c3f20d991ace5c, "1.23456789e+28"),
+    (0x444b1ae4d6e2ef50, "1e+21"),
+    (0x4454542ba12a337c, "1.5e+21"),
+    (0x4460f0cf064dd592, "2.5e+21"),
+    (0x4480f06000983857, "9.999e+21"),
+    (0x45f8ee90ff6181f3, "1.23456789e+29"),
+    (0x4480f0cf064dd592, "1e+22"),
+    (0x448969368974c05b, "1.5e+22"),
+    (0x44952d02c7e14af6, "2.5e+22"),
+    (0x44b52c7800be466d, "9.999e+22"),
+    (0x462f2a353f39e270, "1.23456789e+30"),
+    (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+    (0x44bfc3842bd1f072, "1.5e+23"),
+    (0x44ca784379d99db5, "2.5000000000000003e+23"),
+    (0x44ea779600edd809, "9.999000000000002e+23"),
+    (0x46637a6147842d87, "1.2345678900000002e+31"),
+    (0x4480f0cf064dd593, "1.0000000000000002e+22"),
+    (0x44ea784379d99db4, "1e+24"),
+    (0x44f3da329b633647, "1.5e+24"),
+    (0x45008b2a2c280290, "2.4999999999999997e+24"),
+    (0x45208abdc094a705, "9.999e+24"),
+    (0x469858f9996538e8, "1.23456789e+32"),
+    (0x45208b2a2c280291, "1e+25"),
+    (0x4528d0bf423c03da, "1.5000000000000002e+25"),
+    (0x4534adf4b7320335, "2.5e+25"),
+    (0x4554ad6d30b9d0c7, "9.999000000000002e+25"),
+    (0x46ce6f37ffbe8722, "1.23456789e+33"),
+    (0x44ea784379d99db5, "1.0000000000000001e+24"),
+    (0x0000000000000000, "0"),
+    (0x0000000000000002, "1e-323"),
+    (0x000012688b70e62b, "1e-310"),
+    (0x000016b9a2ec86a3, "1.2345e-310"),
+    (0x000730d67819e8d2, "1e-308"),
+    (0x0008e083a46497de, "1.2345e-308"),
+    (0x01a56e1fc2f8f359, "1e-300"),
+    (0x01aa749ec2ff5c8a, "1.2345e-300"),
+    (0x2b2bff2ee48e0530, "1e-100"),
+    (0x2b3147f19dd7eb02, "1.2345e-100"),
+    (0x358dee7a4ad4b81f, "1e-50"),
+    (0x359279aa13b1824a, "1.2345e-50"),
+    (0x3b5e392010175ee6, "1e-22"),
+    (0x3b62a7bd953243fc, "1.2345e-22"),
+    (0x3b92e3b40a0e9b4f, "1e-21"),
+    (0x3b9751acfa7ed4fb, "1.2345e-21"),
+    (0x3e4a82bc4b32f8e3, "1.2345e-8"),
+    (0x3e8091b5aeffdb8e, "1.2345e-7"),
+    (0x3eb4b6231abfd271, "0.0000012345"),
+    (0x3ee9e3abe16fc70d, "0.000012345"),
+    (0x441ac4da03bc47e4, "123450000000000000000"),
+    (0x4450bb084255acee, "1.2345e+21"),
+    (0x4484e9ca52eb182a, "1.2345e+22"),
+    (0x44b52d02c7e14af6, "1e+23"),
+    (0x44ba243ce7a5de35, "1.2345e+23"),
+    (0x4a511b0ec57e649a, "1e+50"),
+    (0x4a551df25d05a1d3, "1.2345e+50"),
+    (0x54b249ad2594c37d, "1e+100"),
+    (0x54b693878aae8f8a, "1.2345e+100"),
+    (0x7e37e43c8800759c, "1e+300"),
+    (0x7e3d7e7e70161236, "1.2345e+300"),
+    (0x7fe1ccf385ebc8a0, "1e+308"),
+    (0x7fe5f98e6bda7fbc, "1.2345e+308"),
+    (0x3fc999999999999a, "0.2"),
+    (0x3fd3333333333333, "0.3"),
+    (0x3fd3333333333334, "0.30000000000000004"),
+    (0x3fd5555555555555, "0.3333333333333333"),
+    (0x3fe5555555555555, "0.6666666666666666"),
+    (0x400921f9f01b866e, "3.14159"),
+    (0x4340000000000000, "9007199254740992"),
+    (0x4340000000000001, "9007199254740994"),
+    (0x43e0000000000000, "9223372036854776000"),
+    (0x43f0000000000000, "18446744073709552000"),
+    (0x41e0000000000000, "2147483648"),
+    (0x41efffffffe00000, "4294967295"),
+    (0xc1e0000000000000, "-2147483648"),
+    (0x0000000000000001, "5e-324"),
+    (0x0010000000000000, "2.2250738585072014e-308"),
+    (0x7fefffffffffffff, "1.7976931348623157e+308"),
+    (0x3c36b082c2148b8e, "1.23e-18"),
+    (0x4011666666666666, "4.35"),
+    (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+    (0x4054d6f1965708ff, "83.3584953165009"),
+    (0x3d65699c18e66599, "6.085809361948314e-13"),
+    (0x417835c616702f3b, "25386081.402388792"),
+    (0x3e43bbd9ccc9a04a, "9.189263039044495e-9"),
+    (0x48055e9cccc832a7, "9.089614110907861e+38"),
+    (0x39d11195bcde8875, "3.3662057715239064e-30"),
+    (0x42b7c9bcad42ef95, "26155221336815.582"),
+    (0x3f84078a0e1b9f57, "0.009780005031145995"),
+    (0x442bf9249c7b0212, "258007368028446600000"),
+    (0x42a0196821291bdf, "8850653615245.936"),
+    (0x3bf2fb83dd5d1c85, "6.431514678763438e-20"),
+    (0x43f7f052b27fe05f, "27599513342175474000"),
+    (0x3d8389ea5ad27833, "2.2212966791883458e-12"),
+    (0x46c6370631a251de, "9.011449542651579e+32"),
+    (0x47d6d5567a884d3a, "1.2140291653352452e+38"),
+    (0x401112fb814cb89a, "4.268537540729222"),
+    (0x3fb55f2d124e0124, "0.08348352143184862"),
+    (0x467e1547e28eafc8, "3.8134895428068803e+31"),
+    (0x441169317c784a1c, "80293656439246230000"),
+    (0x39b2d7782e1b5689, "9.289664256397825e-31"),
+    (0x3dde371bca83e869, "1.0992250393666881e-10"),
+    (0x40e0c3f3e9183473, "34335.6222039246"),
+    (0x44ca1db9101df6ce, "2.466596386483817e+23"),
+    (0x3d466c061bb07658, "1.593176662818906e-13"),
+    (0x4800a0d80c124831, "7.072895163774262e+38"),
+    (0x39e101dddeaea216, "6.708193768934392e-30"),
+    (0x44f7978a59f4dcb8, "1.7825577076737754e+24"),
+    (0x3f664ac3c3bd335c, "0.002721197464700271"),
+    (0x3dc5377a684a3b2c, "3.859297346649685e-11"),
+    (0x40f4811b05524dda, "83985.68879919444"),
+    (0x440856dadabe1b21, "56122551613905970000"),
+    (0x3df7d04e888a0dcb, "3.465349192836109e-10"),
+    (0x45760fe1a2f1916e, "4.267418526001132e+26"),
+    (0x45b3799240a97cbd, "6.02718586908989e+27"),
+    (0x3ed630c77ca35565, "0.0000052906379528113485"),
+    (0x3dd34351145947e7, "7.007822541634334e-11"),
+    (0x4483fd1ca4c5983c, "1.1799256677119831e+22"),
+    (0x43c4c2f3ea918d2d, "2992052430060542500"),
+    (0x3c815047aa4605e3, "3.003429713807027e-17"),
+    (0x465ba61bb688024a, "8.762273609968928e+30"),
+    (0x39ca08a4dd3780a0, "2.5671274898489638e-30"),
+    (0x443009229351c744, "295806155654347100000"),
+    (0x440da5d2c0195f92, "68363050264269310000"),
+    (0x4155dc45ffd82ec7, "5730583.997569746"),
+    (0x3f3d2d15c54fcad1, "0.0004451921607248446"),
+    (0x412de605107c17c1, "979714.5321967528"),
+    (0x3e3ec3b604aaea48, "7.162917435127832e-9"),
+    (0x43b97e621580432e, "1837013542499462700"),
+    (0x4548508f4a383077, "5.8789304443722115e+25"),
+    (0x479a70d266a81855, "8.786433144468988e+36"),
+    (0x3ced31d6a7564f99, "3.2412607714394783e-15"),
+    (0x4211106c1f62b764, "18322491352.679092"),
+    (0x3bc528ec505e6068, "8.961547538218958e-21"),
+    (0x40511d54f170273d, "68.45830951645344"),
+    (0x3d6ff81a19210df2, "9.086178416262055e-13"),
+    (0x4577dd05a91a172c, "4.61584660202372e+26"),
+    (0x43ca38b15f3c277b, "3778910132542306000"),
+    (0x3fef96b6c252becb, "0.9871476931930031"),
+    (0x399fd0883905b348, "3.9214495732766047e-31"),
+    (0x44be34979667364a, "1.4264114820935135e+23"),
+    (0x3fe9135945915c42, "0.7836119040247314"),
+    (0x3ca07b491982fc19, "1.1436396386992722e-16"),
+    (0x45f586d2d4d33b75, "1.0659483022349826e+29"),
+    (0x3bdac17d7ca3b39b, "2.2663061941321228e-20"),
+    (0x3d6fe29b5be22487, "9.0623140889023e-13"),
+    (0x3c21a24407cd93b2, "4.779664772355486e-19"),
+    (0x3d69be8446d6fb8f, "7.316943391099339e-13"),
+    (0x3ac3dc9ff5550a32, "1.2835397605760822e-25"),
+    (0x46bdf654469688dc, "6.077060885609194e+32"),
+    (0x408e05994d251fd4, "960.6998541736925"),
+    (0x46f04ed9d3d6b1d3, "5.292252234862164e+33"),
+    (0x40499bff033c7ebb, "51.21871986821994"),
+    (0x42f84ad5de7b4205, "427355116385312.3"),
+    (0x44143d572316e2ab, "93338730403669400000"),
+    (0x411060c0553a2e57, "268336.08322975546"),
+    (0x47984266857eb293, "8.061560718459365e+36"),
+    (0x3cccda422e6e86b3, "8.008197534999279e-16"),
+    (0x3e819d65ef74e543, "1.3124076024012477e-7"),
+    (0x425a486d28c74e40, "451537052445.22266"),
+    (0x3ea1f4c1a727b08e, "5.351328822823081e-7"),
+    (0x3ecba04d878c9105, "0.000003293297649158602"),
+    (0x3f69eb2d10a299fe, "0.003163898484746185"),
+    (0x3f6ddebf577c3792, "0.003646253313383371"),
+    (0x3d6bd40c267bcf4e, "7.909281521071979e-13"),
+    (0x41e5c0d16b324af1, "2919664473.571648"),
+    (0x482c3fe6e88f0750, "4.806423291324554e+39"),
+    (0x3f3902c7c3d5a0e3, "0.0003816354472605655"),
+    (0x40ae4e9b140604d8, "3879.3028871422684"),
+    (0x3ee3259da5f1965f, "0.000009129970918129991"),
+    (0x3fe7c8387c4d4ed5, "0.7431909969113472"),
+    (0x39e1e901ba98772e, "7.064321441384191e-30"),
+    (0x41573e226a623252, "6092937.662243443"),
+    (0x46ea00671634f483, "4.2189964279791725e+33"),
+    (0x3a669db3844e9898, "2.2836344796241417e-27"),
+    (0x3f1b4fc55ed6c92f, "0.00010418550637928259"),
+    (0x3e59e35140688897, "2.4110040209209493e-8"),
+    (0x3bc5fc0ddc86d345, "9.310834552303413e-21"),
+    (0x3e048c147c1e69ce, "5.980018635116969e-10"),
+    (0x432baacf4a9e9066, "3893815829022771"),
+    (0x4281d4c642d89cad, "2450694626067.5845"),
+    (0x430c43233a3d5491, "994389740989074.1"),
+    (0x3e40a11dab25461f, "7.743648698532757e-9"),
+    (0x47966847710d02a7, "7.446115947111627e+36"),
+    (0x3aa4df2346aa4977, "3.371994015419166e-26"),
+    (0x3d6a621bdb6fcb94, "7.498567119555232e-13"),
+    (0x3e2fe59a8a9bcae2, "3.7132866407313704e-9"),
+    (0x44365669dd94b447, "412055121212176000000"),
+    (0x45b13a031218def6, "5.331377365433497e+27"),
+    (0x3d695ae465d41a10, "7.206337947240846e-13"),
+    (0x44ac3f840e5b5a21, "6.669896102024955e+22"),
+    (0x42c5547ce86a552d, "46905234085034.35"),
+    (0x467b1ac87c4271cb, "3.43591899216567e+31"),
+    (0x3a11a17f5fc7ed39, "5.563315261169588e-29"),
+    (0x45868038a6ece739, "8.704600315916871e+26"),
+    (0x3c1e382e79f20d58, "4.09550041081302e-19"),
+    (0x3ab602fdb5989ee0, "7.112358627745767e-26"),
+    (0x3d39ed724acc36ea, "9.211307347187952e-14"),
+    (0x3fee584f82dd7cdf, "0.9482801013909031"),
+    (0x421fe55ddf049f97, "34248030145.15585"),
+    (0x45861511987659fb, "8.542675938681163e+26"),
+    (0x3d70ee32d116a8f0, "9.623853942514305e-13"),
+    (0x3e456c261d8221de, "9.97560871430573e-9"),
+    (0x3d2cdffc657507f5, "5.1292206059658864e-14"),
+    (0x46241711235800aa, "7.958510620781497e+29"),
+    (0x441536031ddef4d5, "97818403223576600000"),
+    (0x42ce9efa91e6b44c, "67336315063656.59"),
+    (0x41903d9ac7a82b65, "68118193.91422804"),
+    (0x47f2c11706740697, "3.988589475923807e+38"),
+    (0x3e9ca50317652cec, "4.2683748037860943e-7"),
+    (0x3bc0b03da9f4c97f, "7.067829647211385e-21"),
+    (0x3f6e3dc514571ab4, "0.0036915635307395948"),
+    (0x483a9c298591f94e, "9.054916700583764e+39"),
+    (0x43d7350b6fee5732, "6689021648023898000"),
+    (0x480ce287c4d91c1a, "1.2286270941828826e+39"),
+    (0x4033ac4dd0e10f91, "19.673062376924978"),
+    (0x4152b48e50a54b05, "4903481.260088687"),
+    (0x41ecb446df477dea, "3852613370.2341204"),
+    (0x44d926723265d424, "4.7507346839189226e+23"),
+    (0x41b1a169ec64025f, "295791084.3906612"),
+    (0x4584297d37c96d9b, "7.799821849908514e+26"),
+    (0x43419ab5cc05b220, "9910360413529152"),
+    (0x454c99c1f0d9b713, "6.915204519559766e+25"),
+    (0x3c5bda6625fc443b, "6.0396828945630795e-18"),
+    (0x39dd57905a9b5093, "5.7866983242311175e-30"),
+    (0x3aef3e3eab536223, "8.07616337211703e-25"),
+    (0x3a1705d7150fabdb, "7.264718887317904e-29"),
+    (0x46f5e2052a959136, "7.101404244978458e+33"),
+    (0x4375e356fcbab491, "98574996123371790"),
+    (0x3a143e614f25f8e5, "6.387776668587513e-29"),
+    (0x4235c6b25b1084ae, "93527890704.51828"),
+    (0x42311b08a16e0000, "73467994478"),
+    (0x426ab92ca8ec8000, "918206891876"),
+    (0x425fd77d74f00000, "547037238208"),
+    (0xc233188b5c8c0000, "-82016165004"),
+    (0x425de84266808000, "513802869250"),
+    (0xc26bf661311a0000, "-960781453520"),
+    (0x425b25f6469dc000, "466404055671"),
+    (0xc264b6ad054ba000, "-711713106525"),
+    (0xc26a62f99cb86000, "-906637403587"),
+    (0x4255403228480000, "365085368608"),
+    (0xc26c102eb646a000, "-964244648501"),
+    (0x425ca6376f308000, "492190940354"),
+    (0x42470a28d7cb0000, "197909393302"),
+    (0x42403c1dbf3b8000, "139456118391"),
+    (0xc24120dd9c330000, "-147131676774"),
+    (0x426266011ea20000, "632166085904"),
+    (0xc261c8a7856a4000, "-611046927186"),
+    (0xc2676eb1c3e46000, "-805131132707"),
+    (0x426b81591323e000, "945073723679"),
+    (0x4263df3cae328000, "682797396372"),
+    (0x42659e0a6ea26000, "742766376211"),
+    (0x4266e9bd858a6000, "787286338643"),
+    (0xc2688e8c0f372000, "-843766069689"),
+    (0x426467a23ff96000, "701104259019"),
+    (0x4231505f43860000, "74362864518"),
+    (0x4267a8d07140a000, "812931844613"),
+    (0x4247d85da3630000, "204828526278"),
+    (0xc263ba09247f0000, "-677804319736"),
+    (0xc266448e7745c000, "-765115742766"),
+    (0x42662506b9c54000, "760883826218"),
+    (0xc2666773593cc000, "-769799145958"),
+    (0x4227259f55820000, "49707723457"),
+    (0xc25bd98e372c8000, "-478456372402"),
+    (0xc24332c95e858000, "-164912872715"),
+    (0xc2649fe315bf8000, "-708654444028"),
+    (0xc244e0020ebf0000, "-179315154302"),
+    (0x4246aa7c7dee8000, "194699131869"),
+    (0xc256b423209f8000, "-390045926014"),
+    (0xc2525f7dc2748000, "-315645954514"),
+    (0x424a3ebc482b8000, "225443352663"),
+    (0xbd719799812dea11, "-1e-12"),
+    (0xbda5fcefc3fc6d5b, "-9.999e-12"),
+    (0xbda5fd7fe1796495, "-1e-11"),
+    (0xbddb7c2bb4fb88b1, "-9.999e-11"),
+    (0xbddb7cdfd9d7bdbb, "-1e-10"),
+    (0xbe112d9b511d356f, "-9.999e-10"),
+    (0xbe112e0be826d695, "-1e-9"),
+    (0xbe457902256482cb, "-9.999e-9"),
+    (0xbe45798ee2308c3a, "-1e-8"),
+    (0xbe7ad742aebda37e, "-9.999000000000001e-8"),
+    (0xbe7ad7f29abcaf48, "-1e-7"),
+    (0xbeb0c689ad36862e, "-9.999e-7"),
+    (0xbeb0c6f7a0b5ed8d, "-0.000001"),
+    (0xbee4f82c188427ba, "-0.000009999"),
+    (0xbee4f8b588e368f1, "-0.00001"),
+    (0xbf1a36371ea531a9, "-0.00009999000000000001"),
+    (0xbf1a36e2eb1c432d, "-0.0001"),
+    (0xbf5061e273273f0a, "-0.0009999000000000002"),
+    (0xbf50624dd2f1a9fc, "-0.001"),
+    (0xbf847a5b0ff10ecc, "-0.009999000000000001"),
+];
+
+#[test]
+fn number_to_string_matches_reference_engine() {
+    for &(bits, expected) in EXPECTED {
+        let value = f64::from_bits(bits);
+        assert_eq!(number_to_string(value), expected, "{value:e}");
+    }
+}
+
+#[test]
+fn number_to_string_special_values() {
+    assert_eq!(number_to_string(f64::NAN), "NaN");
+    assert_eq!(number_to_string(f64::INFINITY), "Infinity");
+    assert_eq!(number_to_string(f64::NEG_INFINITY), "-Infinity");
+    assert_eq!(number_to_string(-0.0), "0");
+}
+
+#[test]
+fn every_output_path_formats_numbers_the_same() {
+    let output = run_and_capture(
+        r#"
+        const n = 1e21;
+        console.log(n, 1.5e-7, -0, 2 ** 63);
+        console.log(String(n), `${1.5e-7}`, "" + 100, [1, 0.5].join());
+        console.log(JSON.stringify({ big: n, small: 1.5e-7, whole: 3, neg: -0 }));
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "1e+21 1.5e-7 0 9223372036854776000",
+            "1e+21 1.5e-7 100 1,0.5",
+            r#"{"big":1e+21,"neg":0,"small":1.5e-7,"whole":3}"#,
+        ]
+    );
+}
+
+#[test]
+fn to_locale_string_groups_thousands_and_rounds_fractions() {
+    let cases = [
+        ("1234567.891", "", "1,234,567.891"),
+        ("-1234.5", "", "-1,234.5"),
+        ("0", "", "0"),
+        ("1000", "", "1,000"),
+        ("-1000000", "", "-1,000,000"),
+        ("999.9999", "", "1,000"),
+        ("0.1 + 0.2", "", "0.3"),
+        ("1e21", "", "1,000,000,000,000,000,000,000"),
+        ("1e-7", "", "0"),
+        ("2.5", "{ maximumFractionDigits: 0 }", "3"),
+        ("-2.5", "{ maximumFractionDigits: 0 }", "-3"),
+        ("1234.5678", "{ maximumFractionDigits: 2 }", "1,234.57"),
+        ("5", "{ minimumFractionDigits: 2 }", "5.00"),
+        ("1.5", "{ minimumFractionDigits: 4 }", "1.5000"),
+        ("1234567", "{ useGrouping: false }", "1234567"),
+        ("NaN", "", "NaN"),
+        ("-Infinity", "", "-∞"),
+    ];
+    for (value, options, expected) in cases {
+        let source = if options.is_empty() {
+            format!("console.log(({value}).toLocaleString());")
+        } else {
+            format!("console.log(({value}).toLocaleString('de-DE', {options}));")
+        };
+        assert_eq!(run_and_capture(&source), vec![expected], "{source}");
+    }
+}
+
+#[test]
+fn number_to_string_takes_a_radix() {
+    let output = run_and_capture(
+        r#"
+        console.log((255).toString(16), (-255).toString(2), (0.5).toString(2));
+        console.log((1e21).toString(), (42).valueOf() + 1);
+        try { (1).toString(37); } catch (e) { console.log(e.message); }
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec![
+            "ff -11111111 0.1",
+            "1e+21 43",
+            "toString() radix must be between 2 and 36",
+        ]
+    );
+}
+
+#[test]
+fn json_stringify_writes_numbers_like_number_to_string() {
+    let cases = [
+        ("1e21", "1e+21"),
+        ("0.1 + 0.2", "0.30000000000000004"),
+        ("-0", "0"),
+        ("-5", "-5"),
+        ("2 ** 53", "9007199254740992"),
+        ("123456789012345680000", "123456789012345680000"),
+        ("1e-7", "1e-7"),
+        ("0.000001", "0.000001"),
+        ("-1.5e300", "-1.5e+300"),
+        ("NaN", "null"),
+        ("Infinity", "null"),
+    ];
+    for (value, expected) in cases {
+        let source = format!("console.log(JSON.stringify([{value}]), String({value}));");
+        let output = run_and_capture(&source);
+        let printed = if expected == "null" {
+            format!("[null] {value}")
+        } else {
+            format!("[{expected}] {expected}")
+        };
+        assert_eq!(output, vec![printed], "{source}");
+    }
+}
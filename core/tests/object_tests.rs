@@ -1,6 +1,7 @@
 use crustyjs::lexer::lex;
 use crustyjs::parser::parse;
 use crustyjs::runtime::interpreter::Interpreter;
+use crustyjs::runtime::value::PropName;
 
 fn run_and_capture(source: &str) -> Vec<String> {
     let tokens = lex(source).expect("lex failed");
@@ -193,3 +194,390 @@ fn optional_chaining_on_object_and_missing_path() {
     let out = run_and_capture(src);
     assert_eq!(out, vec!["Rex", "undefined"]);
 }
+
+#[test]
+fn array_prototype_can_be_extended() {
+    let src = r#"
+        Array.prototype.sum = function () {
+            let total = 0;
+            for (const x of this) { total = total + x; }
+            return total;
+        };
+        console.log([1, 2, 3].sum());
+        console.log(Object.getPrototypeOf([]) === Array.prototype);
+        console.log(Object.getPrototypeOf(Array.prototype) === Object.prototype);
+        console.log([].constructor === Array);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["6", "true", "true", "true"]);
+}
+
+#[test]
+fn array_methods_defined_by_script_shadow_the_builtins() {
+    let src = r#"
+        const a = [1, 2, 3];
+        Array.prototype.map = function () { return "patched map"; };
+        Array.prototype.indexOf = function () { return "patched indexOf"; };
+        console.log(a.map((x) => x), a.indexOf(2));
+        delete Array.prototype.map;
+        delete Array.prototype.indexOf;
+        console.log(a.map((x) => x * 2).join(), a.indexOf(2));
+        const b = [1];
+        b.push = function (x) { return "own push " + x; };
+        console.log(b.push(5), b.length);
+        Object.prototype.filter = function () { return "object filter"; };
+        console.log(a.filter((x) => x > 1).join());
+        delete Object.prototype.filter;
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec![
+            "patched map patched indexOf",
+            "2,4,6 1",
+            "own push 5 1",
+            "2,3"
+        ]
+    );
+}
+
+#[test]
+fn standard_prototypes_for_functions_strings_and_objects() {
+    let src = r#"
+        function f() {}
+        Function.prototype.describe = function () { return "fn " + this.name; };
+        String.prototype.shout = function () { return this.toUpperCase() + "!"; };
+        console.log(Object.getPrototypeOf(f) === Function.prototype);
+        console.log(f.describe());
+        console.log("hi".shout());
+        console.log(Object.getPrototypeOf("x") === String.prototype);
+        console.log(({}).constructor === Object);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["true", "fn f", "HI!", "true", "true"]);
+}
+
+#[test]
+fn set_prototype_of_array_and_create_from_array_prototype() {
+    let src = r#"
+        const proto = { first() { return this[0]; } };
+        const arr = [7, 8];
+        Object.setPrototypeOf(arr, proto);
+        console.log(Object.getPrototypeOf(arr) === proto);
+        console.log(arr.first());
+        const o = Object.create(Array.prototype);
+        console.log(Object.getPrototypeOf(o) === Array.prototype);
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(out, vec!["true", "7", "true"]);
+}
+
+#[test]
+fn object_method_shorthand_binds_receiver() {
+    let out = run_and_capture(
+        r#"
+        let counter = {
+            x: 1,
+            bump() { this.x = this.x + 1; return this.x; },
+        };
+        counter.bump();
+        let other = { x: 10, bump: counter.bump };
+        console.log(counter.bump(), other.bump(), counter.bump.name);
+        "#,
+    );
+    assert_eq!(out, vec!["3 11 bump"]);
+}
+
+#[test]
+fn object_generator_method_follows_iterator_protocol() {
+    let out = run_and_capture(
+        r#"
+        let range = {
+            from: 1,
+            to: 3,
+            *values() {
+                for (let i = this.from; i <= this.to; i++) yield i;
+            },
+        };
+        let it = range.values();
+        let first = it.next();
+        console.log(first.value, first.done);
+        let rest = [];
+        for (const v of it) rest.push(v);
+        console.log(rest.join(","), it.next().done);
+        "#,
+    );
+    assert_eq!(out, vec!["1 false", "2,3 true"]);
+}
+
+#[test]
+fn object_computed_accessors_and_literal_keys() {
+    let out = run_and_capture(
+        r#"
+        let key = "size";
+        let box = {
+            _size: 2,
+            get [key]() { return this._size; },
+            set [key](v) { this._size = v * 2; },
+            [key + "Label"]() { return "size " + this._size; },
+            1.0: "one",
+            "two words": 2,
+            get: "plain get",
+            async: "plain async",
+        };
+        box.size = 5;
+        console.log(box.size, box.sizeLabel(), box.sizeLabel.name);
+        console.log(box[1], box["two words"], box.get, box.async);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec!["10 size 10 sizeLabel", "one 2 plain get plain async"]
+    );
+}
+
+#[test]
+fn object_async_method_awaits_with_receiver() {
+    let out = run_and_capture(
+        r#"
+        let loader = {
+            base: 20,
+            async load(n) { const extra = await Promise.resolve(n); return this.base + extra; },
+        };
+        loader.load(1).then(v => console.log(v));
+        "#,
+    );
+    assert_eq!(out, vec!["21"]);
+}
+
+#[test]
+fn delete_returns_whether_the_property_is_gone() {
+    let out = run_and_capture(
+        r#"
+        const o = { a: 1 };
+        Object.defineProperty(o, "fixed", { value: 1, configurable: false });
+        console.log(delete o.a, delete o.zz, o.a, delete o.fixed, o.fixed);
+        console.log(delete Object.freeze({ q: 1 }).q, delete Object.seal({ q: 1 }).q);
+        function f() {}
+        f.prop = 1;
+        console.log(delete f.prop, f.prop);
+        var x = 1;
+        console.log(delete x, x, delete 1, delete "abc".length, delete "abc"[1], delete "abc"[5]);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "true true undefined false 1",
+            "false false",
+            "true undefined",
+            "false 1 true false false true",
+        ]
+    );
+}
+
+#[test]
+fn delete_array_element_leaves_a_hole() {
+    let out = run_and_capture(
+        r#"
+        const arr = [1, 2, 3];
+        console.log(delete arr[1], arr.length, arr[1], arr[2]);
+        console.log(delete arr[9], delete arr.length, arr.length);
+        "#,
+    );
+    assert_eq!(out, vec!["true 3 undefined 3", "true false 3"]);
+}
+
+#[test]
+fn delete_consults_proxy_trap() {
+    let out = run_and_capture(
+        r#"
+        const target = { k: 1, j: 2 };
+        const trapped = new Proxy(target, {
+            deleteProperty(t, key) { console.log("trap", key); return key === "j"; }
+        });
+        console.log(delete trapped.k, delete trapped.j, target.k, target.j);
+        const plain = new Proxy(target, {});
+        console.log(delete plain.k, target.k);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec!["trap k", "trap j", "false true 1 2", "true undefined"]
+    );
+}
+
+#[test]
+fn strict_delete_throws_when_refused() {
+    let out = run_and_capture(
+        r#"
+        "use strict";
+        const o = Object.freeze({ q: 1 });
+        try { delete o.q; } catch (e) { console.log(e instanceof TypeError, e.message); }
+        try { delete [1].length; } catch (e) { console.log(e.name); }
+        console.log(delete o.missing);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "true Cannot delete property 'q' of [object Object]",
+            "TypeError",
+            "true",
+        ]
+    );
+}
+
+#[test]
+fn property_names_with_equal_text_share_one_interned_name() {
+    let built = format!("{}{}", "wid", "th");
+    let a = PropName::from("width");
+    let b = PropName::from(built);
+    assert_eq!(a, b);
+    assert!(std::ptr::eq(a.as_str(), b.as_str()));
+    assert_ne!(a, PropName::from("height"));
+}
+
+#[test]
+fn computed_and_dotted_keys_reach_the_same_property() {
+    let out = run_and_capture(
+        r#"
+        let o = { count: 1 };
+        let k = "co" + "unt";
+        o[k] = o[k] + 1;
+        o.count += 1;
+        for (let i = 0; i < 3; i++) o["n" + i] = i;
+        console.log(o.count, Object.keys(o).sort().join(","), o.n2);
+        "#,
+    );
+    assert_eq!(out, vec!["3 count,n0,n1,n2 2"]);
+}
+
+#[test]
+fn keys_values_and_entries_agree_across_receivers() {
+    let out = run_and_capture(
+        r#"
+        const receivers = {
+            string: "ab",
+            number: 42,
+            boolean: true,
+            object: { a: 1, get b() { return "getter"; } },
+            array: [7, 8],
+            stringObject: Object("hi"),
+            numberObject: Object(5),
+            proxy: new Proxy({ x: 1, y: 2, z: 3 }, {
+                ownKeys() { return ["z", "x", "missing"]; },
+                get(target, key) { return "got " + key; },
+            }),
+        };
+        for (const name in receivers) {
+            const value = receivers[name];
+            console.log(name, JSON.stringify([
+                Object.keys(value), Object.values(value), Object.entries(value),
+            ]));
+        }
+        for (const nullish of [null, undefined]) {
+            for (const fn of [Object.keys, Object.values, Object.entries]) {
+                try {
+                    fn(nullish);
+                    console.log("no error");
+                } catch (e) {
+                    console.log(e instanceof TypeError);
+                }
+            }
+        }
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            r#"string [["0","1"],["a","b"],[["0","a"],["1","b"]]]"#,
+            "number [[],[],[]]",
+            "boolean [[],[],[]]",
+            r#"object [["a","b"],[1,"getter"],[["a",1],["b","getter"]]]"#,
+            r#"array [["0","1"],[7,8],[["0",7],["1",8]]]"#,
+            r#"stringObject [["0","1"],["h","i"],[["0","h"],["1","i"]]]"#,
+            "numberObject [[],[],[]]",
+            r#"proxy [["z","x"],["got z","got x"],[["z","got z"],["x","got x"]]]"#,
+            "true",
+            "true",
+            "true",
+            "true",
+            "true",
+            "true",
+        ]
+    );
+}
+
+#[test]
+fn computed_keys_go_through_to_property_key() {
+    let out = run_and_capture(
+        r#"
+        const obj = {};
+        obj[1] = "one";
+        obj[-0] = "zero";
+        console.log(obj["1"], obj["0"], Object.keys(obj).join(","));
+        const sym = Symbol("k");
+        obj[sym] = 5;
+        console.log(obj[sym], obj["Symbol(k)"], sym in obj, Object.keys(obj).length);
+        const key = { toString() { return "named"; } };
+        obj[key] = 3;
+        console.log(obj.named);
+        const { [sym]: viaSymbol, [1]: viaNumber } = obj;
+        console.log(viaSymbol, viaNumber);
+        const literal = { [sym]: "lit", [2]: "two" };
+        console.log(literal[sym], literal["2"]);
+        Object.defineProperty(obj, sym, { value: 6 });
+        console.log(Object.getOwnPropertyDescriptor(obj, sym).value, Reflect.has(obj, sym));
+        console.log(delete obj[sym], sym in obj);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "one zero 0,1",
+            "5 undefined true 2",
+            "3",
+            "5 one",
+            "lit two",
+            "6 true",
+            "true false",
+        ]
+    );
+}
+
+#[test]
+fn null_prototype_objects_do_not_inherit_from_object_prototype() {
+    let src = r#"
+        Object.prototype.greet = function () { return "hi"; };
+        const bare = Object.create(null);
+        const child = Object.create(bare);
+        console.log(({}).greet());
+        console.log(bare.greet, child.greet, "greet" in bare);
+        console.log(bare instanceof Object, child instanceof Object);
+        let threw = false;
+        try { bare.hasOwnProperty("x"); } catch (err) { threw = err instanceof TypeError; }
+        console.log(threw);
+        const keys = [];
+        for (const k in child) keys.push(k);
+        console.log(keys.length);
+        const o = {};
+        Object.setPrototypeOf(o, null);
+        console.log(o.greet);
+        Object.setPrototypeOf(o, Object.prototype);
+        console.log(o.greet());
+    "#;
+    let out = run_and_capture(src);
+    assert_eq!(
+        out,
+        vec![
+            "hi",
+            "undefined undefined false",
+            "false false",
+            "true",
+            "0",
+            "undefined",
+            "hi"
+        ]
+    );
+}
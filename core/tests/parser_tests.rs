@@ -1,9 +1,10 @@
 use crustyjs::lexer::lex;
 use crustyjs::parser::ast::{
     BinOp, ClassMethodKind, Expr, Literal, ObjectProperty, OptionalOp, Param, Pattern, PropertyKey,
-    Stmt, VarDeclKind,
+    Slot, Stmt, VarDeclKind,
 };
-use crustyjs::parser::parse;
+use crustyjs::parser::{parse, parse_eager};
+use crustyjs::runtime::interpreter::Interpreter;
 
 fn parse_source(source: &str) -> Vec<Stmt> {
     let tokens = lex(source).expect("lexing should succeed");
@@ -73,7 +74,7 @@ fn parse_function_declaration() {
                     default: None,
                 }]
             );
-            assert_eq!(body.len(), 1);
+            assert_eq!(body.stmts().unwrap().len(), 1);
         }
         other => panic!("expected FunctionDecl, got {:?}", other),
     }
@@ -157,7 +158,7 @@ fn parse_try_catch_finally_statement() {
             finally_block,
             ..
         } => {
-            assert_eq!(catch_param.as_deref(), Some("e"));
+            assert_eq!(catch_param, &Some(Pattern::Identifier("e".into())));
             assert!(catch_block.is_some());
             assert!(finally_block.is_some());
         }
@@ -178,6 +179,78 @@ fn parse_new_expression() {
     }
 }
 
+#[test]
+fn parse_new_binds_before_member_access_and_calls() {
+    let stmts = parse_source("new Set(items).size; new Foo().bar();");
+    match &stmts[0] {
+        Stmt::ExprStmt(Expr::MemberAccess { object, property }) => {
+            assert_eq!(property, "size");
+            assert!(matches!(&**object, Expr::New { args, .. } if args.len() == 1));
+        }
+        other => panic!("expected member access on New, got {other:?}"),
+    }
+    match &stmts[1] {
+        Stmt::ExprStmt(Expr::Call { callee, args }) => {
+            assert!(args.is_empty());
+            assert!(matches!(
+                &**callee,
+                Expr::MemberAccess { object, .. } if matches!(&**object, Expr::New { .. })
+            ));
+        }
+        other => panic!("expected call on New, got {other:?}"),
+    }
+}
+
+#[test]
+fn parse_new_takes_member_path_callees() {
+    let stmts = parse_source("new a.b.C(1); new ns[\"Widget\"];");
+    match &stmts[0] {
+        Stmt::ExprStmt(Expr::New { callee, args }) => {
+            assert_eq!(args.len(), 1);
+            assert!(matches!(
+                &**callee,
+                Expr::MemberAccess { object, property }
+                    if property == "C" && matches!(&**object, Expr::MemberAccess { .. })
+            ));
+        }
+        other => panic!("expected New on a member path, got {other:?}"),
+    }
+    match &stmts[1] {
+        Stmt::ExprStmt(Expr::New { callee, args }) => {
+            assert!(args.is_empty());
+            assert!(matches!(&**callee, Expr::ComputedMemberAccess { .. }));
+        }
+        other => panic!("expected New on a computed member, got {other:?}"),
+    }
+}
+
+#[test]
+fn parse_new_with_parenthesized_and_nested_callees() {
+    let stmts = parse_source("new (getClass())(x); new new Foo()();");
+    match &stmts[0] {
+        Stmt::ExprStmt(Expr::New { callee, args }) => {
+            assert_eq!(args, &vec![Expr::Identifier("x".into())]);
+            assert!(matches!(
+                &**callee,
+                Expr::Call { callee, args } if args.is_empty()
+                    && **callee == Expr::Identifier("getClass".into())
+            ));
+        }
+        other => panic!("expected New on a parenthesized call, got {other:?}"),
+    }
+    match &stmts[1] {
+        Stmt::ExprStmt(Expr::New { callee, args }) => {
+            assert!(args.is_empty());
+            assert!(matches!(
+                &**callee,
+                Expr::New { callee, args } if args.is_empty()
+                    && **callee == Expr::Identifier("Foo".into())
+            ));
+        }
+        other => panic!("expected nested New, got {other:?}"),
+    }
+}
+
 #[test]
 fn parse_expression_without_semicolon_at_eof() {
     let stmts = parse_source("console.log(1)");
@@ -229,6 +302,38 @@ fn parse_object_method_shorthand() {
     }
 }
 
+#[test]
+fn parse_object_async_and_generator_methods() {
+    let stmts = parse_source("let obj = { async load() {}, *items() {}, async *[key]() {} }; ");
+    match &stmts[0] {
+        Stmt::VarDecl {
+            init: Some(Expr::ObjectLiteral { properties }),
+            ..
+        } => {
+            let flags: Vec<_> = properties
+                .iter()
+                .map(|property| match property {
+                    ObjectProperty::KeyValue(
+                        _,
+                        Expr::FunctionExpr {
+                            is_async,
+                            is_generator,
+                            ..
+                        },
+                    ) => (*is_async, *is_generator),
+                    other => panic!("expected method, got {other:?}"),
+                })
+                .collect();
+            assert_eq!(flags, vec![(true, false), (false, true), (true, true)]);
+            assert!(matches!(
+                properties[2],
+                ObjectProperty::KeyValue(PropertyKey::Computed(_), _)
+            ));
+        }
+        other => panic!("expected object literal var decl, got {other:?}"),
+    }
+}
+
 #[test]
 fn parse_object_getter_and_setter() {
     let stmts = parse_source("let obj = { get x() { return 1; }, set x(v) { this._x = v; } }; ");
@@ -274,6 +379,29 @@ fn parse_class_getter_and_setter() {
     }
 }
 
+#[test]
+fn parse_static_class_members() {
+    let stmts = parse_source(
+        "class Duck { static [Symbol.hasInstance](v) { return true; } static() { } static get kind() { return 1; } }",
+    );
+    match &stmts[0] {
+        Stmt::Class(class_decl) => {
+            let flags: Vec<bool> = class_decl.methods.iter().map(|m| m.is_static).collect();
+            assert_eq!(flags, vec![true, false, true]);
+            assert!(matches!(
+                class_decl.methods[0].key,
+                PropertyKey::Computed(_)
+            ));
+            assert!(matches!(
+                &class_decl.methods[1].key,
+                PropertyKey::Identifier(name) if name == "static"
+            ));
+            assert_eq!(class_decl.methods[2].kind, ClassMethodKind::Getter);
+        }
+        other => panic!("expected class declaration, got {other:?}"),
+    }
+}
+
 #[test]
 fn parse_optional_chain_expression() {
     let stmts = parse_source("let x = obj?.a?.[k]?.(1);");
@@ -320,7 +448,11 @@ fn parse_for_in_statement() {
     let stmts = parse_source("for (let key in obj) { console.log(key); }");
     assert_eq!(stmts.len(), 1);
     match &stmts[0] {
-        Stmt::ForIn { variable, .. } => assert_eq!(variable, "key"),
+        Stmt::ForIn {
+            kind: Some(VarDeclKind::Let),
+            pattern: Pattern::Identifier(name),
+            ..
+        } => assert_eq!(name, "key"),
         other => panic!("expected for-in statement, got {other:?}"),
     }
 }
@@ -413,3 +545,287 @@ fn parse_rejects_escaped_future_reserved_in_strict() {
     let err = parse_error("\"use strict\"; var \\u0070\\u0075\\u0062\\u006c\\u0069\\u0063 = 1;");
     assert!(err.contains("unexpected token 'public'"));
 }
+
+#[test]
+fn parse_legacy_octal_only_in_sloppy_mode() {
+    let stmts = parse_source("0755; 089;");
+    assert!(matches!(
+        &stmts[0],
+        Stmt::ExprStmt(Expr::Literal(Literal::Number(n))) if *n == 493.0
+    ));
+    for source in ["\"use strict\"; 0755;", "\"use strict\"; 089;"] {
+        let err = parse_error(source);
+        assert!(err.contains("not allowed in strict mode"), "{err}");
+    }
+}
+
+#[test]
+fn parse_rejects_delete_identifier_in_strict() {
+    assert!(matches!(
+        &parse_source("delete x;")[0],
+        Stmt::ExprStmt(Expr::Delete { strict: false, .. })
+    ));
+    let err = parse_error("\"use strict\"; delete x;");
+    assert!(err.contains("Delete of an unqualified identifier"), "{err}");
+    let stmts = parse_source("\"use strict\"; delete o.x;");
+    assert!(matches!(
+        &stmts[1],
+        Stmt::ExprStmt(Expr::Delete { strict: true, .. })
+    ));
+}
+
+#[test]
+fn parse_exponent_is_right_associative() {
+    let stmts = parse_source("2 ** 3 ** 2;");
+    match &stmts[0] {
+        Stmt::ExprStmt(Expr::Binary { op, right, .. }) => {
+            assert_eq!(*op, BinOp::Exp);
+            assert!(matches!(
+                right.as_ref(),
+                Expr::Binary { op: BinOp::Exp, .. }
+            ));
+        }
+        other => panic!("expected exponent expression, got {other:?}"),
+    }
+}
+
+#[test]
+fn parse_rejects_unary_operand_of_exponent() {
+    let err = parse_error("-2 ** 2;");
+    assert!(err.contains("'**'"), "unexpected error: {err}");
+}
+
+#[test]
+fn parse_multiple_declarators() {
+    let stmts = parse_source("let {a} = o, b = 2, c;");
+    match &stmts[0] {
+        Stmt::VarDeclList { kind, declarations } => {
+            assert_eq!(*kind, VarDeclKind::Let);
+            assert_eq!(declarations.len(), 3);
+            assert!(matches!(declarations[0].0, Pattern::ObjectPattern { .. }));
+            assert_eq!(declarations[1].0, Pattern::Identifier("b".into()));
+            assert!(declarations[2].1.is_none());
+        }
+        other => panic!("expected declaration list, got {other:?}"),
+    }
+}
+
+#[test]
+fn parse_rejects_const_without_initializer() {
+    let err = parse_error("const a = 1, b;");
+    assert!(
+        err.contains("missing initializer"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn parse_rejects_multiple_declarators_in_for_of_head() {
+    let err = parse_error("for (let a, b of items) {}");
+    assert!(err.contains("single binding"), "unexpected error: {err}");
+    let err = parse_error("for (let a = 1 of items) {}");
+    assert!(err.contains("initializer"), "unexpected error: {err}");
+}
+
+#[test]
+fn function_bodies_are_parsed_on_first_use() {
+    let source = "function outer(a) { let inner = function () { return [a, { b: `${a}` }]; }; return inner; }";
+    let lazy = parse_source(source);
+    let Stmt::FunctionDecl { body, .. } = &lazy[0] else {
+        panic!("expected FunctionDecl, got {:?}", lazy[0]);
+    };
+    assert!(!body.is_parsed());
+    let stmts = body.stmts().expect("body should parse");
+    assert!(body.is_parsed());
+    let Stmt::VarDecl {
+        init: Some(Expr::FunctionExpr { body: inner, .. }),
+        ..
+    } = &stmts[0]
+    else {
+        panic!("expected inner function, got {:?}", stmts[0]);
+    };
+    assert!(!inner.is_parsed());
+
+    let eager = parse_eager(lex(source).unwrap(), false).expect("parsing should succeed");
+    let Stmt::FunctionDecl { body, .. } = &eager.body[0] else {
+        panic!("expected FunctionDecl, got {:?}", eager.body[0]);
+    };
+    assert!(body.is_parsed());
+    assert_eq!(eager.body, lazy);
+}
+
+#[test]
+fn skipped_bodies_still_need_balanced_brackets() {
+    let err = parse_error("function f() { if (x] {} }");
+    assert!(
+        err.contains("expected RightParen"),
+        "unexpected error: {err}"
+    );
+    let err = parse_error("let g = function () { return [1, 2;");
+    assert!(err.contains("found Eof"), "unexpected error: {err}");
+
+    let stmts = parse_source("function f() { let = ; }\nf;");
+    let Stmt::FunctionDecl { body, .. } = &stmts[0] else {
+        panic!("expected FunctionDecl, got {:?}", stmts[0]);
+    };
+    let err = body.stmts().expect_err("the body should not parse");
+    assert_eq!(err.span.offset(), 19);
+    assert!(parse_eager(lex("function f() { let = ; }").unwrap(), false).is_err());
+}
+
+#[test]
+fn lazy_parsing_skips_most_of_a_large_bundle() {
+    let mut source: String = (0..1_000)
+        .map(|i| {
+            format!(
+                "function handler{i}(req, res) {{\n  const items = req.items.filter((x) => x.id !== {i});\n  for (let j = 0; j < items.length; j++) {{ res.push({{ id: items[j].id, total: items[j].price * {i} }}); }}\n  return res.length > 0 ? res : null;\n}}\n"
+            )
+        })
+        .collect();
+    source.push_str("console.log(handler7({ items: [{ id: 1, price: 2 }] }, []).length);");
+    let program = parse(lex(&source).unwrap()).expect("parsing should succeed");
+    let is_parsed = |stmt: &Stmt| match stmt {
+        Stmt::FunctionDecl { body, .. } => body.is_parsed(),
+        other => panic!("expected FunctionDecl, got {other:?}"),
+    };
+    assert!(!program.body[..1_000].iter().any(is_parsed));
+
+    let mut interp = Interpreter::new();
+    interp.run(&program).expect("the bundle should run");
+    assert_eq!(interp.output(), ["1"]);
+    let parsed: Vec<usize> = (0..1_000)
+        .filter(|&i| is_parsed(&program.body[i]))
+        .collect();
+    assert_eq!(parsed, [7]);
+}
+
+#[test]
+fn comma_operator_forms_sequences_where_an_expression_is_allowed() {
+    let is_pair = |expr: &Expr| matches!(expr, Expr::Sequence(exprs) if exprs.len() == 2);
+
+    let stmts = parse_source(
+        "if (a, b) x;\nwhile (a, b) x;\nthrow a, b;\nswitch (a, b) {}\no[a, b];\n`${a, b}`;",
+    );
+    let Stmt::If { condition, .. } = &stmts[0] else {
+        panic!("expected if, got {:?}", stmts[0]);
+    };
+    assert!(is_pair(condition));
+    let Stmt::While { condition, .. } = &stmts[1] else {
+        panic!("expected while, got {:?}", stmts[1]);
+    };
+    assert!(is_pair(condition));
+    let Stmt::Throw(thrown) = &stmts[2] else {
+        panic!("expected throw, got {:?}", stmts[2]);
+    };
+    assert!(is_pair(thrown));
+    let Stmt::Switch { discriminant, .. } = &stmts[3] else {
+        panic!("expected switch, got {:?}", stmts[3]);
+    };
+    assert!(is_pair(discriminant));
+    assert!(matches!(
+        &stmts[4],
+        Stmt::ExprStmt(Expr::ComputedMemberAccess { property, .. }) if is_pair(property)
+    ));
+    assert!(matches!(
+        &stmts[5],
+        Stmt::ExprStmt(Expr::TemplateLiteral { parts }) if parts.len() == 1
+    ));
+
+    let stmts = parse_source("for (i = 0, j = 9; i < j; i++, j--) {}");
+    let Stmt::ForLoop { init, update, .. } = &stmts[0] else {
+        panic!("expected for loop, got {:?}", stmts[0]);
+    };
+    assert!(matches!(init.as_deref(), Some(Stmt::ExprStmt(init)) if is_pair(init)));
+    assert!(update.as_ref().is_some_and(is_pair));
+}
+
+#[test]
+fn commas_in_arguments_elements_and_defaults_stay_separators() {
+    let stmts = parse_source(
+        "f(a, b);\n[a, b];\nfunction g(p = 1, q = 2) {}\nconst { x = 1, y } = o;\nconst [m = 1, n] = o;",
+    );
+    assert!(matches!(
+        &stmts[0],
+        Stmt::ExprStmt(Expr::Call { args, .. }) if args.len() == 2
+    ));
+    assert!(matches!(
+        &stmts[1],
+        Stmt::ExprStmt(Expr::ArrayLiteral { elements }) if elements.len() == 2
+    ));
+    assert!(matches!(
+        &stmts[2],
+        Stmt::FunctionDecl { params, .. }
+            if params.len() == 2
+                && params[0].default == Some(Expr::Literal(Literal::Number(1.0)))
+    ));
+    assert!(matches!(
+        &stmts[3],
+        Stmt::VarDecl { pattern: Pattern::ObjectPattern { properties }, .. }
+            if properties.len() == 2
+                && properties[0].default == Some(Expr::Literal(Literal::Number(1.0)))
+    ));
+    assert!(matches!(
+        &stmts[4],
+        Stmt::VarDecl { pattern: Pattern::ArrayPattern { elements }, .. } if elements.len() == 2
+    ));
+    assert!(parse_error("for (x of a, b) {}").contains("expected RightParen"));
+}
+
+#[test]
+fn parse_resolves_names_bound_in_the_function() {
+    let stmts = parse_source("function f(a) { let b = a; { let a = b; a = 1; } return g(b); }");
+    let Stmt::FunctionDecl { body, .. } = &stmts[0] else {
+        panic!("expected FunctionDecl, got {:?}", stmts[0]);
+    };
+    let body = body.stmts().unwrap();
+    let local = |name: &str, depth, index| Expr::Local {
+        name: name.into(),
+        slot: Slot { depth, index },
+    };
+    let Stmt::VarDecl { init, .. } = &body[0] else {
+        panic!("expected VarDecl, got {:?}", body[0]);
+    };
+    assert_eq!(init.as_ref(), Some(&local("a", 0, 0)));
+
+    let Stmt::Block(block) = &body[1] else {
+        panic!("expected Block, got {:?}", body[1]);
+    };
+    let Stmt::VarDecl { init, .. } = &block[0] else {
+        panic!("expected VarDecl, got {:?}", block[0]);
+    };
+    assert_eq!(init.as_ref(), Some(&local("b", 1, 1)));
+    assert!(matches!(
+        &block[1],
+        Stmt::ExprStmt(Expr::Assign {
+            slot: Some(Slot { depth: 0, index: 0 }),
+            ..
+        })
+    ));
+
+    // `g` is a global, which is looked up by name.
+    let Stmt::Return(Some(Expr::Call { callee, args })) = &body[2] else {
+        panic!("expected a returned call, got {:?}", body[2]);
+    };
+    assert_eq!(**callee, Expr::Identifier("g".into()));
+    assert_eq!(args[0], local("b", 0, 1));
+}
+
+#[test]
+fn nesting_past_the_limit_is_a_syntax_error() {
+    // Debug builds need a lot of stack per level to reach the limit.
+    let parse_deep = |source: String| {
+        std::thread::Builder::new()
+            .stack_size(256 * 1024 * 1024)
+            .spawn(move || parse(lex(&source).expect("lex failed")).map(|_| ()))
+            .expect("spawn parser thread")
+            .join()
+            .expect("parser thread should not panic")
+    };
+    let parens = format!("{}1{};", "(".repeat(100_000), ")".repeat(100_000));
+    let err = parse_deep(parens).expect_err("deep parentheses should not parse");
+    assert!(err.message.contains("nested too deeply"), "{}", err.message);
+    let blocks = format!("{}{}", "{".repeat(100_000), "}".repeat(100_000));
+    let err = parse_deep(blocks).expect_err("deep blocks should not parse");
+    assert!(err.message.contains("nested too deeply"), "{}", err.message);
+    assert!(parse_deep(format!("{}1{};", "[".repeat(100), "]".repeat(100))).is_ok());
+}
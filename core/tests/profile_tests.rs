@@ -0,0 +1,89 @@
+use crustyjs::Context;
+use crustyjs::diagnostics::profile::Profile;
+use crustyjs::lexer::lex;
+use crustyjs::parser::parse;
+use crustyjs::vm::compiler::Compiler;
+use crustyjs::vm::machine::VM;
+
+fn profile_of(source: &str) -> Profile {
+    let mut ctx = Context::new();
+    ctx.eval(source).expect("script runs");
+    ctx.take_profile()
+}
+
+#[test]
+fn interpreter_counts_match_a_known_workload() {
+    let profile = profile_of(
+        r#"
+        const xs = [];
+        for (let i = 0; i < 50; i++) {
+          xs.push(Math.floor(i / 2));
+        }
+        console.log(xs.join(","));
+        "#,
+    );
+
+    assert_eq!(profile.statements["ForLoop"], 1);
+    assert!(profile.statements["ExprStmt"] >= 51);
+    assert!(profile.expressions["Binary"] >= 100);
+    assert!(profile.expressions["Call"] >= 100);
+
+    let push = profile.builtins["Array.push"];
+    let floor = profile.builtins["Math.floor"];
+    assert_eq!(push.calls, 50);
+    assert_eq!(floor.calls, 50);
+    assert_eq!(profile.builtins["Array.join"].calls, 1);
+    assert_eq!(profile.builtins["console.log"].calls, 1);
+    assert!(push.time + floor.time > std::time::Duration::ZERO);
+    assert!(profile.opcodes.is_empty());
+
+    let ranked = profile.builtins_by_time();
+    assert!(
+        ranked
+            .windows(2)
+            .all(|pair| pair[0].1.time >= pair[1].1.time)
+    );
+}
+
+#[test]
+fn taking_the_profile_starts_the_counters_over() {
+    let mut ctx = Context::new();
+    ctx.eval("let n = 0; n++;").expect("script runs");
+    assert!(!ctx.take_profile().statements.is_empty());
+    let profile = ctx.take_profile();
+    assert!(profile.statements.is_empty() && profile.builtins.is_empty());
+}
+
+#[test]
+fn collector_pauses_are_reported() {
+    let mut ctx = Context::new();
+    ctx.eval("let garbage = [{}, {}, {}];")
+        .expect("script runs");
+    unsafe {
+        ctx.collect_garbage();
+        ctx.collect_garbage();
+    }
+    let gc = ctx.take_profile().gc;
+    assert_eq!(gc.pauses, 2);
+    assert!(gc.max_micros <= gc.total_micros);
+}
+
+#[test]
+fn vm_counts_each_opcode_it_executes() {
+    let source = "let i = 0;\nwhile (i < 5) { i = i + 1; }\nconsole.log(i);\n";
+    let program = parse(lex(source).expect("lexes")).expect("parses");
+    let mut compiler = Compiler::new();
+    let chunk = compiler.compile(program).expect("compiles");
+    assert!(!compiler.requires_tree_walk);
+
+    let mut vm = VM::new();
+    vm.set_output_mode(crustyjs::OutputMode::Capture);
+    vm.run(chunk, None, None).expect("runs");
+    let profile = vm.take_profile();
+
+    assert_eq!(profile.opcodes["Add"], 5);
+    assert_eq!(profile.opcodes["Loop"], 5);
+    assert_eq!(profile.opcodes["LessThan"], 6);
+    assert_eq!(profile.opcodes["Print"], 1);
+    assert_eq!(Profile::by_count(&profile.opcodes)[0].0, "GetGlobal");
+}
@@ -53,3 +53,112 @@ fn promise_finally_runs_and_passes_value() {
 
     assert_eq!(output, vec!["done", "3"]);
 }
+
+#[test]
+fn awaited_rejection_unwinds_through_catch_and_finally() {
+    let output = run_and_capture(
+        r#"
+        function failLater(reason) {
+          return new Promise((_, reject) => setTimeout(() => reject(reason), 1));
+        }
+
+        async function cleanupOnly() {
+          try {
+            await failLater("timeout");
+            console.log("unreachable");
+          } finally {
+            console.log("cleanup");
+          }
+        }
+
+        async function nested() {
+          try {
+            try {
+              await failLater("inner");
+            } finally {
+              console.log("inner finally");
+            }
+          } catch (e) {
+            console.log("caught", e);
+          } finally {
+            console.log("outer finally");
+          }
+          return "recovered";
+        }
+
+        async function early() {
+          try {
+            await null;
+            return "returned";
+          } finally {
+            console.log("finally before resolve");
+          }
+        }
+
+        cleanupOnly()
+          .catch(e => console.log("rejected", e))
+          .then(() => nested())
+          .then(v => console.log(v))
+          .then(() => early())
+          .then(v => console.log(v));
+        "#,
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "cleanup",
+            "rejected timeout",
+            "inner finally",
+            "caught inner",
+            "outer finally",
+            "recovered",
+            "finally before resolve",
+            "returned",
+        ]
+    );
+}
+
+#[test]
+fn finally_runs_when_catch_rethrows_after_await() {
+    let output = run_and_capture(
+        r#"
+        async function retry() {
+          try {
+            await Promise.reject("first");
+          } catch (e) {
+            await new Promise(resolve => setTimeout(resolve, 1));
+            console.log("catch", e);
+            throw "second";
+          } finally {
+            console.log("finally");
+          }
+        }
+        retry().catch(e => console.log("rejected", e));
+        "#,
+    );
+
+    assert_eq!(output, vec!["catch first", "finally", "rejected second"]);
+}
+
+#[test]
+fn promise_any_settles_with_the_first_fulfilment_or_an_aggregate_error() {
+    let output = run_and_capture(
+        r#"
+        Promise.any([Promise.reject(1), 2]).then(v => console.log("fulfilled", v));
+        Promise.any([Promise.reject(1), Promise.reject(new Error("two"))]).catch(e => {
+          console.log(e instanceof AggregateError, e.message, e.errors[0], e.errors[1].message);
+        });
+        Promise.any([]).catch(e => console.log(e.name, e.errors.length));
+        "#,
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "AggregateError 0",
+            "fulfilled 2",
+            "true All promises were rejected 1 two",
+        ]
+    );
+}
@@ -306,7 +306,7 @@ fn delete_nonexistent_property() {
         const obj = { a: 1 };
         console.log(delete obj.z);
     "#);
-    assert_eq!(out, vec!["false"]);
+    assert_eq!(out, vec!["true"]);
 }
 
 // ── Proxy deleteProperty trap ──
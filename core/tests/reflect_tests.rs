@@ -96,7 +96,7 @@ fn reflect_delete_property_nonexistent() {
         const obj = { a: 1 };
         console.log(Reflect.deleteProperty(obj, "z"));
     "#);
-    assert_eq!(out, vec!["false"]);
+    assert_eq!(out, vec!["true"]);
 }
 
 // ── Reflect.ownKeys ──
@@ -179,6 +179,32 @@ fn string_split_with_regex() {
     assert_eq!(out, vec!["3", "one", "two", "three"]);
 }
 
+#[test]
+fn string_split_with_regex_includes_capture_groups() {
+    let out = run(r#"
+        const myString = "Hello 1 word. Sentence number 2.";
+        console.log(myString.split(/(\d)/).join("|"));
+        console.log("a1b2c".split(/\d/).join("|"));
+        console.log("ab".split(/(x)?b/).length);
+    "#);
+    assert_eq!(
+        out,
+        vec!["Hello |1| word. Sentence number |2|.", "a|b|c", "3"]
+    );
+}
+
+#[test]
+fn string_split_with_empty_matching_regex_terminates() {
+    let out = run(r#"
+        console.log("abc".split(/(?:)/).join("|"));
+        console.log("a, b ,c".split(/\s*/).join("|"));
+        console.log("".split(/x/).length);
+        console.log("".split(/(?:)/).length);
+        console.log("a1b2c3".split(/\d/, 2).join("|"));
+    "#);
+    assert_eq!(out, vec!["a|b|c", "a|,|b|,|c", "1", "0", "a|b"]);
+}
+
 #[test]
 fn regex_typeof() {
     let out = run(r#"
@@ -227,3 +253,50 @@ fn regex_invalid_flags_error() {
     );
     assert!(err.contains("invalid regex flag"));
 }
+
+#[test]
+fn regex_unicode_positions_count_utf16_units() {
+    let out = run(r#"
+        const re = /\u{1F600}(.)/gu;
+        const s = "a😀b😀c";
+        let m = re.exec(s);
+        console.log(m[0], m[1], m.index, re.lastIndex);
+        m = re.exec(s);
+        console.log(m[0], m[1], m.index, re.lastIndex);
+        console.log(/😀+/u.exec("xy😀😀z").index, "😀!".search(/!/));
+    "#);
+    assert_eq!(out, vec!["😀b b 1 4", "😀c c 4 7", "2 2"]);
+}
+
+#[test]
+fn regex_unicode_escapes_match_code_points() {
+    let out = run(r#"
+        console.log(/^.$/u.test("😀"), /^😀$/u.test("😀"), /[😀-😂]/u.test("😁"));
+        console.log(/^\uD83D\uDE00$/u.test("😀"), /\u{41}\x42/u.test("AB"));
+        console.log(/\d+/u.exec("٣12")[0], /\w+/u.exec("é_a1")[0]);
+        console.log(/\p{Lu}+/u.exec("abcDEF")[0], /\p{Script=Greek}/u.test("α"), /\P{L}/u.test("a"));
+    "#);
+    assert_eq!(
+        out,
+        vec!["true true true", "true true", "12 _a1", "DEF true false"]
+    );
+}
+
+#[test]
+fn regex_unicode_rejects_forbidden_escapes_with_syntax_error() {
+    let out = run(r#"
+        for (const source of ["\\p{Nope}", "\\01", "\\a", "\\u{110000}"]) {
+            try {
+                new RegExp(source, "u");
+                console.log("compiled");
+            } catch (e) {
+                console.log(e.name);
+            }
+        }
+        console.log(new RegExp("\\a").source);
+    "#);
+    assert_eq!(
+        out,
+        vec!["SyntaxError", "SyntaxError", "SyntaxError", "SyntaxError", "\\a"]
+    );
+}
@@ -0,0 +1,95 @@
+use crustyjs::{ConsoleLevel, RunEvent, RunOptions, run_collect};
+
+fn log(level: ConsoleLevel, text: &str) -> RunEvent {
+    RunEvent::ConsoleLog {
+        level,
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn run_collect_orders_console_and_timer_events() {
+    let report = run_collect(
+        r#"
+        let total = 0;
+        console.log("start");
+        setTimeout(() => { total = total + 2; console.warn("late"); }, 50);
+        setTimeout(() => { total = total + 1; console.error("early"); }, 10);
+        Promise.resolve().then(() => console.info("micro"));
+        "#,
+        RunOptions {
+            globals: vec!["total".into(), "missing".into()],
+            ..RunOptions::default()
+        },
+    );
+    assert_eq!(
+        report.events,
+        vec![
+            log(ConsoleLevel::Log, "start"),
+            log(ConsoleLevel::Info, "micro"),
+            RunEvent::TimerFired { id: 2 },
+            log(ConsoleLevel::Error, "early"),
+            RunEvent::TimerFired { id: 1 },
+            log(ConsoleLevel::Warn, "late"),
+        ]
+    );
+    assert_eq!(report.globals.get("total").map(String::as_str), Some("3"));
+    assert!(!report.globals.contains_key("missing"));
+    assert_eq!(report.virtual_time_ms, 50);
+    assert!(report.steps > 0);
+}
+
+#[test]
+fn run_collect_reports_errors_and_unhandled_rejections() {
+    let report = run_collect(
+        r#"
+        Promise.reject(new Error("ignored"));
+        Promise.reject("caught").catch(() => {});
+        async function fail() { throw "boom"; }
+        fail();
+        console.log("before");
+        throw new TypeError("stop");
+        "#,
+        RunOptions::default(),
+    );
+    let [first, RunEvent::UncaughtError { message, .. }, rest @ ..] = &report.events[..] else {
+        panic!("unexpected events: {:?}", report.events);
+    };
+    assert_eq!(first, &log(ConsoleLevel::Log, "before"));
+    assert!(message.contains("stop"), "{message}");
+    assert_eq!(rest.len(), 2, "{rest:?}");
+    assert!(matches!(
+        &rest[1],
+        RunEvent::UnhandledRejection { value_string } if value_string == "boom"
+    ));
+
+    let report = run_collect("let = ;", RunOptions::default());
+    assert!(matches!(
+        &report.events[..],
+        [RunEvent::UncaughtError { .. }]
+    ));
+}
+
+#[test]
+fn run_collect_honours_step_limit_and_serializes() {
+    let report = run_collect(
+        "console.debug('spin'); while (true) {}",
+        RunOptions {
+            max_steps: Some(1_000),
+            ..RunOptions::default()
+        },
+    );
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(
+        json["events"][0],
+        serde_json::json!({ "type": "ConsoleLog", "level": "debug", "text": "spin" })
+    );
+    assert_eq!(json["events"][1]["type"], "UncaughtError");
+    assert!(
+        json["events"][1]["message"]
+            .as_str()
+            .unwrap()
+            .contains("step limit")
+    );
+    assert_eq!(json["globals"], serde_json::json!({}));
+}
@@ -0,0 +1,211 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crustyjs::{Context, MapFormat, Value, from_value, to_value, to_value_with};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Server {
+    name: String,
+    ports: Vec<u16>,
+    weight: f64,
+    backup: Option<Box<Server>>,
+    tags: BTreeMap<String, String>,
+    health: Health,
+    route: Route,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Health {
+    Up,
+    Degraded(u8),
+    Down { since: u32, reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Route {
+    Static { path: String },
+    Proxy { upstream: String, retries: u32 },
+}
+
+fn sample() -> Server {
+    Server {
+        name: "edge".into(),
+        ports: vec![80, 443],
+        weight: 0.1 + 0.2,
+        backup: Some(Box::new(Server {
+            name: "fallback".into(),
+            ports: Vec::new(),
+            weight: 1e300,
+            backup: None,
+            tags: BTreeMap::new(),
+            health: Health::Down {
+                since: 1_700_000_000,
+                reason: "maintenance".into(),
+            },
+            route: Route::Static {
+                path: "/var/www".into(),
+            },
+        })),
+        tags: BTreeMap::from([("region".into(), "eu".into())]),
+        health: Health::Degraded(3),
+        route: Route::Proxy {
+            upstream: "http://10.0.0.2".into(),
+            retries: 2,
+        },
+    }
+}
+
+#[test]
+fn nested_structs_and_enums_round_trip() {
+    let mut ctx = Context::new();
+    let server = sample();
+    let value = to_value(&mut ctx, &server).expect("serialize");
+    let back: Server = from_value(&ctx, &value).expect("deserialize");
+    assert_eq!(back, server);
+    assert_eq!(back.weight.to_bits(), (0.1f64 + 0.2).to_bits());
+}
+
+#[test]
+fn scripts_see_serialized_values_as_plain_data() {
+    let mut ctx = Context::new();
+    let value = to_value(&mut ctx, &sample()).expect("serialize");
+    ctx.set_global("server", value);
+    let summary = ctx
+        .eval_value(
+            r#"[
+              server.name,
+              server.ports.length,
+              server.backup.backup === null,
+              server.health.Degraded,
+              server.backup.health.Down.reason,
+              server.route.kind,
+              server.tags.region,
+            ].join(",")"#,
+        )
+        .expect("eval");
+    assert_eq!(
+        summary,
+        Value::String("edge,2,true,3,maintenance,proxy,eu".into())
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Settings {
+    title: String,
+    retries: Option<u32>,
+    verbose: Option<bool>,
+    limits: HashMap<u32, String>,
+    unit: (),
+}
+
+#[test]
+fn script_values_read_into_rust_types() {
+    let mut ctx = Context::new();
+    let value = ctx
+        .eval_value(
+            r#"({
+              title: "demo",
+              retries: 3,
+              verbose: undefined,
+              limits: { 10: "ten", 20: "twenty" },
+              unit: undefined,
+              ignored: () => "skipped fields are never read",
+            })"#,
+        )
+        .expect("eval");
+    let settings: Settings = from_value(&ctx, &value).expect("deserialize");
+    assert_eq!(
+        settings,
+        Settings {
+            title: "demo".into(),
+            retries: Some(3),
+            verbose: None,
+            limits: HashMap::from([(10, "ten".into()), (20, "twenty".into())]),
+            unit: (),
+        }
+    );
+}
+
+#[test]
+fn maps_and_sets_read_as_collections() {
+    let mut ctx = Context::new();
+    let value = ctx
+        .eval_value(r#"[new Map([[1, "one"], [2, "two"]]), new Set(["a", "b"])]"#)
+        .expect("eval");
+    let (map, set): (BTreeMap<u32, String>, Vec<String>) =
+        from_value(&ctx, &value).expect("deserialize");
+    assert_eq!(map, BTreeMap::from([(1, "one".into()), (2, "two".into())]));
+    assert_eq!(set, vec!["a", "b"]);
+}
+
+#[test]
+fn maps_can_serialize_as_entry_arrays() {
+    let mut ctx = Context::new();
+    let scores = BTreeMap::from([((1, 2), "pair"), ((3, 4), "other")]);
+    assert!(to_value(&mut ctx, &scores).is_err());
+
+    let value = to_value_with(&mut ctx, &scores, MapFormat::Entries).expect("serialize");
+    ctx.set_global("entries", value);
+    let found = ctx
+        .eval_value("const m = new Map(entries); m.size + ':' + entries[1][0][1]")
+        .expect("eval");
+    assert_eq!(found, Value::String("2:4".into()));
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Root {
+    config: Config,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    handlers: Vec<u32>,
+}
+
+#[test]
+fn conversion_errors_name_the_path_to_the_value() {
+    let mut ctx = Context::new();
+    let value = ctx
+        .eval_value("({ config: { handlers: [1, 2, () => 3] } })")
+        .expect("eval");
+    let err = from_value::<Root>(&ctx, &value).expect_err("functions cannot be read");
+    assert_eq!(err.path(), "config.handlers[2]");
+    assert_eq!(
+        err.to_string(),
+        "cannot convert a function to a Rust value at config.handlers[2]"
+    );
+
+    let value = ctx
+        .eval_value(r#"({ config: { handlers: [1, "two"] } })"#)
+        .expect("eval");
+    let err = from_value::<Root>(&ctx, &value).expect_err("a string is not a u32");
+    assert_eq!(err.path(), "config.handlers[1]");
+
+    let value = ctx
+        .eval_value("const loop = { config: {} }; loop.config.handlers = [loop]; loop")
+        .expect("eval");
+    let err = from_value::<serde_json::Value>(&ctx, &value).expect_err("cycles are refused");
+    assert_eq!(err.path(), "config.handlers[0]");
+
+    let err = to_value(&mut ctx, &vec![u64::MAX]).expect_err("precision would be lost");
+    assert_eq!(
+        err.to_string(),
+        "18446744073709551615 cannot be represented exactly as a number at [0]"
+    );
+}
+
+#[test]
+fn typed_arrays_convert_to_byte_vectors() {
+    let mut ctx = Context::new();
+    let value = ctx.create_uint8_array(&[1, 2, 250]);
+    let bytes: Vec<u8> = from_value(&ctx, &value).expect("a Uint8Array converts to bytes");
+    assert_eq!(bytes, vec![1, 2, 250]);
+    let value = ctx.eval_value("new Float64Array([0.5, -2])").expect("eval");
+    assert_eq!(
+        from_value::<Vec<f64>>(&ctx, &value).expect("floats"),
+        vec![0.5, -2.0]
+    );
+}
@@ -0,0 +1,159 @@
+use crustyjs::{Context, Value};
+
+const PRELUDE: &str = r#"
+    class Counter {
+        constructor(start) { this.count = start; }
+        bump() { this.count += 1; return this.count; }
+    }
+    function makeAdder(n) { return (x) => x + n; }
+    const addTen = makeAdder(10);
+    const config = { name: "prelude", tags: ["a", "b"], lookup: new Map([["k", 1]]) };
+    config.self = config;
+    let hits = 0;
+    function hit() { hits += 1; return hits; }
+"#;
+
+fn prelude_context() -> Context {
+    let mut ctx = Context::new();
+    ctx.eval(PRELUDE).expect("prelude should run");
+    ctx
+}
+
+#[test]
+fn restored_context_keeps_functions_closures_and_classes() {
+    let snapshot = prelude_context()
+        .snapshot()
+        .expect("snapshot should succeed");
+    let mut ctx = Context::from_snapshot(&snapshot);
+
+    let value = ctx
+        .eval_value(
+            r#"
+            const c = new Counter(5);
+            c.bump();
+            [addTen(1), c.bump(), c instanceof Counter, config.self === config,
+             config.tags.join(""), config.lookup.get("k"), hit(), hit()].join(",")
+            "#,
+        )
+        .expect("restored globals should work");
+    assert_eq!(value, Value::String("11,7,true,true,ab,1,1,2".into()));
+}
+
+#[test]
+fn restored_contexts_do_not_share_state() {
+    let snapshot = prelude_context()
+        .snapshot()
+        .expect("snapshot should succeed");
+    let mut first = Context::from_snapshot(&snapshot);
+    let mut second = Context::from_snapshot(&snapshot);
+
+    first
+        .eval("hit(); hit(); config.name = 'changed'; config.tags.push('c');")
+        .expect("eval should succeed");
+    let value = second
+        .eval_value("[hit(), config.name, config.tags.length].join(',')")
+        .expect("eval should succeed");
+    assert_eq!(value, Value::String("1,prelude,2".into()));
+}
+
+#[test]
+fn restoring_does_not_run_the_prelude_again() {
+    let heavy = r#"
+        const table = [];
+        for (let i = 0; i < 20000; i++) { table.push(i * i % 97); }
+        function lookup(i) { return table[i]; }
+    "#;
+
+    let mut ctx = Context::new();
+    let fresh_steps = ctx.step_count();
+    ctx.eval(heavy).expect("prelude should run");
+    assert!(ctx.step_count() - fresh_steps > 20000);
+
+    let snapshot = ctx.snapshot().expect("snapshot should succeed");
+    let mut restored = Context::from_snapshot(&snapshot);
+    assert_eq!(restored.step_count(), fresh_steps);
+
+    assert_eq!(
+        restored
+            .eval_value("lookup(12345)")
+            .expect("lookup should run"),
+        Value::Number((12345.0f64 * 12345.0) % 97.0)
+    );
+}
+
+#[test]
+fn snapshotting_a_pending_promise_names_its_path() {
+    let mut ctx = Context::new();
+    ctx.eval("const state = { ready: new Promise(() => {}) };")
+        .expect("eval should succeed");
+
+    let err = ctx.snapshot().expect_err("a promise cannot be snapshotted");
+    assert!(
+        err.to_string().contains("state.ready"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn host_functions_are_registered_again_by_name() {
+    let mut ctx = Context::new();
+    ctx.set_global_function("double", |args| {
+        Ok(Value::Number(
+            args.get(0).map_or(f64::NAN, Value::to_number) * 2.0,
+        ))
+    });
+    ctx.eval("const tools = { double };")
+        .expect("eval should succeed");
+
+    let snapshot = ctx.snapshot().expect("snapshot should succeed");
+    assert_eq!(snapshot.host_functions(), ["double".to_string()]);
+
+    let mut restored = Context::from_snapshot(&snapshot);
+    let err = restored
+        .eval("tools.double(2);")
+        .expect_err("an unregistered host function cannot be called");
+    assert!(
+        err.to_string().contains("double"),
+        "unexpected error: {err}"
+    );
+
+    restored.set_global_function("double", |args| {
+        Ok(Value::Number(
+            args.get(0).map_or(f64::NAN, Value::to_number) * 3.0,
+        ))
+    });
+    assert_eq!(
+        restored
+            .eval_value("tools.double(2)")
+            .expect("the registered function should be called"),
+        Value::Number(6.0)
+    );
+}
+
+#[test]
+fn restored_classes_keep_private_members_and_fields() {
+    let mut ctx = Context::new();
+    ctx.eval(
+        r#"
+        class Account {
+            #balance = 10;
+            kind = "savings";
+            deposit(n) { this.#balance += n; return this.#balance; }
+            static owns(o) { return #balance in o; }
+        }
+        const existing = new Account();
+        existing.deposit(5);
+        "#,
+    )
+    .expect("prelude should run");
+    let snapshot = ctx.snapshot().expect("snapshot should succeed");
+    let mut restored = Context::from_snapshot(&snapshot);
+
+    let value = restored
+        .eval_value(
+            "[existing.deposit(1), new Account().deposit(2), new Account().kind, \
+             Account.owns(existing), Account.owns({})].join(',')",
+        )
+        .expect("restored classes should work");
+    assert_eq!(value, Value::String("16,12,savings,true,false".into()));
+}
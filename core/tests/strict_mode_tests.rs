@@ -0,0 +1,240 @@
+use crustyjs::lexer::lex;
+use crustyjs::parser::parse;
+use crustyjs::runtime::interpreter::Interpreter;
+
+/// Runs `source`, returning what it printed or the message of the syntax
+/// or runtime error it stopped on.
+fn run(source: &str) -> Result<Vec<String>, String> {
+    let tokens = lex(source).map_err(|err| err.to_string())?;
+    let program = parse(tokens).map_err(|err| err.to_string())?;
+    let mut interp = Interpreter::new();
+    interp.run(&program).map_err(|err| err.to_string())?;
+    Ok(interp.output().to_vec())
+}
+
+/// Runs `snippet` as sloppy mode code and then as strict mode code.
+fn sloppy_and_strict(snippet: &str) -> (Result<Vec<String>, String>, Result<Vec<String>, String>) {
+    (run(snippet), run(&format!("\"use strict\";\n{snippet}")))
+}
+
+fn expect_error(outcome: Result<Vec<String>, String>, needle: &str) {
+    match outcome {
+        Ok(output) => panic!("expected an error mentioning {needle:?}, got output {output:?}"),
+        Err(message) => assert!(
+            message.contains(needle),
+            "expected an error mentioning {needle:?}, got {message:?}"
+        ),
+    }
+}
+
+#[test]
+fn assigning_an_undeclared_name() {
+    let (sloppy, strict) = sloppy_and_strict(
+        r#"
+        function leak() { leaked = 1; }
+        leak();
+        console.log(leaked, globalThis.leaked);
+        "#,
+    );
+    assert_eq!(sloppy, Ok(vec!["1 1".to_string()]));
+    expect_error(strict, "'leaked' is not defined");
+}
+
+#[test]
+fn writing_a_read_only_property() {
+    let (sloppy, strict) = sloppy_and_strict(
+        r#"
+        const point = Object.freeze({ x: 1 });
+        point.x = 2;
+        point.x += 1;
+        console.log(point.x);
+        "#,
+    );
+    assert_eq!(sloppy, Ok(vec!["1".to_string()]));
+    expect_error(strict, "Cannot assign to read only property 'x' of object");
+
+    let (sloppy, strict) = sloppy_and_strict(
+        r#"
+        const list = Object.freeze([1, 2]);
+        list[0] = 9;
+        console.log(list[0]);
+        "#,
+    );
+    assert_eq!(sloppy, Ok(vec!["1".to_string()]));
+    expect_error(strict, "Cannot assign to read only property '0' of object");
+}
+
+#[test]
+fn writing_an_accessor_without_a_setter() {
+    let (sloppy, strict) = sloppy_and_strict(
+        r#"
+        const counter = { get value() { return 1; } };
+        counter.value = 2;
+        console.log(counter.value);
+        "#,
+    );
+    assert_eq!(sloppy, Ok(vec!["1".to_string()]));
+    expect_error(strict, "which has only a getter");
+}
+
+#[test]
+fn adding_a_property_to_a_non_extensible_object() {
+    let (sloppy, strict) = sloppy_and_strict(
+        r#"
+        const sealed = Object.preventExtensions({});
+        sealed.added = 1;
+        console.log(sealed.added);
+        "#,
+    );
+    assert_eq!(sloppy, Ok(vec!["undefined".to_string()]));
+    expect_error(
+        strict,
+        "Cannot add property added, object is not extensible",
+    );
+}
+
+#[test]
+fn strict_writes_that_succeed_behave_as_in_sloppy_mode() {
+    let snippet = r#"
+        const target = { x: 1 };
+        target.x = 2;
+        target.y = 3;
+        const child = Object.create(target);
+        child.x = 4;
+        let seen;
+        const box = { set v(value) { seen = value; } };
+        box.v = 5;
+        console.log(target.x, target.y, child.x, seen);
+    "#;
+    let (sloppy, strict) = sloppy_and_strict(snippet);
+    assert_eq!(sloppy, Ok(vec!["2 3 4 5".to_string()]));
+    assert_eq!(strict, sloppy);
+}
+
+#[test]
+fn duplicate_parameter_names() {
+    let (sloppy, strict) =
+        sloppy_and_strict("function pick(a, a) { return a; }\nconsole.log(pick(1, 2));");
+    assert_eq!(sloppy, Ok(vec!["2".to_string()]));
+    expect_error(
+        strict,
+        "Duplicate parameter name not allowed in this context",
+    );
+
+    // Arrows and lists with destructuring, defaults or rest never allow
+    // them.
+    for source in [
+        "(a, a) => a;",
+        "function f(a, [b, a]) {}",
+        "function f(a, { b: a }) {}",
+        "function f(a, a = 1) {}",
+        "function f(a, ...a) {}",
+    ] {
+        let (sloppy, strict) = sloppy_and_strict(source);
+        expect_error(sloppy, "Duplicate parameter name");
+        expect_error(strict, "Duplicate parameter name");
+    }
+}
+
+#[test]
+fn octal_literals_and_escapes() {
+    let (sloppy, strict) = sloppy_and_strict("console.log(010);");
+    assert_eq!(sloppy, Ok(vec!["8".to_string()]));
+    expect_error(strict, "legacy octal");
+
+    let (sloppy, strict) = sloppy_and_strict(r#"const s = "\012"; console.log("parsed");"#);
+    assert_eq!(sloppy, Ok(vec!["parsed".to_string()]));
+    expect_error(
+        strict,
+        "octal escape sequences are not allowed in strict mode",
+    );
+
+    // `\0` alone and an escaped backslash before digits are not octal.
+    let (sloppy, strict) = sloppy_and_strict(r#"const s = "\0" + "\\012"; console.log("parsed");"#);
+    assert_eq!(sloppy, Ok(vec!["parsed".to_string()]));
+    assert_eq!(strict, sloppy);
+}
+
+#[test]
+fn deleting_an_unqualified_name() {
+    let (sloppy, strict) = sloppy_and_strict("var gone = 1;\nconsole.log(delete gone);");
+    assert_eq!(sloppy, Ok(vec!["false".to_string()]));
+    expect_error(
+        strict,
+        "Delete of an unqualified identifier in strict mode.",
+    );
+}
+
+#[test]
+fn binding_or_assigning_eval_and_arguments() {
+    for source in [
+        "function f(eval) { return eval; }\nconsole.log(f(1));",
+        "var arguments = 1;\nconsole.log(arguments);",
+        "let [eval] = [1];\nconsole.log(eval);",
+        "var arguments;\narguments = 1;\nconsole.log(arguments);",
+        "var eval = 0;\neval++;\nconsole.log(eval);",
+        "let eval;\n({ eval } = { eval: 1 });\nconsole.log(eval);",
+    ] {
+        let (sloppy, strict) = sloppy_and_strict(source);
+        assert_eq!(sloppy, Ok(vec!["1".to_string()]), "{source}");
+        expect_error(strict, "Unexpected eval or arguments in strict mode");
+    }
+}
+
+#[test]
+fn a_function_directive_makes_only_that_function_strict() {
+    let output = run(r#"
+        function strict() {
+          "use strict";
+          function inner() { innerLeak = 1; }
+          try { inner(); } catch (e) { console.log(e instanceof ReferenceError); }
+          try { strictLeak = 1; } catch (e) { console.log(e instanceof ReferenceError); }
+        }
+        strict();
+        const arrow = () => {
+          "use strict";
+          try { arrowLeak = 1; } catch (e) { console.log(e instanceof ReferenceError); }
+        };
+        arrow();
+        sloppyLeak = 1;
+        console.log(typeof innerLeak, typeof strictLeak, typeof arrowLeak, sloppyLeak);
+        "#);
+    assert_eq!(
+        output,
+        Ok(vec![
+            "true".to_string(),
+            "true".to_string(),
+            "true".to_string(),
+            "undefined undefined undefined 1".to_string(),
+        ])
+    );
+
+    assert!(run("function f(a, a) { \"use strict\"; }").is_err());
+    assert!(run("const f = (eval) => { \"use strict\"; };").is_err());
+}
+
+#[test]
+fn a_directive_only_counts_at_the_start_of_a_body() {
+    let output = run(r#"
+        function late() { console.log("first"); "use strict"; lateLeak = 1; }
+        late();
+        function escaped() { "use\x20strict"; escapedLeak = 2; }
+        escaped();
+        console.log(lateLeak, escapedLeak);
+        "#);
+    assert_eq!(output, Ok(vec!["first".to_string(), "1 2".to_string()]));
+}
+
+#[test]
+fn class_bodies_are_strict() {
+    let output = run(r#"
+        class Writer {
+          write() {
+            try { classLeak = 1; } catch (e) { console.log(e instanceof ReferenceError); }
+          }
+        }
+        new Writer().write();
+        "#);
+    assert_eq!(output, Ok(vec!["true".to_string()]));
+    assert!(run("class Pair { set(a, a) {} }").is_err());
+}
@@ -1,6 +1,7 @@
 use crustyjs::lexer::lex;
 use crustyjs::parser::parse;
 use crustyjs::runtime::interpreter::Interpreter;
+use crustyjs::{Context, Value};
 
 fn run_and_capture(source: &str) -> Vec<String> {
     let tokens = lex(source).expect("lexing should succeed");
@@ -183,3 +184,153 @@ fn template_literal_multiple_interpolations() {
     );
     assert_eq!(output, vec!["foo and bar"]);
 }
+
+#[test]
+fn split_honors_limit_and_empty_separator() {
+    let output = run_and_capture(
+        r#"
+        console.log("abc".split("", 2).join("|"));
+        console.log("abc".split("").join("|"));
+        console.log("a,b,c".split(",", 0).length);
+        console.log("a,b,c".split(",", 2).join("|"));
+        console.log("Oh brave new world".split(" ", 3).join("|"));
+    "#,
+    );
+    assert_eq!(output, vec!["a|b", "a|b|c", "0", "a|b", "Oh|brave|new"]);
+}
+
+#[test]
+fn split_without_separator_returns_whole_string() {
+    let output = run_and_capture(
+        r#"
+        const parts = "a,b".split();
+        console.log(parts.length);
+        console.log(parts[0]);
+        console.log("a,b".split(undefined, 0).length);
+    "#,
+    );
+    assert_eq!(output, vec!["1", "a,b", "0"]);
+}
+
+#[test]
+fn locale_compare_orders_case_and_equal_strings() {
+    let output = run_and_capture(
+        r#"
+        console.log("a".localeCompare("b"), "b".localeCompare("a"), "same".localeCompare("same"));
+        console.log("a".localeCompare("B"), "a".localeCompare("A"), "A".localeCompare("a"));
+        console.log("a".localeCompare("A", "en", { sensitivity: "base" }));
+        console.log("a".localeCompare("A", undefined, { sensitivity: "accent" }));
+        console.log(["b", "A", "a", "B"].sort((x, y) => x.localeCompare(y)).join());
+        console.log(["b", "A", "a", "Z"].sort().join());
+        "#,
+    );
+    assert_eq!(
+        output,
+        vec!["-1 1 0", "-1 -1 1", "0", "0", "a,A,b,B", "A,Z,a,b"]
+    );
+}
+
+#[test]
+fn locale_compare_folds_accents_by_sensitivity() {
+    let mut ctx = Context::new();
+    ctx.set_global("accented", Value::String("r\u{e9}sum\u{e9}".into()));
+    ctx.set_global("combining", Value::String("re\u{301}sume\u{301}".into()));
+    let value = ctx
+        .eval_value(
+            r#"
+            [
+                accented.localeCompare("resume"),
+                "resume".localeCompare(accented),
+                accented.localeCompare("resumf"),
+                accented.localeCompare("RESUME", undefined, { sensitivity: "base" }),
+                accented.localeCompare("resume", undefined, { sensitivity: "accent" }),
+                accented.localeCompare("resume", undefined, { sensitivity: "case" }),
+                accented.localeCompare(combining, undefined, { sensitivity: "accent" }),
+                ["Resume", accented, "resume"].sort((x, y) => x.localeCompare(y))[2] === accented,
+            ].join()
+            "#,
+        )
+        .unwrap();
+    assert_eq!(value.to_string(), "1,-1,-1,0,1,0,0,true");
+}
+
+#[test]
+fn plus_equals_builds_a_megabyte_string_in_linear_time() {
+    let start = std::time::Instant::now();
+    let out = run_and_capture(
+        r#"
+        let html = "";
+        for (let i = 0; i < 65536; i++) {
+            html += "<li>entry1</li>\n";
+        }
+        let copy = html;
+        html += "!";
+        console.log(html.length, copy.length, copy.indexOf("!"));
+        "#,
+    );
+    assert_eq!(out, vec!["1048577 1048576 -1"]);
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(20),
+        "building 1MB took {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn string_raw_keeps_template_escapes_uncooked() {
+    let out = run_and_capture(
+        r#"
+        const name = "log";
+        console.log(String.raw`C:\temp\new_${name}.txt`);
+        console.log(String.raw`\u00e9${1 + 1}\x41` === "\\u00e9" + "2" + "\\x41");
+        function tag(strings) { return strings[0] + "|" + strings.raw[0]; }
+        console.log(tag`a\tb`);
+        "#,
+    );
+    assert_eq!(out, vec!["C:\\temp\\new_log.txt", "true", "a\tb|a\\tb"]);
+}
+
+#[test]
+fn string_raw_reads_any_object_with_raw_strings() {
+    let out = run_and_capture(
+        r#"
+        console.log(String.raw({ raw: ["a", "b", "c"] }, 1, 2, 3));
+        console.log(String.raw({ raw: "xyz" }, "-", "+"));
+        console.log(String.raw({ raw: [] }, 1) === "");
+        "#,
+    );
+    assert_eq!(out, vec!["a1b2c", "x-y+z", "true"]);
+}
+
+#[test]
+fn string_from_char_code_and_from_code_point() {
+    let out = run_and_capture(
+        r#"
+        console.log(String.fromCharCode(72, 105, 65536 + 33));
+        console.log(String.fromCharCode(0xD83D, 0xDE00) === "😀");
+        console.log(String.fromCodePoint(0x1F600, 97) === "😀a");
+        const fromCodePoint = String.fromCodePoint;
+        console.log(fromCodePoint(65));
+        for (const bad of [-1, 1.5, 0x110000, NaN]) {
+            try {
+                String.fromCodePoint(bad);
+            } catch (e) {
+                console.log(e instanceof RangeError, e.message);
+            }
+        }
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "Hi!",
+            "true",
+            "true",
+            "A",
+            "true Invalid code point -1",
+            "true Invalid code point 1.5",
+            "true Invalid code point 1114112",
+            "true Invalid code point NaN",
+        ]
+    );
+}
@@ -0,0 +1,186 @@
+use crustyjs::lexer::lex;
+use crustyjs::parser::parse;
+use crustyjs::runtime::interpreter::Interpreter;
+
+fn run_and_capture(source: &str) -> Vec<String> {
+    let tokens = lex(source).expect("lex failed");
+    let program = parse(tokens).expect("parse failed");
+    let mut interp = Interpreter::new();
+    interp.run(&program).expect("runtime error");
+    interp.output().to_vec()
+}
+
+fn run_error(source: &str) -> String {
+    let tokens = lex(source).expect("lex failed");
+    let program = parse(tokens).expect("parse failed");
+    let mut interp = Interpreter::new();
+    interp
+        .run(&program)
+        .expect_err("expected a runtime error")
+        .to_string()
+}
+
+#[test]
+fn reading_a_let_before_its_declaration_throws() {
+    let err = run_error("console.log(x); let x = 1;");
+    assert!(
+        err.contains("Cannot access 'x' before initialization"),
+        "{err}"
+    );
+    let err = run_error("const y = y + 1;");
+    assert!(
+        err.contains("Cannot access 'y' before initialization"),
+        "{err}"
+    );
+}
+
+#[test]
+fn the_dead_zone_shadows_outer_bindings() {
+    let out = run_and_capture(
+        r#"
+        let v = "outer";
+        {
+            try { v = "assigned"; } catch (e) { console.log(e.name, e.message); }
+            try { console.log(v); } catch (e) { console.log(e.message); }
+            let v = "inner";
+            console.log(v);
+        }
+        console.log(v);
+        switch (1) {
+            case 1:
+                try { s; } catch (e) { console.log(e.message); }
+                const s = 2;
+                console.log(s);
+        }
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "ReferenceError Cannot access 'v' before initialization",
+            "Cannot access 'v' before initialization",
+            "inner",
+            "outer",
+            "Cannot access 's' before initialization",
+            "2",
+        ]
+    );
+}
+
+#[test]
+fn typeof_throws_in_the_dead_zone_but_not_for_undeclared_names() {
+    let out = run_and_capture(
+        r#"
+        console.log(typeof undeclared);
+        try { typeof later; } catch (e) { console.log(e.name, e.message); }
+        let later = 1;
+        console.log(typeof later);
+        function f() {
+            try { typeof k; } catch (e) { console.log(e.message); }
+            const k = "";
+            return typeof k;
+        }
+        console.log(f());
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "undefined",
+            "ReferenceError Cannot access 'later' before initialization",
+            "number",
+            "Cannot access 'k' before initialization",
+            "string",
+        ]
+    );
+}
+
+#[test]
+fn closures_called_before_initialization_throw() {
+    let out = run_and_capture(
+        r#"
+        function read() { return value; }
+        try { read(); } catch (e) { console.log(e.message); }
+        let value = 42;
+        console.log(read());
+        const early = () => new Later();
+        try { early(); } catch (e) { console.log(e.message); }
+        class Later {}
+        console.log(early() instanceof Later);
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "Cannot access 'value' before initialization",
+            "42",
+            "Cannot access 'Later' before initialization",
+            "true",
+        ]
+    );
+}
+
+#[test]
+fn let_without_initializer_is_undefined_from_its_declaration() {
+    let out = run_and_capture(
+        r#"
+        try { x; } catch (e) { console.log(e.message); }
+        let x;
+        console.log(x);
+        for (let i = 0; i < 2; i++) {
+            let fresh;
+            console.log(fresh);
+            fresh = i;
+        }
+        "#,
+    );
+    assert_eq!(
+        out,
+        vec![
+            "Cannot access 'x' before initialization",
+            "undefined",
+            "undefined",
+            "undefined",
+        ]
+    );
+}
+
+#[test]
+fn for_let_gives_each_iteration_its_own_binding() {
+    let out = run_and_capture(
+        r#"
+        const fns = [];
+        for (let i = 0; i < 3; i++) {
+            fns.push(() => i);
+        }
+        console.log(fns.map((f) => f()).join());
+
+        const bumped = [];
+        for (let i = 0; i < 3; i++) {
+            bumped.push(() => i);
+            i++;
+        }
+        console.log(bumped.map((f) => f()).join());
+
+        const shared = [];
+        for (var j = 0; j < 3; j++) {
+            shared.push(() => j);
+        }
+        console.log(shared.map((f) => f()).join());
+
+        function* gen() {
+            const made = [];
+            for (let k = 0; k < 3; k++) {
+                yield k;
+                made.push(() => k);
+            }
+            return made.map((f) => f()).join();
+        }
+        const it = gen();
+        let step = it.next();
+        while (!step.done) step = it.next();
+        console.log(step.value);
+        "#,
+    );
+    assert_eq!(out, vec!["0,1,2", "1,3", "3,3,3", "0,1,2"]);
+}
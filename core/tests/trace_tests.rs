@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crustyjs::diagnostics::trace::{TraceConfig, TraceEvent, TraceEventKind};
+use crustyjs::{Context, Value};
+
+fn collecting(config: TraceConfig) -> (TraceConfig, Rc<RefCell<Vec<TraceEvent>>>) {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let sink = Rc::clone(&events);
+    let config = config.sink(move |event: &TraceEvent| sink.borrow_mut().push(event.clone()));
+    (config, events)
+}
+
+fn describe(events: &[TraceEvent]) -> Vec<String> {
+    events
+        .iter()
+        .map(|event| {
+            let what = match event.kind {
+                TraceEventKind::Statement(kind) => kind.to_string(),
+                TraceEventKind::Call => "call".to_string(),
+                TraceEventKind::Return => "return".to_string(),
+            };
+            format!("{}{what} {}", "  ".repeat(event.depth), event.function)
+        })
+        .collect()
+}
+
+const SCRIPT: &str = r#"
+function add(a, b) { return a + b; }
+let sum = 0;
+for (let i = 0; i < 100; i++) { sum = add(sum, i); }
+console.log(sum);
+"#;
+
+#[test]
+fn trace_records_statements_calls_and_returns_in_order() {
+    let (config, events) = collecting(
+        TraceConfig::new()
+            .statements(true)
+            .calls(true)
+            .max_events(12),
+    );
+    let mut ctx = Context::new();
+    ctx.set_trace(config);
+    ctx.eval(SCRIPT).expect("script runs");
+
+    assert_eq!(
+        describe(&events.borrow()),
+        vec![
+            "FunctionDecl <global>",
+            "VarDecl <global>",
+            "ForLoop <global>",
+            "VarDecl <global>",
+            "Block <global>",
+            "ExprStmt <global>",
+            "call add",
+            "  Return add",
+            "return add",
+            "Block <global>",
+            "ExprStmt <global>",
+            "call add",
+        ]
+    );
+    // The cap only stops the trace; the loop runs to the end.
+    assert!(matches!(ctx.get_global("sum"), Ok(Value::Number(n)) if n == 4950.0));
+}
+
+#[test]
+fn call_tracing_alone_skips_statements() {
+    let (config, events) = collecting(TraceConfig::new().calls(true).max_events(4));
+    let mut ctx = Context::new();
+    ctx.set_trace(config);
+    ctx.eval(SCRIPT).expect("script runs");
+
+    assert_eq!(
+        describe(&events.borrow()),
+        vec!["call add", "return add", "call add", "return add"]
+    );
+}
+
+#[test]
+fn path_filter_drops_events_from_other_sources() {
+    let (config, events) = collecting(
+        TraceConfig::new()
+            .statements(true)
+            .calls(true)
+            .path_filter("lib.js"),
+    );
+    let mut ctx = Context::new();
+    ctx.set_trace(config);
+    ctx.eval(SCRIPT).expect("script runs");
+    assert!(events.borrow().is_empty());
+}
+
+#[test]
+fn clearing_the_trace_stops_recording() {
+    let (config, events) = collecting(TraceConfig::new().statements(true));
+    let mut ctx = Context::new();
+    ctx.set_trace(config);
+    ctx.eval("let a = 1;").expect("script runs");
+    ctx.clear_trace();
+    ctx.eval("let b = 2;").expect("script runs");
+
+    assert_eq!(describe(&events.borrow()), vec!["VarDecl <global>"]);
+}
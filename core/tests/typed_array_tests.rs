@@ -0,0 +1,188 @@
+use crustyjs::lexer::lex;
+use crustyjs::parser::parse;
+use crustyjs::runtime::interpreter::Interpreter;
+use crustyjs::{Context, OutputMode};
+
+fn run(source: &str) -> Vec<String> {
+    let tokens = lex(source).expect("lex");
+    let program = parse(tokens).expect("parse");
+    let mut interp = Interpreter::new();
+    interp.run(&program).expect("run");
+    interp.output().to_vec()
+}
+
+#[test]
+fn elements_convert_to_their_type() {
+    let out = run(r#"
+        const u8 = new Uint8Array(3);
+        u8[0] = 257; u8[1] = -1; u8[2] = 3.7;
+        console.log(String(u8));
+        const i8 = new Int8Array([127, 128, -129]);
+        console.log(String(i8));
+        console.log(String(new Uint8ClampedArray([300, -5, 1.5, 2.5, NaN])));
+        console.log(new Uint32Array([-1])[0], new Int32Array([4294967295])[0]);
+        console.log(new Float32Array([0.1])[0], new Float64Array([0.1])[0]);
+        console.log(u8[3], u8.length, u8.byteLength, Uint16Array.BYTES_PER_ELEMENT);
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "1,255,3",
+            "127,-128,127",
+            "255,0,2,2,0",
+            "4294967295 -1",
+            "0.10000000149011612 0.1",
+            "undefined 3 3 2",
+        ]
+    );
+}
+
+#[test]
+fn views_share_their_buffer() {
+    let out = run(r#"
+        const buffer = new ArrayBuffer(8);
+        const bytes = new Uint8Array(buffer);
+        const words = new Uint16Array(buffer, 2, 2);
+        words[0] = 0x0102;
+        console.log(bytes[2], bytes[3], words.byteOffset, words.length);
+        console.log(words.buffer === buffer, buffer.byteLength);
+        const tail = bytes.subarray(-2);
+        tail[0] = 9;
+        console.log(bytes[6], tail.byteOffset);
+        const copy = bytes.slice(2, 4);
+        copy[0] = 0;
+        console.log(bytes[2], copy.length, copy.buffer === buffer);
+    "#);
+    assert_eq!(out, vec!["2 1 2 2", "true 8", "9 6", "2 2 false"]);
+}
+
+#[test]
+fn typed_arrays_are_built_from_arrays_iterables_and_array_likes() {
+    let out = run(r#"
+        console.log(String(new Int16Array([1, "2", true])));
+        console.log(String(new Uint8Array(new Set([4, 5]))));
+        console.log(String(new Uint8Array({ length: 2, 0: 7, 1: 8 })));
+        console.log(String(new Float64Array(new Uint8Array([6, 7]))));
+    "#);
+    assert_eq!(out, vec!["1,2,1", "4,5", "7,8", "6,7"]);
+}
+
+#[test]
+fn set_and_fill_write_ranges() {
+    let out = run(r#"
+        const a = new Uint8Array(6);
+        a.fill(1);
+        a.fill(2, 4);
+        a.set([7, 8], 1);
+        console.log(String(a));
+        a.set(a.subarray(0, 3), 3);
+        console.log(String(a));
+        try { a.set([1, 2], 5); } catch (e) { console.log(e.name, e.message); }
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "1,7,8,1,2,2",
+            "1,7,8,1,7,8",
+            "RangeError offset is out of bounds"
+        ]
+    );
+}
+
+#[test]
+fn typed_arrays_iterate_and_spread() {
+    let out = run(r#"
+        let total = 0;
+        for (const n of new Int8Array([1, -2, 3])) total += n;
+        console.log(total, Math.max(...new Uint16Array([3, 9, 4])));
+        console.log([...new Float32Array([0.5, 1.5])].join(" "));
+    "#);
+    assert_eq!(out, vec!["2 9", "0.5 1.5"]);
+}
+
+#[test]
+fn data_view_reads_and_writes_either_byte_order() {
+    let out = run(r#"
+        const view = new DataView(new ArrayBuffer(8));
+        view.setUint16(0, 0x1234);
+        console.log(view.getUint8(0), view.getUint8(1), view.getUint16(0, true));
+        view.setInt32(0, -2, true);
+        console.log(view.getInt32(0, true), view.getUint32(0, true), view.getInt8(0));
+        view.setFloat64(0, Math.PI);
+        console.log(view.getFloat64(0) === Math.PI, view.getFloat64(0, true) === Math.PI);
+        try { view.getUint32(6); } catch (e) { console.log(e.name, e.message); }
+        const part = new DataView(view.buffer, 2, 4);
+        console.log(part.byteOffset, part.byteLength, ArrayBuffer.isView(part));
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "18 52 13330",
+            "-2 4294967294 -2",
+            "true false",
+            "RangeError Offset is outside the bounds of the DataView",
+            "2 4 true",
+        ]
+    );
+}
+
+#[test]
+fn invalid_construction_throws() {
+    let out = run(r#"
+        try { Uint8Array(2); } catch (e) { console.log(e.name, e.message); }
+        try { new Uint8Array(-1); } catch (e) { console.log(e.name); }
+        try { new Uint16Array(new ArrayBuffer(3)); } catch (e) { console.log(e.message); }
+        try { new Int32Array(new ArrayBuffer(8), 2); } catch (e) { console.log(e.message); }
+        try { new DataView({}); } catch (e) { console.log(e.name); }
+        console.log(typeof new Uint8Array(1), new Uint8Array(1) instanceof Uint8Array);
+        console.log(new Uint8Array(1) instanceof Int8Array, new ArrayBuffer(1) instanceof ArrayBuffer);
+    "#);
+    assert_eq!(
+        out,
+        vec![
+            "TypeError Constructor Uint8Array requires 'new'",
+            "RangeError",
+            "byte length of Uint16Array should be a multiple of 2",
+            "start offset of Int32Array should be a multiple of 4",
+            "TypeError",
+            "object true",
+            "false true",
+        ]
+    );
+}
+
+#[test]
+fn detached_buffers_leave_their_views_empty() {
+    let mut ctx = Context::new_with_realtime(false);
+    ctx.set_output_mode(OutputMode::Capture);
+    unsafe { ctx.install_test262_host() };
+    ctx.eval(
+        r#"
+        const bytes = new Uint8Array([1, 2, 3]);
+        $262.detachArrayBuffer(bytes.buffer);
+        console.log(bytes.length, bytes[0], bytes.buffer.byteLength, bytes.buffer.detached);
+    "#,
+    )
+    .expect("eval should succeed");
+    assert_eq!(ctx.output(), ["0 undefined 0 true"]);
+}
+
+#[test]
+fn hosts_hand_bytes_in_and_read_them_back() {
+    let mut ctx = Context::new();
+    let input = ctx.create_uint8_array(&[1, 2, 3, 250]);
+    ctx.set_global("input", input);
+    let sum = ctx
+        .eval_value("let s = 0; for (const b of input) s += b; input[0] = 9; s")
+        .expect("eval should succeed");
+    assert_eq!(sum.to_number(), 256.0);
+
+    let input = ctx.get_global("input").expect("input is bound");
+    assert_eq!(ctx.bytes_of(&input), Some(vec![9, 2, 3, 250]));
+
+    let words = ctx
+        .eval_value("new Uint16Array([0x0102]).buffer")
+        .expect("eval should succeed");
+    assert_eq!(ctx.bytes_of(&words), Some(vec![2, 1]));
+    assert_eq!(ctx.bytes_of(&sum), None);
+}
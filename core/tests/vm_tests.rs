@@ -1,13 +1,15 @@
 use crustyjs::lexer::lex;
 use crustyjs::parser::parse;
+use crustyjs::runtime::interpreter::Interpreter;
 use crustyjs::vm::bytecode::Opcode;
 use crustyjs::vm::compiler::Compiler;
+use crustyjs::vm::machine::VM;
 
 fn compile_source(source: &str) -> Vec<Opcode> {
     let tokens = lex(source).expect("lex failed");
     let program = parse(tokens).expect("parse failed");
     let mut compiler = Compiler::new();
-    let chunk = compiler.compile(program);
+    let chunk = compiler.compile(program).expect("compile failed");
     chunk.instructions
 }
 
@@ -15,17 +17,36 @@ fn compile_source_with_fallback_flag(source: &str) -> (Vec<Opcode>, bool) {
     let tokens = lex(source).expect("lex failed");
     let program = parse(tokens).expect("parse failed");
     let mut compiler = Compiler::new();
-    let chunk = compiler.compile(program);
+    let chunk = compiler.compile(program).expect("compile failed");
     (chunk.instructions, compiler.requires_tree_walk)
 }
 
+fn assert_vm_matches_interpreter(source: &str) {
+    let program = parse(lex(source).expect("lex failed")).expect("parse failed");
+    let mut compiler = Compiler::new();
+    let chunk = compiler.compile(program.clone()).expect("compile failed");
+    assert!(
+        !compiler.requires_tree_walk,
+        "should compile without fallback"
+    );
+    let mut vm = VM::new();
+    vm.set_output_mode(crustyjs::OutputMode::Capture);
+    vm.run(chunk, None, None).expect("vm run should succeed");
+
+    let mut interp = Interpreter::new();
+    interp
+        .run(&program)
+        .expect("interpreter run should succeed");
+    assert_eq!(vm.output(), interp.output());
+}
+
 fn run_vm_source(source: &str) {
     crustyjs::run_vm(source).expect("vm run should succeed");
 }
 
 fn run_vm_file(path: &str) {
     let source = std::fs::read_to_string(path).expect("read vm source file");
-    crustyjs::run_vm_with_path(&source, Some(std::path::PathBuf::from(path)))
+    crustyjs::run_vm_with_path(&source, Some(std::path::PathBuf::from(path)), None)
         .expect("vm run with path should succeed");
 }
 
@@ -123,6 +144,21 @@ fn vm_compiles_assignment_without_treewalk_fallback() {
     assert!(!ops.is_empty());
 }
 
+#[test]
+fn logical_assignment_falls_back_to_treewalk() {
+    for source in [
+        "let a = 0; a ||= 1;",
+        "let a; a ??= 1;",
+        "let o = {}; o.a &&= 1;",
+    ] {
+        let (_, requires_tree_walk) = compile_source_with_fallback_flag(source);
+        assert!(requires_tree_walk, "{source}");
+    }
+    let output = crustyjs::run_vm_with_path("let a = 0; a ||= 7; console.log(a);", None, None)
+        .expect("fallback run should succeed");
+    assert_eq!(output, ["7"]);
+}
+
 #[test]
 fn vm_path_runs_kitchen_sink_example() {
     run_vm_file("examples/kitchen_sink.js");
@@ -132,3 +168,433 @@ fn vm_path_runs_kitchen_sink_example() {
 fn vm_path_runs_modules_example_with_entry_path() {
     run_vm_file("examples/modules/main.js");
 }
+
+#[test]
+fn compile_exponent_emits_exp_opcode() {
+    let ops = compile_source("let x = 2; x ** 3;");
+    assert!(ops.contains(&Opcode::Exp));
+}
+
+#[test]
+fn vm_print_respects_output_mode() {
+    let tokens = lex("console.log(40 + 2);").expect("lex failed");
+    let program = parse(tokens).expect("parse failed");
+    let chunk = Compiler::new().compile(program).expect("compile failed");
+    let mut vm = VM::new();
+    vm.set_output_mode(crustyjs::OutputMode::Capture);
+    vm.run(chunk, None, None).expect("vm run should succeed");
+    assert_eq!(vm.output(), ["42"]);
+}
+
+#[test]
+fn vm_compiles_declaration_list_without_treewalk_fallback() {
+    let (ops, requires_tree_walk) = compile_source_with_fallback_flag("let a = 1, b = a + 1;");
+    assert!(!requires_tree_walk);
+    assert_eq!(
+        ops.iter()
+            .filter(|op| matches!(op, Opcode::DefineGlobal(_, _)))
+            .count(),
+        2
+    );
+}
+
+fn assert_both_engines_reject(source: &str, name: &str) {
+    let expected = format!("Assignment to constant variable '{name}'");
+    let program = parse(lex(source).expect("lex failed")).expect("parse failed");
+    let mut compiler = Compiler::new();
+    let vm_err = match compiler.compile(program.clone()) {
+        Ok(chunk) => {
+            assert!(
+                !compiler.requires_tree_walk,
+                "should compile without fallback"
+            );
+            VM::new()
+                .run(chunk, None, None)
+                .expect_err("vm should reject the assignment")
+                .to_string()
+        }
+        Err(err) => err.to_string(),
+    };
+    let interp_err = Interpreter::new()
+        .run(&program)
+        .expect_err("interpreter should reject the assignment")
+        .to_string();
+    assert!(vm_err.contains(&expected), "vm: {vm_err}");
+    assert!(interp_err.contains(&expected), "interpreter: {interp_err}");
+}
+
+#[test]
+fn vm_rejects_global_const_reassignment_like_interpreter() {
+    assert_both_engines_reject("const x = 1; x = 2;", "x");
+    assert_both_engines_reject("const x = 1; x += 1;", "x");
+    assert_both_engines_reject("const x = 1; function f() { x++; } f();", "x");
+}
+
+#[test]
+fn vm_rejects_local_const_reassignment_at_compile_time() {
+    assert_both_engines_reject("{ const y = 1; y = 2; }", "y");
+    assert_both_engines_reject("function f() { const z = 1; z -= 1; } f();", "z");
+
+    let program = parse(lex("{ const y = 1; y = 2; }").expect("lex failed")).expect("parse failed");
+    let mut compiler = Compiler::new();
+    let err = compiler
+        .compile(program)
+        .expect_err("reassigning a const local is a compile error");
+    assert!(
+        err.to_string()
+            .contains("Assignment to constant variable 'y'")
+    );
+    assert_eq!(compiler.diagnostics.len(), 1);
+}
+
+#[test]
+fn vm_allows_reassigning_let_and_var_globals() {
+    assert_vm_matches_interpreter(
+        "let a = 1; a = 2; var b = 1; b += 2; var b = 5; console.log(a); console.log(b);",
+    );
+}
+
+#[test]
+fn vm_chunk_records_statement_offsets() {
+    let source = "let a = 1;\nlet b = 2;\n";
+    let program = parse(lex(source).expect("lex failed")).expect("parse failed");
+    assert_eq!(program.offsets, [0, 11]);
+    let chunk = Compiler::new().compile(program).expect("compile failed");
+    assert_eq!(chunk.offsets.len(), chunk.instructions.len());
+    assert_eq!(chunk.offsets.first(), Some(&0));
+    assert_eq!(chunk.offsets.last(), Some(&11));
+    let listing = chunk.disassemble_with_source(source);
+    assert!(listing.lines().any(|line| line.contains("|   2 |")));
+}
+
+#[test]
+fn vm_runtime_error_reports_source_position() {
+    let source = "function outer(f) {\n  f();\n}\n\nouter(1);\n";
+    let chunk = Compiler::new()
+        .compile(parse(lex(source).expect("lex failed")).expect("parse failed"))
+        .expect("compile failed");
+    let mut vm = VM::new();
+    let err = vm
+        .run(
+            chunk,
+            Some(source.to_string()),
+            Some(std::path::PathBuf::from("app.js")),
+        )
+        .expect_err("calling a number should fail");
+    let message = err.to_string();
+    assert_eq!(message, "TypeError: '1' is not a function");
+    let trace = vm.error_trace().expect("the error keeps its trace");
+    assert!(trace.contains("at outer (app.js:1:1)"), "{trace}");
+    assert!(trace.contains("at <global> (app.js:5:1)"), "{trace}");
+}
+
+#[test]
+fn compile_void_pops_operand_and_pushes_undefined() {
+    let ops = compile_source("let x = 1; void x;");
+    assert!(
+        ops.windows(3)
+            .any(|w| matches!(w, [Opcode::GetGlobal(_), Opcode::Pop, Opcode::Undefined]))
+    );
+    assert_vm_matches_interpreter("let x = 1; console.log(void x); console.log(void 0);");
+}
+
+#[test]
+fn compile_sequence_keeps_last_value() {
+    let (ops, requires_tree_walk) = compile_source_with_fallback_flag("let a = (1, 2, 3);");
+    assert!(!requires_tree_walk);
+    assert_eq!(ops.iter().filter(|op| **op == Opcode::Pop).count(), 2);
+    assert_vm_matches_interpreter(
+        r#"
+        let s = 0;
+        for (let i = 0, j = 4; i < j; i++, j--) {
+          s = s + i * j;
+        }
+        console.log(s);
+        console.log((1, "two", 3));
+        "#,
+    );
+}
+
+#[test]
+fn compile_typeof_undeclared_identifier_uses_typeof_global() {
+    let ops = compile_source("typeof missing;");
+    assert!(matches!(ops[0], Opcode::TypeofGlobal(_)));
+    let ops = compile_source("function f(a) { return typeof a; }");
+    assert!(!ops.iter().any(|op| matches!(op, Opcode::TypeofGlobal(_))));
+    assert_vm_matches_interpreter(
+        r#"
+        let n = 1;
+        let u;
+        function f() { return 0; }
+        console.log(typeof n);
+        console.log(typeof u);
+        console.log(typeof f);
+        console.log(typeof "s");
+        console.log(typeof missing);
+        "#,
+    );
+}
+
+#[test]
+fn compile_unary_plus_emits_to_number() {
+    let ops = compile_source(r#"+"7";"#);
+    assert!(ops.contains(&Opcode::ToNumber));
+    assert_vm_matches_interpreter(
+        r#"
+        console.log(+" 42 ");
+        console.log(+true);
+        console.log(+"0x10");
+        console.log(+"");
+        let k = "3";
+        console.log(k++);
+        console.log(k);
+        "#,
+    );
+}
+
+#[test]
+fn compile_this_and_method_calls_use_receiver_opcodes() {
+    let ops = compile_source("this; Math.max(1, 2);");
+    assert!(ops.contains(&Opcode::This));
+    assert!(ops.contains(&Opcode::Dup));
+    assert!(ops.contains(&Opcode::CallMethod(2)));
+    assert!(!ops.iter().any(|op| matches!(op, Opcode::Call(_))));
+    assert_vm_matches_interpreter(
+        r#"
+        console.log(typeof this);
+        console.log(this === globalThis);
+        console.log(this);
+        function f() { return this; }
+        console.log(f());
+        console.log(typeof f());
+        "#,
+    );
+}
+
+/// Operator results that must not depend on which engine runs them.
+const DIFFERENTIAL_EXPRESSIONS: &[&str] = &[
+    "undefined + 1",
+    "null + 1",
+    "true + true",
+    "true + 1",
+    "false + null",
+    "1 + \"2\"",
+    "\"1\" + 2",
+    "\"a\" + undefined",
+    "\"a\" + null",
+    "\"a\" + true",
+    "undefined + \"\"",
+    "1.5 + 2.25",
+    "0.1 + 0.2",
+    "null + \"\"",
+    "\"\" + 0",
+    "\"5\" - 2",
+    "\"5\" * \"4\"",
+    "\"x\" - 1",
+    "null * 3",
+    "true / 2",
+    "undefined - 1",
+    "\" 12 \" % 5",
+    "\"2\" ** 3",
+    "-\"3\"",
+    "-null",
+    "~\"7\"",
+    "\"6\" & 3",
+    "1 < \"2\"",
+    "\"10\" < 9",
+    "\"10\" < \"9\"",
+    "\"a\" < \"b\"",
+    "\"b\" > \"a\"",
+    "\"abc\" >= \"abd\"",
+    "\"abc\" <= \"abc\"",
+    "\"apple\" < \"banana\"",
+    "\"Z\" < \"a\"",
+    "\"abc\" < \"abcd\"",
+    "null < 1",
+    "null >= 0",
+    "null > 0",
+    "null <= 0",
+    "undefined < 1",
+    "undefined >= 0",
+    "undefined <= undefined",
+    "true > false",
+    "true >= 1",
+    "0/0 < 1",
+    "0/0 >= 1",
+    "0/0 <= 0/0",
+    "1 <= 0/0",
+    "\"x\" < 1",
+    "\"x\" >= 1",
+    "\"\" < 1",
+    "-1/0 < 1/0",
+    "2 > 1",
+    "2 >= 2",
+    "\"3\" > 2",
+];
+
+#[test]
+fn vm_and_interpreter_agree_on_operator_coercions() {
+    let source: String = DIFFERENTIAL_EXPRESSIONS
+        .iter()
+        .map(|expr| format!("console.log({expr});\n"))
+        .collect();
+    assert_vm_matches_interpreter(&source);
+}
+
+#[test]
+fn run_vm_captures_output_or_hands_it_to_a_sink() {
+    let lines = crustyjs::run_vm("console.log(1 + 2); console.log(\"done\");")
+        .expect("vm run should succeed");
+    assert_eq!(lines, vec!["3", "done"]);
+
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink_seen = std::sync::Arc::clone(&seen);
+    let sink: crustyjs::runtime::output::OutputSink =
+        std::sync::Arc::new(move |line| sink_seen.lock().unwrap().push(line.to_string()));
+    // The object literal sends this script down the interpreter fallback.
+    let source = "console.log(\"vm\"); const o = { a: 1 }; console.log(o.a);";
+    let returned =
+        crustyjs::run_vm_with_path(source, None, Some(sink)).expect("vm run should succeed");
+    assert!(returned.is_empty());
+    assert_eq!(*seen.lock().unwrap(), vec!["vm", "1"]);
+}
+
+#[test]
+fn vm_define_global_seeds_script_values() {
+    use crustyjs::vm::bytecode::VmValue;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink_seen = Arc::clone(&seen);
+    let mut vm = VM::new_with_sink(Arc::new(move |line| {
+        sink_seen.lock().unwrap().push(line.to_string())
+    }));
+    vm.define_global("base", VmValue::Number(40.0));
+    let config = HashMap::from([("name".to_string(), VmValue::String("demo".into()))]);
+    vm.define_global("config", VmValue::Object(Rc::new(RefCell::new(config))));
+
+    let source = "console.log(base + 2); console.log(config.name);";
+    let program = parse(lex(source).expect("lex failed")).expect("parse failed");
+    let mut compiler = Compiler::new();
+    let chunk = compiler.compile(program).expect("compile failed");
+    assert!(!compiler.requires_tree_walk);
+    vm.run(chunk, None, None).expect("vm run should succeed");
+
+    assert_eq!(*seen.lock().unwrap(), vec!["42", "demo"]);
+    assert!(vm.output().is_empty());
+}
+
+const DISASSEMBLY_SOURCE: &str = "function pick(a, b) {
+  if (a > b) {
+    return a;
+  } else {
+    return b;
+  }
+}
+let i = 0;
+while (i < 2) {
+  i = i + 1;
+}
+pick(i, \"ab\");
+";
+
+#[test]
+fn vm_disassembly_labels_jumps_and_lists_nested_functions() {
+    let program = parse(lex(DISASSEMBLY_SOURCE).expect("lex failed")).expect("parse failed");
+    let chunk = Compiler::new().compile(program).expect("compile failed");
+    let listing = chunk.disassemble_with_source(DISASSEMBLY_SOURCE);
+    let expected = [
+        "0000 |   1 | Constant(0)              ; <function pick/2>",
+        "0001 |   1 | DefineGlobal(1, Var)     ; \"pick\"",
+        "0002 |   8 | Constant(2)              ; 0",
+        "0003 |   8 | DefineGlobal(3, Let)     ; \"i\"",
+        "L1:",
+        "0004 |   9 | GetGlobal(4)             ; \"i\"",
+        "0005 |   9 | Constant(5)              ; 2",
+        "0006 |   9 | LessThan",
+        "0007 |   9 | JumpIfFalse(15)          -> 0015 (L2)",
+        "0008 |   9 | GetGlobal(6)             ; \"i\"",
+        "0009 |   9 | Constant(7)              ; 1",
+        "0010 |   9 | Add",
+        "0011 |   9 | SetGlobal(8)             ; \"i\"",
+        "0012 |   9 | GetGlobal(8)             ; \"i\"",
+        "0013 |   9 | Pop",
+        "0014 |   9 | Loop(4)                  -> 0004 (L1)",
+        "L2:",
+        "0015 |  12 | GetGlobal(9)             ; \"pick\"",
+        "0016 |  12 | GetGlobal(10)            ; \"i\"",
+        "0017 |  12 | Constant(11)             ; \"ab\"",
+        "0018 |  12 | Call(2)",
+        "0019 |  12 | Pop",
+        "-- constants --",
+        "0000 | function | <function pick/2>",
+        "0001 | string   | \"pick\"",
+        "0002 | number   | 0",
+        "0003 | string   | \"i\"",
+        "0004 | string   | \"i\"",
+        "0005 | number   | 2",
+        "0006 | string   | \"i\"",
+        "0007 | number   | 1",
+        "0008 | string   | \"i\"",
+        "0009 | string   | \"pick\"",
+        "0010 | string   | \"i\"",
+        "0011 | string   | \"ab\"",
+        "-- function pick (arity 2) --",
+        "  0000 |   1 | GetLocal(0)",
+        "  0001 |   1 | GetLocal(1)",
+        "  0002 |   1 | GreaterThan",
+        "  0003 |   1 | JumpIfFalse(7)           -> 0007 (L1)",
+        "  0004 |   1 | GetLocal(0)",
+        "  0005 |   1 | Return",
+        "  0006 |   1 | Jump(9)                  -> 0009 (L2)",
+        "  L1:",
+        "  0007 |   1 | GetLocal(1)",
+        "  0008 |   1 | Return",
+        "  L2:",
+        "  0009 |   1 | Undefined",
+        "  0010 |   1 | Return",
+    ];
+    assert_eq!(listing, format!("{}\n", expected.join("\n")));
+}
+
+#[test]
+fn vm_disassembles_a_single_instruction() {
+    let program = parse(lex(DISASSEMBLY_SOURCE).expect("lex failed")).expect("parse failed");
+    let chunk = Compiler::new().compile(program).expect("compile failed");
+    assert_eq!(
+        chunk.disassemble_instruction(1),
+        "0001 | DefineGlobal(1, Var)     ; \"pick\""
+    );
+    let jump = chunk
+        .instructions
+        .iter()
+        .position(|op| matches!(op, Opcode::JumpIfFalse(_)))
+        .expect("the loop condition jumps");
+    assert!(
+        chunk.disassemble_instruction(jump).ends_with("-> 0015"),
+        "{}",
+        chunk.disassemble_instruction(jump)
+    );
+}
+
+#[test]
+fn vm_compiles_comma_sequences_in_loops_statements_and_returns() {
+    assert_vm_matches_interpreter(
+        r#"
+        let s = 0;
+        let i;
+        let j;
+        for (i = 0, j = 5; i < j; i = i + 1, j = j - 1) s = s + i * j;
+        s = s + 1, s = s * 2;
+        console.log(s);
+        console.log(i + ":" + j);
+        function last(a, b) { return (a, b); }
+        console.log(last(1, 2));
+        while (s = s - 10, s > 0) {}
+        console.log(s);
+        "#,
+    );
+}
@@ -1,13 +1,14 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use clap::Parser;
 use colored::Colorize;
 
-use crate::cli::Cli;
+use crate::cli::{Cli, Report};
 use crate::discovery::collect_test_files;
 use crate::execution::{init_thread_pool, run_suite};
 use crate::harness;
-use crate::stats::print_analysis;
+use crate::runner::Limits;
+use crate::stats::{Analysis, RunTotals, print_analysis, render_markdown};
 
 pub fn run() {
     let cli = Cli::parse();
@@ -23,32 +24,65 @@ pub fn run() {
         files.len()
     );
 
-    let summary = run_suite(&cli.path, &files, cli.verbose, cli.analyze);
-    print_totals(
-        summary.passed,
-        summary.failed,
-        summary.skipped,
-        start.elapsed().as_secs_f64(),
-    );
+    let analyze = cli.analyze || cli.report.is_some();
+    let limits = Limits {
+        timeout: Duration::from_millis(cli.timeout_ms),
+        ..Limits::default()
+    };
+    let summary = run_suite(&cli.path, &files, cli.verbose, analyze, limits);
+    let elapsed_secs = start.elapsed().as_secs_f64();
+    let totals = RunTotals {
+        passed: summary.passed,
+        failed: summary.failed,
+        skipped: summary.skipped,
+        timeouts: summary.timeouts,
+        panics: summary.panics,
+        elapsed_secs,
+    };
+    print_totals(&totals);
 
     if cli.analyze {
         print_analysis(&summary.analysis);
     }
 
+    if let Some(report) = &cli.report {
+        write_report(report, &summary.analysis, &totals);
+    }
+
     if summary.failed > 0 && !cli.verbose {
         print_failure_sample(&summary.failures);
     }
 }
 
-fn print_totals(passed: usize, failed: usize, skipped: usize, elapsed_secs: f64) {
+fn write_report(report: &Report, analysis: &Analysis, totals: &RunTotals) {
+    let markdown = render_markdown(analysis, totals);
+    match report {
+        Report::MarkdownStdout => println!("\n{markdown}"),
+        Report::MarkdownFile(path) => match std::fs::write(path, markdown) {
+            Ok(()) => println!("Report written to {}", path.display()),
+            Err(err) => eprintln!(
+                "{} failed to write {}: {err}",
+                "error:".red().bold(),
+                path.display()
+            ),
+        },
+    }
+}
+
+fn print_totals(totals: &RunTotals) {
     println!("\n{}", "=".repeat(60));
     println!(
         "Passed: {} | Failed: {} | Skipped: {}",
-        passed.to_string().green().bold(),
-        failed.to_string().red().bold(),
-        skipped.to_string().yellow().bold()
+        totals.passed.to_string().green().bold(),
+        totals.failed.to_string().red().bold(),
+        totals.skipped.to_string().yellow().bold()
+    );
+    println!(
+        "Of the failures: {} timed out, {} panicked",
+        totals.timeouts.to_string().red(),
+        totals.panics.to_string().red()
     );
-    println!("Completed in {:.2}s", elapsed_secs);
+    println!("Completed in {:.2}s", totals.elapsed_secs);
     println!("{}", "=".repeat(60));
 }
 
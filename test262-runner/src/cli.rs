@@ -13,4 +13,33 @@ pub struct Cli {
 
     #[arg(long, default_value_t = false)]
     pub analyze: bool,
+
+    /// Stop a test that runs longer than this, recording it as a timeout.
+    #[arg(long, value_name = "MS", default_value_t = 10_000)]
+    pub timeout_ms: u64,
+
+    /// Write pass rates by feature and section: `markdown` for stdout or
+    /// `markdown:<path>` for a file.
+    #[arg(long, value_name = "FORMAT[:PATH]", value_parser = parse_report)]
+    pub report: Option<Report>,
+}
+
+/// Where `--report markdown[:path]` goes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Report {
+    MarkdownStdout,
+    MarkdownFile(PathBuf),
 }
+
+fn parse_report(value: &str) -> Result<Report, String> {
+    match value.split_once(':') {
+        None if value == "markdown" => Ok(Report::MarkdownStdout),
+        Some(("markdown", path)) if !path.is_empty() => Ok(Report::MarkdownFile(path.into())),
+        _ => Err(format!(
+            "unsupported report '{value}', expected markdown or markdown:<path>"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,12 @@
+use super::*;
+
+#[test]
+fn test_report_targets() {
+    assert_eq!(parse_report("markdown"), Ok(Report::MarkdownStdout));
+    assert_eq!(
+        parse_report("markdown:out/report.md"),
+        Ok(Report::MarkdownFile("out/report.md".into()))
+    );
+    assert!(parse_report("markdown:").is_err());
+    assert!(parse_report("json").is_err());
+}
@@ -1,19 +1,21 @@
 use std::fs;
-use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
 
 use colored::Colorize;
 use rayon::prelude::*;
 
-use crate::metadata::{self, parse_frontmatter};
-use crate::panic_message::format_panic;
-use crate::runner::{TestResult, run_test};
+use crate::metadata::{TestMetadata, parse_frontmatter};
+use crate::panic_message::PANIC_PREFIX;
+use crate::runner::{Limits, TIMEOUT_PREFIX, TestResult, run_isolated};
 use crate::stats::Analysis;
 
 pub struct SuiteSummary {
     pub passed: usize,
     pub failed: usize,
     pub skipped: usize,
+    /// Failures that were timeouts or panics, also counted in `failed`.
+    pub timeouts: usize,
+    pub panics: usize,
     pub failures: Vec<(PathBuf, String)>,
     pub analysis: Analysis,
 }
@@ -30,19 +32,35 @@ pub fn init_thread_pool() {
         .ok();
 }
 
-pub fn run_suite(root: &Path, files: &[PathBuf], verbose: bool, analyze: bool) -> SuiteSummary {
-    let records: Vec<_> = files.par_iter().map(|path| run_case(path)).collect();
+pub fn run_suite(
+    root: &Path,
+    files: &[PathBuf],
+    verbose: bool,
+    analyze: bool,
+    limits: Limits,
+) -> SuiteSummary {
+    let records: Vec<_> = files
+        .par_iter()
+        .map(|path| run_case(path, limits))
+        .collect();
     let mut summary = SuiteSummary {
         passed: 0,
         failed: 0,
         skipped: 0,
+        timeouts: 0,
+        panics: 0,
         failures: Vec::new(),
         analysis: Analysis::default(),
     };
 
     for record in records {
         if analyze {
-            summary.analysis.record(root, &record.path, &record.result);
+            summary.analysis.record(
+                root,
+                &record.path,
+                &record.metadata.features,
+                &record.result,
+            );
         }
         apply_record(&mut summary, &record.path, &record.result, verbose);
     }
@@ -52,26 +70,28 @@ pub fn run_suite(root: &Path, files: &[PathBuf], verbose: bool, analyze: bool) -
 
 struct CaseRecord {
     path: PathBuf,
+    metadata: TestMetadata,
     result: TestResult,
 }
 
-fn run_case(path: &Path) -> CaseRecord {
+fn run_case(path: &Path, limits: Limits) -> CaseRecord {
     let source = match fs::read_to_string(path) {
         Ok(content) => content,
         Err(err) => {
             return CaseRecord {
                 path: path.to_path_buf(),
+                metadata: TestMetadata::default(),
                 result: TestResult::Skipped(format!("failed to read test: {err}")),
             };
         }
     };
 
-    let meta = parse_frontmatter(&source).unwrap_or_else(metadata::TestMetadata::default);
-    let result = panic::catch_unwind(AssertUnwindSafe(|| run_test(path, &source, &meta)))
-        .unwrap_or_else(|panic_payload| TestResult::Failed(format_panic(panic_payload)));
+    let metadata = parse_frontmatter(&source).unwrap_or_default();
+    let result = run_isolated(path.to_path_buf(), source, metadata.clone(), limits);
 
     CaseRecord {
         path: path.to_path_buf(),
+        metadata,
         result,
     }
 }
@@ -86,6 +106,11 @@ fn apply_record(summary: &mut SuiteSummary, path: &Path, result: &TestResult, ve
         }
         TestResult::Failed(reason) => {
             summary.failed += 1;
+            if reason.starts_with(TIMEOUT_PREFIX) {
+                summary.timeouts += 1;
+            } else if reason.starts_with(PANIC_PREFIX) {
+                summary.panics += 1;
+            }
             if verbose {
                 println!("{} {} - {}", "FAIL".red(), path.display(), reason);
             }
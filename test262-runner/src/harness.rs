@@ -5,39 +5,39 @@ use std::sync::OnceLock;
 
 static HARNESS_CACHE: OnceLock<HashMap<String, String>> = OnceLock::new();
 
-const HOST_HARNESS: &str = r#"(function (global) {
-  if (typeof global.$262 !== "object" || global.$262 === null) {
-    global.$262 = {};
-  }
-
-  var host262 = global.$262;
-  host262.global = global;
-
-  if (typeof host262.evalScript !== "function") {
-    host262.evalScript = function (source) {
-      return global.eval(source);
+/// Prefix of the error message thrown by `$262` members the engine
+/// cannot provide; the runner reports such tests as skipped.
+pub const UNSUPPORTED_MARKER: &str = "Test262:HostUnsupported:";
+
+// `global`, `evalScript`, `gc` and `detachArrayBuffer` are installed by
+// `Context::install_test262_host`; the rest of `$262` is stubbed here.
+const HOST_HARNESS: &str = r#"(function (host262) {
+  function unsupported(name) {
+    return function () {
+      throw new Error("Test262:HostUnsupported: $262." + name);
     };
   }
 
-  if (typeof host262.createRealm !== "function") {
-    host262.createRealm = function () {
-      return {
-        global: global,
-        evalScript: host262.evalScript,
-      };
-    };
-  }
+  host262.createRealm = unsupported("createRealm");
+})(globalThis.$262);"#;
 
-  if (typeof host262.detachArrayBuffer !== "function") {
-    host262.detachArrayBuffer = function () {};
-  }
+/// Fallback for `doneprintHandle.js` when the harness checkout is missing,
+/// so async tests still report completion through `print`.
+const DONE_PRINT_HANDLE: &str = r#"function __consolePrintHandle__(msg) {
+  print(msg);
+}
 
-  if (typeof host262.gc !== "function") {
-    host262.gc = function () {};
+function $DONE(error) {
+  if (error) {
+    if (typeof error === "object" && error !== null && "name" in error) {
+      __consolePrintHandle__("Test262:AsyncTestFailure:" + error.name + ": " + error.message);
+    } else {
+      __consolePrintHandle__("Test262:AsyncTestFailure:Test262Error: " + String(error));
+    }
+  } else {
+    __consolePrintHandle__("Test262:AsyncTestComplete");
   }
-})(globalThis);
-
-var $262 = globalThis.$262;"#;
+}"#;
 
 fn harness_dir() -> PathBuf {
     Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -93,6 +93,8 @@ pub fn compose_source(includes: &[String], test_source: &str) -> String {
         }
         if let Some(content) = cache.get(include.as_str()) {
             parts.push(content);
+        } else if include == "doneprintHandle.js" {
+            parts.push(DONE_PRINT_HANDLE);
         }
     }
 
@@ -1,11 +1,14 @@
 use std::any::Any;
 
+/// Starts the failure recorded for a test that panicked.
+pub const PANIC_PREFIX: &str = "panic: ";
+
 pub fn format_panic(payload: Box<dyn Any + Send>) -> String {
     if let Some(msg) = payload.downcast_ref::<&str>() {
-        return format!("panic while running test: {msg}");
+        return format!("{PANIC_PREFIX}{msg}");
     }
     if let Some(msg) = payload.downcast_ref::<String>() {
-        return format!("panic while running test: {msg}");
+        return format!("{PANIC_PREFIX}{msg}");
     }
-    "panic while running test".to_string()
+    format!("{PANIC_PREFIX}unknown payload")
 }
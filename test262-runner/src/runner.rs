@@ -1,11 +1,46 @@
-use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 use crustyjs_core::errors::{CrustyError, RuntimeError};
-use crustyjs_core::{Context, Value};
+use crustyjs_core::runtime::interpreter::YieldDecision;
+use crustyjs_core::{Context, OutputMode, Value};
 
 use crate::harness;
 use crate::metadata::{strip_frontmatter, Negative, TestMetadata};
+use crate::panic_message::format_panic;
+
+/// Starts the failure recorded for a test its limits stopped.
+pub const TIMEOUT_PREFIX: &str = "timeout: ";
+
+/// Stack for the thread a test runs on. Deeply nested source recurses in
+/// the parser and the interpreter, and debug builds take far more stack
+/// per level than release ones; pages are only committed once touched.
+const TEST_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Evaluation steps between checks of the wall-clock timeout.
+const TIMEOUT_CHECK_INTERVAL: usize = 10_000;
+
+/// How long one test may run.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Evaluation steps before the test is stopped. Async tests whose
+    /// jobs never settle run into it too.
+    pub max_steps: usize,
+    pub timeout: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_steps: 1_000_000,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum TestResult {
@@ -14,166 +49,230 @@ pub enum TestResult {
     Skipped(String),
 }
 
-pub fn run_test(path: &Path, source: &str, metadata: &TestMetadata) -> TestResult {
+/// Runs a test on a thread of its own, so that a panic becomes a failed
+/// result rather than taking the other tests down with it. A test that
+/// neither finishes nor notices its timeout is abandoned to its thread.
+pub fn run_isolated(
+    path: PathBuf,
+    source: String,
+    metadata: TestMetadata,
+    limits: Limits,
+) -> TestResult {
+    let (sender, receiver) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name(format!("test262 {}", path.display()))
+        .stack_size(TEST_STACK_SIZE)
+        .spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                run_test(&path, &source, &metadata, &limits)
+            }))
+            .unwrap_or_else(|payload| TestResult::Failed(format_panic(payload)));
+            sender.send(result).ok();
+        });
+    if let Err(err) = spawned {
+        return TestResult::Skipped(format!("failed to start test thread: {err}"));
+    }
+    match receiver.recv_timeout(limits.timeout * 2) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => TestResult::Failed(format!(
+            "{TIMEOUT_PREFIX}still running after {}ms",
+            (limits.timeout * 2).as_millis()
+        )),
+        Err(RecvTimeoutError::Disconnected) => {
+            TestResult::Failed(format_panic(Box::new("the test thread died")))
+        }
+    }
+}
+
+pub fn run_test(path: &Path, source: &str, metadata: &TestMetadata, limits: &Limits) -> TestResult {
     if metadata.is_module() {
-        return run_module_test(path, metadata);
+        return run_module_test(path, metadata, limits);
     }
 
     let test_source = strip_frontmatter(source);
 
     if metadata.is_raw() {
-        return run_single(test_source, metadata, metadata.is_async());
+        return run_single(test_source, metadata, limits);
     }
 
     if metadata.is_no_strict() {
-        return run_single(
-            &compose(metadata, test_source),
-            metadata,
-            metadata.is_async(),
-        );
+        return run_single(&compose(metadata, test_source), metadata, limits);
     }
 
     if metadata.is_only_strict() {
         let strict_source = format!("\"use strict\";\n{}", compose(metadata, test_source));
-        return run_single(&strict_source, metadata, metadata.is_async());
+        return run_single(&strict_source, metadata, limits);
     }
 
-    let sloppy_result = run_single(
-        &compose(metadata, test_source),
-        metadata,
-        metadata.is_async(),
-    );
+    let sloppy_result = run_single(&compose(metadata, test_source), metadata, limits);
     if matches!(sloppy_result, TestResult::Failed(_)) {
         return sloppy_result;
     }
 
     let strict_source = format!("\"use strict\";\n{}", compose(metadata, test_source));
-    run_single(&strict_source, metadata, metadata.is_async())
+    run_single(&strict_source, metadata, limits)
 }
 
 fn compose(metadata: &TestMetadata, test_source: &str) -> String {
-    harness::compose_source(&metadata.includes, test_source)
+    harness::compose_source(&includes_for(metadata), test_source)
 }
 
-fn run_module_test(path: &Path, metadata: &TestMetadata) -> TestResult {
-    let mut ctx = Context::new_with_realtime(false);
-    ctx.set_max_steps(1_000_000);
-
-    if let Err(err) = ctx.eval(harness::host_harness_source()) {
-        return TestResult::Failed(format!("failed to initialize host harness: {err}"));
+/// Async tests implicitly get `doneprintHandle.js`, which defines `$DONE`
+/// in terms of `print`.
+fn includes_for(metadata: &TestMetadata) -> Vec<String> {
+    let mut includes = metadata.includes.clone();
+    if metadata.is_async() {
+        includes.push("doneprintHandle.js".into());
     }
+    includes
+}
 
-    let done_state = if metadata.is_async() {
-        Some(install_done_callback(&mut ctx))
-    } else {
-        None
-    };
-
-    match ctx.eval_module(path) {
-        Ok(()) => {
-            if metadata.negative.is_some() {
-                TestResult::Failed("expected error but test passed".into())
-            } else if let Some(state) = done_state.as_ref() {
-                evaluate_async_completion(state)
+fn new_test_context(limits: &Limits) -> (Context, Arc<Mutex<AsyncState>>) {
+    let mut ctx = Context::new_with_realtime(false);
+    // The step limit doubles as the timeout for async tests, whose
+    // pending jobs are drained by `eval` before it returns.
+    ctx.set_max_steps(limits.max_steps);
+    let timeout = limits.timeout;
+    ctx.set_yield_hook(
+        TIMEOUT_CHECK_INTERVAL,
+        Box::new(move |run| {
+            if run.elapsed() > timeout {
+                YieldDecision::Interrupt
             } else {
-                TestResult::Passed
+                YieldDecision::Continue
             }
-        }
-        Err(e) => evaluate_error_with_neg(&metadata.negative, &safe_error_message(&e)),
-    }
+        }),
+    );
+    // Negative tests expect syntax errors in functions that never run.
+    ctx.set_lazy_functions(false);
+    // SAFETY: the runner never holds script values across an evaluation.
+    unsafe { ctx.install_test262_host() };
+    let state = install_print_hook(&mut ctx);
+    (ctx, state)
 }
 
-fn run_single(source: &str, metadata: &TestMetadata, is_async: bool) -> TestResult {
-    let negative = metadata.negative.clone();
+fn run_module_test(path: &Path, metadata: &TestMetadata, limits: &Limits) -> TestResult {
+    let (mut ctx, async_state) = new_test_context(limits);
 
-    let mut ctx = Context::new_with_realtime(false);
-    ctx.set_max_steps(1_000_000);
+    if let Err(err) = ctx.eval(&harness::compose_source(&includes_for(metadata), "")) {
+        return TestResult::Failed(format!("failed to initialize host harness: {err}"));
+    }
 
-    let done_state = if is_async {
-        Some(install_done_callback(&mut ctx))
-    } else {
-        None
-    };
+    let result = ctx.eval_module(path);
+    evaluate_result(result, metadata, &async_state, metadata.is_async())
+}
 
+fn run_single(source: &str, metadata: &TestMetadata, limits: &Limits) -> TestResult {
+    let (mut ctx, async_state) = new_test_context(limits);
     let result = ctx.eval(source);
+    evaluate_result(result, metadata, &async_state, metadata.is_async())
+}
 
+fn evaluate_result(
+    result: Result<(), CrustyError>,
+    metadata: &TestMetadata,
+    async_state: &Mutex<AsyncState>,
+    is_async: bool,
+) -> TestResult {
     match result {
         Ok(()) => {
-            if negative.is_some() {
+            if metadata.negative.is_some() {
                 TestResult::Failed("expected error but test passed".into())
-            } else if let Some(state) = done_state.as_ref() {
-                evaluate_async_completion(state)
+            } else if is_async {
+                evaluate_async_completion(async_state)
             } else {
                 TestResult::Passed
             }
         }
-        Err(e) => evaluate_error_with_neg(&negative, &safe_error_message(&e)),
+        Err(CrustyError::Runtime(
+            err @ (RuntimeError::StepLimitExceeded | RuntimeError::Interrupted),
+        )) => TestResult::Failed(format!("{TIMEOUT_PREFIX}{}", timeout_reason(&err))),
+        Err(e) => {
+            let message = safe_error_message(&e);
+            unsupported_host_feature(&message)
+                .unwrap_or_else(|| evaluate_error_with_neg(&metadata.negative, &message))
+        }
     }
 }
 
+const ASYNC_COMPLETE: &str = "Test262:AsyncTestComplete";
+const ASYNC_FAILURE: &str = "Test262:AsyncTestFailure:";
+
 #[derive(Default)]
-struct AsyncDoneState {
-    called: bool,
-    error: Option<String>,
+struct AsyncState {
+    completed: bool,
+    failure: Option<String>,
 }
 
-fn install_done_callback(ctx: &mut Context) -> Arc<Mutex<AsyncDoneState>> {
-    let state = Arc::new(Mutex::new(AsyncDoneState::default()));
-    let callback_state = Arc::clone(&state);
-
-    ctx.set_global_function("$DONE", move |args| {
-        let mut state = callback_state
-            .lock()
-            .expect("$DONE state mutex should not be poisoned");
-
-        state.called = true;
-        if state.error.is_none() {
-            state.error = args.get(0).and_then(done_error_message);
+impl AsyncState {
+    fn observe(&mut self, line: &str) {
+        if line == ASYNC_COMPLETE {
+            self.completed = true;
+        } else if let Some(failure) = line.strip_prefix(ASYNC_FAILURE)
+            && self.failure.is_none()
+        {
+            self.failure = Some(failure.to_string());
         }
+    }
+}
+
+/// Routes both the `print` global and console output through the async
+/// completion protocol, watching for the `Test262:AsyncTest*` lines.
+fn install_print_hook(ctx: &mut Context) -> Arc<Mutex<AsyncState>> {
+    let state = Arc::new(Mutex::new(AsyncState::default()));
 
+    let print_state = Arc::clone(&state);
+    ctx.set_global_function("print", move |args| {
+        let line = args.get(0).map(Value::to_string).unwrap_or_default();
+        print_state
+            .lock()
+            .expect("async state mutex should not be poisoned")
+            .observe(&line);
         Ok(Value::Undefined)
     });
 
+    let console_state = Arc::clone(&state);
+    ctx.set_output_mode(OutputMode::sink(move |line| {
+        console_state
+            .lock()
+            .expect("async state mutex should not be poisoned")
+            .observe(line);
+    }));
+
     state
 }
 
-fn evaluate_async_completion(state: &Arc<Mutex<AsyncDoneState>>) -> TestResult {
+fn evaluate_async_completion(state: &Mutex<AsyncState>) -> TestResult {
     let state = state
         .lock()
-        .expect("$DONE state mutex should not be poisoned");
+        .expect("async state mutex should not be poisoned");
 
-    if !state.called {
-        return TestResult::Failed("async test did not call $DONE".into());
+    if let Some(failure) = &state.failure {
+        return unsupported_host_feature(failure)
+            .unwrap_or_else(|| TestResult::Failed(format!("async test failed: {failure}")));
     }
 
-    if let Some(error) = &state.error {
-        return TestResult::Failed(format!("$DONE called with error: {error}"));
+    if !state.completed {
+        return TestResult::Failed("async test did not report completion".into());
     }
 
     TestResult::Passed
 }
 
-fn done_error_message(value: &Value) -> Option<String> {
-    match value {
-        Value::Undefined => None,
-        Value::Null | Value::Boolean(_) | Value::Number(_) | Value::String(_) => {
-            Some(value.to_string())
-        }
-        Value::Function { .. } => Some("function".into()),
-        Value::NativeFunction { .. } => Some("native function".into()),
-        Value::Symbol(_) => Some("symbol".into()),
-        Value::Object(_) => Some("object".into()),
-        Value::Array(_) => Some("array".into()),
-        Value::Promise(_) => Some("promise".into()),
-        Value::Map(_) => Some("map".into()),
-        Value::Set(_) => Some("set".into()),
-        Value::WeakMap(_) => Some("weakmap".into()),
-        Value::WeakSet(_) => Some("weakset".into()),
-        Value::RegExp(_) => Some("regexp".into()),
-        Value::Proxy(_) => Some("proxy".into()),
+fn timeout_reason(err: &RuntimeError) -> &'static str {
+    match err {
+        RuntimeError::StepLimitExceeded => "step limit exceeded",
+        _ => "wall-clock limit exceeded",
     }
 }
 
+fn unsupported_host_feature(message: &str) -> Option<TestResult> {
+    let (_, feature) = message.split_once(harness::UNSUPPORTED_MARKER)?;
+    Some(TestResult::Skipped(format!(
+        "unsupported host feature:{feature}"
+    )))
+}
+
 fn safe_error_message(error: &CrustyError) -> String {
     match error {
         CrustyError::Syntax(err) => format!("SyntaxError: {}", err.message),
@@ -186,6 +285,9 @@ fn safe_runtime_error_message(error: &RuntimeError) -> String {
         RuntimeError::UndefinedVariable { name } => {
             format!("ReferenceError: '{name}' is not defined")
         }
+        RuntimeError::UninitializedBinding { name } => {
+            format!("ReferenceError: Cannot access '{name}' before initialization")
+        }
         RuntimeError::NotAFunction { name } => format!("TypeError: '{name}' is not a function"),
         RuntimeError::ArityMismatch { expected, got } => {
             format!("TypeError: expected {expected} arguments but got {got}")
@@ -195,6 +297,11 @@ fn safe_runtime_error_message(error: &RuntimeError) -> String {
             format!("TypeError: Assignment to constant variable '{name}'")
         }
         RuntimeError::Thrown { value } => format!("Uncaught {}", format_thrown_value(value)),
+        RuntimeError::StepLimitExceeded
+        | RuntimeError::MicrotaskLimitExceeded { .. }
+        | RuntimeError::Interrupted
+        | RuntimeError::GeneratorSuspend
+        | RuntimeError::GeneratorReturn { .. } => error.to_string(),
     }
 }
 
@@ -211,7 +318,7 @@ fn format_thrown_value(value: &Value) -> String {
                 {
                     format!("{name}: {message}")
                 }
-                (Some(Value::String(name)), _) => name,
+                (Some(Value::String(name)), _) => name.into_string(),
                 _ => "object".into(),
             }
         }
@@ -234,3 +341,6 @@ fn evaluate_error_with_neg(negative: &Option<Negative>, error_msg: &str) -> Test
         None => TestResult::Failed(error_msg.to_string()),
     }
 }
+
+#[cfg(test)]
+mod tests;
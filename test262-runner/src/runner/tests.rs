@@ -0,0 +1,123 @@
+use super::*;
+
+fn async_raw_metadata() -> TestMetadata {
+    TestMetadata {
+        flags: vec!["raw".into(), "async".into()],
+        ..TestMetadata::default()
+    }
+}
+
+#[test]
+fn test_async_completion_through_print() {
+    let source = r#"Promise.resolve().then(function () {
+  print("Test262:AsyncTestComplete");
+});"#;
+    let result = run_test(
+        Path::new("async.js"),
+        source,
+        &async_raw_metadata(),
+        &Limits::default(),
+    );
+    assert!(matches!(result, TestResult::Passed), "{result:?}");
+}
+
+#[test]
+fn test_async_missing_completion_fails() {
+    let source = "Promise.resolve().then(function () {});";
+    let result = run_test(
+        Path::new("async.js"),
+        source,
+        &async_raw_metadata(),
+        &Limits::default(),
+    );
+    assert!(matches!(result, TestResult::Failed(_)), "{result:?}");
+}
+
+#[test]
+fn test_async_failure_print_fails() {
+    let source = r#"setTimeout(function () {
+  print("Test262:AsyncTestFailure:Test262Error: boom");
+}, 0);"#;
+    let result = run_test(
+        Path::new("async.js"),
+        source,
+        &async_raw_metadata(),
+        &Limits::default(),
+    );
+    match result {
+        TestResult::Failed(reason) => assert!(reason.contains("boom"), "{reason}"),
+        other => panic!("expected failure, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_async_done_handle_fallback() {
+    let metadata = TestMetadata {
+        flags: vec!["async".into(), "noStrict".into()],
+        ..TestMetadata::default()
+    };
+    let source = "Promise.resolve(1).then(function () { $DONE(); });";
+    let result = run_test(Path::new("async.js"), source, &metadata, &Limits::default());
+    assert!(matches!(result, TestResult::Passed), "{result:?}");
+}
+
+#[test]
+fn test_host_eval_script_and_gc() {
+    let metadata = TestMetadata {
+        flags: vec!["raw".into()],
+        ..TestMetadata::default()
+    };
+    let source = r#"var value = $262.evalScript("var fromScript = 41; fromScript + 1;");
+if (value !== 42 || fromScript !== 41 || $262.global !== globalThis) {
+  throw new Error("evalScript mismatch");
+}
+$262.gc();"#;
+    let result = run_test(Path::new("host.js"), source, &metadata, &Limits::default());
+    assert!(matches!(result, TestResult::Passed), "{result:?}");
+}
+
+#[test]
+fn test_unsupported_host_feature_is_skipped() {
+    let metadata = TestMetadata {
+        flags: vec!["noStrict".into()],
+        ..TestMetadata::default()
+    };
+    let result = run_test(
+        Path::new("realm.js"),
+        "$262.createRealm();",
+        &metadata,
+        &Limits::default(),
+    );
+    assert!(matches!(result, TestResult::Skipped(_)), "{result:?}");
+}
+
+#[test]
+fn test_deeply_nested_source_fails_without_aborting() {
+    let metadata = TestMetadata {
+        flags: vec!["raw".into()],
+        ..TestMetadata::default()
+    };
+    let source = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+    let result = run_isolated("deep.js".into(), source, metadata, Limits::default());
+    match result {
+        TestResult::Failed(reason) => assert!(reason.contains("nested too deeply"), "{reason}"),
+        other => panic!("expected failure, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_endless_loop_is_recorded_as_a_timeout() {
+    let metadata = TestMetadata {
+        flags: vec!["raw".into()],
+        ..TestMetadata::default()
+    };
+    let limits = Limits {
+        max_steps: usize::MAX,
+        timeout: Duration::from_millis(50),
+    };
+    let result = run_isolated("loop.js".into(), "while (true) {}".into(), metadata, limits);
+    match result {
+        TestResult::Failed(reason) => assert!(reason.starts_with(TIMEOUT_PREFIX), "{reason}"),
+        other => panic!("expected timeout, got {other:?}"),
+    }
+}
@@ -11,30 +11,47 @@ pub struct SectionStats {
     pub skipped: usize,
 }
 
+impl SectionStats {
+    fn count(&mut self, result: &TestResult) {
+        self.total += 1;
+        match result {
+            TestResult::Passed => self.passed += 1,
+            TestResult::Failed(_) => self.failed += 1,
+            TestResult::Skipped(_) => self.skipped += 1,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Analysis {
     pub sections: HashMap<String, SectionStats>,
+    /// Counts per entry of the `features:` frontmatter; a test listing
+    /// several features counts towards each of them.
+    pub features: HashMap<String, SectionStats>,
     pub failure_messages: HashMap<String, usize>,
     pub skip_reasons: HashMap<String, usize>,
 }
 
 impl Analysis {
-    pub fn record(&mut self, root: &Path, path: &Path, result: &TestResult) {
+    pub fn record(&mut self, root: &Path, path: &Path, features: &[String], result: &TestResult) {
         let section = section_from_path(root, path);
-        let stats = self.sections.entry(section).or_default();
-        stats.total += 1;
+        self.sections.entry(section).or_default().count(result);
+        for feature in features {
+            self.features
+                .entry(feature.clone())
+                .or_default()
+                .count(result);
+        }
 
         match result {
-            TestResult::Passed => stats.passed += 1,
+            TestResult::Passed => {}
             TestResult::Failed(reason) => {
-                stats.failed += 1;
                 *self
                     .failure_messages
                     .entry(normalize_message(reason))
                     .or_insert(0) += 1;
             }
             TestResult::Skipped(reason) => {
-                stats.skipped += 1;
                 *self.skip_reasons.entry(reason.clone()).or_insert(0) += 1;
             }
         }
@@ -58,9 +75,15 @@ fn section_from_path(root: &Path, path: &Path) -> String {
     }
 }
 
-fn normalize_message(message: &str) -> String {
+/// Reduces a failure reason to a key shared by every test failing the same
+/// way: the first line, with paths and `:line:col` positions removed.
+pub(super) fn normalize_message(message: &str) -> String {
     let first_line = message.lines().next().unwrap_or_default().trim();
-    let compact = first_line.split_whitespace().collect::<Vec<_>>().join(" ");
+    let compact = first_line
+        .split_whitespace()
+        .map(normalize_word)
+        .collect::<Vec<_>>()
+        .join(" ");
     let max_chars = 140;
 
     if compact.chars().count() <= max_chars {
@@ -70,3 +93,27 @@ fn normalize_message(message: &str) -> String {
         format!("{clipped}...")
     }
 }
+
+fn normalize_word(word: &str) -> String {
+    let trimmed = word.trim_matches(|c: char| "()[]'\"`,".contains(c));
+    if trimmed.contains('/') && (trimmed.contains(".js") || trimmed.starts_with('/')) {
+        return word.replace(trimmed, "<path>");
+    }
+    strip_positions(word)
+}
+
+/// Drops `:12` and `:12:5` suffixes that pin a message to a source line.
+fn strip_positions(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek().is_some_and(char::is_ascii_digit) {
+            while chars.peek().is_some_and(char::is_ascii_digit) {
+                chars.next();
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,90 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::sections::{compare_pass_rate, rate};
+use super::{Analysis, SectionStats};
+
+/// Failure messages listed in the report.
+const TOP_FAILURES: usize = 20;
+
+/// Totals for the whole run, shown above the tables.
+pub struct RunTotals {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// Failures that were timeouts or panics, also counted in `failed`.
+    pub timeouts: usize,
+    pub panics: usize,
+    pub elapsed_secs: f64,
+}
+
+/// Renders the analysis as markdown tables to paste into a PR: pass rates
+/// by feature and by section, worst first, then the most common failures.
+pub fn render_markdown(analysis: &Analysis, totals: &RunTotals) -> String {
+    let total = totals.passed + totals.failed + totals.skipped;
+    let mut out = String::new();
+    writeln!(out, "# test262 results\n").unwrap();
+    writeln!(
+        out,
+        "**{total}** tests: **{}** passed ({}), **{}** failed ({} timed out, {} panicked), **{}** skipped in {:.2}s",
+        totals.passed,
+        rate(totals.passed, total),
+        totals.failed,
+        totals.timeouts,
+        totals.panics,
+        totals.skipped,
+        totals.elapsed_secs
+    )
+    .unwrap();
+
+    write_table(&mut out, "By feature", "Feature", &analysis.features);
+    write_table(&mut out, "By section", "Section", &analysis.sections);
+
+    let mut failures: Vec<_> = analysis.failure_messages.iter().collect();
+    failures.sort_by_key(|(message, count)| (Reverse(**count), *message));
+    if !failures.is_empty() {
+        writeln!(out, "\n## Top {TOP_FAILURES} failure messages\n").unwrap();
+        writeln!(out, "| Count | Message |\n| ---: | --- |").unwrap();
+        for (message, count) in failures.into_iter().take(TOP_FAILURES) {
+            writeln!(out, "| {count} | {} |", escape_cell(message)).unwrap();
+        }
+    }
+    out
+}
+
+fn write_table(out: &mut String, title: &str, key: &str, rows: &HashMap<String, SectionStats>) {
+    if rows.is_empty() {
+        return;
+    }
+    let mut rows: Vec<_> = rows
+        .iter()
+        .map(|(name, stats)| (name.clone(), *stats))
+        .collect();
+    rows.sort_by(|a, b| compare_pass_rate(a, b).then_with(|| a.0.cmp(&b.0)));
+
+    writeln!(out, "\n## {title}\n").unwrap();
+    writeln!(
+        out,
+        "| {key} | Pass rate | Passed | Failed | Skipped | Total |"
+    )
+    .unwrap();
+    writeln!(out, "| --- | ---: | ---: | ---: | ---: | ---: |").unwrap();
+    for (name, stats) in rows {
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} |",
+            escape_cell(&name),
+            rate(stats.passed, stats.total),
+            stats.passed,
+            stats.failed,
+            stats.skipped,
+            stats.total
+        )
+        .unwrap();
+    }
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
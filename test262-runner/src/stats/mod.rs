@@ -1,7 +1,12 @@
 mod analysis;
 mod issues;
+mod markdown;
 mod report;
 mod sections;
 
 pub use analysis::{Analysis, SectionStats};
+pub use markdown::{RunTotals, render_markdown};
 pub use report::print_analysis;
+
+#[cfg(test)]
+mod tests;
@@ -63,7 +63,10 @@ pub(super) fn print_sections(analysis: &Analysis) {
     }
 }
 
-fn compare_pass_rate(a: &(String, SectionStats), b: &(String, SectionStats)) -> std::cmp::Ordering {
+pub(super) fn compare_pass_rate(
+    a: &(String, SectionStats),
+    b: &(String, SectionStats),
+) -> std::cmp::Ordering {
     let a_rate = a.1.passed as f64 / a.1.total as f64;
     let b_rate = b.1.passed as f64 / b.1.total as f64;
     a_rate
@@ -72,7 +75,7 @@ fn compare_pass_rate(a: &(String, SectionStats), b: &(String, SectionStats)) ->
         .then_with(|| b.1.total.cmp(&a.1.total))
 }
 
-fn rate(numerator: usize, denominator: usize) -> String {
+pub(super) fn rate(numerator: usize, denominator: usize) -> String {
     if denominator == 0 {
         return "n/a".to_string();
     }
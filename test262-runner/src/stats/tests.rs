@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use super::analysis::normalize_message;
+use super::*;
+use crate::runner::TestResult;
+
+fn features(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_normalize_message_strips_paths_and_positions() {
+    assert_eq!(
+        normalize_message("SyntaxError at /tmp/test262/test/a.js:12:5: unexpected token"),
+        "SyntaxError at <path> unexpected token"
+    );
+    assert_eq!(
+        normalize_message("Expected (test/built-ins/x.js) got 3\nstack"),
+        "Expected (<path>) got 3"
+    );
+    assert_eq!(normalize_message("line:4 failed"), "line failed");
+}
+
+#[test]
+fn test_markdown_report_sorts_by_pass_rate() {
+    let root = Path::new("test");
+    let mut analysis = Analysis::default();
+    analysis.record(
+        root,
+        Path::new("test/built-ins/Array/a.js"),
+        &features(&["Symbol"]),
+        &TestResult::Passed,
+    );
+    analysis.record(
+        root,
+        Path::new("test/language/expressions/b.js"),
+        &features(&["Symbol", "BigInt"]),
+        &TestResult::Failed("boom at test/x.js:1:2".into()),
+    );
+    analysis.record(
+        root,
+        Path::new("test/language/expressions/c.js"),
+        &[],
+        &TestResult::Skipped("module".into()),
+    );
+
+    assert_eq!(analysis.features["Symbol"].total, 2);
+    assert_eq!(analysis.features["BigInt"].failed, 1);
+
+    let markdown = render_markdown(
+        &analysis,
+        &RunTotals {
+            passed: 1,
+            failed: 1,
+            skipped: 1,
+            timeouts: 1,
+            panics: 0,
+            elapsed_secs: 1.5,
+        },
+    );
+    assert!(markdown.contains("**3** tests: **1** passed"), "{markdown}");
+    assert!(
+        markdown.contains("**1** failed (1 timed out, 0 panicked)"),
+        "{markdown}"
+    );
+    assert!(markdown.contains("in 1.50s"), "{markdown}");
+    let bigint = markdown.find("| BigInt |").unwrap();
+    let symbol = markdown.find("| Symbol |").unwrap();
+    assert!(bigint < symbol, "{markdown}");
+    let language = markdown.find("| language/expressions |").unwrap();
+    let builtins = markdown.find("| built-ins/Array |").unwrap();
+    assert!(language < builtins, "{markdown}");
+    assert!(markdown.contains("| 1 | boom at <path> |"), "{markdown}");
+}